@@ -0,0 +1,148 @@
+// macOS application menu. Built from t() so it can be rebuilt in another
+// language; item ids are handled by the menu event handler in lib.rs.
+
+use tauri::menu::{Menu, MenuItem, PredefinedMenuItem, Submenu};
+use tauri::{AppHandle, Wry};
+
+use crate::i18n::t;
+
+pub fn build(h: &AppHandle) -> tauri::Result<Menu<Wry>> {
+    // App menu
+    let about_item = PredefinedMenuItem::about(h, Some(&t("menu.about")), None)?;
+    let settings_item = MenuItem::with_id(h, "settings", t("menu.settings"), true, Some("CmdOrCtrl+,"))?;
+    let hide_item = PredefinedMenuItem::hide(h, Some(&t("menu.hide")))?;
+    let hide_others_item = PredefinedMenuItem::hide_others(h, Some(&t("menu.hideOthers")))?;
+    let show_all_item = PredefinedMenuItem::show_all(h, Some(&t("menu.showAll")))?;
+    let quit_item = MenuItem::with_id(h, "quit", t("menu.quit"), true, Some("CmdOrCtrl+Q"))?;
+
+    let app_submenu = Submenu::with_items(
+        h,
+        "When",
+        true,
+        &[
+            &about_item,
+            &PredefinedMenuItem::separator(h)?,
+            &settings_item,
+            &PredefinedMenuItem::separator(h)?,
+            &hide_item,
+            &hide_others_item,
+            &show_all_item,
+            &PredefinedMenuItem::separator(h)?,
+            &quit_item,
+        ],
+    )?;
+
+    // File menu
+    let add_torrent_item = MenuItem::with_id(h, "add-torrent", t("menu.addTorrent"), true, Some("CmdOrCtrl+O"))?;
+    let add_magnet_item = MenuItem::with_id(h, "add-magnet", t("menu.addMagnet"), true, Some("CmdOrCtrl+U"))?;
+    let check_feeds_item = MenuItem::with_id(h, "check-feeds", t("menu.checkFeeds"), true, Some("CmdOrCtrl+R"))?;
+
+    let file_submenu = Submenu::with_items(
+        h,
+        t("menu.file"),
+        true,
+        &[
+            &add_torrent_item,
+            &add_magnet_item,
+            &PredefinedMenuItem::separator(h)?,
+            &check_feeds_item,
+        ],
+    )?;
+
+    // Edit menu
+    let undo_item = PredefinedMenuItem::undo(h, Some(&t("menu.undo")))?;
+    let redo_item = PredefinedMenuItem::redo(h, Some(&t("menu.redo")))?;
+    let cut_item = PredefinedMenuItem::cut(h, Some(&t("menu.cut")))?;
+    let copy_item = PredefinedMenuItem::copy(h, Some(&t("menu.copy")))?;
+    let paste_item = PredefinedMenuItem::paste(h, Some(&t("menu.paste")))?;
+    let select_all_item = PredefinedMenuItem::select_all(h, Some(&t("menu.selectAll")))?;
+
+    let edit_submenu = Submenu::with_items(
+        h,
+        t("menu.edit"),
+        true,
+        &[
+            &undo_item,
+            &redo_item,
+            &PredefinedMenuItem::separator(h)?,
+            &cut_item,
+            &copy_item,
+            &paste_item,
+            &select_all_item,
+        ],
+    )?;
+
+    // View menu
+    let view_inbox_item = MenuItem::with_id(h, "view-inbox", t("menu.inbox"), true, Some("CmdOrCtrl+1"))?;
+    let view_playlets_item = MenuItem::with_id(h, "view-playlets", t("menu.playlets"), true, Some("CmdOrCtrl+2"))?;
+    let view_settings_item = MenuItem::with_id(h, "view-settings", t("nav.settings"), true, Some("CmdOrCtrl+3"))?;
+
+    let view_submenu = Submenu::with_items(
+        h,
+        t("menu.view"),
+        true,
+        &[&view_inbox_item, &view_playlets_item, &view_settings_item],
+    )?;
+
+    // Torrents menu
+    let pause_all_item = MenuItem::with_id(h, "pause-all", t("menu.pauseAll"), true, None::<&str>)?;
+    let resume_all_item = MenuItem::with_id(h, "resume-all", t("menu.resumeAll"), true, None::<&str>)?;
+    let clear_completed_item = MenuItem::with_id(h, "clear-completed", t("menu.clearCompleted"), true, None::<&str>)?;
+
+    let torrents_submenu = Submenu::with_items(
+        h,
+        t("menu.torrents"),
+        true,
+        &[
+            &pause_all_item,
+            &resume_all_item,
+            &PredefinedMenuItem::separator(h)?,
+            &clear_completed_item,
+        ],
+    )?;
+
+    // Window menu
+    let minimize_item = PredefinedMenuItem::minimize(h, Some(&t("menu.minimize")))?;
+
+    let window_submenu = Submenu::with_items(
+        h,
+        t("menu.window"),
+        true,
+        &[&minimize_item],
+    )?;
+
+    // Help menu
+    let help_docs_item = MenuItem::with_id(h, "help-docs", t("menu.helpDocs"), true, None::<&str>)?;
+
+    let help_submenu = Submenu::with_items(
+        h,
+        t("menu.help"),
+        true,
+        &[&help_docs_item],
+    )?;
+
+    Menu::with_items(
+        h,
+        &[
+            &app_submenu,
+            &file_submenu,
+            &edit_submenu,
+            &view_submenu,
+            &torrents_submenu,
+            &window_submenu,
+            &help_submenu,
+        ],
+    )
+}
+
+/// Replace the menu bar with one labelled in the current locale.
+pub fn refresh(app: &AppHandle) {
+    match build(app) {
+        Ok(menu) => {
+            if let Err(e) = app.set_menu(menu) {
+                tracing::error!("Failed to set application menu: {}", e);
+            }
+        }
+        Err(e) => tracing::error!("Failed to build application menu: {}", e),
+    }
+}
@@ -14,7 +14,15 @@ pub async fn check_file_associations() -> Result<FileAssociationStatus> {
     {
         Ok(macos_associations::check())
     }
-    #[cfg(not(target_os = "macos"))]
+    #[cfg(target_os = "windows")]
+    {
+        Ok(windows_associations::check())
+    }
+    #[cfg(target_os = "linux")]
+    {
+        Ok(linux_associations::check())
+    }
+    #[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
     {
         Ok(FileAssociationStatus {
             torrent_files: false,
@@ -29,7 +37,15 @@ pub async fn set_default_for_torrents() -> Result<()> {
     {
         macos_associations::set_torrent_default()
     }
-    #[cfg(not(target_os = "macos"))]
+    #[cfg(target_os = "windows")]
+    {
+        windows_associations::set_torrent_default()
+    }
+    #[cfg(target_os = "linux")]
+    {
+        linux_associations::set_torrent_default()
+    }
+    #[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
     {
         Ok(())
     }
@@ -41,7 +57,15 @@ pub async fn set_default_for_magnets() -> Result<()> {
     {
         macos_associations::set_magnet_default()
     }
-    #[cfg(not(target_os = "macos"))]
+    #[cfg(target_os = "windows")]
+    {
+        windows_associations::set_magnet_default()
+    }
+    #[cfg(target_os = "linux")]
+    {
+        linux_associations::set_magnet_default()
+    }
+    #[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
     {
         Ok(())
     }
@@ -265,3 +289,358 @@ mod macos_associations {
         }
     }
 }
+
+#[cfg(target_os = "windows")]
+mod windows_associations {
+    use super::FileAssociationStatus;
+    use crate::errors::{Result, WhenThenError};
+    use std::os::raw::c_void;
+
+    type HKEY = *mut c_void;
+    type LSTATUS = i32;
+
+    // Predefined keys are sign-extended 32-bit handles
+    const HKEY_CLASSES_ROOT: HKEY = 0x80000000u32 as i32 as isize as HKEY;
+    const HKEY_CURRENT_USER: HKEY = 0x80000001u32 as i32 as isize as HKEY;
+
+    const ERROR_SUCCESS: LSTATUS = 0;
+    const ERROR_ACCESS_DENIED: LSTATUS = 5;
+    const KEY_WRITE: u32 = 0x20006;
+    const REG_OPTION_NON_VOLATILE: u32 = 0;
+    const REG_SZ: u32 = 1;
+    const RRF_RT_REG_SZ: u32 = 0x2;
+    const SHCNE_ASSOCCHANGED: i32 = 0x08000000;
+    const SHCNF_IDLIST: u32 = 0;
+
+    const TORRENT_PROG_ID: &str = "When.TorrentFile";
+    const MAGNET_CLASS: &str = "magnet";
+    const TORRENT_USER_CHOICE: &str =
+        r"Software\Microsoft\Windows\CurrentVersion\Explorer\FileExts\.torrent\UserChoice";
+    const MAGNET_USER_CHOICE: &str =
+        r"Software\Microsoft\Windows\Shell\Associations\UrlAssociations\magnet\UserChoice";
+
+    #[link(name = "advapi32")]
+    extern "system" {
+        fn RegCreateKeyExW(
+            hkey: HKEY,
+            sub_key: *const u16,
+            reserved: u32,
+            class: *const u16,
+            options: u32,
+            sam_desired: u32,
+            security_attributes: *const c_void,
+            result: *mut HKEY,
+            disposition: *mut u32,
+        ) -> LSTATUS;
+        fn RegSetValueExW(
+            hkey: HKEY,
+            value_name: *const u16,
+            reserved: u32,
+            value_type: u32,
+            data: *const u8,
+            data_len: u32,
+        ) -> LSTATUS;
+        fn RegGetValueW(
+            hkey: HKEY,
+            sub_key: *const u16,
+            value: *const u16,
+            flags: u32,
+            value_type: *mut u32,
+            data: *mut c_void,
+            data_len: *mut u32,
+        ) -> LSTATUS;
+        fn RegCloseKey(hkey: HKEY) -> LSTATUS;
+    }
+
+    #[link(name = "shell32")]
+    extern "system" {
+        fn SHChangeNotify(event_id: i32, flags: u32, item1: *const c_void, item2: *const c_void);
+    }
+
+    fn wide(s: &str) -> Vec<u16> {
+        s.encode_utf16().chain(std::iter::once(0)).collect()
+    }
+
+    /// Read a string value; `None` for the key's default value.
+    fn read_string(root: HKEY, sub_key: &str, value: Option<&str>) -> Option<String> {
+        let sub_key = wide(sub_key);
+        let value = value.map(wide);
+        let value_ptr = value.as_ref().map_or(std::ptr::null(), |v| v.as_ptr());
+        unsafe {
+            let mut len: u32 = 0;
+            let status = RegGetValueW(
+                root,
+                sub_key.as_ptr(),
+                value_ptr,
+                RRF_RT_REG_SZ,
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+                &mut len,
+            );
+            if status != ERROR_SUCCESS || len == 0 {
+                return None;
+            }
+            let mut buf = vec![0u16; (len as usize).div_ceil(2)];
+            let status = RegGetValueW(
+                root,
+                sub_key.as_ptr(),
+                value_ptr,
+                RRF_RT_REG_SZ,
+                std::ptr::null_mut(),
+                buf.as_mut_ptr() as *mut c_void,
+                &mut len,
+            );
+            if status != ERROR_SUCCESS {
+                return None;
+            }
+            let end = buf.iter().position(|&c| c == 0).unwrap_or(buf.len());
+            Some(String::from_utf16_lossy(&buf[..end]))
+        }
+    }
+
+    fn registry_error(status: LSTATUS, path: &str) -> WhenThenError {
+        if status == ERROR_ACCESS_DENIED {
+            WhenThenError::Internal(format!("Access denied writing HKCU\\{path}"))
+        } else {
+            WhenThenError::Internal(format!("Could not write HKCU\\{path} (error {status})"))
+        }
+    }
+
+    /// Write a string value under HKCU, creating the key if needed.
+    fn write_string(sub_key: &str, value: Option<&str>, data: &str) -> Result<()> {
+        let path = format!(r"Software\Classes\{sub_key}");
+        let wide_path = wide(&path);
+        let value = value.map(wide);
+        let data = wide(data);
+        unsafe {
+            let mut key: HKEY = std::ptr::null_mut();
+            let status = RegCreateKeyExW(
+                HKEY_CURRENT_USER,
+                wide_path.as_ptr(),
+                0,
+                std::ptr::null(),
+                REG_OPTION_NON_VOLATILE,
+                KEY_WRITE,
+                std::ptr::null(),
+                &mut key,
+                std::ptr::null_mut(),
+            );
+            if status != ERROR_SUCCESS {
+                return Err(registry_error(status, &path));
+            }
+            let status = RegSetValueExW(
+                key,
+                value.as_ref().map_or(std::ptr::null(), |v| v.as_ptr()),
+                0,
+                REG_SZ,
+                data.as_ptr() as *const u8,
+                (data.len() * 2) as u32,
+            );
+            RegCloseKey(key);
+            if status != ERROR_SUCCESS {
+                return Err(registry_error(status, &path));
+            }
+        }
+        Ok(())
+    }
+
+    fn exe_path() -> Result<String> {
+        std::env::current_exe()
+            .map(|p| p.to_string_lossy().to_string())
+            .map_err(|e| WhenThenError::Internal(format!("Could not locate the When executable: {e}")))
+    }
+
+    /// Whether a class's open command launches this executable.
+    fn opens_with_us(class: &str) -> bool {
+        let Ok(exe) = exe_path() else {
+            return false;
+        };
+        read_string(HKEY_CLASSES_ROOT, &format!(r"{class}\shell\open\command"), None)
+            .is_some_and(|command| command.to_lowercase().contains(&exe.to_lowercase()))
+    }
+
+    /// Register `class` to open with this executable.
+    fn register_open_command(class: &str, exe: &str) -> Result<()> {
+        write_string(&format!(r"{class}\DefaultIcon"), None, &format!("\"{exe}\",0"))?;
+        write_string(&format!(r"{class}\shell\open\command"), None, &format!("\"{exe}\" \"%1\""))
+    }
+
+    fn notify_changed() {
+        unsafe { SHChangeNotify(SHCNE_ASSOCCHANGED, SHCNF_IDLIST, std::ptr::null(), std::ptr::null()) };
+    }
+
+    /// A choice made in Settings overrides HKCU\Software\Classes and can't be
+    /// changed by apps, so it decides whether we're really the default.
+    fn user_choice_allows(user_choice_key: &str, ours: &str) -> bool {
+        read_string(HKEY_CURRENT_USER, user_choice_key, Some("ProgId"))
+            .is_none_or(|prog_id| prog_id.eq_ignore_ascii_case(ours))
+    }
+
+    fn torrent_is_default() -> bool {
+        user_choice_allows(TORRENT_USER_CHOICE, TORRENT_PROG_ID)
+            && read_string(HKEY_CLASSES_ROOT, ".torrent", None)
+                .is_some_and(|prog_id| prog_id.eq_ignore_ascii_case(TORRENT_PROG_ID))
+            && opens_with_us(TORRENT_PROG_ID)
+    }
+
+    fn magnet_is_default() -> bool {
+        user_choice_allows(MAGNET_USER_CHOICE, MAGNET_CLASS) && opens_with_us(MAGNET_CLASS)
+    }
+
+    pub fn check() -> FileAssociationStatus {
+        FileAssociationStatus {
+            torrent_files: torrent_is_default(),
+            magnet_links: magnet_is_default(),
+        }
+    }
+
+    pub fn set_torrent_default() -> Result<()> {
+        let exe = exe_path()?;
+        write_string(TORRENT_PROG_ID, None, "Torrent file")?;
+        register_open_command(TORRENT_PROG_ID, &exe)?;
+        write_string(".torrent", None, TORRENT_PROG_ID)?;
+        write_string(r".torrent\OpenWithProgids", Some(TORRENT_PROG_ID), "")?;
+        notify_changed();
+
+        if !user_choice_allows(TORRENT_USER_CHOICE, TORRENT_PROG_ID) {
+            return Err(WhenThenError::Internal(
+                "Windows is set to open .torrent files with another app. Choose When under Settings > Apps > Default apps".into(),
+            ));
+        }
+        tracing::info!("Set default .torrent handler to {}", exe);
+        Ok(())
+    }
+
+    pub fn set_magnet_default() -> Result<()> {
+        let exe = exe_path()?;
+        write_string(MAGNET_CLASS, None, "URL:Magnet link")?;
+        write_string(MAGNET_CLASS, Some("URL Protocol"), "")?;
+        register_open_command(MAGNET_CLASS, &exe)?;
+        notify_changed();
+
+        if !user_choice_allows(MAGNET_USER_CHOICE, MAGNET_CLASS) {
+            return Err(WhenThenError::Internal(
+                "Windows is set to open magnet links with another app. Choose When under Settings > Apps > Default apps".into(),
+            ));
+        }
+        tracing::info!("Set default magnet: handler to {}", exe);
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod linux_associations {
+    use super::FileAssociationStatus;
+    use crate::errors::{Result, WhenThenError};
+    use std::path::PathBuf;
+    use std::process::Command;
+
+    const TORRENT_MIME: &str = "application/x-bittorrent";
+    const MAGNET_MIME: &str = "x-scheme-handler/magnet";
+
+    fn xdg_mime(args: &[&str]) -> Result<String> {
+        let output = Command::new("xdg-mime").args(args).output().map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => {
+                WhenThenError::Internal("xdg-mime isn't installed (it comes with xdg-utils)".into())
+            }
+            _ => WhenThenError::Internal(format!("Could not run xdg-mime: {e}")),
+        })?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(WhenThenError::Internal(format!(
+                "xdg-mime {} failed: {}",
+                args.join(" "),
+                stderr.trim()
+            )));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+
+    fn application_dirs() -> Vec<PathBuf> {
+        let home = std::env::var("HOME").unwrap_or_default();
+        let data_home = std::env::var("XDG_DATA_HOME")
+            .ok()
+            .filter(|d| !d.is_empty())
+            .unwrap_or_else(|| format!("{home}/.local/share"));
+        let data_dirs = std::env::var("XDG_DATA_DIRS")
+            .ok()
+            .filter(|d| !d.is_empty())
+            .unwrap_or_else(|| "/usr/local/share:/usr/share".into());
+
+        std::iter::once(data_home.as_str())
+            .chain(data_dirs.split(':'))
+            .map(|dir| PathBuf::from(dir).join("applications"))
+            .collect()
+    }
+
+    /// The installed .desktop file whose Exec launches this executable.
+    fn desktop_file_id() -> Option<String> {
+        // AppImages run from a temporary mount, but the desktop entry points at the image
+        let exe = std::env::var("APPIMAGE")
+            .map(PathBuf::from)
+            .or_else(|_| std::env::current_exe())
+            .ok()?;
+        let exe_name = exe.file_name()?.to_string_lossy().to_string();
+
+        for dir in application_dirs() {
+            let Ok(entries) = std::fs::read_dir(&dir) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if path.extension().and_then(|e| e.to_str()) != Some("desktop") {
+                    continue;
+                }
+                let Ok(contents) = std::fs::read_to_string(&path) else {
+                    continue;
+                };
+                let launches_us = contents.lines().any(|line| {
+                    line.strip_prefix("Exec=")
+                        .and_then(|exec| exec.split_whitespace().next())
+                        .map(|program| program.trim_matches('"'))
+                        .is_some_and(|program| {
+                            program == exe.to_string_lossy()
+                                || std::path::Path::new(program)
+                                    .file_name()
+                                    .is_some_and(|name| name.to_string_lossy() == exe_name)
+                        })
+                });
+                if launches_us {
+                    return Some(entry.file_name().to_string_lossy().to_string());
+                }
+            }
+        }
+        None
+    }
+
+    fn is_default(mime: &str, desktop_id: &str) -> bool {
+        xdg_mime(&["query", "default", mime]).is_ok_and(|current| current == desktop_id)
+    }
+
+    pub fn check() -> FileAssociationStatus {
+        let Some(desktop_id) = desktop_file_id() else {
+            return FileAssociationStatus { torrent_files: false, magnet_links: false };
+        };
+        FileAssociationStatus {
+            torrent_files: is_default(TORRENT_MIME, &desktop_id),
+            magnet_links: is_default(MAGNET_MIME, &desktop_id),
+        }
+    }
+
+    fn set_default(mime: &str) -> Result<()> {
+        let desktop_id = desktop_file_id().ok_or_else(|| {
+            WhenThenError::Internal("Could not find the When .desktop file; is the app installed?".into())
+        })?;
+        xdg_mime(&["default", &desktop_id, mime])?;
+        tracing::info!("Set default {} handler to {}", mime, desktop_id);
+        Ok(())
+    }
+
+    pub fn set_torrent_default() -> Result<()> {
+        set_default(TORRENT_MIME)
+    }
+
+    pub fn set_magnet_default() -> Result<()> {
+        set_default(MAGNET_MIME)
+    }
+}
@@ -1,8 +1,10 @@
 use std::time::Duration;
+use serde::Serialize;
 use tokio::io::AsyncWriteExt;
 use tokio::time::timeout;
 
 use crate::errors::{Result, WhenThenError};
+use crate::models::{CompletionAction, CompletionActionType};
 
 const TIMEOUT: Duration = Duration::from_secs(120);
 
@@ -117,3 +119,182 @@ pub async fn run_shell_command(command: String) -> Result<String> {
         )))
     }
 }
+
+/// Torrent details handed to a completion hook.
+#[derive(Debug, Clone, Serialize)]
+pub struct CompletionPayload {
+    pub name: String,
+    pub info_hash: String,
+    pub files: Vec<String>,
+    pub path: String,
+}
+
+/// A resolved hook invocation. Building one never spawns a process, so hooks can be dry-run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PreparedAction {
+    pub program: &'static str,
+    pub args: Vec<String>,
+    pub stdin: Option<String>,
+}
+
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+fn applescript_escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Substitute `{name}`, `{info_hash}`, `{path}` and `{files}` in a single pass,
+/// so placeholder-like text inside torrent names is never expanded.
+fn fill_placeholders(template: &str, payload: &CompletionPayload, escape: impl Fn(&str) -> String, files_sep: &str) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let tail = &rest[start..];
+        let end = tail.find('}').map(|i| i + 1).unwrap_or(tail.len());
+        match &tail[..end] {
+            "{name}" => out.push_str(&escape(&payload.name)),
+            "{info_hash}" => out.push_str(&escape(&payload.info_hash)),
+            "{path}" => out.push_str(&escape(&payload.path)),
+            "{files}" => {
+                let files: Vec<String> = payload.files.iter().map(|f| escape(f)).collect();
+                out.push_str(&files.join(files_sep));
+            }
+            other => out.push_str(other),
+        }
+        rest = &tail[end..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Build the process invocation for a completion hook without running it.
+/// Shell values are single-quoted; AppleScript values are escaped for use inside a string literal.
+pub fn prepare_completion_action(action: &CompletionAction, payload: &CompletionPayload) -> PreparedAction {
+    let json = serde_json::to_string(payload).unwrap_or_default();
+    match action.action_type {
+        CompletionActionType::Shortcut => PreparedAction {
+            program: "shortcuts",
+            args: vec!["run".into(), action.payload.clone(), "-i".into(), "-".into()],
+            stdin: Some(json),
+        },
+        CompletionActionType::AppleScript => PreparedAction {
+            program: "osascript",
+            args: vec![
+                "-e".into(),
+                fill_placeholders(&action.payload, payload, applescript_escape, "\\n"),
+            ],
+            stdin: None,
+        },
+        CompletionActionType::Shell => PreparedAction {
+            program: "sh",
+            args: vec![
+                "-c".into(),
+                fill_placeholders(&action.payload, payload, shell_quote, " "),
+            ],
+            stdin: Some(json),
+        },
+    }
+}
+
+/// Run a completion hook, feeding the JSON payload on stdin where supported.
+pub async fn run_completion_action(action: &CompletionAction, payload: &CompletionPayload) -> Result<String> {
+    let prepared = prepare_completion_action(action, payload);
+    let program = prepared.program;
+
+    let mut child = tokio::process::Command::new(program)
+        .args(&prepared.args)
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .map_err(|e| WhenThenError::Internal(format!("Failed to spawn {program}: {e}")))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        if let Some(input) = &prepared.stdin {
+            let _ = stdin.write_all(input.as_bytes()).await;
+        }
+        let _ = stdin.shutdown().await;
+    }
+
+    let output = timeout(TIMEOUT, child.wait_with_output())
+        .await
+        .map_err(|_| WhenThenError::Internal(format!("Completion hook ({program}) timed out after 120s")))?
+        .map_err(|e| WhenThenError::Internal(format!("Completion hook ({program}) failed: {e}")))?;
+
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let code = output.status.code().unwrap_or(-1);
+        Err(WhenThenError::Internal(format!(
+            "Completion hook ({program}) failed (exit {code}): {stderr}"
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn payload() -> CompletionPayload {
+        CompletionPayload {
+            name: "Show's \"Pilot\"".into(),
+            info_hash: "abc123".into(),
+            files: vec!["a.mkv".into(), "b c.srt".into()],
+            path: "/downloads/Show".into(),
+        }
+    }
+
+    #[test]
+    fn test_prepare_shell_quotes_placeholders() {
+        let action = CompletionAction {
+            action_type: CompletionActionType::Shell,
+            payload: "echo {name} {files} > {path}/done".into(),
+        };
+        let prepared = prepare_completion_action(&action, &payload());
+        assert_eq!(prepared.program, "sh");
+        assert_eq!(
+            prepared.args[1],
+            "echo 'Show'\\''s \"Pilot\"' 'a.mkv' 'b c.srt' > '/downloads/Show'/done"
+        );
+        assert!(prepared.stdin.unwrap().contains("\"info_hash\":\"abc123\""));
+    }
+
+    #[test]
+    fn test_placeholders_in_values_are_not_expanded() {
+        let mut p = payload();
+        p.name = "{path}".into();
+        let filled = fill_placeholders("{name} {x}", &p, shell_quote, " ");
+        assert_eq!(filled, "'{path}' {x}");
+    }
+
+    #[test]
+    fn test_prepare_applescript_escapes_strings() {
+        let action = CompletionAction {
+            action_type: CompletionActionType::AppleScript,
+            payload: "display notification \"{name}\"".into(),
+        };
+        let prepared = prepare_completion_action(&action, &payload());
+        assert_eq!(prepared.program, "osascript");
+        assert_eq!(prepared.args[1], "display notification \"Show's \\\"Pilot\\\"\"");
+        assert!(prepared.stdin.is_none());
+    }
+
+    #[test]
+    fn test_prepare_shortcut_passes_json() {
+        let action = CompletionAction {
+            action_type: CompletionActionType::Shortcut,
+            payload: "Organize {name}".into(),
+        };
+        let prepared = prepare_completion_action(&action, &payload());
+        assert_eq!(prepared.args, vec!["run", "Organize {name}", "-i", "-"]);
+        assert!(prepared.stdin.unwrap().contains("\"files\":[\"a.mkv\",\"b c.srt\"]"));
+    }
+}
@@ -1,8 +1,10 @@
 use tauri::{AppHandle, Emitter, State};
 
 use crate::errors::{WhenThenError, Result};
-use crate::models::{ChromecastDeviceInfo, DeviceStatus};
+use crate::models::{ChromecastDeviceInfo, DeviceProtocol, DeviceStatus};
+use crate::services::airplay_device::AirPlayConnection;
 use crate::services::chromecast_device::ChromecastConnection;
+use crate::services::device_connection::DeviceConnection;
 use crate::services::chromecast_discovery;
 use crate::state::AppState;
 
@@ -47,10 +49,10 @@ pub async fn chromecast_list_devices(
     let devices: Vec<ChromecastDeviceInfo> = discovered
         .values()
         .map(|d| {
-            let status = if connections.contains_key(&d.id) {
-                DeviceStatus::Connected
-            } else {
-                DeviceStatus::Discovered
+            let status = match connections.get(&d.id) {
+                Some(conn) if conn.is_reconnecting() => DeviceStatus::Connecting,
+                Some(_) => DeviceStatus::Connected,
+                None => DeviceStatus::Discovered,
             };
             d.to_info(status)
         })
@@ -65,6 +67,11 @@ pub async fn chromecast_connect(
     state: State<'_, AppState>,
     device_id: String,
 ) -> Result<()> {
+    connect_device(&app_handle, &state, device_id).await
+}
+
+/// Connect to a discovered device and announce it to the frontend.
+pub(crate) async fn connect_device(app_handle: &AppHandle, state: &AppState, device_id: String) -> Result<()> {
     let device = {
         let devices = state.discovered_devices.read().await;
         devices
@@ -73,20 +80,37 @@ pub async fn chromecast_connect(
             .clone()
     };
 
-    let connection = ChromecastConnection::connect(
-        device.id.clone(),
-        device.name.clone(),
-        device.address.clone(),
-        device.port,
-        Some(app_handle.clone()),
-    )
-    .await?;
+    let connection = match device.protocol {
+        DeviceProtocol::Chromecast => DeviceConnection::Chromecast(
+            ChromecastConnection::connect(
+                device.id.clone(),
+                device.name.clone(),
+                device.address.clone(),
+                device.port,
+                Some(app_handle.clone()),
+            )
+            .await?,
+        ),
+        DeviceProtocol::AirPlay => DeviceConnection::AirPlay(
+            AirPlayConnection::connect(
+                device.id.clone(),
+                device.name.clone(),
+                device.address.clone(),
+                device.port,
+            )
+            .await?,
+        ),
+    };
 
-    state
+    let replaced = state
         .active_connections
         .lock()
         .await
         .insert(device_id.clone(), connection);
+    // Also stops a reconnect still retrying for this device
+    if let Some(replaced) = replaced {
+        replaced.disconnect().await;
+    }
 
     #[derive(serde::Serialize, Clone)]
     struct Connected {
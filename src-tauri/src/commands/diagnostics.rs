@@ -0,0 +1,298 @@
+// Self-check for support requests: is the session up, is the media server
+// reachable, can we write downloads, are the configured services answering.
+
+use std::future::Future;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::pin::Pin;
+use std::time::Duration;
+
+use tauri::State;
+use tokio::task::JoinSet;
+use tokio::time::Instant;
+
+use crate::errors::Result;
+use crate::i18n::{t, t_with};
+use crate::models::{AppConfig, CheckStatus, DiagnosticCheck, DiagnosticsReport, Source, UpnpResult, UpnpStatus};
+use crate::services::torrent_engine;
+use crate::services::{opensub_client, rss};
+use crate::state::AppState;
+
+/// All network checks together must finish within this.
+const NETWORK_BUDGET: Duration = Duration::from_secs(10);
+
+/// Per-request timeout for the HTTP checks.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+type CheckFuture = Pin<Box<dyn Future<Output = DiagnosticCheck> + Send>>;
+
+fn check(id: &str, status: CheckStatus, detail: impl Into<String>, fix: Option<String>) -> DiagnosticCheck {
+    DiagnosticCheck {
+        id: id.to_string(),
+        label: t(&format!("diagnostics.{}.label", label_key(id))),
+        status,
+        detail: detail.into(),
+        remediation: fix,
+    }
+}
+
+/// Locale section for a check id ("media_server_lan" -> "mediaServerLan").
+fn label_key(id: &str) -> String {
+    let mut key = String::with_capacity(id.len());
+    let mut upper = false;
+    for c in id.chars() {
+        if c == '_' {
+            upper = true;
+        } else if upper {
+            key.push(c.to_ascii_uppercase());
+            upper = false;
+        } else {
+            key.push(c);
+        }
+    }
+    key
+}
+
+fn fix(id: &str, name: &str) -> Option<String> {
+    Some(t(&format!("diagnostics.{}.{}", label_key(id), name)))
+}
+
+fn gigabytes(bytes: u64) -> String {
+    format!("{:.1} GB", bytes as f64 / 1_000_000_000.0)
+}
+
+/// Run every check and report pass/warn/fail for each.
+#[tauri::command]
+pub async fn diagnostics_run(state: State<'_, AppState>) -> Result<DiagnosticsReport> {
+    let config = state.config.read().await.clone();
+    let session = state.torrent_session.read().await.clone();
+
+    let mut checks = vec![
+        match &session {
+            Some(_) => check("session", CheckStatus::Pass, "", None),
+            None => check("session", CheckStatus::Fail, "", fix("session", "fix")),
+        },
+        dht_check(session.as_deref()),
+        upnp_check(&config, &state.port_mapping.last().await),
+        download_dir_check(&config),
+    ];
+
+    let deadline = Instant::now() + NETWORK_BUDGET;
+    let port = state.media_server.port();
+    let bind = state.media_server.bind_address();
+    let scheme = state.media_server.scheme();
+    let sources: Vec<Source> = state
+        .rss_state
+        .sources
+        .read()
+        .await
+        .iter()
+        .filter(|s| s.enabled)
+        .cloned()
+        .collect();
+
+    let network: Vec<(&str, CheckFuture)> = vec![
+        ("listen_port", Box::pin(listen_port_check(config.listen_port, session.is_some()))),
+        ("media_server_local", Box::pin(media_server_check("media_server_local", scheme, IpAddr::V4(Ipv4Addr::LOCALHOST), port))),
+        ("media_server_lan", Box::pin(media_server_lan_check(scheme, bind, port))),
+        ("opensubtitles", Box::pin(opensubtitles_check(config.opensubtitles_api_key.clone()))),
+        ("rss_sources", Box::pin(sources_check(sources))),
+    ];
+
+    let mut tasks = JoinSet::new();
+    for (index, (id, future)) in network.into_iter().enumerate() {
+        tasks.spawn(async move {
+            let result = tokio::time::timeout_at(deadline, future).await;
+            (index, result.unwrap_or_else(|_| {
+                check(id, CheckStatus::Fail, t("diagnostics.timedOut"), Some(t("diagnostics.timedOutFix")))
+            }))
+        });
+    }
+    let mut finished = Vec::new();
+    while let Some(joined) = tasks.join_next().await {
+        if let Ok(result) = joined {
+            finished.push(result);
+        }
+    }
+    // Keep a stable order regardless of which check answered first
+    finished.sort_by_key(|(index, _)| *index);
+    checks.extend(finished.into_iter().map(|(_, check)| check));
+
+    Ok(DiagnosticsReport {
+        ran_at: chrono::Utc::now().to_rfc3339(),
+        checks,
+    })
+}
+
+async fn listen_port_check(first: u16, session_up: bool) -> DiagnosticCheck {
+    let last = torrent_engine::last_listen_port(first);
+    let range = format!("{first}–{last}");
+    if !session_up {
+        return check("listen_port", CheckStatus::Skipped, range, None);
+    }
+    if let Some(port) = torrent_engine::bound_listen_port(first).await {
+        return check("listen_port", CheckStatus::Pass, port.to_string(), None);
+    }
+    let from = first.to_string();
+    let to = last.to_string();
+    check(
+        "listen_port",
+        CheckStatus::Fail,
+        range,
+        Some(t_with("diagnostics.listenPort.fix", &[("from", &from), ("to", &to)])),
+    )
+}
+
+async fn media_server_check(id: &'static str, scheme: &str, host: IpAddr, port: u16) -> DiagnosticCheck {
+    let url = format!("{scheme}://{}/health", SocketAddr::new(host, port));
+    // The HTTPS certificate is self-signed, and only reachability matters here
+    let client = reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .danger_accept_invalid_certs(true)
+        .build()
+        .unwrap_or_default();
+    match client.get(&url).send().await {
+        Ok(response) if response.status().is_success() => check(id, CheckStatus::Pass, url, None),
+        Ok(response) => check(id, CheckStatus::Fail, format!("{url}: {}", response.status()), fix(id, "fix")),
+        Err(e) => check(id, CheckStatus::Fail, format!("{url}: {e}"), fix(id, "fix")),
+    }
+}
+
+async fn media_server_lan_check(scheme: &str, bind: IpAddr, port: u16) -> DiagnosticCheck {
+    if bind.is_loopback() {
+        return check(
+            "media_server_lan",
+            CheckStatus::Warn,
+            bind.to_string(),
+            fix("media_server_lan", "localOnly"),
+        );
+    }
+    match torrent_engine::get_local_ip().parse::<IpAddr>() {
+        Ok(ip) if !ip.is_loopback() => media_server_check("media_server_lan", scheme, ip, port).await,
+        _ => check("media_server_lan", CheckStatus::Warn, "", fix("media_server_lan", "noNetwork")),
+    }
+}
+
+fn dht_check(session: Option<&librqbit::Session>) -> DiagnosticCheck {
+    let Some(session) = session else {
+        return check("dht", CheckStatus::Skipped, "", None);
+    };
+    if session.get_dht().is_none() {
+        return check("dht", CheckStatus::Warn, "", fix("dht", "disabled"));
+    }
+    match torrent_engine::dht_node_count(session) {
+        Some(0) => check("dht", CheckStatus::Warn, "0", fix("dht", "fix")),
+        Some(nodes) => check("dht", CheckStatus::Pass, nodes.to_string(), None),
+        None => check("dht", CheckStatus::Pass, "", None),
+    }
+}
+
+fn upnp_check(config: &AppConfig, result: &UpnpResult) -> DiagnosticCheck {
+    let from = config.listen_port.to_string();
+    let to = torrent_engine::last_listen_port(config.listen_port).to_string();
+    let fix = || Some(t_with("diagnostics.upnp.fix", &[("from", &from), ("to", &to)]));
+    if !config.enable_upnp {
+        return check("upnp", CheckStatus::Warn, "", fix());
+    }
+    match result.status {
+        UpnpStatus::Mapped => {
+            let detail = match (&result.external_ip, result.port) {
+                (Some(ip), Some(port)) => format!("{ip}:{port}"),
+                _ => result.port.map(|p| p.to_string()).unwrap_or_default(),
+            };
+            check("upnp", CheckStatus::Pass, detail, None)
+        }
+        UpnpStatus::Failed => check("upnp", CheckStatus::Warn, result.error.clone().unwrap_or_default(), fix()),
+        UpnpStatus::Pending | UpnpStatus::Disabled => {
+            check("upnp", CheckStatus::Pass, t("diagnostics.upnp.requested"), None)
+        }
+    }
+}
+
+fn download_dir_check(config: &AppConfig) -> DiagnosticCheck {
+    let dir = torrent_engine::expand_path(&config.download_directory);
+    let shown = dir.display().to_string();
+    if !dir.is_dir() {
+        return check("download_dir", CheckStatus::Fail, shown, fix("download_dir", "missing"));
+    }
+
+    let probe = dir.join(".when-write-test");
+    let writable = std::fs::write(&probe, b"").is_ok();
+    let _ = std::fs::remove_file(&probe);
+    if !writable {
+        return check("download_dir", CheckStatus::Fail, shown, fix("download_dir", "notWritable"));
+    }
+
+    match torrent_engine::available_space(&dir) {
+        Some(free) if free < config.disk_space_reserve_bytes => check(
+            "download_dir",
+            CheckStatus::Warn,
+            format!("{shown} ({} free)", gigabytes(free)),
+            fix("download_dir", "lowSpace"),
+        ),
+        Some(free) => check("download_dir", CheckStatus::Pass, format!("{shown} ({} free)", gigabytes(free)), None),
+        None => check("download_dir", CheckStatus::Pass, shown, None),
+    }
+}
+
+async fn opensubtitles_check(api_key: String) -> DiagnosticCheck {
+    if api_key.trim().is_empty() {
+        return check("opensubtitles", CheckStatus::Skipped, t("diagnostics.opensubtitles.noKey"), None);
+    }
+    match opensub_client::check_api_key(api_key.trim()).await {
+        Ok(true) => check("opensubtitles", CheckStatus::Pass, "", None),
+        Ok(false) => check("opensubtitles", CheckStatus::Fail, "", fix("opensubtitles", "fix")),
+        Err(e) => check("opensubtitles", CheckStatus::Warn, e.to_string(), fix("opensubtitles", "unreachable")),
+    }
+}
+
+/// HEAD every enabled source at once. Any HTTP answer counts as reachable,
+/// since some feeds reject HEAD but are otherwise fine.
+async fn sources_check(sources: Vec<Source>) -> DiagnosticCheck {
+    if sources.is_empty() {
+        return check("rss_sources", CheckStatus::Skipped, "", None);
+    }
+    let client = reqwest::Client::builder().timeout(REQUEST_TIMEOUT).build().unwrap_or_default();
+    let total = sources.len();
+    let mut tasks = JoinSet::new();
+    for source in sources {
+        let client = client.clone();
+        tasks.spawn(async move {
+            let url = rss::search_url_for_term(&source.url, "");
+            let reachable = client.head(&url).send().await.is_ok();
+            (source.name, reachable)
+        });
+    }
+
+    let mut unreachable = Vec::new();
+    while let Some(joined) = tasks.join_next().await {
+        if let Ok((name, false)) = joined {
+            unreachable.push(name);
+        }
+    }
+    unreachable.sort();
+
+    let status = match unreachable.len() {
+        0 => CheckStatus::Pass,
+        n if n == total => CheckStatus::Fail,
+        _ => CheckStatus::Warn,
+    };
+    let reachable = (total - unreachable.len()).to_string();
+    let total = total.to_string();
+    let mut detail = t_with("diagnostics.rssSources.summary", &[("reachable", &reachable), ("total", &total)]);
+    if !unreachable.is_empty() {
+        detail = format!("{detail}: {}", unreachable.join(", "));
+    }
+    let remediation = (status != CheckStatus::Pass).then(|| t("diagnostics.rssSources.fix"));
+    check("rss_sources", status, detail, remediation)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_ids_map_to_locale_sections() {
+        assert_eq!(label_key("media_server_lan"), "mediaServerLan");
+        assert_eq!(label_key("dht"), "dht");
+    }
+}
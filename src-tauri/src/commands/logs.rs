@@ -0,0 +1,71 @@
+use tauri::{AppHandle, Manager, State};
+
+use crate::commands::settings::apply_config;
+use crate::errors::{Result, WhenThenError};
+use crate::services::logs::LogEntry;
+use crate::services::torrent_engine;
+use crate::state::AppState;
+
+/// Entries returned when no limit is given.
+const DEFAULT_LIMIT: usize = 500;
+
+/// Newest log entries, oldest first. `level_filter` ("warn", "error", ...)
+/// keeps that level and anything more severe.
+#[tauri::command]
+pub async fn logs_get_recent(
+    state: State<'_, AppState>,
+    limit: Option<usize>,
+    level_filter: Option<String>,
+) -> Result<Vec<LogEntry>> {
+    let min_level = level_filter
+        .filter(|level| !level.trim().is_empty())
+        .map(|level| {
+            level
+                .trim()
+                .parse::<tracing::Level>()
+                .map_err(|_| WhenThenError::InvalidInput(format!("Unknown log level: {level}")))
+        })
+        .transpose()?;
+    Ok(state.logs.recent(limit.unwrap_or(DEFAULT_LIMIT), min_level))
+}
+
+/// Write the buffered log to `path`. `keep_log_files` turns daily log files
+/// under app_data_dir/logs on or off, from now and for later launches.
+/// Returns the number of entries written.
+#[tauri::command]
+pub async fn logs_export(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    path: String,
+    keep_log_files: Option<bool>,
+) -> Result<usize> {
+    let path = torrent_engine::expand_path(&path);
+    let written = state.logs.export(&path)?;
+
+    if let Some(enabled) = keep_log_files {
+        if enabled {
+            let dir = app
+                .path()
+                .app_data_dir()
+                .map_err(|e| WhenThenError::Internal(format!("No app data directory: {e}")))?;
+            state.logs.enable_file(dir.join("logs"))?;
+        } else {
+            state.logs.disable_file();
+        }
+        let mut config = state.config.read().await.clone();
+        if config.log_to_file != enabled {
+            config.log_to_file = enabled;
+            apply_config(&app, &state, config).await;
+        }
+    }
+
+    tracing::info!("Exported {} log entries to {}", written, path.display());
+    Ok(written)
+}
+
+/// Change what gets logged without restarting: a level name for the app's own
+/// output ("debug"), or a full filter like "when_lib=trace,librqbit=debug".
+#[tauri::command]
+pub async fn logs_set_level(state: State<'_, AppState>, level: String) -> Result<()> {
+    state.logs.set_level(&level)
+}
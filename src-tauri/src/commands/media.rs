@@ -1,11 +1,12 @@
 use serde::Serialize;
-use tauri::State;
+use tauri::{AppHandle, State};
 
-use crate::errors::Result;
-use crate::models::{SubtitleInfo, SubtitleDownloadResult};
+use crate::errors::{Result, WhenThenError};
+use crate::models::{MediaServerCertInfo, PlaybackState, SubtitleCandidate, SubtitleData, SubtitleInfo, SubtitleDownloadResult, SubtitleQuota, ThumbnailInfo};
 use crate::services::subtitle_handler;
 use crate::services::subtitle_search;
-use crate::services::torrent_engine::{get_local_ip, move_torrent_files as engine_move_files};
+use crate::services::thumbnails;
+use crate::services::torrent_engine::move_torrent_files as engine_move_files;
 use crate::state::AppState;
 
 #[derive(Debug, Clone, Serialize)]
@@ -22,20 +23,61 @@ pub async fn subtitle_load_file(
 ) -> Result<SubtitleInfo> {
     let data = subtitle_handler::load_subtitle_file(&path)?;
 
-    let name = data.original_name.clone();
-    let format = if path.ends_with(".srt") {
-        "srt".to_string()
-    } else {
-        "vtt".to_string()
-    };
-
+    let info = subtitle_info(&state, &data);
     *state.current_subtitles.write().await = Some(data);
+    Ok(info)
+}
+
+fn subtitle_info(state: &AppState, data: &SubtitleData) -> SubtitleInfo {
+    SubtitleInfo {
+        url: format!("{}/subtitles.vtt", state.media_server.base_url()),
+        name: data.original_name.clone(),
+        format: data.format.clone(),
+        encoding: data.encoding.clone(),
+        dropped_cues: data.dropped_cues,
+        offset_ms: data.offset_ms,
+    }
+}
+
+/// Shift the loaded subtitles. The offset is absolute: calling again replaces it.
+#[tauri::command]
+pub async fn subtitle_set_offset(
+    state: State<'_, AppState>,
+    offset_ms: i64,
+) -> Result<SubtitleInfo> {
+    let info = {
+        let mut subtitles = state.current_subtitles.write().await;
+        let data = subtitles
+            .as_mut()
+            .ok_or_else(|| WhenThenError::NotFound("No subtitles loaded".into()))?;
+        data.vtt_content = subtitle_handler::shift_vtt(&data.source_vtt, offset_ms);
+        data.offset_ms = offset_ms;
+        subtitle_info(&state, data)
+    };
 
-    let local_ip = get_local_ip();
-    let port = state.media_server.port;
-    let url = format!("http://{}:{}/subtitles.vtt", local_ip, port);
+    // Devices cache the side-loaded track; toggling it makes them fetch the shifted file.
+    // Only Chromecasts have one, and they're reloaded with the connections map unlocked.
+    let chromecasts: Vec<_> = state
+        .active_connections
+        .lock()
+        .await
+        .iter()
+        .filter_map(|(device_id, conn)| conn.chromecast().map(|c| (device_id.clone(), c)))
+        .collect();
+    for (device_id, conn) in chromecasts {
+        let playing = conn
+            .get_status()
+            .await
+            .map(|s| s.state != PlaybackState::Idle)
+            .unwrap_or(false);
+        if playing {
+            if let Err(e) = conn.reload_subtitles().await {
+                tracing::warn!("Failed to reload subtitles on {}: {}", device_id, e);
+            }
+        }
+    }
 
-    Ok(SubtitleInfo { url, name, format })
+    Ok(info)
 }
 
 #[tauri::command]
@@ -46,25 +88,41 @@ pub async fn subtitle_clear(state: State<'_, AppState>) -> Result<()> {
 
 #[tauri::command]
 pub async fn media_server_url(state: State<'_, AppState>) -> Result<String> {
-    let local_ip = get_local_ip();
-    let port = state.media_server.port;
-    Ok(format!("http://{}:{}", local_ip, port))
+    Ok(state.media_server.base_url())
+}
+
+/// Fingerprint and expiry of the HTTPS certificate, so it can be checked and
+/// trusted on other devices. None while the server runs plain HTTP.
+#[tauri::command]
+pub async fn media_server_cert_info(state: State<'_, AppState>) -> Result<Option<MediaServerCertInfo>> {
+    Ok(state.media_server.cert_info())
 }
 
 #[tauri::command]
 pub async fn get_playlist_url(state: State<'_, AppState>, torrent_id: usize) -> Result<String> {
-    let local_ip = get_local_ip();
-    let port = state.media_server.port;
-    Ok(format!("http://{}:{}/torrent/{}/playlist.m3u8", local_ip, port, torrent_id))
+    let token = &state.media_server.stream_token;
+    Ok(format!("{}/torrent/{}/playlist.m3u8?token={}", state.media_server.base_url(), torrent_id, token))
+}
+
+/// Preview frame for a video file. Starts generating it when there's none yet,
+/// so the frontend polls until the status is ready or unavailable.
+#[tauri::command]
+pub async fn media_get_thumbnail(
+    state: State<'_, AppState>,
+    torrent_id: usize,
+    file_index: usize,
+) -> Result<ThumbnailInfo> {
+    thumbnails::request(&state, torrent_id, file_index).await
 }
 
 #[tauri::command]
 pub async fn move_torrent_files(
+    app_handle: AppHandle,
     state: State<'_, AppState>,
     torrent_id: usize,
     destination: String,
 ) -> Result<()> {
-    engine_move_files(&state, torrent_id, destination).await
+    engine_move_files(&state, &app_handle, torrent_id, destination).await
 }
 
 #[tauri::command]
@@ -77,6 +135,32 @@ pub async fn subtitle_search_opensubtitles(
     subtitle_search::search_and_download(&state, torrent_id, file_index, languages).await
 }
 
+#[tauri::command]
+pub async fn subtitle_search_candidates(
+    state: State<'_, AppState>,
+    torrent_id: usize,
+    file_index: usize,
+    languages: Vec<String>,
+) -> Result<Vec<SubtitleCandidate>> {
+    subtitle_search::search_candidates(&state, torrent_id, file_index, languages).await
+}
+
+#[tauri::command]
+pub async fn subtitle_download_candidate(
+    state: State<'_, AppState>,
+    torrent_id: usize,
+    file_index: usize,
+    file_id: i64,
+    language: String,
+) -> Result<SubtitleDownloadResult> {
+    subtitle_search::download_candidate(&state, torrent_id, file_index, file_id, language).await
+}
+
+#[tauri::command]
+pub async fn subtitle_quota(state: State<'_, AppState>) -> Result<Option<SubtitleQuota>> {
+    subtitle_search::quota(&state).await
+}
+
 #[tauri::command]
 pub async fn list_media_players() -> Result<Vec<MediaPlayer>> {
     #[cfg(target_os = "macos")]
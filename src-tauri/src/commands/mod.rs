@@ -7,3 +7,11 @@ pub mod automation;
 pub mod associations;
 pub mod rss;
 pub mod scraper;
+pub mod torznab;
+pub mod stats;
+pub mod logs;
+pub mod diagnostics;
+pub mod network;
+pub mod window;
+pub mod startup;
+pub mod power;
@@ -0,0 +1,90 @@
+// Listen port, UPnP and DHT status, plus an optional outside check of
+// whether peers can reach the port. Users behind CGNAT see "closed" here
+// rather than wondering why speeds are poor.
+
+use std::time::Duration;
+
+use tauri::State;
+
+use crate::errors::Result;
+use crate::models::{NetworkStatus, PortReachability};
+use crate::services::torrent_engine;
+use crate::state::AppState;
+
+const CHECK_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[tauri::command]
+pub async fn network_status(state: State<'_, AppState>, check_reachability: Option<bool>) -> Result<NetworkStatus> {
+    let config = state.config.read().await.clone();
+    let session = state.torrent_session.read().await.clone();
+
+    let bound_port = match &session {
+        Some(_) => torrent_engine::bound_listen_port(config.listen_port).await,
+        None => None,
+    };
+    let reachability = match bound_port {
+        Some(port) if check_reachability.unwrap_or(false) => check_port(&config.port_check_url, port).await,
+        _ => PortReachability::Unknown,
+    };
+
+    Ok(NetworkStatus {
+        listen_port_start: config.listen_port,
+        listen_port_end: torrent_engine::last_listen_port(config.listen_port),
+        bound_port,
+        upnp: state.port_mapping.last().await,
+        dht_nodes: session.as_deref().and_then(torrent_engine::dht_node_count),
+        reachability,
+    })
+}
+
+/// Ask the configured service whether `port` is open. Anything short of a
+/// clear yes or no is unknown.
+async fn check_port(url_template: &str, port: u16) -> PortReachability {
+    let template = url_template.trim();
+    if template.is_empty() {
+        return PortReachability::Unknown;
+    }
+    let url = template.replace("{port}", &port.to_string());
+    let client = reqwest::Client::builder().timeout(CHECK_TIMEOUT).build().unwrap_or_default();
+    match client.get(&url).send().await {
+        Ok(response) if response.status().is_success() => match response.text().await {
+            Ok(body) => parse_reachability(&body),
+            Err(_) => PortReachability::Unknown,
+        },
+        _ => PortReachability::Unknown,
+    }
+}
+
+/// Accepts a bare word ("open", "true", "closed", ...) or JSON with an
+/// `open` or `reachable` boolean.
+fn parse_reachability(body: &str) -> PortReachability {
+    let flag = serde_json::from_str::<serde_json::Value>(body).ok().and_then(|json| {
+        json.as_bool()
+            .or_else(|| json.get("open").and_then(|v| v.as_bool()))
+            .or_else(|| json.get("reachable").and_then(|v| v.as_bool()))
+    });
+    let flag = flag.or_else(|| match body.trim().to_ascii_lowercase().as_str() {
+        "open" | "yes" | "1" => Some(true),
+        "closed" | "no" | "0" => Some(false),
+        _ => None,
+    });
+    match flag {
+        Some(true) => PortReachability::Open,
+        Some(false) => PortReachability::Closed,
+        None => PortReachability::Unknown,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reachability_answers_are_read_from_words_or_json() {
+        assert_eq!(parse_reachability("open\n"), PortReachability::Open);
+        assert_eq!(parse_reachability("false"), PortReachability::Closed);
+        assert_eq!(parse_reachability(r#"{"port": 4240, "open": true}"#), PortReachability::Open);
+        assert_eq!(parse_reachability(r#"{"reachable": false}"#), PortReachability::Closed);
+        assert_eq!(parse_reachability("<html>rate limited</html>"), PortReachability::Unknown);
+    }
+}
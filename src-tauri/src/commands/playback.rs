@@ -1,33 +1,51 @@
-use tauri::{AppHandle, State};
+use std::time::Duration;
+
+use tauri::{AppHandle, Emitter, State};
+use tracing::{info, warn};
 use uuid::Uuid;
 
+use crate::commands::chromecast::connect_device;
 use crate::errors::{WhenThenError, Result};
-use crate::models::PlaybackStatusResponse;
+use crate::models::{CastGroup, PlaybackStatusResponse, QueueItem, StreamReadiness};
+use crate::services::cast_group::{self, PlaybackAction};
 use crate::services::media_server::TokenEntry;
-use crate::services::torrent_engine::{get_local_ip, expand_path};
+use crate::services::torrent_engine::{resolve_id, stream_readiness};
+use crate::services::torrent_outputs;
+use crate::services::watch_history;
 use crate::state::AppState;
 
-#[tauri::command]
-pub async fn playback_cast_torrent(
-    _app_handle: AppHandle,
-    state: State<'_, AppState>,
-    device_id: String,
-    torrent_id: usize,
-    file_index: usize,
-) -> Result<()> {
-    let local_ip = get_local_ip();
-    let port = state.media_server.port;
+/// How often readiness is checked while a cast waits for the file.
+const READY_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// What a device needs to load a file.
+struct CastMedia {
+    url: String,
+    content_type: String,
+    title: String,
+    subtitle_url: Option<String>,
+    history_key: String,
+}
+
+async fn subtitle_url(state: &AppState) -> Option<String> {
+    let subs = state.current_subtitles.read().await;
+    subs.as_ref()
+        .map(|_| format!("{}/subtitles.vtt", state.media_server.base_url()))
+}
+
+async fn torrent_media(state: &AppState, torrent_id: usize, file_index: usize) -> Result<CastMedia> {
+    let base_url = state.media_server.base_url();
     let url = format!(
-        "http://{}:{}/torrent/{}/stream/{}",
-        local_ip, port, torrent_id, file_index
+        "{}/torrent/{}/stream/{}?token={}",
+        base_url, torrent_id, file_index, state.media_server.stream_token
     );
 
-    let content_type = {
+    let (content_type, title, history_key) = {
         let session_guard = state.torrent_session.read().await;
         let session = session_guard
             .as_ref()
             .ok_or_else(|| WhenThenError::Torrent("Session not initialized".into()))?;
 
+        let torrent_id = resolve_id(state, torrent_id).await;
         let handle = session
             .get(librqbit::api::TorrentIdOrHash::Id(torrent_id))
             .ok_or(WhenThenError::TorrentNotFound(torrent_id))?;
@@ -41,47 +59,33 @@ pub async fn playback_cast_torrent(
                     }).collect::<Vec<_>>()
                 })
                 .unwrap_or_default()
-        }).map_err(|e| WhenThenError::Torrent(format!("Metadata error: {e}")))?;
+        }).map_err(|e| WhenThenError::torrent("Metadata error", e))?;
 
         let filename = file_details
             .get(file_index)
             .ok_or_else(|| WhenThenError::Torrent("File index out of range".into()))?;
 
-        mime_guess::from_path(filename)
+        let content_type = mime_guess::from_path(filename)
             .first_raw()
             .unwrap_or("application/octet-stream")
-            .to_string()
-    };
-
-    let subtitle_url = {
-        let subs = state.current_subtitles.read().await;
-        if subs.is_some() {
-            Some(format!("http://{}:{}/subtitles.vtt", local_ip, port))
-        } else {
-            None
-        }
+            .to_string();
+        let title = filename.rsplit('/').next().unwrap_or(filename).to_string();
+        (content_type, title, watch_history::torrent_key(&handle.info_hash().as_string(), file_index))
     };
 
-    let connections = state.active_connections.lock().await;
-    let conn = connections
-        .get(&device_id)
-        .ok_or_else(|| WhenThenError::DeviceNotFound(device_id.clone()))?;
-
-    conn.load_media(url, content_type, subtitle_url).await?;
-
-    Ok(())
+    Ok(CastMedia {
+        url,
+        content_type,
+        title,
+        subtitle_url: subtitle_url(state).await,
+        history_key,
+    })
 }
 
-#[tauri::command]
-pub async fn playback_cast_local_file(
-    _app_handle: AppHandle,
-    state: State<'_, AppState>,
-    device_id: String,
-    file_path: String,
-) -> Result<()> {
-    let path = std::path::Path::new(&file_path);
+async fn local_media(state: &AppState, file_path: &str) -> Result<CastMedia> {
+    let path = std::path::Path::new(file_path);
     if !path.exists() {
-        return Err(WhenThenError::FileNotFound(file_path));
+        return Err(WhenThenError::FileNotFound(file_path.to_string()));
     }
 
     let token = Uuid::new_v4().to_string();
@@ -90,136 +94,471 @@ pub async fn playback_cast_local_file(
         .write()
         .await
         .insert(token.clone(), TokenEntry {
-            path: file_path.clone(),
+            path: file_path.to_string(),
             created_at: std::time::Instant::now(),
         });
 
-    let local_ip = get_local_ip();
-    let port = state.media_server.port;
-    let url = format!("http://{}:{}/local/{}", local_ip, port, token);
+    let base_url = state.media_server.base_url();
+    let url = format!("{}/local/{}", base_url, token);
 
-    let content_type = mime_guess::from_path(&file_path)
+    let content_type = mime_guess::from_path(file_path)
         .first_raw()
         .unwrap_or("application/octet-stream")
         .to_string();
 
-    let subtitle_url = {
-        let subs = state.current_subtitles.read().await;
-        if subs.is_some() {
-            Some(format!("http://{}:{}/subtitles.vtt", local_ip, port))
-        } else {
-            None
+    let title = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| file_path.to_string());
+
+    Ok(CastMedia {
+        url,
+        content_type,
+        title,
+        subtitle_url: subtitle_url(state).await,
+        history_key: watch_history::local_key(file_path),
+    })
+}
+
+async fn load_on_device(state: &AppState, device_id: &str, media: &CastMedia, start_position: Option<f64>) -> Result<()> {
+    let connections = state.active_connections.lock().await;
+    let conn = connections
+        .get(device_id)
+        .ok_or_else(|| WhenThenError::DeviceNotFound(device_id.to_string()))?;
+
+    // A Chromecast load with subtitles waits on the receiver; other commands
+    // shouldn't queue up behind it on the connections map
+    if let Some(chromecast) = conn.chromecast() {
+        drop(connections);
+        chromecast.load_media(media.url.clone(), media.content_type.clone(), media.subtitle_url.clone()).await?;
+        if let Some(position) = start_position.filter(|p| *p > 0.0) {
+            chromecast.seek(position).await?;
+        }
+        return Ok(());
+    }
+
+    conn.load_media(media.url.clone(), media.content_type.clone(), media.subtitle_url.clone()).await?;
+    if let Some(position) = start_position.filter(|p| *p > 0.0) {
+        conn.seek(position).await?;
+    }
+    Ok(())
+}
+
+async fn torrent_readiness(state: &AppState, torrent_id: usize, file_index: usize) -> Result<StreamReadiness> {
+    let session = state
+        .torrent_session
+        .read()
+        .await
+        .clone()
+        .ok_or(WhenThenError::SessionNotReady)?;
+    let internal_id = resolve_id(state, torrent_id).await;
+    let handle = session
+        .get(librqbit::api::TorrentIdOrHash::Id(internal_id))
+        .ok_or(WhenThenError::TorrentNotFound(torrent_id))?;
+    stream_readiness(&handle, file_index)
+        .ok_or_else(|| WhenThenError::InvalidInput(format!("File index out of range: {file_index}")))
+}
+
+/// Poll until the start of the file is downloaded or `timeout` passes,
+/// emitting playback:buffering each time. Casting goes ahead either way.
+async fn wait_until_ready(
+    app_handle: &AppHandle,
+    state: &AppState,
+    device_id: &str,
+    torrent_id: usize,
+    file_index: usize,
+    timeout: Duration,
+) -> Result<()> {
+    let deadline = tokio::time::Instant::now() + timeout;
+    loop {
+        let readiness = torrent_readiness(state, torrent_id, file_index).await?;
+        let _ = app_handle.emit(
+            "playback:buffering",
+            serde_json::json!({
+                "device_id": device_id,
+                "torrent_id": torrent_id,
+                "file_index": file_index,
+                "readiness": &readiness,
+            }),
+        );
+        if readiness.ready {
+            return Ok(());
+        }
+        if tokio::time::Instant::now() + READY_POLL_INTERVAL > deadline {
+            info!(torrent_id, file_index, "Casting before the file is ready; wait timed out");
+            return Ok(());
+        }
+        tokio::time::sleep(READY_POLL_INTERVAL).await;
+    }
+}
+
+/// Whether enough of a torrent file is downloaded to cast it without the
+/// receiver buffering right away.
+#[tauri::command]
+pub async fn playback_check_ready(
+    state: State<'_, AppState>,
+    torrent_id: usize,
+    file_index: usize,
+) -> Result<StreamReadiness> {
+    torrent_readiness(&state, torrent_id, file_index).await
+}
+
+/// `wait_ready_secs` holds off the LOAD for up to that long while the start
+/// of the file downloads, with playback:buffering events meanwhile.
+#[tauri::command]
+pub async fn playback_cast_torrent(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    device_id: String,
+    torrent_id: usize,
+    file_index: usize,
+    start_position: Option<f64>,
+    wait_ready_secs: Option<u64>,
+) -> Result<()> {
+    let media = torrent_media(&state, torrent_id, file_index).await?;
+    if let Some(secs) = wait_ready_secs.filter(|secs| *secs > 0) {
+        wait_until_ready(&app_handle, &state, &device_id, torrent_id, file_index, Duration::from_secs(secs)).await?;
+    }
+    load_on_device(&state, &device_id, &media, start_position).await?;
+    state.watch_history.write().await.start(&device_id, media.history_key);
+    Ok(())
+}
+
+/// Cast several files of a torrent as one receiver queue, e.g. a season, so
+/// the TV moves on to the next file by itself. Files that aren't video or
+/// audio are skipped. A receiver that won't take the queue gets just the first
+/// file, and playback:queue-unsupported says why.
+#[tauri::command]
+pub async fn playback_cast_torrent_queue(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    device_id: String,
+    torrent_id: usize,
+    file_indices: Vec<usize>,
+) -> Result<()> {
+    let mut medias = Vec::new();
+    for file_index in file_indices {
+        let media = torrent_media(&state, torrent_id, file_index).await?;
+        if media.content_type.starts_with("video/") || media.content_type.starts_with("audio/") {
+            medias.push(media);
         }
+    }
+    let Some(first) = medias.first() else {
+        return Err(WhenThenError::InvalidInput("None of the chosen files can be played".into()));
     };
 
+    let items = medias
+        .iter()
+        .map(|media| QueueItem {
+            url: media.url.clone(),
+            content_type: media.content_type.clone(),
+            title: media.title.clone(),
+            history_key: Some(media.history_key.clone()),
+        })
+        .collect();
+    let queued = {
+        let connections = state.active_connections.lock().await;
+        let conn = connections
+            .get(&device_id)
+            .ok_or_else(|| WhenThenError::DeviceNotFound(device_id.clone()))?;
+        match conn.chromecast() {
+            // Waits for the receiver to start the queue, so the map isn't held meanwhile
+            Some(chromecast) => {
+                drop(connections);
+                chromecast.load_queue(items).await
+            }
+            None => conn.load_queue(items).await,
+        }
+    };
+    if let Err(e) = queued {
+        warn!(device_id, error = %e, "Queue not accepted; casting the first file alone");
+        let _ = app_handle.emit(
+            "playback:queue-unsupported",
+            serde_json::json!({ "device_id": &device_id, "error": e.to_string() }),
+        );
+        load_on_device(&state, &device_id, first, None).await?;
+    }
+    state.watch_history.write().await.start(&device_id, first.history_key.clone());
+    Ok(())
+}
+
+/// Skip to the next file of a cast queue.
+#[tauri::command]
+pub async fn playback_queue_next(state: State<'_, AppState>, device_id: String) -> Result<()> {
     let connections = state.active_connections.lock().await;
     let conn = connections
         .get(&device_id)
         .ok_or_else(|| WhenThenError::DeviceNotFound(device_id.clone()))?;
+    conn.queue_jump(1).await
+}
 
-    conn.load_media(url, content_type, subtitle_url).await?;
+/// Go back to the previous file of a cast queue.
+#[tauri::command]
+pub async fn playback_queue_prev(state: State<'_, AppState>, device_id: String) -> Result<()> {
+    let connections = state.active_connections.lock().await;
+    let conn = connections
+        .get(&device_id)
+        .ok_or_else(|| WhenThenError::DeviceNotFound(device_id.clone()))?;
+    conn.queue_jump(-1).await
+}
 
+#[tauri::command]
+pub async fn playback_cast_local_file(
+    _app_handle: AppHandle,
+    state: State<'_, AppState>,
+    device_id: String,
+    file_path: String,
+) -> Result<()> {
+    let media = local_media(&state, &file_path).await?;
+    load_on_device(&state, &device_id, &media, None).await?;
+    state.watch_history.write().await.start(&device_id, media.history_key);
     Ok(())
 }
 
+/// Cast one file to several devices at once. The returned group id works with
+/// the playback controls like a device id.
+#[tauri::command]
+pub async fn playback_cast_group(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    device_ids: Vec<String>,
+    torrent_id: Option<usize>,
+    file_index: Option<usize>,
+    file_path: Option<String>,
+) -> Result<CastGroup> {
+    if device_ids.is_empty() {
+        return Err(WhenThenError::InvalidInput("A cast group needs at least one device".into()));
+    }
+    let media = match (torrent_id, file_index, file_path) {
+        (Some(torrent_id), Some(file_index), _) => torrent_media(&state, torrent_id, file_index).await?,
+        (_, _, Some(file_path)) => local_media(&state, &file_path).await?,
+        _ => return Err(WhenThenError::InvalidInput("Pass a torrent file or a local file path".into())),
+    };
+
+    let group_id = cast_group::new_group_id();
+    let mut members = Vec::new();
+    for device_id in device_ids {
+        let connected = state.active_connections.lock().await.contains_key(&device_id);
+        let result = match connected {
+            true => Ok(()),
+            false => connect_device(&app_handle, &state, device_id.clone()).await,
+        };
+        let result = match result {
+            Ok(()) => load_on_device(&state, &device_id, &media, None).await,
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(()) => members.push(device_id),
+            Err(e) => cast_group::drop_member(&app_handle, &state, &group_id, &device_id, &e.to_string()).await,
+        }
+    }
+
+    let leader = members
+        .first()
+        .cloned()
+        .ok_or_else(|| WhenThenError::CastConnection("No device in the group could load the media".into()))?;
+    state.watch_history.write().await.start(&leader, media.history_key);
+
+    let group = CastGroup {
+        id: group_id,
+        device_ids: members,
+        leader,
+    };
+    state.cast_groups.write().await.insert(group.id.clone(), group.clone());
+    Ok(group)
+}
+
 #[tauri::command]
 pub async fn playback_play(
+    app_handle: AppHandle,
     state: State<'_, AppState>,
     device_id: String,
 ) -> Result<()> {
-    let connections = state.active_connections.lock().await;
-    let conn = connections
-        .get(&device_id)
-        .ok_or_else(|| WhenThenError::DeviceNotFound(device_id.clone()))?;
-    conn.play().await
+    cast_group::control(&app_handle, &state, &device_id, PlaybackAction::Play).await
 }
 
 #[tauri::command]
 pub async fn playback_pause(
+    app_handle: AppHandle,
     state: State<'_, AppState>,
     device_id: String,
 ) -> Result<()> {
-    let connections = state.active_connections.lock().await;
-    let conn = connections
-        .get(&device_id)
-        .ok_or_else(|| WhenThenError::DeviceNotFound(device_id.clone()))?;
-    conn.pause().await
+    cast_group::control(&app_handle, &state, &device_id, PlaybackAction::Pause).await
 }
 
 #[tauri::command]
 pub async fn playback_stop(
+    app_handle: AppHandle,
     state: State<'_, AppState>,
     device_id: String,
 ) -> Result<()> {
-    let connections = state.active_connections.lock().await;
-    let conn = connections
-        .get(&device_id)
-        .ok_or_else(|| WhenThenError::DeviceNotFound(device_id.clone()))?;
-    let result = conn.stop().await;
-    drop(connections);
+    // History follows the group leader
+    let history_device = match cast_group::get(&state, &device_id).await {
+        Some(group) => group.leader,
+        None => device_id.clone(),
+    };
+    // Capture where playback was before the receiver unloads the media
+    let last_status = {
+        let connections = state.active_connections.lock().await;
+        match connections.get(&history_device) {
+            Some(conn) => conn.get_status().await.ok(),
+            None => None,
+        }
+    };
+    let result = cast_group::control(&app_handle, &state, &device_id, PlaybackAction::Stop).await;
+    // A stopped group has nothing left to control
+    state.cast_groups.write().await.remove(&device_id);
     *state.current_subtitles.write().await = None;
+
+    if let Some(status) = last_status {
+        state
+            .watch_history
+            .write()
+            .await
+            .stop(&history_device, status.current_time, status.duration);
+        watch_history::persist(&app_handle, &state).await;
+    }
     result
 }
 
 #[tauri::command]
 pub async fn playback_seek(
+    app_handle: AppHandle,
     state: State<'_, AppState>,
     device_id: String,
     position_secs: f64,
 ) -> Result<()> {
-    let connections = state.active_connections.lock().await;
-    let conn = connections
-        .get(&device_id)
-        .ok_or_else(|| WhenThenError::DeviceNotFound(device_id.clone()))?;
-    conn.seek(position_secs).await
+    cast_group::control(&app_handle, &state, &device_id, PlaybackAction::Seek(position_secs)).await
 }
 
 #[tauri::command]
 pub async fn playback_seek_relative(
+    app_handle: AppHandle,
     state: State<'_, AppState>,
     device_id: String,
     delta_secs: f64,
 ) -> Result<()> {
+    // Groups seek relative to the leader's position
+    let status_device = match cast_group::get(&state, &device_id).await {
+        Some(group) => group.leader,
+        None => device_id.clone(),
+    };
     let connections = state.active_connections.lock().await;
     let conn = connections
-        .get(&device_id)
-        .ok_or_else(|| WhenThenError::DeviceNotFound(device_id.clone()))?;
+        .get(&status_device)
+        .ok_or_else(|| WhenThenError::DeviceNotFound(status_device.clone()))?;
 
     let status = conn.get_status().await?;
     // Release lock before await to avoid holding across suspension point
     drop(connections);
 
     let new_position = (status.current_time + delta_secs).max(0.0);
+    cast_group::control(&app_handle, &state, &device_id, PlaybackAction::Seek(new_position)).await
+}
+
+#[tauri::command]
+pub async fn playback_set_volume(
+    state: State<'_, AppState>,
+    device_id: String,
+    volume: f64,
+) -> Result<()> {
     let connections = state.active_connections.lock().await;
     let conn = connections
         .get(&device_id)
         .ok_or_else(|| WhenThenError::DeviceNotFound(device_id.clone()))?;
-    conn.seek(new_position).await
+    conn.set_volume(volume.clamp(0.0, 1.0)).await
 }
 
 #[tauri::command]
-pub async fn playback_set_volume(
+pub async fn playback_set_muted(
     state: State<'_, AppState>,
     device_id: String,
-    volume: f64,
+    muted: bool,
 ) -> Result<()> {
     let connections = state.active_connections.lock().await;
     let conn = connections
         .get(&device_id)
         .ok_or_else(|| WhenThenError::DeviceNotFound(device_id.clone()))?;
-    conn.set_volume(volume.clamp(0.0, 1.0)).await
+    conn.set_muted(muted).await
 }
 
 #[tauri::command]
-pub async fn playback_get_status(
+pub async fn playback_set_subtitle_enabled(
     state: State<'_, AppState>,
     device_id: String,
-) -> Result<PlaybackStatusResponse> {
+    enabled: bool,
+) -> Result<()> {
     let connections = state.active_connections.lock().await;
     let conn = connections
         .get(&device_id)
         .ok_or_else(|| WhenThenError::DeviceNotFound(device_id.clone()))?;
-    conn.get_status().await
+    conn.set_subtitle_enabled(enabled).await
+}
+
+#[tauri::command]
+pub async fn playback_get_status(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    device_id: String,
+) -> Result<PlaybackStatusResponse> {
+    let (status, history_device) = match cast_group::get(&state, &device_id).await {
+        Some(group) => {
+            let status = cast_group::status(&app_handle, &state, &group).await?;
+            (status, group.leader)
+        }
+        None => {
+            let connections = state.active_connections.lock().await;
+            let conn = connections
+                .get(&device_id)
+                .ok_or_else(|| WhenThenError::DeviceNotFound(device_id.clone()))?;
+            (conn.get_status().await?, device_id)
+        }
+    };
+
+    let save = state
+        .watch_history
+        .write()
+        .await
+        .record(&history_device, status.current_time, status.duration);
+    if save {
+        watch_history::persist(&app_handle, &state).await;
+    }
+    Ok(status)
+}
+
+/// Position to resume a torrent file from, if it was left unfinished.
+#[tauri::command]
+pub async fn playback_get_resume(
+    state: State<'_, AppState>,
+    torrent_id: usize,
+    file_index: usize,
+) -> Result<Option<f64>> {
+    let info_hash = {
+        let session_guard = state.torrent_session.read().await;
+        let session = session_guard
+            .as_ref()
+            .ok_or_else(|| WhenThenError::Torrent("Session not initialized".into()))?;
+
+        let torrent_id = resolve_id(&state, torrent_id).await;
+        session
+            .get(librqbit::api::TorrentIdOrHash::Id(torrent_id))
+            .ok_or(WhenThenError::TorrentNotFound(torrent_id))?
+            .info_hash()
+            .as_string()
+    };
+
+    let key = watch_history::torrent_key(&info_hash, file_index);
+    Ok(state.watch_history.read().await.resume_position(&key))
+}
+
+#[tauri::command]
+pub async fn playback_clear_history(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<()> {
+    state.watch_history.write().await.clear();
+    watch_history::persist(&app_handle, &state).await;
+    Ok(())
 }
 
 #[tauri::command]
@@ -229,12 +568,13 @@ pub async fn playback_open_in_app(
     file_index: usize,
     app_name: String,
 ) -> Result<()> {
-    let (download_dir, relative_path) = {
+    let (output_folder, torrent_name, relative_path) = {
         let session_guard = state.torrent_session.read().await;
         let session = session_guard
             .as_ref()
             .ok_or_else(|| WhenThenError::Torrent("Session not initialized".into()))?;
 
+        let torrent_id = resolve_id(&state, torrent_id).await;
         let handle = session
             .get(librqbit::api::TorrentIdOrHash::Id(torrent_id))
             .ok_or(WhenThenError::TorrentNotFound(torrent_id))?;
@@ -248,18 +588,18 @@ pub async fn playback_open_in_app(
                     }).collect::<Vec<_>>()
                 })
                 .unwrap_or_default()
-        }).map_err(|e| WhenThenError::Torrent(format!("Metadata error: {e}")))?;
+        }).map_err(|e| WhenThenError::torrent("Metadata error", e))?;
 
         let relative = file_details
             .get(file_index)
             .ok_or_else(|| WhenThenError::Torrent("File index out of range".into()))?
             .clone();
 
-        let cfg = state.config.read().await;
-        (cfg.download_directory.clone(), relative)
+        let folder = torrent_outputs::folder(&state, &handle.info_hash().as_string()).await;
+        (folder, handle.name().unwrap_or_default(), relative)
     };
 
-    let full_path = expand_path(&download_dir).join(&relative_path);
+    let full_path = torrent_outputs::file_path(&output_folder, &torrent_name, &relative_path);
     if !full_path.exists() {
         return Err(WhenThenError::FileNotFound(
             full_path.to_string_lossy().to_string(),
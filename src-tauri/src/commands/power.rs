@@ -0,0 +1,8 @@
+use crate::models::PowerCapabilities;
+use crate::services::power_monitor;
+
+/// Whether the platform reports battery state, so the pause-on-battery setting can be shown.
+#[tauri::command]
+pub fn power_capabilities() -> PowerCapabilities {
+    power_monitor::capabilities()
+}
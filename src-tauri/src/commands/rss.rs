@@ -4,49 +4,33 @@ use tauri::State;
 use tauri_plugin_store::StoreExt;
 
 use crate::errors::Result;
-use crate::models::{BadItem, FeedFilter, FeedTestResult, Interest, PendingMatch, Source, TorrentFilePreview, TorrentMetadata};
-use crate::services::rss;
+use crate::models::{BadItem, FeedAuth, FeedFilter, FeedTestResult, FilterValidationError, HealthReport, Interest, InterestPreset, MatchedInterest, PendingMatch, SearchResponse, SearchResult, Source, SourceMetadata, SourceStatus, TorrentAddedResponse, TorrentFilePreview, TorrentMetadata};
+use crate::services::persistence::{self, RssStore};
+use crate::services::{rss, search, source_metadata};
 use crate::state::AppState;
 
-const SOURCES_STORE: &str = "sources.json";
-const INTERESTS_STORE: &str = "interests.json";
-const SEEN_ITEMS_STORE: &str = "seen_items.json";
-const BAD_ITEMS_STORE: &str = "bad_items.json";
-
 /// Max age for seen items before cleanup (60 days in seconds).
 const SEEN_ITEMS_MAX_AGE_SECS: i64 = 60 * 24 * 60 * 60;
 
-async fn persist_sources(app: &tauri::AppHandle, state: &AppState) {
-    if let Ok(store) = app.store(SOURCES_STORE) {
-        let sources = state.rss_state.sources.read().await;
-        if let Ok(value) = serde_json::to_value(&*sources) {
-            store.set("sources", value);
-            if let Err(e) = store.save() {
-                tracing::error!("Failed to save RSS sources: {}", e);
-            }
-        }
-    }
+/// Max age for pending matches before they are dropped on load (14 days in seconds).
+const PENDING_MATCHES_MAX_AGE_SECS: i64 = 14 * 24 * 60 * 60;
+
+fn persist_sources(state: &AppState) {
+    state.rss_writer.request(RssStore::Sources);
 }
 
 /// Internal version callable from rss service.
-pub async fn persist_sources_internal(app: &tauri::AppHandle, state: &AppState) {
-    persist_sources(app, state).await;
+pub fn persist_sources_internal(state: &AppState) {
+    persist_sources(state);
 }
 
-async fn persist_interests(app: &tauri::AppHandle, state: &AppState) {
-    if let Ok(store) = app.store(INTERESTS_STORE) {
-        let interests = state.rss_state.interests.read().await;
-        if let Ok(value) = serde_json::to_value(&*interests) {
-            store.set("interests", value);
-            if let Err(e) = store.save() {
-                tracing::error!("Failed to save RSS interests: {}", e);
-            }
-        }
-    }
+pub fn persist_interests(state: &AppState) {
+    state.rss_writer.request(RssStore::Interests);
 }
 
 pub async fn load_sources(app: &tauri::AppHandle, state: &AppState) {
-    if let Ok(store) = app.store(SOURCES_STORE) {
+    persistence::recover(app, RssStore::Sources.file());
+    if let Ok(store) = app.store(RssStore::Sources.file()) {
         // Load store contents from disk file before reading
         if let Err(e) = store.reload() {
             tracing::warn!("Could not load sources store: {}", e);
@@ -57,11 +41,13 @@ pub async fn load_sources(app: &tauri::AppHandle, state: &AppState) {
                 *state.rss_state.sources.write().await = sources;
             }
         }
+        persistence::release(&store);
     }
 }
 
 pub async fn load_interests(app: &tauri::AppHandle, state: &AppState) {
-    if let Ok(store) = app.store(INTERESTS_STORE) {
+    persistence::recover(app, RssStore::Interests.file());
+    if let Ok(store) = app.store(RssStore::Interests.file()) {
         // Load store contents from disk file before reading
         if let Err(e) = store.reload() {
             tracing::warn!("Could not load interests store: {}", e);
@@ -72,13 +58,15 @@ pub async fn load_interests(app: &tauri::AppHandle, state: &AppState) {
                 *state.rss_state.interests.write().await = interests;
             }
         }
+        persistence::release(&store);
     }
 }
 
 pub async fn load_seen_items(app: &tauri::AppHandle, state: &AppState) {
     use std::collections::HashMap;
 
-    if let Ok(store) = app.store(SEEN_ITEMS_STORE) {
+    persistence::recover(app, RssStore::SeenItems.file());
+    if let Ok(store) = app.store(RssStore::SeenItems.file()) {
         if let Err(e) = store.reload() {
             tracing::warn!("Could not load seen items store: {}", e);
         }
@@ -99,25 +87,19 @@ pub async fn load_seen_items(app: &tauri::AppHandle, state: &AppState) {
                 *state.rss_state.seen_items.lock().await = cleaned;
             }
         }
+        persistence::release(&store);
     }
 }
 
-pub async fn persist_seen_items(app: &tauri::AppHandle, state: &AppState) {
-    if let Ok(store) = app.store(SEEN_ITEMS_STORE) {
-        let seen = state.rss_state.seen_items.lock().await;
-        if let Ok(value) = serde_json::to_value(&*seen) {
-            store.set("seen_items", value);
-            if let Err(e) = store.save() {
-                tracing::error!("Failed to save seen items: {}", e);
-            }
-        }
-    }
+pub fn persist_seen_items(state: &AppState) {
+    state.rss_writer.request(RssStore::SeenItems);
 }
 
 pub async fn load_bad_items(app: &tauri::AppHandle, state: &AppState) {
     use std::collections::HashMap;
 
-    if let Ok(store) = app.store(BAD_ITEMS_STORE) {
+    persistence::recover(app, RssStore::BadItems.file());
+    if let Ok(store) = app.store(RssStore::BadItems.file()) {
         if let Err(e) = store.reload() {
             tracing::warn!("Could not load bad items store: {}", e);
         }
@@ -127,25 +109,90 @@ pub async fn load_bad_items(app: &tauri::AppHandle, state: &AppState) {
                 *state.rss_state.bad_items.write().await = items;
             }
         }
+        persistence::release(&store);
+    }
+}
+
+pub fn persist_bad_items(state: &AppState) {
+    state.rss_writer.request(RssStore::BadItems);
+}
+
+pub async fn load_pending_matches(app: &tauri::AppHandle, state: &AppState) {
+    persistence::recover(app, RssStore::PendingMatches.file());
+    if let Ok(store) = app.store(RssStore::PendingMatches.file()) {
+        if let Err(e) = store.reload() {
+            tracing::warn!("Could not load pending matches store: {}", e);
+        }
+        if let Some(value) = store.get("pending_matches") {
+            if let Ok(matches) = serde_json::from_value::<Vec<PendingMatch>>(value) {
+                // Drop matches that have been waiting longer than 14 days
+                let now = chrono::Utc::now();
+                let fresh: Vec<PendingMatch> = matches
+                    .into_iter()
+                    .filter(|m| {
+                        chrono::DateTime::parse_from_rfc3339(&m.created_at)
+                            .map(|t| (now - t.with_timezone(&chrono::Utc)).num_seconds() < PENDING_MATCHES_MAX_AGE_SECS)
+                            .unwrap_or(false)
+                    })
+                    .collect();
+                let fresh = fresh
+                    .into_iter()
+                    .map(|mut m| {
+                        if m.sources.is_empty() {
+                            m.sources.push(m.source_name.clone());
+                        }
+                        m
+                    })
+                    .collect::<Vec<_>>();
+
+                tracing::info!("Loaded {} pending matches from disk", fresh.len());
+                *state.rss_state.pending_matches.write().await = fresh;
+            }
+        }
+        persistence::release(&store);
     }
 }
 
-pub async fn persist_bad_items(app: &tauri::AppHandle, state: &AppState) {
-    if let Ok(store) = app.store(BAD_ITEMS_STORE) {
-        let bad = state.rss_state.bad_items.read().await;
-        if let Ok(value) = serde_json::to_value(&*bad) {
-            store.set("bad_items", value);
-            if let Err(e) = store.save() {
-                tracing::error!("Failed to save bad items: {}", e);
+pub fn persist_pending_matches(state: &AppState) {
+    state.rss_writer.request(RssStore::PendingMatches);
+}
+
+pub async fn load_match_counts(app: &tauri::AppHandle, state: &AppState) {
+    use std::collections::HashMap;
+
+    persistence::recover(app, RssStore::MatchCounts.file());
+    if let Ok(store) = app.store(RssStore::MatchCounts.file()) {
+        if let Err(e) = store.reload() {
+            tracing::warn!("Could not load interest match counts store: {}", e);
+        }
+        if let Some(value) = store.get("match_counts") {
+            if let Ok(counts) = serde_json::from_value::<HashMap<String, u32>>(value) {
+                *state.rss_state.match_counts.write().await = counts;
             }
         }
+        persistence::release(&store);
     }
 }
 
+pub fn persist_match_counts(state: &AppState) {
+    state.rss_writer.request(RssStore::MatchCounts);
+}
+
 // ── Source commands ───────────────────────────────────────────────────────────
 
 #[tauri::command]
-pub async fn rss_add_source(app: tauri::AppHandle, state: State<'_, AppState>, source: Source) -> Result<Source> {
+pub async fn rss_add_source(app: tauri::AppHandle, state: State<'_, AppState>, mut source: Source) -> Result<Source> {
+    if state.rss_state.sources.read().await.iter().any(|s| s.url == source.url) {
+        return Err(crate::errors::AppError::InvalidInput("Source URL already exists".into()));
+    }
+
+    // Only a URL was pasted: name it after the feed and pick up its icon
+    if source.name.trim().is_empty() || source.name.trim() == source.url {
+        let metadata = source_metadata::fetch(&app, &source.url).await;
+        source.name = metadata.suggested_name;
+        source.icon_path = source.icon_path.or(metadata.icon_path);
+    }
+
     {
         let mut sources = state.rss_state.sources.write().await;
 
@@ -155,12 +202,18 @@ pub async fn rss_add_source(app: tauri::AppHandle, state: State<'_, AppState>, s
 
         sources.push(source.clone());
     }
-    persist_sources(&app, &state).await;
+    persist_sources(&state);
     Ok(source)
 }
 
+/// Suggested name, description and cached icon for a feed URL.
 #[tauri::command]
-pub async fn rss_update_source(app: tauri::AppHandle, state: State<'_, AppState>, source: Source) -> Result<Source> {
+pub async fn rss_fetch_source_metadata(app: tauri::AppHandle, url: String) -> Result<SourceMetadata> {
+    Ok(source_metadata::fetch(&app, url.trim()).await)
+}
+
+#[tauri::command]
+pub async fn rss_update_source(state: State<'_, AppState>, source: Source) -> Result<Source> {
     {
         let mut sources = state.rss_state.sources.write().await;
 
@@ -170,17 +223,17 @@ pub async fn rss_update_source(app: tauri::AppHandle, state: State<'_, AppState>
             return Err(crate::errors::AppError::NotFound("Source not found".into()));
         }
     }
-    persist_sources(&app, &state).await;
+    persist_sources(&state);
     Ok(source)
 }
 
 #[tauri::command]
-pub async fn rss_remove_source(app: tauri::AppHandle, state: State<'_, AppState>, source_id: String) -> Result<()> {
+pub async fn rss_remove_source(state: State<'_, AppState>, source_id: String) -> Result<()> {
     {
         let mut sources = state.rss_state.sources.write().await;
         sources.retain(|s| s.id != source_id);
     }
-    persist_sources(&app, &state).await;
+    persist_sources(&state);
     Ok(())
 }
 
@@ -200,7 +253,7 @@ pub async fn rss_list_sources(app: tauri::AppHandle, state: State<'_, AppState>)
 }
 
 #[tauri::command]
-pub async fn rss_toggle_source(app: tauri::AppHandle, state: State<'_, AppState>, source_id: String, enabled: bool) -> Result<()> {
+pub async fn rss_toggle_source(state: State<'_, AppState>, source_id: String, enabled: bool) -> Result<()> {
     {
         let mut sources = state.rss_state.sources.write().await;
 
@@ -210,44 +263,78 @@ pub async fn rss_toggle_source(app: tauri::AppHandle, state: State<'_, AppState>
             return Err(crate::errors::AppError::NotFound("Source not found".into()));
         }
     }
-    persist_sources(&app, &state).await;
+    persist_sources(&state);
     Ok(())
 }
 
+#[tauri::command]
+pub async fn rss_source_status(state: State<'_, AppState>) -> Result<Vec<SourceStatus>> {
+    let sources = state.rss_state.sources.read().await;
+    Ok(sources.iter().map(SourceStatus::from).collect())
+}
+
 // ── Interest commands ─────────────────────────────────────────────────────────
 
 #[tauri::command]
-pub async fn rss_add_interest(app: tauri::AppHandle, state: State<'_, AppState>, interest: Interest) -> Result<Interest> {
+pub async fn rss_add_interest(state: State<'_, AppState>, interest: Interest) -> Result<Interest> {
+    rss::validate_interest(&interest)?;
     {
         let mut interests = state.rss_state.interests.write().await;
         interests.push(interest.clone());
     }
-    persist_interests(&app, &state).await;
+    persist_interests(&state);
+    Ok(interest)
+}
+
+/// Ready-made interests for the "start from a preset" picker.
+#[tauri::command]
+pub async fn rss_interest_presets() -> Result<Vec<InterestPreset>> {
+    Ok(rss::interest_presets())
+}
+
+/// Create and save an interest from a preset.
+#[tauri::command]
+pub async fn rss_apply_preset(
+    state: State<'_, AppState>,
+    preset_id: String,
+    name: String,
+    search_term: Option<String>,
+) -> Result<Interest> {
+    let interest = rss::interest_from_preset(&preset_id, &name, search_term)?;
+    state.rss_state.interests.write().await.push(interest.clone());
+    persist_interests(&state);
     Ok(interest)
 }
 
 #[tauri::command]
-pub async fn rss_update_interest(app: tauri::AppHandle, state: State<'_, AppState>, interest: Interest) -> Result<Interest> {
+pub async fn rss_update_interest(state: State<'_, AppState>, mut interest: Interest) -> Result<Interest> {
+    rss::validate_interest(&interest)?;
     {
         let mut interests = state.rss_state.interests.write().await;
 
         if let Some(existing) = interests.iter_mut().find(|i| i.id == interest.id) {
+            // Match times are the service's to keep
+            interest.last_match_at = existing.last_match_at.clone();
+            interest.stale_alert_reset_at = existing.stale_alert_reset_at.clone();
             *existing = interest.clone();
         } else {
             return Err(crate::errors::AppError::NotFound("Interest not found".into()));
         }
     }
-    persist_interests(&app, &state).await;
+    persist_interests(&state);
     Ok(interest)
 }
 
 #[tauri::command]
-pub async fn rss_remove_interest(app: tauri::AppHandle, state: State<'_, AppState>, interest_id: String) -> Result<()> {
+pub async fn rss_remove_interest(state: State<'_, AppState>, interest_id: String) -> Result<()> {
     {
         let mut interests = state.rss_state.interests.write().await;
         interests.retain(|i| i.id != interest_id);
     }
-    persist_interests(&app, &state).await;
+    persist_interests(&state);
+    if state.rss_state.match_counts.write().await.remove(&interest_id).is_some() {
+        persist_match_counts(&state);
+    }
     Ok(())
 }
 
@@ -266,26 +353,90 @@ pub async fn rss_list_interests(app: tauri::AppHandle, state: State<'_, AppState
     Ok(interests.clone())
 }
 
+/// Enable or disable an interest. `reset_count` clears its approved-match
+/// count, so a capped interest can start over instead of stopping again on
+/// the next approval.
 #[tauri::command]
-pub async fn rss_toggle_interest(app: tauri::AppHandle, state: State<'_, AppState>, interest_id: String, enabled: bool) -> Result<()> {
+pub async fn rss_toggle_interest(
+    state: State<'_, AppState>,
+    interest_id: String,
+    enabled: bool,
+    reset_count: Option<bool>,
+) -> Result<()> {
     {
         let mut interests = state.rss_state.interests.write().await;
 
         if let Some(interest) = interests.iter_mut().find(|i| i.id == interest_id) {
+            // Time spent disabled doesn't count toward a stale alert
+            if enabled && !interest.enabled {
+                interest.stale_alert_reset_at = Some(chrono::Utc::now().to_rfc3339());
+            }
             interest.enabled = enabled;
         } else {
             return Err(crate::errors::AppError::NotFound("Interest not found".into()));
         }
     }
-    persist_interests(&app, &state).await;
+    persist_interests(&state);
+    if reset_count.unwrap_or(false) && state.rss_state.match_counts.write().await.remove(&interest_id).is_some() {
+        persist_match_counts(&state);
+    }
     Ok(())
 }
 
+/// Approved matches per interest id.
+#[tauri::command]
+pub async fn rss_interest_match_counts(state: State<'_, AppState>) -> Result<std::collections::HashMap<String, u32>> {
+    Ok(state.rss_state.match_counts.read().await.clone())
+}
+
 // ── Test command ──────────────────────────────────────────────────────────────
 
 #[tauri::command]
-pub async fn rss_test_interest(url: String, filters: Vec<FeedFilter>) -> Result<FeedTestResult> {
-    rss::test_feed(&url, &filters).await
+pub async fn rss_test_interest(
+    url: String,
+    filters: Vec<FeedFilter>,
+    auth: Option<FeedAuth>,
+    user_agent: Option<String>,
+    request_timeout_secs: Option<u64>,
+) -> Result<FeedTestResult> {
+    let opts = rss::FeedFetchOptions {
+        auth: auth.as_ref(),
+        user_agent: user_agent.as_deref(),
+        timeout_secs: request_timeout_secs,
+    };
+    rss::test_feed(&url, &filters, &opts).await
+}
+
+/// Check a single filter, returning what's wrong with it if anything.
+#[tauri::command]
+pub async fn rss_validate_filter(filter: FeedFilter) -> Result<Option<FilterValidationError>> {
+    Ok(rss::validate_filter(&filter).err())
+}
+
+/// Test an interest, saved or not, against saved feed sources and scrapers.
+#[tauri::command]
+pub async fn rss_test_interest_against_sources(
+    state: State<'_, AppState>,
+    interest: Interest,
+    source_ids: Vec<String>,
+) -> Result<FeedTestResult> {
+    rss::test_interest_against_sources(&state, &interest, &source_ids).await
+}
+
+/// Search every searchable source once, without creating an interest.
+#[tauri::command]
+pub async fn rss_search(state: State<'_, AppState>, query: String) -> Result<SearchResponse> {
+    search::search_all(&state, &query).await
+}
+
+/// Add a result from rss_search as a torrent.
+#[tauri::command]
+pub async fn rss_add_search_result(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+    result: SearchResult,
+) -> Result<TorrentAddedResponse> {
+    search::add_result(&state, &app, result).await
 }
 
 // ── Screener commands ─────────────────────────────────────────────────────────
@@ -307,8 +458,22 @@ pub async fn rss_fetch_metadata(app_handle: tauri::AppHandle, match_id: String)
     rss::fetch_metadata(&app_handle, &match_id).await
 }
 
+/// Scrape the match's trackers for seeders and leechers. The best counts are
+/// also kept on the match.
+#[tauri::command]
+pub async fn rss_scrape_health(app_handle: tauri::AppHandle, match_id: String) -> Result<HealthReport> {
+    rss::check_health(&app_handle, &match_id).await
+}
+
+/// Start filling in metadata for pending matches in the background; results
+/// arrive as rss:metadata-fetched. Returns how many were queued.
 #[tauri::command]
-pub async fn rss_approve_match(app_handle: tauri::AppHandle, match_id: String) -> Result<i64> {
+pub async fn rss_prefetch_metadata(app_handle: tauri::AppHandle) -> Result<usize> {
+    Ok(rss::prefetch_metadata(&app_handle).await)
+}
+
+#[tauri::command]
+pub async fn rss_approve_match(app_handle: tauri::AppHandle, match_id: String) -> Result<Option<i64>> {
     rss::approve_match(&app_handle, &match_id).await
 }
 
@@ -349,7 +514,7 @@ pub async fn rss_mark_bad(
         let mut bad_items = state.rss_state.bad_items.write().await;
         bad_items.insert(info_hash, bad_item);
     }
-    persist_bad_items(&app_handle, &state).await;
+    persist_bad_items(&state);
 
     // Optionally trigger re-scan for the interest
     let mut new_matches = 0;
@@ -364,7 +529,6 @@ pub async fn rss_mark_bad(
 
 #[tauri::command]
 pub async fn rss_unmark_bad(
-    app_handle: tauri::AppHandle,
     state: State<'_, AppState>,
     info_hash: String,
 ) -> Result<()> {
@@ -372,7 +536,7 @@ pub async fn rss_unmark_bad(
         let mut bad_items = state.rss_state.bad_items.write().await;
         bad_items.remove(&info_hash);
     }
-    persist_bad_items(&app_handle, &state).await;
+    persist_bad_items(&state);
     Ok(())
 }
 
@@ -388,11 +552,23 @@ fn get_demo_matches() -> Vec<PendingMatch> {
             id: "demo-1".to_string(),
             source_id: "demo-source-1".to_string(),
             source_name: "Linux ISOs".to_string(),
+            sources: vec!["Linux ISOs".to_string()],
             interest_id: "demo-interest-1".to_string(),
             interest_name: "Ubuntu".to_string(),
+            matched_interests: vec![MatchedInterest {
+                id: "demo-interest-1".to_string(),
+                name: "Ubuntu".to_string(),
+            }],
             title: "ubuntu-24.04.1-desktop-amd64.iso".to_string(),
             magnet_uri: Some("magnet:?xt=urn:btih:demo1".to_string()),
             torrent_url: None,
+            size: None,
+            web_seeds: Vec::new(),
+            last_error: None,
+            failure_count: 0,
+            rejected_reason: None,
+            seeders_rejected_reason: None,
+            health: None,
             created_at: chrono::Utc::now().to_rfc3339(),
             metadata: Some(TorrentMetadata {
                 name: "ubuntu-24.04.1-desktop-amd64.iso".to_string(),
@@ -405,16 +581,31 @@ fn get_demo_matches() -> Vec<PendingMatch> {
                     is_suspicious: false,
                 }],
             }),
+            is_upgrade: false,
+            replaces_torrent_id: None,
+            replaces_title: None,
         },
         PendingMatch {
             id: "demo-2".to_string(),
             source_id: "demo-source-2".to_string(),
             source_name: "Blender Films".to_string(),
+            sources: vec!["Blender Films".to_string()],
             interest_id: "demo-interest-2".to_string(),
             interest_name: "Open Movies".to_string(),
+            matched_interests: vec![MatchedInterest {
+                id: "demo-interest-2".to_string(),
+                name: "Open Movies".to_string(),
+            }],
             title: "Big.Buck.Bunny.2008.4K.60fps.mkv".to_string(),
             magnet_uri: Some("magnet:?xt=urn:btih:demo2".to_string()),
             torrent_url: None,
+            size: None,
+            web_seeds: Vec::new(),
+            last_error: None,
+            failure_count: 0,
+            rejected_reason: None,
+            seeders_rejected_reason: None,
+            health: None,
             created_at: chrono::Utc::now().to_rfc3339(),
             metadata: Some(TorrentMetadata {
                 name: "Big.Buck.Bunny.2008.4K.60fps".to_string(),
@@ -435,18 +626,36 @@ fn get_demo_matches() -> Vec<PendingMatch> {
                     },
                 ],
             }),
+            is_upgrade: false,
+            replaces_torrent_id: None,
+            replaces_title: None,
         },
         PendingMatch {
             id: "demo-3".to_string(),
             source_id: "demo-source-2".to_string(),
             source_name: "Blender Films".to_string(),
+            sources: vec!["Blender Films".to_string()],
             interest_id: "demo-interest-2".to_string(),
             interest_name: "Open Movies".to_string(),
+            matched_interests: vec![MatchedInterest {
+                id: "demo-interest-2".to_string(),
+                name: "Open Movies".to_string(),
+            }],
             title: "Sintel.2010.1080p.mkv".to_string(),
             magnet_uri: Some("magnet:?xt=urn:btih:demo3".to_string()),
             torrent_url: None,
+            size: None,
+            web_seeds: Vec::new(),
+            last_error: None,
+            failure_count: 0,
+            rejected_reason: None,
+            seeders_rejected_reason: None,
+            health: None,
             created_at: chrono::Utc::now().to_rfc3339(),
             metadata: None,
+            is_upgrade: false,
+            replaces_torrent_id: None,
+            replaces_title: None,
         },
     ]
 }
@@ -1,32 +1,99 @@
 // Scraper commands for web scraping torrent sites.
 
-use tauri::State;
+use std::collections::HashMap;
+
+use tauri::{AppHandle, State};
+use tauri_plugin_store::StoreExt;
 
 use crate::errors::Result;
 use crate::models::{ScraperConfig, ScraperTestResult};
+use crate::services::persistence::{self, RssStore};
 use crate::services::scraper;
 use crate::state::AppState;
 
+/// Max age for seen items before cleanup (60 days in seconds).
+const SEEN_ITEMS_MAX_AGE_SECS: i64 = 60 * 24 * 60 * 60;
+
+pub fn persist_configs(state: &AppState) {
+    state.rss_writer.request(RssStore::ScraperConfigs);
+}
+
+pub async fn load_configs(app: &AppHandle, state: &AppState) {
+    persistence::recover(app, RssStore::ScraperConfigs.file());
+    if let Ok(store) = app.store(RssStore::ScraperConfigs.file()) {
+        if let Err(e) = store.reload() {
+            tracing::warn!("Could not load scrapers store: {}", e);
+        }
+        if let Some(value) = store.get("scrapers") {
+            if let Ok(configs) = serde_json::from_value::<Vec<ScraperConfig>>(value) {
+                tracing::info!("Loaded {} scrapers from disk", configs.len());
+                *state.scraper_state.configs.write().await = configs;
+            }
+        }
+        persistence::release(&store);
+    }
+}
+
+pub fn persist_seen_items(state: &AppState) {
+    state.rss_writer.request(RssStore::ScraperSeenItems);
+}
+
+pub async fn load_seen_items(app: &AppHandle, state: &AppState) {
+    persistence::recover(app, RssStore::ScraperSeenItems.file());
+    if let Ok(store) = app.store(RssStore::ScraperSeenItems.file()) {
+        if let Err(e) = store.reload() {
+            tracing::warn!("Could not load scraper seen items store: {}", e);
+        }
+        if let Some(value) = store.get("seen_items") {
+            if let Ok(items) = serde_json::from_value::<HashMap<String, String>>(value) {
+                // Clean up entries older than 60 days
+                let now = chrono::Utc::now();
+                let cleaned: HashMap<String, String> = items
+                    .into_iter()
+                    .filter(|(_, timestamp)| {
+                        chrono::DateTime::parse_from_rfc3339(timestamp)
+                            .map(|t| (now - t.with_timezone(&chrono::Utc)).num_seconds() < SEEN_ITEMS_MAX_AGE_SECS)
+                            .unwrap_or(false)
+                    })
+                    .collect();
+
+                tracing::info!("Loaded {} seen scraper items from disk", cleaned.len());
+                *state.scraper_state.seen_items.lock().await = cleaned;
+            }
+        }
+        persistence::release(&store);
+    }
+}
+
 #[tauri::command]
 pub async fn scraper_add_config(state: State<'_, AppState>, config: ScraperConfig) -> Result<()> {
-    let mut configs = state.scraper_state.configs.write().await;
-    configs.push(config);
+    state.scraper_state.configs.write().await.push(config);
+    persist_configs(&state);
     Ok(())
 }
 
 #[tauri::command]
-pub async fn scraper_update_config(state: State<'_, AppState>, config: ScraperConfig) -> Result<()> {
-    let mut configs = state.scraper_state.configs.write().await;
-    if let Some(existing) = configs.iter_mut().find(|c| c.id == config.id) {
-        *existing = config;
+pub async fn scraper_update_config(state: State<'_, AppState>, mut config: ScraperConfig) -> Result<()> {
+    {
+        let mut configs = state.scraper_state.configs.write().await;
+        if let Some(existing) = configs.iter_mut().find(|c| c.id == config.id) {
+            // Editing shouldn't reset the schedule or backoff
+            config.next_check_at = existing.next_check_at.take();
+            config.failure_count = existing.failure_count;
+            config.retry_after = existing.retry_after.take();
+            config.last_checked = existing.last_checked.take();
+            config.last_error = existing.last_error.take();
+            *existing = config;
+        }
     }
+    persist_configs(&state);
     Ok(())
 }
 
 #[tauri::command]
 pub async fn scraper_remove_config(state: State<'_, AppState>, id: String) -> Result<()> {
-    let mut configs = state.scraper_state.configs.write().await;
-    configs.retain(|c| c.id != id);
+    state.scraper_state.configs.write().await.retain(|c| c.id != id);
+    persist_configs(&state);
     Ok(())
 }
 
@@ -38,10 +105,13 @@ pub async fn scraper_list_configs(state: State<'_, AppState>) -> Result<Vec<Scra
 
 #[tauri::command]
 pub async fn scraper_toggle(state: State<'_, AppState>, id: String, enabled: bool) -> Result<()> {
-    let mut configs = state.scraper_state.configs.write().await;
-    if let Some(config) = configs.iter_mut().find(|c| c.id == id) {
-        config.enabled = enabled;
+    {
+        let mut configs = state.scraper_state.configs.write().await;
+        if let Some(config) = configs.iter_mut().find(|c| c.id == id) {
+            config.enabled = enabled;
+        }
     }
+    persist_configs(&state);
     Ok(())
 }
 
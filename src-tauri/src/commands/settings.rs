@@ -1,16 +1,25 @@
+use std::collections::HashSet;
+use std::hash::Hash;
 use std::sync::atomic::Ordering;
 
-use tauri::{AppHandle, State};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, State};
 use tauri_plugin_store::StoreExt;
 
-use crate::errors::Result;
-use crate::models::AppConfig;
-use crate::services::{torrent_engine, folder_watcher};
+use crate::commands;
+use crate::errors::{Result, WhenThenError};
+use crate::i18n;
+use crate::shortcut;
+use crate::models::{migrations, AppConfig, BadItem, Interest, ScraperConfig, SettingsImportSummary, Source, TorznabIndexer};
+use crate::services::{torrent_engine, folder_watcher, media_server, port_mapping, power_monitor};
 use crate::state::AppState;
 
 const STORE_FILE: &str = "settings.json";
 const STORE_KEY: &str = "config";
 
+/// Bumped when the export format changes in a way older builds can't read.
+const EXPORT_VERSION: u32 = 1;
+
 #[tauri::command]
 pub async fn settings_get(
     app: AppHandle,
@@ -34,8 +43,50 @@ pub async fn settings_get(
 pub async fn settings_update(
     app: AppHandle,
     state: State<'_, AppState>,
-    config: AppConfig,
+    mut config: AppConfig,
 ) -> Result<AppConfig> {
+    // The login item and the global shortcut live outside the store; if
+    // either can't be changed, keep its old value, save everything else and
+    // say why
+    let old_config = state.config.read().await.clone();
+    let mut outcome = Ok(());
+    if config.start_at_login != old_config.start_at_login {
+        if let Err(e) = commands::startup::set_enabled(&app, config.start_at_login) {
+            config.start_at_login = old_config.start_at_login;
+            outcome = Err(e);
+        }
+    }
+    if config.global_shortcut != old_config.global_shortcut {
+        if let Err(e) = shortcut::apply(&app, config.global_shortcut.as_deref()) {
+            config.global_shortcut = old_config.global_shortcut.clone();
+            outcome = outcome.and(Err(e));
+        }
+    }
+    apply_config(&app, &state, config.clone()).await;
+    outcome.map(|()| config)
+}
+
+/// Switch the app language ("system" follows the OS) and save the choice.
+/// Returns the translations for the frontend.
+#[tauri::command]
+pub async fn i18n_set_locale(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    locale: String,
+) -> Result<serde_json::Value> {
+    // Tags without their own file are fine; they fall back (es-AR -> es -> en)
+    let locale = locale.trim().to_string();
+    if locale.is_empty() {
+        return Err(WhenThenError::InvalidInput("Locale is empty".into()));
+    }
+    let mut config = state.config.read().await.clone();
+    config.locale = locale.clone();
+    apply_config(&app, &state, config).await;
+    Ok(i18n::get_translations_for_locale(Some(locale)))
+}
+
+/// Make `config` current: restart whatever depends on the changed fields, then persist it.
+pub(crate) async fn apply_config(app: &AppHandle, state: &AppState, config: AppConfig) {
     let old_config = state.config.read().await.clone();
     let mut current = state.config.write().await;
     *current = config.clone();
@@ -46,14 +97,30 @@ pub async fn settings_update(
         torrent_engine::apply_speed_limits(session, config.max_download_speed, config.max_upload_speed);
     }
 
+    // A higher download limit frees slots for queued torrents
+    if config.max_active_downloads != old_config.max_active_downloads {
+        torrent_engine::promote_queued(state, app).await;
+    }
+
+    // Forward or release the listen port without restarting the session
+    if old_config.enable_upnp != config.enable_upnp {
+        if config.enable_upnp {
+            port_mapping::start(app).await;
+        } else {
+            port_mapping::stop(app).await;
+        }
+    }
+
     // Restart folder watcher if watch config changed
     if old_config.watch_folders != config.watch_folders
         || old_config.watch_folders_enabled != config.watch_folders_enabled
+        || old_config.watch_folders_depth != config.watch_folders_depth
     {
         folder_watcher::stop_watching(&state.folder_watcher).await;
         if config.watch_folders_enabled && !config.watch_folders.is_empty() {
             if let Some(handle) = folder_watcher::start_watching(
                 config.watch_folders.clone(),
+                config.watch_folders_depth,
                 app.clone(),
             ) {
                 *state.folder_watcher.lock().await = Some(handle);
@@ -61,9 +128,42 @@ pub async fn settings_update(
         }
     }
 
+    // Restart the media server if its address, port, auth requirement or scheme changed
+    if old_config.media_server_bind_address != config.media_server_bind_address
+        || old_config.media_server_port != config.media_server_port
+        || old_config.require_stream_auth != config.require_stream_auth
+        || old_config.media_server_tls_enabled != config.media_server_tls_enabled
+    {
+        let addr = media_server::parse_bind_address(&config.media_server_bind_address);
+        let media_state = state.media_server_state(app).await;
+        state.media_server.restart(media_state, addr, config.media_server_port).await;
+        let _ = app.emit("media-server:restarted", state.media_server.base_url());
+    }
+
+    // New OpenSubtitles credentials need a fresh login
+    if old_config.opensubtitles_api_key != config.opensubtitles_api_key
+        || old_config.opensubtitles_username != config.opensubtitles_username
+        || old_config.opensubtitles_password != config.opensubtitles_password
+    {
+        state.opensub_session.lock().await.reset();
+    }
+
+    // Pause or resume for battery now rather than at the next poll
+    if old_config.pause_on_battery != config.pause_on_battery {
+        power_monitor::refresh(app).await;
+    }
+
     // Toggle tray icon visibility
     if old_config.show_tray_icon != config.show_tray_icon {
-        crate::tray::set_visible(&app, config.show_tray_icon);
+        crate::tray::set_visible(app, config.show_tray_icon);
+    }
+
+    // Relabel the native menus in the new language
+    if old_config.locale != config.locale {
+        i18n::set_locale(&config.locale);
+        crate::tray::refresh_menu();
+        #[cfg(target_os = "macos")]
+        crate::app_menu::refresh(app);
     }
 
     // Persist to store
@@ -73,8 +173,246 @@ pub async fn settings_update(
             let _ = store.save();
         }
     }
+}
+
+/// Everything needed to set the app up on another machine.
+#[derive(Serialize, Deserialize)]
+struct SettingsBundle {
+    version: u32,
+    exported_at: String,
+    config: AppConfig,
+    #[serde(default)]
+    sources: Vec<Source>,
+    #[serde(default)]
+    interests: Vec<Interest>,
+    #[serde(default)]
+    scrapers: Vec<ScraperConfig>,
+    #[serde(default)]
+    indexers: Vec<TorznabIndexer>,
+    #[serde(default)]
+    bad_items: Vec<BadItem>,
+}
+
+/// Blank out passwords, API keys, cookies and auth headers.
+fn strip_secrets(bundle: &mut SettingsBundle) {
+    bundle.config.opensubtitles_api_key.clear();
+    bundle.config.opensubtitles_username.clear();
+    bundle.config.opensubtitles_password.clear();
+    for source in &mut bundle.sources {
+        source.auth.password = None;
+        source.auth.cookie_string = None;
+        source.auth.header_value = None;
+    }
+    for scraper in &mut bundle.scrapers {
+        scraper.cookie_string = None;
+        scraper
+            .extra_headers
+            .retain(|name, _| !name.eq_ignore_ascii_case("cookie") && !name.eq_ignore_ascii_case("authorization"));
+    }
+    for indexer in &mut bundle.indexers {
+        indexer.api_key.clear();
+    }
+}
+
+/// A secret that was left out of an export: missing, empty or a redaction marker.
+fn is_blank_secret(value: Option<&str>) -> bool {
+    value.is_none_or(|v| v.trim().is_empty() || v == "<redacted>")
+}
+
+fn keep_secret(imported: &mut Option<String>, existing: &Option<String>) {
+    if is_blank_secret(imported.as_deref()) {
+        imported.clone_from(existing);
+    }
+}
+
+/// Fill the secrets an export left out from the matching items already here,
+/// matched by id or URL, so importing without secrets doesn't wipe them.
+fn keep_existing_secrets(
+    bundle: &mut SettingsBundle,
+    sources: &[Source],
+    scrapers: &[ScraperConfig],
+    indexers: &[TorznabIndexer],
+) {
+    for source in &mut bundle.sources {
+        if let Some(existing) = sources.iter().find(|s| s.id == source.id || s.url.trim() == source.url.trim()) {
+            keep_secret(&mut source.auth.password, &existing.auth.password);
+            keep_secret(&mut source.auth.cookie_string, &existing.auth.cookie_string);
+            keep_secret(&mut source.auth.header_value, &existing.auth.header_value);
+        }
+    }
+    for scraper in &mut bundle.scrapers {
+        if let Some(existing) = scrapers
+            .iter()
+            .find(|c| c.id == scraper.id || c.base_url.trim() == scraper.base_url.trim())
+        {
+            keep_secret(&mut scraper.cookie_string, &existing.cookie_string);
+            for (name, value) in &existing.extra_headers {
+                let secret = name.eq_ignore_ascii_case("cookie") || name.eq_ignore_ascii_case("authorization");
+                let imported = scraper.extra_headers.get(name).map(String::as_str);
+                if secret && is_blank_secret(imported) {
+                    scraper.extra_headers.insert(name.clone(), value.clone());
+                }
+            }
+        }
+    }
+    for indexer in &mut bundle.indexers {
+        if let Some(existing) = indexers
+            .iter()
+            .find(|i| i.id == indexer.id || i.base_url.trim() == indexer.base_url.trim())
+        {
+            if is_blank_secret(Some(&indexer.api_key)) {
+                indexer.api_key.clone_from(&existing.api_key);
+            }
+        }
+    }
+}
+
+/// Append the imported items whose key isn't present yet. Returns how many were added.
+fn merge_by<T, K: Eq + Hash>(existing: &mut Vec<T>, imported: Vec<T>, key: impl Fn(&T) -> K) -> usize {
+    let mut keys: HashSet<K> = existing.iter().map(&key).collect();
+    let before = existing.len();
+    existing.extend(imported.into_iter().filter(|item| keys.insert(key(item))));
+    existing.len() - before
+}
+
+/// Write config, sources, interests, scrapers, indexers and bad items to one
+/// JSON file. Credentials are left out unless `include_secrets` is set.
+#[tauri::command]
+pub async fn settings_export(state: State<'_, AppState>, path: String, include_secrets: Option<bool>) -> Result<()> {
+    let mut bundle = SettingsBundle {
+        version: EXPORT_VERSION,
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        config: state.config.read().await.clone(),
+        sources: state.rss_state.sources.read().await.clone(),
+        interests: state.rss_state.interests.read().await.clone(),
+        scrapers: state.scraper_state.configs.read().await.clone(),
+        indexers: state.torznab_state.indexers.read().await.clone(),
+        bad_items: state.rss_state.bad_items.read().await.values().cloned().collect(),
+    };
+    if !include_secrets.unwrap_or(false) {
+        strip_secrets(&mut bundle);
+    }
+
+    let json = serde_json::to_string_pretty(&bundle)
+        .map_err(|e| WhenThenError::Internal(format!("Failed to serialize settings: {e}")))?;
+    let path = torrent_engine::expand_path(&path);
+    std::fs::write(&path, json)
+        .map_err(|e| WhenThenError::Internal(format!("Failed to write {}: {e}", path.display())))?;
+    tracing::info!("Exported settings to {}", path.display());
+    Ok(())
+}
+
+/// Restore a file from settings_export. `merge` keeps what's here and adds
+/// sources by URL, interests by name, scrapers and indexers by URL, and bad
+/// items by info hash; otherwise everything is replaced.
+#[tauri::command]
+pub async fn settings_import(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    path: String,
+    merge: bool,
+) -> Result<SettingsImportSummary> {
+    let path = torrent_engine::expand_path(&path);
+    let raw = std::fs::read_to_string(&path)
+        .map_err(|e| WhenThenError::FileNotFound(format!("{}: {e}", path.display())))?;
+    let mut value: serde_json::Value = serde_json::from_str(&raw)
+        .map_err(|e| WhenThenError::InvalidInput(format!("Not a settings export: {e}")))?;
+    match value.get("version").and_then(|v| v.as_u64()) {
+        Some(version) if version >= 1 && version <= EXPORT_VERSION as u64 => {}
+        Some(version) => {
+            return Err(WhenThenError::InvalidInput(format!(
+                "Settings export version {version} isn't supported by this version of the app"
+            )))
+        }
+        None => return Err(WhenThenError::InvalidInput("Not a settings export: no version".into())),
+    }
+    // Exports carry the config in whatever shape the exporting build used
+    if let Some(config) = value.get_mut("config") {
+        let (migrated, _) = migrations::migrate(config.take()).map_err(WhenThenError::InvalidInput)?;
+        *config = migrated;
+    }
+    let mut bundle: SettingsBundle = serde_json::from_value(value)
+        .map_err(|e| WhenThenError::InvalidInput(format!("Invalid settings export: {e}")))?;
+
+    // Exports without secrets shouldn't wipe the credentials already set here
+    let current = state.config.read().await.clone();
+    for (imported, existing) in [
+        (&mut bundle.config.opensubtitles_api_key, &current.opensubtitles_api_key),
+        (&mut bundle.config.opensubtitles_username, &current.opensubtitles_username),
+        (&mut bundle.config.opensubtitles_password, &current.opensubtitles_password),
+    ] {
+        if is_blank_secret(Some(imported.as_str())) {
+            imported.clone_from(existing);
+        }
+    }
+
+    let summary = {
+        let rss = &state.rss_state;
+        let mut sources = rss.sources.write().await;
+        let mut interests = rss.interests.write().await;
+        let mut scrapers = state.scraper_state.configs.write().await;
+        let mut indexers = state.torznab_state.indexers.write().await;
+        let mut bad_items = rss.bad_items.write().await;
+
+        keep_existing_secrets(&mut bundle, &sources, &scrapers, &indexers);
+        // Indexers go through the same checks as adding one by hand
+        bundle.indexers.retain(|indexer| match commands::torznab::validate_indexer(indexer) {
+            Ok(()) => true,
+            Err(e) => {
+                tracing::warn!("Skipping imported indexer {}: {}", indexer.name, e);
+                false
+            }
+        });
+
+        if merge {
+            SettingsImportSummary {
+                sources: merge_by(&mut sources, bundle.sources, |s| s.url.trim().to_string()),
+                interests: merge_by(&mut interests, bundle.interests, |i| i.name.trim().to_lowercase()),
+                scrapers: merge_by(&mut scrapers, bundle.scrapers, |c| c.base_url.trim().to_string()),
+                indexers: merge_by(&mut indexers, bundle.indexers, |i| i.base_url.trim().to_string()),
+                bad_items: {
+                    let before = bad_items.len();
+                    for item in bundle.bad_items {
+                        bad_items.entry(item.info_hash.clone()).or_insert(item);
+                    }
+                    bad_items.len() - before
+                },
+            }
+        } else {
+            let summary = SettingsImportSummary {
+                sources: bundle.sources.len(),
+                interests: bundle.interests.len(),
+                scrapers: bundle.scrapers.len(),
+                indexers: bundle.indexers.len(),
+                bad_items: bundle.bad_items.len(),
+            };
+            *sources = bundle.sources;
+            *interests = bundle.interests;
+            *scrapers = bundle.scrapers;
+            *indexers = bundle.indexers;
+            *bad_items = bundle
+                .bad_items
+                .into_iter()
+                .map(|item| (item.info_hash.clone(), item))
+                .collect();
+            summary
+        }
+    };
+
+    commands::rss::persist_sources_internal(&state);
+    commands::rss::persist_interests(&state);
+    commands::rss::persist_bad_items(&state);
+    commands::scraper::persist_configs(&state);
+    commands::torznab::persist_indexers(&app, &state).await;
+
+    // Merging keeps local settings; replacing applies the imported ones
+    if !merge {
+        apply_config(&app, &state, bundle.config).await;
+    }
 
-    Ok(config)
+    tracing::info!("Imported settings from {} ({:?})", path.display(), summary);
+    let _ = app.emit("settings:imported", &summary);
+    Ok(summary)
 }
 
 #[tauri::command]
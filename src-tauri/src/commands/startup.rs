@@ -0,0 +1,208 @@
+// Start at login: a LaunchAgent on macOS, the HKCU Run key on Windows and an
+// XDG autostart entry on Linux. The app always starts in the tray; each login
+// item passes --hidden so one that fires while the app is already running
+// doesn't bring up the main window either.
+
+use std::path::PathBuf;
+
+use tauri::{AppHandle, Manager, State};
+
+use crate::commands::settings::apply_config;
+use crate::errors::{Result, WhenThenError};
+use crate::state::AppState;
+
+/// Argument passed by the login item. `--minimized` is accepted too.
+pub const HIDDEN_ARG: &str = "--hidden";
+
+/// Whether the app was launched to sit in the tray, e.g. by the login item.
+pub fn launched_hidden<I, S>(args: I) -> bool
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    args.into_iter()
+        .any(|arg| matches!(arg.as_ref(), HIDDEN_ARG | "--minimized"))
+}
+
+/// Whether the login item is installed, as the OS sees it.
+#[tauri::command]
+pub async fn get_start_at_login(app: AppHandle) -> Result<bool> {
+    is_enabled(&app)
+}
+
+/// Install or remove the login item and save the choice.
+#[tauri::command]
+pub async fn set_start_at_login(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    enabled: bool,
+) -> Result<()> {
+    set_enabled(&app, enabled)?;
+    let mut config = state.config.read().await.clone();
+    config.start_at_login = enabled;
+    apply_config(&app, &state, config).await;
+    Ok(())
+}
+
+fn executable() -> Result<PathBuf> {
+    std::env::current_exe()
+        .map_err(|e| WhenThenError::Config(format!("Can't find the app executable: {e}")))
+}
+
+#[cfg(not(target_os = "windows"))]
+fn write_file(path: &std::path::Path, contents: &str) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| WhenThenError::Config(format!("Can't create {}: {e}", dir.display())))?;
+    }
+    std::fs::write(path, contents)
+        .map_err(|e| WhenThenError::Config(format!("Can't write {}: {e}", path.display())))
+}
+
+#[cfg(not(target_os = "windows"))]
+fn remove_file(path: &std::path::Path) -> Result<()> {
+    match std::fs::remove_file(path) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(WhenThenError::Config(format!("Can't remove {}: {e}", path.display()))),
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn launch_agent_path(app: &AppHandle) -> Result<PathBuf> {
+    let home = dirs::home_dir().ok_or_else(|| WhenThenError::Config("No home directory".into()))?;
+    Ok(home
+        .join("Library/LaunchAgents")
+        .join(format!("{}.plist", app.config().identifier)))
+}
+
+#[cfg(target_os = "macos")]
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+/// Install or remove the login item.
+#[cfg(target_os = "macos")]
+pub(crate) fn set_enabled(app: &AppHandle, enabled: bool) -> Result<()> {
+    let path = launch_agent_path(app)?;
+    if !enabled {
+        return remove_file(&path);
+    }
+    // The binary inside the bundle, so the agent follows the app wherever it's installed from
+    let exe = executable()?;
+    let plist = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>Label</key>
+    <string>{label}</string>
+    <key>ProgramArguments</key>
+    <array>
+        <string>{exe}</string>
+        <string>{HIDDEN_ARG}</string>
+    </array>
+    <key>RunAtLoad</key>
+    <true/>
+</dict>
+</plist>
+"#,
+        label = xml_escape(&app.config().identifier),
+        exe = xml_escape(&exe.to_string_lossy()),
+    );
+    write_file(&path, &plist)
+}
+
+#[cfg(target_os = "macos")]
+fn is_enabled(app: &AppHandle) -> Result<bool> {
+    Ok(launch_agent_path(app)?.exists())
+}
+
+#[cfg(target_os = "windows")]
+const RUN_KEY: &str = r"HKCU\Software\Microsoft\Windows\CurrentVersion\Run";
+
+#[cfg(target_os = "windows")]
+fn run_value(app: &AppHandle) -> String {
+    app.config()
+        .product_name
+        .clone()
+        .unwrap_or_else(|| app.config().identifier.clone())
+}
+
+/// Install or remove the login item.
+#[cfg(target_os = "windows")]
+pub(crate) fn set_enabled(app: &AppHandle, enabled: bool) -> Result<()> {
+    use std::process::Command;
+
+    let name = run_value(app);
+    let output = if enabled {
+        let command = format!("\"{}\" {HIDDEN_ARG}", executable()?.display());
+        Command::new("reg")
+            .args(["add", RUN_KEY, "/v", &name, "/t", "REG_SZ", "/d", &command, "/f"])
+            .output()
+    } else {
+        if !is_enabled(app)? {
+            return Ok(());
+        }
+        Command::new("reg").args(["delete", RUN_KEY, "/v", &name, "/f"]).output()
+    }
+    .map_err(|e| WhenThenError::Config(format!("Can't run reg.exe: {e}")))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(WhenThenError::Config(format!(
+            "Can't update {RUN_KEY}: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )))
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn is_enabled(app: &AppHandle) -> Result<bool> {
+    let status = std::process::Command::new("reg")
+        .args(["query", RUN_KEY, "/v", &run_value(app)])
+        .output()
+        .map_err(|e| WhenThenError::Config(format!("Can't run reg.exe: {e}")))?
+        .status;
+    Ok(status.success())
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn autostart_path(app: &AppHandle) -> Result<PathBuf> {
+    let config = dirs::config_dir().ok_or_else(|| WhenThenError::Config("No config directory".into()))?;
+    Ok(config
+        .join("autostart")
+        .join(format!("{}.desktop", app.config().identifier)))
+}
+
+/// Install or remove the login item.
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+pub(crate) fn set_enabled(app: &AppHandle, enabled: bool) -> Result<()> {
+    let path = autostart_path(app)?;
+    if !enabled {
+        return remove_file(&path);
+    }
+    let name = app.config().product_name.clone().unwrap_or_else(|| "When".into());
+    let entry = format!(
+        "[Desktop Entry]\nType=Application\nName={name}\nExec=\"{}\" {HIDDEN_ARG}\nX-GNOME-Autostart-enabled=true\n",
+        executable()?.display(),
+    );
+    write_file(&path, &entry)
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn is_enabled(app: &AppHandle) -> Result<bool> {
+    Ok(autostart_path(app)?.exists())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hidden_and_minimized_flags_start_in_the_tray() {
+        assert!(launched_hidden(["/Applications/When.app/Contents/MacOS/when", "--hidden"]));
+        assert!(launched_hidden(["when", "--minimized"]));
+        assert!(!launched_hidden(["when", "magnet:?xt=urn:btih:abc"]));
+    }
+}
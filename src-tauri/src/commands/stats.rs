@@ -0,0 +1,12 @@
+use tauri::State;
+
+use crate::errors::Result;
+use crate::models::{StatsRange, UsageStats};
+use crate::services::stats;
+use crate::state::AppState;
+
+/// Daily download/upload totals for the range, plus this month's sum.
+#[tauri::command]
+pub async fn stats_get(state: State<'_, AppState>, range: StatsRange) -> Result<UsageStats> {
+    Ok(stats::usage(&state, range).await)
+}
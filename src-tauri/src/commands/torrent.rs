@@ -1,8 +1,12 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::Command;
+
 use tauri::{AppHandle, State};
 
-use crate::errors::Result;
-use crate::models::{TorrentAddOptions, TorrentAddedResponse, TorrentDetails, TorrentFileInfo, TorrentSummary};
-use crate::services::torrent_engine;
+use crate::errors::{Result, WhenThenError};
+use crate::models::{CompletedPage, FileOrderStatus, ImportReport, MagnetPreview, TorrentAddOptions, TorrentAddedResponse, TorrentCreated, TorrentDetails, TorrentFileInfo, TorrentPeers, TorrentSummary, TorrentTracker, TorrentVerifyReport};
+use crate::services::{magnet, rss, subtitle_search, torrent_creator, torrent_engine, torrent_import, torrent_labels, torrent_verify};
 use crate::state::AppState;
 
 #[tauri::command]
@@ -23,6 +27,52 @@ pub async fn torrent_add_magnet(
     torrent_engine::add_magnet(&state, &app_handle, magnet_url, options).await
 }
 
+/// Read a magnet link without adding it, so the UI can show what it is
+/// before metadata arrives.
+#[tauri::command]
+pub async fn magnet_parse(state: State<'_, AppState>, uri: String) -> Result<MagnetPreview> {
+    let parsed = magnet::parse(&uri).map_err(WhenThenError::InvalidInput)?;
+    let session = state.torrent_session.read().await.clone();
+    let existing_id = match session {
+        Some(session) => torrent_engine::managed_torrent_id(&state, &session, &parsed.info_hash).await,
+        None => None,
+    };
+    let marked_bad = rss::bad_item_for_hash(&state.rss_state, &parsed.info_hash).await.is_some();
+
+    Ok(MagnetPreview {
+        info_hash: parsed.info_hash,
+        name: parsed.name,
+        trackers: parsed.trackers,
+        web_seeds: parsed.web_seeds,
+        existing_id,
+        marked_bad,
+    })
+}
+
+/// Add torrents from a drop: .torrent file paths and text with magnet links.
+/// Results arrive as a tray:drop-result event.
+#[tauri::command]
+pub async fn torrent_add_dropped(app_handle: AppHandle, paths: Vec<String>, text: Option<String>) -> Result<()> {
+    let paths = paths.into_iter().map(std::path::PathBuf::from).collect();
+    crate::tray::drop::handle_drop(&app_handle, paths, text);
+    Ok(())
+}
+
+/// Import a folder of another client's .torrent files, checking the data where
+/// it already lives. `destination_map` maps file names to data folders for
+/// torrents whose resume data doesn't say. Progress arrives as
+/// torrent:import-progress events.
+#[tauri::command]
+pub async fn torrent_import_directory(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    path: String,
+    destination_map: Option<HashMap<String, String>>,
+) -> Result<Vec<ImportReport>> {
+    let dir = torrent_engine::expand_path(&path);
+    torrent_import::import_directory(&state, &app_handle, &dir, &destination_map.unwrap_or_default()).await
+}
+
 #[tauri::command]
 pub async fn torrent_add_file(
     app_handle: AppHandle,
@@ -44,8 +94,65 @@ pub async fn torrent_add_bytes(
 }
 
 #[tauri::command]
-pub async fn torrent_list(state: State<'_, AppState>) -> Result<Vec<TorrentSummary>> {
-    torrent_engine::list_torrents(&state).await
+pub async fn torrent_add_url(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    url: String,
+    options: Option<TorrentAddOptions>,
+) -> Result<TorrentAddedResponse> {
+    let bytes = rss::fetch_torrent_url(&state.rss_state, &url).await?;
+    torrent_engine::add_torrent_bytes(&state, &app_handle, bytes, options).await
+}
+
+/// All torrents, or only those labelled `label` when one is given.
+#[tauri::command]
+pub async fn torrent_list(state: State<'_, AppState>, label: Option<String>) -> Result<Vec<TorrentSummary>> {
+    let mut torrents = torrent_engine::list_torrents(&state).await?;
+    if let Some(label) = torrent_labels::normalize(label.as_deref()) {
+        torrents.retain(|t| t.label.as_deref() == Some(label.as_str()));
+    }
+    Ok(torrents)
+}
+
+/// Show a torrent under another name; an empty name restores the original.
+/// Returns the name now shown.
+#[tauri::command]
+pub async fn torrent_set_display_name(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    id: usize,
+    name: String,
+) -> Result<String> {
+    torrent_engine::set_display_name(&state, &app_handle, id, &name).await
+}
+
+/// Label a torrent; an empty or missing label clears it.
+#[tauri::command]
+pub async fn torrent_set_label(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    id: usize,
+    label: Option<String>,
+) -> Result<()> {
+    let id = torrent_engine::resolve_id(&state, id).await;
+    let session = state
+        .torrent_session
+        .read()
+        .await
+        .clone()
+        .ok_or(WhenThenError::SessionNotReady)?;
+    let handle = session
+        .get(librqbit::api::TorrentIdOrHash::Id(id))
+        .ok_or(WhenThenError::TorrentNotFound(id))?;
+    let label = torrent_labels::normalize(label.as_deref());
+    torrent_labels::set_label(&app_handle, &state, &handle.info_hash().as_string(), label).await;
+    Ok(())
+}
+
+/// Labels in use, sorted.
+#[tauri::command]
+pub async fn torrent_list_labels(state: State<'_, AppState>) -> Result<Vec<String>> {
+    Ok(state.torrent_labels.read().await.labels())
 }
 
 #[tauri::command]
@@ -65,13 +172,21 @@ pub async fn torrent_files(
 }
 
 #[tauri::command]
-pub async fn torrent_pause(state: State<'_, AppState>, id: usize) -> Result<()> {
-    torrent_engine::pause_torrent(&state, id).await
+pub async fn torrent_pause(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    id: usize,
+) -> Result<()> {
+    torrent_engine::pause_torrent(&state, &app_handle, id).await
 }
 
 #[tauri::command]
-pub async fn torrent_resume(state: State<'_, AppState>, id: usize) -> Result<()> {
-    torrent_engine::resume_torrent(&state, id).await
+pub async fn torrent_resume(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    id: usize,
+) -> Result<()> {
+    torrent_engine::resume_torrent(&state, &app_handle, id).await
 }
 
 #[tauri::command]
@@ -83,13 +198,34 @@ pub async fn torrent_recheck(
     torrent_engine::recheck_torrent(&state, &app_handle, id).await
 }
 
+/// Announce to trackers and the DHT now. Returns the number of trackers; 0
+/// (and nothing done) for paused, queued or finished torrents.
+#[tauri::command]
+pub async fn torrent_reannounce(
+    state: State<'_, AppState>,
+    id: usize,
+) -> Result<usize> {
+    torrent_engine::reannounce_torrent(&state, id).await
+}
+
+/// Drop every peer connection so new ones are attempted. Returns the number
+/// of peers dropped; 0 (and nothing done) for torrents that aren't running.
+#[tauri::command]
+pub async fn torrent_force_reconnect(
+    state: State<'_, AppState>,
+    id: usize,
+) -> Result<usize> {
+    torrent_engine::force_reconnect_torrent(&state, id).await
+}
+
 #[tauri::command]
 pub async fn torrent_delete(
+    app_handle: AppHandle,
     state: State<'_, AppState>,
     id: usize,
     delete_files: bool,
 ) -> Result<()> {
-    torrent_engine::delete_torrent(&state, id, delete_files).await
+    torrent_engine::delete_torrent(&state, &app_handle, id, delete_files).await
 }
 
 #[tauri::command]
@@ -102,6 +238,33 @@ pub async fn torrent_update_files(
     torrent_engine::update_torrent_files(&state, &app_handle, id, only_files).await
 }
 
+/// Attach an HTTP web seed (BEP 19) to a torrent. Always fails for now: the
+/// torrent engine doesn't support web seeds.
+#[tauri::command]
+pub async fn torrent_add_webseed(id: usize, url: String) -> Result<TorrentAddedResponse> {
+    torrent_engine::add_web_seed(id, &url)
+}
+
+/// Download the given files one at a time, in order.
+#[tauri::command]
+pub async fn torrent_set_file_order(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    id: usize,
+    ordered_indices: Vec<usize>,
+) -> Result<FileOrderStatus> {
+    torrent_engine::set_file_order(&state, &app_handle, id, ordered_indices).await
+}
+
+#[tauri::command]
+pub async fn torrent_cancel_file_order(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    id: usize,
+) -> Result<()> {
+    torrent_engine::cancel_file_order(&state, &app_handle, id).await
+}
+
 #[tauri::command]
 pub async fn torrent_rename_files(
     state: State<'_, AppState>,
@@ -110,3 +273,170 @@ pub async fn torrent_rename_files(
 ) -> Result<()> {
     torrent_engine::rename_torrent_files(&state, torrent_id, renames).await
 }
+
+#[tauri::command]
+pub async fn torrent_trackers(
+    state: State<'_, AppState>,
+    id: usize,
+) -> Result<Vec<TorrentTracker>> {
+    torrent_engine::get_torrent_trackers(&state, id).await
+}
+
+/// Connected peers with per-peer rates; empty for paused torrents.
+#[tauri::command]
+pub async fn torrent_peers(
+    state: State<'_, AppState>,
+    id: usize,
+) -> Result<TorrentPeers> {
+    torrent_engine::get_torrent_peers(&state, id).await
+}
+
+#[tauri::command]
+pub async fn torrent_add_tracker(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    id: usize,
+    url: String,
+) -> Result<TorrentAddedResponse> {
+    torrent_engine::add_torrent_tracker(&state, &app_handle, id, url).await
+}
+
+/// Hash-check a torrent's files on disk without rechecking it in the session.
+#[tauri::command]
+pub async fn torrent_verify(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    id: usize,
+) -> Result<TorrentVerifyReport> {
+    torrent_verify::verify(&state, &app_handle, id).await
+}
+
+/// Stop a running verify; its report comes back with `cancelled` set.
+#[tauri::command]
+pub async fn torrent_verify_cancel(
+    state: State<'_, AppState>,
+    id: usize,
+) -> Result<bool> {
+    Ok(torrent_verify::cancel(&state, id).await)
+}
+
+/// Make a .torrent from a local file or folder, write it to `destination`
+/// and start seeding it from where it is.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn torrent_create(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    path: String,
+    destination: String,
+    trackers: Option<Vec<String>>,
+    piece_size: Option<u64>,
+    private: Option<bool>,
+    comment: Option<String>,
+) -> Result<TorrentCreated> {
+    let options = torrent_creator::CreateOptions {
+        trackers: trackers.unwrap_or_default(),
+        piece_size,
+        private: private.unwrap_or(false),
+        comment,
+        created_by: Some(concat!("When/", env!("CARGO_PKG_VERSION")).to_string()),
+        creation_date: Some(chrono::Utc::now().timestamp()),
+    };
+    torrent_creator::create(&state, &app_handle, path, destination, options).await
+}
+
+/// Stop hashing a torrent being made from `path`; torrent_create then fails
+/// as cancelled.
+#[tauri::command]
+pub async fn torrent_create_cancel(
+    state: State<'_, AppState>,
+    path: String,
+) -> Result<bool> {
+    Ok(torrent_creator::cancel(&state, &path).await)
+}
+
+/// Finished downloads, newest first, `limit` (default 50) after skipping `offset`.
+#[tauri::command]
+pub async fn completed_history(
+    state: State<'_, AppState>,
+    offset: Option<usize>,
+    limit: Option<usize>,
+) -> Result<CompletedPage> {
+    let history = state.completed_history.read().await;
+    Ok(history.page(offset.unwrap_or(0), limit.unwrap_or(50)))
+}
+
+/// URL of the completed downloads feed, for subscribing from another machine.
+/// Its token is saved, so the URL stays the same across restarts.
+#[tauri::command]
+pub async fn completed_feed_url(state: State<'_, AppState>) -> Result<String> {
+    let base_url = state.media_server.base_url();
+    let history = state.completed_history.read().await;
+    Ok(format!("{base_url}/feed/completed.xml?token={}", history.feed_token()))
+}
+
+/// Show a downloaded file in Finder or Explorer.
+#[tauri::command]
+pub async fn torrent_reveal_file(
+    state: State<'_, AppState>,
+    id: usize,
+    file_index: usize,
+) -> Result<()> {
+    let file = subtitle_search::locate_video(&state, id, file_index).await?;
+    if !file.path.exists() {
+        return Err(WhenThenError::FileNotFound(file.path.to_string_lossy().to_string()));
+    }
+    open_in_file_manager(&file.path, true)
+}
+
+/// Open the folder a torrent's data is in.
+#[tauri::command]
+pub async fn torrent_open_folder(
+    state: State<'_, AppState>,
+    id: usize,
+) -> Result<()> {
+    let folder = torrent_engine::torrent_folder(&state, id).await?;
+    if !folder.is_dir() {
+        return Err(WhenThenError::FileNotFound(folder.to_string_lossy().to_string()));
+    }
+    open_in_file_manager(&folder, false)
+}
+
+/// Open `path` in the platform file manager. With `select`, open its folder
+/// with it highlighted; Linux file managers have no common way to do that, so
+/// they just get the folder.
+fn open_in_file_manager(path: &Path, select: bool) -> Result<()> {
+    #[cfg(target_os = "macos")]
+    let mut command = {
+        let mut command = Command::new("open");
+        if select {
+            command.arg("-R");
+        }
+        command.arg(path);
+        command
+    };
+    #[cfg(target_os = "windows")]
+    let mut command = {
+        use std::os::windows::process::CommandExt;
+        let mut command = Command::new("explorer");
+        // explorer parses its own command line; the path must be quoted as one token after the comma
+        if select {
+            command.raw_arg(format!("/select,\"{}\"", path.display()));
+        } else {
+            command.arg(path);
+        }
+        command
+    };
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    let mut command = {
+        let mut command = Command::new("xdg-open");
+        let target = if select { path.parent().unwrap_or(path) } else { path };
+        command.arg(target);
+        command
+    };
+
+    command
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| WhenThenError::Internal(format!("Failed to open {}: {e}", path.display())))
+}
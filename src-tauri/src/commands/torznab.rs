@@ -0,0 +1,123 @@
+// Torznab indexer commands (Jackett, Prowlarr).
+
+use tauri::{AppHandle, State};
+use tauri_plugin_store::StoreExt;
+
+use crate::errors::{Result, WhenThenError};
+use crate::models::{TorznabIndexer, TorznabSearchResult};
+use crate::services::torznab;
+use crate::state::AppState;
+
+const INDEXERS_STORE: &str = "torznab_indexers.json";
+
+pub async fn persist_indexers(app: &AppHandle, state: &AppState) {
+    if let Ok(store) = app.store(INDEXERS_STORE) {
+        let indexers = state.torznab_state.indexers.read().await;
+        if let Ok(value) = serde_json::to_value(&*indexers) {
+            store.set("indexers", value);
+            if let Err(e) = store.save() {
+                tracing::error!("Failed to save Torznab indexers: {}", e);
+            }
+        }
+    }
+}
+
+pub async fn load_indexers(app: &AppHandle, state: &AppState) {
+    if let Ok(store) = app.store(INDEXERS_STORE) {
+        if let Err(e) = store.reload() {
+            tracing::warn!("Could not load Torznab indexers store: {}", e);
+        }
+        if let Some(value) = store.get("indexers") {
+            if let Ok(indexers) = serde_json::from_value::<Vec<TorznabIndexer>>(value) {
+                tracing::info!("Loaded {} Torznab indexers from disk", indexers.len());
+                *state.torznab_state.indexers.write().await = indexers;
+            }
+        }
+    }
+}
+
+pub(crate) fn validate_indexer(indexer: &TorznabIndexer) -> Result<()> {
+    let url = indexer.base_url.trim();
+    if !(url.starts_with("http://") || url.starts_with("https://")) {
+        return Err(WhenThenError::InvalidInput(format!("Indexer URL must be http(s): {url}")));
+    }
+    if indexer.api_key.trim().is_empty() {
+        return Err(WhenThenError::InvalidInput("Indexer API key is required".into()));
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn torznab_add_indexer(app: AppHandle, state: State<'_, AppState>, indexer: TorznabIndexer) -> Result<()> {
+    validate_indexer(&indexer)?;
+    state.torznab_state.indexers.write().await.push(indexer);
+    persist_indexers(&app, &state).await;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn torznab_update_indexer(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    mut indexer: TorznabIndexer,
+) -> Result<()> {
+    validate_indexer(&indexer)?;
+    {
+        let mut indexers = state.torznab_state.indexers.write().await;
+        if let Some(existing) = indexers.iter_mut().find(|i| i.id == indexer.id) {
+            // Editing shouldn't reset the schedule or backoff
+            indexer.next_check_at = existing.next_check_at.take();
+            indexer.failure_count = existing.failure_count;
+            indexer.retry_after = existing.retry_after.take();
+            indexer.last_checked = existing.last_checked.take();
+            indexer.last_error = existing.last_error.take();
+            *existing = indexer;
+        }
+    }
+    persist_indexers(&app, &state).await;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn torznab_remove_indexer(app: AppHandle, state: State<'_, AppState>, id: String) -> Result<()> {
+    state.torznab_state.indexers.write().await.retain(|i| i.id != id);
+    persist_indexers(&app, &state).await;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn torznab_list_indexers(state: State<'_, AppState>) -> Result<Vec<TorznabIndexer>> {
+    let indexers = state.torznab_state.indexers.read().await;
+    Ok(indexers.clone())
+}
+
+#[tauri::command]
+pub async fn torznab_toggle_indexer(app: AppHandle, state: State<'_, AppState>, id: String, enabled: bool) -> Result<()> {
+    {
+        let mut indexers = state.torznab_state.indexers.write().await;
+        if let Some(indexer) = indexers.iter_mut().find(|i| i.id == id) {
+            indexer.enabled = enabled;
+        }
+    }
+    persist_indexers(&app, &state).await;
+    Ok(())
+}
+
+/// Search one saved indexer directly, outside of any interest.
+#[tauri::command]
+pub async fn torznab_search(
+    state: State<'_, AppState>,
+    indexer_id: String,
+    query: String,
+) -> Result<Vec<TorznabSearchResult>> {
+    let indexer = state
+        .torznab_state
+        .indexers
+        .read()
+        .await
+        .iter()
+        .find(|i| i.id == indexer_id)
+        .cloned()
+        .ok_or_else(|| WhenThenError::NotFound(format!("Indexer {indexer_id}")))?;
+    torznab::search_results(&indexer, &query).await
+}
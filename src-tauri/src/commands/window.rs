@@ -0,0 +1,17 @@
+use tauri::AppHandle;
+
+use crate::errors::Result;
+use crate::windows;
+
+/// Show a window by label, creating it if needed; `picker` opens on the
+/// screen under the mouse.
+#[tauri::command]
+pub async fn window_show(app_handle: AppHandle, label: String) -> Result<()> {
+    windows::show(&app_handle, &label)
+}
+
+/// Hide a window by label; `picker` hides every screen's picker.
+#[tauri::command]
+pub async fn window_hide(app_handle: AppHandle, label: String) -> Result<()> {
+    windows::hide(&app_handle, &label)
+}
@@ -1,3 +1,4 @@
+use serde::ser::SerializeStruct;
 use serde::Serialize;
 
 #[derive(Debug, thiserror::Error)]
@@ -8,6 +9,9 @@ pub enum WhenThenError {
     #[error("Torrent not found: {0}")]
     TorrentNotFound(usize),
 
+    #[error("Torrent session not initialized")]
+    SessionNotReady,
+
     #[error("Device not found: {0}")]
     DeviceNotFound(String),
 
@@ -17,6 +21,10 @@ pub enum WhenThenError {
     #[error("Cast playback error: {0}")]
     CastPlayback(String),
 
+    /// The device dropped and a reconnect is under way; worth retrying shortly.
+    #[error("Reconnecting to {0}")]
+    CastReconnecting(String),
+
     #[error("Unsupported format: {0}")]
     UnsupportedFormat(String),
 
@@ -46,36 +54,191 @@ pub enum WhenThenError {
 
     #[error("Scraper error: {0}")]
     Scraper(String),
+
+    #[error("Site is blocking automated requests: {0}")]
+    ScraperBlocked(String),
+
+    /// The global shortcut is held by another app.
+    #[error("Shortcut is already in use: {0}")]
+    ShortcutTaken(String),
+
+    /// The torrent was marked bad from the RSS inbox; adding it again needs
+    /// the user to confirm.
+    #[error("\"{0}\" was marked as bad")]
+    MarkedBad(String),
+
+    /// Another error plus the underlying cause chain it was built from.
+    #[error("{error}")]
+    Detailed {
+        error: Box<WhenThenError>,
+        details: String,
+    },
 }
 
 // Type alias for backwards compatibility
 pub type AppError = WhenThenError;
 
+impl WhenThenError {
+    /// Stable identifier the frontend can branch on. Don't rename these.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Torrent(_) => "TORRENT_ERROR",
+            Self::TorrentNotFound(_) => "TORRENT_NOT_FOUND",
+            Self::SessionNotReady => "SESSION_NOT_READY",
+            Self::DeviceNotFound(_) => "DEVICE_NOT_FOUND",
+            Self::CastConnection(_) => "CAST_CONNECTION",
+            Self::CastPlayback(_) => "CAST_PLAYBACK",
+            Self::CastReconnecting(_) => "CAST_RECONNECTING",
+            Self::UnsupportedFormat(_) => "UNSUPPORTED_FORMAT",
+            Self::FileNotFound(_) => "FILE_NOT_FOUND",
+            Self::SubtitleParse(_) => "SUBTITLE_PARSE",
+            Self::OpenSubtitles(_) => "OPENSUBTITLES",
+            Self::Config(_) => "CONFIG",
+            Self::Internal(_) => "INTERNAL",
+            Self::InvalidInput(_) => "INVALID_INPUT",
+            Self::NotFound(_) => "NOT_FOUND",
+            Self::Rss(_) => "RSS",
+            Self::Scraper(_) => "SCRAPER",
+            Self::ScraperBlocked(_) => "SCRAPER_BLOCKED",
+            Self::ShortcutTaken(_) => "SHORTCUT_TAKEN",
+            Self::MarkedBad(_) => "MARKED_BAD",
+            Self::Detailed { error, .. } => error.code(),
+        }
+    }
+
+    /// Attach the underlying cause, unless it adds nothing to the message.
+    pub fn with_details(self, details: impl Into<String>) -> Self {
+        let details = details.into();
+        if details.is_empty() || self.to_string().ends_with(&details) {
+            return self;
+        }
+        Self::Detailed {
+            error: Box::new(self),
+            details,
+        }
+    }
+
+    pub fn details(&self) -> Option<&str> {
+        match self {
+            Self::Detailed { details, .. } => Some(details),
+            _ => None,
+        }
+    }
+
+    /// A librqbit failure: `context: error` as the message, the full anyhow
+    /// chain in details.
+    pub fn torrent(context: &str, err: impl Into<anyhow::Error>) -> Self {
+        let err = err.into();
+        Self::Torrent(format!("{context}: {err}")).with_details(format!("{err:#}"))
+    }
+}
+
+/// An error and its sources, joined like anyhow's `{:#}`.
+pub fn error_chain(err: &dyn std::error::Error) -> String {
+    let mut chain = err.to_string();
+    let mut source = err.source();
+    while let Some(cause) = source {
+        chain.push_str(": ");
+        chain.push_str(&cause.to_string());
+        source = cause.source();
+    }
+    chain
+}
+
+/// Crosses the command boundary as `{ code, message, details? }`.
 impl Serialize for WhenThenError {
     fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
     where
         S: serde::Serializer,
     {
-        serializer.serialize_str(&self.to_string())
+        let details = self.details();
+        let mut error = serializer.serialize_struct("WhenThenError", 2 + usize::from(details.is_some()))?;
+        error.serialize_field("code", self.code())?;
+        error.serialize_field("message", &self.to_string())?;
+        if let Some(details) = details {
+            error.serialize_field("details", details)?;
+        }
+        error.end()
     }
 }
 
 impl From<anyhow::Error> for WhenThenError {
     fn from(err: anyhow::Error) -> Self {
-        WhenThenError::Internal(err.to_string())
+        WhenThenError::Internal(err.to_string()).with_details(format!("{err:#}"))
     }
 }
 
 impl From<reqwest::Error> for WhenThenError {
     fn from(err: reqwest::Error) -> Self {
-        WhenThenError::Rss(err.to_string())
+        WhenThenError::Rss(err.to_string()).with_details(error_chain(&err))
     }
 }
 
 impl From<feed_rs::parser::ParseFeedError> for WhenThenError {
     fn from(err: feed_rs::parser::ParseFeedError) -> Self {
-        WhenThenError::Rss(err.to_string())
+        WhenThenError::Rss(err.to_string()).with_details(error_chain(&err))
     }
 }
 
 pub type Result<T> = std::result::Result<T, WhenThenError>;
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn every_variant_serializes_with_a_stable_code() {
+        let cases = [
+            (WhenThenError::Torrent("x".into()), "TORRENT_ERROR", "Torrent error: x"),
+            (WhenThenError::TorrentNotFound(3), "TORRENT_NOT_FOUND", "Torrent not found: 3"),
+            (WhenThenError::SessionNotReady, "SESSION_NOT_READY", "Torrent session not initialized"),
+            (WhenThenError::DeviceNotFound("x".into()), "DEVICE_NOT_FOUND", "Device not found: x"),
+            (WhenThenError::CastConnection("x".into()), "CAST_CONNECTION", "Cast connection error: x"),
+            (WhenThenError::CastPlayback("x".into()), "CAST_PLAYBACK", "Cast playback error: x"),
+            (WhenThenError::CastReconnecting("x".into()), "CAST_RECONNECTING", "Reconnecting to x"),
+            (WhenThenError::UnsupportedFormat("x".into()), "UNSUPPORTED_FORMAT", "Unsupported format: x"),
+            (WhenThenError::FileNotFound("x".into()), "FILE_NOT_FOUND", "File not found: x"),
+            (WhenThenError::SubtitleParse("x".into()), "SUBTITLE_PARSE", "Subtitle parse error: x"),
+            (WhenThenError::OpenSubtitles("x".into()), "OPENSUBTITLES", "OpenSubtitles error: x"),
+            (WhenThenError::Config("x".into()), "CONFIG", "Config error: x"),
+            (WhenThenError::Internal("x".into()), "INTERNAL", "Internal error: x"),
+            (WhenThenError::InvalidInput("x".into()), "INVALID_INPUT", "Invalid input: x"),
+            (WhenThenError::NotFound("x".into()), "NOT_FOUND", "Not found: x"),
+            (WhenThenError::Rss("x".into()), "RSS", "RSS error: x"),
+            (WhenThenError::Scraper("x".into()), "SCRAPER", "Scraper error: x"),
+            (
+                WhenThenError::ScraperBlocked("x".into()),
+                "SCRAPER_BLOCKED",
+                "Site is blocking automated requests: x",
+            ),
+            (WhenThenError::ShortcutTaken("x".into()), "SHORTCUT_TAKEN", "Shortcut is already in use: x"),
+            (WhenThenError::MarkedBad("x".into()), "MARKED_BAD", "\"x\" was marked as bad"),
+        ];
+        for (error, code, message) in cases {
+            assert_eq!(
+                serde_json::to_value(&error).unwrap(),
+                json!({ "code": code, "message": message })
+            );
+        }
+    }
+
+    #[test]
+    fn chained_errors_keep_the_cause_in_details() {
+        let err = anyhow::anyhow!("connection refused").context("tracker announce failed");
+        let error = WhenThenError::torrent("Failed to add torrent", err);
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            json!({
+                "code": "TORRENT_ERROR",
+                "message": "Torrent error: Failed to add torrent: tracker announce failed",
+                "details": "tracker announce failed: connection refused",
+            })
+        );
+
+        // Nothing to add when the cause has no sources of its own
+        let error = WhenThenError::torrent("Failed to pause", anyhow::anyhow!("not running"));
+        assert_eq!(error.details(), None);
+    }
+}
@@ -3,45 +3,130 @@
 use serde_json::Value;
 use std::collections::HashMap;
 use std::path::PathBuf;
-use std::sync::OnceLock;
+use std::sync::{LazyLock, OnceLock, RwLock};
 use tauri::Manager;
 
-static TRANSLATIONS: OnceLock<HashMap<String, Value>> = OnceLock::new();
+/// Always bundled, and the last step of every fallback chain.
+const BASE_LOCALE: &str = "en";
+
+static TRANSLATIONS: LazyLock<RwLock<HashMap<String, Value>>> = LazyLock::new(|| RwLock::new(HashMap::new()));
 static LOCALES_DIR: OnceLock<PathBuf> = OnceLock::new();
 
-/// Initialize translations from bundled locale files.
-pub fn init(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
+/// Initialize translations from bundled locale files. `locale` is the saved
+/// preference; "system" or empty uses the detected one.
+pub fn init(app: &tauri::App, locale: &str) -> Result<(), Box<dyn std::error::Error>> {
     // Store the locales directory for later use
     let locales_dir = app
         .path()
         .resolve("resources/locales", tauri::path::BaseDirectory::Resource)?;
     let _ = LOCALES_DIR.set(locales_dir);
 
-    let locale = detect_system_locale();
-    let translations = load_locale_file(&locale);
-
-    let _ = TRANSLATIONS.set(translations);
-    tracing::info!("Loaded translations for locale: {}", locale);
+    set_locale(locale);
     Ok(())
 }
 
-/// Load translations from a locale file.
+/// Reload the Rust-side translations used by t(). Returns the locale that was
+/// actually loaded.
+pub fn set_locale(locale: &str) -> String {
+    let resolved = resolve_preference(locale);
+    let translations = load_locale_file(&resolved);
+    *TRANSLATIONS.write().unwrap_or_else(|e| e.into_inner()) = translations;
+    tracing::info!("Loaded translations for locale: {}", resolved);
+    resolved
+}
+
+/// "system", empty, or a locale tag -> the best bundled locale.
+fn resolve_preference(locale: &str) -> String {
+    match locale.trim() {
+        "" | "system" => detect_system_locale(),
+        tag => resolve(tag),
+    }
+}
+
+/// Codes of the bundled locale files, sorted.
+pub fn available_locales() -> Vec<String> {
+    let Some(dir) = LOCALES_DIR.get() else {
+        return Vec::new();
+    };
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut locales: Vec<String> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|path| path.file_stem().and_then(|s| s.to_str()).map(str::to_string))
+        .collect();
+    locales.sort();
+    locales
+}
+
+/// Candidates from most to least specific: "es_AR.UTF-8" -> es-ar, es, en.
+fn fallback_chain(tag: &str) -> Vec<String> {
+    // Drop encoding and modifier suffixes ("UTF-8", "@euro", "@rg=...")
+    let tag = tag.split(['.', '@']).next().unwrap_or_default();
+    let tag = tag.trim().replace('_', "-").to_lowercase();
+
+    let mut chain = Vec::new();
+    let mut parts: Vec<&str> = tag.split('-').filter(|part| !part.is_empty()).collect();
+    while !parts.is_empty() {
+        chain.push(parts.join("-"));
+        parts.pop();
+    }
+    if !chain.iter().any(|locale| locale == BASE_LOCALE) {
+        chain.push(BASE_LOCALE.to_string());
+    }
+    chain
+}
+
+/// Bundled locale file name matching `candidate`, ignoring case and `_`/`-`.
+fn find_available<'a>(candidate: &str, available: &'a [String]) -> Option<&'a String> {
+    available
+        .iter()
+        .find(|locale| locale.replace('_', "-").eq_ignore_ascii_case(candidate))
+}
+
+/// First step of the fallback chain that has a bundled file.
+fn resolve_in(tag: &str, available: &[String]) -> String {
+    fallback_chain(tag)
+        .iter()
+        .find_map(|candidate| find_available(candidate, available))
+        .cloned()
+        .unwrap_or_else(|| BASE_LOCALE.to_string())
+}
+
+fn resolve(tag: &str) -> String {
+    resolve_in(tag, &available_locales())
+}
+
+/// Load translations for `locale`, layered over each less specific locale in
+/// its chain so a partial file (es-AR) falls back key by key to es, then en.
 fn load_locale_file(locale: &str) -> HashMap<String, Value> {
     let Some(locales_dir) = LOCALES_DIR.get() else {
         return HashMap::new();
     };
+    let available = available_locales();
 
-    let locale_path = locales_dir.join(format!("{}.json", locale));
-    let content = std::fs::read_to_string(&locale_path).unwrap_or_else(|_| {
-        // Fallback to English
-        let fallback_path = locales_dir.join("en.json");
-        std::fs::read_to_string(fallback_path).unwrap_or_else(|_| "{}".to_string())
-    });
+    let mut merged = Value::Object(serde_json::Map::new());
+    for candidate in fallback_chain(locale).iter().rev() {
+        let Some(file) = find_available(candidate, &available) else {
+            continue;
+        };
+        let locale_path = locales_dir.join(format!("{}.json", file));
+        let content = match std::fs::read_to_string(&locale_path) {
+            Ok(content) => content,
+            Err(e) => {
+                tracing::warn!("Failed to read {}: {}", locale_path.display(), e);
+                continue;
+            }
+        };
+        match serde_json::from_str::<Value>(&content) {
+            Ok(json) => overlay(&mut merged, json),
+            Err(e) => tracing::warn!("Failed to parse {}: {}", locale_path.display(), e),
+        }
+    }
 
-    let json: Value = serde_json::from_str(&content).unwrap_or(Value::Object(serde_json::Map::new()));
     let mut translations = HashMap::new();
-
-    if let Value::Object(map) = json {
+    if let Value::Object(map) = merged {
         for (key, value) in map {
             translations.insert(key, value);
         }
@@ -50,18 +135,34 @@ fn load_locale_file(locale: &str) -> HashMap<String, Value> {
     translations
 }
 
-/// Detect system locale, returning "en" or "es" (fallback to "en").
-pub fn detect_system_locale() -> String {
+/// Merge `top` into `base`, replacing leaves and recursing into sections.
+fn overlay(base: &mut Value, top: Value) {
+    match (base, top) {
+        (Value::Object(base), Value::Object(top)) => {
+            for (key, value) in top {
+                match base.get_mut(&key) {
+                    Some(existing) => overlay(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, top) => *base = top,
+    }
+}
+
+/// The OS language setting as a tag like "es_AR", if there is one.
+fn system_locale_tag() -> Option<String> {
     #[cfg(target_os = "macos")]
     {
         if let Ok(output) = std::process::Command::new("defaults")
             .args(["read", "-g", "AppleLocale"])
             .output()
         {
-            let locale_str = String::from_utf8_lossy(&output.stdout);
-            let locale = locale_str.trim().to_lowercase();
-            if locale.starts_with("es") {
-                return "es".to_string();
+            let locale = String::from_utf8_lossy(&output.stdout).trim().to_string();
+            if !locale.is_empty() {
+                return Some(locale);
             }
         }
     }
@@ -70,8 +171,8 @@ pub fn detect_system_locale() -> String {
     {
         // Check LANG environment variable first
         if let Ok(lang) = std::env::var("LANG") {
-            if lang.to_lowercase().starts_with("es") {
-                return "es".to_string();
+            if !lang.is_empty() {
+                return Some(lang);
             }
         }
     }
@@ -81,21 +182,28 @@ pub fn detect_system_locale() -> String {
         // Check LANG or LC_ALL environment variables
         for var in ["LC_ALL", "LC_MESSAGES", "LANG"] {
             if let Ok(lang) = std::env::var(var) {
-                if lang.to_lowercase().starts_with("es") {
-                    return "es".to_string();
+                // "C" and "POSIX" mean no language was chosen
+                if !lang.is_empty() && lang != "C" && lang != "POSIX" {
+                    return Some(lang);
                 }
             }
         }
     }
 
-    "en".to_string()
+    None
+}
+
+/// Detect the system locale and return the closest bundled one (fallback to "en").
+pub fn detect_system_locale() -> String {
+    match system_locale_tag() {
+        Some(tag) => resolve(&tag),
+        None => BASE_LOCALE.to_string(),
+    }
 }
 
 /// Get a translation by dot-separated key path.
 pub fn t(key: &str) -> String {
-    let Some(translations) = TRANSLATIONS.get() else {
-        return key.to_string();
-    };
+    let translations = TRANSLATIONS.read().unwrap_or_else(|e| e.into_inner());
 
     let parts: Vec<&str> = key.split('.').collect();
     if parts.is_empty() {
@@ -121,7 +229,6 @@ pub fn t(key: &str) -> String {
 }
 
 /// Get a translation with placeholder interpolation.
-#[allow(dead_code)]
 pub fn t_with(key: &str, args: &[(&str, &str)]) -> String {
     let mut result = t(key);
     for (placeholder, value) in args {
@@ -131,12 +238,10 @@ pub fn t_with(key: &str, args: &[(&str, &str)]) -> String {
 }
 
 /// Get the full translations object for the frontend.
-/// If locale is "system" or empty, uses the system-detected locale.
+/// If locale is "system" or empty, uses the system-detected locale; any other
+/// tag resolves to the closest bundled locale.
 pub fn get_translations_for_locale(locale: Option<String>) -> Value {
-    let resolved_locale = match locale.as_deref() {
-        Some("system") | Some("") | None => detect_system_locale(),
-        Some(l) => l.to_string(),
-    };
+    let resolved_locale = resolve_preference(locale.as_deref().unwrap_or_default());
 
     let translations = load_locale_file(&resolved_locale);
 
@@ -150,12 +255,10 @@ pub fn get_translations_for_locale(locale: Option<String>) -> Value {
 /// Get the cached translations (used by Rust-side t() function).
 #[allow(dead_code)]
 pub fn get_all_translations() -> Value {
-    let Some(translations) = TRANSLATIONS.get() else {
-        return Value::Object(serde_json::Map::new());
-    };
+    let translations = TRANSLATIONS.read().unwrap_or_else(|e| e.into_inner());
 
     let mut map = serde_json::Map::new();
-    for (key, value) in translations {
+    for (key, value) in translations.iter() {
         map.insert(key.clone(), value.clone());
     }
     Value::Object(map)
@@ -171,4 +274,23 @@ mod tests {
         let result = template.replace("{name}", "World");
         assert_eq!(result, "Hello, World!");
     }
+
+    #[test]
+    fn regional_tags_fall_back_to_language_then_english() {
+        assert_eq!(fallback_chain("es_AR.UTF-8"), vec!["es-ar", "es", "en"]);
+        assert_eq!(fallback_chain("en_US@rg=gbzzzz"), vec!["en-us", "en"]);
+        assert_eq!(fallback_chain(""), vec!["en"]);
+
+        let available = vec!["en".to_string(), "es".to_string(), "pt-BR".to_string()];
+        assert_eq!(resolve_in("es-AR", &available), "es");
+        assert_eq!(resolve_in("pt_BR", &available), "pt-BR");
+        assert_eq!(resolve_in("de_DE", &available), "en");
+    }
+
+    #[test]
+    fn overlay_keeps_keys_missing_from_the_specific_locale() {
+        let mut base = serde_json::json!({ "menu": { "quit": "Quit", "about": "About" } });
+        overlay(&mut base, serde_json::json!({ "menu": { "quit": "Salir" } }));
+        assert_eq!(base, serde_json::json!({ "menu": { "quit": "Salir", "about": "About" } }));
+    }
 }
@@ -1,3 +1,5 @@
+#[cfg(target_os = "macos")]
+mod app_menu;
 mod commands;
 mod dock;
 mod errors;
@@ -6,8 +8,10 @@ mod models;
 #[cfg(target_os = "macos")]
 mod move_to_applications;
 mod services;
+mod shortcut;
 mod state;
 mod tray;
+mod windows;
 
 use std::sync::atomic::Ordering;
 
@@ -16,44 +20,108 @@ use services::media_server::MediaServerState;
 use state::AppState;
 #[cfg(any(target_os = "macos", target_os = "ios"))]
 use tauri::Emitter;
-use tauri::{Manager, RunEvent, WindowEvent};
+use tauri::{Manager, RunEvent};
 use serde_json::Value;
-use tracing::info;
-use tracing_subscriber::EnvFilter;
+use tracing::{info, warn};
+use tracing_subscriber::prelude::*;
+use tracing_subscriber::{reload, EnvFilter};
 
 #[tauri::command]
 fn get_translations(locale: Option<String>) -> Value {
     i18n::get_translations_for_locale(locale)
 }
 
-/// Load saved config from tauri-plugin-store, falling back to defaults.
+/// Codes of the bundled locales ("en", "es", ...), for the language picker.
+#[tauri::command]
+fn get_available_locales() -> Vec<String> {
+    i18n::available_locales()
+}
+
+/// Load saved config from tauri-plugin-store, upgrading older shapes. When the
+/// stored JSON needed a migration or didn't parse, the original is copied to
+/// settings.backup.json first so nothing is lost.
 fn load_saved_config(app: &tauri::App) -> AppConfig {
+    use models::{migrations, CONFIG_VERSION};
     use tauri_plugin_store::StoreExt;
     const STORE_FILE: &str = "settings.json";
+    const BACKUP_FILE: &str = "settings.backup.json";
     const STORE_KEY: &str = "config";
 
-    if let Ok(store) = app.store(STORE_FILE) {
-        if let Some(value) = store.get(STORE_KEY) {
-            if let Ok(config) = serde_json::from_value::<AppConfig>(value) {
-                info!("Loaded saved config from store");
-                return config;
+    let Ok(store) = app.store(STORE_FILE) else {
+        info!("No saved config found, using defaults");
+        return AppConfig::default();
+    };
+    let Some(original) = store.get(STORE_KEY) else {
+        info!("No saved config found, using defaults");
+        return AppConfig::default();
+    };
+
+    let backup = |reason: &str| match app.store(BACKUP_FILE) {
+        Ok(backup) => {
+            backup.set(STORE_KEY, original.clone());
+            backup.set("reason", reason);
+            backup.set("backed_up_at", chrono::Utc::now().to_rfc3339());
+            match backup.save() {
+                Ok(()) => warn!("Backed up previous config to {} ({})", BACKUP_FILE, reason),
+                Err(e) => tracing::error!("Failed to back up config: {}", e),
             }
         }
+        Err(e) => tracing::error!("Failed to open {}: {}", BACKUP_FILE, e),
+    };
+
+    let config = match migrations::migrate(original.clone()) {
+        Ok((migrated, from)) => match serde_json::from_value::<AppConfig>(migrated.clone()) {
+            Ok(config) => {
+                if from != CONFIG_VERSION {
+                    info!("Migrated saved config from version {} to {}", from, CONFIG_VERSION);
+                    backup(&format!("migrated from version {from}"));
+                }
+                config
+            }
+            Err(e) => {
+                warn!("Saved config didn't parse, keeping what still fits: {}", e);
+                backup(&format!("parse failure: {e}"));
+                let (config, dropped) = migrations::salvage(&migrated);
+                warn!("Reset config fields to defaults: {}", dropped.join(", "));
+                config
+            }
+        },
+        Err(e) => {
+            warn!("Saved config unusable, using defaults: {}", e);
+            backup(&e);
+            return AppConfig::default();
+        }
+    };
+
+    // Store the upgraded shape so the migration runs once
+    if config.config_version != original.get("config_version").and_then(Value::as_u64).unwrap_or(0) as u32 {
+        if let Ok(value) = serde_json::to_value(&config) {
+            store.set(STORE_KEY, value);
+            let _ = store.save();
+        }
     }
-    info!("No saved config found, using defaults");
-    AppConfig::default()
+    info!("Loaded saved config from store");
+    config
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    tracing_subscriber::fmt()
-        .with_env_filter(EnvFilter::from_default_env().add_directive("when_then=info".parse().unwrap()))
-        .init();
-
     // Start with defaults; saved config is loaded in setup() once the store is available
     let config = AppConfig::default();
     let app_state = AppState::new(config);
 
+    // RUST_LOG wins; otherwise our own crate at info. logs_set_level swaps it at runtime.
+    let filter = EnvFilter::try_from_default_env()
+        .or_else(|_| services::logs::filter_for(services::logs::DEFAULT_LEVEL))
+        .unwrap_or_else(|_| EnvFilter::new("info"));
+    let (filter, filter_handle) = reload::Layer::new(filter);
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(tracing_subscriber::fmt::layer())
+        .with(app_state.logs.layer())
+        .init();
+    app_state.logs.set_filter_handle(filter_handle);
+
     let app = tauri::Builder::default()
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_store::Builder::default().build())
@@ -61,12 +129,16 @@ pub fn run() {
         .plugin(tauri_plugin_deep_link::init())
         .plugin(tauri_plugin_positioner::init())
         .plugin(tauri_plugin_notification::init())
-        .plugin(tauri_plugin_single_instance::init(|app, _argv, _cwd| {
-            // Focus main window when second instance is launched
-            if let Some(window) = app.get_webview_window("main") {
-                let _ = window.show();
-                let _ = window.set_focus();
+        .plugin(shortcut::plugin())
+        .plugin(tauri_plugin_single_instance::init(|app, argv, cwd| {
+            // Windows and Linux pass magnets and .torrent files to the second instance's argv
+            let opened = services::opened_items::from_argv(&argv, std::path::Path::new(&cwd));
+            // Focus main window when second instance is launched, unless it's
+            // the login item starting the app again or it only had something to open
+            if opened.is_empty() && !commands::startup::launched_hidden(&argv) {
+                let _ = windows::show(app, windows::MAIN);
             }
+            services::opened_items::handle(app, opened);
         }))
         .manage(app_state)
         .setup(|app| {
@@ -79,6 +151,20 @@ pub fn run() {
 
             let saved_config = load_saved_config(app);
             let state = app.state::<AppState>();
+            if saved_config.log_to_file {
+                match app.path().app_data_dir() {
+                    Ok(dir) => {
+                        if let Err(e) = state.logs.enable_file(dir.join("logs")) {
+                            tracing::warn!("{}", e);
+                        }
+                    }
+                    Err(e) => tracing::warn!("No app data directory for log files: {}", e),
+                }
+            }
+            if let Err(e) = shortcut::apply(app.handle(), saved_config.global_shortcut.as_deref()) {
+                warn!("Global shortcut not registered: {}", e);
+            }
+            let saved_locale = saved_config.locale.clone();
             {
                 let config = state.config.clone();
                 tauri::async_runtime::block_on(async {
@@ -87,7 +173,7 @@ pub fn run() {
             }
 
             // Initialize i18n
-            if let Err(e) = i18n::init(app) {
+            if let Err(e) = i18n::init(app, &saved_locale) {
                 tracing::error!("Failed to initialize i18n: {}", e);
             }
 
@@ -96,6 +182,10 @@ pub fn run() {
             let media_server = state.media_server.clone();
             let current_subtitles = state.current_subtitles.clone();
             let local_file_tokens = state.local_file_tokens.clone();
+            let torrent_ids = state.torrent_ids.clone();
+            let thumbnails = state.thumbnails.clone();
+            let completed_history = state.completed_history.clone();
+            let event_bus = state.event_bus.clone();
 
             let app_data_dir = app.path().app_data_dir()
                 .map_err(|e| {
@@ -103,177 +193,32 @@ pub fn run() {
                     e
                 })?;
             let persistence_dir = app_data_dir.join("session");
+            thumbnails.set_dir(app_data_dir.join("thumbs"));
+            media_server.set_tls_dir(app_data_dir.join("tls"));
 
             // Set up tray icon
             tray::setup(app.handle())?;
 
             // Set up macOS application menu
             #[cfg(target_os = "macos")]
-            {
-                use tauri::menu::{Menu, MenuItem, Submenu, PredefinedMenuItem};
-                use crate::i18n::t;
-
-                let h = app.handle();
-
-                // App menu
-                let about_item = PredefinedMenuItem::about(h, Some(&t("menu.about")), None)?;
-                let settings_item = MenuItem::with_id(h, "settings", t("menu.settings"), true, Some("CmdOrCtrl+,"))?;
-                let hide_item = PredefinedMenuItem::hide(h, Some(&t("menu.hide")))?;
-                let hide_others_item = PredefinedMenuItem::hide_others(h, Some(&t("menu.hideOthers")))?;
-                let show_all_item = PredefinedMenuItem::show_all(h, Some(&t("menu.showAll")))?;
-                let quit_item = MenuItem::with_id(h, "quit", t("menu.quit"), true, Some("CmdOrCtrl+Q"))?;
-
-                let app_submenu = Submenu::with_items(
-                    h,
-                    "When",
-                    true,
-                    &[
-                        &about_item,
-                        &PredefinedMenuItem::separator(h)?,
-                        &settings_item,
-                        &PredefinedMenuItem::separator(h)?,
-                        &hide_item,
-                        &hide_others_item,
-                        &show_all_item,
-                        &PredefinedMenuItem::separator(h)?,
-                        &quit_item,
-                    ],
-                )?;
-
-                // File menu
-                let add_torrent_item = MenuItem::with_id(h, "add-torrent", t("menu.addTorrent"), true, Some("CmdOrCtrl+O"))?;
-                let add_magnet_item = MenuItem::with_id(h, "add-magnet", t("menu.addMagnet"), true, Some("CmdOrCtrl+U"))?;
-                let check_feeds_item = MenuItem::with_id(h, "check-feeds", t("menu.checkFeeds"), true, Some("CmdOrCtrl+R"))?;
-
-                let file_submenu = Submenu::with_items(
-                    h,
-                    t("menu.file"),
-                    true,
-                    &[
-                        &add_torrent_item,
-                        &add_magnet_item,
-                        &PredefinedMenuItem::separator(h)?,
-                        &check_feeds_item,
-                    ],
-                )?;
-
-                // Edit menu
-                let undo_item = PredefinedMenuItem::undo(h, Some(&t("menu.undo")))?;
-                let redo_item = PredefinedMenuItem::redo(h, Some(&t("menu.redo")))?;
-                let cut_item = PredefinedMenuItem::cut(h, Some(&t("menu.cut")))?;
-                let copy_item = PredefinedMenuItem::copy(h, Some(&t("menu.copy")))?;
-                let paste_item = PredefinedMenuItem::paste(h, Some(&t("menu.paste")))?;
-                let select_all_item = PredefinedMenuItem::select_all(h, Some(&t("menu.selectAll")))?;
-
-                let edit_submenu = Submenu::with_items(
-                    h,
-                    t("menu.edit"),
-                    true,
-                    &[
-                        &undo_item,
-                        &redo_item,
-                        &PredefinedMenuItem::separator(h)?,
-                        &cut_item,
-                        &copy_item,
-                        &paste_item,
-                        &select_all_item,
-                    ],
-                )?;
-
-                // View menu
-                let view_inbox_item = MenuItem::with_id(h, "view-inbox", t("menu.inbox"), true, Some("CmdOrCtrl+1"))?;
-                let view_playlets_item = MenuItem::with_id(h, "view-playlets", t("menu.playlets"), true, Some("CmdOrCtrl+2"))?;
-                let view_settings_item = MenuItem::with_id(h, "view-settings", t("nav.settings"), true, Some("CmdOrCtrl+3"))?;
-
-                let view_submenu = Submenu::with_items(
-                    h,
-                    t("menu.view"),
-                    true,
-                    &[&view_inbox_item, &view_playlets_item, &view_settings_item],
-                )?;
-
-                // Torrents menu
-                let pause_all_item = MenuItem::with_id(h, "pause-all", t("menu.pauseAll"), true, None::<&str>)?;
-                let resume_all_item = MenuItem::with_id(h, "resume-all", t("menu.resumeAll"), true, None::<&str>)?;
-                let clear_completed_item = MenuItem::with_id(h, "clear-completed", t("menu.clearCompleted"), true, None::<&str>)?;
-
-                let torrents_submenu = Submenu::with_items(
-                    h,
-                    t("menu.torrents"),
-                    true,
-                    &[
-                        &pause_all_item,
-                        &resume_all_item,
-                        &PredefinedMenuItem::separator(h)?,
-                        &clear_completed_item,
-                    ],
-                )?;
-
-                // Window menu
-                let minimize_item = PredefinedMenuItem::minimize(h, Some(&t("menu.minimize")))?;
-
-                let window_submenu = Submenu::with_items(
-                    h,
-                    t("menu.window"),
-                    true,
-                    &[&minimize_item],
-                )?;
-
-                // Help menu
-                let help_docs_item = MenuItem::with_id(h, "help-docs", t("menu.helpDocs"), true, None::<&str>)?;
-
-                let help_submenu = Submenu::with_items(
-                    h,
-                    t("menu.help"),
-                    true,
-                    &[&help_docs_item],
-                )?;
-
-                let menu = Menu::with_items(
-                    h,
-                    &[
-                        &app_submenu,
-                        &file_submenu,
-                        &edit_submenu,
-                        &view_submenu,
-                        &torrents_submenu,
-                        &window_submenu,
-                        &help_submenu,
-                    ],
-                )?;
-                app.set_menu(menu)?;
-            }
+            app.set_menu(app_menu::build(app.handle())?)?;
 
             // Close = hide main window (background mode)
-            if let Some(main_window) = app.get_webview_window("main") {
-                let handle = app.handle().clone();
-                main_window.on_window_event(move |event| {
-                    if let WindowEvent::CloseRequested { api, .. } = event {
-                        api.prevent_close();
-                        if let Some(win) = handle.get_webview_window("main") {
-                            let _ = win.hide();
-                        }
-                    }
-                });
+            if let Some(main_window) = app.get_webview_window(windows::MAIN) {
+                windows::hide_on_close(&main_window);
             }
 
-            // Close = hide picker window (reuse, don't destroy)
-            if let Some(picker) = app.get_webview_window("picker") {
-                let handle = app.handle().clone();
-                picker.on_window_event(move |event| {
-                    if let WindowEvent::CloseRequested { api, .. } = event {
-                        api.prevent_close();
-                        if let Some(win) = handle.get_webview_window("picker") {
-                            let _ = win.hide();
-                        }
-                    }
-                });
-            }
+            // Cold start with a magnet or .torrent file on the command line
+            let args: Vec<String> = std::env::args().collect();
+            let cwd = std::env::current_dir().unwrap_or_default();
+            services::opened_items::handle(app.handle(), services::opened_items::from_argv(&args, &cwd));
 
             let folder_watcher = state.folder_watcher.clone();
             let rss_state = state.rss_state.clone();
             let app_handle_for_watcher = app.handle().clone();
             let app_handle_for_rss = app.handle().clone();
+            let stats_sampler = state.stats_sampler.clone();
+            let app_handle_for_stats = app.handle().clone();
 
             tauri::async_runtime::spawn(async move {
                 let cfg = config.read().await;
@@ -281,10 +226,14 @@ pub fn run() {
                 let cfg_snapshot = cfg.clone();
                 drop(cfg);
 
+                // Restored torrents pick up their custom names as soon as the session is up
+                services::display_names::load(&app_handle_for_stats, &app_handle_for_stats.state::<AppState>()).await;
+
                 match services::torrent_engine::init_session(&cfg_snapshot, persistence_dir).await {
                     Ok(session) => {
                         *torrent_session.write().await = Some(session);
                         info!("Torrent session ready");
+                        services::torrent_engine::spawn_session_monitor(app_handle_for_stats.clone());
                     }
                     Err(e) => {
                         tracing::error!("Failed to init torrent session: {}", e);
@@ -295,26 +244,62 @@ pub fn run() {
                     torrent_session: torrent_session.clone(),
                     current_subtitles,
                     local_file_tokens,
+                    torrent_ids,
+                    thumbnails,
+                    completed_history,
+                    app_handle: Some(app_handle_for_stats.clone()),
+                    event_bus,
+                    stream_token: media_server.stream_token.clone(),
+                    require_stream_auth: cfg_snapshot.require_stream_auth,
+                    tls_enabled: cfg_snapshot.media_server_tls_enabled,
                 };
-                media_server.start(media_state).await;
-                info!("Media server ready on port {}", port);
+                // The handle was created before the saved config loaded, so apply its address and port here
+                let bind_address = services::media_server::parse_bind_address(&cfg_snapshot.media_server_bind_address);
+                media_server.restart(media_state, bind_address, port).await;
+                info!("Media server ready on {}:{}", bind_address, port);
+
+                if cfg_snapshot.enable_upnp {
+                    services::port_mapping::start(&app_handle_for_stats).await;
+                }
+                services::power_monitor::start(app_handle_for_stats.clone());
+
+                let stats_state = app_handle_for_stats.state::<AppState>();
+                services::stats::load(&app_handle_for_stats, &stats_state).await;
+                *stats_sampler.lock().await = Some(services::stats::start(app_handle_for_stats.clone()));
 
                 // Start folder watcher if enabled
                 if cfg_snapshot.watch_folders_enabled && !cfg_snapshot.watch_folders.is_empty() {
                     if let Some(handle) = services::folder_watcher::start_watching(
                         cfg_snapshot.watch_folders.clone(),
+                        cfg_snapshot.watch_folders_depth,
                         app_handle_for_watcher,
                     ) {
                         *folder_watcher.lock().await = Some(handle);
                     }
                 }
 
-                // Load persisted RSS sources, interests, seen items, and bad items
+                // Load persisted RSS sources, interests, seen items, bad items, and pending matches
                 let rss_app_state = app_handle_for_rss.state::<AppState>();
                 commands::rss::load_sources(&app_handle_for_rss, &rss_app_state).await;
                 commands::rss::load_interests(&app_handle_for_rss, &rss_app_state).await;
                 commands::rss::load_seen_items(&app_handle_for_rss, &rss_app_state).await;
                 commands::rss::load_bad_items(&app_handle_for_rss, &rss_app_state).await;
+                commands::rss::load_pending_matches(&app_handle_for_rss, &rss_app_state).await;
+                commands::rss::load_match_counts(&app_handle_for_rss, &rss_app_state).await;
+                services::persistence::start(app_handle_for_rss.clone(), &rss_app_state);
+                commands::scraper::load_configs(&app_handle_for_rss, &rss_app_state).await;
+                commands::scraper::load_seen_items(&app_handle_for_rss, &rss_app_state).await;
+                commands::torznab::load_indexers(&app_handle_for_rss, &rss_app_state).await;
+                services::watch_history::load(&app_handle_for_rss, &rss_app_state).await;
+                services::completed_history::load(&app_handle_for_rss, &rss_app_state).await;
+                services::torrent_labels::load(&app_handle_for_rss, &rss_app_state).await;
+                services::file_order::load(&app_handle_for_rss, &rss_app_state).await;
+                services::torrent_outputs::load(&app_handle_for_rss, &rss_app_state).await;
+                services::cleanup::start(app_handle_for_rss.clone());
+                {
+                    let count = rss_app_state.rss_state.pending_matches.read().await.len();
+                    let _ = services::event_bus::emit(&app_handle_for_rss, "rss:pending-count", count);
+                }
 
                 // Check for demo mode (marker file in app support directory)
                 let demo_marker = app_handle_for_rss.path().app_data_dir()
@@ -338,11 +323,28 @@ pub fn run() {
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
+            // Startup commands
+            commands::startup::get_start_at_login,
+            commands::startup::set_start_at_login,
+            // Power commands
+            commands::power::power_capabilities,
+            // Window commands
+            commands::window::window_show,
+            commands::window::window_hide,
             // Torrent commands
             commands::torrent::torrent_add_magnet,
+            commands::torrent::magnet_parse,
             commands::torrent::torrent_add_file,
+            commands::torrent::torrent_add_dropped,
+            commands::torrent::torrent_import_directory,
             commands::torrent::torrent_add_bytes,
+            commands::torrent::torrent_add_url,
             commands::torrent::torrent_list,
+            commands::torrent::torrent_reannounce,
+            commands::torrent::torrent_force_reconnect,
+            commands::torrent::torrent_set_label,
+            commands::torrent::torrent_set_display_name,
+            commands::torrent::torrent_list_labels,
             commands::torrent::torrent_details,
             commands::torrent::torrent_files,
             commands::torrent::torrent_pause,
@@ -351,6 +353,9 @@ pub fn run() {
             commands::torrent::torrent_recheck,
             commands::torrent::torrent_sync_restored,
             commands::torrent::torrent_update_files,
+            commands::torrent::torrent_add_webseed,
+            commands::torrent::torrent_set_file_order,
+            commands::torrent::torrent_cancel_file_order,
             // Chromecast commands
             commands::chromecast::chromecast_start_discovery,
             commands::chromecast::chromecast_stop_discovery,
@@ -359,7 +364,12 @@ pub fn run() {
             commands::chromecast::chromecast_disconnect,
             // Playback commands
             commands::playback::playback_cast_torrent,
+            commands::playback::playback_check_ready,
+            commands::playback::playback_cast_torrent_queue,
+            commands::playback::playback_queue_next,
+            commands::playback::playback_queue_prev,
             commands::playback::playback_cast_local_file,
+            commands::playback::playback_cast_group,
             commands::playback::playback_open_in_app,
             commands::playback::playback_play,
             commands::playback::playback_pause,
@@ -367,18 +377,30 @@ pub fn run() {
             commands::playback::playback_seek,
             commands::playback::playback_seek_relative,
             commands::playback::playback_set_volume,
+            commands::playback::playback_set_muted,
+            commands::playback::playback_set_subtitle_enabled,
             commands::playback::playback_get_status,
+            commands::playback::playback_get_resume,
+            commands::playback::playback_clear_history,
             // Media commands
             commands::media::subtitle_load_file,
             commands::media::subtitle_clear,
+            commands::media::subtitle_set_offset,
             commands::media::media_server_url,
+            commands::media::media_server_cert_info,
+            commands::media::media_get_thumbnail,
             commands::media::get_playlist_url,
             commands::media::list_media_players,
             commands::media::move_torrent_files,
             commands::media::subtitle_search_opensubtitles,
+            commands::media::subtitle_search_candidates,
+            commands::media::subtitle_download_candidate,
+            commands::media::subtitle_quota,
             // Settings commands
             commands::settings::settings_get,
             commands::settings::settings_update,
+            commands::settings::settings_export,
+            commands::settings::settings_import,
             commands::settings::check_opened_via_url,
             // Automation commands
             commands::automation::check_automation_permission,
@@ -387,27 +409,49 @@ pub fn run() {
             commands::automation::run_shell_command,
             // Rename command
             commands::torrent::torrent_rename_files,
+            commands::torrent::torrent_trackers,
+            commands::torrent::torrent_peers,
+            commands::torrent::torrent_add_tracker,
+            commands::torrent::torrent_reveal_file,
+            commands::torrent::torrent_open_folder,
+            commands::torrent::torrent_verify,
+            commands::torrent::torrent_verify_cancel,
+            commands::torrent::torrent_create,
+            commands::torrent::torrent_create_cancel,
+            commands::torrent::completed_history,
+            commands::torrent::completed_feed_url,
             // Association commands
             commands::associations::check_file_associations,
             commands::associations::set_default_for_torrents,
             commands::associations::set_default_for_magnets,
             // RSS source commands
             commands::rss::rss_add_source,
+            commands::rss::rss_fetch_source_metadata,
             commands::rss::rss_update_source,
             commands::rss::rss_remove_source,
             commands::rss::rss_list_sources,
             commands::rss::rss_toggle_source,
+            commands::rss::rss_source_status,
             // RSS interest commands
             commands::rss::rss_add_interest,
             commands::rss::rss_update_interest,
             commands::rss::rss_remove_interest,
             commands::rss::rss_list_interests,
             commands::rss::rss_toggle_interest,
+            commands::rss::rss_interest_match_counts,
             commands::rss::rss_test_interest,
+            commands::rss::rss_test_interest_against_sources,
+            commands::rss::rss_search,
+            commands::rss::rss_add_search_result,
+            commands::rss::rss_validate_filter,
+            commands::rss::rss_interest_presets,
+            commands::rss::rss_apply_preset,
             // RSS screener commands
             commands::rss::rss_list_pending,
             commands::rss::rss_pending_count,
             commands::rss::rss_fetch_metadata,
+            commands::rss::rss_scrape_health,
+            commands::rss::rss_prefetch_metadata,
             commands::rss::rss_approve_match,
             commands::rss::rss_reject_match,
             commands::rss::rss_check_now,
@@ -424,8 +468,26 @@ pub fn run() {
             commands::scraper::scraper_list_configs,
             commands::scraper::scraper_toggle,
             commands::scraper::scraper_test,
+            // Torznab commands
+            commands::torznab::torznab_add_indexer,
+            commands::torznab::torznab_update_indexer,
+            commands::torznab::torznab_remove_indexer,
+            commands::torznab::torznab_list_indexers,
+            commands::torznab::torznab_toggle_indexer,
+            commands::torznab::torznab_search,
+            // Bandwidth stats
+            commands::stats::stats_get,
+            // Logs
+            commands::logs::logs_get_recent,
+            commands::logs::logs_export,
+            commands::logs::logs_set_level,
+            // Diagnostics
+            commands::diagnostics::diagnostics_run,
+            commands::network::network_status,
             // i18n commands
             get_translations,
+            get_available_locales,
+            commands::settings::i18n_set_locale,
         ])
         .build(tauri::generate_context!())
         .expect("error while building When");
@@ -481,12 +543,15 @@ pub fn run() {
                     });
                 }
                 "view-inbox" => {
+                    let _ = windows::show(app_handle, windows::MAIN);
                     let _ = app_handle.emit("menu:navigate", "inbox");
                 }
                 "view-playlets" => {
+                    let _ = windows::show(app_handle, windows::MAIN);
                     let _ = app_handle.emit("menu:navigate", "rules");
                 }
                 "view-settings" => {
+                    let _ = windows::show(app_handle, windows::MAIN);
                     let _ = app_handle.emit("menu:navigate", "settings");
                 }
                 "pause-all" => {
@@ -520,12 +585,7 @@ pub fn run() {
                                 None => return,
                             }
                         };
-                        let torrents: Vec<_> = session.with_torrents(|iter| {
-                            iter.map(|(id, h)| (id, h.clone())).collect()
-                        });
-                        for (_id, torrent_handle) in torrents {
-                            let _ = session.unpause(&torrent_handle).await;
-                        }
+                        services::torrent_engine::resume_all(&state, &handle, &session).await;
                         let _ = handle.emit("torrents:changed", ());
                     });
                 }
@@ -546,7 +606,7 @@ pub fn run() {
                                 .collect()
                         });
                         for id in completed_ids {
-                            let _ = services::torrent_engine::delete_torrent(&state, id, false).await;
+                            let _ = services::torrent_engine::delete_torrent(&state, &handle, id, false).await;
                         }
                         let _ = handle.emit("torrents:changed", ());
                     });
@@ -565,16 +625,13 @@ pub fn run() {
         match event {
             #[cfg(any(target_os = "macos", target_os = "ios"))]
             RunEvent::Opened { urls } => {
-                handle_opened_urls(app_handle, urls);
+                let opened = urls.iter().filter_map(services::opened_items::from_url).collect();
+                services::opened_items::handle(app_handle, opened);
             }
             #[cfg(target_os = "macos")]
             RunEvent::Reopen { .. } => {
                 // Dock icon clicked - show main window
-                if let Some(window) = app_handle.get_webview_window("main") {
-                    let _ = window.show();
-                    let _ = window.unminimize();
-                    let _ = window.set_focus();
-                }
+                let _ = windows::show(app_handle, windows::MAIN);
             }
             RunEvent::ExitRequested { api, .. } => {
                 let state = app_handle.state::<AppState>();
@@ -597,7 +654,12 @@ fn handle_shutdown(app_handle: &tauri::AppHandle) {
     let discovery_shutdown = state.discovery_shutdown.clone();
     let folder_watcher = state.folder_watcher.clone();
 
+    // Let the frontend show a spinner while torrent state is flushed
+    let _ = app_handle.emit("app:shutting-down", ());
+
     tauri::async_runtime::block_on(async {
+        shortcut::unregister(app_handle);
+
         // Stop folder watcher
         services::folder_watcher::stop_watching(&folder_watcher).await;
 
@@ -605,6 +667,18 @@ fn handle_shutdown(app_handle: &tauri::AppHandle) {
         media_server.stop().await;
         info!("Media server stopped");
 
+        // Record the last stretch of traffic while torrent counters still exist
+        services::stats::stop(&state.stats_sampler).await;
+
+        // Write RSS changes still waiting out the debounce
+        state.rss_writer.flush().await;
+
+        // Don't leave the listen port forwarded after quitting
+        services::port_mapping::stop(app_handle).await;
+
+        // Flush torrent progress before the process exits
+        services::torrent_engine::shutdown_session(&state).await;
+
         // Stop Chromecast discovery
         if let Some(tx) = discovery_shutdown.lock().await.take() {
             let _ = tx.send(());
@@ -619,105 +693,3 @@ fn handle_shutdown(app_handle: &tauri::AppHandle) {
         }
     });
 }
-
-#[cfg(any(target_os = "macos", target_os = "ios"))]
-fn handle_opened_urls(app_handle: &tauri::AppHandle, urls: Vec<tauri::Url>) {
-    // Mark that the app was opened via file/URL so the frontend skips showing the main window.
-    if !urls.is_empty() {
-        let state = app_handle.state::<AppState>();
-        state.opened_via_url.store(true, Ordering::SeqCst);
-    }
-
-    let app_handle = app_handle.clone();
-    tauri::async_runtime::spawn(async move {
-        let state = app_handle.state::<AppState>();
-        // Poll for session init: 30 retries * 500ms = 15s timeout
-        let mut retries = 0;
-        loop {
-            let guard = state.torrent_session.read().await;
-            if guard.is_some() {
-                break;
-            }
-            drop(guard);
-            retries += 1;
-            if retries > 30 {
-                tracing::error!("Torrent session not ready after 15s, giving up on opened URLs");
-                return;
-            }
-            tokio::time::sleep(std::time::Duration::from_millis(500)).await;
-        }
-
-        for url in &urls {
-            let result: crate::errors::Result<()> = match url.scheme() {
-                "magnet" => {
-                    let magnet_uri = url.as_str().to_string();
-                    info!("Handling magnet link: {}", magnet_uri);
-
-                    // Parse magnet to get info hash and display name
-                    let pending = services::torrent_engine::parse_magnet_info(&magnet_uri);
-
-                    // Emit pending event immediately so it shows in UI
-                    let _ = app_handle.emit("torrent:pending", &pending);
-                    info!("Emitted pending magnet: {} ({})", pending.name, pending.info_hash);
-
-                    // Clone what we need for the background task
-                    let inner_state: AppState = (*app_handle.state::<AppState>()).clone();
-                    let inner_handle = app_handle.clone();
-                    let info_hash_for_error = pending.info_hash.clone();
-
-                    // Spawn the actual add in background (no timeout - stays in "fetching" state indefinitely)
-                    tokio::spawn(async move {
-                        match services::torrent_engine::add_magnet(
-                            &inner_state,
-                            &inner_handle,
-                            magnet_uri.clone(),
-                            None,
-                        ).await {
-                            Ok(_) => {
-                                info!("Magnet added successfully");
-                            }
-                            Err(e) => {
-                                tracing::error!("Failed to add magnet: {:?}", e);
-                                let _ = inner_handle.emit("torrent:pending-failed", &serde_json::json!({
-                                    "info_hash": info_hash_for_error,
-                                    "error": e.to_string()
-                                }));
-                            }
-                        }
-                    });
-
-                    Ok(())
-                }
-                "file" => {
-                    if let Ok(path) = url.to_file_path() {
-                        let is_torrent = path.extension()
-                            .map(|ext| ext == "torrent")
-                            .unwrap_or(false);
-                        if is_torrent {
-                            let path_str = path.to_string_lossy().to_string();
-                            info!("Handling torrent file: {}", path_str);
-                            services::torrent_engine::add_torrent_file(
-                                &state,
-                                &app_handle,
-                                path_str,
-                                None,
-                            )
-                            .await
-                            .map(|_| ())
-                        } else {
-                            Ok(())
-                        }
-                    } else {
-                        Ok(())
-                    }
-                }
-                _ => Ok(()),
-            };
-
-            if let Err(e) = result {
-                tracing::error!("Failed to handle opened URL {}: {}", url, e);
-                let _ = app_handle.emit("torrent:error", e.to_string());
-            }
-        }
-    });
-}
@@ -1,7 +1,13 @@
 use serde::{Deserialize, Serialize};
 
+/// Version of the stored config shape; see `migrations`.
+pub const CONFIG_VERSION: u32 = 1;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppConfig {
+    /// Stored config shape; `migrations::migrate` brings older ones up to date
+    #[serde(default = "current_config_version")]
+    pub config_version: u32,
     pub download_directory: String,
     pub theme: ThemeMode,
     pub color_scheme: String,
@@ -9,29 +15,84 @@ pub struct AppConfig {
     pub max_download_speed: u64,
     pub max_upload_speed: u64,
     pub media_server_port: u16,
+    /// Interface the media server listens on ("0.0.0.0" = all, "127.0.0.1" = this machine only)
+    #[serde(default = "default_bind_address")]
+    pub media_server_bind_address: String,
+    /// Serve media over HTTPS with a self-signed certificate
+    #[serde(default)]
+    pub media_server_tls_enabled: bool,
     pub auto_play_next: bool,
     #[serde(default = "default_subtitle_languages")]
     pub subtitle_languages: Vec<String>,
     #[serde(default)]
     pub opensubtitles_api_key: String,
+    /// Optional OpenSubtitles account; logging in raises the daily download quota
+    #[serde(default)]
+    pub opensubtitles_username: String,
+    #[serde(default)]
+    pub opensubtitles_password: String,
     #[serde(default)]
     pub enable_upnp: bool,
     #[serde(default = "default_listen_port")]
     pub listen_port: u16,
+    /// Service asked whether the listen port is open from outside; `{port}` is
+    /// replaced with the port (empty = reachability is reported as unknown)
+    #[serde(default)]
+    pub port_check_url: String,
+    /// Watched folders; plain path strings from older configs load as rules with defaults
     #[serde(default)]
-    pub watch_folders: Vec<String>,
+    pub watch_folders: Vec<WatchFolderRule>,
     #[serde(default)]
     pub watch_folders_enabled: bool,
+    /// How many subfolder levels below each watch folder are scanned (0 = top level only)
+    #[serde(default)]
+    pub watch_folders_depth: u32,
+    /// Move added trigger files into a processed/ subfolder instead of leaving or deleting them
+    #[serde(default)]
+    pub watch_folders_move_processed: bool,
     /// Separate folder for incomplete downloads (empty = same as download_directory)
     #[serde(default)]
     pub incomplete_directory: String,
     /// Max tasks executing at the same time (0 = unlimited)
     #[serde(default)]
     pub max_concurrent_tasks: u32,
+    /// Max torrents downloading at once; extras wait in a queue (0 = unlimited)
+    #[serde(default)]
+    pub max_active_downloads: u32,
+    /// Add new torrents paused, to start them later by hand
+    #[serde(default)]
+    pub add_torrents_paused: bool,
+    /// Remove finished torrents after seeding this many days (0 = never)
+    #[serde(default)]
+    pub remove_completed_after_days: u32,
+    /// Also delete the data of torrents removed by cleanup
+    #[serde(default)]
+    pub remove_completed_delete_data: bool,
+    /// Free space to keep on the download volume when adding torrents (default 2 GB)
+    #[serde(default = "default_disk_space_reserve")]
+    pub disk_space_reserve_bytes: u64,
+    /// Unpack .zip/.rar archives when a torrent finishes
+    #[serde(default)]
+    pub auto_extract_archives: bool,
+    /// Subfolder of the torrent folder archives are unpacked into (empty = torrent folder)
+    #[serde(default)]
+    pub extract_subfolder: String,
     #[serde(default)]
     pub delete_torrent_file_on_add: bool,
     #[serde(default = "default_true")]
     pub show_tray_icon: bool,
+    /// Launch in the tray when the user logs in
+    #[serde(default)]
+    pub start_at_login: bool,
+    /// Pause unfinished torrents while running on battery (macOS)
+    #[serde(default)]
+    pub pause_on_battery: bool,
+    /// System-wide shortcut that toggles the inbox panel, e.g. "Alt+Super+T"
+    #[serde(default)]
+    pub global_shortcut: Option<String>,
+    /// Show download speed and active count in the tray while downloading
+    #[serde(default = "default_true")]
+    pub show_tray_activity: bool,
     #[serde(default)]
     pub default_cast_device: String,
     #[serde(default)]
@@ -46,12 +107,41 @@ pub struct AppConfig {
     /// Metadata fetch timeout in seconds (default 30)
     #[serde(default = "default_metadata_timeout")]
     pub metadata_timeout_secs: u32,
+    /// Require a per-run token on torrent stream and playlist URLs
+    #[serde(default = "default_true")]
+    pub require_stream_auth: bool,
+    /// Show a desktop notification when a torrent finishes
+    #[serde(default = "default_true")]
+    pub notify_on_complete: bool,
+    /// Show a desktop notification when an RSS item matches an interest
+    #[serde(default = "default_true")]
+    pub notify_on_rss_match: bool,
+    /// Trackers appended to every magnet that doesn't already list them
+    #[serde(default = "default_trackers")]
+    pub default_trackers: Vec<String>,
+    /// Automation to run when a torrent finishes downloading
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_complete_action: Option<CompletionAction>,
+    /// Bytes per calendar month, downloaded plus uploaded; torrents pause once it's reached
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub monthly_data_cap: Option<u64>,
+    /// Keep daily log files under app_data_dir/logs
+    #[serde(default)]
+    pub log_to_file: bool,
+}
+
+fn current_config_version() -> u32 {
+    CONFIG_VERSION
 }
 
 fn default_rss_interval() -> u32 {
     15
 }
 
+fn default_bind_address() -> String {
+    "0.0.0.0".to_string()
+}
+
 fn default_locale() -> String {
     "system".to_string()
 }
@@ -60,6 +150,105 @@ fn default_metadata_timeout() -> u32 {
     30
 }
 
+fn default_disk_space_reserve() -> u64 {
+    2_000_000_000
+}
+
+/// Reliable public trackers for better peer discovery on sparse magnets.
+fn default_trackers() -> Vec<String> {
+    [
+        "udp://tracker.opentrackr.org:1337/announce",
+        "udp://open.stealth.si:80/announce",
+        "udp://tracker.torrent.eu.org:451/announce",
+        "udp://tracker.bittor.pw:1337/announce",
+        "udp://public.popcorn-tracker.org:6969/announce",
+        "udp://tracker.dler.org:6969/announce",
+        "udp://exodus.desync.com:6969/announce",
+        "udp://open.demonii.com:1337/announce",
+    ]
+    .into_iter()
+    .map(String::from)
+    .collect()
+}
+
+/// A watched folder and how torrents dropped into it are added.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(from = "WatchFolderRuleRepr")]
+pub struct WatchFolderRule {
+    pub path: String,
+    /// Download directory for torrents from this folder (None = default)
+    pub output_folder: Option<String>,
+    /// Start downloading per add_torrents_paused; off adds torrents paused
+    pub auto_start: bool,
+    pub label: Option<String>,
+    /// Interest whose download folder, label and organize template apply to
+    /// torrents from this folder, as if they were approved matches
+    pub interest_id: Option<String>,
+}
+
+impl WatchFolderRule {
+    pub fn new(path: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            output_folder: None,
+            auto_start: true,
+            label: None,
+            interest_id: None,
+        }
+    }
+}
+
+/// Accepts both the legacy bare-path form and the full rule object.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum WatchFolderRuleRepr {
+    Path(String),
+    Rule {
+        path: String,
+        #[serde(default)]
+        output_folder: Option<String>,
+        #[serde(default = "default_true")]
+        auto_start: bool,
+        #[serde(default)]
+        label: Option<String>,
+        #[serde(default)]
+        interest_id: Option<String>,
+    },
+}
+
+impl From<WatchFolderRuleRepr> for WatchFolderRule {
+    fn from(repr: WatchFolderRuleRepr) -> Self {
+        match repr {
+            WatchFolderRuleRepr::Path(path) => WatchFolderRule::new(path),
+            WatchFolderRuleRepr::Rule { path, output_folder, auto_start, label, interest_id } => WatchFolderRule {
+                path,
+                output_folder,
+                auto_start,
+                label,
+                interest_id,
+            },
+        }
+    }
+}
+
+/// Kind of automation run by a completion hook.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum CompletionActionType {
+    Shortcut,
+    AppleScript,
+    Shell,
+}
+
+/// Automation triggered when a torrent completes.
+/// `payload` is the shortcut name, AppleScript source or shell command;
+/// `{name}`, `{info_hash}`, `{path}` and `{files}` placeholders are substituted.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CompletionAction {
+    pub action_type: CompletionActionType,
+    pub payload: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum ThemeMode {
@@ -88,6 +277,7 @@ impl Default for AppConfig {
             .to_string();
 
         Self {
+            config_version: CONFIG_VERSION,
             download_directory: download_dir,
             theme: ThemeMode::System,
             color_scheme: "auto".to_string(),
@@ -95,23 +285,188 @@ impl Default for AppConfig {
             max_download_speed: 0,
             max_upload_speed: 0,
             media_server_port: 9080,
+            media_server_bind_address: default_bind_address(),
+            media_server_tls_enabled: false,
             auto_play_next: true,
             subtitle_languages: default_subtitle_languages(),
             opensubtitles_api_key: String::new(),
+            opensubtitles_username: String::new(),
+            opensubtitles_password: String::new(),
             enable_upnp: true,
             listen_port: 4240,
+            port_check_url: String::new(),
             watch_folders: vec![],
             watch_folders_enabled: false,
+            watch_folders_depth: 0,
+            watch_folders_move_processed: false,
             incomplete_directory: String::new(),
             max_concurrent_tasks: 0,
+            max_active_downloads: 0,
+            add_torrents_paused: false,
+            remove_completed_after_days: 0,
+            remove_completed_delete_data: false,
+            disk_space_reserve_bytes: default_disk_space_reserve(),
+            auto_extract_archives: false,
+            extract_subfolder: String::new(),
             delete_torrent_file_on_add: false,
             show_tray_icon: true,
+            start_at_login: false,
+            pause_on_battery: false,
+            global_shortcut: None,
+            show_tray_activity: true,
             default_cast_device: String::new(),
             default_media_player: String::new(),
             default_move_destination: String::new(),
             rss_check_interval_minutes: default_rss_interval(),
             locale: default_locale(),
             metadata_timeout_secs: default_metadata_timeout(),
+            require_stream_auth: true,
+            notify_on_complete: true,
+            notify_on_rss_match: true,
+            default_trackers: default_trackers(),
+            on_complete_action: None,
+            monthly_data_cap: None,
+            log_to_file: false,
+        }
+    }
+}
+
+/// What a settings import added (merge) or restored (replace).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SettingsImportSummary {
+    pub sources: usize,
+    pub interests: usize,
+    pub scrapers: usize,
+    pub indexers: usize,
+    pub bad_items: usize,
+}
+
+/// Upgrades for stored configs, applied to the raw JSON before it's read as an
+/// `AppConfig`, so a renamed or retyped field doesn't throw away the rest.
+pub mod migrations {
+    use serde_json::{Map, Value};
+
+    use super::{AppConfig, CONFIG_VERSION};
+
+    type Migration = fn(&mut Map<String, Value>);
+
+    /// `STEPS[n]` upgrades a version `n` config to version `n + 1`.
+    const STEPS: [Migration; CONFIG_VERSION as usize] = [v0_to_v1];
+
+    /// Configs saved before versioning stored watch folders as bare paths.
+    fn v0_to_v1(config: &mut Map<String, Value>) {
+        if let Some(Value::Array(folders)) = config.get_mut("watch_folders") {
+            for folder in folders.iter_mut() {
+                if let Value::String(path) = folder {
+                    *folder = serde_json::json!({ "path": path, "auto_start": true });
+                }
+            }
+        }
+    }
+
+    /// Upgrade `value` to `CONFIG_VERSION` and fill in missing fields from the
+    /// defaults. Returns the upgraded JSON and the version it was saved with.
+    pub fn migrate(value: Value) -> Result<(Value, u32), String> {
+        let Value::Object(mut config) = value else {
+            return Err("Stored config is not a JSON object".into());
+        };
+        let from = config
+            .get("config_version")
+            .and_then(Value::as_u64)
+            .map_or(0, |v| v as u32);
+
+        for step in STEPS.iter().skip(from as usize) {
+            step(&mut config);
+        }
+
+        if let Ok(Value::Object(defaults)) = serde_json::to_value(AppConfig::default()) {
+            for (key, default) in defaults {
+                config.entry(key).or_insert(default);
+            }
         }
+        config.insert("config_version".into(), Value::from(CONFIG_VERSION));
+        Ok((Value::Object(config), from))
+    }
+
+    /// Read as much of `value` as possible: fields that don't fit their type
+    /// keep the default. Returns the config and the names of the dropped fields.
+    pub fn salvage(value: &Value) -> (AppConfig, Vec<String>) {
+        let mut config = match serde_json::to_value(AppConfig::default()) {
+            Ok(Value::Object(defaults)) => defaults,
+            _ => return (AppConfig::default(), Vec::new()),
+        };
+        let mut dropped = Vec::new();
+        if let Value::Object(stored) = value {
+            for (key, field) in stored {
+                let previous = config.insert(key.clone(), field.clone());
+                if serde_json::from_value::<AppConfig>(Value::Object(config.clone())).is_err() {
+                    match previous {
+                        Some(previous) => config.insert(key.clone(), previous),
+                        None => config.remove(key),
+                    };
+                    dropped.push(key.clone());
+                }
+            }
+        }
+        let config = serde_json::from_value(Value::Object(config)).unwrap_or_default();
+        (config, dropped)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::migrations::{migrate, salvage};
+    use super::*;
+
+    #[test]
+    fn migrates_unversioned_config() {
+        let old = json!({
+            "download_directory": "/Volumes/Media",
+            "theme": "dark",
+            "watch_folders": ["/Users/me/Torrents"],
+            "watch_folders_enabled": true,
+        });
+        let (migrated, from) = migrate(old).unwrap();
+        assert_eq!(from, 0);
+
+        let config: AppConfig = serde_json::from_value(migrated).unwrap();
+        assert_eq!(config.config_version, CONFIG_VERSION);
+        assert_eq!(config.download_directory, "/Volumes/Media");
+        assert_eq!(config.theme, ThemeMode::Dark);
+        assert_eq!(config.watch_folders, vec![WatchFolderRule::new("/Users/me/Torrents")]);
+        // Fields without a serde default come from AppConfig::default()
+        assert_eq!(config.media_server_port, 9080);
+        assert!(config.auto_play_next);
+    }
+
+    #[test]
+    fn current_config_passes_through() {
+        let mut config = AppConfig::default();
+        config.download_directory = "/tmp/downloads".into();
+        let (migrated, from) = migrate(serde_json::to_value(&config).unwrap()).unwrap();
+        assert_eq!(from, CONFIG_VERSION);
+        let config: AppConfig = serde_json::from_value(migrated).unwrap();
+        assert_eq!(config.download_directory, "/tmp/downloads");
+    }
+
+    #[test]
+    fn salvage_keeps_fields_that_still_fit() {
+        let stored = json!({
+            "download_directory": "/Volumes/Media",
+            "media_server_port": "not a port",
+            "listen_port": 51413,
+        });
+        let (config, dropped) = salvage(&stored);
+        assert_eq!(config.download_directory, "/Volumes/Media");
+        assert_eq!(config.listen_port, 51413);
+        assert_eq!(config.media_server_port, 9080);
+        assert_eq!(dropped, vec!["media_server_port".to_string()]);
+    }
+
+    #[test]
+    fn rejects_non_object() {
+        assert!(migrate(json!(["not", "a", "config"])).is_err());
     }
 }
@@ -7,9 +7,17 @@ pub struct ChromecastDeviceInfo {
     pub model: String,
     pub address: String,
     pub port: u16,
+    pub protocol: DeviceProtocol,
     pub status: DeviceStatus,
 }
 
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum DeviceProtocol {
+    Chromecast,
+    AirPlay,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum DeviceStatus {
@@ -19,6 +27,14 @@ pub enum DeviceStatus {
     Error,
 }
 
+/// Devices playing the same media together. The leader's status stands for the group.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CastGroup {
+    pub id: String,
+    pub device_ids: Vec<String>,
+    pub leader: String,
+}
+
 #[derive(Debug, Clone)]
 pub struct DiscoveredDevice {
     pub id: String,
@@ -26,6 +42,7 @@ pub struct DiscoveredDevice {
     pub model: String,
     pub address: String,
     pub port: u16,
+    pub protocol: DeviceProtocol,
 }
 
 impl DiscoveredDevice {
@@ -36,6 +53,7 @@ impl DiscoveredDevice {
             model: self.model.clone(),
             address: self.address.clone(),
             port: self.port,
+            protocol: self.protocol,
             status,
         }
     }
@@ -0,0 +1,34 @@
+// Self-check report for support requests.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+    /// Not applicable with the current settings, e.g. no API key to check.
+    Skipped,
+}
+
+/// Outcome of one check.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiagnosticCheck {
+    /// Stable identifier, e.g. "media_server_lan".
+    pub id: String,
+    /// Localized name of what was checked.
+    pub label: String,
+    pub status: CheckStatus,
+    /// What was found: a port, a node count, free space.
+    pub detail: String,
+    /// Localized suggestion for anything that isn't a pass.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remediation: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiagnosticsReport {
+    pub ran_at: String,
+    pub checks: Vec<DiagnosticCheck>,
+}
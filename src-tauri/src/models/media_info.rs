@@ -20,6 +20,16 @@ impl Quality {
             Quality::Q480p => "480p",
         }
     }
+
+    /// Higher is better.
+    pub fn rank(self) -> u8 {
+        match self {
+            Quality::Q480p => 1,
+            Quality::Q720p => 2,
+            Quality::Q1080p => 3,
+            Quality::Q2160p => 4,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -42,6 +52,17 @@ impl MediaSource {
             MediaSource::DvdRip => "DVDRip",
         }
     }
+
+    /// Higher is better. Untouched web downloads beat re-encoded web rips; BluRay beats both.
+    pub fn rank(self) -> u8 {
+        match self {
+            MediaSource::DvdRip => 1,
+            MediaSource::Hdtv => 2,
+            MediaSource::WebRip => 3,
+            MediaSource::WebDl => 4,
+            MediaSource::BluRay => 5,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
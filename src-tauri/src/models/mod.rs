@@ -6,6 +6,11 @@ mod subtitle;
 mod rss;
 mod media_info;
 mod scraper;
+mod torznab;
+mod stats;
+mod diagnostics;
+mod network;
+mod power;
 
 pub use torrent::*;
 pub use device::*;
@@ -15,3 +20,8 @@ pub use subtitle::*;
 pub use rss::*;
 pub use media_info::*;
 pub use scraper::*;
+pub use torznab::*;
+pub use stats::*;
+pub use diagnostics::*;
+pub use network::*;
+pub use power::*;
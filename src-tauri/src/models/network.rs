@@ -0,0 +1,54 @@
+// Listen port, UPnP and DHT state, for seeing why speeds are poor.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum UpnpStatus {
+    Disabled,
+    /// Enabled, but no mapping attempt has finished yet
+    Pending,
+    Mapped,
+    Failed,
+}
+
+/// Outcome of the latest UPnP port mapping attempt, also sent as network:upnp-result.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct UpnpResult {
+    pub status: UpnpStatus,
+    pub port: Option<u16>,
+    /// The router's public address, when it reports one
+    pub external_ip: Option<String>,
+    pub error: Option<String>,
+}
+
+impl UpnpResult {
+    pub fn with_status(status: UpnpStatus) -> Self {
+        Self {
+            status,
+            port: None,
+            external_ip: None,
+            error: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum PortReachability {
+    Open,
+    Closed,
+    /// Not checked, no check service configured, or it gave no clear answer
+    Unknown,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetworkStatus {
+    pub listen_port_start: u16,
+    pub listen_port_end: u16,
+    /// Port the session is listening on, if one in the range answers
+    pub bound_port: Option<u16>,
+    pub upnp: UpnpResult,
+    pub dht_nodes: Option<u64>,
+    pub reachability: PortReachability,
+}
@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct PlaybackStatusResponse {
     pub device_id: String,
     pub state: PlaybackState,
@@ -10,6 +10,9 @@ pub struct PlaybackStatusResponse {
     pub is_muted: bool,
     pub media_title: Option<String>,
     pub content_type: Option<String>,
+    /// For cast groups: largest position gap between the leader and another member
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_drift_secs: Option<f64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -32,19 +35,95 @@ impl Default for PlaybackStatusResponse {
             is_muted: false,
             media_title: None,
             content_type: None,
+            max_drift_secs: None,
         }
     }
 }
 
+/// One file in a receiver-side queue.
+#[derive(Debug, Clone)]
+pub struct QueueItem {
+    pub url: String,
+    pub content_type: String,
+    pub title: String,
+    /// Watch history key to record under while this item plays
+    pub history_key: Option<String>,
+}
+
+/// Where playback of a file last stopped.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WatchEntry {
+    pub position_secs: f64,
+    pub duration_secs: f64,
+    pub completed: bool,
+    pub updated_at: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SubtitleInfo {
     pub url: String,
     pub name: String,
+    /// Format of the loaded file (srt, vtt, ass, ssa); it's served as VTT
     pub format: String,
+    /// Text encoding the file was decoded from
+    pub encoding: String,
+    /// Cues skipped because their timestamps couldn't be parsed
+    pub dropped_cues: usize,
+    /// Timing shift applied to every cue, in milliseconds
+    pub offset_ms: i64,
 }
 
 #[derive(Debug, Clone)]
 pub struct SubtitleData {
+    /// What the media server serves: `source_vtt` shifted by `offset_ms`
     pub vtt_content: String,
+    pub source_vtt: String,
+    pub offset_ms: i64,
     pub original_name: String,
+    pub format: String,
+    pub encoding: String,
+    pub dropped_cues: usize,
+}
+
+/// How much of the start of a torrent file is on disk, for deciding whether
+/// casting can begin without the receiver buffering straight away.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StreamReadiness {
+    pub ready: bool,
+    /// Contiguous bytes downloaded from the start of the file
+    pub buffered_head_bytes: u64,
+    pub total_bytes: u64,
+    /// None when nothing is downloading, so there's no estimate
+    pub est_seconds_until_ready: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ThumbnailStatus {
+    Ready,
+    /// Queued or running; ask again shortly.
+    Generating,
+    /// No ffmpeg, not a video, or not enough of the file downloaded yet.
+    Unavailable,
+}
+
+/// Preview frame for a torrent file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThumbnailInfo {
+    pub status: ThumbnailStatus,
+    /// Media server URL of the JPEG, when ready
+    pub url: Option<String>,
+    /// Why there's no thumbnail, when unavailable
+    pub reason: Option<String>,
+}
+
+/// The media server's self-signed certificate, for checking it on clients.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MediaServerCertInfo {
+    /// SHA-256 of the DER certificate, as colon-separated hex
+    pub fingerprint_sha256: String,
+    /// RFC 3339
+    pub not_after: String,
+    /// Host names and IPs the certificate covers
+    pub hosts: Vec<String>,
 }
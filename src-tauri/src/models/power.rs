@@ -0,0 +1,19 @@
+// Battery awareness, for pausing torrents off AC power.
+
+use serde::{Deserialize, Serialize};
+
+/// What this platform can report about power; the frontend hides settings it can't honor.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PowerCapabilities {
+    pub battery_detection: bool,
+}
+
+/// Sent as power:state-changed.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PowerStatus {
+    pub on_battery: bool,
+    /// Torrents are being paused because of the battery
+    pub holding: bool,
+    /// How many torrents are paused for battery
+    pub paused: usize,
+}
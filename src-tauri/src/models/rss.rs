@@ -2,6 +2,8 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::models::Quality;
+
 fn default_true() -> bool {
     true
 }
@@ -39,6 +41,117 @@ pub struct Source {
     pub check_interval_minutes: u32,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub last_checked: Option<String>,
+    /// Credentials for private feeds.
+    #[serde(flatten)]
+    pub auth: FeedAuth,
+    /// Custom User-Agent for feeds that block the default one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_agent: Option<String>,
+    /// Request timeout in seconds (defaults to 30).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_timeout_secs: Option<u64>,
+    /// Error message from the most recent failed check.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+    /// Last successful check timestamp (ISO 8601).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_success_at: Option<String>,
+    /// Total number of new feed items seen across all checks.
+    #[serde(default)]
+    pub items_seen_total: u64,
+    /// Cached site icon, from rss_fetch_source_metadata.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon_path: Option<String>,
+}
+
+/// What rss_fetch_source_metadata found out about a feed URL.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceMetadata {
+    /// The feed's title, or the URL's host when it couldn't be read.
+    pub suggested_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub icon_path: Option<String>,
+}
+
+/// Health summary for a source, returned by rss_source_status.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceStatus {
+    pub source_id: String,
+    pub source_name: String,
+    pub enabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_checked: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_success_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+    pub consecutive_failures: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry_after: Option<String>,
+    pub items_seen_total: u64,
+}
+
+impl From<&Source> for SourceStatus {
+    fn from(source: &Source) -> Self {
+        Self {
+            source_id: source.id.clone(),
+            source_name: source.name.clone(),
+            enabled: source.enabled,
+            last_checked: source.last_checked.clone(),
+            last_success_at: source.last_success_at.clone(),
+            last_error: source.last_error.clone(),
+            consecutive_failures: source.failure_count,
+            retry_after: source.retry_after.clone(),
+            items_seen_total: source.items_seen_total,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum FeedAuthType {
+    #[default]
+    None,
+    Basic,
+    Cookie,
+    Header,
+}
+
+/// Authentication settings for feeds that need a passkey cookie or auth header.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct FeedAuth {
+    #[serde(default)]
+    pub auth_type: FeedAuthType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+    /// Raw Cookie header value, e.g. "uid=123; pass=abc".
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cookie_string: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub header_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub header_value: Option<String>,
+}
+
+/// Redacts secrets so credentials never end up in logs.
+impl std::fmt::Debug for FeedAuth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fn redact(v: &Option<String>) -> Option<&'static str> {
+            v.as_ref().map(|_| "<redacted>")
+        }
+        f.debug_struct("FeedAuth")
+            .field("auth_type", &self.auth_type)
+            .field("username", &self.username)
+            .field("password", &redact(&self.password))
+            .field("cookie_string", &redact(&self.cookie_string))
+            .field("header_name", &self.header_name)
+            .field("header_value", &redact(&self.header_value))
+            .finish()
+    }
 }
 
 /// An interest is a pattern to watch for across all sources.
@@ -56,9 +169,80 @@ pub struct Interest {
     /// Custom download folder for matched torrents.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub download_path: Option<String>,
+    /// Folder completed downloads are moved into, e.g. "/Media/{interest}/{title} Season {season}".
+    /// Placeholders: {interest}, {title}, {season}, {episode}, {year}, {quality}, {name}.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub organize_template: Option<String>,
     /// Enable smart episode detection to prevent duplicate episodes.
     #[serde(default)]
     pub smart_episode_filter: bool,
+    /// Tie-breaker when several interests match the same item (higher wins).
+    #[serde(default)]
+    pub priority: i32,
+    /// Replace already-matched episodes when a better release shows up.
+    #[serde(default)]
+    pub upgrade_policy: UpgradePolicy,
+    /// Disable the interest once this many matches have been approved.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_matches: Option<u32>,
+    /// Disable the interest once a full season pack has been approved.
+    #[serde(default)]
+    pub auto_disable_when_complete: bool,
+    /// Label given to torrents added from approved matches.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_label: Option<String>,
+    /// Warn when nothing has matched for this many days.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stale_alert_days: Option<u32>,
+    /// When an item last matched (ISO 8601). Kept up to date by the RSS service.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_match_at: Option<String>,
+    /// When quiet time last started over without a match: the interest was
+    /// enabled, or a stale alert went out (ISO 8601).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stale_alert_reset_at: Option<String>,
+    /// Which files of a matched torrent to download, e.g. leaving out samples
+    /// and rars in season packs. Every enabled filter must pass a file's path.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub file_filters: Vec<FeedFilter>,
+    /// Add approved torrents paused (true) or started (false) regardless of
+    /// the add_torrents_paused setting.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub add_paused: Option<bool>,
+    /// Don't match releases of a whole season, e.g. "Show S02" or "Season 2 Complete".
+    #[serde(default)]
+    pub skip_season_packs: bool,
+    /// Checked against a match's file names and total size once its metadata
+    /// is fetched, which happens automatically when there are any.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub metadata_filters: Vec<FeedFilter>,
+    /// Fewest seeders a match's trackers must report; fewer marks it rejected.
+    /// Checking health starts automatically for new matches when set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_seeders: Option<u32>,
+}
+
+/// Payload of rss:interest-stale.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InterestStale {
+    pub interest_id: String,
+    pub interest_name: String,
+    /// Whole days since the last match, or since alerts started counting
+    pub quiet_days: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_match_at: Option<String>,
+}
+
+/// When a better release of an already-matched episode should be queued.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UpgradePolicy {
+    pub enabled: bool,
+    /// Stop upgrading once an episode reaches this quality, and skip releases above it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_quality: Option<Quality>,
+    /// Delete the previous download (and its files) when an upgrade is approved.
+    #[serde(default)]
+    pub auto_replace: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
@@ -86,6 +270,39 @@ pub enum FilterType {
     SizeRange,
     /// Wildcard pattern (* and ? syntax).
     Wildcard,
+    /// Minimum seeders, for feeds that report them.
+    MinSeeders,
+}
+
+/// A ready-made interest to start from. `name_key` and `description_key`
+/// are i18n keys.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InterestPreset {
+    pub id: String,
+    pub name_key: String,
+    pub description_key: String,
+    pub filters: Vec<FeedFilter>,
+    pub filter_logic: FilterLogic,
+    pub smart_episode_filter: bool,
+}
+
+/// What's wrong with a filter that can't be evaluated as written.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum FilterErrorKind {
+    EmptyValue,
+    InvalidRegex,
+    InvalidSizeRange,
+    InvalidNumber,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FilterValidationError {
+    pub kind: FilterErrorKind,
+    pub message: String,
+    /// Character offset in the value where the problem is, when known.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub position: Option<usize>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -103,6 +320,51 @@ pub struct FeedTestItem {
     pub matched_filter: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub size: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seeders: Option<u32>,
+    /// Feed source or scraper the item came from, when testing against saved sources.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_name: Option<String>,
+    /// Matches, but was already seen, so it would only have been caught by an earlier filter.
+    #[serde(default)]
+    pub would_have_matched: bool,
+}
+
+/// One result of a manual search across sources, scrapers and indexers.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchResult {
+    pub title: String,
+    pub source_name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seeders: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub magnet_uri: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub torrent_url: Option<String>,
+}
+
+/// A source that couldn't be searched.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchFailure {
+    pub source_name: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchResponse {
+    pub results: Vec<SearchResult>,
+    pub failures: Vec<SearchFailure>,
+}
+
+/// An interest that matched a pending item.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MatchedInterest {
+    pub id: String,
+    pub name: String,
 }
 
 /// A pending RSS match awaiting user approval.
@@ -111,17 +373,85 @@ pub struct PendingMatch {
     pub id: String,
     pub source_id: String,
     pub source_name: String,
+    /// Every source carrying this release, in the order they were seen.
+    /// Matches saved before duplicates were merged load with just source_name.
+    #[serde(default)]
+    pub sources: Vec<String>,
+    /// Primary (most specific) matched interest; its download_path is used on approval.
     pub interest_id: String,
     pub interest_name: String,
+    /// All interests that matched this item, primary first.
+    #[serde(default)]
+    pub matched_interests: Vec<MatchedInterest>,
     pub title: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub magnet_uri: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub torrent_url: Option<String>,
+    /// Size the source reported, for recognizing the same release elsewhere.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<u64>,
+    /// HTTP web seeds the feed offered, attached when the match is approved.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub web_seeds: Vec<String>,
+    /// Why the last approval failed, while the match waits to be tried again.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+    /// Failed approvals so far, manual and automatic.
+    #[serde(default)]
+    pub failure_count: u32,
+    /// Why the interest's metadata filters turned the match down. It stays
+    /// in the inbox so the filters can be tuned; approving still works.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rejected_reason: Option<String>,
+    /// Why the interest's min_seeders turned the match down, kept apart from
+    /// rejected_reason so neither check clears the other's.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seeders_rejected_reason: Option<String>,
+    /// Best counts from the last tracker scrape, for sorting by health.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub health: Option<TorrentHealth>,
     pub created_at: String,
     /// Torrent metadata fetched for preview.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub metadata: Option<TorrentMetadata>,
+    /// A better release of an episode that was already matched.
+    #[serde(default)]
+    pub is_upgrade: bool,
+    /// Torrent holding the release this one replaces, if that was downloaded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replaces_torrent_id: Option<usize>,
+    /// Title of the release this one replaces.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replaces_title: Option<String>,
+}
+
+/// What one tracker reported for a torrent in a scrape, or why it didn't.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrackerScrape {
+    pub tracker: String,
+    pub seeders: Option<u32>,
+    pub leechers: Option<u32>,
+    pub completed: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// The highest counts any tracker reported, which is the closest a scrape
+/// gets to the size of the swarm.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TorrentHealth {
+    pub seeders: u32,
+    pub leechers: u32,
+    pub checked_at: String,
+}
+
+/// Result of rss_scrape_health: each tracker's answer and the aggregate,
+/// None when no tracker answered.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthReport {
+    pub trackers: Vec<TrackerScrape>,
+    pub health: Option<TorrentHealth>,
 }
 
 /// Torrent metadata for screening before download.
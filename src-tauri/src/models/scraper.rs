@@ -1,5 +1,7 @@
 // Web scraper configuration for non-RSS torrent sites.
 
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -23,12 +25,57 @@ pub struct ScraperConfig {
     /// Delay between requests in milliseconds.
     #[serde(default = "default_delay")]
     pub request_delay_ms: u64,
+    /// Most result pages fetched per search.
+    #[serde(default = "default_max_pages")]
+    pub max_pages: u32,
+    /// CSS selector for the link to the next results page.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_page_selector: Option<String>,
+    /// Appended to the first page's URL for later pages, with a {page} placeholder
+    /// (e.g. "&page={page}"). Used when there's no next-page link to follow.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub page_param: Option<String>,
+    /// CSS selector for the link to an item's detail page, relative to item container.
+    /// Followed when the list item itself has no magnet/torrent link.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail_link_selector: Option<String>,
+    /// CSS selector for the magnet/torrent link on the detail page.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail_magnet_selector: Option<String>,
+    /// Raw Cookie header sent with every request, e.g. a cf_clearance cookie
+    /// copied from a browser that passed the site's challenge.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cookie_string: Option<String>,
+    /// Extra headers sent with every request. A "User-Agent" here replaces the default.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub extra_headers: HashMap<String, String>,
+    /// Check interval in minutes (overrides the global RSS interval).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub check_interval: Option<u32>,
+    /// Next scheduled check timestamp (ISO 8601).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_check_at: Option<String>,
+    /// Consecutive failure count for backoff calculation.
+    #[serde(default)]
+    pub failure_count: u32,
+    /// Don't retry until this timestamp (ISO 8601).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_after: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_checked: Option<String>,
+    /// Error message from the most recent failed check.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
 }
 
 fn default_delay() -> u64 {
     500
 }
 
+fn default_max_pages() -> u32 {
+    1
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScrapedItem {
     pub title: String,
@@ -38,10 +85,18 @@ pub struct ScrapedItem {
     pub torrent_url: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub size: Option<u64>,
+    /// Detail page to fetch the link from when the list item has none.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail_url: Option<String>,
+    /// Whether the link came from the detail page.
+    #[serde(default)]
+    pub from_detail_page: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScraperTestResult {
     pub items: Vec<ScrapedItem>,
     pub total_count: usize,
+    /// Items found on each page fetched, in order.
+    pub page_counts: Vec<usize>,
 }
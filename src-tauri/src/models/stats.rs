@@ -0,0 +1,43 @@
+// Bandwidth usage totals.
+
+use serde::{Deserialize, Serialize};
+
+/// Bytes transferred on one local calendar day.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DailyUsage {
+    /// Local date, "YYYY-MM-DD".
+    pub date: String,
+    pub downloaded: u64,
+    pub uploaded: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageStats {
+    /// Days in the requested range that saw traffic, oldest first.
+    pub days: Vec<DailyUsage>,
+    pub month_downloaded: u64,
+    pub month_uploaded: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub monthly_data_cap: Option<u64>,
+}
+
+/// How far back stats_get looks, counting today.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum StatsRange {
+    Week,
+    Month,
+    Year,
+    All,
+}
+
+impl StatsRange {
+    pub fn days(self) -> Option<i64> {
+        match self {
+            Self::Week => Some(7),
+            Self::Month => Some(30),
+            Self::Year => Some(365),
+            Self::All => None,
+        }
+    }
+}
@@ -8,10 +8,53 @@ pub struct SubtitleSearchResult {
     pub file_name: String,
     pub download_count: i64,
     pub ratings: f64,
+    pub moviehash_match: bool,
+}
+
+/// A scored search result offered to the user before anything is downloaded.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubtitleCandidate {
+    pub file_id: i64,
+    pub file_name: String,
+    pub language: String,
+    pub score: f64,
+    pub download_count: i64,
+    pub ratings: f64,
+    pub moviehash_match: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SubtitleDownloadResult {
     pub file_name: String,
     pub file_path: String,
+    #[serde(default)]
+    pub quota: Option<SubtitleQuota>,
+    #[serde(default)]
+    pub source: SubtitleSource,
+    /// Language code, or "unknown" for local files without one in their name
+    #[serde(default)]
+    pub language: String,
+}
+
+/// Where a subtitle returned by the one-shot search came from.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SubtitleSource {
+    /// Already on disk next to the video
+    Local,
+    #[default]
+    OpenSubtitles,
+}
+
+/// OpenSubtitles download allowance, as last reported by the API.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SubtitleQuota {
+    /// Downloads left until the counter resets
+    pub remaining: i64,
+    /// Downloads used since the last reset
+    pub requests: i64,
+    /// Daily allowance, when known
+    pub allowed: Option<i64>,
+    pub reset_time: Option<String>,
+    pub logged_in: bool,
 }
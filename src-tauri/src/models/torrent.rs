@@ -6,9 +6,36 @@ pub struct TorrentAddedResponse {
     pub name: String,
     pub info_hash: String,
     pub files: Vec<TorrentFileInfo>,
+    /// librqbit was already managing this torrent, so nothing new was added.
+    #[serde(default)]
+    pub already_existed: bool,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportStatus {
+    Imported,
+    AlreadyManaged,
+    Failed,
+}
+
+/// Outcome for one .torrent file from torrent_import_directory.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportReport {
+    pub file: String,
+    pub status: ImportStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub torrent_id: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Folder the existing data was checked in.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub destination: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct TorrentSummary {
     pub id: usize,
     pub name: String,
@@ -18,9 +45,31 @@ pub struct TorrentSummary {
     pub download_speed: u64,
     pub upload_speed: u64,
     pub peers_connected: usize,
+    #[serde(default)]
+    pub queued_peers: usize,
+    #[serde(default)]
+    pub connecting_peers: usize,
     pub total_bytes: u64,
     pub downloaded_bytes: u64,
+    #[serde(default)]
+    pub uploaded_bytes: u64,
     pub file_count: usize,
+    pub label: Option<String>,
+    /// Seconds left at the smoothed download speed; None while stalled, 0 once done
+    #[serde(default)]
+    pub eta_secs: Option<u64>,
+    /// Distributed copies among connected peers. librqbit doesn't expose
+    /// peers' piece bitfields, so this is None for now.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub availability: Option<f64>,
+}
+
+/// Torrent list entries that changed since the last torrents:delta.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TorrentsDelta {
+    pub changed: Vec<TorrentSummary>,
+    /// Ids that left the session
+    pub removed: Vec<usize>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -38,6 +87,127 @@ pub struct TorrentDetails {
     pub file_count: usize,
     pub files: Vec<TorrentFileInfo>,
     pub output_folder: String,
+    pub trackers: Vec<TorrentTracker>,
+    pub label: Option<String>,
+    /// Set while the files are being downloaded in a chosen order
+    pub file_order: Option<FileOrderStatus>,
+}
+
+/// How a file order is applied. librqbit has no per-file priority, so the
+/// only strategy is narrowing the file selection and widening it as files finish.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum FileOrderStrategy {
+    Selection,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileOrderStatus {
+    pub strategy: FileOrderStrategy,
+    /// File indices, first to download first
+    pub order: Vec<usize>,
+    /// Index into `order` of the file downloading now
+    pub position: usize,
+    pub current_file: Option<usize>,
+}
+
+/// A tracker the torrent announces to. librqbit doesn't report per-tracker
+/// announce results, so only the URL is available.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TorrentTracker {
+    pub url: String,
+}
+
+/// A peer a live torrent is connected to. librqbit doesn't report client
+/// names or per-peer progress, so those stay empty.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TorrentPeer {
+    pub address: String,
+    /// librqbit's connection state, e.g. "live" or "connecting".
+    pub state: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client: Option<String>,
+    /// Bytes per second, from the change since the previous call.
+    pub download_speed: u64,
+    pub upload_speed: u64,
+    pub downloaded_bytes: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub progress: Option<f64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TorrentPeers {
+    pub peers: Vec<TorrentPeer>,
+    /// Connected peer count, reported even when per-peer details aren't.
+    pub connected: usize,
+    pub details_available: bool,
+}
+
+/// Piece check results for one file. Pieces spanning a file boundary count
+/// toward both files.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FileVerifyResult {
+    pub index: usize,
+    pub path: String,
+    pub good_pieces: usize,
+    pub bad_pieces: usize,
+    /// Pieces that couldn't be read because the file is absent or too short
+    pub missing_pieces: usize,
+}
+
+/// Outcome of hash-checking a torrent's data on disk.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TorrentVerifyReport {
+    pub id: usize,
+    pub total_pieces: usize,
+    pub good_pieces: usize,
+    pub bad_pieces: usize,
+    pub missing_pieces: usize,
+    /// Share of all pieces that matched, 0.0 to 100.0
+    pub percent_good: f64,
+    pub files: Vec<FileVerifyResult>,
+    /// Stopped early by torrent_verify_cancel; counts cover the pieces checked so far
+    pub cancelled: bool,
+}
+
+/// A finished download, for the history view and the completed feed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompletedEntry {
+    pub info_hash: String,
+    pub name: String,
+    pub total_bytes: u64,
+    /// RFC 3339, UTC
+    pub completed_at: String,
+}
+
+/// A torrent the daily cleanup removed, in torrents:auto-removed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AutoRemovedTorrent {
+    pub id: usize,
+    pub name: String,
+    pub info_hash: String,
+    /// Its data was deleted along with it
+    pub data_deleted: bool,
+}
+
+/// One page of completed_history, newest first.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompletedPage {
+    pub items: Vec<CompletedEntry>,
+    /// Entries across all pages
+    pub total: usize,
+}
+
+/// A .torrent made by torrent_create, and the seeding torrent added from it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TorrentCreated {
+    /// Where the .torrent file was written
+    pub torrent_path: String,
+    pub info_hash: String,
+    pub piece_size: u64,
+    pub piece_count: usize,
+    pub total_bytes: u64,
+    pub torrent: TorrentAddedResponse,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -49,12 +219,34 @@ pub struct TorrentFileInfo {
     pub is_playable: bool,
     pub mime_type: Option<String>,
     pub stream_url: Option<String>,
+    #[serde(default)]
+    pub downloaded_bytes: u64,
+    /// 0.0 to 1.0
+    #[serde(default)]
+    pub progress: f64,
+    /// Part of the torrent's file selection (only_files); false files aren't downloaded
+    #[serde(default = "default_selected")]
+    pub selected: bool,
+}
+
+fn default_selected() -> bool {
+    true
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TorrentAddOptions {
     pub output_folder: Option<String>,
     pub only_files: Option<Vec<usize>>,
+    /// Add without starting the download (true) or start it right away
+    /// (false); None follows the add_torrents_paused setting.
+    #[serde(default, alias = "paused")]
+    pub add_paused: Option<bool>,
+    /// Label for newly added torrents; ignored if the torrent is already managed.
+    #[serde(default)]
+    pub label: Option<String>,
+    /// Add a magnet even though it was marked bad, once the user confirmed.
+    #[serde(default)]
+    pub allow_bad: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -63,6 +255,8 @@ pub enum TorrentState {
     Initializing,
     Downloading,
     Paused,
+    /// Waiting for a free slot under max_active_downloads
+    Queued,
     Completed,
     Error,
 }
@@ -73,3 +267,17 @@ pub struct PendingMagnet {
     pub info_hash: String,
     pub name: String,
 }
+
+/// What a magnet link says, read before adding it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MagnetPreview {
+    /// Lowercase hex
+    pub info_hash: String,
+    pub name: Option<String>,
+    pub trackers: Vec<String>,
+    pub web_seeds: Vec<String>,
+    /// Id of the torrent already in the session with this hash
+    pub existing_id: Option<usize>,
+    /// Marked bad from the RSS inbox
+    pub marked_bad: bool,
+}
@@ -0,0 +1,71 @@
+// Torznab indexers (Jackett, Prowlarr) searched on behalf of interests.
+
+use serde::{Deserialize, Serialize};
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct TorznabIndexer {
+    pub id: String,
+    pub name: String,
+    /// Indexer endpoint, e.g. "http://localhost:9696/1/" for Prowlarr.
+    /// "/api" is appended unless the URL already ends with it.
+    pub base_url: String,
+    pub api_key: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Torznab category ids to restrict searches to (e.g. 5000 for TV).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub categories: Vec<u32>,
+    /// Check interval in minutes (overrides the global RSS interval).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub check_interval: Option<u32>,
+    /// Next scheduled check timestamp (ISO 8601).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_check_at: Option<String>,
+    /// Consecutive failure count for backoff calculation.
+    #[serde(default)]
+    pub failure_count: u32,
+    /// Don't retry until this timestamp (ISO 8601).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_after: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_checked: Option<String>,
+    /// Error message from the most recent failed check.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+}
+
+/// Redacts the API key so it never ends up in logs.
+impl std::fmt::Debug for TorznabIndexer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TorznabIndexer")
+            .field("id", &self.id)
+            .field("name", &self.name)
+            .field("base_url", &self.base_url)
+            .field("api_key", &"<redacted>")
+            .field("enabled", &self.enabled)
+            .field("categories", &self.categories)
+            .field("failure_count", &self.failure_count)
+            .field("last_error", &self.last_error)
+            .finish()
+    }
+}
+
+/// One result from a manual indexer search.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TorznabSearchResult {
+    pub title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub magnet_uri: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub torrent_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seeders: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub published_date: Option<String>,
+}
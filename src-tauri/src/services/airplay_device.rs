@@ -0,0 +1,209 @@
+// AirPlay v1 video sender.
+//
+// The receiver fetches media itself, so it's pointed at the same media server
+// URLs Chromecasts use. Receivers that require a password or pairing are
+// rejected at connect time.
+
+use std::sync::LazyLock;
+use std::time::Duration;
+
+use regex::Regex;
+use tracing::{info, warn};
+
+use crate::errors::{Result, WhenThenError};
+use crate::models::{PlaybackState, PlaybackStatusResponse};
+
+const REQUEST_TIMEOUT_SECS: u64 = 10;
+const USER_AGENT: &str = "MediaControl/1.0";
+
+static PLIST_NUMBER_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"<key>([^<]+)</key>\s*<(?:real|integer)>([^<]+)</(?:real|integer)>").unwrap()
+});
+
+static BUFFER_EMPTY_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"<key>playbackBufferEmpty</key>\s*<true/>").unwrap());
+
+pub struct AirPlayConnection {
+    pub device_id: String,
+    pub device_name: String,
+    base_url: String,
+    session_id: String,
+    client: reqwest::Client,
+}
+
+impl AirPlayConnection {
+    pub async fn connect(
+        device_id: String,
+        device_name: String,
+        address: String,
+        port: u16,
+    ) -> Result<Self> {
+        let host = if address.contains(':') { format!("[{address}]") } else { address };
+        let client = reqwest::Client::builder()
+            .user_agent(USER_AGENT)
+            .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+            .build()
+            .map_err(|e| WhenThenError::CastConnection(format!("HTTP client: {e}")))?;
+
+        let conn = Self {
+            device_id,
+            device_name,
+            base_url: format!("http://{host}:{port}"),
+            session_id: uuid::Uuid::new_v4().to_string(),
+            client,
+        };
+
+        // server-info needs no session and is where locked receivers refuse us
+        let response = conn
+            .request(reqwest::Method::GET, "/server-info")
+            .send()
+            .await
+            .map_err(|e| WhenThenError::CastConnection(format!("Connect failed: {e}")))?;
+        conn.check_access(&response)?;
+
+        info!("Connected to AirPlay receiver: {}", conn.device_name);
+        Ok(conn)
+    }
+
+    fn request(&self, method: reqwest::Method, path: &str) -> reqwest::RequestBuilder {
+        self.client
+            .request(method, format!("{}{}", self.base_url, path))
+            .header("X-Apple-Session-ID", &self.session_id)
+    }
+
+    fn check_access(&self, response: &reqwest::Response) -> Result<()> {
+        match response.status().as_u16() {
+            // 470/471 are AirPlay's "pairing required" responses
+            401 | 403 | 470 | 471 => Err(WhenThenError::CastConnection(format!(
+                "{} requires a password or pairing, which isn't supported yet",
+                self.device_name
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    async fn post(&self, path: &str, action: &str) -> Result<()> {
+        let response = self
+            .request(reqwest::Method::POST, path)
+            .send()
+            .await
+            .map_err(|e| WhenThenError::CastPlayback(format!("{action}: {e}")))?;
+        self.check_access(&response)?;
+        if !response.status().is_success() {
+            return Err(WhenThenError::CastPlayback(format!("{action}: HTTP {}", response.status())));
+        }
+        Ok(())
+    }
+
+    pub async fn load_media(
+        &self,
+        url: String,
+        _content_type: String,
+        subtitle_url: Option<String>,
+    ) -> Result<()> {
+        if subtitle_url.is_some() {
+            warn!("AirPlay receivers can't side-load subtitles; playing without them");
+        }
+        let body = format!("Content-Location: {url}\nStart-Position: 0\n");
+        let response = self
+            .request(reqwest::Method::POST, "/play")
+            .header("Content-Type", "text/parameters")
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| WhenThenError::CastPlayback(format!("Load media: {e}")))?;
+        self.check_access(&response)?;
+        if !response.status().is_success() {
+            return Err(WhenThenError::CastPlayback(format!("Load media: HTTP {}", response.status())));
+        }
+        info!("Media loaded on AirPlay receiver");
+        Ok(())
+    }
+
+    pub async fn play(&self) -> Result<()> {
+        self.post("/rate?value=1.000000", "Play").await
+    }
+
+    pub async fn pause(&self) -> Result<()> {
+        self.post("/rate?value=0.000000", "Pause").await
+    }
+
+    pub async fn stop(&self) -> Result<()> {
+        self.post("/stop", "Stop").await
+    }
+
+    pub async fn seek(&self, position: f64) -> Result<()> {
+        self.post(&format!("/scrub?position={position:.6}"), "Seek").await
+    }
+
+    pub async fn get_status(&self) -> Result<PlaybackStatusResponse> {
+        let response = self
+            .request(reqwest::Method::GET, "/playback-info")
+            .send()
+            .await
+            .map_err(|e| WhenThenError::CastPlayback(format!("Get status: {e}")))?;
+        self.check_access(&response)?;
+        let body = response
+            .text()
+            .await
+            .map_err(|e| WhenThenError::CastPlayback(format!("Get status: {e}")))?;
+
+        Ok(parse_playback_info(&self.device_id, &body))
+    }
+
+    pub async fn disconnect(&self) {
+        info!("Disconnected from AirPlay receiver: {}", self.device_name);
+    }
+}
+
+/// Map the receiver's /playback-info plist onto a status response.
+fn parse_playback_info(device_id: &str, plist: &str) -> PlaybackStatusResponse {
+    let number = |key: &str| {
+        PLIST_NUMBER_RE
+            .captures_iter(plist)
+            .find(|caps| &caps[1] == key)
+            .and_then(|caps| caps[2].trim().parse::<f64>().ok())
+    };
+
+    // No duration means nothing is loaded
+    let Some(duration) = number("duration").filter(|d| *d > 0.0) else {
+        return PlaybackStatusResponse {
+            device_id: device_id.to_string(),
+            ..Default::default()
+        };
+    };
+    let state = match number("rate") {
+        Some(rate) if rate > 0.0 => PlaybackState::Playing,
+        _ if BUFFER_EMPTY_RE.is_match(plist) => PlaybackState::Buffering,
+        _ => PlaybackState::Paused,
+    };
+
+    PlaybackStatusResponse {
+        device_id: device_id.to_string(),
+        state,
+        current_time: number("position").unwrap_or(0.0),
+        duration,
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_playback_info() {
+        let playing = "<dict>\n\t<key>duration</key>\n\t<real>1801.5</real>\n\t<key>position</key>\n\t<real>42.25</real>\n\t<key>rate</key>\n\t<real>1</real>\n\t<key>readyToPlay</key>\n\t<true/>\n</dict>";
+        let status = parse_playback_info("tv", playing);
+        assert_eq!(status.state, PlaybackState::Playing);
+        assert_eq!(status.current_time, 42.25);
+        assert_eq!(status.duration, 1801.5);
+
+        let paused = playing.replace("<real>1</real>", "<integer>0</integer>");
+        assert_eq!(parse_playback_info("tv", &paused).state, PlaybackState::Paused);
+
+        let idle = parse_playback_info("tv", "<dict>\n\t<key>readyToPlay</key>\n\t<false/>\n</dict>");
+        assert_eq!(idle.state, PlaybackState::Idle);
+        assert_eq!(idle.device_id, "tv");
+    }
+}
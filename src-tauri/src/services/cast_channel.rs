@@ -0,0 +1,337 @@
+// Minimal Cast v2 sender for messages rust_cast's typed API doesn't cover
+// (text tracks, EDIT_TRACKS_INFO, queues). One connection is kept open for the
+// length of a cast session and reopened if the receiver drops it.
+
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde_json::{json, Value};
+use tokio::io::{AsyncReadExt, AsyncWriteExt, ReadHalf, WriteHalf};
+use tokio::net::TcpStream;
+use tokio::sync::{oneshot, Mutex};
+
+use crate::errors::{WhenThenError, Result};
+use crate::models::QueueItem;
+
+const SENDER_ID: &str = "sender-when";
+const RECEIVER_ID: &str = "receiver-0";
+const NS_CONNECTION: &str = "urn:x-cast:com.google.cast.tp.connection";
+const NS_HEARTBEAT: &str = "urn:x-cast:com.google.cast.tp.heartbeat";
+pub const NS_MEDIA: &str = "urn:x-cast:com.google.cast.media";
+
+/// Subtitle track id used for side-loaded VTT files.
+pub const SUBTITLE_TRACK_ID: u32 = 1;
+
+/// Receivers close a virtual connection that goes quiet for too long.
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Largest frame read back before the connection is treated as broken.
+const MAX_FRAME_LEN: usize = 64 * 1024;
+
+type TlsStream = tokio_native_tls::TlsStream<TcpStream>;
+type Writer = Arc<Mutex<WriteHalf<TlsStream>>>;
+
+fn write_varint(buf: &mut Vec<u8>, mut value: u64) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            buf.push(byte);
+            break;
+        }
+        buf.push(byte | 0x80);
+    }
+}
+
+fn write_string_field(buf: &mut Vec<u8>, field: u8, value: &str) {
+    buf.push((field << 3) | 2);
+    write_varint(buf, value.len() as u64);
+    buf.extend_from_slice(value.as_bytes());
+}
+
+/// Encode a CastMessage protobuf with a length prefix, as expected on the wire.
+fn encode_message(destination: &str, namespace: &str, payload: &str) -> Vec<u8> {
+    let mut msg = Vec::with_capacity(payload.len() + 128);
+    msg.extend_from_slice(&[0x08, 0x00]); // protocol_version = CASTV2_1_0
+    write_string_field(&mut msg, 2, SENDER_ID);
+    write_string_field(&mut msg, 3, destination);
+    write_string_field(&mut msg, 4, namespace);
+    msg.extend_from_slice(&[0x28, 0x00]); // payload_type = STRING
+    write_string_field(&mut msg, 6, payload);
+
+    let mut framed = Vec::with_capacity(msg.len() + 4);
+    framed.extend_from_slice(&(msg.len() as u32).to_be_bytes());
+    framed.extend_from_slice(&msg);
+    framed
+}
+
+/// Sender connection shared by everything a ChromecastConnection sends
+/// outside rust_cast, with request ids counting up across the session.
+pub struct Sender {
+    address: String,
+    port: u16,
+    link: Mutex<Option<Link>>,
+    next_request_id: AtomicU32,
+}
+
+/// An open connection with its virtual connection to one transport.
+struct Link {
+    transport_id: String,
+    writer: Writer,
+    /// Cleared by the reader task when the receiver closes the connection.
+    alive: Arc<AtomicBool>,
+    /// Stops the reader task when the link is dropped.
+    _shutdown: oneshot::Sender<()>,
+}
+
+impl Sender {
+    pub fn new(address: String, port: u16) -> Self {
+        Self {
+            address,
+            port,
+            link: Mutex::new(None),
+            next_request_id: AtomicU32::new(1),
+        }
+    }
+
+    pub fn request_id(&self) -> u32 {
+        self.next_request_id.fetch_add(1, Ordering::Relaxed)
+    }
+
+    /// Send JSON messages to a receiver app's transport, opening the
+    /// connection first if there's none for that transport yet.
+    pub async fn send(&self, transport_id: &str, messages: &[(&str, Value)]) -> Result<()> {
+        let mut frames = Vec::new();
+        for (namespace, payload) in messages {
+            frames.extend(encode_message(transport_id, namespace, &payload.to_string()));
+        }
+
+        let mut link = self.link.lock().await;
+        let reusable = link
+            .as_ref()
+            .filter(|l| l.transport_id == transport_id && l.alive.load(Ordering::Relaxed));
+        if let Some(open) = reusable {
+            if write_frames(&open.writer, &frames).await.is_ok() {
+                return Ok(());
+            }
+        }
+
+        // No connection yet, a different transport, or the old one went away
+        *link = None;
+        let open = open_link(&self.address, self.port, transport_id).await?;
+        write_frames(&open.writer, &frames).await?;
+        *link = Some(open);
+        Ok(())
+    }
+
+    /// Close the connection, if one is open.
+    pub async fn close(&self) {
+        if let Some(link) = self.link.lock().await.take() {
+            let close = encode_message(&link.transport_id, NS_CONNECTION, &json!({ "type": "CLOSE" }).to_string());
+            let _ = write_frames(&link.writer, &close).await;
+            let _ = link.writer.lock().await.shutdown().await;
+        }
+    }
+}
+
+async fn open_link(address: &str, port: u16, transport_id: &str) -> Result<Link> {
+    let connector = native_tls::TlsConnector::builder()
+        // Cast devices use self-signed certificates
+        .danger_accept_invalid_certs(true)
+        .danger_accept_invalid_hostnames(true)
+        .build()
+        .map_err(|e| WhenThenError::CastConnection(format!("TLS setup: {e}")))?;
+    let connector = tokio_native_tls::TlsConnector::from(connector);
+
+    let tcp = TcpStream::connect((address, port))
+        .await
+        .map_err(|e| WhenThenError::CastConnection(format!("Connect failed: {e}")))?;
+    let stream = connector
+        .connect(address, tcp)
+        .await
+        .map_err(|e| WhenThenError::CastConnection(format!("TLS handshake: {e}")))?;
+    let (reader, writer) = tokio::io::split(stream);
+    let writer = Arc::new(Mutex::new(writer));
+
+    let connect = json!({ "type": "CONNECT" }).to_string();
+    let mut frames = encode_message(RECEIVER_ID, NS_CONNECTION, &connect);
+    frames.extend(encode_message(transport_id, NS_CONNECTION, &connect));
+    write_frames(&writer, &frames).await?;
+
+    let alive = Arc::new(AtomicBool::new(true));
+    let (shutdown, stopped) = oneshot::channel();
+    tokio::spawn(keep_alive(reader, writer.clone(), alive.clone(), stopped));
+
+    Ok(Link {
+        transport_id: transport_id.to_string(),
+        writer,
+        alive,
+        _shutdown: shutdown,
+    })
+}
+
+async fn write_frames(writer: &Writer, frames: &[u8]) -> Result<()> {
+    let mut writer = writer.lock().await;
+    writer
+        .write_all(frames)
+        .await
+        .map_err(|e| WhenThenError::CastPlayback(format!("Send failed: {e}")))?;
+    writer.flush().await.ok();
+    Ok(())
+}
+
+/// Ping the receiver and discard what it sends back, so its status
+/// broadcasts don't back up, until the connection closes or the link is dropped.
+async fn keep_alive(
+    mut reader: ReadHalf<TlsStream>,
+    writer: Writer,
+    alive: Arc<AtomicBool>,
+    mut stopped: oneshot::Receiver<()>,
+) {
+    let ping = encode_message(RECEIVER_ID, NS_HEARTBEAT, &json!({ "type": "PING" }).to_string());
+    let mut heartbeat = tokio::time::interval(HEARTBEAT_INTERVAL);
+    let mut buf = Vec::new();
+    loop {
+        tokio::select! {
+            _ = &mut stopped => break,
+            _ = heartbeat.tick() => {
+                if write_frames(&writer, &ping).await.is_err() {
+                    break;
+                }
+            }
+            frame = read_frame(&mut reader, &mut buf) => {
+                if frame.is_err() {
+                    break;
+                }
+            }
+        }
+    }
+    alive.store(false, Ordering::Relaxed);
+}
+
+async fn read_frame(reader: &mut ReadHalf<TlsStream>, buf: &mut Vec<u8>) -> std::io::Result<()> {
+    let len = reader.read_u32().await? as usize;
+    if len > MAX_FRAME_LEN {
+        return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "Cast frame too large"));
+    }
+    buf.resize(len, 0);
+    reader.read_exact(buf).await?;
+    Ok(())
+}
+
+/// LOAD payload with a side-loaded WebVTT subtitle track enabled.
+pub fn load_with_subtitles(request_id: u32, session_id: &str, url: &str, content_type: &str, subtitle_url: &str) -> Value {
+    json!({
+        "type": "LOAD",
+        "requestId": request_id,
+        "sessionId": session_id,
+        "autoplay": true,
+        "activeTrackIds": [SUBTITLE_TRACK_ID],
+        "media": {
+            "contentId": url,
+            "contentType": content_type,
+            "streamType": "BUFFERED",
+            "textTrackStyle": {
+                "backgroundColor": "#00000000",
+                "foregroundColor": "#FFFFFFFF",
+                "edgeType": "OUTLINE",
+                "edgeColor": "#000000FF",
+                "fontScale": 1.0,
+            },
+            "tracks": [{
+                "trackId": SUBTITLE_TRACK_ID,
+                "type": "TEXT",
+                "subtype": "SUBTITLES",
+                "trackContentId": subtitle_url,
+                "trackContentType": "text/vtt",
+                "name": "Subtitles",
+            }],
+        },
+    })
+}
+
+/// EDIT_TRACKS_INFO payload toggling the subtitle track.
+pub fn edit_tracks_info(request_id: u32, media_session_id: i32, enabled: bool) -> Value {
+    let active: Vec<u32> = if enabled { vec![SUBTITLE_TRACK_ID] } else { Vec::new() };
+    json!({
+        "type": "EDIT_TRACKS_INFO",
+        "requestId": request_id,
+        "mediaSessionId": media_session_id,
+        "activeTrackIds": active,
+    })
+}
+
+/// QUEUE_LOAD payload playing `items` in order from the first.
+pub fn queue_load(request_id: u32, session_id: &str, items: &[QueueItem]) -> Value {
+    let items: Vec<Value> = items
+        .iter()
+        .map(|item| {
+            json!({
+                "autoplay": true,
+                "preloadTime": 20,
+                "media": {
+                    "contentId": item.url,
+                    "contentType": item.content_type,
+                    "streamType": "BUFFERED",
+                    "metadata": { "metadataType": 0, "title": item.title },
+                },
+            })
+        })
+        .collect();
+    json!({
+        "type": "QUEUE_LOAD",
+        "requestId": request_id,
+        "sessionId": session_id,
+        "startIndex": 0,
+        "repeatMode": "REPEAT_OFF",
+        "items": items,
+    })
+}
+
+/// QUEUE_UPDATE payload moving `jump` items forward (or back, if negative).
+pub fn queue_jump(request_id: u32, media_session_id: i32, jump: i32) -> Value {
+    json!({
+        "type": "QUEUE_UPDATE",
+        "requestId": request_id,
+        "mediaSessionId": media_session_id,
+        "jump": jump,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_message_framing() {
+        let framed = encode_message("web-1", NS_MEDIA, "{}");
+        let len = u32::from_be_bytes([framed[0], framed[1], framed[2], framed[3]]) as usize;
+        assert_eq!(len, framed.len() - 4);
+        assert_eq!(&framed[4..6], &[0x08, 0x00]);
+        assert!(framed.ends_with(&[0x32, 0x02, b'{', b'}']));
+    }
+
+    #[test]
+    fn test_queue_load_keeps_order() {
+        let item = |n: u32| QueueItem {
+            url: format!("http://h/torrent/1/stream/{n}"),
+            content_type: "video/mp4".into(),
+            title: format!("E{n}"),
+            history_key: None,
+        };
+        let load = queue_load(5, "s-1", &[item(2), item(0)]);
+        assert_eq!(load["type"], "QUEUE_LOAD");
+        assert_eq!(load["requestId"], 5);
+        assert_eq!(load["items"][0]["media"]["contentId"], "http://h/torrent/1/stream/2");
+        assert_eq!(load["items"][1]["media"]["metadata"]["title"], "E0");
+        assert_eq!(queue_jump(6, 7, -1)["jump"], -1);
+    }
+
+    #[test]
+    fn test_varint_multi_byte() {
+        let mut buf = Vec::new();
+        write_varint(&mut buf, 300);
+        assert_eq!(buf, vec![0xac, 0x02]);
+    }
+}
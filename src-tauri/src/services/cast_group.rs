@@ -0,0 +1,138 @@
+// Cast groups: several devices playing the same media, controlled as one.
+//
+// A group id can be passed anywhere a device id is accepted by the playback
+// commands. Members that stop responding are dropped from the group instead of
+// failing the command for the rest.
+
+use tauri::{AppHandle, Emitter};
+use tracing::warn;
+
+use crate::errors::{Result, WhenThenError};
+use crate::models::{CastGroup, PlaybackStatusResponse};
+use crate::services::device_connection::DeviceConnection;
+use crate::state::AppState;
+
+#[derive(Debug, Clone, Copy)]
+pub enum PlaybackAction {
+    Play,
+    Pause,
+    Stop,
+    Seek(f64),
+}
+
+#[derive(serde::Serialize, Clone)]
+struct MemberLost {
+    group_id: String,
+    device_id: String,
+    reason: String,
+}
+
+pub fn new_group_id() -> String {
+    format!("group-{}", uuid::Uuid::new_v4())
+}
+
+pub async fn get(state: &AppState, id: &str) -> Option<CastGroup> {
+    state.cast_groups.read().await.get(id).cloned()
+}
+
+async fn run(conn: &DeviceConnection, action: PlaybackAction) -> Result<()> {
+    match action {
+        PlaybackAction::Play => conn.play().await,
+        PlaybackAction::Pause => conn.pause().await,
+        PlaybackAction::Stop => conn.stop().await,
+        PlaybackAction::Seek(position) => conn.seek(position).await,
+    }
+}
+
+/// Run an action on one device, or on every member when `id` is a group.
+pub async fn control(app_handle: &AppHandle, state: &AppState, id: &str, action: PlaybackAction) -> Result<()> {
+    let Some(group) = get(state, id).await else {
+        let connections = state.active_connections.lock().await;
+        let conn = connections
+            .get(id)
+            .ok_or_else(|| WhenThenError::DeviceNotFound(id.to_string()))?;
+        return run(conn, action).await;
+    };
+
+    for device_id in &group.device_ids {
+        let result = {
+            let connections = state.active_connections.lock().await;
+            match connections.get(device_id) {
+                Some(conn) => run(conn, action).await,
+                None => Err(WhenThenError::DeviceNotFound(device_id.clone())),
+            }
+        };
+        if let Err(e) = result {
+            drop_member(app_handle, state, &group.id, device_id, &e.to_string()).await;
+        }
+    }
+
+    if get(state, id).await.is_none() {
+        return Err(WhenThenError::CastPlayback("Every device in the group was lost".into()));
+    }
+    Ok(())
+}
+
+/// Leader status for a group, with the largest position gap to any other member.
+pub async fn status(app_handle: &AppHandle, state: &AppState, group: &CastGroup) -> Result<PlaybackStatusResponse> {
+    let mut statuses = Vec::new();
+    for device_id in &group.device_ids {
+        let result = {
+            let connections = state.active_connections.lock().await;
+            match connections.get(device_id) {
+                Some(conn) => conn.get_status().await,
+                None => Err(WhenThenError::DeviceNotFound(device_id.clone())),
+            }
+        };
+        match result {
+            Ok(status) => statuses.push(status),
+            Err(e) => drop_member(app_handle, state, &group.id, device_id, &e.to_string()).await,
+        }
+    }
+
+    let group = get(state, &group.id)
+        .await
+        .ok_or_else(|| WhenThenError::CastPlayback("Every device in the group was lost".into()))?;
+    let mut leader = statuses
+        .iter()
+        .find(|s| s.device_id == group.leader)
+        .cloned()
+        .ok_or_else(|| WhenThenError::DeviceNotFound(group.leader.clone()))?;
+
+    let drift = statuses
+        .iter()
+        .map(|s| (s.current_time - leader.current_time).abs())
+        .fold(0.0, f64::max);
+    leader.device_id = group.id;
+    leader.max_drift_secs = Some(drift);
+    Ok(leader)
+}
+
+/// Remove a failed member, handing leadership on if needed. Groups with no
+/// members left are deleted.
+pub async fn drop_member(app_handle: &AppHandle, state: &AppState, group_id: &str, device_id: &str, reason: &str) {
+    warn!("Cast group {} lost {}: {}", group_id, device_id, reason);
+    {
+        let mut groups = state.cast_groups.write().await;
+        if let Some(group) = groups.get_mut(group_id) {
+            group.device_ids.retain(|d| d != device_id);
+            if group.leader == device_id {
+                group.leader = group.device_ids.first().cloned().unwrap_or_default();
+            }
+            if group.device_ids.is_empty() {
+                groups.remove(group_id);
+            }
+        }
+    }
+
+    app_handle
+        .emit(
+            "playback:group-member-lost",
+            MemberLost {
+                group_id: group_id.to_string(),
+                device_id: device_id.to_string(),
+                reason: reason.to_string(),
+            },
+        )
+        .unwrap_or_default();
+}
@@ -1,27 +1,53 @@
+use std::future::Future;
+use std::pin::Pin;
 use std::sync::Arc;
 use rust_cast::{
     CastDevice,
     channels::{
-        media::{Media, StreamType},
-        receiver::CastDeviceApp,
+        media::{Media, Metadata, StreamType},
+        receiver::{CastDeviceApp, Volume},
     },
 };
+use tauri::Manager;
 use tokio::sync::Mutex;
-use tracing::{info, warn};
+use tracing::{debug, info, warn};
 
 use crate::errors::{WhenThenError, Result};
-use crate::models::{PlaybackState, PlaybackStatusResponse};
+use crate::models::{PlaybackState, PlaybackStatusResponse, QueueItem};
+use crate::services::device_connection::{DeviceConnection, Reconnecting};
+use crate::services::{cast_channel, watch_history};
+use crate::state::AppState;
 
 /// Connection attempt timeout.
 const CONNECT_TIMEOUT_SECS: u64 = 10;
 
+/// How often the status watcher asks the receiver for media status.
+const STATUS_POLL_INTERVAL_MS: u64 = 1500;
+
+/// Reconnect attempts after a heartbeat fails, before giving up.
+const RECONNECT_ATTEMPTS: u32 = 5;
+
+/// Wait before the first reconnect attempt; doubles after each failure.
+const RECONNECT_FIRST_DELAY: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// Status polls after QUEUE_LOAD before deciding the receiver ignored it.
+const QUEUE_LOAD_POLLS: u32 = 10;
+
+#[derive(Clone)]
 pub struct ChromecastConnection {
     pub device_id: String,
     pub device_name: String,
+    address: String,
+    port: u16,
     device: Arc<Mutex<Option<CastDevice<'static>>>>,
     transport_id: Arc<Mutex<Option<String>>>,
     session_id: Arc<Mutex<Option<String>>>,
+    /// Connection for the messages rust_cast can't send.
+    sender: Arc<cast_channel::Sender>,
     heartbeat_shutdown: Arc<Mutex<Option<tokio::sync::oneshot::Sender<()>>>>,
+    status_shutdown: Arc<Mutex<Option<tokio::sync::oneshot::Sender<()>>>>,
+    /// Items of the queue last loaded; empty after a single-item load.
+    queue: Arc<Mutex<Vec<QueueItem>>>,
     /// Optional handle to emit events back to the frontend.
     app_handle: Option<tauri::AppHandle>,
 }
@@ -38,8 +64,9 @@ impl ChromecastConnection {
         port: u16,
         app_handle: Option<tauri::AppHandle>,
     ) -> Result<Self> {
+        let connect_address = address.clone();
         let connect_fut = tokio::task::spawn_blocking(move || {
-            CastDevice::connect_without_host_verification(address, port)
+            CastDevice::connect_without_host_verification(connect_address, port)
         });
 
         let cast_device = tokio::time::timeout(
@@ -79,14 +106,20 @@ impl ChromecastConnection {
         let conn = Self {
             device_id: device_id.clone(),
             device_name: device_name.clone(),
+            sender: Arc::new(cast_channel::Sender::new(address.clone(), port)),
+            address,
+            port,
             device,
             transport_id: Arc::new(Mutex::new(Some(transport_id))),
             session_id: Arc::new(Mutex::new(Some(session_id))),
             heartbeat_shutdown: Arc::new(Mutex::new(None)),
+            status_shutdown: Arc::new(Mutex::new(None)),
+            queue: Arc::new(Mutex::new(Vec::new())),
             app_handle,
         };
 
         conn.start_heartbeat().await;
+        conn.start_status_watcher().await;
 
         info!("Connected to Chromecast: {}", device_name);
         Ok(conn)
@@ -96,6 +129,8 @@ impl ChromecastConnection {
         let device = self.device.clone();
         let device_id = self.device_id.clone();
         let device_name = self.device_name.clone();
+        let address = self.address.clone();
+        let port = self.port;
         let app_handle = self.app_handle.clone();
         let (tx, mut rx) = tokio::sync::oneshot::channel::<()>();
         *self.heartbeat_shutdown.lock().await = Some(tx);
@@ -105,30 +140,114 @@ impl ChromecastConnection {
                 tokio::select! {
                     _ = &mut rx => break,
                     _ = tokio::time::sleep(std::time::Duration::from_secs(5)) => {
-                        let dev = device.lock().await;
-                        if let Some(ref d) = *dev {
-                            match d.heartbeat.ping() {
-                                Ok(_) => {},
-                                Err(e) => {
-                                    warn!("Heartbeat failed for {}: {}", device_name, e);
-                                    if let Some(ref handle) = app_handle {
-                                        #[derive(serde::Serialize, Clone)]
-                                        struct Disconnected { id: String, name: String, reason: String }
-                                        let _ = tauri::Emitter::emit(handle, "chromecast:disconnected", Disconnected {
-                                            id: device_id.clone(),
-                                            name: device_name.clone(),
-                                            reason: format!("Heartbeat failed: {e}"),
-                                        });
-                                    }
+                        // The lock is released before reconnecting, which disconnects this connection
+                        let ping = match *device.lock().await {
+                            Some(ref d) => d.heartbeat.ping(),
+                            None => break,
+                        };
+                        if let Err(e) = ping {
+                            warn!("Heartbeat failed for {}: {}", device_name, e);
+                            if let Some(handle) = app_handle {
+                                begin_reconnect(handle, device_id, device_name, address, port, format!("Heartbeat failed: {e}")).await;
+                            }
+                            break;
+                        }
+                    }
+                }
+            }
+        });
+    }
+
+    /// Poll media status in the background and emit `playback:status` when it
+    /// changes, plus `playback:ended` when playback runs out and
+    /// `playback:queue-item-changed` when a queue moves on.
+    async fn start_status_watcher(&self) {
+        let Some(app_handle) = self.app_handle.clone() else {
+            return;
+        };
+        let device = self.device.clone();
+        let transport_id = self.transport_id.clone();
+        let device_id = self.device_id.clone();
+        let queue = self.queue.clone();
+        let (tx, mut rx) = tokio::sync::oneshot::channel::<()>();
+        *self.status_shutdown.lock().await = Some(tx);
+
+        tokio::spawn(async move {
+            let mut last: Option<PlaybackStatusResponse> = None;
+            let mut last_content: Option<String> = None;
+            loop {
+                tokio::select! {
+                    _ = &mut rx => break,
+                    _ = tokio::time::sleep(std::time::Duration::from_millis(STATUS_POLL_INTERVAL_MS)) => {
+                        // query_media releases the device lock before returning
+                        let (mut status, content_id) = match query_media(&device, &transport_id, &device_id).await {
+                            Ok(media) => media,
+                            Err(e) => {
+                                if device.lock().await.is_none() {
                                     break;
                                 }
+                                debug!("Status poll failed for {}: {}", device_id, e);
+                                continue;
+                            }
+                        };
+                        // rust_cast doesn't parse currentItemId, so the queue
+                        // position is found from the content that's loaded
+                        if content_id.is_some() && content_id != last_content {
+                            let queue = queue.lock().await;
+                            let current = queue
+                                .iter()
+                                .position(|item| Some(&item.url) == content_id.as_ref());
+                            if let Some(index) = current {
+                                let item = &queue[index];
+                                if let Some(key) = item.history_key.clone() {
+                                    app_handle.state::<AppState>().watch_history.write().await.start(&device_id, key);
+                                }
+                                #[derive(serde::Serialize, Clone)]
+                                struct QueueItemChanged { device_id: String, index: usize, title: String }
+                                tauri::Emitter::emit(&app_handle, "playback:queue-item-changed", QueueItemChanged {
+                                    device_id: device_id.clone(),
+                                    index,
+                                    title: item.title.clone(),
+                                }).unwrap_or_default();
+                            }
+                            last_content = content_id;
+                        }
+
+                        // Whole seconds are enough for the UI and keep idle ticks quiet
+                        status.current_time = status.current_time.floor();
+                        if last.as_ref() == Some(&status) {
+                            continue;
+                        }
+
+                        let was_active = last
+                            .as_ref()
+                            .is_some_and(|prev| prev.state != PlaybackState::Idle);
+                        crate::services::event_bus::emit(&app_handle, "playback:status", &status).unwrap_or_default();
+                        if was_active && status.state == PlaybackState::Idle {
+                            #[derive(serde::Serialize, Clone)]
+                            struct Ended { device_id: String }
+                            tauri::Emitter::emit(&app_handle, "playback:ended", Ended {
+                                device_id: device_id.clone(),
+                            }).unwrap_or_default();
+                        }
+
+                        // Keep resume positions current without the frontend polling
+                        if status.state != PlaybackState::Idle {
+                            let state = app_handle.state::<AppState>();
+                            let save = state
+                                .watch_history
+                                .write()
+                                .await
+                                .record(&device_id, status.current_time, status.duration);
+                            if save {
+                                watch_history::persist(&app_handle, &state).await;
                             }
-                        } else {
-                            break;
                         }
+                        last = Some(status);
                     }
                 }
             }
+            debug!("Status watcher stopped for {}", device_id);
         });
     }
 
@@ -136,36 +255,154 @@ impl ChromecastConnection {
         &self,
         url: String,
         content_type: String,
-        _subtitle_url: Option<String>,
+        subtitle_url: Option<String>,
     ) -> Result<()> {
-        let dev = self.device.lock().await;
-        let dev = dev
-            .as_ref()
-            .ok_or_else(|| WhenThenError::CastConnection("Not connected".into()))?;
-        let tid = self.transport_id.lock().await;
-        let tid = tid
-            .as_ref()
+        let (tid, sid) = self.app_ids().await?;
+        self.queue.lock().await.clear();
+
+        let Some(subtitle_url) = subtitle_url else {
+            let dev = self.device.lock().await;
+            let dev = dev
+                .as_ref()
+                .ok_or_else(|| WhenThenError::CastConnection("Not connected".into()))?;
+            dev.media.load(
+                tid.as_str(),
+                sid.as_str(),
+                &Media {
+                    content_id: url,
+                    content_type,
+                    stream_type: StreamType::Buffered,
+                    duration: None,
+                    metadata: None,
+                },
+            )
+            .map_err(|e| WhenThenError::CastPlayback(format!("Load media: {e}")))?;
+
+            info!("Media loaded on Chromecast");
+            return Ok(());
+        };
+
+        // rust_cast's Media has no tracks field, so LOAD goes out as raw JSON
+        let load = cast_channel::load_with_subtitles(self.sender.request_id(), &sid, &url, &content_type, &subtitle_url);
+        self.sender.send(&tid, &[(cast_channel::NS_MEDIA, load)]).await?;
+
+        // Wait for the media session to appear, then make sure the track is active.
+        // Nothing is locked while waiting, so status polls and controls carry on.
+        let mut media_session_id = None;
+        for _ in 0..10 {
+            tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+            if let Some((id, _)) = self.current_media(&tid).await {
+                media_session_id = Some(id);
+                break;
+            }
+        }
+
+        match media_session_id {
+            Some(id) => {
+                let edit = cast_channel::edit_tracks_info(self.sender.request_id(), id, true);
+                self.sender.send(&tid, &[(cast_channel::NS_MEDIA, edit)]).await?;
+                info!("Media loaded on Chromecast with subtitles");
+            }
+            None => warn!("Media session not reported after LOAD; subtitles may be inactive"),
+        }
+        Ok(())
+    }
+
+    /// Transport and session ids of the running receiver app.
+    async fn app_ids(&self) -> Result<(String, String)> {
+        let tid = self
+            .transport_id
+            .lock()
+            .await
+            .clone()
             .ok_or_else(|| WhenThenError::CastConnection("No transport".into()))?;
-        let sid = self.session_id.lock().await;
-        let sid = sid
-            .as_ref()
+        let sid = self
+            .session_id
+            .lock()
+            .await
+            .clone()
             .ok_or_else(|| WhenThenError::CastConnection("No session".into()))?;
+        Ok((tid, sid))
+    }
 
-        dev.media.load(
-            tid.as_str(),
-            sid.as_str(),
-            &Media {
-                content_id: url,
-                content_type,
-                stream_type: StreamType::Buffered,
-                duration: None,
-                metadata: None,
-            },
-        )
-        .map_err(|e| WhenThenError::CastPlayback(format!("Load media: {e}")))?;
+    /// Media session id and content id of what's loaded, if anything is.
+    /// Holds the device lock only for the one status request.
+    async fn current_media(&self, tid: &str) -> Option<(i32, Option<String>)> {
+        let dev = self.device.lock().await;
+        let status = dev.as_ref()?.media.get_status(tid, None).ok()?;
+        let entry = status.entries.first()?;
+        Some((entry.media_session_id, entry.media.as_ref().map(|media| media.content_id.clone())))
+    }
 
-        info!("Media loaded on Chromecast");
-        Ok(())
+    /// Load `items` as a receiver queue so the default media receiver plays
+    /// them back to back. Fails if the receiver doesn't start the first item,
+    /// which is how receivers without queue support respond to QUEUE_LOAD.
+    pub async fn load_queue(&self, items: Vec<QueueItem>) -> Result<()> {
+        let first = items
+            .first()
+            .map(|item| item.url.clone())
+            .ok_or_else(|| WhenThenError::InvalidInput("The queue is empty".into()))?;
+        let (tid, sid) = self.app_ids().await?;
+
+        // rust_cast has no typed queue messages
+        let load = cast_channel::queue_load(self.sender.request_id(), &sid, &items);
+        self.sender.send(&tid, &[(cast_channel::NS_MEDIA, load)]).await?;
+
+        for _ in 0..QUEUE_LOAD_POLLS {
+            tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+            let loaded = self
+                .current_media(&tid)
+                .await
+                .is_some_and(|(_, content_id)| content_id.as_deref() == Some(first.as_str()));
+            if loaded {
+                info!("Queue of {} items loaded on Chromecast", items.len());
+                *self.queue.lock().await = items;
+                return Ok(());
+            }
+        }
+        Err(WhenThenError::CastPlayback("The receiver didn't start the queue".into()))
+    }
+
+    pub async fn queue_next(&self) -> Result<()> {
+        self.queue_jump(1).await
+    }
+
+    pub async fn queue_prev(&self) -> Result<()> {
+        self.queue_jump(-1).await
+    }
+
+    async fn queue_jump(&self, jump: i32) -> Result<()> {
+        let (tid, _) = self.app_ids().await?;
+        let (media_session_id, _) = self
+            .current_media(&tid)
+            .await
+            .ok_or_else(|| WhenThenError::CastPlayback("No active media session".into()))?;
+        let update = cast_channel::queue_jump(self.sender.request_id(), media_session_id, jump);
+        self.sender.send(&tid, &[(cast_channel::NS_MEDIA, update)]).await
+    }
+
+    /// Show or hide the side-loaded subtitle track.
+    pub async fn set_subtitle_enabled(&self, enabled: bool) -> Result<()> {
+        let (tid, _) = self.app_ids().await?;
+        let (media_session_id, _) = self
+            .current_media(&tid)
+            .await
+            .ok_or_else(|| WhenThenError::CastPlayback("No active media session".into()))?;
+        let edit = cast_channel::edit_tracks_info(self.sender.request_id(), media_session_id, enabled);
+        self.sender.send(&tid, &[(cast_channel::NS_MEDIA, edit)]).await
+    }
+
+    /// Turn the subtitle track off and on so the receiver fetches it again.
+    pub async fn reload_subtitles(&self) -> Result<()> {
+        let (tid, _) = self.app_ids().await?;
+        let Some((media_session_id, _)) = self.current_media(&tid).await else {
+            return Ok(());
+        };
+        let messages = [
+            (cast_channel::NS_MEDIA, cast_channel::edit_tracks_info(self.sender.request_id(), media_session_id, false)),
+            (cast_channel::NS_MEDIA, cast_channel::edit_tracks_info(self.sender.request_id(), media_session_id, true)),
+        ];
+        self.sender.send(&tid, &messages).await
     }
 
     pub async fn play(&self) -> Result<()> {
@@ -259,7 +496,6 @@ impl ChromecastConnection {
             .as_ref()
             .ok_or_else(|| WhenThenError::CastConnection("Not connected".into()))?;
 
-        use rust_cast::channels::receiver::Volume;
         dev.receiver.set_volume(Volume {
             level: Some(level as f32),
             muted: None,
@@ -269,55 +505,232 @@ impl ChromecastConnection {
         Ok(())
     }
 
-    pub async fn get_status(&self) -> Result<PlaybackStatusResponse> {
+    pub async fn set_muted(&self, muted: bool) -> Result<()> {
         let dev = self.device.lock().await;
         let dev = dev
             .as_ref()
             .ok_or_else(|| WhenThenError::CastConnection("Not connected".into()))?;
-        let tid = self.transport_id.lock().await;
-        let tid = tid
-            .as_ref()
-            .ok_or_else(|| WhenThenError::CastConnection("No transport".into()))?;
-
-        let status = dev.media.get_status(tid.as_str(), None)
-            .map_err(|e| WhenThenError::CastPlayback(format!("Get status: {e}")))?;
 
-        let device_id = self.device_id.clone();
-
-        let response = if let Some(entry) = status.entries.first() {
-            let state = match entry.player_state {
-                rust_cast::channels::media::PlayerState::Playing => PlaybackState::Playing,
-                rust_cast::channels::media::PlayerState::Paused => PlaybackState::Paused,
-                rust_cast::channels::media::PlayerState::Buffering => PlaybackState::Buffering,
-                _ => PlaybackState::Idle,
-            };
+        dev.receiver.set_volume(Volume {
+            level: None,
+            muted: Some(muted),
+        })
+        .map_err(|e| WhenThenError::CastPlayback(format!("Set muted: {e}")))?;
 
-            PlaybackStatusResponse {
-                device_id,
-                state,
-                current_time: entry.current_time.unwrap_or(0.0) as f64,
-                duration: entry.media.as_ref().and_then(|m| m.duration).map(|d| d as f64).unwrap_or(0.0),
-                volume: 1.0,
-                is_muted: false,
-                media_title: None,
-                content_type: entry.media.as_ref().map(|m| m.content_type.clone()),
-            }
-        } else {
-            PlaybackStatusResponse {
-                device_id,
-                ..Default::default()
-            }
-        };
+        Ok(())
+    }
 
-        Ok(response)
+    pub async fn get_status(&self) -> Result<PlaybackStatusResponse> {
+        query_status(&self.device, &self.transport_id, &self.device_id).await
     }
 
     pub async fn disconnect(&self) {
         if let Some(tx) = self.heartbeat_shutdown.lock().await.take() {
             let _ = tx.send(());
         }
+        if let Some(tx) = self.status_shutdown.lock().await.take() {
+            let _ = tx.send(());
+        }
+        self.sender.close().await;
         let mut dev = self.device.lock().await;
         *dev = None;
         info!("Disconnected from Chromecast: {}", self.device_name);
     }
 }
+
+/// Swap a dropped device's connection for a Reconnecting entry and start
+/// retrying. Playback commands fail with CastReconnecting meanwhile.
+async fn begin_reconnect(
+    app_handle: tauri::AppHandle,
+    device_id: String,
+    device_name: String,
+    address: String,
+    port: u16,
+    reason: String,
+) {
+    let state = app_handle.state::<AppState>();
+    let mut connections = state.active_connections.lock().await;
+    // Already disconnected by the user, or replaced by a new connection
+    if !matches!(connections.get(&device_id), Some(DeviceConnection::Chromecast(_))) {
+        return;
+    }
+
+    let task = tokio::spawn(reconnect(app_handle.clone(), device_id.clone(), device_name.clone(), address, port));
+    let dropped = connections.insert(
+        device_id.clone(),
+        DeviceConnection::Reconnecting(Reconnecting { device_name: device_name.clone(), task }),
+    );
+    drop(connections);
+    if let Some(dropped) = dropped {
+        dropped.disconnect().await;
+    }
+
+    info!("Reconnecting to Chromecast: {}", device_name);
+    let _ = tauri::Emitter::emit(&app_handle, "chromecast:reconnecting", DeviceEvent {
+        id: device_id,
+        name: device_name,
+        reason: Some(reason),
+    });
+}
+
+#[derive(serde::Serialize, Clone)]
+struct DeviceEvent {
+    id: String,
+    name: String,
+    reason: Option<String>,
+}
+
+/// Retry with backoff, launching the media receiver again on success. Emits
+/// chromecast:reconnected, or chromecast:gone after the last attempt.
+///
+/// Boxed because the new connection's heartbeat can start another reconnect,
+/// which would make the future's type contain itself.
+fn reconnect(
+    app_handle: tauri::AppHandle,
+    device_id: String,
+    device_name: String,
+    address: String,
+    port: u16,
+) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+    Box::pin(async move {
+        let state = app_handle.state::<AppState>();
+        let mut delay = RECONNECT_FIRST_DELAY;
+        for attempt in 1..=RECONNECT_ATTEMPTS {
+            tokio::time::sleep(delay).await;
+            delay *= 2;
+
+            // A rebooted TV may come back on a new address
+            let (address, port) = match state.discovered_devices.read().await.get(&device_id) {
+                Some(found) => (found.address.clone(), found.port),
+                None => (address.clone(), port),
+            };
+            let connection = match ChromecastConnection::connect(
+                device_id.clone(),
+                device_name.clone(),
+                address,
+                port,
+                Some(app_handle.clone()),
+            )
+            .await
+            {
+                Ok(connection) => connection,
+                Err(e) => {
+                    warn!(attempt, "Reconnect to {} failed: {}", device_name, e);
+                    continue;
+                }
+            };
+
+            let mut connections = state.active_connections.lock().await;
+            if !connections.get(&device_id).is_some_and(DeviceConnection::is_reconnecting) {
+                drop(connections);
+                connection.disconnect().await;
+                return;
+            }
+            connections.insert(device_id.clone(), DeviceConnection::Chromecast(connection));
+            drop(connections);
+
+            info!(attempt, "Reconnected to Chromecast: {}", device_name);
+            let _ = tauri::Emitter::emit(&app_handle, "chromecast:reconnected", DeviceEvent {
+                id: device_id,
+                name: device_name,
+                reason: None,
+            });
+            return;
+        }
+
+        let mut connections = state.active_connections.lock().await;
+        if connections.get(&device_id).is_some_and(DeviceConnection::is_reconnecting) {
+            connections.remove(&device_id);
+        }
+        drop(connections);
+
+        warn!("Giving up on Chromecast {} after {} attempts", device_name, RECONNECT_ATTEMPTS);
+        let _ = tauri::Emitter::emit(&app_handle, "chromecast:gone", DeviceEvent {
+            id: device_id,
+            name: device_name,
+            reason: Some(format!("No answer after {RECONNECT_ATTEMPTS} reconnect attempts")),
+        });
+    })
+}
+
+/// Current media status. Shared by get_status and the status watcher.
+async fn query_status(
+    device: &Mutex<Option<CastDevice<'static>>>,
+    transport_id: &Mutex<Option<String>>,
+    device_id: &str,
+) -> Result<PlaybackStatusResponse> {
+    query_media(device, transport_id, device_id).await.map(|(status, _)| status)
+}
+
+/// Media status plus the content id of what's loaded.
+async fn query_media(
+    device: &Mutex<Option<CastDevice<'static>>>,
+    transport_id: &Mutex<Option<String>>,
+    device_id: &str,
+) -> Result<(PlaybackStatusResponse, Option<String>)> {
+    let dev = device.lock().await;
+    let dev = dev
+        .as_ref()
+        .ok_or_else(|| WhenThenError::CastConnection("Not connected".into()))?;
+    let tid = transport_id.lock().await;
+    let tid = tid
+        .as_ref()
+        .ok_or_else(|| WhenThenError::CastConnection("No transport".into()))?;
+
+    // Volume lives on the receiver, not the media session, so it's known even before anything loads
+    let volume = dev.receiver.get_status()
+        .map_err(|e| WhenThenError::CastPlayback(format!("Get receiver status: {e}")))?
+        .volume;
+    let volume_level = volume.level.map(|l| l as f64).unwrap_or(1.0);
+    let is_muted = volume.muted.unwrap_or(false);
+
+    let status = dev.media.get_status(tid.as_str(), None)
+        .map_err(|e| WhenThenError::CastPlayback(format!("Get status: {e}")))?;
+
+    let device_id = device_id.to_string();
+    let content_id = status
+        .entries
+        .first()
+        .and_then(|entry| entry.media.as_ref())
+        .map(|media| media.content_id.clone());
+
+    let response = if let Some(entry) = status.entries.first() {
+        let state = match entry.player_state {
+            rust_cast::channels::media::PlayerState::Playing => PlaybackState::Playing,
+            rust_cast::channels::media::PlayerState::Paused => PlaybackState::Paused,
+            rust_cast::channels::media::PlayerState::Buffering => PlaybackState::Buffering,
+            _ => PlaybackState::Idle,
+        };
+
+        PlaybackStatusResponse {
+            device_id,
+            state,
+            current_time: entry.current_time.unwrap_or(0.0) as f64,
+            duration: entry.media.as_ref().and_then(|m| m.duration).map(|d| d as f64).unwrap_or(0.0),
+            volume: volume_level,
+            is_muted,
+            media_title: entry.media.as_ref().and_then(|m| m.metadata.as_ref()).and_then(metadata_title),
+            content_type: entry.media.as_ref().map(|m| m.content_type.clone()),
+            max_drift_secs: None,
+        }
+    } else {
+        PlaybackStatusResponse {
+            device_id,
+            volume: volume_level,
+            is_muted,
+            ..Default::default()
+        }
+    };
+
+    Ok((response, content_id))
+}
+
+fn metadata_title(metadata: &Metadata) -> Option<String> {
+    match metadata {
+        Metadata::Generic(m) => m.title.clone(),
+        Metadata::Movie(m) => m.title.clone(),
+        Metadata::TvShow(m) => m.episode_title.clone().or_else(|| m.series_title.clone()),
+        Metadata::MusicTrack(m) => m.title.clone(),
+        Metadata::Photo(m) => m.title.clone(),
+    }
+}
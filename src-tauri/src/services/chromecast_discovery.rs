@@ -5,14 +5,20 @@ use tauri::{AppHandle, Emitter};
 use tokio::sync::RwLock;
 use tracing::{info, warn, error};
 
-use crate::models::DiscoveredDevice;
+use crate::models::{DeviceProtocol, DiscoveredDevice};
 
 const CHROMECAST_SERVICE: &str = "_googlecast._tcp.local.";
+const AIRPLAY_SERVICE: &str = "_airplay._tcp.local.";
+
+const SERVICES: &[(&str, DeviceProtocol)] = &[
+    (CHROMECAST_SERVICE, DeviceProtocol::Chromecast),
+    (AIRPLAY_SERVICE, DeviceProtocol::AirPlay),
+];
 
 pub async fn start_discovery(
     app_handle: AppHandle,
     discovered_devices: Arc<RwLock<HashMap<String, DiscoveredDevice>>>,
-    mut shutdown_rx: tokio::sync::oneshot::Receiver<()>,
+    shutdown_rx: tokio::sync::oneshot::Receiver<()>,
 ) {
     let mdns = match ServiceDaemon::new() {
         Ok(d) => d,
@@ -22,24 +28,54 @@ pub async fn start_discovery(
         }
     };
 
-    let receiver = match mdns.browse(CHROMECAST_SERVICE) {
-        Ok(r) => r,
-        Err(e) => {
-            error!("Failed to browse for Chromecast: {}", e);
-            return;
-        }
-    };
+    // One browse loop per service type so a blocking recv on one never eats the other's events
+    let mut stops = Vec::new();
+    for (service, protocol) in SERVICES {
+        let receiver = match mdns.browse(service) {
+            Ok(r) => r,
+            Err(e) => {
+                error!("Failed to browse for {}: {}", service, e);
+                continue;
+            }
+        };
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        stops.push(tx);
+        tokio::spawn(browse(
+            receiver,
+            *protocol,
+            app_handle.clone(),
+            discovered_devices.clone(),
+            rx,
+        ));
+    }
+    if stops.is_empty() {
+        let _ = mdns.shutdown();
+        return;
+    }
 
-    info!("Started Chromecast discovery");
+    info!("Started device discovery");
+    let _ = shutdown_rx.await;
 
+    info!("Stopping device discovery");
+    for tx in stops {
+        let _ = tx.send(());
+    }
+    for (service, _) in SERVICES {
+        let _ = mdns.stop_browse(service);
+    }
+    let _ = mdns.shutdown();
+}
+
+async fn browse(
+    receiver: mdns_sd::Receiver<ServiceEvent>,
+    protocol: DeviceProtocol,
+    app_handle: AppHandle,
+    discovered_devices: Arc<RwLock<HashMap<String, DiscoveredDevice>>>,
+    mut shutdown_rx: tokio::sync::oneshot::Receiver<()>,
+) {
     loop {
         tokio::select! {
-            _ = &mut shutdown_rx => {
-                info!("Stopping Chromecast discovery");
-                let _ = mdns.stop_browse(CHROMECAST_SERVICE);
-                let _ = mdns.shutdown();
-                break;
-            }
+            _ = &mut shutdown_rx => break,
             event = tokio::task::spawn_blocking({
                 let receiver = receiver.clone();
                 move || receiver.recv()
@@ -48,6 +84,7 @@ pub async fn start_discovery(
                     Ok(Ok(service_event)) => {
                         handle_service_event(
                             service_event,
+                            protocol,
                             &app_handle,
                             &discovered_devices,
                         ).await;
@@ -68,6 +105,7 @@ pub async fn start_discovery(
 
 async fn handle_service_event(
     event: ServiceEvent,
+    protocol: DeviceProtocol,
     app_handle: &AppHandle,
     discovered_devices: &Arc<RwLock<HashMap<String, DiscoveredDevice>>>,
 ) {
@@ -82,14 +120,20 @@ async fn handle_service_event(
             let port = info.get_port();
 
             let properties = info.get_properties();
-            let friendly_name = properties
-                .get_property_val_str("fn")
-                .unwrap_or("Chromecast")
-                .to_string();
-            let model = properties
-                .get_property_val_str("md")
-                .unwrap_or("Unknown")
-                .to_string();
+            let (friendly_name, model) = match protocol {
+                DeviceProtocol::Chromecast => (
+                    properties.get_property_val_str("fn").unwrap_or("Chromecast").to_string(),
+                    properties.get_property_val_str("md").unwrap_or("Unknown").to_string(),
+                ),
+                // AirPlay receivers put their name in the instance name, not the TXT record
+                DeviceProtocol::AirPlay => (
+                    info.get_fullname()
+                        .strip_suffix(&format!(".{AIRPLAY_SERVICE}"))
+                        .unwrap_or("AirPlay")
+                        .to_string(),
+                    properties.get_property_val_str("model").unwrap_or("Unknown").to_string(),
+                ),
+            };
 
             let id = format!("{}:{}", address, port);
 
@@ -99,9 +143,10 @@ async fn handle_service_event(
                 model: model.clone(),
                 address: address.clone(),
                 port,
+                protocol,
             };
 
-            info!("Chromecast found: {} ({}) at {}:{}", friendly_name, model, address, port);
+            info!("{:?} device found: {} ({}) at {}:{}", protocol, friendly_name, model, address, port);
 
             discovered_devices.write().await.insert(id.clone(), device);
 
@@ -112,6 +157,7 @@ async fn handle_service_event(
                 model: String,
                 address: String,
                 port: u16,
+                protocol: DeviceProtocol,
             }
 
             app_handle
@@ -123,6 +169,7 @@ async fn handle_service_event(
                         model,
                         address,
                         port,
+                        protocol,
                     },
                 )
                 .unwrap_or_default();
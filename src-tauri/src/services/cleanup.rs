@@ -0,0 +1,175 @@
+// Daily removal of torrents that have been seeding for longer than
+// remove_completed_after_days, so a long-running session doesn't pile up
+// hundreds of finished torrents.
+//
+// The clock starts at the completion time in completed_history. Torrents
+// labeled "keep" are never removed. Data is only deleted where the app owns
+// it: inside the download or incomplete directory, and never for torrents
+// created from the user's own files or imported from another client.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use tauri::{AppHandle, Emitter, Manager};
+use tracing::{info, warn};
+
+use crate::models::AutoRemovedTorrent;
+use crate::services::torrent_engine::{self, expand_path};
+use crate::services::torrent_outputs;
+use crate::state::AppState;
+
+const CLEANUP_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Label that exempts a torrent from cleanup, compared case-insensitively.
+pub const KEEP_LABEL: &str = "keep";
+
+/// Run the cleanup once a day for as long as the app runs, the first time
+/// right away.
+pub fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(CLEANUP_INTERVAL);
+        loop {
+            interval.tick().await;
+            run(&app).await;
+        }
+    });
+}
+
+/// Whether a torrent that finished at `completed_at` has seeded for `days`.
+pub fn is_due(completed_at: &str, days: u32, now: DateTime<Utc>) -> bool {
+    days > 0
+        && DateTime::parse_from_rfc3339(completed_at)
+            .is_ok_and(|at| now - at.with_timezone(&Utc) >= chrono::Duration::days(days.into()))
+}
+
+/// Whether the app can delete data in `folder`: the torrent was downloaded
+/// by the app, not created or imported, and its data sits under a folder the
+/// app downloads to, wherever it was first added or since moved.
+pub fn owns_data(folder: &Path, external: bool, app_dirs: &[PathBuf]) -> bool {
+    !external && app_dirs.iter().any(|dir| folder.starts_with(dir))
+}
+
+/// Remove every torrent that's due and emit torrents:auto-removed with them.
+pub async fn run(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    let (days, delete_data, app_dirs) = {
+        let cfg = state.config.read().await;
+        let mut dirs = vec![expand_path(&cfg.download_directory)];
+        if !cfg.incomplete_directory.is_empty() {
+            dirs.push(expand_path(&cfg.incomplete_directory));
+        }
+        (cfg.remove_completed_after_days, cfg.remove_completed_delete_data, dirs)
+    };
+    if days == 0 {
+        return;
+    }
+    let Some(session) = state.torrent_session.read().await.clone() else {
+        return;
+    };
+
+    let now = Utc::now();
+    let finished: Vec<(usize, String, String)> = session.with_torrents(|torrents| {
+        torrents
+            .filter(|(_, handle)| handle.stats().finished)
+            .map(|(id, handle)| (id, handle.info_hash().as_string(), handle.name().unwrap_or_default()))
+            .collect()
+    });
+
+    let mut removed = Vec::new();
+    for (internal_id, info_hash, name) in finished {
+        let due = state
+            .completed_history
+            .read()
+            .await
+            .completed_at(&info_hash)
+            .is_some_and(|at| is_due(at, days, now));
+        let keep = state
+            .torrent_labels
+            .read()
+            .await
+            .get(&info_hash)
+            .is_some_and(|label| label.eq_ignore_ascii_case(KEEP_LABEL));
+        if !due || keep {
+            continue;
+        }
+
+        let id = state.torrent_ids.read().await.external(internal_id);
+        let shown = state.torrent_names.read().await.get(&internal_id).cloned().unwrap_or_else(|| name.clone());
+        // Look up the data before the torrent and its recorded folder are forgotten
+        let folder = torrent_outputs::folder(&state, &info_hash).await;
+        let external = state.torrent_outputs.read().await.is_external(&info_hash);
+        let data = (delete_data && !name.is_empty() && owns_data(&folder, external, &app_dirs))
+            .then(|| folder.join(&name));
+        if delete_data && data.is_none() {
+            info!(info_hash = %info_hash, folder = %folder.display(), "Keeping data the app didn't download");
+        }
+
+        // librqbit only knows where the data was first downloaded, so the
+        // recorded folder is deleted here instead
+        if let Err(e) = torrent_engine::delete_torrent(&state, app, id, false).await {
+            warn!(id, error = %e, "Cleanup failed to remove torrent");
+            continue;
+        }
+        let data_deleted = data.is_some_and(|path| remove_data(&path));
+        info!(id, name = %shown, data_deleted, "Removed finished torrent after {} days", days);
+        removed.push(AutoRemovedTorrent { id, name: shown, info_hash, data_deleted });
+    }
+
+    if !removed.is_empty() {
+        let _ = app.emit("torrents:auto-removed", &removed);
+        let _ = app.emit("torrents:changed", ());
+    }
+}
+
+/// Delete a torrent's file or folder. True if something was deleted.
+fn remove_data(path: &Path) -> bool {
+    let result = if path.is_dir() {
+        std::fs::remove_dir_all(path)
+    } else if path.is_file() {
+        std::fs::remove_file(path)
+    } else {
+        return false;
+    };
+    match result {
+        Ok(()) => true,
+        Err(e) => {
+            warn!(path = %path.display(), error = %e, "Failed to delete torrent data");
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn due_after_the_seeding_days_and_only_owned_data_goes() {
+        let now = DateTime::parse_from_rfc3339("2026-03-10T12:00:00+00:00").unwrap().with_timezone(&Utc);
+        assert!(is_due("2026-03-03T12:00:00+00:00", 7, now));
+        assert!(!is_due("2026-03-04T12:00:00+00:00", 7, now));
+        assert!(!is_due("2026-01-01T00:00:00+00:00", 0, now), "0 turns cleanup off");
+        assert!(!is_due("yesterday", 1, now));
+
+        let dirs = [PathBuf::from("/data/downloads"), PathBuf::from("/data/incomplete")];
+        assert!(owns_data(Path::new("/data/downloads"), false, &dirs));
+        assert!(owns_data(Path::new("/data/incomplete/shows"), false, &dirs));
+        assert!(!owns_data(Path::new("/media/library"), false, &dirs), "moved out of the app's folders");
+    }
+
+    #[test]
+    fn created_and_imported_torrents_keep_their_data() {
+        let dirs = [PathBuf::from("/data/downloads")];
+        let mut outputs = torrent_outputs::TorrentOutputs::default();
+        // Created from ~/Videos/movie.mkv, seeding from ~/Videos
+        assert!(outputs.mark_external("created"));
+        // Imported from another client that saved into the download directory
+        assert!(outputs.mark_external("imported"));
+        assert!(!outputs.mark_external("imported"));
+
+        assert!(!owns_data(Path::new("/home/me/Videos"), outputs.is_external("created"), &dirs));
+        assert!(!owns_data(Path::new("/data/downloads/qbittorrent"), outputs.is_external("imported"), &dirs));
+        assert!(owns_data(Path::new("/data/downloads"), outputs.is_external("downloaded"), &dirs));
+    }
+}
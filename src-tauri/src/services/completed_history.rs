@@ -0,0 +1,193 @@
+// Finished downloads and when they finished, keyed by info hash. Feeds the
+// history view and /feed/completed.xml, which lets another machine follow
+// what this one has downloaded. The feed has its own token, saved with the
+// history, so a subscribed URL keeps working across restarts.
+
+use std::collections::HashMap;
+
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
+use tracing::{info, warn};
+
+use crate::models::{CompletedEntry, CompletedPage};
+use crate::state::AppState;
+
+const HISTORY_STORE: &str = "completed_history.json";
+
+/// Oldest entries are dropped past this many.
+const MAX_ENTRIES: usize = 1000;
+
+#[derive(Debug, Default)]
+pub struct CompletedHistory {
+    entries: HashMap<String, CompletedEntry>,
+    /// Token for /feed/completed.xml; empty until loaded
+    feed_token: String,
+}
+
+impl CompletedHistory {
+    pub fn feed_token(&self) -> &str {
+        &self.feed_token
+    }
+
+    /// Create the feed token if there isn't one yet. Returns true if it did.
+    pub fn ensure_feed_token(&mut self) -> bool {
+        if !self.feed_token.is_empty() {
+            return false;
+        }
+        self.feed_token = uuid::Uuid::new_v4().simple().to_string();
+        true
+    }
+
+    /// Add or replace the entry for its info hash.
+    pub fn record(&mut self, entry: CompletedEntry) {
+        self.entries.insert(entry.info_hash.clone(), entry);
+        if self.entries.len() > MAX_ENTRIES {
+            let oldest = self
+                .entries
+                .values()
+                .min_by(|a, b| a.completed_at.cmp(&b.completed_at))
+                .map(|e| e.info_hash.clone());
+            if let Some(hash) = oldest {
+                self.entries.remove(&hash);
+            }
+        }
+    }
+
+    /// When a torrent finished downloading (RFC 3339), if it's on record.
+    pub fn completed_at(&self, info_hash: &str) -> Option<&str> {
+        self.entries.get(info_hash).map(|e| e.completed_at.as_str())
+    }
+
+    /// `limit` entries after skipping `offset`, newest first.
+    pub fn page(&self, offset: usize, limit: usize) -> CompletedPage {
+        let mut items: Vec<&CompletedEntry> = self.entries.values().collect();
+        // RFC 3339 timestamps in UTC sort as strings
+        items.sort_by(|a, b| b.completed_at.cmp(&a.completed_at));
+        CompletedPage {
+            total: items.len(),
+            items: items.into_iter().skip(offset).take(limit).cloned().collect(),
+        }
+    }
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// RSS 2.0 feed of `entries`. `enclosure` gives the playlist URL of a
+/// torrent still in the session.
+pub fn render_feed(
+    entries: &[CompletedEntry],
+    link: &str,
+    enclosure: impl Fn(&CompletedEntry) -> Option<String>,
+) -> String {
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<rss version=\"2.0\">\n<channel>\n");
+    xml.push_str("<title>When: completed downloads</title>\n");
+    xml.push_str(&format!("<link>{}</link>\n", xml_escape(link)));
+    xml.push_str("<description>Torrents this machine finished downloading</description>\n");
+    for entry in entries {
+        xml.push_str("<item>\n");
+        xml.push_str(&format!("<title>{}</title>\n", xml_escape(&entry.name)));
+        if let Ok(at) = chrono::DateTime::parse_from_rfc3339(&entry.completed_at) {
+            xml.push_str(&format!("<pubDate>{}</pubDate>\n", at.to_rfc2822()));
+        }
+        xml.push_str(&format!("<guid isPermaLink=\"false\">{}</guid>\n", entry.info_hash));
+        if let Some(url) = enclosure(entry) {
+            xml.push_str(&format!(
+                "<enclosure url=\"{}\" length=\"0\" type=\"application/x-mpegURL\"/>\n",
+                xml_escape(&url)
+            ));
+        }
+        xml.push_str("</item>\n");
+    }
+    xml.push_str("</channel>\n</rss>\n");
+    xml
+}
+
+pub async fn persist(app: &AppHandle, state: &AppState) {
+    if let Ok(store) = app.store(HISTORY_STORE) {
+        let history = state.completed_history.read().await;
+        if let Ok(value) = serde_json::to_value(&history.entries) {
+            store.set("entries", value);
+            store.set("feed_token", history.feed_token.clone());
+            if let Err(e) = store.save() {
+                warn!("Failed to save completed history: {}", e);
+            }
+        }
+    }
+}
+
+pub async fn load(app: &AppHandle, state: &AppState) {
+    if let Ok(store) = app.store(HISTORY_STORE) {
+        if let Err(e) = store.reload() {
+            warn!("Could not load completed history store: {}", e);
+        }
+        if let Some(value) = store.get("entries") {
+            if let Ok(entries) = serde_json::from_value::<HashMap<String, CompletedEntry>>(value) {
+                info!("Loaded {} completed downloads from disk", entries.len());
+                state.completed_history.write().await.entries = entries;
+            }
+        }
+        if let Some(token) = store.get("feed_token").and_then(|v| v.as_str().map(str::to_string)) {
+            state.completed_history.write().await.feed_token = token;
+        }
+    }
+    let created = state.completed_history.write().await.ensure_feed_token();
+    if created {
+        persist(app, state).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(hash: &str, name: &str, at: &str) -> CompletedEntry {
+        CompletedEntry {
+            info_hash: hash.into(),
+            name: name.into(),
+            total_bytes: 1024,
+            completed_at: at.into(),
+        }
+    }
+
+    #[test]
+    fn feed_token_is_made_once() {
+        let mut history = CompletedHistory::default();
+        assert!(history.ensure_feed_token());
+        let token = history.feed_token().to_string();
+        assert_eq!(token.len(), 32);
+        assert!(!history.ensure_feed_token());
+        assert_eq!(history.feed_token(), token);
+    }
+
+    #[test]
+    fn pages_are_newest_first() {
+        let mut history = CompletedHistory::default();
+        history.record(entry("a", "First", "2026-01-01T10:00:00+00:00"));
+        history.record(entry("b", "Second", "2026-01-02T10:00:00+00:00"));
+        history.record(entry("c", "Third", "2026-01-03T10:00:00+00:00"));
+
+        let page = history.page(1, 5);
+        assert_eq!(page.total, 3);
+        let names: Vec<_> = page.items.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, ["Second", "First"]);
+    }
+
+    #[test]
+    fn feed_is_escaped_rss() {
+        let entries = [entry("abc", "Tom & Jerry <1080p>", "2026-01-02T10:00:00+00:00")];
+        let xml = render_feed(&entries, "http://host:9080/", |e| {
+            Some(format!("http://host:9080/torrent/3/playlist.m3u8?token=t&x={}", e.info_hash))
+        });
+        assert!(xml.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<rss version=\"2.0\">"));
+        assert!(xml.contains("<title>Tom &amp; Jerry &lt;1080p&gt;</title>"));
+        assert!(xml.contains("<pubDate>Fri, ") && xml.contains(" Jan 2026 10:00:00 +0000</pubDate>"));
+        assert!(xml.contains("<guid isPermaLink=\"false\">abc</guid>"));
+        assert!(xml.contains("url=\"http://host:9080/torrent/3/playlist.m3u8?token=t&amp;x=abc\""));
+    }
+}
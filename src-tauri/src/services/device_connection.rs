@@ -0,0 +1,142 @@
+// A connected cast target of either protocol, so playback commands don't need
+// to know which one they're talking to. A Chromecast that stops answering
+// heartbeats stays here as Reconnecting until it comes back or gives up.
+
+use crate::errors::{Result, WhenThenError};
+use crate::models::{PlaybackStatusResponse, QueueItem};
+use crate::services::airplay_device::AirPlayConnection;
+use crate::services::chromecast_device::ChromecastConnection;
+
+pub enum DeviceConnection {
+    Chromecast(ChromecastConnection),
+    AirPlay(AirPlayConnection),
+    Reconnecting(Reconnecting),
+}
+
+/// A dropped device with its retry task running.
+pub struct Reconnecting {
+    pub device_name: String,
+    pub task: tokio::task::JoinHandle<()>,
+}
+
+fn unsupported(what: &str) -> WhenThenError {
+    WhenThenError::CastPlayback(format!("{what} isn't supported on AirPlay receivers"))
+}
+
+fn reconnecting(r: &Reconnecting) -> WhenThenError {
+    WhenThenError::CastReconnecting(r.device_name.clone())
+}
+
+impl DeviceConnection {
+    pub async fn load_media(&self, url: String, content_type: String, subtitle_url: Option<String>) -> Result<()> {
+        match self {
+            Self::Chromecast(c) => c.load_media(url, content_type, subtitle_url).await,
+            Self::AirPlay(a) => a.load_media(url, content_type, subtitle_url).await,
+            Self::Reconnecting(r) => Err(reconnecting(r)),
+        }
+    }
+
+    pub async fn load_queue(&self, items: Vec<QueueItem>) -> Result<()> {
+        match self {
+            Self::Chromecast(c) => c.load_queue(items).await,
+            Self::AirPlay(_) => Err(unsupported("Queued playback")),
+            Self::Reconnecting(r) => Err(reconnecting(r)),
+        }
+    }
+
+    /// Skip to the next queued item, or back to the previous one.
+    pub async fn queue_jump(&self, jump: i32) -> Result<()> {
+        match self {
+            Self::Chromecast(c) if jump < 0 => c.queue_prev().await,
+            Self::Chromecast(c) => c.queue_next().await,
+            Self::AirPlay(_) => Err(unsupported("Queued playback")),
+            Self::Reconnecting(r) => Err(reconnecting(r)),
+        }
+    }
+
+    pub async fn play(&self) -> Result<()> {
+        match self {
+            Self::Chromecast(c) => c.play().await,
+            Self::AirPlay(a) => a.play().await,
+            Self::Reconnecting(r) => Err(reconnecting(r)),
+        }
+    }
+
+    pub async fn pause(&self) -> Result<()> {
+        match self {
+            Self::Chromecast(c) => c.pause().await,
+            Self::AirPlay(a) => a.pause().await,
+            Self::Reconnecting(r) => Err(reconnecting(r)),
+        }
+    }
+
+    pub async fn stop(&self) -> Result<()> {
+        match self {
+            Self::Chromecast(c) => c.stop().await,
+            Self::AirPlay(a) => a.stop().await,
+            Self::Reconnecting(r) => Err(reconnecting(r)),
+        }
+    }
+
+    pub async fn seek(&self, position: f64) -> Result<()> {
+        match self {
+            Self::Chromecast(c) => c.seek(position).await,
+            Self::AirPlay(a) => a.seek(position).await,
+            Self::Reconnecting(r) => Err(reconnecting(r)),
+        }
+    }
+
+    pub async fn set_volume(&self, level: f64) -> Result<()> {
+        match self {
+            Self::Chromecast(c) => c.set_volume(level).await,
+            Self::AirPlay(_) => Err(unsupported("Volume control")),
+            Self::Reconnecting(r) => Err(reconnecting(r)),
+        }
+    }
+
+    pub async fn set_muted(&self, muted: bool) -> Result<()> {
+        match self {
+            Self::Chromecast(c) => c.set_muted(muted).await,
+            Self::AirPlay(_) => Err(unsupported("Muting")),
+            Self::Reconnecting(r) => Err(reconnecting(r)),
+        }
+    }
+
+    pub async fn set_subtitle_enabled(&self, enabled: bool) -> Result<()> {
+        match self {
+            Self::Chromecast(c) => c.set_subtitle_enabled(enabled).await,
+            Self::AirPlay(_) => Err(unsupported("Subtitles")),
+            Self::Reconnecting(r) => Err(reconnecting(r)),
+        }
+    }
+
+    pub async fn get_status(&self) -> Result<PlaybackStatusResponse> {
+        match self {
+            Self::Chromecast(c) => c.get_status().await,
+            Self::AirPlay(a) => a.get_status().await,
+            Self::Reconnecting(r) => Err(reconnecting(r)),
+        }
+    }
+
+    /// Also stops a reconnect in progress.
+    pub async fn disconnect(&self) {
+        match self {
+            Self::Chromecast(c) => c.disconnect().await,
+            Self::AirPlay(a) => a.disconnect().await,
+            Self::Reconnecting(r) => r.task.abort(),
+        }
+    }
+
+    pub fn is_reconnecting(&self) -> bool {
+        matches!(self, Self::Reconnecting(_))
+    }
+
+    /// A handle on a Chromecast connection that outlives the connections map
+    /// lock, for calls that wait on the receiver.
+    pub fn chromecast(&self) -> Option<ChromecastConnection> {
+        match self {
+            Self::Chromecast(c) => Some(c.clone()),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,67 @@
+// Names the user gave torrents, shown instead of the name in their metadata.
+// Only the display changes; files on disk keep their names.
+//
+// Keyed by info hash, like labels, so a name survives re-adds and restarts.
+
+use std::collections::HashMap;
+
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
+use tracing::{info, warn};
+
+use crate::state::AppState;
+
+const NAMES_STORE: &str = "torrent_names.json";
+
+#[derive(Debug, Default)]
+pub struct DisplayNames {
+    by_hash: HashMap<String, String>,
+}
+
+impl DisplayNames {
+    pub fn get(&self, info_hash: &str) -> Option<String> {
+        self.by_hash.get(info_hash).cloned()
+    }
+
+    /// Set or, with None, clear a torrent's name. Returns true if it changed.
+    pub fn set(&mut self, info_hash: &str, name: Option<String>) -> bool {
+        match name {
+            Some(name) => self.by_hash.insert(info_hash.to_string(), name.clone()) != Some(name),
+            None => self.by_hash.remove(info_hash).is_some(),
+        }
+    }
+}
+
+pub async fn persist(app: &AppHandle, state: &AppState) {
+    if let Ok(store) = app.store(NAMES_STORE) {
+        let names = state.display_names.read().await;
+        if let Ok(value) = serde_json::to_value(&names.by_hash) {
+            store.set("names", value);
+            if let Err(e) = store.save() {
+                warn!("Failed to save torrent names: {}", e);
+            }
+        }
+    }
+}
+
+pub async fn load(app: &AppHandle, state: &AppState) {
+    if let Ok(store) = app.store(NAMES_STORE) {
+        if let Err(e) = store.reload() {
+            warn!("Could not load torrent names store: {}", e);
+        }
+        if let Some(value) = store.get("names") {
+            if let Ok(by_hash) = serde_json::from_value::<HashMap<String, String>>(value) {
+                info!("Loaded {} torrent names from disk", by_hash.len());
+                state.display_names.write().await.by_hash = by_hash;
+            }
+        }
+    }
+}
+
+/// Name a torrent and save the change.
+pub async fn set_name(app: &AppHandle, state: &AppState, info_hash: &str, name: Option<String>) {
+    let changed = state.display_names.write().await.set(info_hash, name);
+    if changed {
+        persist(app, state).await;
+    }
+}
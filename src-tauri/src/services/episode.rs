@@ -0,0 +1,141 @@
+// Which episodes a release covers, for the smart episode filter: single
+// episodes, multi-episode files, anime absolute numbering, season packs and
+// daily shows.
+
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// S01E01-E02, S01E01E02, S01E01-02
+static RANGE_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\bS(\d{1,2})E(\d{1,3})(?:-?E|-)(\d{1,3})\b").unwrap());
+
+static SEASON_EP_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\bS(\d{1,2})E(\d{1,3})").unwrap());
+
+/// 1x01; the boundaries keep resolutions like 1920x1080 out
+static X_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?i)\b(\d{1,2})x(\d{2,3})\b").unwrap());
+
+/// 2024.01.15 or 2024-01-15
+static DAILY_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\b(\d{4})[.\-](\d{2})[.\-](\d{2})\b").unwrap());
+
+/// "Mushoku Tensei S2 - 01": anime releases that number episodes per season
+static SEASON_DASH_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\b(?:S|Season[ ._]?)(\d{1,2})\s-\s(\d{1,4})(?:v\d)?\b").unwrap()
+});
+
+/// S02 or Season 2 without an episode
+static SEASON_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\b(?:S|Season[ ._]?)(\d{1,2})\b").unwrap());
+
+/// "One Piece - 1071", "Frieren - 12v2", "Show Episode 24"
+static ABSOLUTE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)(?:\s-\s|\b(?:Episode|Ep)[ .]?)(\d{1,4})(?:v\d)?\b").unwrap()
+});
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Episode {
+    Single { season: u32, episode: u32 },
+    /// Several episodes in one release, first to last
+    Range { season: u32, start: u32, end: u32 },
+    /// Anime-style numbering that runs across seasons
+    Absolute(u32),
+    SeasonPack(u32),
+    /// Air date of a daily show, YYYY-MM-DD
+    Daily(String),
+}
+
+impl Episode {
+    /// Keys for every episode the release covers, as tracked per interest.
+    /// Absolute numbers and season packs get their own namespaces, so
+    /// "Show - 12" never collides with S01E12 and a pack with its episodes.
+    pub fn keys(&self) -> Vec<String> {
+        match self {
+            Episode::Single { season, episode } => vec![format!("S{season:02}E{episode:02}")],
+            Episode::Range { season, start, end } => {
+                (*start..=*end).map(|episode| format!("S{season:02}E{episode:02}")).collect()
+            }
+            Episode::Absolute(number) => vec![format!("A{number:04}")],
+            Episode::SeasonPack(season) => vec![format!("S{season:02}")],
+            Episode::Daily(date) => vec![date.clone()],
+        }
+    }
+}
+
+/// Episodes a release covers, from its title. None for movies and anything
+/// else without numbering.
+pub fn parse(title: &str) -> Option<Episode> {
+    let number = |caps: &regex::Captures, i: usize| caps.get(i)?.as_str().parse::<u32>().ok();
+
+    if let Some(caps) = RANGE_RE.captures(title) {
+        let (season, start, end) = (number(&caps, 1)?, number(&caps, 2)?, number(&caps, 3)?);
+        if end > start {
+            return Some(Episode::Range { season, start, end });
+        }
+    }
+    if let Some(caps) = SEASON_EP_RE.captures(title).or_else(|| X_RE.captures(title)) {
+        return Some(Episode::Single { season: number(&caps, 1)?, episode: number(&caps, 2)? });
+    }
+    if let Some(caps) = DAILY_RE.captures(title) {
+        return Some(Episode::Daily(format!("{}-{}-{}", &caps[1], &caps[2], &caps[3])));
+    }
+    if let Some(caps) = SEASON_DASH_RE.captures(title) {
+        return Some(Episode::Single { season: number(&caps, 1)?, episode: number(&caps, 2)? });
+    }
+    if let Some(caps) = SEASON_RE.captures(title) {
+        return Some(Episode::SeasonPack(number(&caps, 1)?));
+    }
+    // Four digits that look like a year are a movie's, not an episode's
+    ABSOLUTE_RE
+        .captures(title)
+        .and_then(|caps| number(&caps, 1))
+        .filter(|n| !(1900..=2099).contains(n))
+        .map(Episode::Absolute)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn real_world_titles() {
+        use Episode::*;
+        let cases: &[(&str, Option<Episode>)] = &[
+            ("The.Bear.S03E04.1080p.WEB.h264-ETHEL", Some(Single { season: 3, episode: 4 })),
+            ("Show S1E9 720p HDTV", Some(Single { season: 1, episode: 9 })),
+            ("Doctor Who 2005 S13E01 1080p", Some(Single { season: 13, episode: 1 })),
+            ("Friends 1x01 The Pilot", Some(Single { season: 1, episode: 1 })),
+            ("Show.S01E01-E02.1080p.WEB-DL", Some(Range { season: 1, start: 1, end: 2 })),
+            ("Show.S02E05E06.720p.HDTV", Some(Range { season: 2, start: 5, end: 6 })),
+            ("Show S04E09-10 1080p", Some(Range { season: 4, start: 9, end: 10 })),
+            ("Show.S01E01-1080p.WEB", Some(Single { season: 1, episode: 1 })),
+            ("Movie.2019.1920x1080.BluRay", None),
+            ("[SubsPlease] One Piece - 1071 (1080p) [A1B2C3D4].mkv", Some(Absolute(1071))),
+            ("[Erai-raws] Sousou no Frieren - 12v2 [1080p].mkv", Some(Absolute(12))),
+            ("Naruto Shippuden Episode 500 720p", Some(Absolute(500))),
+            ("Some Film - 2019 1080p BluRay", None),
+            ("Show.S02.1080p.BluRay.x264-GROUP", Some(SeasonPack(2))),
+            ("Show Season 2 Complete 720p", Some(SeasonPack(2))),
+            ("Show.Season.03.1080p.WEB-DL", Some(SeasonPack(3))),
+            ("[SubsPlease] Mushoku Tensei S2 - 01 (1080p)", Some(Single { season: 2, episode: 1 })),
+            ("[Erai-raws] Oshi no Ko Season 2 - 05v2 [1080p]", Some(Single { season: 2, episode: 5 })),
+            ("[SubsPlease] Mushoku Tensei S2 (01-12) (1080p) [Batch]", Some(SeasonPack(2))),
+            ("The.Daily.Show.2024.01.15.Guest.720p", Some(Daily("2024-01-15".into()))),
+            ("Late Show 2024-03-07 1080p", Some(Daily("2024-03-07".into()))),
+            ("Movie.2024.1080p.BluRay.x264-GROUP", None),
+        ];
+        for (title, expected) in cases {
+            assert_eq!(parse(title), *expected, "{title}");
+        }
+    }
+
+    #[test]
+    fn keys_cover_ranges_in_separate_namespaces() {
+        assert_eq!(
+            Episode::Range { season: 1, start: 1, end: 3 }.keys(),
+            ["S01E01", "S01E02", "S01E03"]
+        );
+        assert_eq!(Episode::Absolute(12).keys(), ["A0012"]);
+        assert_eq!(Episode::SeasonPack(2).keys(), ["S02"]);
+        assert_ne!(Episode::Absolute(12).keys(), Episode::Single { season: 1, episode: 12 }.keys());
+    }
+}
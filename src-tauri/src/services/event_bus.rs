@@ -0,0 +1,56 @@
+// Events shared with remote clients.
+//
+// Progress, pending-count and playback events go to the webview as usual and
+// also onto a broadcast channel, which the media server's /ws bridge
+// subscribes to so a phone remote sees the same updates.
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::broadcast;
+
+use crate::state::AppState;
+
+/// Events buffered per subscriber; a slower client skips the oldest.
+const CAPACITY: usize = 256;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BusEvent {
+    pub event: &'static str,
+    pub payload: serde_json::Value,
+}
+
+pub struct EventBus {
+    tx: broadcast::Sender<BusEvent>,
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self {
+            tx: broadcast::channel(CAPACITY).0,
+        }
+    }
+}
+
+impl EventBus {
+    pub fn subscribe(&self) -> broadcast::Receiver<BusEvent> {
+        self.tx.subscribe()
+    }
+
+    pub fn publish<S: Serialize>(&self, event: &'static str, payload: &S) {
+        // Skip serializing when no remote is connected
+        if self.tx.receiver_count() == 0 {
+            return;
+        }
+        if let Ok(payload) = serde_json::to_value(payload) {
+            let _ = self.tx.send(BusEvent { event, payload });
+        }
+    }
+}
+
+/// Emit to the webview and to remote subscribers.
+pub fn emit<S: Serialize + Clone>(app: &AppHandle, event: &'static str, payload: S) -> tauri::Result<()> {
+    if let Some(state) = app.try_state::<AppState>() {
+        state.event_bus.publish(event, &payload);
+    }
+    app.emit(event, payload)
+}
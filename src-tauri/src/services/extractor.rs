@@ -0,0 +1,252 @@
+// Unpack .zip and .rar archives from finished downloads.
+//
+// Zips are read with the zip crate. Rars need an external tool (unar or unrar);
+// when neither is installed the archive is reported as failed.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use regex::Regex;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+use tracing::{info, warn};
+
+/// `name.part01.rar`, `name.part2.rar`, ... (captures the volume number)
+static RAR_PART_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\.part(\d+)\.rar$").unwrap());
+
+/// Places GUI apps on macOS don't get in PATH but Homebrew installs into.
+const EXTRA_TOOL_DIRS: &[&str] = &["/opt/homebrew/bin", "/usr/local/bin"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ArchiveKind {
+    Zip,
+    Rar,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExtractedEvent {
+    pub torrent_id: usize,
+    pub files: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExtractFailedEvent {
+    pub torrent_id: usize,
+    pub archive: String,
+    pub message: String,
+}
+
+/// Archive type for a file, or None if it isn't the volume extraction starts from.
+/// Later volumes of a multi-part rar (`.part02.rar`, `.r00`, ...) are skipped.
+fn archive_kind(path: &Path) -> Option<ArchiveKind> {
+    let name = path.file_name()?.to_string_lossy().to_lowercase();
+    if name.ends_with(".zip") {
+        return Some(ArchiveKind::Zip);
+    }
+    if !name.ends_with(".rar") {
+        return None;
+    }
+    match RAR_PART_RE.captures(&name) {
+        Some(caps) => {
+            let volume: u32 = caps[1].parse().ok()?;
+            (volume == 1).then_some(ArchiveKind::Rar)
+        }
+        None => Some(ArchiveKind::Rar),
+    }
+}
+
+/// Find an installed rar extractor, preferring unar.
+fn find_rar_tool() -> Option<PathBuf> {
+    find_tool(&["unar", "unrar"])
+}
+
+/// First of `tools` found in PATH or the Homebrew directories.
+pub(crate) fn find_tool(tools: &[&str]) -> Option<PathBuf> {
+    let mut dirs: Vec<PathBuf> = std::env::var_os("PATH")
+        .map(|path| std::env::split_paths(&path).collect())
+        .unwrap_or_default();
+    dirs.extend(EXTRA_TOOL_DIRS.iter().map(PathBuf::from));
+
+    tools.iter().find_map(|tool| {
+        dirs.iter().map(|dir| dir.join(tool)).find(|p| p.is_file())
+    })
+}
+
+fn extract_zip(archive: &Path, dest: &Path) -> Result<Vec<PathBuf>, String> {
+    let file = std::fs::File::open(archive).map_err(|e| e.to_string())?;
+    let mut zip = zip::ZipArchive::new(file).map_err(|e| e.to_string())?;
+    let mut produced = Vec::new();
+
+    for i in 0..zip.len() {
+        let mut entry = zip.by_index(i).map_err(|e| e.to_string())?;
+        // enclosed_name rejects absolute paths and ".." escapes
+        let Some(relative) = entry.enclosed_name() else {
+            warn!(entry = entry.name(), "Skipping unsafe path in zip");
+            continue;
+        };
+        let target = dest.join(relative);
+        if entry.is_dir() {
+            std::fs::create_dir_all(&target).map_err(|e| e.to_string())?;
+            continue;
+        }
+        if let Some(parent) = target.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let mut out = std::fs::File::create(&target).map_err(|e| e.to_string())?;
+        std::io::copy(&mut entry, &mut out).map_err(|e| e.to_string())?;
+        produced.push(target);
+    }
+
+    Ok(produced)
+}
+
+fn extract_rar(archive: &Path, dest: &Path) -> Result<Vec<PathBuf>, String> {
+    let tool = find_rar_tool().ok_or("No rar extractor found (install unar or unrar)")?;
+    std::fs::create_dir_all(dest).map_err(|e| e.to_string())?;
+    let before = list_files(dest);
+
+    let mut cmd = std::process::Command::new(&tool);
+    if tool.ends_with("unar") {
+        // -f overwrites, -D extracts straight into dest without a wrapper folder
+        cmd.arg("-f").arg("-D").arg("-o").arg(dest).arg(archive);
+    } else {
+        // unrar wants the destination with a trailing separator
+        cmd.arg("x").arg("-o+").arg("-y").arg(archive).arg(format!("{}/", dest.display()));
+    }
+    let output = cmd.output().map_err(|e| format!("Failed to run {}: {e}", tool.display()))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("{} failed: {}", tool.display(), stderr.trim()));
+    }
+
+    let mut produced: Vec<PathBuf> = list_files(dest).difference(&before).cloned().collect();
+    produced.sort();
+    Ok(produced)
+}
+
+fn list_files(dir: &Path) -> HashSet<PathBuf> {
+    let mut files = HashSet::new();
+    let mut stack = vec![dir.to_path_buf()];
+    while let Some(current) = stack.pop() {
+        let Ok(entries) = std::fs::read_dir(&current) else { continue };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                stack.push(path);
+            } else {
+                files.insert(path);
+            }
+        }
+    }
+    files
+}
+
+/// Extract every archive among a finished torrent's files into `dest`.
+/// Runs on a blocking thread and reports the outcome as events.
+pub fn spawn_extraction(app_handle: AppHandle, torrent_id: usize, files: Vec<PathBuf>, dest: PathBuf) {
+    let archives: Vec<(PathBuf, ArchiveKind)> = files
+        .into_iter()
+        .filter_map(|path| archive_kind(&path).map(|kind| (path, kind)))
+        .collect();
+    if archives.is_empty() {
+        return;
+    }
+
+    tokio::spawn(async move {
+        let result = tokio::task::spawn_blocking(move || {
+            let mut produced = Vec::new();
+            let mut failures = Vec::new();
+            for (archive, kind) in archives {
+                let outcome = match kind {
+                    ArchiveKind::Zip => extract_zip(&archive, &dest),
+                    ArchiveKind::Rar => extract_rar(&archive, &dest),
+                };
+                match outcome {
+                    Ok(files) => {
+                        info!(archive = %archive.display(), count = files.len(), "Extracted archive");
+                        produced.extend(files);
+                    }
+                    Err(message) => failures.push((archive, message)),
+                }
+            }
+            (produced, failures)
+        })
+        .await;
+
+        let (produced, failures) = match result {
+            Ok(r) => r,
+            Err(e) => {
+                warn!(torrent_id, error = %e, "Extraction task panicked");
+                (Vec::new(), vec![(PathBuf::new(), e.to_string())])
+            }
+        };
+
+        for (archive, message) in failures {
+            warn!(torrent_id, archive = %archive.display(), %message, "Failed to extract archive");
+            app_handle
+                .emit(
+                    "torrent:extract-failed",
+                    ExtractFailedEvent {
+                        torrent_id,
+                        archive: archive.to_string_lossy().to_string(),
+                        message,
+                    },
+                )
+                .unwrap_or_default();
+        }
+
+        if !produced.is_empty() {
+            app_handle
+                .emit(
+                    "torrent:extracted",
+                    ExtractedEvent {
+                        torrent_id,
+                        files: produced.iter().map(|p| p.to_string_lossy().to_string()).collect(),
+                    },
+                )
+                .unwrap_or_default();
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_archive_kind_first_volume_only() {
+        assert_eq!(archive_kind(Path::new("Show/show.rar")), Some(ArchiveKind::Rar));
+        assert_eq!(archive_kind(Path::new("show.r00")), None);
+        assert_eq!(archive_kind(Path::new("show.part01.rar")), Some(ArchiveKind::Rar));
+        assert_eq!(archive_kind(Path::new("show.part1.RAR")), Some(ArchiveKind::Rar));
+        assert_eq!(archive_kind(Path::new("show.part02.rar")), None);
+        assert_eq!(archive_kind(Path::new("subs.ZIP")), Some(ArchiveKind::Zip));
+        assert_eq!(archive_kind(Path::new("movie.mkv")), None);
+    }
+
+    #[test]
+    fn test_extract_zip() {
+        let dir = std::env::temp_dir().join(format!("when-extract-test-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let archive = dir.join("subs.zip");
+
+        let mut writer = zip::ZipWriter::new(std::fs::File::create(&archive).unwrap());
+        let options = zip::write::SimpleFileOptions::default();
+        writer.start_file("en/movie.srt", options).unwrap();
+        writer.write_all(b"1\n00:00:01,000 --> 00:00:02,000\nHi\n").unwrap();
+        writer.start_file("../escape.srt", options).unwrap();
+        writer.write_all(b"nope").unwrap();
+        writer.finish().unwrap();
+
+        let dest = dir.join("extracted");
+        let produced = extract_zip(&archive, &dest).unwrap();
+        assert_eq!(produced, vec![dest.join("en/movie.srt")]);
+        assert!(dest.join("en/movie.srt").is_file());
+        assert!(!dir.join("escape.srt").exists());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,151 @@
+// Download a torrent's files in a chosen order, e.g. the first episode of a
+// season pack before the rest.
+//
+// librqbit has no per-file priority, so the order is approximated with the
+// file selection: only the files up to the current position are selected, and
+// the selection grows by one file each time the torrent finishes what it has.
+// Keyed by info hash so an order survives re-adds and restarts.
+
+use std::collections::{BTreeSet, HashMap};
+
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
+use tracing::{info, warn};
+
+use crate::errors::{Result, WhenThenError};
+use crate::models::{FileOrderStatus, FileOrderStrategy};
+use crate::state::AppState;
+
+const FILE_ORDER_STORE: &str = "file_order.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileOrder {
+    /// File indices, first to download first
+    pub order: Vec<usize>,
+    /// Index into `order` of the file being downloaded
+    pub position: usize,
+    /// Selection to put back once the order is done or canceled
+    pub restore: Vec<usize>,
+}
+
+impl FileOrder {
+    /// Selection while the file at `position` downloads: it and everything before it.
+    pub fn selection(&self) -> Vec<usize> {
+        self.order[..=self.position.min(self.order.len() - 1)].to_vec()
+    }
+
+    /// The original selection plus every ordered file.
+    pub fn full_selection(&self) -> Vec<usize> {
+        self.restore
+            .iter()
+            .chain(&self.order)
+            .copied()
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    }
+
+    pub fn status(&self) -> FileOrderStatus {
+        FileOrderStatus {
+            strategy: FileOrderStrategy::Selection,
+            order: self.order.clone(),
+            position: self.position,
+            current_file: self.order.get(self.position).copied(),
+        }
+    }
+}
+
+/// Check an order from the frontend against a torrent with `file_count` files.
+pub fn validate(order: &[usize], file_count: usize) -> Result<()> {
+    if order.is_empty() {
+        return Err(WhenThenError::InvalidInput("File order is empty".into()));
+    }
+    let mut seen = BTreeSet::new();
+    for &index in order {
+        if index >= file_count {
+            return Err(WhenThenError::InvalidInput(format!(
+                "File {index} is out of range; the torrent has {file_count} files"
+            )));
+        }
+        if !seen.insert(index) {
+            return Err(WhenThenError::InvalidInput(format!("File {index} is listed twice")));
+        }
+    }
+    Ok(())
+}
+
+/// First position at or after `from` whose file isn't complete yet;
+/// `order.len()` once they all are.
+pub fn next_position(order: &[usize], from: usize, complete: impl Fn(usize) -> bool) -> usize {
+    (from..order.len())
+        .find(|&pos| !complete(order[pos]))
+        .unwrap_or(order.len())
+}
+
+#[derive(Debug, Default)]
+pub struct FileOrders {
+    by_hash: HashMap<String, FileOrder>,
+}
+
+impl FileOrders {
+    pub fn get(&self, info_hash: &str) -> Option<&FileOrder> {
+        self.by_hash.get(info_hash)
+    }
+
+    pub fn insert(&mut self, info_hash: &str, order: FileOrder) {
+        self.by_hash.insert(info_hash.to_string(), order);
+    }
+
+    pub fn remove(&mut self, info_hash: &str) -> Option<FileOrder> {
+        self.by_hash.remove(info_hash)
+    }
+}
+
+pub async fn persist(app: &AppHandle, state: &AppState) {
+    if let Ok(store) = app.store(FILE_ORDER_STORE) {
+        let orders = state.file_orders.read().await;
+        if let Ok(value) = serde_json::to_value(&orders.by_hash) {
+            store.set("orders", value);
+            if let Err(e) = store.save() {
+                warn!("Failed to save file orders: {}", e);
+            }
+        }
+    }
+}
+
+pub async fn load(app: &AppHandle, state: &AppState) {
+    if let Ok(store) = app.store(FILE_ORDER_STORE) {
+        if let Err(e) = store.reload() {
+            warn!("Could not load file order store: {}", e);
+        }
+        if let Some(value) = store.get("orders") {
+            if let Ok(by_hash) = serde_json::from_value::<HashMap<String, FileOrder>>(value) {
+                info!("Loaded {} file orders from disk", by_hash.len());
+                state.file_orders.write().await.by_hash = by_hash;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn window_skips_finished_files_and_restores_everything() {
+        let order = vec![3, 0, 2];
+        let done = [3];
+        assert_eq!(next_position(&order, 0, |i| done.contains(&i)), 1);
+        assert_eq!(next_position(&order, 2, |_| true), 3);
+
+        let file_order = FileOrder { order, position: 1, restore: vec![0, 1] };
+        assert_eq!(file_order.selection(), [3, 0]);
+        assert_eq!(file_order.full_selection(), [0, 1, 2, 3]);
+
+        assert!(validate(&[0, 2], 3).is_ok());
+        assert!(validate(&[], 3).is_err());
+        assert!(validate(&[1, 1], 3).is_err());
+        assert!(validate(&[3], 3).is_err());
+    }
+}
@@ -1,19 +1,43 @@
-// Watches folders for new .torrent files and auto-adds them.
-use std::path::Path;
+// Watches folders for new .torrent and magnet files and auto-adds them.
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
 use notify::{Config, Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use notify::event::ModifyKind;
 use serde::Serialize;
 use tauri::{AppHandle, Emitter, Manager};
 use tokio::sync::{mpsc, Mutex};
 use tracing::{info, warn};
 
+use crate::errors::{Result, WhenThenError};
+use crate::models::{TorrentAddOptions, TorrentAddedResponse, WatchFolderRule};
 use crate::state::AppState;
+use crate::services::rss::ApprovedTorrent;
 use crate::services::torrent_engine;
 
+/// Trigger files are moved here (inside the watched folder) when configured.
+const PROCESSED_DIR: &str = "processed";
+/// A file must keep the same size this long before it's considered fully written.
+const STABLE_FOR: Duration = Duration::from_secs(2);
+const STABLE_POLL: Duration = Duration::from_millis(500);
+/// Give up on files that are still growing after this long.
+const STABLE_TIMEOUT: Duration = Duration::from_secs(120);
+
 #[derive(Clone, Serialize)]
 pub struct FolderWatchEvent {
     pub path: String,
     pub torrent_id: usize,
     pub torrent_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+}
+
+/// A watched folder's resolved path and the rule it came from.
+#[derive(Clone)]
+struct WatchRoot {
+    path: PathBuf,
+    rule: WatchFolderRule,
 }
 
 pub struct FolderWatcherHandle {
@@ -21,26 +45,195 @@ pub struct FolderWatcherHandle {
     shutdown_tx: mpsc::Sender<()>,
 }
 
+/// What a dropped file should be added as.
+enum WatchedKind {
+    Torrent,
+    Magnet,
+    /// .txt files count only if their content is a magnet link.
+    MaybeMagnet,
+}
+
+fn watched_kind(path: &Path) -> Option<WatchedKind> {
+    let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+    match ext.as_str() {
+        "torrent" => Some(WatchedKind::Torrent),
+        "magnet" => Some(WatchedKind::Magnet),
+        "txt" => Some(WatchedKind::MaybeMagnet),
+        _ => None,
+    }
+}
+
+/// First magnet link in a text file's content.
+fn parse_magnet(content: &str) -> Option<String> {
+    content
+        .lines()
+        .map(str::trim)
+        .find(|line| line.starts_with("magnet:?"))
+        .map(String::from)
+}
+
+/// The watched root containing `path`, if it's within `max_depth` subfolders and not in processed/.
+/// With nested watch folders the deepest (most specific) one wins.
+fn watched_root<'a>(path: &Path, roots: &'a [WatchRoot], max_depth: u32) -> Option<&'a WatchRoot> {
+    roots.iter().filter(|root| {
+        let Ok(relative) = path.strip_prefix(&root.path) else {
+            return false;
+        };
+        let dirs: Vec<_> = relative.parent().map(|p| p.components().collect()).unwrap_or_default();
+        dirs.len() as u32 <= max_depth
+            && dirs.first().is_none_or(|c| c.as_os_str() != PROCESSED_DIR)
+    })
+    .max_by_key(|root| root.path.components().count())
+}
+
+/// Wait until the file size stops changing, so half-written downloads aren't picked up.
+async fn wait_until_stable(path: &Path) -> bool {
+    let started = std::time::Instant::now();
+    let mut last_size = None;
+    let mut stable_since = std::time::Instant::now();
+
+    while started.elapsed() < STABLE_TIMEOUT {
+        let Ok(meta) = tokio::fs::metadata(path).await else {
+            return false;
+        };
+        let size = meta.len();
+        if last_size != Some(size) {
+            last_size = Some(size);
+            stable_since = std::time::Instant::now();
+        } else if size > 0 && stable_since.elapsed() >= STABLE_FOR {
+            return true;
+        }
+        tokio::time::sleep(STABLE_POLL).await;
+    }
+    false
+}
+
+async fn add_watched_file(
+    state: &AppState,
+    app_handle: &AppHandle,
+    path: &Path,
+    rule: &WatchFolderRule,
+) -> Result<Option<TorrentAddedResponse>> {
+    let Some(kind) = watched_kind(path) else {
+        return Ok(None);
+    };
+    // The rule's own folder and label win over its interest's
+    let interest = match &rule.interest_id {
+        Some(id) => state.rss_state.interests.read().await.iter().find(|i| &i.id == id).cloned(),
+        None => None,
+    };
+    let options = Some(TorrentAddOptions {
+        output_folder: rule.output_folder.clone().or_else(|| interest.as_ref().and_then(|i| i.download_path.clone())),
+        only_files: None,
+        add_paused: (!rule.auto_start).then_some(true),
+        label: rule.label.clone().or_else(|| interest.as_ref().and_then(|i| i.default_label.clone())),
+        allow_bad: false,
+    });
+
+    let added = match kind {
+        WatchedKind::Torrent => {
+            let bytes = tokio::fs::read(path)
+                .await
+                .map_err(|e| WhenThenError::FileNotFound(format!("{}: {}", path.display(), e)))?;
+            torrent_engine::add_torrent_bytes(state, app_handle, bytes, options).await.map(Some)
+        }
+        WatchedKind::Magnet | WatchedKind::MaybeMagnet => {
+            let content = tokio::fs::read_to_string(path)
+                .await
+                .map_err(|e| WhenThenError::FileNotFound(format!("{}: {}", path.display(), e)))?;
+            match parse_magnet(&content) {
+                Some(magnet) => torrent_engine::add_magnet(state, app_handle, magnet, options).await.map(Some),
+                None if matches!(kind, WatchedKind::Magnet) => {
+                    Err(WhenThenError::InvalidInput(format!("No magnet link in {}", path.display())))
+                }
+                None => Ok(None),
+            }
+        }
+    }?;
+
+    // Completion organizes it per the interest, like an approved match
+    if let (Some(response), Some(interest)) = (&added, interest) {
+        state.rss_state.approved_torrents.write().await.entry(response.id).or_insert_with(|| ApprovedTorrent {
+            interest_id: interest.id,
+            interest_name: interest.name,
+        });
+    }
+    Ok(added)
+}
+
+/// Move or delete the trigger file after a successful add, per settings.
+async fn finish_trigger_file(state: &AppState, path: &Path, root: &Path) {
+    let (move_processed, delete_on_add) = {
+        let cfg = state.config.read().await;
+        (cfg.watch_folders_move_processed, cfg.delete_torrent_file_on_add)
+    };
+
+    if move_processed {
+        let processed = root.join(PROCESSED_DIR);
+        let Some(file_name) = path.file_name() else {
+            return;
+        };
+        if let Err(e) = tokio::fs::create_dir_all(&processed).await {
+            warn!("Failed to create {}: {e}", processed.display());
+            return;
+        }
+        if let Err(e) = tokio::fs::rename(path, processed.join(file_name)).await {
+            warn!("Failed to move {} to processed: {e}", path.display());
+        }
+    } else if delete_on_add {
+        let _ = tokio::fs::remove_file(path).await;
+    }
+}
+
+async fn process_path(app_handle: &AppHandle, path: PathBuf, root: WatchRoot) {
+    if !wait_until_stable(&path).await {
+        return;
+    }
+
+    info!("Folder watch detected: {}", path.display());
+    let state = app_handle.state::<AppState>();
+    match add_watched_file(&state, app_handle, &path, &root.rule).await {
+        Ok(Some(result)) => {
+            finish_trigger_file(&state, &path, &root.path).await;
+            let event = FolderWatchEvent {
+                path: path.to_string_lossy().to_string(),
+                torrent_id: result.id,
+                torrent_name: result.name.clone(),
+                label: root.rule.label.clone(),
+            };
+            app_handle
+                .emit("folder_watch:torrent_detected", &event)
+                .unwrap_or_default();
+            info!("Auto-added torrent from watched folder: {}", result.name);
+        }
+        Ok(None) => {}
+        Err(e) => {
+            warn!("Failed to add torrent from watched folder: {e}");
+        }
+    }
+}
+
 pub fn start_watching(
-    folders: Vec<String>,
+    rules: Vec<WatchFolderRule>,
+    max_depth: u32,
     app_handle: AppHandle,
 ) -> Option<FolderWatcherHandle> {
-    if folders.is_empty() {
+    if rules.is_empty() {
         return None;
     }
 
-    let (event_tx, mut event_rx) = mpsc::channel::<String>(64);
+    let (event_tx, mut event_rx) = mpsc::channel::<PathBuf>(64);
     let (shutdown_tx, mut shutdown_rx) = mpsc::channel::<()>(1);
 
     let event_tx_clone = event_tx.clone();
     let mut watcher = match RecommendedWatcher::new(
-        move |result: Result<Event, notify::Error>| {
+        move |result: std::result::Result<Event, notify::Error>| {
             if let Ok(event) = result {
-                if matches!(event.kind, EventKind::Create(_)) {
+                // Browsers often write to a temp name and rename when done
+                if matches!(event.kind, EventKind::Create(_) | EventKind::Modify(ModifyKind::Name(_))) {
                     for path in event.paths {
-                        if path.extension().map(|e| e == "torrent").unwrap_or(false) {
-                            let path_str = path.to_string_lossy().to_string();
-                            let _ = event_tx_clone.try_send(path_str);
+                        if watched_kind(&path).is_some() {
+                            let _ = event_tx_clone.try_send(path);
                         }
                     }
                 }
@@ -55,49 +248,46 @@ pub fn start_watching(
         }
     };
 
-    for folder in &folders {
-        let path = Path::new(folder);
+    let mode = if max_depth > 0 { RecursiveMode::Recursive } else { RecursiveMode::NonRecursive };
+    let mut roots = Vec::new();
+    for rule in rules {
+        let folder = &rule.path;
+        // Event paths are reported canonicalized (e.g. /private/var on macOS)
+        let path = torrent_engine::expand_path(folder);
+        let path = path.canonicalize().unwrap_or(path);
         if path.is_dir() {
-            if let Err(e) = watcher.watch(path, RecursiveMode::NonRecursive) {
+            if let Err(e) = watcher.watch(&path, mode) {
                 warn!("Failed to watch folder {folder}: {e}");
             } else {
                 info!("Watching folder: {folder}");
+                roots.push(WatchRoot { path, rule });
             }
         } else {
             warn!("Skipping non-existent folder: {folder}");
         }
     }
 
+    let in_flight: Arc<Mutex<HashSet<PathBuf>>> = Arc::new(Mutex::new(HashSet::new()));
+
     tokio::spawn(async move {
         loop {
             tokio::select! {
                 Some(path) = event_rx.recv() => {
-                    // Debounce: wait for the file to finish writing
-                    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+                    let Some(root) = watched_root(&path, &roots, max_depth).cloned() else {
+                        continue;
+                    };
 
-                    let file_path = Path::new(&path);
-                    if !file_path.exists() {
+                    // Create and rename events can both fire for one file
+                    if !in_flight.lock().await.insert(path.clone()) {
                         continue;
                     }
 
-                    info!("Folder watch detected: {path}");
-                    let state = app_handle.state::<AppState>();
-                    match torrent_engine::add_torrent_file(&state, &app_handle, path.clone(), None).await {
-                        Ok(result) => {
-                            let event = FolderWatchEvent {
-                                path,
-                                torrent_id: result.id,
-                                torrent_name: result.name.clone(),
-                            };
-                            app_handle
-                                .emit("folder_watch:torrent_detected", &event)
-                                .unwrap_or_default();
-                            info!("Auto-added torrent from watched folder: {}", result.name);
-                        }
-                        Err(e) => {
-                            warn!("Failed to add torrent from watched folder: {e}");
-                        }
-                    }
+                    let app_handle = app_handle.clone();
+                    let in_flight = in_flight.clone();
+                    tokio::spawn(async move {
+                        process_path(&app_handle, path.clone(), root).await;
+                        in_flight.lock().await.remove(&path);
+                    });
                 }
                 _ = shutdown_rx.recv() => {
                     info!("Folder watcher shutting down");
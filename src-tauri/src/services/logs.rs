@@ -0,0 +1,272 @@
+// In-app copy of recent log output, so bug reports don't need a terminal.
+// A tracing layer mirrors events into a bounded buffer and, when enabled,
+// into daily files under app_data_dir/logs.
+
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+
+use chrono::{Local, NaiveDate};
+use serde::Serialize;
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::{reload, EnvFilter, Layer, Registry};
+
+use crate::errors::{Result, WhenThenError};
+
+/// Entries kept in memory; the oldest are dropped first.
+const CAPACITY: usize = 5_000;
+
+/// Daily log files kept before the oldest are deleted.
+const KEEP_LOG_FILES: usize = 7;
+
+/// Used when RUST_LOG isn't set.
+pub const DEFAULT_LEVEL: &str = "info";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LogEntry {
+    pub timestamp: String,
+    pub level: String,
+    pub target: String,
+    pub message: String,
+}
+
+struct DailyFile {
+    dir: PathBuf,
+    date: NaiveDate,
+    file: std::fs::File,
+}
+
+#[derive(Default)]
+pub struct LogStore {
+    entries: Mutex<VecDeque<(Level, LogEntry)>>,
+    filter: OnceLock<reload::Handle<EnvFilter, Registry>>,
+    file: Mutex<Option<DailyFile>>,
+}
+
+/// Directives for a level name: our own crate at `level`, dependencies at warn.
+/// Anything that already looks like a directive list is used as is.
+pub fn filter_for(level: &str) -> Result<EnvFilter> {
+    let level = level.trim();
+    let directives = if level.contains('=') || level.contains(',') {
+        level.to_string()
+    } else {
+        level
+            .parse::<Level>()
+            .map_err(|_| WhenThenError::InvalidInput(format!("Unknown log level: {level}")))?;
+        format!("warn,when_lib={level}")
+    };
+    EnvFilter::try_new(&directives).map_err(|e| WhenThenError::InvalidInput(format!("Invalid log filter: {e}")))
+}
+
+impl LogStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The buffer layer, to be added to the subscriber.
+    pub fn layer(self: &Arc<Self>) -> LogLayer {
+        LogLayer { store: self.clone() }
+    }
+
+    /// Keep the handle used by `set_level`. Called once while installing the subscriber.
+    pub fn set_filter_handle(&self, handle: reload::Handle<EnvFilter, Registry>) {
+        let _ = self.filter.set(handle);
+    }
+
+    /// Swap the active filter without restarting.
+    pub fn set_level(&self, level: &str) -> Result<()> {
+        let filter = filter_for(level)?;
+        let handle = self
+            .filter
+            .get()
+            .ok_or_else(|| WhenThenError::Internal("Log filter not installed".into()))?;
+        handle
+            .reload(filter)
+            .map_err(|e| WhenThenError::Internal(format!("Failed to change log level: {e}")))?;
+        tracing::info!("Log level set to {}", level);
+        Ok(())
+    }
+
+    /// Newest `limit` entries at `min_level` or more severe, oldest first.
+    pub fn recent(&self, limit: usize, min_level: Option<Level>) -> Vec<LogEntry> {
+        let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        let mut recent: Vec<LogEntry> = entries
+            .iter()
+            .rev()
+            .filter(|(level, _)| min_level.is_none_or(|min| *level <= min))
+            .take(limit)
+            .map(|(_, entry)| entry.clone())
+            .collect();
+        recent.reverse();
+        recent
+    }
+
+    /// Write the whole buffer as plain text lines.
+    pub fn export(&self, path: &Path) -> Result<usize> {
+        let entries = self.recent(CAPACITY, None);
+        let mut text = String::new();
+        for entry in &entries {
+            let _ = writeln!(text, "{}", format_line(entry));
+        }
+        std::fs::write(path, text)
+            .map_err(|e| WhenThenError::Internal(format!("Failed to write {}: {e}", path.display())))?;
+        Ok(entries.len())
+    }
+
+    /// Also append every entry to `dir/when-YYYY-MM-DD.log`.
+    pub fn enable_file(&self, dir: PathBuf) -> Result<()> {
+        let daily = open_daily(&dir, Local::now().date_naive())
+            .map_err(|e| WhenThenError::Internal(format!("Failed to open log file in {}: {e}", dir.display())))?;
+        *self.file.lock().unwrap_or_else(|e| e.into_inner()) = Some(daily);
+        Ok(())
+    }
+
+    pub fn disable_file(&self) {
+        *self.file.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    }
+
+    fn push(&self, level: Level, entry: LogEntry) {
+        self.write_file(&entry);
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        if entries.len() == CAPACITY {
+            entries.pop_front();
+        }
+        entries.push_back((level, entry));
+    }
+
+    fn write_file(&self, entry: &LogEntry) {
+        let mut file = self.file.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(daily) = file.as_mut() else {
+            return;
+        };
+        let today = Local::now().date_naive();
+        // On failure keep writing to yesterday's file; logging about the logger would recurse
+        if daily.date != today {
+            if let Ok(next) = open_daily(&daily.dir, today) {
+                *daily = next;
+            }
+        }
+        let _ = writeln!(daily.file, "{}", format_line(entry));
+    }
+}
+
+fn format_line(entry: &LogEntry) -> String {
+    format!("{} {:>5} {}: {}", entry.timestamp, entry.level, entry.target, entry.message)
+}
+
+/// Open today's file for appending and delete all but the newest few.
+fn open_daily(dir: &Path, date: NaiveDate) -> std::io::Result<DailyFile> {
+    std::fs::create_dir_all(dir)?;
+    let file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(dir.join(format!("when-{}.log", date.format("%Y-%m-%d"))))?;
+
+    let mut logs: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with("when-") && n.ends_with(".log"))
+        })
+        .collect();
+    // Dated names sort oldest first
+    logs.sort();
+    for old in logs.iter().rev().skip(KEEP_LOG_FILES) {
+        let _ = std::fs::remove_file(old);
+    }
+
+    Ok(DailyFile {
+        dir: dir.to_path_buf(),
+        date,
+        file,
+    })
+}
+
+pub struct LogLayer {
+    store: Arc<LogStore>,
+}
+
+impl<S: Subscriber> Layer<S> for LogLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+        self.store.push(
+            *metadata.level(),
+            LogEntry {
+                timestamp: Local::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, false),
+                level: metadata.level().to_string(),
+                target: metadata.target().to_string(),
+                message: visitor.message + &visitor.fields,
+            },
+        );
+    }
+}
+
+/// The event's message followed by any other fields as `key=value`.
+#[derive(Default)]
+struct MessageVisitor {
+    message: String,
+    fields: String,
+}
+
+impl MessageVisitor {
+    fn add(&mut self, field: &Field, value: std::fmt::Arguments<'_>) {
+        if field.name() == "message" {
+            let _ = write!(self.message, "{value}");
+        } else {
+            let _ = write!(self.fields, " {}={}", field.name(), value);
+        }
+    }
+}
+
+impl Visit for MessageVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.add(field, format_args!("{value}"));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.add(field, format_args!("{value:?}"));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(message: &str, level: Level) -> (Level, LogEntry) {
+        (
+            level,
+            LogEntry {
+                timestamp: String::new(),
+                level: level.to_string(),
+                target: "when_lib".into(),
+                message: message.into(),
+            },
+        )
+    }
+
+    #[test]
+    fn recent_filters_by_severity_and_keeps_order() {
+        let store = LogStore::new();
+        for (message, level) in [("a", Level::INFO), ("b", Level::WARN), ("c", Level::DEBUG), ("d", Level::ERROR)] {
+            let (level, entry) = entry(message, level);
+            store.push(level, entry);
+        }
+        let messages = |entries: Vec<LogEntry>| entries.into_iter().map(|e| e.message).collect::<Vec<_>>();
+        assert_eq!(messages(store.recent(10, Some(Level::WARN))), vec!["b", "d"]);
+        assert_eq!(messages(store.recent(2, None)), vec!["c", "d"]);
+    }
+
+    #[test]
+    fn level_names_become_crate_directives() {
+        assert!(filter_for("debug").is_ok());
+        assert!(filter_for("when_lib=trace,librqbit=info").is_ok());
+        assert!(filter_for("loud").is_err());
+    }
+}
@@ -0,0 +1,193 @@
+// Magnet URI parsing, without any network access.
+//
+// Everything that needs an info hash or tracker list from a magnet goes
+// through `parse`, so the add path, RSS duplicate checks and the pre-add
+// preview all agree on what a magnet says.
+
+/// The parts of a magnet link we use.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Magnet {
+    /// Lowercase hex, whether the link used hex or base32
+    pub info_hash: String,
+    /// `dn`, decoded
+    pub name: Option<String>,
+    /// `tr`, decoded, in order, without duplicates
+    pub trackers: Vec<String>,
+    /// `ws`, decoded
+    pub web_seeds: Vec<String>,
+}
+
+/// Parse a `magnet:?` URI. Fails when it isn't a magnet, has no BitTorrent
+/// info hash, or a parameter is malformed.
+pub fn parse(uri: &str) -> Result<Magnet, String> {
+    let uri = uri.trim();
+    let query = uri
+        .get(..8)
+        .filter(|prefix| prefix.eq_ignore_ascii_case("magnet:?"))
+        .map(|_| &uri[8..])
+        .ok_or_else(|| "Not a magnet link".to_string())?;
+
+    let mut info_hash = None;
+    let mut name = None;
+    let mut trackers: Vec<String> = Vec::new();
+    let mut web_seeds = Vec::new();
+
+    for part in query.split('&').filter(|p| !p.is_empty()) {
+        let (key, raw) = part
+            .split_once('=')
+            .ok_or_else(|| format!("Malformed magnet parameter \"{part}\""))?;
+        // BEP 9 allows numbered keys such as tr.1
+        let key = key.split_once('.').map_or(key, |(base, _)| base);
+        // Spaces in names are often written as +, before percent-encoding
+        let value = if key == "dn" { decode(&raw.replace('+', " ")) } else { decode(raw) };
+        match key {
+            "xt" if info_hash.is_none() => {
+                if let Some(hash) = strip_prefix_ignore_case(&value, "urn:btih:") {
+                    info_hash = Some(normalize_hash(hash).ok_or_else(|| format!("Invalid info hash \"{hash}\""))?);
+                }
+            }
+            "dn" => name = Some(value).filter(|n| !n.trim().is_empty()),
+            "tr" if !trackers.contains(&value) => trackers.push(value),
+            "ws" => web_seeds.push(value),
+            _ => {}
+        }
+    }
+
+    Ok(Magnet {
+        info_hash: info_hash.ok_or_else(|| "Magnet link has no BitTorrent info hash".to_string())?,
+        name,
+        trackers,
+        web_seeds,
+    })
+}
+
+/// Just the info hash, skipping parameters that don't parse. Duplicate checks
+/// use this so a sloppy feed link still matches a torrent already seen.
+pub fn info_hash(uri: &str) -> Option<String> {
+    let (_, query) = uri.trim().split_once('?')?;
+    query.split('&').find_map(|part| {
+        let (key, raw) = part.split_once('=')?;
+        if key.split_once('.').map_or(key, |(base, _)| base) != "xt" {
+            return None;
+        }
+        let value = decode(raw);
+        strip_prefix_ignore_case(&value, "urn:btih:").and_then(normalize_hash)
+    })
+}
+
+/// Percent-decode, replacing bytes that aren't UTF-8; some feeds encode names
+/// in Latin-1.
+fn decode(value: &str) -> String {
+    String::from_utf8_lossy(&urlencoding::decode_binary(value.as_bytes())).into_owned()
+}
+
+fn strip_prefix_ignore_case<'a>(value: &'a str, prefix: &str) -> Option<&'a str> {
+    let head = value.get(..prefix.len())?;
+    head.eq_ignore_ascii_case(prefix).then(|| &value[prefix.len()..])
+}
+
+/// 40 hex digits or 32 base32 characters, as lowercase hex.
+fn normalize_hash(hash: &str) -> Option<String> {
+    match hash.len() {
+        40 if hash.chars().all(|c| c.is_ascii_hexdigit()) => Some(hash.to_lowercase()),
+        32 => base32_to_hex(hash),
+        _ => None,
+    }
+}
+
+/// Decode an RFC 4648 base32 string (no padding) into lowercase hex.
+fn base32_to_hex(input: &str) -> Option<String> {
+    let mut bits: u64 = 0;
+    let mut bit_count = 0;
+    let mut out = String::with_capacity(input.len() * 5 / 4);
+
+    for c in input.chars() {
+        let val = match c.to_ascii_uppercase() {
+            c @ 'A'..='Z' => c as u64 - 'A' as u64,
+            c @ '2'..='7' => c as u64 - '2' as u64 + 26,
+            _ => return None,
+        };
+        bits = (bits << 5) | val;
+        bit_count += 5;
+        if bit_count >= 8 {
+            bit_count -= 8;
+            out.push_str(&format!("{:02x}", (bits >> bit_count) & 0xff));
+        }
+    }
+
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEX: &str = "c12fe1c06bba254a9dc9f519b335aa7c1367a88a";
+
+    #[test]
+    fn hex_hashes_are_lowercased() {
+        let magnet = parse("magnet:?xt=urn:btih:C12FE1C06BBA254A9DC9F519B335AA7C1367A88A").unwrap();
+        assert_eq!(magnet.info_hash, HEX);
+        assert_eq!(parse(&format!("magnet:?xt=urn:btih:{HEX}")).unwrap().info_hash, HEX);
+    }
+
+    #[test]
+    fn base32_hashes_become_hex_in_either_case() {
+        assert_eq!(parse("magnet:?xt=urn:btih:YEX6DQDLXISUVHOJ6UM3GNNKPQJWPKEK").unwrap().info_hash, HEX);
+        assert_eq!(parse("magnet:?xt=urn:btih:yex6dqdlxisuvhoj6um3gnnkpqjwpkek").unwrap().info_hash, HEX);
+    }
+
+    #[test]
+    fn prefixes_are_case_insensitive() {
+        assert_eq!(parse(&format!("MAGNET:?xt=URN:BTIH:{HEX}")).unwrap().info_hash, HEX);
+    }
+
+    #[test]
+    fn names_are_url_decoded() {
+        let magnet = parse(&format!("magnet:?xt=urn:btih:{HEX}&dn=Big%20Buck%20Bunny%20%5B1080p%5D")).unwrap();
+        assert_eq!(magnet.name.as_deref(), Some("Big Buck Bunny [1080p]"));
+        let magnet = parse(&format!("magnet:?dn=Sintel+2010%2B&xt=urn:btih:{HEX}")).unwrap();
+        assert_eq!(magnet.name.as_deref(), Some("Sintel 2010+"));
+        assert_eq!(parse(&format!("magnet:?xt=urn:btih:{HEX}&dn=")).unwrap().name, None);
+    }
+
+    #[test]
+    fn every_tracker_and_web_seed_is_kept_once() {
+        let magnet = parse(&format!(
+            "magnet:?xt=urn:btih:{HEX}&tr=udp%3A%2F%2Fa.example%3A80&tr=https://b.example/announce\
+             &tr.1=udp%3A%2F%2Fa.example%3A80&ws=https%3A%2F%2Fseed.example%2Ffile"
+        ))
+        .unwrap();
+        assert_eq!(magnet.trackers, vec!["udp://a.example:80", "https://b.example/announce"]);
+        assert_eq!(magnet.web_seeds, vec!["https://seed.example/file"]);
+    }
+
+    #[test]
+    fn malformed_links_are_rejected() {
+        assert!(parse("https://example.com/file.torrent").is_err());
+        assert!(parse("magnet:").is_err());
+        assert!(parse("magnet:?dn=NoHash").is_err());
+        assert!(parse("magnet:?xt=urn:btih:tooshort").is_err());
+        assert!(parse("magnet:?xt=urn:btih:ZZZZZZZZZZZZZZZZZZZZZZZZZZZZZZZZZZZZZZZZ").is_err());
+        assert!(parse("magnet:?xt=urn:btih:11111111111111111111111111111111").is_err());
+        assert!(parse(&format!("magnet:?xt=urn:btih:{HEX}&junk")).is_err());
+    }
+
+    #[test]
+    fn the_lenient_hash_skips_malformed_parameters() {
+        assert_eq!(info_hash(&format!("magnet:?junk&xt=urn:btih:{HEX}&dn")).as_deref(), Some(HEX));
+        assert_eq!(info_hash("magnet:?xt=urn:btih:tooshort&dn=x"), None);
+    }
+
+    #[test]
+    fn names_that_arent_utf8_still_parse() {
+        let magnet = parse(&format!("magnet:?xt=urn:btih:{HEX}&dn=Caf%E9")).unwrap();
+        assert_eq!(magnet.name.as_deref(), Some("Caf\u{fffd}"));
+    }
+
+    #[test]
+    fn other_urn_types_are_skipped() {
+        let magnet = parse(&format!("magnet:?xt=urn:ed2k:abcdef&xt=urn:btih:{HEX}")).unwrap();
+        assert_eq!(magnet.info_hash, HEX);
+    }
+}
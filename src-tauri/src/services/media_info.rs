@@ -140,10 +140,42 @@ fn extract_title(name: &str, info: &MediaInfo) -> String {
     title
 }
 
+/// Whether `new` is a strictly better release than `old`: resolution first,
+/// then source tier. Unknown values rank lowest.
+pub fn is_better_release(new: &MediaInfo, old: &MediaInfo) -> bool {
+    let rank = |info: &MediaInfo| {
+        (
+            info.quality.map_or(0, Quality::rank),
+            info.source.map_or(0, MediaSource::rank),
+        )
+    };
+    rank(new) > rank(old)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_is_better_release() {
+        let better = |new: &str, old: &str| is_better_release(&parse(new), &parse(old));
+
+        assert!(better("Show.S01E01.720p.HDTV", "Show.S01E01.480p.HDTV"));
+        assert!(better("Show.S01E01.1080p.HDTV", "Show.S01E01.720p.BluRay"));
+        assert!(better("Show.S01E01.2160p.WEBRip", "Show.S01E01.1080p.BluRay"));
+        assert!(!better("Show.S01E01.720p.BluRay", "Show.S01E01.1080p.HDTV"));
+
+        // Same resolution: BluRay > WEB-DL > WEBRip > HDTV
+        assert!(better("Show.S01E01.1080p.WEB-DL", "Show.S01E01.1080p.WEBRip"));
+        assert!(better("Show.S01E01.1080p.BluRay", "Show.S01E01.1080p.WEB-DL"));
+        assert!(better("Show.S01E01.1080p.WEBRip", "Show.S01E01.1080p.HDTV"));
+        assert!(!better("Show.S01E01.1080p.WEB-DL", "Show.S01E01.1080p.BluRay"));
+
+        // Identical or unknown quality is never an upgrade
+        assert!(!better("Show.S01E01.1080p.WEB-DL", "Show.S01E01.1080p.WEB-DL"));
+        assert!(!better("Show.S01E01", "Show.S01E01.720p.HDTV"));
+    }
+
     #[test]
     fn test_parse_movie() {
         let info = parse("Movie.2024.1080p.BluRay.x264-GROUP");
@@ -1,24 +1,50 @@
 use std::collections::HashMap;
-use std::net::SocketAddr;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use std::path::PathBuf;
-use std::sync::Arc;
+use std::pin::Pin;
+use std::sync::{Arc, OnceLock};
+use std::sync::atomic::{AtomicBool, AtomicU16, Ordering};
+use std::task::{Context, Poll};
 use axum::{
     Router,
     body::Body,
-    extract::{Path, State as AxumState},
-    http::{HeaderMap, HeaderValue, StatusCode, header},
-    response::IntoResponse,
+    extract::{Path, Query, Request, State as AxumState},
+    http::{HeaderMap, HeaderValue, Method, StatusCode, header},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
     routing::get,
+    Json,
 };
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
 use tokio::sync::RwLock;
-use tokio::io::AsyncReadExt;
-use tower_http::cors::CorsLayer;
-use tracing::{info, error};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncSeek, AsyncSeekExt, ReadBuf};
+use tokio_util::io::ReaderStream;
+use tower_http::cors::{AllowOrigin, CorsLayer};
+use tracing::{info, error, warn};
 
-use crate::models::SubtitleData;
+use crate::models::{MediaServerCertInfo, SubtitleData};
+use crate::services::completed_history::{self, CompletedHistory};
+use crate::services::event_bus::EventBus;
+use crate::services::{media_tls, remote_bridge};
+use crate::services::thumbnails::Thumbnails;
+use crate::services::torrent_engine::{get_local_ip, stream_readiness};
+use crate::services::torrent_ids::TorrentIdMap;
+
+/// Origins of the app's own webviews.
+const APP_ORIGINS: [&str; 4] = [
+    "tauri://localhost",
+    "https://tauri.localhost",
+    "http://localhost",
+    "http://127.0.0.1",
+];
 
 /// Tokens expire after 1 hour.
 const TOKEN_TTL_SECS: u64 = 3600;
+/// Bind attempts made when the port is still held by a stopped server.
+const BIND_RETRIES: u32 = 10;
+/// Read size used when streaming file bodies.
+const STREAM_CHUNK_SIZE: usize = 64 * 1024;
 /// Cleanup runs every 10 minutes.
 const TOKEN_CLEANUP_INTERVAL_SECS: u64 = 600;
 
@@ -33,71 +59,177 @@ pub struct MediaServerState {
     pub torrent_session: Arc<RwLock<Option<Arc<librqbit::Session>>>>,
     pub current_subtitles: Arc<RwLock<Option<SubtitleData>>>,
     pub local_file_tokens: Arc<RwLock<HashMap<String, TokenEntry>>>,
+    pub torrent_ids: Arc<RwLock<TorrentIdMap>>,
+    pub thumbnails: Arc<Thumbnails>,
+    /// Finished downloads for /feed/completed.xml
+    pub completed_history: Arc<RwLock<CompletedHistory>>,
+    /// For media:stream-waiting events and remote commands; None in tests.
+    pub app_handle: Option<AppHandle>,
+    /// Events pushed to /ws clients
+    pub event_bus: Arc<EventBus>,
+    /// Access token required on /torrent/... routes.
+    pub stream_token: String,
+    pub require_stream_auth: bool,
+    /// Serve HTTPS with the certificate from services::media_tls
+    pub tls_enabled: bool,
 }
 
 pub struct MediaServerHandle {
-    pub port: u16,
+    port: AtomicU16,
+    bind_address: std::sync::RwLock<IpAddr>,
+    /// Random per-run token appended to stream and playlist URLs.
+    pub stream_token: String,
     shutdown_tx: Arc<RwLock<Option<tokio::sync::oneshot::Sender<()>>>>,
+    cleanup_started: AtomicBool,
+    /// Where the HTTPS certificate is kept
+    tls_dir: OnceLock<PathBuf>,
+    /// Certificate in use; None while serving plain HTTP
+    cert_info: std::sync::RwLock<Option<MediaServerCertInfo>>,
+}
+
+/// Parse a configured bind address, falling back to all interfaces.
+pub fn parse_bind_address(value: &str) -> IpAddr {
+    value.trim().parse().unwrap_or_else(|_| {
+        warn!("Invalid media server bind address '{}', using 0.0.0.0", value);
+        IpAddr::V4(Ipv4Addr::UNSPECIFIED)
+    })
 }
 
 impl MediaServerHandle {
-    pub fn new(port: u16) -> Self {
+    pub fn new(port: u16, bind_address: IpAddr) -> Self {
         Self {
-            port,
+            port: AtomicU16::new(port),
+            bind_address: std::sync::RwLock::new(bind_address),
+            stream_token: uuid::Uuid::new_v4().simple().to_string(),
             shutdown_tx: Arc::new(RwLock::new(None)),
+            cleanup_started: AtomicBool::new(false),
+            tls_dir: OnceLock::new(),
+            cert_info: std::sync::RwLock::new(None),
+        }
+    }
+
+    /// Where the HTTPS certificate is kept. Called once during setup.
+    pub fn set_tls_dir(&self, dir: PathBuf) {
+        let _ = self.tls_dir.set(dir);
+    }
+
+    /// The certificate being served, if the server is running HTTPS.
+    pub fn cert_info(&self) -> Option<MediaServerCertInfo> {
+        self.cert_info.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    pub fn port(&self) -> u16 {
+        self.port.load(Ordering::SeqCst)
+    }
+
+    pub fn bind_address(&self) -> IpAddr {
+        *self.bind_address.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Host to put in URLs handed to players: the bind address, or the LAN IP when bound to all interfaces.
+    pub fn advertised_host(&self) -> String {
+        let addr = self.bind_address();
+        if addr.is_unspecified() {
+            get_local_ip()
+        } else {
+            addr.to_string()
+        }
+    }
+
+    pub fn scheme(&self) -> &'static str {
+        if self.cert_info().is_some() {
+            "https"
+        } else {
+            "http"
+        }
+    }
+
+    /// Base URL (scheme, host and port) of the running server.
+    pub fn base_url(&self) -> String {
+        format!("{}://{}:{}", self.scheme(), self.advertised_host(), self.port())
+    }
+
+    /// Certificate for the current address, or None to serve plain HTTP.
+    /// Failing to make one falls back to HTTP rather than not serving at all.
+    fn tls_material(&self) -> Option<media_tls::TlsMaterial> {
+        let Some(dir) = self.tls_dir.get() else {
+            warn!("No certificate directory, serving media over HTTP");
+            return None;
+        };
+        let mut hosts = vec!["localhost".to_string(), "127.0.0.1".to_string()];
+        let advertised = self.advertised_host();
+        if !hosts.contains(&advertised) {
+            hosts.push(advertised);
+        }
+        match media_tls::load_or_generate(dir, &hosts) {
+            Ok(material) => Some(material),
+            Err(e) => {
+                error!("Media server certificate unavailable, serving over HTTP: {}", e);
+                None
+            }
         }
     }
 
     pub async fn start(&self, state: MediaServerState) {
-        let port = self.port;
+        let addr = SocketAddr::new(self.bind_address(), self.port());
         let shutdown_tx = self.shutdown_tx.clone();
 
+        // The Cast receiver fetches subtitle tracks cross-origin from its own web origin,
+        // so the VTT route accepts any origin; everything else stays restricted to the app.
         let cors = CorsLayer::new()
-            .allow_origin([
-                "tauri://localhost".parse().unwrap(),
-                "https://tauri.localhost".parse().unwrap(),
-                "http://localhost".parse().unwrap(),
-                "http://127.0.0.1".parse().unwrap(),
-            ])
+            .allow_origin(AllowOrigin::predicate(|origin: &HeaderValue, parts: &axum::http::request::Parts| {
+                parts.uri.path() == "/subtitles.vtt"
+                    || APP_ORIGINS.iter().any(|allowed| origin.as_bytes() == allowed.as_bytes())
+            }))
             .allow_methods(tower_http::cors::Any)
             .allow_headers(tower_http::cors::Any);
 
-        let app = Router::new()
-            .route("/torrent/{torrent_id}/stream/{file_idx}", get(stream_torrent))
-            .route("/torrent/{torrent_id}/playlist.m3u8", get(serve_playlist))
-            .route("/local/{token}", get(serve_local_file))
-            .route("/subtitles.vtt", get(serve_subtitles))
-            .route("/health", get(health_check))
-            .layer(cors)
-            .with_state(state.clone());
-
-        let addr = SocketAddr::from(([0, 0, 0, 0], port));
-        let listener = match tokio::net::TcpListener::bind(addr).await {
-            Ok(l) => l,
-            Err(e) => {
-                error!("Failed to bind media server to port {}: {}", port, e);
-                return;
+        let app = build_router(state.clone(), cors);
+
+        // A restart may race the previous listener's release, so retry briefly
+        let mut attempt = 0;
+        let listener = loop {
+            match tokio::net::TcpListener::bind(addr).await {
+                Ok(l) => break l,
+                Err(e) if attempt < BIND_RETRIES => {
+                    attempt += 1;
+                    warn!("Media server bind to {} failed ({}), retrying", addr, e);
+                    tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+                }
+                Err(e) => {
+                    error!("Failed to bind media server to {}: {}", addr, e);
+                    return;
+                }
             }
         };
 
-        info!("Media server listening on http://0.0.0.0:{}", port);
+        let tls = if state.tls_enabled { self.tls_material() } else { None };
+        *self.cert_info.write().unwrap_or_else(|e| e.into_inner()) = tls.as_ref().map(|m| m.info.clone());
+        info!("Media server listening on {}://{}", self.scheme(), addr);
 
         let (tx, rx) = tokio::sync::oneshot::channel::<()>();
         *shutdown_tx.write().await = Some(tx);
 
-        let tokens = state.local_file_tokens.clone();
-        tokio::spawn(async move {
-            loop {
-                tokio::time::sleep(std::time::Duration::from_secs(TOKEN_CLEANUP_INTERVAL_SECS)).await;
-                let mut map = tokens.write().await;
-                let before = map.len();
-                map.retain(|_, entry| entry.created_at.elapsed().as_secs() < TOKEN_TTL_SECS);
-                let removed = before - map.len();
-                if removed > 0 {
-                    info!("Expired {} local file token(s)", removed);
+        if !self.cleanup_started.swap(true, Ordering::SeqCst) {
+            let tokens = state.local_file_tokens.clone();
+            tokio::spawn(async move {
+                loop {
+                    tokio::time::sleep(std::time::Duration::from_secs(TOKEN_CLEANUP_INTERVAL_SECS)).await;
+                    let mut map = tokens.write().await;
+                    let before = map.len();
+                    map.retain(|_, entry| entry.created_at.elapsed().as_secs() < TOKEN_TTL_SECS);
+                    let removed = before - map.len();
+                    if removed > 0 {
+                        info!("Expired {} local file token(s)", removed);
+                    }
                 }
-            }
-        });
+            });
+        }
+
+        if let Some(tls) = tls {
+            serve_tls(listener, app, tls, rx).await;
+            return;
+        }
 
         tokio::spawn(async move {
             axum::serve(listener, app)
@@ -114,6 +246,153 @@ impl MediaServerHandle {
             let _ = tx.send(());
         }
     }
+
+    /// Stop the running server and start it again on a new address and port.
+    pub async fn restart(&self, state: MediaServerState, addr: IpAddr, port: u16) {
+        self.stop().await;
+        *self.bind_address.write().unwrap_or_else(|e| e.into_inner()) = addr;
+        self.port.store(port, Ordering::SeqCst);
+        self.start(state).await;
+    }
+}
+
+/// Serve `app` over HTTPS on an already bound listener until `shutdown` fires.
+async fn serve_tls(
+    listener: tokio::net::TcpListener,
+    app: Router,
+    tls: media_tls::TlsMaterial,
+    shutdown: tokio::sync::oneshot::Receiver<()>,
+) {
+    let config = match axum_server::tls_rustls::RustlsConfig::from_pem(tls.cert_pem, tls.key_pem).await {
+        Ok(config) => config,
+        Err(e) => {
+            error!("Invalid media server certificate: {}", e);
+            return;
+        }
+    };
+    let listener = match listener.into_std() {
+        Ok(listener) => listener,
+        Err(e) => {
+            error!("Media server listener error: {}", e);
+            return;
+        }
+    };
+
+    let handle = axum_server::Handle::new();
+    let stopper = handle.clone();
+    tokio::spawn(async move {
+        shutdown.await.ok();
+        stopper.graceful_shutdown(None);
+    });
+    tokio::spawn(async move {
+        axum_server::from_tcp_rustls(listener, config)
+            .handle(handle)
+            .serve(app.into_make_service())
+            .await
+            .unwrap_or_else(|e| error!("Media server error: {}", e));
+    });
+}
+
+/// Routes served by the media server. HEAD is registered alongside GET so players can probe size and type.
+fn build_router(state: MediaServerState, cors: CorsLayer) -> Router {
+    let torrent_routes = Router::new()
+        .route("/torrent/{torrent_id}/stream/{file_idx}", get(stream_torrent).head(stream_torrent))
+        .route("/torrent/{torrent_id}/playlist.m3u8", get(serve_playlist).head(serve_playlist))
+        .route("/torrent/{torrent_id}/ready/{file_idx}", get(stream_ready))
+        .route("/thumb/{torrent_id}/{file_idx}", get(serve_thumbnail))
+        .route_layer(middleware::from_fn_with_state(state.clone(), require_stream_token));
+    // Subscribed from other machines, so it takes the saved feed token
+    // rather than the per-run stream token
+    let feed_routes = Router::new()
+        .route("/feed/completed.xml", get(serve_completed_feed))
+        .route("/feed/torrent/{torrent_id}/playlist.m3u8", get(serve_feed_playlist).head(serve_feed_playlist))
+        .route("/feed/torrent/{torrent_id}/stream/{file_idx}", get(stream_torrent).head(stream_torrent))
+        .route_layer(middleware::from_fn_with_state(state.clone(), require_feed_token));
+    // The remote bridge can pause torrents and playback, so it needs the
+    // token even when streams don't
+    let remote_routes = Router::new()
+        .route("/ws", get(remote_bridge::ws_handler))
+        .route_layer(middleware::from_fn_with_state(state.clone(), require_remote_token));
+
+    Router::new()
+        .merge(torrent_routes)
+        .merge(feed_routes)
+        .merge(remote_routes)
+        .route("/local/{token}", get(serve_local_file).head(serve_local_file))
+        .route("/subtitles.vtt", get(serve_subtitles))
+        .route("/health", get(health_check))
+        .layer(cors)
+        .with_state(state)
+}
+
+/// Reject torrent routes without a valid `?token=` or `X-Stream-Token` when auth is required.
+async fn require_stream_token(
+    AxumState(state): AxumState<MediaServerState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if !state.require_stream_auth {
+        return next.run(request).await;
+    }
+
+    if has_token(&request, &state.stream_token) {
+        next.run(request).await
+    } else {
+        (StatusCode::UNAUTHORIZED, "Missing or invalid stream token").into_response()
+    }
+}
+
+/// Reject the completed feed and its enclosures without the feed token when
+/// auth is required.
+async fn require_feed_token(
+    AxumState(state): AxumState<MediaServerState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if !state.require_stream_auth {
+        return next.run(request).await;
+    }
+    let expected = state.completed_history.read().await.feed_token().to_string();
+    if !expected.is_empty() && has_token(&request, &expected) {
+        next.run(request).await
+    } else {
+        (StatusCode::UNAUTHORIZED, "Missing or invalid feed token").into_response()
+    }
+}
+
+/// Reject /ws without a valid token, whatever require_stream_auth says.
+async fn require_remote_token(
+    AxumState(state): AxumState<MediaServerState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if has_token(&request, &state.stream_token) {
+        next.run(request).await
+    } else {
+        (StatusCode::UNAUTHORIZED, "Missing or invalid stream token").into_response()
+    }
+}
+
+/// Whether the request carries `expected` in `X-Stream-Token` or `?token=`.
+fn has_token(request: &Request, expected: &str) -> bool {
+    let header_ok = request
+        .headers()
+        .get("x-stream-token")
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| tokens_match(v, expected));
+    let query_ok = request.uri().query().is_some_and(|q| {
+        q.split('&')
+            .filter_map(|pair| pair.strip_prefix("token="))
+            .any(|v| tokens_match(v, expected))
+    });
+    header_ok || query_ok
+}
+
+/// Compare tokens in time that doesn't depend on where they first differ, so
+/// response timing can't be used to guess one byte by byte.
+fn tokens_match(given: &str, expected: &str) -> bool {
+    given.len() == expected.len()
+        && given.bytes().zip(expected.bytes()).fold(0, |diff, (a, b)| diff | (a ^ b)) == 0
 }
 
 /// Parse a header value string, returning 500 on failure.
@@ -125,13 +404,51 @@ fn parse_header(value: &str) -> Result<HeaderValue, StatusCode> {
 }
 
 /// Build standard response headers for media streaming.
-fn build_media_headers(content_type: &str) -> Result<HeaderMap, StatusCode> {
+fn build_media_headers(content_type: &str, filename: &str) -> Result<HeaderMap, StatusCode> {
     let mut h = HeaderMap::new();
     h.insert(header::CONTENT_TYPE, parse_header(content_type)?);
     h.insert(header::ACCEPT_RANGES, parse_header("bytes")?);
+    let encoded = urlencoding::encode(filename);
+    h.insert(
+        header::CONTENT_DISPOSITION,
+        parse_header(&format!("inline; filename=\"{encoded}\"; filename*=UTF-8''{encoded}"))?,
+    );
+    Ok(h)
+}
+
+/// Headers for a full (range = None) or partial response, including Content-Length.
+fn build_stream_headers(
+    content_type: &str,
+    filename: &str,
+    file_length: u64,
+    range: Option<(u64, u64)>,
+) -> Result<HeaderMap, StatusCode> {
+    let mut h = build_media_headers(content_type, filename)?;
+    let content_length = match range {
+        Some((start, end)) => {
+            let cr = format!("bytes {}-{}/{}", start, end, file_length);
+            h.insert(header::CONTENT_RANGE, parse_header(&cr)?);
+            end - start + 1
+        }
+        None => file_length,
+    };
+    h.insert(header::CONTENT_LENGTH, parse_header(&content_length.to_string())?);
     Ok(h)
 }
 
+/// Parse the request's Range header, if any. Err holds a ready 416 response.
+fn requested_range(headers: &HeaderMap, file_length: u64) -> Result<Option<(u64, u64)>, Response> {
+    let Some(range_str) = headers.get(header::RANGE).and_then(|v| v.to_str().ok()) else {
+        return Ok(None);
+    };
+    parse_range(range_str, file_length).map(Some).map_err(|status| {
+        let cr = format!("bytes */{}", file_length);
+        let mut h = HeaderMap::new();
+        if let Ok(v) = parse_header(&cr) { h.insert(header::CONTENT_RANGE, v); }
+        (status, h, "Invalid range").into_response()
+    })
+}
+
 /// Validate and parse a Range header. Returns (start, end) or a 416 response.
 fn parse_range(range_str: &str, file_length: u64) -> Result<(u64, u64), StatusCode> {
     let range_str = range_str.trim_start_matches("bytes=");
@@ -162,12 +479,121 @@ fn parse_range(range_str: &str, file_length: u64) -> Result<(u64, u64), StatusCo
     Ok((start, end))
 }
 
+/// Payload of media:stream-waiting, sent when a torrent stream starts and
+/// stops waiting on pieces that aren't downloaded yet.
+#[derive(Debug, Clone, Serialize)]
+struct StreamWaiting {
+    torrent_id: usize,
+    file_index: usize,
+    waiting: bool,
+}
+
+/// Reader wrapper that calls `notify(true)` when a read has to wait and
+/// `notify(false)` once data arrives again. librqbit's file stream only
+/// returns Pending while the piece under it is still missing.
+struct StallNotify<R, F> {
+    inner: R,
+    waiting: bool,
+    notify: F,
+}
+
+impl<R, F> StallNotify<R, F> {
+    fn new(inner: R, notify: F) -> Self {
+        Self { inner, waiting: false, notify }
+    }
+}
+
+impl<R: AsyncRead + Unpin, F: FnMut(bool) + Unpin> AsyncRead for StallNotify<R, F> {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<std::io::Result<()>> {
+        let this = self.get_mut();
+        let result = Pin::new(&mut this.inner).poll_read(cx, buf);
+        if result.is_pending() != this.waiting {
+            this.waiting = result.is_pending();
+            (this.notify)(this.waiting);
+        }
+        result
+    }
+}
+
+impl<R: AsyncSeek + Unpin, F: Unpin> AsyncSeek for StallNotify<R, F> {
+    fn start_seek(self: Pin<&mut Self>, position: std::io::SeekFrom) -> std::io::Result<()> {
+        Pin::new(&mut self.get_mut().inner).start_seek(position)
+    }
+
+    fn poll_complete(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<u64>> {
+        Pin::new(&mut self.get_mut().inner).poll_complete(cx)
+    }
+}
+
+/// Stream `len` bytes starting at `start` without buffering the span in memory.
+/// A read error mid-stream ends the body early, so the client sees a truncated response.
+///
+/// The seek has to come before any read: librqbit's file stream moves its
+/// piece priority to wherever it's positioned, so seeking is what makes a
+/// player's jump to 70% fetch the pieces there first.
+async fn range_body<R>(mut reader: R, start: u64, len: u64) -> std::io::Result<Body>
+where
+    R: AsyncRead + AsyncSeek + Unpin + Send + 'static,
+{
+    reader.seek(std::io::SeekFrom::Start(start)).await?;
+    Ok(Body::from_stream(ReaderStream::with_capacity(reader.take(len), STREAM_CHUNK_SIZE)))
+}
+
+/// Whether enough of the file's start is downloaded to begin playback; see
+/// torrent_engine::stream_readiness.
+async fn stream_ready(
+    Path((torrent_id, file_idx)): Path<(usize, usize)>,
+    AxumState(state): AxumState<MediaServerState>,
+) -> Response {
+    let Some(session) = state.torrent_session.read().await.clone() else {
+        return (StatusCode::SERVICE_UNAVAILABLE, "Torrent session not ready").into_response();
+    };
+
+    let torrent_id = state.torrent_ids.read().await.resolve(torrent_id);
+    let Some(handle) = session.get(librqbit::api::TorrentIdOrHash::Id(torrent_id)) else {
+        return (StatusCode::NOT_FOUND, "Torrent not found").into_response();
+    };
+
+    match stream_readiness(&handle, file_idx) {
+        Some(readiness) => Json(readiness).into_response(),
+        None => (StatusCode::NOT_FOUND, "File index out of range").into_response(),
+    }
+}
+
+/// A thumbnail made by services::thumbnails; 404 until it exists.
+async fn serve_thumbnail(
+    Path((torrent_id, file_idx)): Path<(usize, usize)>,
+    AxumState(state): AxumState<MediaServerState>,
+) -> Response {
+    let Some(session) = state.torrent_session.read().await.clone() else {
+        return (StatusCode::SERVICE_UNAVAILABLE, "Torrent session not ready").into_response();
+    };
+
+    let torrent_id = state.torrent_ids.read().await.resolve(torrent_id);
+    let Some(handle) = session.get(librqbit::api::TorrentIdOrHash::Id(torrent_id)) else {
+        return (StatusCode::NOT_FOUND, "Torrent not found").into_response();
+    };
+    let Some(path) = state.thumbnails.path_for(&handle.info_hash().as_string(), file_idx) else {
+        return (StatusCode::NOT_FOUND, "Thumbnail not found").into_response();
+    };
+
+    match tokio::fs::read(&path).await {
+        Ok(bytes) => (
+            [(header::CONTENT_TYPE, "image/jpeg"), (header::CACHE_CONTROL, "max-age=86400")],
+            bytes,
+        )
+            .into_response(),
+        Err(_) => (StatusCode::NOT_FOUND, "Thumbnail not found").into_response(),
+    }
+}
+
 async fn health_check() -> &'static str {
     "ok"
 }
 
 async fn stream_torrent(
-    Path((torrent_id, file_idx)): Path<(usize, usize)>,
+    method: Method,
+    Path((external_id, file_idx)): Path<(usize, usize)>,
     AxumState(state): AxumState<MediaServerState>,
     headers: HeaderMap,
 ) -> impl IntoResponse {
@@ -182,6 +608,7 @@ async fn stream_torrent(
         }
     };
 
+    let torrent_id = state.torrent_ids.read().await.resolve(external_id);
     let handle = match session.get(librqbit::api::TorrentIdOrHash::Id(torrent_id)) {
         Some(h) => h,
         None => {
@@ -216,6 +643,22 @@ async fn stream_torrent(
         .first_raw()
         .unwrap_or("application/octet-stream");
 
+    let display_name = filename.rsplit('/').next().unwrap_or(filename);
+
+    let range = match requested_range(&headers, file_length) {
+        Ok(r) => r,
+        Err(response) => return response,
+    };
+    let status = if range.is_some() { StatusCode::PARTIAL_CONTENT } else { StatusCode::OK };
+    let response_headers = match build_stream_headers(content_type, display_name, file_length, range) {
+        Ok(h) => h,
+        Err(s) => return (s, "Header error").into_response(),
+    };
+
+    if method == Method::HEAD {
+        return (status, response_headers).into_response();
+    }
+
     let stream = match handle.clone().stream(file_idx) {
         Ok(s) => s,
         Err(e) => {
@@ -223,76 +666,36 @@ async fn stream_torrent(
                 .into_response();
         }
     };
+    let app_handle = state.app_handle.clone();
+    let stream = StallNotify::new(stream, move |waiting| {
+        if let Some(app) = &app_handle {
+            let _ = app.emit(
+                "media:stream-waiting",
+                StreamWaiting { torrent_id: external_id, file_index: file_idx, waiting },
+            );
+        }
+    });
 
-    let range_header = headers.get(header::RANGE).and_then(|v| v.to_str().ok());
-
-    match range_header {
-        Some(range_str) => {
-            let (start, end) = match parse_range(range_str, file_length) {
-                Ok(r) => r,
-                Err(status) => {
-                    let cr = format!("bytes */{}", file_length);
-                    let mut h = HeaderMap::new();
-                    if let Ok(v) = parse_header(&cr) { h.insert(header::CONTENT_RANGE, v); }
-                    return (status, h, "Invalid range").into_response();
-                }
-            };
-
-            let chunk_size = end - start + 1;
-
-            use tokio::io::AsyncSeekExt;
-            let mut stream = stream;
-            if let Err(e) = stream.seek(std::io::SeekFrom::Start(start)).await {
-                return (StatusCode::INTERNAL_SERVER_ERROR, format!("Seek error: {e}"))
-                    .into_response();
-            }
-
-            let mut buf = vec![0u8; chunk_size as usize];
-            match stream.read_exact(&mut buf).await {
-                Ok(_) => {
-                    let mut response_headers = match build_media_headers(content_type) {
-                        Ok(h) => h,
-                        Err(s) => return (s, "Header error").into_response(),
-                    };
-                    let cr = format!("bytes {}-{}/{}", start, end, file_length);
-                    match parse_header(&cr) {
-                        Ok(v) => { response_headers.insert(header::CONTENT_RANGE, v); }
-                        Err(s) => return (s, "Header error").into_response(),
-                    }
-                    match parse_header(&chunk_size.to_string()) {
-                        Ok(v) => { response_headers.insert(header::CONTENT_LENGTH, v); }
-                        Err(s) => return (s, "Header error").into_response(),
-                    }
-
-                    (StatusCode::PARTIAL_CONTENT, response_headers, buf).into_response()
-                }
+    match range {
+        Some((start, end)) => {
+            match range_body(stream, start, end - start + 1).await {
+                Ok(body) => (status, response_headers, body).into_response(),
                 Err(e) => {
-                    error!("Error reading torrent file: {}", e);
-                    (StatusCode::INTERNAL_SERVER_ERROR, format!("Read error: {e}"))
+                    error!("Error seeking torrent file: {}", e);
+                    (StatusCode::INTERNAL_SERVER_ERROR, format!("Seek error: {e}"))
                         .into_response()
                 }
             }
         }
         None => {
-            let stream = stream;
-            let reader = tokio_util::io::ReaderStream::new(stream);
-            let body = Body::from_stream(reader);
-
-            let mut response_headers = match build_media_headers(content_type) {
-                Ok(h) => h,
-                Err(s) => return (s, "Header error").into_response(),
-            };
-            match parse_header(&file_length.to_string()) {
-                Ok(v) => { response_headers.insert(header::CONTENT_LENGTH, v); }
-                Err(s) => return (s, "Header error").into_response(),
-            }
-
-            (StatusCode::OK, response_headers, body).into_response()
+            let reader = ReaderStream::with_capacity(stream, STREAM_CHUNK_SIZE);
+            (status, response_headers, Body::from_stream(reader)).into_response()
         }
     }
 }
 
 async fn serve_local_file(
+    method: Method,
     Path(token): Path<String>,
     AxumState(state): AxumState<MediaServerState>,
     headers: HeaderMap,
@@ -325,77 +728,39 @@ async fn serve_local_file(
         .first_raw()
         .unwrap_or("application/octet-stream");
 
-    let range_header = headers.get(header::RANGE).and_then(|v| v.to_str().ok());
-
-    match range_header {
-        Some(range_str) => {
-            let (start, end) = match parse_range(range_str, file_length) {
-                Ok(r) => r,
-                Err(status) => {
-                    let cr = format!("bytes */{}", file_length);
-                    let mut h = HeaderMap::new();
-                    if let Ok(v) = parse_header(&cr) { h.insert(header::CONTENT_RANGE, v); }
-                    return (status, h, "Invalid range").into_response();
-                }
-            };
-
-            let chunk_size = end - start + 1;
-
-            use tokio::io::AsyncSeekExt;
-            let mut file = match tokio::fs::File::open(&file_path).await {
-                Ok(f) => f,
-                Err(e) => {
-                    return (StatusCode::INTERNAL_SERVER_ERROR, format!("Open error: {e}"))
-                        .into_response();
-                }
-            };
-
-            if let Err(e) = file.seek(std::io::SeekFrom::Start(start)).await {
-                return (StatusCode::INTERNAL_SERVER_ERROR, format!("Seek error: {e}"))
-                    .into_response();
-            }
-
-            let mut buf = vec![0u8; chunk_size as usize];
-            if let Err(e) = file.read_exact(&mut buf).await {
-                return (StatusCode::INTERNAL_SERVER_ERROR, format!("Read error: {e}"))
-                    .into_response();
-            }
+    let range = match requested_range(&headers, file_length) {
+        Ok(r) => r,
+        Err(response) => return response,
+    };
+    let status = if range.is_some() { StatusCode::PARTIAL_CONTENT } else { StatusCode::OK };
+    let response_headers = match build_stream_headers(content_type, &filename, file_length, range) {
+        Ok(h) => h,
+        Err(s) => return (s, "Header error").into_response(),
+    };
 
-            let mut response_headers = match build_media_headers(content_type) {
-                Ok(h) => h,
-                Err(s) => return (s, "Header error").into_response(),
-            };
-            let cr = format!("bytes {}-{}/{}", start, end, file_length);
-            match parse_header(&cr) {
-                Ok(v) => { response_headers.insert(header::CONTENT_RANGE, v); }
-                Err(s) => return (s, "Header error").into_response(),
-            }
-            match parse_header(&chunk_size.to_string()) {
-                Ok(v) => { response_headers.insert(header::CONTENT_LENGTH, v); }
-                Err(s) => return (s, "Header error").into_response(),
-            }
+    if method == Method::HEAD {
+        return (status, response_headers).into_response();
+    }
 
-            (StatusCode::PARTIAL_CONTENT, response_headers, buf).into_response()
+    let file = match tokio::fs::File::open(&file_path).await {
+        Ok(f) => f,
+        Err(e) => {
+            return (StatusCode::INTERNAL_SERVER_ERROR, format!("Open error: {e}"))
+                .into_response();
         }
-        None => {
-            match tokio::fs::read(&file_path).await {
-                Ok(data) => {
-                    let mut response_headers = match build_media_headers(content_type) {
-                        Ok(h) => h,
-                        Err(s) => return (s, "Header error").into_response(),
-                    };
-                    match parse_header(&file_length.to_string()) {
-                        Ok(v) => { response_headers.insert(header::CONTENT_LENGTH, v); }
-                        Err(s) => return (s, "Header error").into_response(),
-                    }
+    };
 
-                    (StatusCode::OK, response_headers, data).into_response()
-                }
-                Err(e) => {
-                    (StatusCode::INTERNAL_SERVER_ERROR, format!("Read error: {e}"))
-                        .into_response()
-                }
+    match range {
+        Some((start, end)) => match range_body(file, start, end - start + 1).await {
+            Ok(body) => (status, response_headers, body).into_response(),
+            Err(e) => {
+                (StatusCode::INTERNAL_SERVER_ERROR, format!("Seek error: {e}"))
+                    .into_response()
             }
+        },
+        None => {
+            let reader = ReaderStream::with_capacity(file, STREAM_CHUNK_SIZE);
+            (status, response_headers, Body::from_stream(reader)).into_response()
         }
     }
 }
@@ -411,6 +776,8 @@ async fn serve_subtitles(
                 Ok(v) => { headers.insert(header::CONTENT_TYPE, v); }
                 Err(s) => return (s, "Header error").into_response(),
             }
+            // Offsets change the content behind the same URL
+            headers.insert(header::CACHE_CONTROL, HeaderValue::from_static("no-cache"));
             (StatusCode::OK, headers, data.vtt_content.clone()).into_response()
         }
         None => (StatusCode::NOT_FOUND, "No subtitles loaded").into_response(),
@@ -420,7 +787,23 @@ async fn serve_subtitles(
 async fn serve_playlist(
     Path(torrent_id): Path<usize>,
     AxumState(state): AxumState<MediaServerState>,
-) -> impl IntoResponse {
+) -> Response {
+    let token = state.stream_token.clone();
+    playlist_response(&state, torrent_id, |idx| format!("/torrent/{torrent_id}/stream/{idx}?token={token}")).await
+}
+
+/// Playlist linked from the completed feed. Its streams go through the feed
+/// routes so subscribers never see the stream token.
+async fn serve_feed_playlist(
+    Path(torrent_id): Path<usize>,
+    AxumState(state): AxumState<MediaServerState>,
+) -> Response {
+    let token = state.completed_history.read().await.feed_token().to_string();
+    playlist_response(&state, torrent_id, |idx| format!("/feed/torrent/{torrent_id}/stream/{idx}?token={token}")).await
+}
+
+/// M3U8 of a torrent's playable files, each linked by `stream_url(file_idx)`.
+async fn playlist_response(state: &MediaServerState, torrent_id: usize, stream_url: impl Fn(usize) -> String) -> Response {
     let session = {
         let guard = state.torrent_session.read().await;
         match guard.as_ref() {
@@ -432,6 +815,7 @@ async fn serve_playlist(
         }
     };
 
+    let torrent_id = state.torrent_ids.read().await.resolve(torrent_id);
     let handle = match session.get(librqbit::api::TorrentIdOrHash::Id(torrent_id)) {
         Some(h) => h,
         None => {
@@ -478,7 +862,7 @@ async fn serve_playlist(
         // Use -1 for unknown duration
         let display_name = name.rsplit('/').next().unwrap_or(&name);
         playlist.push_str(&format!("#EXTINF:-1,{}\n", display_name));
-        playlist.push_str(&format!("/torrent/{}/stream/{}\n", torrent_id, idx));
+        playlist.push_str(&format!("{}\n", stream_url(idx)));
         let _ = duration_bytes; // silence unused warning
     }
 
@@ -490,3 +874,390 @@ async fn serve_playlist(
 
     (StatusCode::OK, headers, playlist).into_response()
 }
+
+/// Entries in the completed feed when `?limit=` isn't given.
+const FEED_DEFAULT_LIMIT: usize = 50;
+
+#[derive(Deserialize)]
+struct FeedQuery {
+    limit: Option<usize>,
+}
+
+/// RSS 2.0 feed of the latest finished downloads. Items whose torrent is
+/// still in the session link to its playlist.
+async fn serve_completed_feed(
+    AxumState(state): AxumState<MediaServerState>,
+    Query(query): Query<FeedQuery>,
+    headers: HeaderMap,
+) -> Response {
+    let limit = query.limit.unwrap_or(FEED_DEFAULT_LIMIT);
+    let entries = state.completed_history.read().await.page(0, limit).items;
+
+    let session = state.torrent_session.read().await.clone();
+    let by_hash: HashMap<String, usize> = match session {
+        Some(session) => session.with_torrents(|torrents| {
+            torrents
+                .map(|(id, handle)| (handle.info_hash().as_string(), id))
+                .collect()
+        }),
+        None => HashMap::new(),
+    };
+    let torrent_ids = state.torrent_ids.read().await;
+
+    let host = headers
+        .get(header::HOST)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("localhost");
+    let base = format!("{}://{}", if state.tls_enabled { "https" } else { "http" }, host);
+    // Enclosures are signed with the feed token, never the stream token
+    let token = if state.require_stream_auth {
+        format!("?token={}", state.completed_history.read().await.feed_token())
+    } else {
+        String::new()
+    };
+
+    let xml = completed_history::render_feed(&entries, &format!("{base}/"), |entry| {
+        by_hash.get(&entry.info_hash).map(|&id| {
+            format!("{base}/feed/torrent/{}/playlist.m3u8{token}", torrent_ids.external(id))
+        })
+    });
+    (
+        [(header::CONTENT_TYPE, "application/rss+xml; charset=utf-8")],
+        xml,
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tower::ServiceExt;
+
+    fn test_state() -> MediaServerState {
+        MediaServerState {
+            torrent_session: Arc::new(RwLock::new(None)),
+            current_subtitles: Arc::new(RwLock::new(None)),
+            local_file_tokens: Arc::new(RwLock::new(HashMap::new())),
+            torrent_ids: Arc::new(RwLock::new(TorrentIdMap::default())),
+            thumbnails: Arc::new(Thumbnails::default()),
+            completed_history: Arc::new(RwLock::new(CompletedHistory::default())),
+            app_handle: None,
+            event_bus: Arc::new(EventBus::default()),
+            stream_token: "secret".to_string(),
+            require_stream_auth: true,
+            tls_enabled: false,
+        }
+    }
+
+    async fn router_with_file(contents: &[u8]) -> (Router, String, PathBuf) {
+        let path = std::env::temp_dir().join(format!("when-media-test-{}.mp4", uuid::Uuid::new_v4()));
+        std::fs::write(&path, contents).unwrap();
+
+        let state = test_state();
+        let token = "test-token".to_string();
+        state.local_file_tokens.write().await.insert(
+            token.clone(),
+            TokenEntry { path: path.to_string_lossy().to_string(), created_at: std::time::Instant::now() },
+        );
+
+        (build_router(state, CorsLayer::new()), token, path)
+    }
+
+    #[tokio::test]
+    async fn test_head_local_file_returns_headers_only() {
+        let (app, token, path) = router_with_file(b"0123456789").await;
+
+        let response = app
+            .oneshot(Request::head(format!("/local/{token}")).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_LENGTH], "10");
+        assert_eq!(response.headers()[header::ACCEPT_RANGES], "bytes");
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "video/mp4");
+        let disposition = response.headers()[header::CONTENT_DISPOSITION].to_str().unwrap();
+        assert!(disposition.starts_with("inline; filename=\"when-media-test-"));
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert!(body.is_empty());
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[tokio::test]
+    async fn test_get_local_file_range() {
+        let (app, token, path) = router_with_file(b"0123456789").await;
+
+        let response = app
+            .oneshot(
+                Request::get(format!("/local/{token}"))
+                    .header(header::RANGE, "bytes=2-5")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+        assert_eq!(response.headers()[header::CONTENT_RANGE], "bytes 2-5/10");
+        assert_eq!(response.headers()[header::CONTENT_LENGTH], "4");
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(&body[..], b"2345");
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[tokio::test]
+    async fn test_head_local_file_range() {
+        let (app, token, path) = router_with_file(b"0123456789").await;
+
+        let response = app
+            .oneshot(
+                Request::head(format!("/local/{token}"))
+                    .header(header::RANGE, "bytes=-3")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+        assert_eq!(response.headers()[header::CONTENT_RANGE], "bytes 7-9/10");
+        assert_eq!(response.headers()[header::CONTENT_LENGTH], "3");
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[tokio::test]
+    async fn test_torrent_routes_require_token() {
+        let app = build_router(test_state(), CorsLayer::new());
+
+        let response = app
+            .clone()
+            .oneshot(Request::get("/torrent/0/stream/0").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        // A valid token gets past auth; no session is running, so the handler answers 503
+        let response = app
+            .clone()
+            .oneshot(Request::get("/torrent/0/stream/0?token=secret").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+        let response = app
+            .oneshot(
+                Request::get("/torrent/0/playlist.m3u8")
+                    .header("x-stream-token", "secret")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    #[tokio::test]
+    async fn test_remote_bridge_requires_token_even_without_stream_auth() {
+        let state = MediaServerState { require_stream_auth: false, ..test_state() };
+        let app = build_router(state, CorsLayer::new());
+
+        let response = app
+            .clone()
+            .oneshot(Request::get("/ws").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        // Past auth, a plain GET isn't a WebSocket upgrade and is turned down by the handler instead
+        let response = app
+            .oneshot(Request::get("/ws?token=secret").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_ne!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[test]
+    fn tokens_compare_whole() {
+        assert!(tokens_match("secret", "secret"));
+        assert!(!tokens_match("secreT", "secret"));
+        assert!(!tokens_match("secret2", "secret"));
+        assert!(!tokens_match("", "secret"));
+    }
+
+    #[tokio::test]
+    async fn test_completed_feed_requires_token() {
+        let state = test_state();
+        state.completed_history.write().await.ensure_feed_token();
+        let feed_token = state.completed_history.read().await.feed_token().to_string();
+        state.completed_history.write().await.record(crate::models::CompletedEntry {
+            info_hash: "abc".into(),
+            name: "Show S01E01".into(),
+            total_bytes: 10,
+            completed_at: "2026-01-02T10:00:00+00:00".into(),
+        });
+        let app = build_router(state, CorsLayer::new());
+
+        let response = app
+            .clone()
+            .oneshot(Request::get("/feed/completed.xml").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        // The per-run stream token isn't enough
+        let response = app
+            .clone()
+            .oneshot(Request::get("/feed/completed.xml?token=secret").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = app
+            .oneshot(
+                Request::get(format!("/feed/completed.xml?token={feed_token}&limit=10"))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "application/rss+xml; charset=utf-8");
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let xml = String::from_utf8(body.to_vec()).unwrap();
+        assert!(xml.contains("<title>Show S01E01</title>"));
+        // The torrent isn't in a session, so there's nothing to link to
+        assert!(!xml.contains("<enclosure"));
+    }
+
+    #[tokio::test]
+    async fn test_feed_enclosures_take_the_feed_token() {
+        let state = test_state();
+        state.completed_history.write().await.ensure_feed_token();
+        let feed_token = state.completed_history.read().await.feed_token().to_string();
+        let app = build_router(state, CorsLayer::new());
+
+        for path in ["/feed/torrent/0/playlist.m3u8", "/feed/torrent/0/stream/0"] {
+            let response = app
+                .clone()
+                .oneshot(Request::get(format!("{path}?token=secret")).body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::UNAUTHORIZED, "{path}");
+
+            // Past auth, there's no session to serve from
+            let response = app
+                .clone()
+                .oneshot(Request::get(format!("{path}?token={feed_token}")).body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE, "{path}");
+        }
+    }
+
+    /// Zero-filled reader that records the largest read buffer it was handed.
+    struct TrackingReader {
+        pos: u64,
+        len: u64,
+        max_read: Arc<std::sync::atomic::AtomicUsize>,
+    }
+
+    impl AsyncRead for TrackingReader {
+        fn poll_read(
+            mut self: std::pin::Pin<&mut Self>,
+            _cx: &mut std::task::Context<'_>,
+            buf: &mut tokio::io::ReadBuf<'_>,
+        ) -> std::task::Poll<std::io::Result<()>> {
+            self.max_read.fetch_max(buf.remaining(), std::sync::atomic::Ordering::Relaxed);
+            let n = (buf.remaining() as u64).min(self.len - self.pos) as usize;
+            buf.put_slice(&vec![0u8; n]);
+            self.pos += n as u64;
+            std::task::Poll::Ready(Ok(()))
+        }
+    }
+
+    impl AsyncSeek for TrackingReader {
+        fn start_seek(mut self: std::pin::Pin<&mut Self>, position: std::io::SeekFrom) -> std::io::Result<()> {
+            if let std::io::SeekFrom::Start(p) = position {
+                self.pos = p;
+            }
+            Ok(())
+        }
+
+        fn poll_complete(
+            self: std::pin::Pin<&mut Self>,
+            _cx: &mut std::task::Context<'_>,
+        ) -> std::task::Poll<std::io::Result<u64>> {
+            std::task::Poll::Ready(Ok(self.pos))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_large_range_is_streamed_in_chunks() {
+        let max_read = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let reader = TrackingReader { pos: 0, len: 1 << 40, max_read: max_read.clone() };
+
+        // 64 MiB out of a 1 TiB "file"
+        let len = 64 * 1024 * 1024;
+        let body = range_body(reader, 1 << 30, len).await.unwrap();
+        let bytes = axum::body::to_bytes(body, usize::MAX).await.unwrap();
+
+        assert_eq!(bytes.len() as u64, len);
+        assert!(max_read.load(std::sync::atomic::Ordering::Relaxed) <= STREAM_CHUNK_SIZE);
+    }
+
+    /// Reader that logs each seek and read, and has no data for its first read,
+    /// like a torrent stream landing on a piece that isn't downloaded yet.
+    struct PendingOnceReader {
+        ops: Arc<std::sync::Mutex<Vec<String>>>,
+        pending: bool,
+    }
+
+    impl AsyncRead for PendingOnceReader {
+        fn poll_read(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut ReadBuf<'_>,
+        ) -> Poll<std::io::Result<()>> {
+            self.ops.lock().unwrap().push("read".into());
+            if self.pending {
+                self.pending = false;
+                cx.waker().wake_by_ref();
+                return Poll::Pending;
+            }
+            buf.put_slice(b"data");
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    impl AsyncSeek for PendingOnceReader {
+        fn start_seek(self: Pin<&mut Self>, position: std::io::SeekFrom) -> std::io::Result<()> {
+            self.ops.lock().unwrap().push(format!("seek {position:?}"));
+            Ok(())
+        }
+
+        fn poll_complete(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<std::io::Result<u64>> {
+            Poll::Ready(Ok(0))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_range_seeks_before_reading_and_reports_waits() {
+        let ops = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let reader = PendingOnceReader { ops: ops.clone(), pending: true };
+        let waits = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let seen = waits.clone();
+        let reader = StallNotify::new(reader, move |waiting| seen.lock().unwrap().push(waiting));
+
+        let body = range_body(reader, 700, 4).await.unwrap();
+        let bytes = axum::body::to_bytes(body, usize::MAX).await.unwrap();
+
+        assert_eq!(&bytes[..], b"data");
+        let ops = ops.lock().unwrap();
+        assert_eq!(ops[0], "seek Start(700)");
+        assert!(ops[1..].iter().all(|op| op == "read"));
+        assert_eq!(*waits.lock().unwrap(), vec![true, false]);
+    }
+}
@@ -0,0 +1,123 @@
+// Self-signed certificate for serving media over HTTPS.
+//
+// Made on first use and kept under app_data_dir/tls, next to a small JSON
+// file with its fingerprint, expiry and hosts, since rcgen can't read a
+// certificate back. A new one is made when the old one is about to expire or
+// doesn't cover the address players are given.
+
+use std::path::Path;
+
+use chrono::{DateTime, Datelike, Duration, Utc};
+use sha2::{Digest, Sha256};
+use tracing::info;
+
+use crate::models::MediaServerCertInfo;
+
+const CERT_FILE: &str = "media_cert.pem";
+const KEY_FILE: &str = "media_key.pem";
+const INFO_FILE: &str = "media_cert.json";
+
+/// Validity of a new certificate.
+const VALID_DAYS: i64 = 365;
+
+/// Certificates this close to expiring are replaced.
+const RENEW_BEFORE_DAYS: i64 = 7;
+
+pub struct TlsMaterial {
+    pub cert_pem: Vec<u8>,
+    pub key_pem: Vec<u8>,
+    pub info: MediaServerCertInfo,
+}
+
+/// The saved certificate, or a new one when there's none, it's about to
+/// expire, or it doesn't cover every entry in `hosts`.
+pub fn load_or_generate(dir: &Path, hosts: &[String]) -> Result<TlsMaterial, String> {
+    if let Some(material) = load(dir) {
+        if usable(&material.info, hosts, Utc::now()) {
+            return Ok(material);
+        }
+        info!("Media server certificate is expiring or missing a host, making a new one");
+    }
+    generate(dir, hosts)
+}
+
+fn usable(info: &MediaServerCertInfo, hosts: &[String], now: DateTime<Utc>) -> bool {
+    let fresh = DateTime::parse_from_rfc3339(&info.not_after)
+        .is_ok_and(|not_after| now < not_after.with_timezone(&Utc) - Duration::days(RENEW_BEFORE_DAYS));
+    fresh && hosts.iter().all(|host| info.hosts.contains(host))
+}
+
+fn load(dir: &Path) -> Option<TlsMaterial> {
+    let cert_pem = std::fs::read(dir.join(CERT_FILE)).ok()?;
+    let key_pem = std::fs::read(dir.join(KEY_FILE)).ok()?;
+    let info = serde_json::from_slice(&std::fs::read(dir.join(INFO_FILE)).ok()?).ok()?;
+    Some(TlsMaterial { cert_pem, key_pem, info })
+}
+
+fn generate(dir: &Path, hosts: &[String]) -> Result<TlsMaterial, String> {
+    let today = Utc::now().date_naive();
+    let expires = today + Duration::days(VALID_DAYS);
+
+    // Entries that parse as IPs become IP SANs, the rest DNS names
+    let mut params = rcgen::CertificateParams::new(hosts.to_vec()).map_err(|e| e.to_string())?;
+    params.distinguished_name.push(rcgen::DnType::CommonName, "When media server");
+    params.not_before = rcgen::date_time_ymd(today.year(), today.month() as u8, today.day() as u8);
+    params.not_after = rcgen::date_time_ymd(expires.year(), expires.month() as u8, expires.day() as u8);
+    let key_pair = rcgen::KeyPair::generate().map_err(|e| e.to_string())?;
+    let cert = params.self_signed(&key_pair).map_err(|e| e.to_string())?;
+
+    let info = MediaServerCertInfo {
+        fingerprint_sha256: fingerprint(cert.der()),
+        not_after: expires.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc().to_rfc3339(),
+        hosts: hosts.to_vec(),
+    };
+    let material = TlsMaterial {
+        cert_pem: cert.pem().into_bytes(),
+        key_pem: key_pair.serialize_pem().into_bytes(),
+        info,
+    };
+
+    std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    std::fs::write(dir.join(CERT_FILE), &material.cert_pem).map_err(|e| e.to_string())?;
+    std::fs::write(dir.join(KEY_FILE), &material.key_pem).map_err(|e| e.to_string())?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = std::fs::set_permissions(dir.join(KEY_FILE), std::fs::Permissions::from_mode(0o600));
+    }
+    let info_json = serde_json::to_vec_pretty(&material.info).map_err(|e| e.to_string())?;
+    std::fs::write(dir.join(INFO_FILE), info_json).map_err(|e| e.to_string())?;
+
+    info!(fingerprint = %material.info.fingerprint_sha256, "Generated media server certificate");
+    Ok(material)
+}
+
+/// "AB:CD:..." form shown by browsers and OS certificate dialogs.
+fn fingerprint(der: &[u8]) -> String {
+    Sha256::digest(der)
+        .iter()
+        .map(|b| format!("{b:02X}"))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn certificates_are_replaced_near_expiry_or_when_a_host_is_missing() {
+        let info = MediaServerCertInfo {
+            fingerprint_sha256: String::new(),
+            not_after: "2026-06-30T00:00:00+00:00".into(),
+            hosts: vec!["192.168.1.10".into(), "localhost".into()],
+        };
+        let now = DateTime::parse_from_rfc3339("2026-06-01T00:00:00+00:00").unwrap().with_timezone(&Utc);
+        let host = |h: &str| vec![h.to_string()];
+
+        assert!(usable(&info, &host("192.168.1.10"), now));
+        assert!(!usable(&info, &host("192.168.1.22"), now));
+        assert!(!usable(&info, &host("localhost"), now + Duration::days(25)));
+        assert_eq!(fingerprint(b""), "E3:B0:C4:42:98:FC:1C:14:9A:FB:F4:C8:99:6F:B9:24:27:AE:41:E4:64:9B:93:4C:A4:95:99:1B:78:52:B8:55");
+    }
+}
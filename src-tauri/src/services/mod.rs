@@ -1,12 +1,46 @@
 pub mod torrent_engine;
+pub mod torrent_ids;
+pub mod torrent_import;
+pub mod magnet;
+pub mod opened_items;
+pub mod torrent_labels;
+pub mod display_names;
+pub mod torrent_outputs;
+pub mod file_order;
+pub mod torrent_verify;
+pub mod torrent_creator;
+pub mod web_seeds;
+pub mod tracker_scrape;
 pub mod media_server;
+pub mod media_tls;
+pub mod remote_bridge;
 pub mod chromecast_discovery;
 pub mod chromecast_device;
+pub mod cast_group;
+pub mod airplay_device;
+pub mod device_connection;
+pub mod cast_channel;
 pub mod subtitle_handler;
 pub mod opensub_client;
 pub mod subtitle_search;
+pub mod thumbnails;
 pub mod folder_watcher;
 pub mod rss;
+pub mod source_metadata;
 pub mod media_info;
+pub mod episode;
 pub mod subtitle_scorer;
 pub mod scraper;
+pub mod torznab;
+pub mod search;
+pub mod stats;
+pub mod notifications;
+pub mod extractor;
+pub mod watch_history;
+pub mod completed_history;
+pub mod cleanup;
+pub mod persistence;
+pub mod logs;
+pub mod event_bus;
+pub mod port_mapping;
+pub mod power_monitor;
@@ -0,0 +1,67 @@
+// Desktop notifications for torrent completion and new RSS matches.
+
+use tauri::{AppHandle, Manager};
+use tauri_plugin_notification::NotificationExt;
+use tracing::warn;
+
+use crate::i18n::{t, t_with};
+use crate::state::AppState;
+
+/// True when the main window is focused, in which case the UI already shows the event.
+fn main_window_focused(app_handle: &AppHandle) -> bool {
+    app_handle
+        .get_webview_window("main")
+        .and_then(|w| w.is_focused().ok())
+        .unwrap_or(false)
+}
+
+fn show(app_handle: &AppHandle, title: &str, body: &str) {
+    if main_window_focused(app_handle) {
+        return;
+    }
+    if let Err(e) = app_handle
+        .notification()
+        .builder()
+        .title(title)
+        .body(body)
+        .show()
+    {
+        warn!("Failed to show notification: {}", e);
+    }
+}
+
+/// Notify that a torrent finished downloading, if enabled in settings.
+pub async fn notify_torrent_complete(app_handle: &AppHandle, name: &str) {
+    let state = app_handle.state::<AppState>();
+    if !state.config.read().await.notify_on_complete {
+        return;
+    }
+    show(app_handle, &t("notifications.downloadComplete"), name);
+}
+
+/// Notify that an interest hasn't matched anything for a while, if match
+/// notifications are enabled.
+pub async fn notify_interest_stale(app_handle: &AppHandle, interest: &str, days: i64) {
+    let state = app_handle.state::<AppState>();
+    if !state.config.read().await.notify_on_rss_match {
+        return;
+    }
+    show(
+        app_handle,
+        &t_with("notifications.interestStale", &[("interest", interest)]),
+        &t_with("notifications.interestStaleBody", &[("days", &days.to_string())]),
+    );
+}
+
+/// Notify that an RSS item matched an interest, if enabled in settings.
+pub async fn notify_rss_match(app_handle: &AppHandle, title: &str, interest: &str) {
+    let state = app_handle.state::<AppState>();
+    if !state.config.read().await.notify_on_rss_match {
+        return;
+    }
+    show(
+        app_handle,
+        &t_with("notifications.newMatch", &[("feedName", interest)]),
+        title,
+    );
+}
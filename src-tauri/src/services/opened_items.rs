@@ -0,0 +1,166 @@
+// Magnet links and .torrent files the app was asked to open. macOS hands them
+// over as RunEvent::Opened URLs; Windows and Linux pass them as command-line
+// arguments, either to the first launch or, through the single-instance
+// plugin, to the copy that's already running.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::Ordering;
+
+use tauri::{AppHandle, Emitter, Manager};
+use tracing::{error, info};
+
+use crate::services::torrent_engine;
+use crate::state::AppState;
+
+/// Polls for the torrent session before giving up: 30 * 500ms = 15s.
+const SESSION_READY_RETRIES: u32 = 30;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum OpenedItem {
+    Magnet(String),
+    TorrentFile(PathBuf),
+}
+
+fn is_torrent_path(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("torrent"))
+}
+
+/// What an opened URL asks for, if it's something the app handles.
+pub fn from_url(url: &tauri::Url) -> Option<OpenedItem> {
+    match url.scheme() {
+        "magnet" => Some(OpenedItem::Magnet(url.as_str().to_string())),
+        "file" => url
+            .to_file_path()
+            .ok()
+            .filter(|path| is_torrent_path(path))
+            .map(OpenedItem::TorrentFile),
+        _ => None,
+    }
+}
+
+/// What a command-line argument asks for. Relative paths are taken from
+/// `cwd`, and only .torrent files that exist count, so flags and stray
+/// arguments are ignored.
+pub fn from_arg(arg: &str, cwd: &Path) -> Option<OpenedItem> {
+    if arg.get(..7).is_some_and(|scheme| scheme.eq_ignore_ascii_case("magnet:")) {
+        return Some(OpenedItem::Magnet(arg.to_string()));
+    }
+    if arg.starts_with("file://") {
+        return tauri::Url::parse(arg).ok().and_then(|url| from_url(&url));
+    }
+    if arg.starts_with('-') {
+        return None;
+    }
+    let path = cwd.join(arg);
+    (is_torrent_path(&path) && path.is_file()).then_some(OpenedItem::TorrentFile(path))
+}
+
+/// Items in a full argv, skipping the executable.
+pub fn from_argv<S: AsRef<str>>(argv: &[S], cwd: &Path) -> Vec<OpenedItem> {
+    argv.iter()
+        .skip(1)
+        .filter_map(|arg| from_arg(arg.as_ref(), cwd))
+        .collect()
+}
+
+/// Add each item once the torrent session is up. Marks the app as opened via
+/// URL so the frontend doesn't bring up the main window for it.
+pub fn handle(app_handle: &AppHandle, items: Vec<OpenedItem>) {
+    if items.is_empty() {
+        return;
+    }
+    app_handle
+        .state::<AppState>()
+        .opened_via_url
+        .store(true, Ordering::SeqCst);
+
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        let state = app_handle.state::<AppState>();
+        let mut retries = 0;
+        while state.torrent_session.read().await.is_none() {
+            retries += 1;
+            if retries > SESSION_READY_RETRIES {
+                error!("Torrent session not ready after 15s, giving up on opened items");
+                return;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+        }
+
+        for item in items {
+            match item {
+                OpenedItem::Magnet(magnet_uri) => add_magnet(&app_handle, magnet_uri),
+                OpenedItem::TorrentFile(path) => {
+                    let path = path.to_string_lossy().to_string();
+                    info!("Handling torrent file: {}", path);
+                    if let Err(e) = torrent_engine::add_torrent_file(&state, &app_handle, path.clone(), None).await {
+                        error!("Failed to open {}: {}", path, e);
+                        let _ = app_handle.emit("torrent:error", e.to_string());
+                    }
+                }
+            }
+        }
+    });
+}
+
+/// Show the magnet as pending right away and add it in the background; it
+/// can sit fetching metadata for as long as it takes.
+fn add_magnet(app_handle: &AppHandle, magnet_uri: String) {
+    info!("Handling magnet link: {}", magnet_uri);
+    let pending = torrent_engine::parse_magnet_info(&magnet_uri);
+    let _ = app_handle.emit("torrent:pending", &pending);
+    info!("Emitted pending magnet: {} ({})", pending.name, pending.info_hash);
+
+    let state: AppState = (*app_handle.state::<AppState>()).clone();
+    let app_handle = app_handle.clone();
+    tokio::spawn(async move {
+        match torrent_engine::add_magnet(&state, &app_handle, magnet_uri, None).await {
+            Ok(_) => info!("Magnet added successfully"),
+            Err(e) => {
+                error!("Failed to add magnet: {:?}", e);
+                let _ = app_handle.emit(
+                    "torrent:pending-failed",
+                    &serde_json::json!({
+                        "info_hash": pending.info_hash,
+                        "error": e.to_string()
+                    }),
+                );
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn argv_keeps_magnets_and_existing_torrent_files() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().to_path_buf();
+        std::fs::write(dir.join("show.torrent"), b"d4:infodee").unwrap();
+
+        let magnet = "magnet:?xt=urn:btih:c12fe1c06bba254a9dc9f519b335aa7c1367a88a";
+        let absolute = dir.join("show.torrent").to_string_lossy().to_string();
+        let argv = [
+            "/usr/bin/when",
+            "--hidden",
+            magnet,
+            "show.torrent",
+            "missing.torrent",
+            "notes.txt",
+            absolute.as_str(),
+        ];
+        assert_eq!(
+            from_argv(&argv, &dir),
+            [
+                OpenedItem::Magnet(magnet.into()),
+                OpenedItem::TorrentFile(dir.join("show.torrent")),
+                OpenedItem::TorrentFile(dir.join("show.torrent")),
+            ]
+        );
+        // The executable itself is never an item
+        assert!(from_argv(&[magnet], &dir).is_empty());
+    }
+}
@@ -1,13 +1,50 @@
 use std::io::{Read, Seek, SeekFrom};
 use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use serde::Deserialize;
+use tokio::sync::Mutex;
+use tracing::info;
 
 use crate::errors::{WhenThenError, Result};
-use crate::models::SubtitleSearchResult;
+use crate::models::{SubtitleQuota, SubtitleSearchResult};
 
 const API_BASE: &str = "https://api.opensubtitles.com/api/v1";
 const USER_AGENT: &str = "whenThen v1.0.0";
+/// Login tokens are valid for 24 hours; renew a little early.
+const TOKEN_TTL: Duration = Duration::from_secs(23 * 60 * 60);
+
+/// Login token and quota, kept in memory only.
+#[derive(Default)]
+pub struct OpenSubSession {
+    token: Option<UserToken>,
+    quota: Option<SubtitleQuota>,
+}
+
+impl OpenSubSession {
+    /// Forget the login, e.g. after the account settings changed.
+    pub fn reset(&mut self) {
+        self.token = None;
+        self.quota = None;
+    }
+}
+
+#[derive(Clone)]
+struct UserToken {
+    token: String,
+    /// Host to send logged-in requests to (VIP accounts get their own)
+    base_url: String,
+    expires_at: Instant,
+}
+
+/// What a request needs to authenticate. Never logged: it holds the password.
+pub struct Auth {
+    pub api_key: String,
+    pub username: String,
+    pub password: String,
+    pub session: Arc<Mutex<OpenSubSession>>,
+}
 
 #[derive(Deserialize)]
 struct SearchResponse {
@@ -25,6 +62,8 @@ struct SearchAttributes {
     language: String,
     download_count: i64,
     ratings: f64,
+    #[serde(default)]
+    moviehash_match: bool,
     files: Vec<SearchFile>,
 }
 
@@ -38,10 +77,137 @@ struct SearchFile {
 struct DownloadResponse {
     link: String,
     file_name: String,
+    #[serde(default)]
+    requests: i64,
+    #[serde(default)]
+    remaining: i64,
+    #[serde(default)]
+    reset_time_utc: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct LoginResponse {
+    token: String,
+    #[serde(default)]
+    base_url: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct UserInfoResponse {
+    data: UserInfo,
+}
+
+#[derive(Deserialize)]
+struct UserInfo {
+    allowed_downloads: i64,
+    remaining_downloads: i64,
+    #[serde(default)]
+    downloads_count: i64,
+}
+
+pub struct DownloadedSubtitle {
+    pub file_name: String,
+    pub content: Vec<u8>,
+    pub quota: SubtitleQuota,
+}
+
+impl Auth {
+    fn logged_in(&self) -> bool {
+        !self.username.is_empty()
+    }
+}
+
+/// Log in with the configured account, returning the cached token while it's valid.
+/// Anonymous (API key only) use returns `None`.
+async fn user_token(client: &reqwest::Client, auth: &Auth, force: bool) -> Result<Option<UserToken>> {
+    if !auth.logged_in() {
+        return Ok(None);
+    }
+
+    // Held across the login so concurrent requests don't each log in
+    let mut session = auth.session.lock().await;
+    if let Some(token) = session.token.as_ref().filter(|t| !force && t.expires_at > Instant::now()) {
+        return Ok(Some(token.clone()));
+    }
+
+    let body = serde_json::json!({ "username": auth.username, "password": auth.password });
+    let response = client
+        .post(format!("{}/login", API_BASE))
+        .header("Api-Key", &auth.api_key)
+        .header("User-Agent", USER_AGENT)
+        .header("Content-Type", "application/json")
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| WhenThenError::OpenSubtitles(format!("Login request failed: {e}")))?;
+
+    if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+        session.token = None;
+        return Err(WhenThenError::OpenSubtitles(
+            "OpenSubtitles login failed. Check your username and password in Settings.".into()
+        ));
+    }
+    if !response.status().is_success() {
+        return Err(WhenThenError::OpenSubtitles(format!(
+            "Login failed with status {}",
+            response.status()
+        )));
+    }
+
+    let login: LoginResponse = response
+        .json()
+        .await
+        .map_err(|e| WhenThenError::OpenSubtitles(format!("Failed to parse login response: {e}")))?;
+
+    info!("Logged in to OpenSubtitles as {}", auth.username);
+    let token = UserToken {
+        token: login.token,
+        base_url: login
+            .base_url
+            .map(|host| format!("https://{}/api/v1", host.trim_end_matches('/')))
+            .unwrap_or_else(|| API_BASE.to_string()),
+        expires_at: Instant::now() + TOKEN_TTL,
+    };
+    session.token = Some(token.clone());
+    Ok(Some(token))
+}
+
+/// Send a request built against the right API host, logging in first when an
+/// account is configured. A 401 means the token was revoked or expired early,
+/// so log in again and retry once.
+async fn send<F>(client: &reqwest::Client, auth: &Auth, action: &str, build: F) -> Result<reqwest::Response>
+where
+    F: Fn(&str) -> reqwest::RequestBuilder,
+{
+    let attempt = |token: Option<UserToken>| {
+        let base = token.as_ref().map_or(API_BASE, |t| t.base_url.as_str());
+        let mut request = build(base)
+            .header("Api-Key", &auth.api_key)
+            .header("User-Agent", USER_AGENT);
+        if let Some(token) = &token {
+            request = request.bearer_auth(&token.token);
+        }
+        async move {
+            request
+                .send()
+                .await
+                .map_err(|e| WhenThenError::OpenSubtitles(format!("{action} request failed: {e}")))
+        }
+    };
+
+    let token = user_token(client, auth, false).await?;
+    let had_token = token.is_some();
+    let response = attempt(token).await?;
+    if response.status() != reqwest::StatusCode::UNAUTHORIZED || !had_token {
+        return Ok(response);
+    }
+
+    let token = user_token(client, auth, true).await?;
+    attempt(token).await
 }
 
 pub async fn search(
-    api_key: &str,
+    auth: &Auth,
     languages: &[String],
     query: &str,
     movie_hash: Option<&str>,
@@ -49,23 +215,19 @@ pub async fn search(
     let client = reqwest::Client::new();
 
     let lang_str = languages.join(",");
-    let mut url = format!(
-        "{}/subtitles?languages={}&query={}",
-        API_BASE,
+    let mut params = format!(
+        "languages={}&query={}",
         urlencoded(&lang_str),
         urlencoded(query),
     );
     if let Some(hash) = movie_hash {
-        url.push_str(&format!("&moviehash={}", hash));
+        params.push_str(&format!("&moviehash={}", hash));
     }
 
-    let response = client
-        .get(&url)
-        .header("Api-Key", api_key)
-        .header("User-Agent", USER_AGENT)
-        .send()
-        .await
-        .map_err(|e| WhenThenError::OpenSubtitles(format!("Search request failed: {e}")))?;
+    let response = send(&client, auth, "Search", |base| {
+        client.get(format!("{}/subtitles?{}", base, params))
+    })
+    .await?;
 
     if !response.status().is_success() {
         let status = response.status();
@@ -91,6 +253,7 @@ pub async fn search(
                 file_name: file.file_name.clone(),
                 download_count: entry.attributes.download_count,
                 ratings: entry.attributes.ratings,
+                moviehash_match: entry.attributes.moviehash_match,
             });
         }
     }
@@ -98,20 +261,18 @@ pub async fn search(
     Ok(results)
 }
 
-pub async fn download(api_key: &str, file_id: i64) -> Result<(String, Vec<u8>)> {
+pub async fn download(auth: &Auth, file_id: i64) -> Result<DownloadedSubtitle> {
     let client = reqwest::Client::new();
 
     let body = serde_json::json!({ "file_id": file_id });
 
-    let response = client
-        .post(format!("{}/download", API_BASE))
-        .header("Api-Key", api_key)
-        .header("User-Agent", USER_AGENT)
-        .header("Content-Type", "application/json")
-        .json(&body)
-        .send()
-        .await
-        .map_err(|e| WhenThenError::OpenSubtitles(format!("Download request failed: {e}")))?;
+    let response = send(&client, auth, "Download", |base| {
+        client
+            .post(format!("{}/download", base))
+            .header("Content-Type", "application/json")
+            .json(&body)
+    })
+    .await?;
 
     if !response.status().is_success() {
         let status = response.status();
@@ -127,6 +288,15 @@ pub async fn download(api_key: &str, file_id: i64) -> Result<(String, Vec<u8>)>
         .await
         .map_err(|e| WhenThenError::OpenSubtitles(format!("Failed to parse download response: {e}")))?;
 
+    let quota = SubtitleQuota {
+        remaining: dl_resp.remaining,
+        requests: dl_resp.requests,
+        allowed: None,
+        reset_time: dl_resp.reset_time_utc.clone(),
+        logged_in: auth.logged_in(),
+    };
+    auth.session.lock().await.quota = Some(quota.clone());
+
     let file_bytes = client
         .get(&dl_resp.link)
         .header("User-Agent", USER_AGENT)
@@ -137,7 +307,69 @@ pub async fn download(api_key: &str, file_id: i64) -> Result<(String, Vec<u8>)>
         .await
         .map_err(|e| WhenThenError::OpenSubtitles(format!("Failed to read subtitle bytes: {e}")))?;
 
-    Ok((dl_resp.file_name, file_bytes.to_vec()))
+    Ok(DownloadedSubtitle {
+        file_name: dl_resp.file_name,
+        content: file_bytes.to_vec(),
+        quota,
+    })
+}
+
+/// Current download quota. Logged-in accounts are asked directly; anonymous use
+/// only knows what the last download reported.
+pub async fn quota(auth: &Auth) -> Result<Option<SubtitleQuota>> {
+    if !auth.logged_in() {
+        return Ok(auth.session.lock().await.quota.clone());
+    }
+
+    let client = reqwest::Client::new();
+    let response = send(&client, auth, "Quota", |base| {
+        client.get(format!("{}/infos/user", base))
+    })
+    .await?;
+
+    if !response.status().is_success() {
+        return Err(WhenThenError::OpenSubtitles(format!(
+            "Quota request failed with status {}",
+            response.status()
+        )));
+    }
+
+    let info: UserInfoResponse = response
+        .json()
+        .await
+        .map_err(|e| WhenThenError::OpenSubtitles(format!("Failed to parse user info: {e}")))?;
+
+    let mut session = auth.session.lock().await;
+    let quota = SubtitleQuota {
+        remaining: info.data.remaining_downloads,
+        requests: info.data.downloads_count,
+        allowed: Some(info.data.allowed_downloads),
+        // Only download responses say when the counter resets
+        reset_time: session.quota.as_ref().and_then(|q| q.reset_time.clone()),
+        logged_in: true,
+    };
+    session.quota = Some(quota.clone());
+    Ok(Some(quota))
+}
+
+/// Whether OpenSubtitles accepts `api_key`. Asks for the subtitle format list,
+/// which needs a key but no login or download quota.
+pub async fn check_api_key(api_key: &str) -> Result<bool> {
+    let response = reqwest::Client::new()
+        .get(format!("{}/infos/formats", API_BASE))
+        .header("Api-Key", api_key)
+        .header("User-Agent", USER_AGENT)
+        .send()
+        .await
+        .map_err(|e| WhenThenError::OpenSubtitles(format!("Key check failed: {e}")))?;
+
+    match response.status() {
+        status if status.is_success() => Ok(true),
+        reqwest::StatusCode::UNAUTHORIZED | reqwest::StatusCode::FORBIDDEN => Ok(false),
+        status => Err(WhenThenError::OpenSubtitles(format!(
+            "Key check failed with status {status}"
+        ))),
+    }
 }
 
 /// Compute the OpenSubtitles hash for a file.
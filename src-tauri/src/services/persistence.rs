@@ -0,0 +1,265 @@
+// Background writer for the RSS and scraper stores. Saves are requested over
+// a channel, coalesced for a couple of seconds, and written to a temp file
+// that's renamed into place, so a crash mid-save never leaves a truncated
+// store behind.
+
+use std::collections::HashSet;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use tauri::{AppHandle, Manager};
+use tokio::sync::{mpsc, oneshot};
+use tokio::time::Instant;
+use tracing::{error, info, warn};
+
+use crate::state::AppState;
+
+/// How long requests are collected before the dirty stores are written.
+const DEBOUNCE: Duration = Duration::from_secs(2);
+
+/// Longest shutdown waits for pending writes.
+const FLUSH_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RssStore {
+    Sources,
+    Interests,
+    SeenItems,
+    BadItems,
+    PendingMatches,
+    MatchCounts,
+    ScraperConfigs,
+    ScraperSeenItems,
+}
+
+impl RssStore {
+    pub fn file(self) -> &'static str {
+        match self {
+            Self::Sources => "sources.json",
+            Self::Interests => "interests.json",
+            Self::SeenItems => "seen_items.json",
+            Self::BadItems => "bad_items.json",
+            Self::PendingMatches => "pending_matches.json",
+            Self::MatchCounts => "interest_match_counts.json",
+            Self::ScraperConfigs => "scrapers.json",
+            Self::ScraperSeenItems => "scraper_seen_items.json",
+        }
+    }
+
+    pub fn key(self) -> &'static str {
+        match self {
+            Self::Sources => "sources",
+            Self::Interests => "interests",
+            Self::SeenItems => "seen_items",
+            Self::BadItems => "bad_items",
+            Self::PendingMatches => "pending_matches",
+            Self::MatchCounts => "match_counts",
+            Self::ScraperConfigs => "scrapers",
+            Self::ScraperSeenItems => "seen_items",
+        }
+    }
+
+    /// Current contents, read under the matching lock.
+    async fn snapshot(self, state: &AppState) -> serde_json::Result<serde_json::Value> {
+        let rss = &state.rss_state;
+        match self {
+            Self::Sources => serde_json::to_value(&*rss.sources.read().await),
+            Self::Interests => serde_json::to_value(&*rss.interests.read().await),
+            Self::SeenItems => serde_json::to_value(&*rss.seen_items.lock().await),
+            Self::BadItems => serde_json::to_value(&*rss.bad_items.read().await),
+            Self::PendingMatches => serde_json::to_value(&*rss.pending_matches.read().await),
+            Self::MatchCounts => serde_json::to_value(&*rss.match_counts.read().await),
+            Self::ScraperConfigs => serde_json::to_value(&*state.scraper_state.configs.read().await),
+            Self::ScraperSeenItems => serde_json::to_value(&*state.scraper_state.seen_items.lock().await),
+        }
+    }
+}
+
+enum Message {
+    Write(RssStore),
+    Flush(oneshot::Sender<()>),
+}
+
+/// Sending half kept in AppState. Requests made before `start` wait in the channel.
+pub struct RssWriter {
+    tx: mpsc::UnboundedSender<Message>,
+    rx: std::sync::Mutex<Option<mpsc::UnboundedReceiver<Message>>>,
+}
+
+impl RssWriter {
+    pub fn new() -> Self {
+        let (tx, rx) = mpsc::unbounded_channel();
+        Self {
+            tx,
+            rx: std::sync::Mutex::new(Some(rx)),
+        }
+    }
+
+    /// Schedule a save of `store`.
+    pub fn request(&self, store: RssStore) {
+        let _ = self.tx.send(Message::Write(store));
+    }
+
+    /// Write everything pending now.
+    pub async fn flush(&self) {
+        let (ack_tx, ack_rx) = oneshot::channel();
+        if self.tx.send(Message::Flush(ack_tx)).is_ok()
+            && tokio::time::timeout(FLUSH_TIMEOUT, ack_rx).await.is_err()
+        {
+            warn!("Timed out flushing RSS stores");
+        }
+    }
+}
+
+/// Start the writer task. Only the first call does anything.
+pub fn start(app: AppHandle, state: &AppState) {
+    let Some(mut rx) = state.rss_writer.rx.lock().ok().and_then(|mut rx| rx.take()) else {
+        return;
+    };
+    tauri::async_runtime::spawn(async move {
+        let mut dirty = HashSet::new();
+        let mut deadline = Instant::now();
+        loop {
+            let message = if dirty.is_empty() {
+                rx.recv().await
+            } else {
+                match tokio::time::timeout_at(deadline, rx.recv()).await {
+                    Ok(message) => message,
+                    Err(_) => {
+                        write_dirty(&app, &mut dirty).await;
+                        continue;
+                    }
+                }
+            };
+            match message {
+                Some(Message::Write(store)) => {
+                    if dirty.is_empty() {
+                        deadline = Instant::now() + DEBOUNCE;
+                    }
+                    dirty.insert(store);
+                }
+                Some(Message::Flush(ack)) => {
+                    write_dirty(&app, &mut dirty).await;
+                    let _ = ack.send(());
+                }
+                None => {
+                    write_dirty(&app, &mut dirty).await;
+                    break;
+                }
+            }
+        }
+    });
+}
+
+async fn write_dirty(app: &AppHandle, dirty: &mut HashSet<RssStore>) {
+    let state = app.state::<AppState>();
+    for store in dirty.drain() {
+        let Ok(path) = store_path(app, store.file()) else {
+            error!("No app data directory to save {}", store.file());
+            continue;
+        };
+        let value = match store.snapshot(&state).await {
+            Ok(value) => {
+                let mut contents = serde_json::Map::new();
+                contents.insert(store.key().to_string(), value);
+                serde_json::Value::Object(contents)
+            }
+            Err(e) => {
+                error!("Failed to serialize {}: {}", store.file(), e);
+                continue;
+            }
+        };
+        let result = tokio::task::spawn_blocking(move || {
+            let contents = serde_json::to_vec_pretty(&value).map_err(std::io::Error::other)?;
+            write_atomic(&path, &contents)
+        })
+        .await;
+        match result {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => error!("Failed to save {}: {}", store.file(), e),
+            Err(e) => error!("Save task for {} failed: {}", store.file(), e),
+        }
+    }
+}
+
+/// Where the store plugin keeps `file`.
+fn store_path(app: &AppHandle, file: &str) -> tauri::Result<PathBuf> {
+    Ok(app.path().app_data_dir()?.join(file))
+}
+
+fn temp_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".tmp");
+    path.with_file_name(name)
+}
+
+/// Write to a temp file, sync it, then rename it over `path`.
+fn write_atomic(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let temp = temp_path(path);
+    let mut file = std::fs::File::create(&temp)?;
+    file.write_all(contents)?;
+    file.sync_all()?;
+    std::fs::rename(&temp, path)
+}
+
+/// Deal with a temp file left by a save that didn't finish renaming. A
+/// complete one is newer than the store, so it replaces it; a partial one
+/// is discarded.
+fn recover_file(path: &Path) {
+    let temp = temp_path(path);
+    let Ok(contents) = std::fs::read(&temp) else {
+        return;
+    };
+    if serde_json::from_slice::<serde_json::Value>(&contents).is_ok() {
+        match std::fs::rename(&temp, path) {
+            Ok(()) => info!("Recovered {} from an unfinished save", path.display()),
+            Err(e) => warn!("Could not recover {}: {}", path.display(), e),
+        }
+    } else {
+        warn!("Discarding partial save {}", temp.display());
+        let _ = std::fs::remove_file(&temp);
+    }
+}
+
+/// Drop the plugin's cached copy once a store is loaded, so it can't save a
+/// stale version over the writer's files on exit.
+pub fn release<R: tauri::Runtime>(store: &tauri_plugin_store::Store<R>) {
+    store.close_resource();
+}
+
+/// Call before loading `file` through the store plugin.
+pub fn recover(app: &AppHandle, file: &str) {
+    if let Ok(path) = store_path(app, file) {
+        recover_file(&path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recovers_complete_temp_files_and_drops_partial_ones() {
+        let dir = std::env::temp_dir().join(format!("whenthen-persistence-{}", std::process::id()));
+        let path = dir.join("seen_items.json");
+
+        write_atomic(&path, br#"{"seen_items":{"a":"1"}}"#).unwrap();
+        assert!(!temp_path(&path).exists());
+
+        std::fs::write(temp_path(&path), br#"{"seen_items":{"a":"1","b":"2"}}"#).unwrap();
+        recover_file(&path);
+        assert!(!temp_path(&path).exists());
+        assert_eq!(std::fs::read(&path).unwrap(), br#"{"seen_items":{"a":"1","b":"2"}}"#);
+
+        std::fs::write(temp_path(&path), br#"{"seen_items":{"a":"#).unwrap();
+        recover_file(&path);
+        assert!(!temp_path(&path).exists());
+        assert_eq!(std::fs::read(&path).unwrap(), br#"{"seen_items":{"a":"1","b":"2"}}"#);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
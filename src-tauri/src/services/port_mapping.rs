@@ -0,0 +1,199 @@
+// UPnP port forwarding for the torrent listen port.
+//
+// librqbit can forward the port itself but doesn't say whether the router
+// agreed, and only reads the setting when the session starts. Doing it here
+// lets the outcome show up in network status and diagnostics, and lets the
+// setting be toggled without a restart. Outcomes go out as network:upnp-result.
+
+use std::net::{IpAddr, SocketAddr};
+use std::time::Duration;
+
+use igd_next::aio::tokio::search_gateway;
+use igd_next::{PortMappingProtocol, SearchOptions};
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::{Mutex, RwLock};
+use tracing::{info, warn};
+
+use crate::i18n::{t, t_with};
+use crate::models::{UpnpResult, UpnpStatus};
+use crate::services::torrent_engine;
+use crate::state::AppState;
+
+/// Mapping lifetime requested from the router; renewed at half of it.
+const LEASE_SECS: u32 = 3600;
+
+/// Wait before retrying after a failed attempt.
+const RETRY_INTERVAL: Duration = Duration::from_secs(300);
+
+/// How long to look for a gateway on the network.
+const SEARCH_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Checks for the session's listener before giving up on this attempt.
+const LISTENER_CHECKS: u32 = 10;
+
+const DESCRIPTION: &str = "When";
+
+/// TCP for peer connections, UDP for uTP peers and the DHT.
+const PROTOCOLS: [PortMappingProtocol; 2] = [PortMappingProtocol::TCP, PortMappingProtocol::UDP];
+
+pub struct PortMapping {
+    task: Mutex<Option<tauri::async_runtime::JoinHandle<()>>>,
+    last: RwLock<UpnpResult>,
+}
+
+impl Default for PortMapping {
+    fn default() -> Self {
+        Self {
+            task: Mutex::new(None),
+            last: RwLock::new(UpnpResult::with_status(UpnpStatus::Disabled)),
+        }
+    }
+}
+
+impl PortMapping {
+    pub async fn last(&self) -> UpnpResult {
+        self.last.read().await.clone()
+    }
+}
+
+async fn record(app: &AppHandle, result: UpnpResult) {
+    let state = app.state::<AppState>();
+    let mut last = state.port_mapping.last.write().await;
+    if *last != result {
+        *last = result.clone();
+        drop(last);
+        let _ = app.emit("network:upnp-result", result);
+    }
+}
+
+/// Start forwarding the listen port, replacing any mapping task already running.
+pub async fn start(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    stop_task(&state).await;
+    let mut mapped = mapped_port(&state.port_mapping.last().await);
+    record(app, UpnpResult::with_status(UpnpStatus::Pending)).await;
+
+    let handle = app.clone();
+    let task = tauri::async_runtime::spawn(async move {
+        loop {
+            let first = handle.state::<AppState>().config.read().await.listen_port;
+            let result = map_once(first).await;
+            // The listener moved, so the router shouldn't keep forwarding the old port
+            if let Some(port) = mapped_port(&result) {
+                if let Some(old) = mapped.filter(|old| *old != port) {
+                    release(old).await;
+                }
+                mapped = Some(port);
+            }
+            let wait = if result.status == UpnpStatus::Mapped {
+                Duration::from_secs(u64::from(LEASE_SECS) / 2)
+            } else {
+                RETRY_INTERVAL
+            };
+            record(&handle, result).await;
+            tokio::time::sleep(wait).await;
+        }
+    });
+    *state.port_mapping.task.lock().await = Some(task);
+}
+
+/// Stop renewing and remove the mapping from the router. Also run at shutdown.
+pub async fn stop(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    stop_task(&state).await;
+    if let Some(port) = mapped_port(&state.port_mapping.last().await) {
+        release(port).await;
+    }
+    record(app, UpnpResult::with_status(UpnpStatus::Disabled)).await;
+}
+
+fn mapped_port(result: &UpnpResult) -> Option<u16> {
+    result.port.filter(|_| result.status == UpnpStatus::Mapped)
+}
+
+async fn release(port: u16) {
+    match remove(port).await {
+        Ok(()) => info!(port, "Removed UPnP port mapping"),
+        Err(e) => warn!(port, "Could not remove UPnP port mapping: {}", e),
+    }
+}
+
+async fn stop_task(state: &AppState) {
+    if let Some(task) = state.port_mapping.task.lock().await.take() {
+        task.abort();
+    }
+}
+
+async fn map_once(first_port: u16) -> UpnpResult {
+    let mut port = None;
+    for _ in 0..LISTENER_CHECKS {
+        port = torrent_engine::bound_listen_port(first_port).await;
+        if port.is_some() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_secs(1)).await;
+    }
+    let Some(port) = port else {
+        return UpnpResult {
+            error: Some(t("diagnostics.upnp.notListening")),
+            ..UpnpResult::with_status(UpnpStatus::Failed)
+        };
+    };
+
+    match add(port).await {
+        Ok(external_ip) => {
+            info!(port, external_ip = ?external_ip, "UPnP port mapping active");
+            UpnpResult {
+                port: Some(port),
+                external_ip,
+                ..UpnpResult::with_status(UpnpStatus::Mapped)
+            }
+        }
+        Err(e) => {
+            warn!(port, "UPnP port mapping failed: {}", e);
+            UpnpResult {
+                port: Some(port),
+                error: Some(e),
+                ..UpnpResult::with_status(UpnpStatus::Failed)
+            }
+        }
+    }
+}
+
+/// Ask the router to forward `port`; returns its public address if it tells us.
+/// Errors are translated, since they're shown in diagnostics.
+async fn add(port: u16) -> Result<Option<String>, String> {
+    let local_ip: IpAddr = torrent_engine::get_local_ip()
+        .parse()
+        .map_err(|_| t("diagnostics.upnp.noLocalAddress"))?;
+    let gateway = search_gateway(SearchOptions {
+        timeout: Some(SEARCH_TIMEOUT),
+        ..Default::default()
+    })
+    .await
+    .map_err(|e| t_with("diagnostics.upnp.noRouter", &[("error", &e.to_string())]))?;
+    for protocol in PROTOCOLS {
+        gateway
+            .add_port(protocol, port, SocketAddr::new(local_ip, port), LEASE_SECS, DESCRIPTION)
+            .await
+            .map_err(|e| t_with("diagnostics.upnp.refused", &[("error", &e.to_string())]))?;
+    }
+    Ok(gateway.get_external_ip().await.ok().map(|ip| ip.to_string()))
+}
+
+/// Remove both mappings, trying the second even if the first fails.
+async fn remove(port: u16) -> Result<(), String> {
+    let gateway = search_gateway(SearchOptions {
+        timeout: Some(SEARCH_TIMEOUT),
+        ..Default::default()
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+    let mut result = Ok(());
+    for protocol in PROTOCOLS {
+        if let Err(e) = gateway.remove_port(protocol, port).await {
+            result = Err(e.to_string());
+        }
+    }
+    result
+}
@@ -0,0 +1,187 @@
+// Pause torrents on battery power. Only macOS reports the power source (read
+// from `pmset -g batt`); elsewhere the monitor never starts and the setting
+// is hidden. Torrents paused here resume when AC power comes back, and ones
+// the user resumes on battery are left running. Changes go out as
+// power:state-changed.
+
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::Mutex;
+use tracing::info;
+
+use crate::models::{PowerCapabilities, PowerStatus};
+use crate::services::torrent_engine;
+use crate::state::AppState;
+
+#[derive(Default)]
+pub struct PowerMonitor {
+    on_battery: AtomicBool,
+    /// On battery with pause_on_battery set
+    holding: AtomicBool,
+    /// Torrents paused for battery, by librqbit id
+    paused: Mutex<HashSet<usize>>,
+    /// Torrents the user resumed while on battery, by librqbit id
+    overrides: Mutex<HashSet<usize>>,
+}
+
+impl PowerMonitor {
+    /// Whether torrents are being held back for battery; queued ones shouldn't start.
+    pub fn holding(&self) -> bool {
+        self.holding.load(Ordering::SeqCst)
+    }
+
+    /// Keep a torrent the user resumed on battery running until AC returns.
+    pub async fn user_resumed(&self, id: usize) {
+        if self.holding() {
+            self.paused.lock().await.remove(&id);
+            self.overrides.lock().await.insert(id);
+        }
+    }
+
+    async fn status(&self) -> PowerStatus {
+        PowerStatus {
+            on_battery: self.on_battery.load(Ordering::SeqCst),
+            holding: self.holding(),
+            paused: self.paused.lock().await.len(),
+        }
+    }
+}
+
+pub fn capabilities() -> PowerCapabilities {
+    PowerCapabilities {
+        battery_detection: cfg!(target_os = "macos"),
+    }
+}
+
+/// Power source from `pmset -g batt`: true on battery (or UPS), false on AC.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn parse_pmset(output: &str) -> Option<bool> {
+    let source = output.lines().next()?;
+    if source.contains("'AC Power'") {
+        Some(false)
+    } else if source.contains("'Battery Power'") || source.contains("'UPS Power'") {
+        Some(true)
+    } else {
+        None
+    }
+}
+
+/// Poll the power source for as long as the app runs.
+#[cfg(target_os = "macos")]
+pub fn start(app: AppHandle) {
+    use std::time::Duration;
+
+    const POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(POLL_INTERVAL);
+        loop {
+            interval.tick().await;
+            let output = match tokio::process::Command::new("pmset").args(["-g", "batt"]).output().await {
+                Ok(output) => output,
+                Err(e) => {
+                    tracing::warn!(error = %e, "Can't read the power source; battery pausing is off");
+                    return;
+                }
+            };
+            if let Some(on_battery) = parse_pmset(&String::from_utf8_lossy(&output.stdout)) {
+                update(&app, on_battery).await;
+            }
+        }
+    });
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn start(_app: AppHandle) {}
+
+/// Apply a changed pause_on_battery setting right away.
+pub async fn refresh(app: &AppHandle) {
+    let on_battery = app.state::<AppState>().power.on_battery.load(Ordering::SeqCst);
+    update(app, on_battery).await;
+}
+
+async fn update(app: &AppHandle, on_battery: bool) {
+    let state = app.state::<AppState>();
+    let power = &state.power;
+    let hold = on_battery && state.config.read().await.pause_on_battery;
+    let was_on_battery = power.on_battery.swap(on_battery, Ordering::SeqCst);
+    let was_holding = power.holding.swap(hold, Ordering::SeqCst);
+
+    let paused_before = power.paused.lock().await.len();
+    if hold {
+        pause_running(&state).await;
+    } else if was_holding {
+        resume_paused(&state, app).await;
+    }
+
+    let status = power.status().await;
+    if was_on_battery != on_battery || was_holding != hold || status.paused != paused_before {
+        let _ = app.emit("power:state-changed", &status);
+    }
+}
+
+/// Pause every unfinished torrent that's running, except what the user resumed.
+async fn pause_running(state: &AppState) {
+    let Some(session) = state.torrent_session.read().await.clone() else {
+        return;
+    };
+    let overrides = state.power.overrides.lock().await.clone();
+    let running: Vec<_> = session.with_torrents(|torrents| {
+        torrents
+            .filter(|(id, h)| {
+                let stats = h.stats();
+                !overrides.contains(id)
+                    && !stats.finished
+                    && !matches!(
+                        stats.state,
+                        librqbit::TorrentStatsState::Paused | librqbit::TorrentStatsState::Error
+                    )
+            })
+            .map(|(id, h)| (id, h.clone()))
+            .collect()
+    });
+    if running.is_empty() {
+        return;
+    }
+
+    let mut paused = state.power.paused.lock().await;
+    for (id, handle) in running {
+        match session.pause(&handle).await {
+            Ok(_) => {
+                paused.insert(id);
+            }
+            Err(e) => tracing::warn!(id, error = %e, "Failed to pause torrent for battery"),
+        }
+    }
+    info!(count = paused.len(), "Paused torrents on battery");
+}
+
+/// Resume what was paused for battery and forget the user's overrides.
+async fn resume_paused(state: &AppState, app: &AppHandle) {
+    let ids: Vec<usize> = state.power.paused.lock().await.drain().collect();
+    state.power.overrides.lock().await.clear();
+    if let Some(session) = state.torrent_session.read().await.clone() {
+        if !ids.is_empty() {
+            info!(count = ids.len(), "Resuming torrents paused on battery");
+        }
+        torrent_engine::resume_ids(state, app, &session, ids).await;
+    }
+    torrent_engine::promote_queued(state, app).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pmset_reports_the_power_source() {
+        let battery = "Now drawing from 'Battery Power'\n -InternalBattery-0 (id=1234)\t87%; discharging; 5:12 remaining present: true\n";
+        let ac = "Now drawing from 'AC Power'\n -InternalBattery-0 (id=1234)\t100%; charged; 0:00 remaining present: true\n";
+        assert_eq!(parse_pmset(battery), Some(true));
+        assert_eq!(parse_pmset(ac), Some(false));
+        assert_eq!(parse_pmset("Now drawing from 'UPS Power'\n"), Some(true));
+        assert_eq!(parse_pmset(""), None);
+    }
+}
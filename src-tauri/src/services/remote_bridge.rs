@@ -0,0 +1,194 @@
+// WebSocket bridge for remote control, served on the media server's /ws.
+//
+// Clients always authenticate with the stream token, even when the torrent
+// routes are left open, then receive bus events as `{"event": ..., "payload": ...}` frames and may
+// send a few commands, each answered with `{"reply": ..., "ok": ...}`.
+// Sockets that flood commands or keep sending garbage are closed.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use axum::{
+    extract::{
+        ws::{close_code, CloseFrame, Message, WebSocket, WebSocketUpgrade},
+        State as AxumState,
+    },
+    response::Response,
+};
+use serde::Deserialize;
+use serde_json::json;
+use tauri::{Emitter, Manager};
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{debug, info, warn};
+
+use crate::services::cast_group::{self, PlaybackAction};
+use crate::services::media_server::MediaServerState;
+use crate::services::torrent_engine;
+use crate::state::AppState;
+
+/// Largest inbound frame; commands are a few dozen bytes.
+const MAX_MESSAGE_SIZE: usize = 4 * 1024;
+
+/// Commands allowed per window before the socket is closed.
+const MAX_COMMANDS: usize = 10;
+const COMMAND_WINDOW: Duration = Duration::from_secs(5);
+
+/// Malformed or unknown commands tolerated before the socket is closed.
+const MAX_INVALID: u32 = 3;
+
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(tag = "cmd", rename_all = "snake_case")]
+enum RemoteCommand {
+    Pause { torrent_id: usize },
+    Resume { torrent_id: usize },
+    PlaybackPause { device_id: String },
+    PlaybackPlay { device_id: String },
+}
+
+impl RemoteCommand {
+    fn name(&self) -> &'static str {
+        match self {
+            RemoteCommand::Pause { .. } => "pause",
+            RemoteCommand::Resume { .. } => "resume",
+            RemoteCommand::PlaybackPause { .. } => "playback_pause",
+            RemoteCommand::PlaybackPlay { .. } => "playback_play",
+        }
+    }
+}
+
+/// Sliding-window command limit.
+struct CommandLimit {
+    recent: VecDeque<Instant>,
+}
+
+impl CommandLimit {
+    fn new() -> Self {
+        Self { recent: VecDeque::new() }
+    }
+
+    fn allow(&mut self, now: Instant) -> bool {
+        while self.recent.front().is_some_and(|t| now.duration_since(*t) >= COMMAND_WINDOW) {
+            self.recent.pop_front();
+        }
+        if self.recent.len() >= MAX_COMMANDS {
+            return false;
+        }
+        self.recent.push_back(now);
+        true
+    }
+}
+
+pub async fn ws_handler(ws: WebSocketUpgrade, AxumState(state): AxumState<MediaServerState>) -> Response {
+    ws.max_message_size(MAX_MESSAGE_SIZE)
+        .on_upgrade(move |socket| run(socket, state))
+}
+
+async fn close(socket: &mut WebSocket, code: u16, reason: &'static str) {
+    let frame = CloseFrame { code, reason: reason.into() };
+    let _ = socket.send(Message::Close(Some(frame))).await;
+}
+
+async fn run(mut socket: WebSocket, state: MediaServerState) {
+    info!("Remote control connected");
+    let mut events = state.event_bus.subscribe();
+    let mut limit = CommandLimit::new();
+    let mut invalid = 0;
+
+    loop {
+        tokio::select! {
+            event = events.recv() => match event {
+                Ok(event) => {
+                    let Ok(text) = serde_json::to_string(&event) else { continue };
+                    if socket.send(Message::Text(text.into())).await.is_err() {
+                        break;
+                    }
+                }
+                Err(RecvError::Lagged(skipped)) => debug!(skipped, "Remote control fell behind, skipping events"),
+                Err(RecvError::Closed) => break,
+            },
+            message = socket.recv() => match message {
+                Some(Ok(Message::Text(text))) => {
+                    if !limit.allow(Instant::now()) {
+                        warn!("Remote control sent too many commands, closing");
+                        close(&mut socket, close_code::POLICY, "Too many commands").await;
+                        break;
+                    }
+                    let reply = match serde_json::from_str::<RemoteCommand>(&text) {
+                        Ok(command) => {
+                            let name = command.name();
+                            match dispatch(&state, command).await {
+                                Ok(()) => json!({ "reply": name, "ok": true }),
+                                Err(e) => json!({ "reply": name, "ok": false, "error": e }),
+                            }
+                        }
+                        Err(e) => {
+                            invalid += 1;
+                            if invalid > MAX_INVALID {
+                                warn!("Remote control sent too many invalid commands, closing");
+                                close(&mut socket, close_code::POLICY, "Invalid commands").await;
+                                break;
+                            }
+                            json!({ "reply": null, "ok": false, "error": e.to_string() })
+                        }
+                    };
+                    if socket.send(Message::Text(reply.to_string().into())).await.is_err() {
+                        break;
+                    }
+                }
+                Some(Ok(Message::Binary(_))) => {
+                    close(&mut socket, close_code::UNSUPPORTED, "Text frames only").await;
+                    break;
+                }
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                // Pings are answered by axum
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+    info!("Remote control disconnected");
+}
+
+async fn dispatch(state: &MediaServerState, command: RemoteCommand) -> Result<(), String> {
+    let Some(app) = &state.app_handle else {
+        return Err("Remote control is unavailable".into());
+    };
+    let app_state = app.state::<AppState>();
+    let result = match command {
+        RemoteCommand::Pause { torrent_id } => torrent_engine::pause_torrent(&app_state, app, torrent_id).await,
+        RemoteCommand::Resume { torrent_id } => torrent_engine::resume_torrent(&app_state, app, torrent_id).await,
+        RemoteCommand::PlaybackPause { device_id } => {
+            return cast_group::control(app, &app_state, &device_id, PlaybackAction::Pause)
+                .await
+                .map_err(|e| e.to_string());
+        }
+        RemoteCommand::PlaybackPlay { device_id } => {
+            return cast_group::control(app, &app_state, &device_id, PlaybackAction::Play)
+                .await
+                .map_err(|e| e.to_string());
+        }
+    };
+    result.map_err(|e| e.to_string())?;
+    // Let the desktop UI catch up with a change it didn't make
+    let _ = app.emit("torrents:changed", ());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn commands_parse_and_are_rate_limited() {
+        assert_eq!(
+            serde_json::from_str::<RemoteCommand>(r#"{"cmd":"pause","torrent_id":3}"#).unwrap(),
+            RemoteCommand::Pause { torrent_id: 3 }
+        );
+        assert!(serde_json::from_str::<RemoteCommand>(r#"{"cmd":"delete","torrent_id":3}"#).is_err());
+
+        let mut limit = CommandLimit::new();
+        let start = Instant::now();
+        assert!((0..MAX_COMMANDS).all(|_| limit.allow(start)));
+        assert!(!limit.allow(start + Duration::from_secs(1)));
+        assert!(limit.allow(start + COMMAND_WINDOW));
+    }
+}
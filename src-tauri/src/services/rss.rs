@@ -1,49 +1,68 @@
 // RSS sources, interests, and screener inbox.
 
-use std::collections::HashMap;
-use std::sync::Arc;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, LazyLock};
 use std::time::Duration;
 
 use chrono::Utc;
 use regex::Regex;
 use tauri::{AppHandle, Emitter, Manager};
 use tokio::sync::{Mutex, RwLock};
-use tracing::{info, warn};
+use tracing::{debug, info, warn};
 
 use crate::errors::Result;
 use crate::models::{
-    BadItem, FeedFilter, FeedTestItem, FeedTestResult, FilterLogic, FilterType, Interest,
-    PendingMatch, Source, TorrentFilePreview, TorrentMetadata,
+    BadItem, FeedAuth, FeedAuthType, FeedFilter, FeedTestItem, FeedTestResult, FilterErrorKind,
+    FilterLogic, FilterType, FilterValidationError, HealthReport, Interest, InterestPreset, InterestStale, MatchedInterest, MediaInfo, PendingMatch, Source, TorrentFilePreview,
+    TorrentMetadata, UpgradePolicy,
 };
-use crate::services::torrent_engine;
+use crate::services::episode::{self, Episode};
+use crate::services::{event_bus, magnet, media_info, torrent_engine, tracker_scrape, web_seeds};
 use crate::state::AppState;
 
 /// Check if a URL contains the {search} placeholder.
-fn has_search_placeholder(url: &str) -> bool {
+pub(crate) fn has_search_placeholder(url: &str) -> bool {
     url.contains("{search}")
 }
 
-/// Build a search URL by substituting {search} with the interest's search term.
-fn build_search_url(url_template: &str, interest: &Interest) -> String {
-    let term = interest
+/// The text searched for on an interest's behalf: its search term, or its name.
+pub(crate) fn search_term(interest: &Interest) -> &str {
+    interest
         .search_term
         .as_deref()
         .filter(|s| !s.is_empty())
-        .unwrap_or(&interest.name);
-    let encoded = urlencoding::encode(term);
-    url_template.replace("{search}", &encoded)
+        .unwrap_or(&interest.name)
+}
+
+/// Build a search URL by substituting {search} with the interest's search term.
+fn build_search_url(url_template: &str, interest: &Interest) -> String {
+    search_url_for_term(url_template, search_term(interest))
+}
+
+/// Substitute {search} in a URL template with an encoded search term.
+pub(crate) fn search_url_for_term(url_template: &str, term: &str) -> String {
+    url_template.replace("{search}", &urlencoding::encode(term))
+}
+
+/// Seen-items key for a feed item, scoped to the interest for search placeholder feeds.
+fn seen_item_key(source: &Source, interest: Option<&Interest>, item: &ParsedFeedItem) -> String {
+    let base_id = if source.use_guid_dedup { &item.guid } else { &item.id };
+    match interest {
+        Some(interest) => format!("{}:{}:{}", source.id, interest.id, base_id),
+        None => format!("{}:{}", source.id, base_id),
+    }
 }
 
 /// Calculate backoff duration based on failure count.
 /// Exponential backoff: 1, 2, 4, 8, 16 min, capped at 30 min.
-fn calculate_backoff(failure_count: u32) -> Duration {
+pub(crate) fn calculate_backoff(failure_count: u32) -> Duration {
     let mins = (1u64 << failure_count.saturating_sub(1).min(5)).min(30);
     Duration::from_secs(mins * 60)
 }
 
-/// Check if source is in backoff period.
-fn is_in_backoff(source: &Source) -> bool {
-    if let Some(retry_after) = &source.retry_after {
+/// Check if a source or scraper is in its backoff period.
+pub(crate) fn is_in_backoff(retry_after: Option<&str>) -> bool {
+    if let Some(retry_after) = retry_after {
         if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(retry_after) {
             return Utc::now() < dt.with_timezone(&Utc);
         }
@@ -51,34 +70,130 @@ fn is_in_backoff(source: &Source) -> bool {
     false
 }
 
-/// Extract episode identifier from title (S01E01, 1x01, or daily format).
-fn extract_episode_id(title: &str) -> Option<String> {
-    // S01E01, S1E1 pattern
-    let season_ep = Regex::new(r"(?i)S(\d{1,2})E(\d{1,2})").ok()?;
-    if let Some(caps) = season_ep.captures(title) {
-        let season: u32 = caps.get(1)?.as_str().parse().ok()?;
-        let episode: u32 = caps.get(2)?.as_str().parse().ok()?;
-        return Some(format!("S{:02}E{:02}", season, episode));
-    }
+/// Scheduling fields of a scraper or indexer, borrowed for updating.
+pub(crate) struct CheckSchedule<'a> {
+    pub check_interval: Option<u32>,
+    pub next_check_at: &'a mut Option<String>,
+    pub failure_count: &'a mut u32,
+    pub retry_after: &'a mut Option<String>,
+    pub last_checked: &'a mut Option<String>,
+    pub last_error: &'a mut Option<String>,
+}
+
+/// A search target checked on its own schedule, like scrapers and indexers.
+pub(crate) trait Scheduled: Clone {
+    fn id(&self) -> &str;
+    fn name(&self) -> &str;
+    fn enabled(&self) -> bool;
+    fn schedule(&mut self) -> CheckSchedule<'_>;
+}
+
+/// Run `check` on every enabled item that's due, with the same scheduling and
+/// backoff as feed sources. `force` (the manual "check now") runs every enabled
+/// item regardless of schedule. `kind` names the items in logs. Returns the
+/// number of new matches, or None when nothing ran.
+pub(crate) async fn check_on_schedule<T, F, Fut>(
+    app_handle: &AppHandle,
+    items: &RwLock<Vec<T>>,
+    kind: &str,
+    global_interval_mins: u32,
+    global_check_due: bool,
+    force: bool,
+    mut check: F,
+) -> Option<usize>
+where
+    T: Scheduled,
+    F: FnMut(T) -> Fut,
+    Fut: std::future::Future<Output = Result<usize>>,
+{
+    let candidates = items.read().await.clone();
+    let now_utc = Utc::now();
+    let mut total_matched = 0;
+    let mut checked: Vec<T> = Vec::new();
+
+    for mut item in candidates {
+        if !item.enabled() {
+            continue;
+        }
+
+        if !force {
+            let schedule = item.schedule();
+            if is_in_backoff(schedule.retry_after.as_deref()) {
+                continue;
+            }
+            let due = match schedule.next_check_at.as_deref() {
+                Some(next_check) => chrono::DateTime::parse_from_rfc3339(next_check)
+                    .map(|dt| now_utc >= dt.with_timezone(&Utc))
+                    .unwrap_or(true),
+                None => global_check_due,
+            };
+            if !due {
+                continue;
+            }
+        }
+
+        let name = item.name().to_string();
+        match check(item.clone()).await {
+            Ok(count) => {
+                total_matched += count;
+                if count > 0 {
+                    info!("{} {} queued {} new items for screening", kind, name, count);
+                }
+                let schedule = item.schedule();
+                *schedule.failure_count = 0;
+                *schedule.retry_after = None;
+                *schedule.last_error = None;
+            }
+            Err(e) => {
+                warn!("Failed to check {} {}: {}", kind.to_lowercase(), name, e);
+                let _ = app_handle.emit(
+                    "rss:source-error",
+                    serde_json::json!({
+                        "source_id": item.id(),
+                        "source_name": name,
+                        "message": e.to_string(),
+                        "blocked": matches!(e, crate::errors::WhenThenError::ScraperBlocked(_)),
+                    }),
+                );
+                let schedule = item.schedule();
+                *schedule.failure_count = schedule.failure_count.saturating_add(1);
+                *schedule.last_error = Some(e.to_string());
+                if !force {
+                    let backoff = calculate_backoff(*schedule.failure_count);
+                    *schedule.retry_after =
+                        Some((now_utc + chrono::Duration::from_std(backoff).unwrap_or_default()).to_rfc3339());
+                    info!("{} {} will retry in {} minutes", kind, name, backoff.as_secs() / 60);
+                }
+            }
+        }
 
-    // 1x01, 01x01 pattern
-    let x_pattern = Regex::new(r"(?i)(\d{1,2})x(\d{2})").ok()?;
-    if let Some(caps) = x_pattern.captures(title) {
-        let season: u32 = caps.get(1)?.as_str().parse().ok()?;
-        let episode: u32 = caps.get(2)?.as_str().parse().ok()?;
-        return Some(format!("S{:02}E{:02}", season, episode));
+        let schedule = item.schedule();
+        if !force {
+            let interval_mins = schedule.check_interval.unwrap_or(global_interval_mins);
+            *schedule.next_check_at = Some((now_utc + chrono::Duration::minutes(interval_mins as i64)).to_rfc3339());
+        }
+        *schedule.last_checked = Some(now_utc.to_rfc3339());
+        checked.push(item);
     }
 
-    // Daily format: 2024.01.15 or 2024-01-15
-    let daily = Regex::new(r"(\d{4})[.\-](\d{2})[.\-](\d{2})").ok()?;
-    if let Some(caps) = daily.captures(title) {
-        let year = caps.get(1)?.as_str();
-        let month = caps.get(2)?.as_str();
-        let day = caps.get(3)?.as_str();
-        return Some(format!("{}-{}-{}", year, month, day));
+    if checked.is_empty() {
+        return None;
     }
 
-    None
+    // Only write back scheduling fields, so edits made during the run survive
+    let mut items = items.write().await;
+    for mut updated in checked {
+        let Some(item) = items.iter_mut().find(|i| i.id() == updated.id()) else {
+            continue;
+        };
+        let (from, to) = (updated.schedule(), item.schedule());
+        *to.failure_count = *from.failure_count;
+        *to.retry_after = from.retry_after.take();
+        *to.next_check_at = from.next_check_at.take();
+        *to.last_checked = from.last_checked.take();
+        *to.last_error = from.last_error.take();
+    }
+    Some(total_matched)
 }
 
 /// Check if title contains PROPER or REPACK quality upgrade markers.
@@ -104,6 +219,191 @@ fn wildcard_to_regex(pattern: &str) -> String {
     result
 }
 
+/// Extract the info hash from a magnet URI's `xt=urn:btih:` parameter.
+/// Base32 hashes are converted to hex so both forms compare equal. Returns lowercase hex.
+pub fn extract_info_hash(magnet_uri: &str) -> Option<String> {
+    magnet::info_hash(magnet_uri)
+}
+
+/// Normalize a title for fuzzy comparison (lowercase, alphanumeric words only).
+fn normalize_title(title: &str) -> String {
+    title
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// The bad item marked with this info hash, if any.
+pub async fn bad_item_for_hash(rss_state: &RssState, info_hash: &str) -> Option<BadItem> {
+    let bad_items = rss_state.bad_items.read().await;
+    bad_items
+        .iter()
+        .find(|(hash, _)| hash.eq_ignore_ascii_case(info_hash))
+        .map(|(_, item)| item.clone())
+}
+
+/// Check if an item was marked bad, by info hash or (for hashless items) by normalized title.
+pub async fn is_bad_item(rss_state: &RssState, title: &str, magnet_uri: Option<&str>) -> bool {
+    let bad_items = rss_state.bad_items.read().await;
+    if bad_items.is_empty() {
+        return false;
+    }
+
+    if let Some(hash) = magnet_uri.and_then(extract_info_hash) {
+        return bad_items.keys().any(|k| k.eq_ignore_ascii_case(&hash));
+    }
+
+    let normalized = normalize_title(title);
+    bad_items.values().any(|b| normalize_title(&b.title) == normalized)
+}
+
+/// Release title for spotting the same release on different sources:
+/// bracketed tags like `[rarbg]` or `[1080p]` dropped, then normalize_title.
+fn normalize_release_title(title: &str) -> String {
+    static BRACKETED: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\[[^\]]*\]").unwrap());
+    normalize_title(&BRACKETED.replace_all(title, " "))
+}
+
+/// Whether two matches are the same release: same info hash, or the same
+/// normalized title at the same reported size.
+fn is_same_release(a: &PendingMatch, b: &PendingMatch) -> bool {
+    let hash_a = a.magnet_uri.as_deref().and_then(extract_info_hash);
+    let hash_b = b.magnet_uri.as_deref().and_then(extract_info_hash);
+    if let (Some(hash_a), Some(hash_b)) = (hash_a, hash_b) {
+        return hash_a == hash_b;
+    }
+    a.size.is_some()
+        && a.size == b.size
+        && normalize_release_title(&a.title) == normalize_release_title(&b.title)
+}
+
+/// Fold `new` into a pending match for the same release, if there is one.
+/// The source is added to its list, and a magnet replaces a torrent URL.
+fn merge_duplicate(matches: &mut [PendingMatch], new: &PendingMatch) -> bool {
+    let Some(existing) = matches.iter_mut().find(|m| is_same_release(m, new)) else {
+        return false;
+    };
+    for source in &new.sources {
+        if !existing.sources.contains(source) {
+            existing.sources.push(source.clone());
+        }
+    }
+    for seed in &new.web_seeds {
+        if !existing.web_seeds.contains(seed) {
+            existing.web_seeds.push(seed.clone());
+        }
+    }
+    for interest in &new.matched_interests {
+        if !existing.matched_interests.iter().any(|i| i.id == interest.id) {
+            existing.matched_interests.push(interest.clone());
+        }
+    }
+    if existing.magnet_uri.is_none() && new.magnet_uri.is_some() {
+        existing.source_id = new.source_id.clone();
+        existing.source_name = new.source_name.clone();
+        existing.magnet_uri = new.magnet_uri.clone();
+        existing.torrent_url = new.torrent_url.clone();
+        existing.metadata = None;
+    }
+    true
+}
+
+/// Queue a match for screening, or merge it into one already queued from
+/// another source. Returns whether it was queued as new.
+pub(crate) async fn queue_pending(app_handle: &AppHandle, rss_state: &RssState, pending: &PendingMatch) -> bool {
+    let added = {
+        let mut matches = rss_state.pending_matches.write().await;
+        if merge_duplicate(&mut matches, pending) {
+            info!(title = %pending.title, source = %pending.source_name, "Merged duplicate pending match");
+            false
+        } else {
+            matches.push(pending.clone());
+            true
+        }
+    };
+    let state = app_handle.state::<AppState>();
+    crate::commands::rss::persist_pending_matches(&state);
+
+    // A match restarts the interests' stale timers
+    let now = Utc::now().to_rfc3339();
+    for interest in rss_state.interests.write().await.iter_mut() {
+        if pending.matched_interests.iter().any(|m| m.id == interest.id) || interest.id == pending.interest_id {
+            interest.last_match_at = Some(now.clone());
+        }
+    }
+    crate::commands::rss::persist_interests(&state);
+    if added {
+        screen_new_match(app_handle, rss_state, pending).await;
+    }
+    added
+}
+
+/// How often interests are checked for having gone quiet.
+const STALE_CHECK_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// When an interest's quiet time started: its last match or the last reset,
+/// whichever is later.
+fn quiet_since(interest: &Interest) -> Option<chrono::DateTime<Utc>> {
+    [&interest.last_match_at, &interest.stale_alert_reset_at]
+        .into_iter()
+        .flatten()
+        .filter_map(|t| chrono::DateTime::parse_from_rfc3339(t).ok())
+        .map(|t| t.with_timezone(&Utc))
+        .max()
+}
+
+/// Whether an enabled interest with a stale alert has been quiet long enough to warn.
+fn is_stale(interest: &Interest, now: chrono::DateTime<Utc>) -> bool {
+    let Some(days) = interest.stale_alert_days.filter(|d| *d > 0 && interest.enabled) else {
+        return false;
+    };
+    quiet_since(interest).is_some_and(|since| now - since >= chrono::Duration::days(days as i64))
+}
+
+/// Warn about interests that have gone quiet, once per stretch of
+/// stale_alert_days, via rss:interest-stale and a desktop notification.
+async fn check_stale_interests(app_handle: &AppHandle, rss_state: &RssState) {
+    let now = Utc::now();
+    let mut alerts = Vec::new();
+    let mut changed = false;
+    for interest in rss_state.interests.write().await.iter_mut() {
+        if !interest.enabled || interest.stale_alert_days.unwrap_or(0) == 0 {
+            continue;
+        }
+        if quiet_since(interest).is_none() {
+            // Never matched: start counting now
+            interest.stale_alert_reset_at = Some(now.to_rfc3339());
+            changed = true;
+        } else if is_stale(interest, now) {
+            let counted_from = interest
+                .last_match_at
+                .as_deref()
+                .or(interest.stale_alert_reset_at.as_deref())
+                .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok())
+                .map(|t| t.with_timezone(&Utc))
+                .unwrap_or(now);
+            alerts.push(InterestStale {
+                interest_id: interest.id.clone(),
+                interest_name: interest.name.clone(),
+                quiet_days: (now - counted_from).num_days(),
+                last_match_at: interest.last_match_at.clone(),
+            });
+            interest.stale_alert_reset_at = Some(now.to_rfc3339());
+            changed = true;
+        }
+    }
+    if changed {
+        crate::commands::rss::persist_interests(&app_handle.state::<AppState>());
+    }
+    for alert in alerts {
+        info!(interest = %alert.interest_name, days = alert.quiet_days, "Interest has gone quiet");
+        let _ = app_handle.emit("rss:interest-stale", &alert);
+        crate::services::notifications::notify_interest_stale(app_handle, &alert.interest_name, alert.quiet_days).await;
+    }
+}
+
 /// Cleanup seen items older than max age (60 days).
 async fn maybe_cleanup_seen_items(rss_state: &RssState) {
     const CLEANUP_INTERVAL_SECS: u64 = 3600; // 1 hour
@@ -161,10 +461,83 @@ pub struct RssState {
     pub bad_items: Arc<RwLock<HashMap<String, BadItem>>>,
     pub pending_matches: Arc<RwLock<Vec<PendingMatch>>>,
     pub service_handle: Arc<Mutex<Option<RssServiceHandle>>>,
-    /// Seen episodes per interest: interest_id -> set of episode identifiers
-    pub seen_episodes: Arc<Mutex<HashMap<String, std::collections::HashSet<String>>>>,
+    /// Seen episodes per interest: interest_id -> episode identifier -> release matched
+    pub seen_episodes: Arc<Mutex<HashMap<String, HashMap<String, SeenEpisode>>>>,
     /// Last cleanup timestamp for periodic maintenance
     pub last_cleanup: Arc<Mutex<std::time::Instant>>,
+    /// Torrents added by approving a match: torrent id -> where it came from
+    pub approved_torrents: Arc<RwLock<HashMap<usize, ApprovedTorrent>>>,
+    /// Approved matches per interest, counted toward `max_matches`
+    pub match_counts: Arc<RwLock<HashMap<String, u32>>>,
+    /// Items from each source's last full fetch, reused when the feed answers 304
+    pub feed_items: Arc<RwLock<HashMap<String, Vec<ParsedFeedItem>>>>,
+    /// Limits metadata lookups to MAX_METADATA_FETCHES at once
+    pub metadata_slots: Arc<tokio::sync::Semaphore>,
+    /// Metadata lookups in progress: match id -> lookup
+    pub metadata_fetches: Arc<std::sync::Mutex<HashMap<String, MetadataFetch>>>,
+    /// Whether rss_prefetch_metadata is walking the inbox
+    pub prefetching: Arc<std::sync::atomic::AtomicBool>,
+    /// Matches being approved or waiting on an approval retry
+    pub approving: Arc<Mutex<HashSet<String>>>,
+    /// File filters waiting on an approved magnet's metadata: info hash -> filters
+    pub pending_file_filters: Arc<RwLock<HashMap<String, PendingFileFilters>>>,
+}
+
+/// An interest's file filters, to apply once the torrent's file list is known.
+pub struct PendingFileFilters {
+    pub match_id: String,
+    pub filters: Vec<FeedFilter>,
+}
+
+/// Metadata lookups running at once. Each adds a paused torrent to the
+/// session and asks the DHT; a dozen at a time slow everything down.
+const MAX_METADATA_FETCHES: usize = 2;
+
+/// Result of a metadata lookup, None until it's done.
+type MetadataOutcome = Option<std::result::Result<TorrentMetadata, String>>;
+
+/// A metadata lookup in progress, shared by every caller asking for the same match.
+pub struct MetadataFetch {
+    result: tokio::sync::watch::Receiver<MetadataOutcome>,
+    cancel: Arc<tokio::sync::Notify>,
+}
+
+/// Takes a lookup out of `metadata_fetches` when it ends, however it ends.
+struct FetchRegistration {
+    fetches: Arc<std::sync::Mutex<HashMap<String, MetadataFetch>>>,
+    match_id: String,
+}
+
+impl Drop for FetchRegistration {
+    fn drop(&mut self) {
+        self.fetches.lock().unwrap_or_else(|e| e.into_inner()).remove(&self.match_id);
+    }
+}
+
+/// The release matched for an episode, kept to recognise better ones later.
+#[derive(Debug, Clone)]
+pub struct SeenEpisode {
+    pub media: MediaInfo,
+    pub title: String,
+    /// Torrent it was downloaded into, once its match is approved
+    pub torrent_id: Option<usize>,
+}
+
+/// What episode tracking decided about an item for one interest.
+enum EpisodeCheck {
+    New,
+    Duplicate,
+    /// A season pack the interest skips
+    SeasonPack,
+    /// A better release of an episode matched before
+    Upgrade(SeenEpisode),
+}
+
+/// The interest behind a torrent added from an approved match.
+#[derive(Debug, Clone)]
+pub struct ApprovedTorrent {
+    pub interest_id: String,
+    pub interest_name: String,
 }
 
 impl RssState {
@@ -178,6 +551,14 @@ impl RssState {
             service_handle: Arc::new(Mutex::new(None)),
             seen_episodes: Arc::new(Mutex::new(HashMap::new())),
             last_cleanup: Arc::new(Mutex::new(std::time::Instant::now())),
+            approved_torrents: Arc::new(RwLock::new(HashMap::new())),
+            match_counts: Arc::new(RwLock::new(HashMap::new())),
+            feed_items: Arc::new(RwLock::new(HashMap::new())),
+            metadata_slots: Arc::new(tokio::sync::Semaphore::new(MAX_METADATA_FETCHES)),
+            metadata_fetches: Arc::new(std::sync::Mutex::new(HashMap::new())),
+            prefetching: Arc::new(std::sync::atomic::AtomicBool::new(false)),
+            approving: Arc::new(Mutex::new(HashSet::new())),
+            pending_file_filters: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 }
@@ -204,14 +585,82 @@ pub struct FetchFeedResult {
     pub not_modified: bool,
 }
 
+/// Default User-Agent sent to feeds.
+const DEFAULT_USER_AGENT: &str = concat!("whenThen/", env!("CARGO_PKG_VERSION"));
+
+/// Default feed request timeout so a hanging feed can't stall the polling loop.
+const DEFAULT_FEED_TIMEOUT_SECS: u64 = 30;
+
+/// Per-request settings for fetching a feed.
+#[derive(Debug, Clone, Default)]
+pub struct FeedFetchOptions<'a> {
+    pub auth: Option<&'a FeedAuth>,
+    pub user_agent: Option<&'a str>,
+    pub timeout_secs: Option<u64>,
+}
+
+impl<'a> FeedFetchOptions<'a> {
+    pub fn from_source(source: &'a Source) -> Self {
+        Self {
+            auth: Some(&source.auth),
+            user_agent: source.user_agent.as_deref(),
+            timeout_secs: source.request_timeout_secs,
+        }
+    }
+}
+
+/// Build an HTTP client with the configured User-Agent and timeout.
+pub(crate) fn build_feed_client(opts: &FeedFetchOptions<'_>) -> Result<reqwest::Client> {
+    let user_agent = opts
+        .user_agent
+        .filter(|ua| !ua.is_empty())
+        .unwrap_or(DEFAULT_USER_AGENT);
+    let timeout_secs = opts
+        .timeout_secs
+        .filter(|t| *t > 0)
+        .unwrap_or(DEFAULT_FEED_TIMEOUT_SECS);
+
+    Ok(reqwest::Client::builder()
+        .user_agent(user_agent)
+        .timeout(Duration::from_secs(timeout_secs))
+        .build()?)
+}
+
+/// Attach feed credentials to a request.
+fn apply_auth(request: reqwest::RequestBuilder, auth: Option<&FeedAuth>) -> reqwest::RequestBuilder {
+    let Some(auth) = auth else {
+        return request;
+    };
+
+    match auth.auth_type {
+        FeedAuthType::None => request,
+        FeedAuthType::Basic => match &auth.username {
+            Some(username) => request.basic_auth(username, auth.password.as_deref()),
+            None => {
+                warn!("Basic auth configured without a username, sending unauthenticated");
+                request
+            }
+        },
+        FeedAuthType::Cookie => match &auth.cookie_string {
+            Some(cookie) => request.header(reqwest::header::COOKIE, cookie),
+            None => request,
+        },
+        FeedAuthType::Header => match (&auth.header_name, &auth.header_value) {
+            (Some(name), Some(value)) if !name.is_empty() => request.header(name.as_str(), value.as_str()),
+            _ => request,
+        },
+    }
+}
+
 /// Fetch and parse an RSS feed from URL with optional conditional headers.
 pub async fn fetch_feed_with_cache(
     url: &str,
     etag: Option<&str>,
     last_modified: Option<&str>,
+    opts: &FeedFetchOptions<'_>,
 ) -> Result<FetchFeedResult> {
-    let client = reqwest::Client::new();
-    let mut request = client.get(url);
+    let client = build_feed_client(opts)?;
+    let mut request = apply_auth(client.get(url), opts.auth);
 
     if let Some(etag) = etag {
         request = request.header("If-None-Match", etag);
@@ -246,7 +695,7 @@ pub async fn fetch_feed_with_cache(
     let bytes = response.bytes().await?;
     let feed = feed_rs::parser::parse(&bytes[..])?;
 
-    let items = parse_feed_entries(feed);
+    let items = parse_feed_entries(feed, &bytes);
 
     Ok(FetchFeedResult {
         items,
@@ -257,18 +706,26 @@ pub async fn fetch_feed_with_cache(
 }
 
 /// Fetch and parse an RSS feed from URL (simple version without caching).
-pub async fn fetch_feed(url: &str) -> Result<Vec<ParsedFeedItem>> {
-    let response = reqwest::get(url).await?;
+pub async fn fetch_feed(url: &str, opts: &FeedFetchOptions<'_>) -> Result<Vec<ParsedFeedItem>> {
+    let client = build_feed_client(opts)?;
+    let response = apply_auth(client.get(url), opts.auth).send().await?;
     let bytes = response.bytes().await?;
     let feed = feed_rs::parser::parse(&bytes[..])?;
-    Ok(parse_feed_entries(feed))
+    Ok(parse_feed_entries(feed, &bytes))
 }
 
-/// Parse feed entries into ParsedFeedItem structs.
-fn parse_feed_entries(feed: feed_rs::model::Feed) -> Vec<ParsedFeedItem> {
+/// Parse feed entries into ParsedFeedItem structs. The raw document is
+/// scanned for seeder counts, which feed-rs drops with unknown namespaces.
+pub(crate) fn parse_feed_entries(feed: feed_rs::model::Feed, raw: &[u8]) -> Vec<ParsedFeedItem> {
+    let mut raw_seeders = seeders_per_entry(&String::from_utf8_lossy(raw));
+    if raw_seeders.len() != feed.entries.len() {
+        raw_seeders.clear();
+    }
+
     feed.entries
         .into_iter()
-        .map(|entry| {
+        .enumerate()
+        .map(|(index, entry)| {
             let id = entry.id.clone();
             // Extract GUID - some feeds use a dedicated guid field in extensions
             let guid = id.clone();
@@ -277,10 +734,15 @@ fn parse_feed_entries(feed: feed_rs::model::Feed) -> Vec<ParsedFeedItem> {
             // Look for magnet URI in links or content
             let mut magnet_uri = None;
             let mut torrent_url = None;
+            let mut seeds = Vec::new();
 
             // Check all links
             for link in &entry.links {
-                if link.href.starts_with("magnet:") {
+                if link.rel.as_deref() == Some("enclosure")
+                    && web_seeds::is_seed_hint(&link.href, link.media_type.as_deref())
+                {
+                    seeds.push(link.href.clone());
+                } else if link.href.starts_with("magnet:") {
                     magnet_uri = Some(link.href.clone());
                 } else if link.href.ends_with(".torrent") {
                     torrent_url = Some(link.href.clone());
@@ -301,7 +763,12 @@ fn parse_feed_entries(feed: feed_rs::model::Feed) -> Vec<ParsedFeedItem> {
                 for content in &media.content {
                     if let Some(url) = &content.url {
                         let url_str = url.to_string();
-                        if url_str.starts_with("magnet:") {
+                        let media_type = content.content_type.as_ref().map(|t| t.to_string());
+                        if web_seeds::is_seed_hint(&url_str, media_type.as_deref()) {
+                            if !seeds.contains(&url_str) {
+                                seeds.push(url_str);
+                            }
+                        } else if url_str.starts_with("magnet:") {
                             magnet_uri = Some(url_str);
                         } else if url_str.ends_with(".torrent") || torrent_url.is_none() {
                             torrent_url = Some(url_str);
@@ -348,6 +815,13 @@ fn parse_feed_entries(feed: feed_rs::model::Feed) -> Vec<ParsedFeedItem> {
 
             let published = entry.published.map(|d| d.to_rfc3339());
 
+            // Namespace extension first, then "Seeders: N" in the description
+            let seeders = raw_seeders.get(index).copied().flatten().or_else(|| {
+                let body = entry.content.as_ref().and_then(|c| c.body.as_deref());
+                let summary = entry.summary.as_ref().map(|s| s.content.as_str());
+                body.into_iter().chain(summary).find_map(extract_seeders_from_text)
+            });
+
             ParsedFeedItem {
                 id,
                 guid,
@@ -355,7 +829,9 @@ fn parse_feed_entries(feed: feed_rs::model::Feed) -> Vec<ParsedFeedItem> {
                 magnet_uri,
                 torrent_url,
                 size,
+                seeders,
                 published_date: published,
+                web_seeds: seeds,
             }
         })
         .collect()
@@ -370,8 +846,59 @@ pub struct ParsedFeedItem {
     pub magnet_uri: Option<String>,
     pub torrent_url: Option<String>,
     pub size: Option<u64>,
+    pub seeders: Option<u32>,
     #[allow(dead_code)]
     pub published_date: Option<String>,
+    /// Enclosures serving the content itself, usable as web seeds.
+    pub web_seeds: Vec<String>,
+}
+
+static ENTRY_START_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"<(?:item|entry)[\s>]").unwrap());
+
+/// `<torrent:seeds>`, `<nyaa:seeders>` and Torznab's `<torznab:attr name="seeders" value="N"/>`.
+static SEEDERS_TAG_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?i)<(?:[\w-]+:)?(?:seeders|seeds)>\s*(\d+)\s*<|name="seeders"\s+value="(\d+)""#).unwrap()
+});
+
+static SEEDERS_TEXT_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\bseed(?:er)?s\s*[:=]\s*(\d+)").unwrap());
+
+/// Seeder count from each `<item>`/`<entry>` in a raw feed document, in order.
+fn seeders_per_entry(xml: &str) -> Vec<Option<u32>> {
+    let starts: Vec<usize> = ENTRY_START_RE.find_iter(xml).map(|m| m.start()).collect();
+    starts
+        .iter()
+        .enumerate()
+        .map(|(i, &start)| {
+            let end = starts.get(i + 1).copied().unwrap_or(xml.len());
+            SEEDERS_TAG_RE
+                .captures(&xml[start..end])
+                .and_then(|caps| caps.get(1).or(caps.get(2)))
+                .and_then(|m| m.as_str().parse().ok())
+        })
+        .collect()
+}
+
+/// Seeder count written into a description, e.g. "Seeders: 42".
+fn extract_seeders_from_text(text: &str) -> Option<u32> {
+    SEEDERS_TEXT_RE.captures(text)?.get(1)?.as_str().parse().ok()
+}
+
+/// Parse a size range in MB: "min-max", "-max" or "min-". None when malformed.
+fn parse_size_range(value: &str) -> Option<(Option<u64>, Option<u64>)> {
+    let (min, max) = value.split_once('-')?;
+    let bound = |part: &str| -> Option<Option<u64>> {
+        let part = part.trim();
+        if part.is_empty() {
+            Some(None)
+        } else {
+            part.parse().ok().map(Some)
+        }
+    };
+    match (bound(min)?, bound(max)?) {
+        (None, None) => None,
+        range => Some(range),
+    }
 }
 
 /// Extract size in bytes from title patterns like "1.5 GB" or "500 MB".
@@ -404,9 +931,13 @@ fn evaluate_single_filter(item: &ParsedFeedItem, filter: &FeedFilter) -> bool {
             let pattern = filter.value.to_lowercase();
             !title_lower.contains(&pattern)
         }
-        FilterType::Regex => Regex::new(&filter.value)
-            .map(|re| re.is_match(&item.title))
-            .unwrap_or(false),
+        FilterType::Regex => match Regex::new(&filter.value) {
+            Ok(re) => re.is_match(&item.title),
+            Err(e) => {
+                warn_invalid_pattern(&filter.value, &e);
+                false
+            }
+        },
         FilterType::Wildcard => {
             let pattern = wildcard_to_regex(&filter.value.to_lowercase());
             Regex::new(&format!("(?i){}", pattern))
@@ -414,109 +945,709 @@ fn evaluate_single_filter(item: &ParsedFeedItem, filter: &FeedFilter) -> bool {
                 .unwrap_or(false)
         }
         FilterType::SizeRange => {
-            if let Some(size) = item.size {
-                let parts: Vec<&str> = filter.value.split('-').collect();
-                if parts.len() == 2 {
-                    let min_mb: u64 = parts[0].parse().unwrap_or(0);
-                    let max_mb: u64 = parts[1].parse().unwrap_or(u64::MAX);
+            // No size info or a malformed range = pass through
+            match (item.size, parse_size_range(&filter.value)) {
+                (Some(size), Some((min_mb, max_mb))) => {
                     let size_mb = size / (1024 * 1024);
-                    size_mb >= min_mb && size_mb <= max_mb
-                } else {
-                    true
+                    min_mb.is_none_or(|min| size_mb >= min) && max_mb.is_none_or(|max| size_mb <= max)
                 }
-            } else {
-                true // No size info = pass through
+                _ => true,
+            }
+        }
+        FilterType::MinSeeders => {
+            // No seeder info = pass through
+            match (item.seeders, filter.value.trim().parse::<u32>()) {
+                (Some(seeders), Ok(min)) => seeders >= min,
+                _ => true,
             }
         }
     }
 }
 
-/// Evaluate filters against a feed item.
-pub fn evaluate_filters(item: &ParsedFeedItem, filters: &[FeedFilter]) -> Option<String> {
-    evaluate_filters_with_logic(item, filters, &FilterLogic::And)
+/// Patterns already reported as invalid, so each is only logged once per run.
+static WARNED_PATTERNS: LazyLock<std::sync::Mutex<HashSet<String>>> =
+    LazyLock::new(|| std::sync::Mutex::new(HashSet::new()));
+
+fn warn_invalid_pattern(pattern: &str, error: &regex::Error) {
+    let Ok(mut warned) = WARNED_PATTERNS.lock() else {
+        return;
+    };
+    if warned.insert(pattern.to_string()) {
+        warn!("Regex filter /{}/ never matches: {}", pattern, regex_error(pattern, error).message);
+    }
 }
 
-/// Evaluate filters with specified and/or logic.
-pub fn evaluate_filters_with_logic(
-    item: &ParsedFeedItem,
-    filters: &[FeedFilter],
-    logic: &FilterLogic,
-) -> Option<String> {
-    let enabled_filters: Vec<_> = filters.iter().filter(|f| f.enabled).collect();
-    if enabled_filters.is_empty() {
-        return Some("no filters".to_string());
+/// Check that a filter can be evaluated as written.
+pub fn validate_filter(filter: &FeedFilter) -> std::result::Result<(), FilterValidationError> {
+    let invalid = |kind, message: &str, position| FilterValidationError {
+        kind,
+        message: message.to_string(),
+        position,
+    };
+
+    if filter.value.trim().is_empty() {
+        return Err(invalid(FilterErrorKind::EmptyValue, "Filter value is empty", None));
     }
 
-    let results: Vec<bool> = enabled_filters
-        .iter()
-        .map(|f| evaluate_single_filter(item, f))
-        .collect();
+    match filter.filter_type {
+        FilterType::Regex => Regex::new(&filter.value)
+            .map(|_| ())
+            .map_err(|e| regex_error(&filter.value, &e)),
+        FilterType::SizeRange => {
+            let Some((min, max)) = filter.value.split_once('-') else {
+                return Err(invalid(
+                    FilterErrorKind::InvalidSizeRange,
+                    "Size range must look like \"min-max\" in MB, e.g. \"500-4000\"",
+                    None,
+                ));
+            };
+            let parse = |part: &str, offset: usize| -> std::result::Result<Option<u64>, FilterValidationError> {
+                let part = part.trim();
+                if part.is_empty() {
+                    return Ok(None);
+                }
+                part.parse().map(Some).map_err(|_| {
+                    invalid(
+                        FilterErrorKind::InvalidSizeRange,
+                        &format!("\"{part}\" isn't a whole number of MB"),
+                        Some(offset),
+                    )
+                })
+            };
+            let min_offset = filter.value.len() - filter.value.trim_start().len();
+            let max_offset = filter.value[..min.len() + 1].chars().count();
+            match (parse(min, min_offset)?, parse(max, max_offset)?) {
+                (None, None) => Err(invalid(
+                    FilterErrorKind::InvalidSizeRange,
+                    "Size range needs a minimum, a maximum or both",
+                    None,
+                )),
+                (Some(min), Some(max)) if min > max => Err(invalid(
+                    FilterErrorKind::InvalidSizeRange,
+                    &format!("Minimum {min} MB is larger than maximum {max} MB"),
+                    None,
+                )),
+                _ => Ok(()),
+            }
+        }
+        FilterType::MinSeeders => filter.value.trim().parse::<u32>().map(|_| ()).map_err(|_| {
+            invalid(
+                FilterErrorKind::InvalidNumber,
+                &format!("\"{}\" isn't a whole number of seeders", filter.value.trim()),
+                None,
+            )
+        }),
+        // Plain text and wildcards (escaped before compiling) are always valid
+        FilterType::MustContain | FilterType::MustNotContain | FilterType::Wildcard => Ok(()),
+    }
+}
 
-    let matches = match logic {
-        FilterLogic::Or => results.iter().any(|&r| r),
-        FilterLogic::And => results.iter().all(|&r| r),
+/// Turn a regex compile error into a one-line message and the offending position.
+fn regex_error(pattern: &str, error: &regex::Error) -> FilterValidationError {
+    let text = error.to_string();
+    let message = text
+        .lines()
+        .find_map(|line| line.strip_prefix("error: "))
+        .unwrap_or(&text)
+        .to_string();
+    // Single-line patterns are echoed indented by 4 with a caret under the problem
+    let position = if pattern.contains('\n') {
+        None
+    } else {
+        text.lines()
+            .find(|line| line.trim_start().starts_with('^') && line.trim().chars().all(|c| c == '^'))
+            .and_then(|line| line.find('^'))
+            .and_then(|column| column.checked_sub(4))
     };
 
-    if !matches {
-        return None;
+    FilterValidationError {
+        kind: FilterErrorKind::InvalidRegex,
+        message,
+        position,
     }
+}
 
-    // Build matched filter description
-    let desc: Vec<String> = enabled_filters
-        .iter()
-        .zip(results.iter())
-        .filter_map(|(f, matched)| {
-            if !matched {
-                return None;
-            }
-            match f.filter_type {
-                FilterType::MustContain => Some(format!("contains \"{}\"", f.value)),
-                FilterType::MustNotContain => Some(format!("excludes \"{}\"", f.value)),
-                FilterType::Regex => Some(format!("regex /{}/", f.value)),
-                FilterType::Wildcard => Some(format!("wildcard \"{}\"", f.value)),
-                FilterType::SizeRange => Some(format!("size {}", f.value)),
-            }
-        })
-        .collect();
+/// Reject interests with enabled filters that can't be evaluated. Empty values
+/// are allowed here since the editor saves new filters before they're filled in.
+pub fn validate_interest_filters(filters: &[FeedFilter]) -> Result<()> {
+    for (i, filter) in filters.iter().enumerate() {
+        if !filter.enabled || filter.value.trim().is_empty() {
+            continue;
+        }
+        if let Err(e) = validate_filter(filter) {
+            let at = e.position.map(|p| format!(" (at position {p})")).unwrap_or_default();
+            return Err(crate::errors::WhenThenError::InvalidInput(format!(
+                "Filter {}: {}{}",
+                i + 1,
+                e.message,
+                at
+            )));
+        }
+    }
+    Ok(())
+}
 
-    Some(desc.join(", "))
+/// Check the feed, file and metadata filters of an interest being saved.
+pub fn validate_interest(interest: &Interest) -> Result<()> {
+    validate_interest_filters(&interest.filters)?;
+    validate_interest_filters(&interest.file_filters)?;
+    validate_interest_filters(&interest.metadata_filters)
 }
 
-/// Test a feed URL with filters without downloading anything.
-pub async fn test_feed(url: &str, filters: &[FeedFilter]) -> Result<FeedTestResult> {
-    let items = fetch_feed(url).await?;
-    let total_count = items.len();
+// ── Interest presets ──────────────────────────────────────────────────────────
 
-    let test_items: Vec<FeedTestItem> = items
-        .iter()
-        .map(|item| {
-            let matched_filter = evaluate_filters(item, filters);
-            FeedTestItem {
-                title: item.title.clone(),
-                matches: matched_filter.is_some(),
-                matched_filter,
-                size: item.size,
-            }
-        })
-        .collect();
+struct PresetSpec {
+    id: &'static str,
+    filters: &'static [(FilterType, &'static str)],
+    filter_logic: FilterLogic,
+    smart_episode_filter: bool,
+}
 
-    let matched_count = test_items.iter().filter(|i| i.matches).count();
+/// Starting points for people who haven't written filters before. Names and
+/// descriptions live in the locale files under presets.<id>.
+const INTEREST_PRESETS: &[PresetSpec] = &[
+    PresetSpec {
+        id: "webDl1080p",
+        filters: &[
+            (FilterType::MustContain, "1080p"),
+            (FilterType::Regex, r"(?i)\bweb[-. ]?dl\b"),
+        ],
+        filter_logic: FilterLogic::And,
+        smart_episode_filter: true,
+    },
+    PresetSpec {
+        id: "excludeCam",
+        filters: &[
+            (FilterType::MustNotContain, "HDCAM"),
+            (FilterType::MustNotContain, "CAMRip"),
+            (FilterType::MustNotContain, ".CAM."),
+            (FilterType::MustNotContain, " CAM "),
+            (FilterType::MustNotContain, "TELESYNC"),
+            (FilterType::MustNotContain, "HDTS"),
+            (FilterType::MustNotContain, ".TS."),
+            (FilterType::MustNotContain, " TS "),
+        ],
+        filter_logic: FilterLogic::And,
+        smart_episode_filter: false,
+    },
+    PresetSpec {
+        id: "dailyByDate",
+        filters: &[(
+            FilterType::Regex,
+            r"\b(19|20)\d{2}[. -](0[1-9]|1[0-2])[. -](0[1-9]|[12]\d|3[01])\b",
+        )],
+        filter_logic: FilterLogic::And,
+        smart_episode_filter: false,
+    },
+    PresetSpec {
+        id: "releaseGroup",
+        // Example groups; the user swaps in the one they trust
+        filters: &[(FilterType::Regex, r"(?i)[-.\[ ](NTb|FLUX|EDITH)\]?$")],
+        filter_logic: FilterLogic::And,
+        smart_episode_filter: true,
+    },
+    PresetSpec {
+        id: "uhdHdr",
+        filters: &[
+            (FilterType::MustContain, "2160p"),
+            (FilterType::Regex, r"(?i)\b(hdr(10)?|dv|dolby[. ]vision)\b"),
+        ],
+        filter_logic: FilterLogic::And,
+        smart_episode_filter: true,
+    },
+];
+
+impl PresetSpec {
+    fn to_preset(&self) -> InterestPreset {
+        InterestPreset {
+            id: self.id.to_string(),
+            name_key: format!("presets.{}.name", self.id),
+            description_key: format!("presets.{}.description", self.id),
+            filters: self
+                .filters
+                .iter()
+                .map(|(filter_type, value)| FeedFilter {
+                    filter_type: filter_type.clone(),
+                    value: value.to_string(),
+                    enabled: true,
+                })
+                .collect(),
+            filter_logic: self.filter_logic.clone(),
+            smart_episode_filter: self.smart_episode_filter,
+        }
+    }
+}
 
-    Ok(FeedTestResult {
-        items: test_items,
-        total_count,
-        matched_count,
+pub fn interest_presets() -> Vec<InterestPreset> {
+    INTEREST_PRESETS.iter().map(PresetSpec::to_preset).collect()
+}
+
+/// A new interest built from a preset, not yet saved.
+pub fn interest_from_preset(preset_id: &str, name: &str, search_term: Option<String>) -> Result<Interest> {
+    let preset = INTEREST_PRESETS
+        .iter()
+        .find(|p| p.id == preset_id)
+        .ok_or_else(|| crate::errors::WhenThenError::NotFound(format!("Interest preset {preset_id}")))?
+        .to_preset();
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(crate::errors::WhenThenError::InvalidInput("Interest name is empty".into()));
+    }
+    Ok(Interest {
+        id: uuid::Uuid::new_v4().to_string(),
+        name: name.to_string(),
+        enabled: true,
+        filters: preset.filters,
+        filter_logic: preset.filter_logic,
+        search_term: search_term.map(|t| t.trim().to_string()).filter(|t| !t.is_empty()),
+        download_path: None,
+        organize_template: None,
+        smart_episode_filter: preset.smart_episode_filter,
+        priority: 0,
+        upgrade_policy: UpgradePolicy::default(),
+        max_matches: None,
+        auto_disable_when_complete: false,
+        default_label: None,
+        stale_alert_days: None,
+        last_match_at: None,
+        stale_alert_reset_at: None,
+        file_filters: Vec::new(),
+        add_paused: None,
+        skip_season_packs: false,
+        metadata_filters: Vec::new(),
+        min_seeders: None,
     })
 }
 
-/// Start the RSS polling service.
-pub fn start_service(app_handle: AppHandle, rss_state: Arc<RssState>) -> RssServiceHandle {
-    let (shutdown_tx, mut shutdown_rx) = tokio::sync::oneshot::channel();
+/// Count how many enabled filters an item satisfies (used to rank interests by specificity).
+fn count_matching_filters(item: &ParsedFeedItem, filters: &[FeedFilter]) -> usize {
+    filters
+        .iter()
+        .filter(|f| f.enabled && evaluate_single_filter(item, f))
+        .count()
+}
+
+/// Find all interests matching an item, ordered with the primary interest first.
+/// Ranking: explicit priority, then number of matched filters, then original order.
+/// Also returns the release the item replaces when it's an upgrade for the primary interest.
+async fn find_matching_interests<'a>(
+    rss_state: &RssState,
+    item: &ParsedFeedItem,
+    interests: &[&'a Interest],
+) -> (Vec<&'a Interest>, Option<SeenEpisode>) {
+    let mut matched: Vec<(&'a Interest, usize, Option<SeenEpisode>)> = Vec::new();
+
+    for interest in interests {
+        if evaluate_filters_with_logic(item, &interest.filters, &interest.filter_logic).is_none() {
+            continue;
+        }
+
+        let replaces = match check_episode(rss_state, interest, &item.title).await {
+            EpisodeCheck::New => None,
+            EpisodeCheck::Duplicate | EpisodeCheck::SeasonPack => continue,
+            EpisodeCheck::Upgrade(previous) => Some(previous),
+        };
+
+        matched.push((interest, count_matching_filters(item, &interest.filters), replaces));
+    }
+
+    matched.sort_by(|a, b| b.0.priority.cmp(&a.0.priority).then(b.1.cmp(&a.1)));
+    let replaces = matched.first_mut().and_then(|m| m.2.take());
+    (matched.into_iter().map(|(i, _, _)| i).collect(), replaces)
+}
+
+/// Check the item's episodes against those seen for an interest. Repeats are
+/// skipped when the smart episode filter is on, except PROPER/REPACK releases
+/// and strictly better releases the interest's upgrade policy wants. A
+/// multi-episode release is new if any of its episodes is. Nothing is marked
+/// seen here; see mark_episode_seen.
+async fn check_episode(rss_state: &RssState, interest: &Interest, title: &str) -> EpisodeCheck {
+    let episode = episode::parse(title);
+    if interest.skip_season_packs && matches!(episode, Some(Episode::SeasonPack(_))) {
+        info!("Skipping season pack for interest {}: {}", interest.name, title);
+        return EpisodeCheck::SeasonPack;
+    }
+    let policy = &interest.upgrade_policy;
+    if !interest.smart_episode_filter && !policy.enabled {
+        return EpisodeCheck::New;
+    }
+    let Some(episode) = episode else {
+        return EpisodeCheck::New;
+    };
+    let keys = episode.keys();
+
+    let seen_eps = rss_state.seen_episodes.lock().await;
+    let Some(interest_eps) = seen_eps.get(&interest.id) else {
+        return EpisodeCheck::New;
+    };
+    if keys.iter().any(|key| !interest_eps.contains_key(key)) {
+        return EpisodeCheck::New;
+    }
+
+    let media = media_info::parse(title);
+    if policy.enabled && keys.iter().all(|key| is_wanted_upgrade(policy, &interest_eps[key].media, &media)) {
+        info!("Upgrade for episode {} of interest {}: {}", keys[0], interest.name, title);
+        return EpisodeCheck::Upgrade(interest_eps[&keys[0]].clone());
+    }
+    // PROPER/REPACK bypasses dedup for quality upgrades
+    if !interest.smart_episode_filter || is_quality_upgrade(title) {
+        return EpisodeCheck::New;
+    }
+    info!("Skipping duplicate episode {} for interest {}", keys[0], interest.name);
+    EpisodeCheck::Duplicate
+}
+
+/// Record the item's episodes as seen for the interest its match is queued
+/// under: new episodes are added, and an upgrade replaces the releases it
+/// supersedes.
+async fn mark_episode_seen(rss_state: &RssState, interest: &Interest, title: &str) {
+    let policy = &interest.upgrade_policy;
+    if !interest.smart_episode_filter && !policy.enabled {
+        return;
+    }
+    let Some(episode) = episode::parse(title) else {
+        return;
+    };
+    let keys = episode.keys();
+    let record = SeenEpisode {
+        media: media_info::parse(title),
+        title: title.to_string(),
+        torrent_id: None,
+    };
+
+    let mut seen_eps = rss_state.seen_episodes.lock().await;
+    let interest_eps = seen_eps.entry(interest.id.clone()).or_default();
+    let upgrade = policy.enabled
+        && keys
+            .iter()
+            .all(|key| interest_eps.get(key).is_some_and(|previous| is_wanted_upgrade(policy, &previous.media, &record.media)));
+    for key in keys {
+        if upgrade {
+            interest_eps.insert(key, record.clone());
+        } else {
+            interest_eps.entry(key).or_insert_with(|| record.clone());
+        }
+    }
+}
+
+/// Whether the policy wants `new` in place of the release matched before.
+fn is_wanted_upgrade(policy: &UpgradePolicy, previous: &MediaInfo, new: &MediaInfo) -> bool {
+    if !media_info::is_better_release(new, previous) {
+        return false;
+    }
+    match policy.target_quality {
+        // Stop once the target is reached, and don't go past it
+        Some(target) => {
+            previous.quality.is_none_or(|q| q.rank() < target.rank())
+                && new.quality.is_some_and(|q| q.rank() <= target.rank())
+        }
+        None => true,
+    }
+}
+
+/// Evaluate filters against a feed item.
+pub fn evaluate_filters(item: &ParsedFeedItem, filters: &[FeedFilter]) -> Option<String> {
+    evaluate_filters_with_logic(item, filters, &FilterLogic::And)
+}
+
+/// Evaluate filters with specified and/or logic.
+pub fn evaluate_filters_with_logic(
+    item: &ParsedFeedItem,
+    filters: &[FeedFilter],
+    logic: &FilterLogic,
+) -> Option<String> {
+    let enabled_filters: Vec<_> = filters.iter().filter(|f| f.enabled).collect();
+    if enabled_filters.is_empty() {
+        return Some("no filters".to_string());
+    }
+
+    let results: Vec<bool> = enabled_filters
+        .iter()
+        .map(|f| evaluate_single_filter(item, f))
+        .collect();
+
+    let matches = match logic {
+        FilterLogic::Or => results.iter().any(|&r| r),
+        FilterLogic::And => results.iter().all(|&r| r),
+    };
+
+    if !matches {
+        return None;
+    }
+
+    // Build matched filter description
+    let desc: Vec<String> = enabled_filters
+        .iter()
+        .zip(results.iter())
+        .filter(|(_, matched)| **matched)
+        .map(|(f, _)| describe_filter(f))
+        .collect();
+
+    Some(desc.join(", "))
+}
+
+/// A filter as shown to the user, e.g. `contains "1080p"`.
+fn describe_filter(f: &FeedFilter) -> String {
+    match f.filter_type {
+        FilterType::MustContain => format!("contains \"{}\"", f.value),
+        FilterType::MustNotContain => format!("excludes \"{}\"", f.value),
+        FilterType::Regex => format!("regex /{}/", f.value),
+        FilterType::Wildcard => format!("wildcard \"{}\"", f.value),
+        FilterType::SizeRange => match parse_size_range(&f.value) {
+            Some((Some(min), None)) => format!("size ≥ {min} MB"),
+            Some((None, Some(max))) => format!("size ≤ {max} MB"),
+            _ => format!("size {} MB", f.value),
+        },
+        FilterType::MinSeeders => format!("≥ {} seeders", f.value.trim()),
+    }
+}
+
+/// The metadata stage of an interest's filters, run on a match's file list.
+/// Text filters pass if any file name matches them, except "must not
+/// contain", which every file must pass; size ranges apply to the total
+/// size. Seeder counts aren't part of the metadata, so min seeders filters
+/// are left to the seeder check. Returns why the metadata fails, or None if
+/// it passes.
+pub fn metadata_rejection(metadata: &TorrentMetadata, filters: &[FeedFilter]) -> Option<String> {
+    let items: Vec<ParsedFeedItem> = metadata
+        .files
+        .iter()
+        .map(|file| ParsedFeedItem {
+            id: String::new(),
+            guid: String::new(),
+            title: file.name.clone(),
+            magnet_uri: None,
+            torrent_url: None,
+            size: Some(metadata.total_size),
+            seeders: None,
+            published_date: None,
+            web_seeds: Vec::new(),
+        })
+        .collect();
+    let failed = filters
+        .iter()
+        .filter(|f| f.enabled && f.filter_type != FilterType::MinSeeders)
+        .find(|filter| {
+            let mut results = items.iter().map(|item| evaluate_single_filter(item, filter));
+            let passes = match filter.filter_type {
+                FilterType::MustNotContain => results.all(|r| r),
+                _ => results.any(|r| r),
+            };
+            !passes
+        })?;
+    Some(match failed.filter_type {
+        FilterType::MustContain => format!("No file contains \"{}\"", failed.value),
+        FilterType::MustNotContain => format!("A file contains \"{}\"", failed.value),
+        FilterType::Regex => format!("No file matches /{}/", failed.value),
+        FilterType::Wildcard => format!("No file matches \"{}\"", failed.value),
+        FilterType::SizeRange => format!("Total size is out of range ({})", describe_filter(failed)),
+        FilterType::MinSeeders => format!("Too few seeders ({})", describe_filter(failed)),
+    })
+}
+
+/// Files of a torrent an interest's file filters keep, and the paths of those they skip.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileSelection {
+    pub only_files: Vec<usize>,
+    pub skipped: Vec<String>,
+}
+
+/// Run each (path, size) through an interest's file filters; a file is kept
+/// if it passes all of them. None when there's nothing to leave out, or when
+/// the filters would leave out everything, in which case the torrent is
+/// downloaded whole rather than added empty.
+pub fn select_files(files: &[(String, u64)], filters: &[FeedFilter]) -> Option<FileSelection> {
+    if !filters.iter().any(|f| f.enabled) {
+        return None;
+    }
+    let mut selection = FileSelection { only_files: Vec::new(), skipped: Vec::new() };
+    for (index, (path, size)) in files.iter().enumerate() {
+        let item = ParsedFeedItem {
+            id: String::new(),
+            guid: String::new(),
+            title: path.clone(),
+            magnet_uri: None,
+            torrent_url: None,
+            size: Some(*size),
+            seeders: None,
+            published_date: None,
+            web_seeds: Vec::new(),
+        };
+        if evaluate_filters(&item, filters).is_some() {
+            selection.only_files.push(index);
+        } else {
+            selection.skipped.push(path.clone());
+        }
+    }
+    if selection.skipped.is_empty() {
+        return None;
+    }
+    if selection.only_files.is_empty() {
+        warn!("File filters leave out every file; downloading all {}", files.len());
+        return None;
+    }
+    Some(selection)
+}
+
+/// Test a feed URL with filters without downloading anything.
+pub async fn test_feed(
+    url: &str,
+    filters: &[FeedFilter],
+    opts: &FeedFetchOptions<'_>,
+) -> Result<FeedTestResult> {
+    let items = fetch_feed(url, opts).await?;
+    let total_count = items.len();
+
+    let test_items: Vec<FeedTestItem> = items
+        .iter()
+        .map(|item| {
+            let matched_filter = evaluate_filters(item, filters);
+            FeedTestItem {
+                title: item.title.clone(),
+                matches: matched_filter.is_some(),
+                matched_filter,
+                size: item.size,
+                seeders: item.seeders,
+                source_id: None,
+                source_name: None,
+                would_have_matched: false,
+            }
+        })
+        .collect();
+
+    let matched_count = test_items.iter().filter(|i| i.matches).count();
+
+    Ok(FeedTestResult {
+        items: test_items,
+        total_count,
+        matched_count,
+    })
+}
+
+/// Test an interest against saved feed sources and scrapers (by id).
+/// Feeds are fetched with their cache headers, falling back to the items from
+/// their last fetch when unchanged. Items already seen are flagged when the
+/// interest would have matched them.
+pub async fn test_interest_against_sources(
+    state: &AppState,
+    interest: &Interest,
+    source_ids: &[String],
+) -> Result<FeedTestResult> {
+    let mut test_items = Vec::new();
+    let mut first_error = None;
+
+    for source_id in source_ids {
+        let source = state
+            .rss_state
+            .sources
+            .read()
+            .await
+            .iter()
+            .find(|s| &s.id == source_id)
+            .cloned();
+        let result = match source {
+            Some(source) => test_interest_against_feed(&state.rss_state, interest, &source).await,
+            None => {
+                let config = state
+                    .scraper_state
+                    .configs
+                    .read()
+                    .await
+                    .iter()
+                    .find(|c| &c.id == source_id)
+                    .cloned()
+                    .ok_or_else(|| crate::errors::WhenThenError::NotFound(format!("Source {source_id}")))?;
+                crate::services::scraper::test_interest(&state.scraper_state, interest, &config).await
+            }
+        };
+
+        match result {
+            Ok(items) => test_items.extend(items),
+            Err(e) => {
+                warn!("Interest test failed for source {}: {}", source_id, e);
+                if first_error.is_none() {
+                    first_error = Some(e);
+                }
+            }
+        }
+    }
+
+    // Only fail when no source could be tested
+    if let Some(e) = first_error.filter(|_| test_items.is_empty()) {
+        return Err(e);
+    }
+
+    let matched_count = test_items.iter().filter(|i| i.matches).count();
+    Ok(FeedTestResult {
+        total_count: test_items.len(),
+        items: test_items,
+        matched_count,
+    })
+}
+
+async fn test_interest_against_feed(
+    rss_state: &RssState,
+    interest: &Interest,
+    source: &Source,
+) -> Result<Vec<FeedTestItem>> {
+    let opts = FeedFetchOptions::from_source(source);
+    let placeholder = has_search_placeholder(&source.url);
+
+    let items = if placeholder {
+        fetch_feed(&build_search_url(&source.url, interest), &opts).await?
+    } else {
+        let result = fetch_feed_with_cache(
+            &source.url,
+            source.etag.as_deref(),
+            source.last_modified.as_deref(),
+            &opts,
+        )
+        .await?;
+        let cached = rss_state.feed_items.read().await.get(&source.id).cloned();
+        match (result.not_modified, cached) {
+            (true, Some(items)) => items,
+            // Nothing cached since startup, so fetch the full feed
+            (true, None) => fetch_feed(&source.url, &opts).await?,
+            (false, _) => {
+                rss_state
+                    .feed_items
+                    .write()
+                    .await
+                    .insert(source.id.clone(), result.items.clone());
+                result.items
+            }
+        }
+    };
+
+    let seen = rss_state.seen_items.lock().await;
+    Ok(items
+        .iter()
+        .map(|item| {
+            let matched_filter = evaluate_filters_with_logic(item, &interest.filters, &interest.filter_logic);
+            let key = seen_item_key(source, placeholder.then_some(interest), item);
+            FeedTestItem {
+                title: item.title.clone(),
+                matches: matched_filter.is_some(),
+                would_have_matched: matched_filter.is_some() && seen.contains_key(&key),
+                matched_filter,
+                size: item.size,
+                seeders: item.seeders,
+                source_id: Some(source.id.clone()),
+                source_name: Some(source.name.clone()),
+            }
+        })
+        .collect())
+}
+
+/// Start the RSS polling service.
+pub fn start_service(app_handle: AppHandle, rss_state: Arc<RssState>) -> RssServiceHandle {
+    let (shutdown_tx, mut shutdown_rx) = tokio::sync::oneshot::channel();
 
     let handle = app_handle.clone();
     tokio::spawn(async move {
         let mut interval = tokio::time::interval(Duration::from_secs(60));
         let mut last_global_check = std::time::Instant::now() - Duration::from_secs(3600); // Check immediately on startup
+        let mut last_stale_check: Option<std::time::Instant> = None;
 
         loop {
             tokio::select! {
@@ -530,6 +1661,11 @@ pub fn start_service(app_handle: AppHandle, rss_state: Arc<RssState>) -> RssServ
                     // Periodic cleanup of old seen items
                     maybe_cleanup_seen_items(&rss_state).await;
 
+                    if last_stale_check.is_none_or(|t| t.elapsed() >= STALE_CHECK_INTERVAL) {
+                        last_stale_check = Some(std::time::Instant::now());
+                        check_stale_interests(&handle, &rss_state).await;
+                    }
+
                     // Get global check interval from settings
                     let global_interval_mins = state.config.read().await.rss_check_interval_minutes;
                     let global_interval_secs = (global_interval_mins as u64) * 60;
@@ -557,7 +1693,7 @@ pub fn start_service(app_handle: AppHandle, rss_state: Arc<RssState>) -> RssServ
                         }
 
                         // Check if source is in backoff
-                        if is_in_backoff(&source) {
+                        if is_in_backoff(source.retry_after.as_deref()) {
                             continue;
                         }
 
@@ -575,13 +1711,11 @@ pub fn start_service(app_handle: AppHandle, rss_state: Arc<RssState>) -> RssServ
                         }
 
                         match check_source_for_matches_with_cache(&handle, &rss_state, &source, &enabled_interests).await {
-                            Ok((count, new_etag, new_last_modified)) => {
+                            Ok((count, new_items, new_etag, new_last_modified)) => {
                                 if count > 0 {
                                     info!("Source {} queued {} new items for screening", source.name, count);
                                 }
-                                // Reset failure count on success
-                                source.failure_count = 0;
-                                source.retry_after = None;
+                                record_check_success(&mut source, new_items);
                                 // Update cache headers
                                 if new_etag.is_some() {
                                     source.etag = new_etag;
@@ -591,9 +1725,10 @@ pub fn start_service(app_handle: AppHandle, rss_state: Arc<RssState>) -> RssServ
                                 }
                             }
                             Err(e) => {
+                                // Timeouts land here too, so a hanging feed backs off like any other failure
                                 warn!("Failed to check source {}: {}", source.name, e);
                                 // Increment failure count and set backoff
-                                source.failure_count = source.failure_count.saturating_add(1);
+                                record_check_failure(&handle, &mut source, &e);
                                 let backoff = calculate_backoff(source.failure_count);
                                 source.retry_after = Some((now_utc + chrono::Duration::from_std(backoff).unwrap_or_default()).to_rfc3339());
                                 info!("Source {} will retry in {} minutes", source.name, backoff.as_secs() / 60);
@@ -617,13 +1752,32 @@ pub fn start_service(app_handle: AppHandle, rss_state: Arc<RssState>) -> RssServ
                         }
                     }
 
+                    crate::services::scraper::check_scrapers(
+                        &handle,
+                        &state,
+                        &enabled_interests,
+                        global_interval_mins,
+                        global_check_due,
+                        false,
+                    )
+                    .await;
+                    crate::services::torznab::check_indexers(
+                        &handle,
+                        &state,
+                        &enabled_interests,
+                        global_interval_mins,
+                        global_check_due,
+                        false,
+                    )
+                    .await;
+
                     if global_check_due {
                         last_global_check = now_instant;
                     }
 
                     // Persist seen items and sources after checking
-                    crate::commands::rss::persist_seen_items(&handle, &state).await;
-                    crate::commands::rss::persist_sources_internal(&handle, &state).await;
+                    crate::commands::rss::persist_seen_items(&state);
+                    crate::commands::rss::persist_sources_internal(&state);
                 }
             }
         }
@@ -632,18 +1786,42 @@ pub fn start_service(app_handle: AppHandle, rss_state: Arc<RssState>) -> RssServ
     RssServiceHandle { shutdown_tx }
 }
 
+/// Reset failure tracking after a successful check.
+fn record_check_success(source: &mut Source, new_items: usize) {
+    source.failure_count = 0;
+    source.retry_after = None;
+    source.last_error = None;
+    source.last_success_at = Some(Utc::now().to_rfc3339());
+    source.items_seen_total = source.items_seen_total.saturating_add(new_items as u64);
+}
+
+/// Record a failed check and notify the frontend.
+fn record_check_failure(app_handle: &AppHandle, source: &mut Source, error: &crate::errors::WhenThenError) {
+    let message = error.to_string();
+    source.failure_count = source.failure_count.saturating_add(1);
+    source.last_error = Some(message.clone());
+    let _ = app_handle.emit(
+        "rss:source-error",
+        serde_json::json!({
+            "source_id": source.id,
+            "source_name": source.name,
+            "message": message,
+        }),
+    );
+}
+
 /// Check a source against all interests with HTTP caching support.
-/// Returns (match_count, new_etag, new_last_modified).
+/// Returns (match_count, new_item_count, new_etag, new_last_modified).
 async fn check_source_for_matches_with_cache(
     app_handle: &AppHandle,
     rss_state: &RssState,
     source: &Source,
     interests: &[&Interest],
-) -> Result<(usize, Option<String>, Option<String>)> {
+) -> Result<(usize, usize, Option<String>, Option<String>)> {
     // For search placeholder URLs, we can't use caching (different URL per interest)
     if has_search_placeholder(&source.url) {
-        let count = check_source_for_matches(app_handle, rss_state, source, interests).await?;
-        return Ok((count, None, None));
+        let (count, new_items) = check_source_for_matches(app_handle, rss_state, source, interests).await?;
+        return Ok((count, new_items, None, None));
     }
 
     // Use ETag/Last-Modified caching for standard feeds
@@ -651,23 +1829,26 @@ async fn check_source_for_matches_with_cache(
         &source.url,
         source.etag.as_deref(),
         source.last_modified.as_deref(),
+        &FeedFetchOptions::from_source(source),
     )
     .await?;
 
     if result.not_modified {
         info!("Source {} unchanged (304 Not Modified)", source.name);
-        return Ok((0, None, None));
+        return Ok((0, 0, None, None));
     }
+    rss_state
+        .feed_items
+        .write()
+        .await
+        .insert(source.id.clone(), result.items.clone());
 
     let mut matched_count = 0;
+    let mut new_items = 0;
 
     for item in &result.items {
         // RACE CONDITION FIX: Build the dedup key based on source settings
-        let item_key = if source.use_guid_dedup {
-            format!("{}:{}", source.id, item.guid)
-        } else {
-            format!("{}:{}", source.id, item.id)
-        };
+        let item_key = seen_item_key(source, None, item);
 
         // RACE CONDITION FIX: Hold lock across check+insert
         let mut seen = rss_state.seen_items.lock().await;
@@ -675,88 +1856,100 @@ async fn check_source_for_matches_with_cache(
             continue;
         }
 
+        // Items count as new once they're marked seen; unmatched ones are
+        // looked at again on every poll
         let now = Utc::now().to_rfc3339();
         if item.magnet_uri.is_none() && item.torrent_url.is_none() {
+            new_items += 1;
             seen.insert(item_key.clone(), now);
             continue;
         }
 
-        // PROPER/REPACK bypasses dedup for quality upgrades
-        let is_upgrade = is_quality_upgrade(&item.title);
-
-        // Check against all interests (first match wins)
-        for interest in interests {
-            let matched =
-                evaluate_filters_with_logic(item, &interest.filters, &interest.filter_logic);
-            if matched.is_none() {
-                continue;
-            }
-
-            // Skip repeated episodes unless this is a PROPER/REPACK upgrade
-            if interest.smart_episode_filter && !is_upgrade {
-                if let Some(episode_id) = extract_episode_id(&item.title) {
-                    let mut seen_eps = rss_state.seen_episodes.lock().await;
-                    let interest_eps = seen_eps.entry(interest.id.clone()).or_default();
-                    if interest_eps.contains(&episode_id) {
-                        info!("Skipping duplicate episode {} for interest {}", episode_id, interest.name);
-                        continue;
-                    }
-                    interest_eps.insert(episode_id);
-                }
-            }
-
-            // Insert to seen BEFORE dropping lock (race condition fix)
-            seen.insert(item_key.clone(), now.clone());
-            drop(seen);
+        if is_bad_item(rss_state, &item.title, item.magnet_uri.as_deref()).await {
+            info!("Skipping bad item: {}", item.title);
+            new_items += 1;
+            seen.insert(item_key.clone(), now);
+            continue;
+        }
 
-            let pending = PendingMatch {
-                id: uuid::Uuid::new_v4().to_string(),
-                source_id: source.id.clone(),
-                source_name: source.name.clone(),
-                interest_id: interest.id.clone(),
-                interest_name: interest.name.clone(),
-                title: item.title.clone(),
-                magnet_uri: item.magnet_uri.clone(),
-                torrent_url: item.torrent_url.clone(),
-                created_at: Utc::now().to_rfc3339(),
-                metadata: None,
-            };
+        // Evaluate every interest; the most specific one becomes the primary
+        let (matched_interests, replaces) = find_matching_interests(rss_state, item, interests).await;
+        let Some(primary) = matched_interests.first().copied() else {
+            continue;
+        };
+        mark_episode_seen(rss_state, primary, &item.title).await;
 
-            rss_state
-                .pending_matches
-                .write()
-                .await
-                .push(pending.clone());
-            matched_count += 1;
+        // Insert to seen BEFORE dropping lock (race condition fix)
+        new_items += 1;
+        seen.insert(item_key.clone(), now.clone());
+        drop(seen);
 
-            let _ = app_handle.emit(
-                "rss:new-match",
-                serde_json::json!({
-                    "id": pending.id,
-                    "source_name": source.name,
-                    "interest_name": interest.name,
-                    "title": item.title,
-                }),
-            );
+        let pending = PendingMatch {
+            id: uuid::Uuid::new_v4().to_string(),
+            source_id: source.id.clone(),
+            source_name: source.name.clone(),
+            sources: vec![source.name.clone()],
+            interest_id: primary.id.clone(),
+            interest_name: primary.name.clone(),
+            matched_interests: matched_interests
+                .iter()
+                .map(|i| MatchedInterest {
+                    id: i.id.clone(),
+                    name: i.name.clone(),
+                })
+                .collect(),
+            title: item.title.clone(),
+            magnet_uri: item.magnet_uri.clone(),
+            torrent_url: item.torrent_url.clone(),
+            size: item.size,
+            web_seeds: item.web_seeds.clone(),
+            last_error: None,
+            failure_count: 0,
+            rejected_reason: None,
+            seeders_rejected_reason: None,
+            health: None,
+            created_at: Utc::now().to_rfc3339(),
+            metadata: None,
+            is_upgrade: replaces.is_some(),
+            replaces_torrent_id: replaces.as_ref().and_then(|r| r.torrent_id),
+            replaces_title: replaces.map(|r| r.title),
+        };
 
-            break;
+        if !queue_pending(app_handle, rss_state, &pending).await {
+            continue;
         }
-    }
 
-    let count = rss_state.pending_matches.read().await.len();
-    let _ = app_handle.emit("rss:pending-count", count);
+        matched_count += 1;
 
-    Ok((matched_count, result.etag, result.last_modified))
+        let _ = app_handle.emit(
+            "rss:new-match",
+            serde_json::json!({
+                "id": pending.id,
+                "source_name": source.name,
+                "interest_name": primary.name,
+                "interest_names": matched_interests.iter().map(|i| i.name.as_str()).collect::<Vec<_>>(),
+                "title": item.title,
+            }),
+        );
+        crate::services::notifications::notify_rss_match(app_handle, &item.title, &primary.name).await;
+    }
+
+    let count = rss_state.pending_matches.read().await.len();
+    let _ = event_bus::emit(app_handle, "rss:pending-count", count);
+
+    Ok((matched_count, new_items, result.etag, result.last_modified))
 }
 
 /// Check a source against all interests and queue matches for screening.
+/// Returns (match_count, new_item_count).
 async fn check_source_for_matches(
     app_handle: &AppHandle,
     rss_state: &RssState,
     source: &Source,
     interests: &[&Interest],
-) -> Result<usize> {
+) -> Result<(usize, usize)> {
     let mut matched_count = 0;
+    let mut new_items = 0;
 
     if has_search_placeholder(&source.url) {
         // Placeholder mode: fetch per interest with substituted search term
@@ -764,9 +1957,9 @@ async fn check_source_for_matches(
             let url = build_search_url(&source.url, interest);
             info!("Fetching search URL for interest '{}': {}", interest.name, url);
 
-            match fetch_feed(&url).await {
+            match fetch_feed(&url, &FeedFetchOptions::from_source(source)).await {
                 Ok(items) => {
-                    let count = process_items_for_interest(
+                    let (count, new) = process_items_for_interest(
                         app_handle,
                         rss_state,
                         source,
@@ -776,6 +1969,7 @@ async fn check_source_for_matches(
                     )
                     .await;
                     matched_count += count;
+                    new_items += new;
                 }
                 Err(e) => {
                     warn!(
@@ -787,15 +1981,11 @@ async fn check_source_for_matches(
         }
     } else {
         // Standard mode: fetch once, match all interests
-        let items = fetch_feed(&source.url).await?;
+        let items = fetch_feed(&source.url, &FeedFetchOptions::from_source(source)).await?;
 
         for item in &items {
             // Build the dedup key based on source settings
-            let item_key = if source.use_guid_dedup {
-                format!("{}:{}", source.id, item.guid)
-            } else {
-                format!("{}:{}", source.id, item.id)
-            };
+            let item_key = seen_item_key(source, None, item);
 
             // RACE CONDITION FIX: Hold lock across check+insert
             let mut seen = rss_state.seen_items.lock().await;
@@ -803,106 +1993,114 @@ async fn check_source_for_matches(
                 continue;
             }
 
+            // Items count as new once they're marked seen; unmatched ones are
+            // looked at again on every poll
             let now = Utc::now().to_rfc3339();
             if item.magnet_uri.is_none() && item.torrent_url.is_none() {
+                new_items += 1;
                 seen.insert(item_key.clone(), now);
                 continue;
             }
 
-            // PROPER/REPACK bypasses dedup for quality upgrades
-            let is_upgrade = is_quality_upgrade(&item.title);
+            if is_bad_item(rss_state, &item.title, item.magnet_uri.as_deref()).await {
+                info!("Skipping bad item: {}", item.title);
+                new_items += 1;
+                seen.insert(item_key.clone(), now);
+                continue;
+            }
 
-            // Check against all interests (first match wins)
-            for interest in interests {
-                let matched =
-                    evaluate_filters_with_logic(item, &interest.filters, &interest.filter_logic);
-                if matched.is_none() {
-                    continue;
-                }
+            // Evaluate every interest; the most specific one becomes the primary
+            let (matched_interests, replaces) = find_matching_interests(rss_state, item, interests).await;
+            let Some(primary) = matched_interests.first().copied() else {
+                continue;
+            };
+            mark_episode_seen(rss_state, primary, &item.title).await;
 
-                // Smart episode filter: check if we've seen this episode for this interest
-                if interest.smart_episode_filter && !is_upgrade {
-                    if let Some(episode_id) = extract_episode_id(&item.title) {
-                        let mut seen_eps = rss_state.seen_episodes.lock().await;
-                        let interest_eps = seen_eps.entry(interest.id.clone()).or_default();
-                        if interest_eps.contains(&episode_id) {
-                            info!("Skipping duplicate episode {} for interest {}", episode_id, interest.name);
-                            continue;
-                        }
-                        interest_eps.insert(episode_id);
-                    }
-                }
+            // Insert to seen BEFORE dropping lock (race condition fix)
+            new_items += 1;
+            seen.insert(item_key.clone(), now.clone());
+            drop(seen);
 
-                // Insert to seen BEFORE dropping lock (race condition fix)
-                seen.insert(item_key.clone(), now.clone());
-                drop(seen);
-
-                let pending = PendingMatch {
-                    id: uuid::Uuid::new_v4().to_string(),
-                    source_id: source.id.clone(),
-                    source_name: source.name.clone(),
-                    interest_id: interest.id.clone(),
-                    interest_name: interest.name.clone(),
-                    title: item.title.clone(),
-                    magnet_uri: item.magnet_uri.clone(),
-                    torrent_url: item.torrent_url.clone(),
-                    created_at: Utc::now().to_rfc3339(),
-                    metadata: None,
-                };
+            let pending = PendingMatch {
+                id: uuid::Uuid::new_v4().to_string(),
+                source_id: source.id.clone(),
+                source_name: source.name.clone(),
+                sources: vec![source.name.clone()],
+                interest_id: primary.id.clone(),
+                interest_name: primary.name.clone(),
+                matched_interests: matched_interests
+                    .iter()
+                    .map(|i| MatchedInterest {
+                        id: i.id.clone(),
+                        name: i.name.clone(),
+                    })
+                    .collect(),
+                title: item.title.clone(),
+                magnet_uri: item.magnet_uri.clone(),
+                torrent_url: item.torrent_url.clone(),
+                size: item.size,
+                web_seeds: item.web_seeds.clone(),
+                last_error: None,
+                failure_count: 0,
+                rejected_reason: None,
+                seeders_rejected_reason: None,
+                health: None,
+                created_at: Utc::now().to_rfc3339(),
+                metadata: None,
+                is_upgrade: replaces.is_some(),
+                replaces_torrent_id: replaces.as_ref().and_then(|r| r.torrent_id),
+                replaces_title: replaces.map(|r| r.title),
+            };
 
-                rss_state
-                    .pending_matches
-                    .write()
-                    .await
-                    .push(pending.clone());
-                matched_count += 1;
+            if !queue_pending(app_handle, rss_state, &pending).await {
+                continue;
+            }
 
-                let _ = app_handle.emit(
-                    "rss:new-match",
-                    serde_json::json!({
-                        "id": pending.id,
-                        "source_name": source.name,
-                        "interest_name": interest.name,
-                        "title": item.title,
-                    }),
-                );
+            matched_count += 1;
 
-                break;
-            }
+            let _ = app_handle.emit(
+                "rss:new-match",
+                serde_json::json!({
+                    "id": pending.id,
+                    "source_name": source.name,
+                    "interest_name": primary.name,
+                    "interest_names": matched_interests.iter().map(|i| i.name.as_str()).collect::<Vec<_>>(),
+                    "title": item.title,
+                }),
+            );
+            crate::services::notifications::notify_rss_match(app_handle, &item.title, &primary.name).await;
         }
     }
 
     let count = rss_state.pending_matches.read().await.len();
-    let _ = app_handle.emit("rss:pending-count", count);
+    let _ = event_bus::emit(app_handle, "rss:pending-count", count);
 
-    Ok(matched_count)
+    Ok((matched_count, new_items))
 }
 
 /// Process feed items for a specific interest (used in placeholder mode).
-async fn process_items_for_interest(
+/// Returns (match_count, new_item_count).
+pub(crate) async fn process_items_for_interest(
     app_handle: &AppHandle,
     rss_state: &RssState,
     source: &Source,
     interest: &Interest,
     items: &[ParsedFeedItem],
     use_interest_key: bool,
-) -> usize {
+) -> (usize, usize) {
     let mut matched_count = 0;
+    let mut new_items = 0;
 
     for item in items {
         // Build the dedup key, optionally using GUID
-        let base_id = if source.use_guid_dedup { &item.guid } else { &item.id };
-        let item_key = if use_interest_key {
-            format!("{}:{}:{}", source.id, interest.id, base_id)
-        } else {
-            format!("{}:{}", source.id, base_id)
-        };
+        let item_key = seen_item_key(source, use_interest_key.then_some(interest), item);
 
         // RACE CONDITION FIX: Hold lock across check+insert
         let mut seen = rss_state.seen_items.lock().await;
         if seen.contains_key(&item_key) {
             continue;
         }
+        new_items += 1;
 
         let now = Utc::now().to_rfc3339();
         if item.magnet_uri.is_none() && item.torrent_url.is_none() {
@@ -910,6 +2108,12 @@ async fn process_items_for_interest(
             continue;
         }
 
+        if is_bad_item(rss_state, &item.title, item.magnet_uri.as_deref()).await {
+            info!("Skipping bad item: {}", item.title);
+            seen.insert(item_key, now);
+            continue;
+        }
+
         let matched =
             evaluate_filters_with_logic(item, &interest.filters, &interest.filter_logic);
         if matched.is_none() {
@@ -917,22 +2121,16 @@ async fn process_items_for_interest(
             continue;
         }
 
-        // PROPER/REPACK bypasses dedup for quality upgrades
-        let is_upgrade = is_quality_upgrade(&item.title);
-
         // Smart episode filter: check if we've seen this episode for this interest
-        if interest.smart_episode_filter && !is_upgrade {
-            if let Some(episode_id) = extract_episode_id(&item.title) {
-                let mut seen_eps = rss_state.seen_episodes.lock().await;
-                let interest_eps = seen_eps.entry(interest.id.clone()).or_default();
-                if interest_eps.contains(&episode_id) {
-                    info!("Skipping duplicate episode {} for interest {}", episode_id, interest.name);
-                    seen.insert(item_key, now);
-                    continue;
-                }
-                interest_eps.insert(episode_id);
+        let replaces = match check_episode(rss_state, interest, &item.title).await {
+            EpisodeCheck::New => None,
+            EpisodeCheck::Duplicate | EpisodeCheck::SeasonPack => {
+                seen.insert(item_key, now);
+                continue;
             }
-        }
+            EpisodeCheck::Upgrade(previous) => Some(previous),
+        };
+        mark_episode_seen(rss_state, interest, &item.title).await;
 
         // Insert to seen BEFORE dropping lock (race condition fix)
         seen.insert(item_key, now);
@@ -942,20 +2140,34 @@ async fn process_items_for_interest(
             id: uuid::Uuid::new_v4().to_string(),
             source_id: source.id.clone(),
             source_name: source.name.clone(),
+            sources: vec![source.name.clone()],
             interest_id: interest.id.clone(),
             interest_name: interest.name.clone(),
+            matched_interests: vec![MatchedInterest {
+                id: interest.id.clone(),
+                name: interest.name.clone(),
+            }],
             title: item.title.clone(),
             magnet_uri: item.magnet_uri.clone(),
             torrent_url: item.torrent_url.clone(),
+            size: item.size,
+            web_seeds: item.web_seeds.clone(),
+            last_error: None,
+            failure_count: 0,
+            rejected_reason: None,
+            seeders_rejected_reason: None,
+            health: None,
             created_at: Utc::now().to_rfc3339(),
             metadata: None,
+            is_upgrade: replaces.is_some(),
+            replaces_torrent_id: replaces.as_ref().and_then(|r| r.torrent_id),
+            replaces_title: replaces.map(|r| r.title),
         };
 
-        rss_state
-            .pending_matches
-            .write()
-            .await
-            .push(pending.clone());
+        if !queue_pending(app_handle, rss_state, &pending).await {
+            continue;
+        }
+
         matched_count += 1;
 
         let _ = app_handle.emit(
@@ -967,16 +2179,301 @@ async fn process_items_for_interest(
                 "title": item.title,
             }),
         );
+        crate::services::notifications::notify_rss_match(app_handle, &item.title, &interest.name).await;
     }
 
-    matched_count
+    (matched_count, new_items)
 }
 
-/// Fetch torrent metadata for screening preview.
+/// Fetch torrent metadata for screening preview. At most
+/// MAX_METADATA_FETCHES run at once; asking for a match that's already being
+/// fetched waits for that lookup instead of starting another.
 pub async fn fetch_metadata(app_handle: &AppHandle, match_id: &str) -> Result<TorrentMetadata> {
     let state = app_handle.state::<AppState>();
     let rss_state = &state.rss_state;
 
+    let joined = {
+        let mut fetches = rss_state.metadata_fetches.lock().unwrap_or_else(|e| e.into_inner());
+        match fetches.get(match_id) {
+            Some(running) => Err(running.result.clone()),
+            None => {
+                let (tx, rx) = tokio::sync::watch::channel(None);
+                let cancel = Arc::new(tokio::sync::Notify::new());
+                fetches.insert(match_id.to_string(), MetadataFetch { result: rx, cancel: cancel.clone() });
+                Ok((tx, cancel))
+            }
+        }
+    };
+    let (tx, cancel) = match joined {
+        Ok(started) => started,
+        Err(running) => return wait_for_metadata(running).await,
+    };
+    let _registration = FetchRegistration {
+        fetches: rss_state.metadata_fetches.clone(),
+        match_id: match_id.to_string(),
+    };
+
+    let result = fetch_metadata_queued(&state, match_id, &cancel).await;
+    if let Ok(metadata) = &result {
+        screen_metadata(app_handle, &state, match_id, metadata).await;
+    }
+    let _ = tx.send(Some(result.as_ref().cloned().map_err(|e| e.to_string())));
+    result
+}
+
+/// Run the match's interest's metadata filters on its fetched metadata. A
+/// match that fails is marked with the reason rather than dropped, and
+/// rss:auto-rejected goes out; one that passes loses any earlier reason.
+async fn screen_metadata(app_handle: &AppHandle, state: &AppState, match_id: &str, metadata: &TorrentMetadata) {
+    let rss_state = &state.rss_state;
+    let Some(interest_id) = rss_state
+        .pending_matches
+        .read()
+        .await
+        .iter()
+        .find(|m| m.id == match_id)
+        .map(|m| m.interest_id.clone())
+    else {
+        return;
+    };
+    let filters = rss_state
+        .interests
+        .read()
+        .await
+        .iter()
+        .find(|i| i.id == interest_id)
+        .map(|i| i.metadata_filters.clone())
+        .unwrap_or_default();
+    let reason = metadata_rejection(metadata, &filters);
+
+    let changed = {
+        let mut matches = rss_state.pending_matches.write().await;
+        match matches.iter_mut().find(|m| m.id == match_id) {
+            Some(m) if m.rejected_reason != reason => {
+                m.rejected_reason = reason.clone();
+                true
+            }
+            _ => false,
+        }
+    };
+    if !changed {
+        return;
+    }
+    crate::commands::rss::persist_pending_matches(state);
+    if let Some(reason) = reason {
+        info!(match_id, reason = %reason, "Metadata filters rejected match");
+        let _ = app_handle.emit(
+            "rss:auto-rejected",
+            serde_json::json!({ "match_id": match_id, "reason": reason, "stage": "metadata" }),
+        );
+    }
+}
+
+/// Fetch a new match's metadata in the background when its interest has
+/// metadata filters to run on it, and check its health when the interest
+/// wants a minimum of seeders. Metadata waits its turn in the metadata queue
+/// like any other lookup.
+async fn screen_new_match(app_handle: &AppHandle, rss_state: &RssState, pending: &PendingMatch) {
+    let (filtered, min_seeders) = rss_state
+        .interests
+        .read()
+        .await
+        .iter()
+        .find(|i| i.id == pending.interest_id)
+        .map(|i| (i.metadata_filters.iter().any(|f| f.enabled), i.min_seeders))
+        .unwrap_or_default();
+
+    if filtered && pending.metadata.is_none() {
+        let app_handle = app_handle.clone();
+        let match_id = pending.id.clone();
+        tokio::spawn(async move {
+            match fetch_metadata(&app_handle, &match_id).await {
+                Ok(metadata) => {
+                    let _ = app_handle.emit(
+                        "rss:metadata-fetched",
+                        serde_json::json!({ "match_id": match_id, "metadata": metadata }),
+                    );
+                }
+                Err(e) => warn!(match_id = %match_id, error = %e, "Metadata screening failed"),
+            }
+        });
+    }
+    if min_seeders.is_some() {
+        let app_handle = app_handle.clone();
+        let match_id = pending.id.clone();
+        tokio::spawn(async move {
+            if let Err(e) = check_health(&app_handle, &match_id).await {
+                warn!(match_id = %match_id, error = %e, "Health check failed");
+            }
+        });
+    }
+}
+
+/// Scrape a pending match's trackers and keep the best counts on the match,
+/// sending them out as rss:health-checked. A match with fewer seeders than
+/// its interest's min_seeders is marked rejected; one with enough loses any
+/// earlier seeder rejection. Metadata rejections are left alone.
+pub async fn check_health(app_handle: &AppHandle, match_id: &str) -> Result<HealthReport> {
+    let state = app_handle.state::<AppState>();
+    let rss_state = &state.rss_state;
+    let pending = rss_state
+        .pending_matches
+        .read()
+        .await
+        .iter()
+        .find(|m| m.id == match_id)
+        .cloned()
+        .ok_or_else(|| crate::errors::WhenThenError::NotFound("Match not found".into()))?;
+    let interest_id = pending.interest_id;
+
+    let uri = pending
+        .magnet_uri
+        .or(pending.torrent_url)
+        .ok_or_else(|| crate::errors::WhenThenError::InvalidInput("No torrent URI".into()))?;
+    let (info_hash, trackers) = if uri.starts_with("magnet:") {
+        let magnet = magnet::parse(&uri).map_err(crate::errors::WhenThenError::InvalidInput)?;
+        (magnet.info_hash, magnet.trackers)
+    } else {
+        let bytes = fetch_torrent_url(rss_state, &uri).await?;
+        tracker_scrape::torrent_trackers(&bytes)
+            .ok_or_else(|| crate::errors::WhenThenError::InvalidInput("Not a valid torrent file".into()))?
+    };
+    if trackers.is_empty() {
+        return Err(crate::errors::WhenThenError::InvalidInput("The torrent lists no trackers to ask".into()));
+    }
+
+    let results = tracker_scrape::scrape(&trackers, &info_hash)
+        .await
+        .map_err(crate::errors::WhenThenError::Internal)?;
+    let health = tracker_scrape::aggregate(&results, Utc::now().to_rfc3339());
+    let Some(found) = health.clone() else {
+        return Ok(HealthReport { trackers: results, health });
+    };
+
+    let min_seeders = rss_state
+        .interests
+        .read()
+        .await
+        .iter()
+        .find(|i| i.id == interest_id)
+        .and_then(|i| i.min_seeders);
+    let rejection = min_seeders
+        .filter(|&min| found.seeders < min)
+        .map(|min| format!("Only {} seeders, {} needed", found.seeders, min));
+    {
+        let mut matches = rss_state.pending_matches.write().await;
+        if let Some(m) = matches.iter_mut().find(|m| m.id == match_id) {
+            m.health = Some(found.clone());
+            m.seeders_rejected_reason = rejection.clone();
+        }
+    }
+    crate::commands::rss::persist_pending_matches(&state);
+    let _ = app_handle.emit("rss:health-checked", serde_json::json!({ "match_id": match_id, "health": found }));
+    if let Some(reason) = rejection {
+        info!(match_id, reason = %reason, "Too few seeders, rejected match");
+        let _ = app_handle.emit(
+            "rss:auto-rejected",
+            serde_json::json!({ "match_id": match_id, "reason": reason, "stage": "seeders" }),
+        );
+    }
+
+    Ok(HealthReport { trackers: results, health })
+}
+
+/// The result of a lookup another caller started.
+async fn wait_for_metadata(mut result: tokio::sync::watch::Receiver<MetadataOutcome>) -> Result<TorrentMetadata> {
+    let outcome = result.wait_for(Option::is_some).await.map(|outcome| outcome.clone());
+    match outcome {
+        Ok(Some(outcome)) => outcome.map_err(crate::errors::WhenThenError::Torrent),
+        _ => Err(crate::errors::WhenThenError::Internal("Metadata fetch was abandoned".into())),
+    }
+}
+
+fn metadata_canceled() -> crate::errors::WhenThenError {
+    crate::errors::WhenThenError::InvalidInput("The match left the inbox during the metadata fetch".into())
+}
+
+/// Stop the metadata lookup for a match that's leaving the inbox, and wait
+/// until its paused torrent is gone so approving can add the real one.
+async fn cancel_metadata_fetch(rss_state: &RssState, match_id: &str) {
+    let running = rss_state
+        .metadata_fetches
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(match_id)
+        .map(|fetch| (fetch.result.clone(), fetch.cancel.clone()));
+    if let Some((mut result, cancel)) = running {
+        cancel.notify_one();
+        let _ = result.wait_for(Option::is_some).await;
+        info!(match_id, "Canceled metadata fetch");
+    }
+}
+
+/// Fill in metadata for every pending match without it, in the background
+/// and one at a time so a slot stays free for matches the user opens. Each
+/// result goes out as rss:metadata-fetched. Returns how many matches were
+/// queued, or 0 if a prefetch is already running.
+pub async fn prefetch_metadata(app_handle: &AppHandle) -> usize {
+    use std::sync::atomic::Ordering;
+
+    let state = app_handle.state::<AppState>();
+    if state.rss_state.prefetching.swap(true, Ordering::SeqCst) {
+        return 0;
+    }
+    let match_ids: Vec<String> = state
+        .rss_state
+        .pending_matches
+        .read()
+        .await
+        .iter()
+        .filter(|m| m.metadata.is_none())
+        .map(|m| m.id.clone())
+        .collect();
+    let count = match_ids.len();
+
+    let app_handle = app_handle.clone();
+    tokio::spawn(async move {
+        let state = app_handle.state::<AppState>();
+        for match_id in match_ids {
+            // Approved, rejected or opened since the walk started
+            let wanted = state
+                .rss_state
+                .pending_matches
+                .read()
+                .await
+                .iter()
+                .any(|m| m.id == match_id && m.metadata.is_none());
+            if !wanted {
+                continue;
+            }
+            match fetch_metadata(&app_handle, &match_id).await {
+                Ok(metadata) => {
+                    let _ = app_handle.emit(
+                        "rss:metadata-fetched",
+                        serde_json::json!({ "match_id": match_id, "metadata": metadata }),
+                    );
+                }
+                Err(e) => warn!(match_id = %match_id, error = %e, "Metadata prefetch failed"),
+            }
+        }
+        state.rss_state.prefetching.store(false, Ordering::SeqCst);
+    });
+    count
+}
+
+/// Wait for a free slot, then look the metadata up.
+async fn fetch_metadata_queued(
+    state: &AppState,
+    match_id: &str,
+    cancel: &tokio::sync::Notify,
+) -> Result<TorrentMetadata> {
+    let rss_state = &state.rss_state;
+    let _slot = tokio::select! {
+        slot = rss_state.metadata_slots.acquire() => slot
+            .map_err(|_| crate::errors::WhenThenError::Internal("Metadata queue closed".into()))?,
+        _ = cancel.notified() => return Err(metadata_canceled()),
+    };
+
     // Find the pending match
     let pending = {
         let matches = rss_state.pending_matches.read().await;
@@ -996,11 +2493,11 @@ pub async fn fetch_metadata(app_handle: &AppHandle, match_id: &str) -> Result<To
     let add_torrent = if uri.starts_with("magnet:") {
         librqbit::AddTorrent::from_url(&uri)
     } else {
-        let bytes = download_torrent_file(&uri).await?;
+        let bytes = fetch_torrent_url(rss_state, &uri).await?;
         librqbit::AddTorrent::TorrentFileBytes(bytes.into())
     };
 
-    let metadata = fetch_torrent_metadata_via_session(&state, add_torrent).await?;
+    let metadata = fetch_torrent_metadata_via_session(state, add_torrent, cancel).await?;
 
     // Update the pending match with metadata
     {
@@ -1009,14 +2506,17 @@ pub async fn fetch_metadata(app_handle: &AppHandle, match_id: &str) -> Result<To
             m.metadata = Some(metadata.clone());
         }
     }
+    crate::commands::rss::persist_pending_matches(state);
 
     Ok(metadata)
 }
 
 /// Fetch metadata by adding torrent paused, reading info, then deleting.
+/// Stops early, still deleting the torrent, when `cancel` is notified.
 async fn fetch_torrent_metadata_via_session(
     state: &AppState,
     add_torrent: librqbit::AddTorrent<'_>,
+    cancel: &tokio::sync::Notify,
 ) -> Result<TorrentMetadata> {
     // Get configurable timeout from settings
     let timeout_secs = state.config.read().await.metadata_timeout_secs;
@@ -1034,28 +2534,31 @@ async fn fetch_torrent_metadata_via_session(
     let response = session
         .add_torrent(add_torrent, Some(add_opts))
         .await
-        .map_err(|e| crate::errors::WhenThenError::Torrent(e.to_string()))?;
+        .map_err(|e| crate::errors::WhenThenError::torrent("Failed to add torrent", e))?;
 
-    let handle = match response {
-        librqbit::AddTorrentResponse::Added(_, h) => h,
-        librqbit::AddTorrentResponse::AlreadyManaged(_, h) => h,
+    // A torrent that was already in the session isn't ours to delete
+    let (added, handle) = match response {
+        librqbit::AddTorrentResponse::Added(_, h) => (true, h),
+        librqbit::AddTorrentResponse::AlreadyManaged(_, h) => (false, h),
         librqbit::AddTorrentResponse::ListOnly(_) => {
             return Err(crate::errors::WhenThenError::Torrent("List-only mode".into()));
         }
     };
 
     // Wait for metadata (with configurable timeout)
-    let metadata_result = tokio::time::timeout(Duration::from_secs(timeout_secs as u64), async {
-        loop {
-            // Check if we have metadata
-            let has_meta = handle.with_metadata(|_| ()).is_ok();
-            if has_meta {
-                break;
+    let waited = tokio::select! {
+        waited = tokio::time::timeout(Duration::from_secs(timeout_secs as u64), async {
+            loop {
+                // Check if we have metadata
+                let has_meta = handle.with_metadata(|_| ()).is_ok();
+                if has_meta {
+                    break;
+                }
+                tokio::time::sleep(Duration::from_millis(500)).await;
             }
-            tokio::time::sleep(Duration::from_millis(500)).await;
-        }
-    })
-    .await;
+        }) => Some(waited),
+        _ = cancel.notified() => None,
+    };
 
     // Get file info from handle
     let file_infos: Vec<(String, u64)> = handle
@@ -1076,10 +2579,16 @@ async fn fetch_torrent_metadata_via_session(
     let torrent_name = handle.name().unwrap_or_else(|| "Unknown".to_string());
 
     // Delete the paused torrent
-    let torrent_id = handle.id();
-    let _ = session
-        .delete(librqbit::api::TorrentIdOrHash::Id(torrent_id), false)
-        .await;
+    if added {
+        let torrent_id = handle.id();
+        let _ = session
+            .delete(librqbit::api::TorrentIdOrHash::Id(torrent_id), false)
+            .await;
+    }
+
+    let Some(metadata_result) = waited else {
+        return Err(metadata_canceled());
+    };
 
     // Check if metadata fetch timed out
     if metadata_result.is_err() && file_infos.is_empty() {
@@ -1142,31 +2651,269 @@ fn is_suspicious_file(name: &str) -> bool {
         || lower.ends_with(".dll")
 }
 
-/// Download a .torrent file from URL.
-async fn download_torrent_file(url: &str) -> Result<Vec<u8>> {
-    let response = reqwest::get(url).await?;
-    let bytes = response.bytes().await?;
-    Ok(bytes.to_vec())
+/// Timeout for fetching a .torrent URL added by the user.
+const TORRENT_URL_TIMEOUT_SECS: u64 = 15;
+/// Largest .torrent file we'll download; real ones are well under this.
+const MAX_TORRENT_FILE_BYTES: usize = 10 * 1024 * 1024;
+/// Nesting limit for bencode validation so hostile input can't blow the stack.
+const MAX_BENCODE_DEPTH: usize = 64;
+
+/// End offset of the bencoded value starting at `pos`, or None if malformed.
+pub(crate) fn bencode_value_end(data: &[u8], pos: usize, depth: usize) -> Option<usize> {
+    if depth > MAX_BENCODE_DEPTH {
+        return None;
+    }
+    match *data.get(pos)? {
+        b'i' => {
+            let end = pos + data[pos..].iter().position(|&b| b == b'e')?;
+            let digits = data.get(pos + 1..end)?;
+            let digits = digits.strip_prefix(b"-").unwrap_or(digits);
+            (!digits.is_empty() && digits.iter().all(u8::is_ascii_digit)).then_some(end + 1)
+        }
+        b'l' | b'd' => {
+            let is_dict = data[pos] == b'd';
+            let mut cur = pos + 1;
+            while *data.get(cur)? != b'e' {
+                if is_dict && !data[cur].is_ascii_digit() {
+                    return None; // dict keys must be strings
+                }
+                cur = bencode_value_end(data, cur, depth + 1)?;
+                if is_dict {
+                    cur = bencode_value_end(data, cur, depth + 1)?;
+                }
+            }
+            Some(cur + 1)
+        }
+        b'0'..=b'9' => {
+            let colon = pos + data[pos..].iter().position(|&b| b == b':')?;
+            let len: usize = std::str::from_utf8(&data[pos..colon]).ok()?.parse().ok()?;
+            let end = colon.checked_add(1)?.checked_add(len)?;
+            (end <= data.len()).then_some(end)
+        }
+        _ => None,
+    }
+}
+
+/// Whether `data` is a complete bencoded dictionary with an `info` dictionary.
+fn is_torrent_file(data: &[u8]) -> bool {
+    if data.first() != Some(&b'd') || bencode_value_end(data, 0, 0) != Some(data.len()) {
+        return false;
+    }
+    // Walk the top-level keys looking for info
+    let mut cur = 1;
+    while cur < data.len() - 1 {
+        let Some(key_end) = bencode_value_end(data, cur, 1) else {
+            return false;
+        };
+        let key = &data[cur..key_end];
+        if key == b"4:info" {
+            return data.get(key_end) == Some(&b'd');
+        }
+        let Some(value_end) = bencode_value_end(data, key_end, 1) else {
+            return false;
+        };
+        cur = value_end;
+    }
+    false
+}
+
+/// Download a .torrent from a URL, using a configured source's auth and
+/// User-Agent when the host matches. Rejects oversized or non-torrent bodies.
+pub async fn fetch_torrent_url(rss_state: &RssState, url: &str) -> Result<Vec<u8>> {
+    fetch_torrent(rss_state, url).await.map_err(|failure| failure.error)
+}
+
+/// fetch_torrent_url, telling apart download errors worth retrying.
+async fn fetch_torrent(rss_state: &RssState, url: &str) -> std::result::Result<Vec<u8>, ApproveFailure> {
+    let parsed = reqwest::Url::parse(url)
+        .map_err(|e| crate::errors::WhenThenError::InvalidInput(format!("Invalid URL: {e}")))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(crate::errors::WhenThenError::InvalidInput("Only http and https URLs are supported".into()).into());
+    }
+
+    let source = match parsed.host_str() {
+        Some(host) => rss_state
+            .sources
+            .read()
+            .await
+            .iter()
+            .find(|s| {
+                reqwest::Url::parse(&s.url)
+                    .ok()
+                    .and_then(|u| u.host_str().map(|h| h.eq_ignore_ascii_case(host)))
+                    .unwrap_or(false)
+            })
+            .cloned(),
+        None => None,
+    };
+
+    let mut opts = source.as_ref().map(FeedFetchOptions::from_source).unwrap_or_default();
+    opts.timeout_secs = Some(TORRENT_URL_TIMEOUT_SECS);
+    let client = build_feed_client(&opts)?;
+
+    let mut response = apply_auth(client.get(parsed), opts.auth)
+        .send()
+        .await
+        .and_then(reqwest::Response::error_for_status)
+        .map_err(ApproveFailure::from_download)?;
+
+    if response.content_length().is_some_and(|len| len as usize > MAX_TORRENT_FILE_BYTES) {
+        return Err(crate::errors::WhenThenError::InvalidInput("Torrent file is too large".into()).into());
+    }
+
+    let mut bytes = Vec::new();
+    while let Some(chunk) = response.chunk().await.map_err(ApproveFailure::from_download)? {
+        if bytes.len() + chunk.len() > MAX_TORRENT_FILE_BYTES {
+            return Err(crate::errors::WhenThenError::InvalidInput("Torrent file is too large".into()).into());
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+
+    if !is_torrent_file(&bytes) {
+        return Err(crate::errors::WhenThenError::InvalidInput("URL did not return a valid .torrent file".into()).into());
+    }
+    Ok(bytes)
 }
 
-/// Approve a pending match and start the download.
-pub async fn approve_match(app_handle: &AppHandle, match_id: &str) -> Result<i64> {
+/// Waits before each automatic retry of an approval that failed on a
+/// transient download error, a few minutes in all.
+const APPROVE_RETRY_DELAYS: [Duration; 3] = [
+    Duration::from_secs(30),
+    Duration::from_secs(60),
+    Duration::from_secs(120),
+];
+
+/// Why an approval didn't go through, and whether trying again may help.
+struct ApproveFailure {
+    error: crate::errors::WhenThenError,
+    transient: bool,
+}
+
+impl From<crate::errors::WhenThenError> for ApproveFailure {
+    fn from(error: crate::errors::WhenThenError) -> Self {
+        Self { error, transient: false }
+    }
+}
+
+impl ApproveFailure {
+    fn from_download(e: reqwest::Error) -> Self {
+        Self { transient: is_transient(&e), error: e.into() }
+    }
+}
+
+/// Whether a failed .torrent download is worth trying again: timeouts,
+/// connection failures, rate limiting and server errors.
+fn is_transient(e: &reqwest::Error) -> bool {
+    e.is_timeout()
+        || e.is_connect()
+        || e.status().is_some_and(|s| s.is_server_error() || s == reqwest::StatusCode::TOO_MANY_REQUESTS)
+}
+
+/// Approve a pending match and start the download. The match stays in the
+/// inbox until the torrent is added; if that fails, it's marked with the
+/// error, rss:approve-failed goes out, and transient download errors are
+/// retried in the background. Approving again while a retry is pending
+/// returns None and changes nothing.
+pub async fn approve_match(app_handle: &AppHandle, match_id: &str) -> Result<Option<i64>> {
     info!("Approving match: {}", match_id);
     let state = app_handle.state::<AppState>();
     let rss_state = &state.rss_state;
+    cancel_metadata_fetch(rss_state, match_id).await;
+
+    if !rss_state.approving.lock().await.insert(match_id.to_string()) {
+        info!("Approval of {} is already in progress", match_id);
+        return Ok(None);
+    }
+    match try_approve(app_handle, &state, match_id).await {
+        Ok(id) => {
+            rss_state.approving.lock().await.remove(match_id);
+            Ok(Some(id))
+        }
+        Err(failure) => {
+            record_approve_failure(app_handle, &state, match_id, &failure.error, failure.transient).await;
+            if failure.transient {
+                spawn_approve_retries(app_handle.clone(), match_id.to_string());
+            } else {
+                rss_state.approving.lock().await.remove(match_id);
+            }
+            Err(failure.error)
+        }
+    }
+}
+
+/// Retry a failed approval with backoff, stopping at the first success, a
+/// non-transient error or the match leaving the inbox. Emits rss:approved
+/// when a retry gets the torrent added.
+fn spawn_approve_retries(app_handle: AppHandle, match_id: String) {
+    tokio::spawn(async move {
+        let state = app_handle.state::<AppState>();
+        for (attempt, delay) in APPROVE_RETRY_DELAYS.iter().enumerate() {
+            tokio::time::sleep(*delay).await;
+            info!(match_id = %match_id, attempt = attempt + 1, "Retrying approval");
+            match try_approve(&app_handle, &state, &match_id).await {
+                Ok(torrent_id) => {
+                    let _ = app_handle.emit(
+                        "rss:approved",
+                        serde_json::json!({ "match_id": match_id, "torrent_id": torrent_id }),
+                    );
+                    break;
+                }
+                Err(failure) => {
+                    let retrying = failure.transient && attempt + 1 < APPROVE_RETRY_DELAYS.len();
+                    record_approve_failure(&app_handle, &state, &match_id, &failure.error, retrying).await;
+                    if !retrying {
+                        break;
+                    }
+                }
+            }
+        }
+        state.rss_state.approving.lock().await.remove(&match_id);
+    });
+}
 
-    // Find and remove the pending match
+/// Note a failed approval on the match and tell the frontend.
+async fn record_approve_failure(
+    app_handle: &AppHandle,
+    state: &AppState,
+    match_id: &str,
+    error: &crate::errors::WhenThenError,
+    retrying: bool,
+) {
+    let failure_count = {
+        let mut matches = state.rss_state.pending_matches.write().await;
+        let Some(pending) = matches.iter_mut().find(|m| m.id == match_id) else {
+            return;
+        };
+        pending.last_error = Some(error.to_string());
+        pending.failure_count += 1;
+        pending.failure_count
+    };
+    crate::commands::rss::persist_pending_matches(state);
+
+    warn!(match_id, failure_count, retrying, error = %error, "Approval failed");
+    let _ = app_handle.emit(
+        "rss:approve-failed",
+        serde_json::json!({
+            "match_id": match_id,
+            "error": error.to_string(),
+            "failure_count": failure_count,
+            "retrying": retrying,
+        }),
+    );
+}
+
+/// Add the match's torrent and, once it's in, take the match out of the inbox.
+async fn try_approve(app_handle: &AppHandle, state: &AppState, match_id: &str) -> std::result::Result<i64, ApproveFailure> {
+    let rss_state = &state.rss_state;
+
+    // Find the pending match; it's removed only once the add succeeds
     let pending = {
-        let mut matches = rss_state.pending_matches.write().await;
+        let matches = rss_state.pending_matches.read().await;
         info!("Current pending matches: {}", matches.len());
-        let idx = matches
-            .iter()
-            .position(|m| m.id == match_id)
-            .ok_or_else(|| {
-                warn!("Match not found: {}", match_id);
-                crate::errors::WhenThenError::NotFound("Match not found".into())
-            })?;
-        matches.remove(idx)
+        matches.iter().find(|m| m.id == match_id).cloned().ok_or_else(|| {
+            warn!("Match not found: {}", match_id);
+            crate::errors::WhenThenError::NotFound("Match not found".into())
+        })?
     };
 
     info!(
@@ -1176,15 +2923,25 @@ pub async fn approve_match(app_handle: &AppHandle, match_id: &str) -> Result<i64
         pending.torrent_url.as_ref().map(|s| &s[..50.min(s.len())])
     );
 
-    // Get custom download path from interest if set
-    let download_path = {
+    // Get custom download path, label, file filters, paused override and upgrade handling from the primary interest
+    let (download_path, label, file_filters, add_paused, auto_replace) = {
         let interests = rss_state.interests.read().await;
-        interests
-            .iter()
-            .find(|i| i.id == pending.interest_id)
-            .and_then(|i| i.download_path.clone())
+        let interest = interests.iter().find(|i| i.id == pending.interest_id);
+        (
+            interest.and_then(|i| i.download_path.clone()),
+            interest.and_then(|i| i.default_label.clone()),
+            interest.map(|i| i.file_filters.clone()).unwrap_or_default(),
+            interest.and_then(|i| i.add_paused),
+            interest.is_some_and(|i| i.upgrade_policy.auto_replace),
+        )
     };
 
+    // With metadata from screening, unwanted files are left out from the start
+    let selection = pending.metadata.as_ref().and_then(|metadata| {
+        let files: Vec<(String, u64)> = metadata.files.iter().map(|f| (f.name.clone(), f.size)).collect();
+        select_files(&files, &file_filters)
+    });
+
     // Get URI
     let uri = pending
         .magnet_uri
@@ -1200,39 +2957,350 @@ pub async fn approve_match(app_handle: &AppHandle, match_id: &str) -> Result<i64
         info!("Using custom download path: {}", path);
     }
 
-    // Add torrent with optional custom download path
-    let options = download_path.map(|path| crate::models::TorrentAddOptions {
-        output_folder: Some(path),
-        only_files: None,
+    // Add torrent with optional custom download path, label, file selection and paused override
+    let customized = download_path.is_some() || label.is_some() || selection.is_some() || add_paused.is_some();
+    let options = customized.then(|| crate::models::TorrentAddOptions {
+        output_folder: download_path,
+        only_files: selection.as_ref().map(|s| s.only_files.clone()),
+        add_paused,
+        label,
+        allow_bad: false,
     });
     let result = if uri.starts_with("magnet:") {
-        torrent_engine::add_magnet(&state, app_handle, uri, options).await
+        torrent_engine::add_magnet(state, app_handle, uri, options).await
     } else {
-        let bytes = download_torrent_file(&uri).await?;
-        torrent_engine::add_torrent_bytes(&state, app_handle, bytes, options).await
+        let bytes = fetch_torrent(rss_state, &uri).await?;
+        torrent_engine::add_torrent_bytes(state, app_handle, bytes, options).await
     };
 
     let response = result?;
-    info!("Torrent added successfully: id={}", response.id);
+    rss_state.pending_matches.write().await.retain(|m| m.id != match_id);
+    crate::commands::rss::persist_pending_matches(state);
+    if response.already_existed {
+        info!("Torrent for {} is already in the session: id={}", pending.title, response.id);
+    } else {
+        info!("Torrent added successfully: id={}", response.id);
+    }
+
+    // Remember the origin so completion can organize files per the interest's template.
+    // A torrent that was already there keeps the origin it was added with.
+    state
+        .rss_state
+        .approved_torrents
+        .write()
+        .await
+        .entry(response.id)
+        .or_insert_with(|| ApprovedTorrent {
+            interest_id: pending.interest_id.clone(),
+            interest_name: pending.interest_name.clone(),
+        });
+
+    // Remember which torrent holds these episodes so a later upgrade can replace it
+    if let Some(episode) = episode::parse(&pending.title) {
+        let mut seen_eps = rss_state.seen_episodes.lock().await;
+        let interest_eps = seen_eps.entry(pending.interest_id.clone()).or_default();
+        for episode_id in episode.keys() {
+            if response.already_existed {
+                // Same data as the copy from the other feed, whichever title it was recorded under
+                interest_eps
+                    .entry(episode_id)
+                    .or_insert_with(|| SeenEpisode {
+                        media: media_info::parse(&pending.title),
+                        title: pending.title.clone(),
+                        torrent_id: None,
+                    })
+                    .torrent_id = Some(response.id);
+            } else if let Some(record) = interest_eps
+                .get_mut(&episode_id)
+                .filter(|record| record.title == pending.title)
+            {
+                record.torrent_id = Some(response.id);
+            }
+        }
+    }
+
+    if response.already_existed {
+        clear_duplicate_matches(app_handle, state, &pending, &response.info_hash).await;
+    } else if let Some(selection) = selection {
+        report_skipped_files(app_handle, match_id, response.id, &selection.skipped);
+    } else if !file_filters.is_empty() {
+        filter_files_when_known(app_handle, state, match_id, &response, file_filters).await;
+    }
+
+    let replaced = pending.replaces_torrent_id.filter(|_| pending.is_upgrade && auto_replace);
+    if let Some(old_id) = replaced {
+        info!(
+            "Replacing {} with upgrade {}",
+            pending.replaces_title.as_deref().unwrap_or("previous release"),
+            pending.title
+        );
+        if let Err(e) = torrent_engine::delete_torrent(state, app_handle, old_id, true).await {
+            warn!("Failed to remove replaced torrent {}: {}", old_id, e);
+        }
+    }
+
+    // A copy of something already downloading isn't a new approval
+    if !response.already_existed {
+        record_approval(app_handle, state, &pending).await;
+    }
 
     // Emit pending count update
     let count = rss_state.pending_matches.read().await.len();
-    let _ = app_handle.emit("rss:pending-count", count);
+    let _ = event_bus::emit(app_handle, "rss:pending-count", count);
 
     Ok(response.id as i64)
 }
 
+/// Apply an interest's file filters to a torrent added without screening
+/// metadata. A .torrent arrives with its file list, so the selection happens
+/// now; a magnet's waits until the session monitor sees its metadata.
+async fn filter_files_when_known(
+    app_handle: &AppHandle,
+    state: &AppState,
+    match_id: &str,
+    added: &crate::models::TorrentAddedResponse,
+    filters: Vec<FeedFilter>,
+) {
+    if added.files.is_empty() {
+        debug!(info_hash = %added.info_hash, "Waiting on metadata to filter files");
+        state.rss_state.pending_file_filters.write().await.insert(
+            added.info_hash.clone(),
+            PendingFileFilters { match_id: match_id.to_string(), filters },
+        );
+        return;
+    }
+    let files: Vec<(String, u64)> = added.files.iter().map(|f| (f.path.clone(), f.length)).collect();
+    if let Some(selection) = select_files(&files, &filters) {
+        apply_file_selection(app_handle, state, match_id, added.id, selection).await;
+    }
+}
+
+/// Called by the session monitor once an approved magnet's metadata is in:
+/// narrow its files to what the interest's file filters keep.
+pub async fn apply_pending_file_filters(app_handle: AppHandle, torrent_id: usize, info_hash: String) {
+    let state = app_handle.state::<AppState>();
+    let Some(pending) = state.rss_state.pending_file_filters.write().await.remove(&info_hash) else {
+        return;
+    };
+    let files = match torrent_engine::get_torrent_files(&state, torrent_id).await {
+        Ok(files) => files,
+        Err(e) => {
+            warn!(torrent_id, error = %e, "Can't list files to filter");
+            return;
+        }
+    };
+    let files: Vec<(String, u64)> = files.into_iter().map(|f| (f.path, f.length)).collect();
+    if let Some(selection) = select_files(&files, &pending.filters) {
+        apply_file_selection(&app_handle, &state, &pending.match_id, torrent_id, selection).await;
+    }
+}
+
+async fn apply_file_selection(
+    app_handle: &AppHandle,
+    state: &AppState,
+    match_id: &str,
+    torrent_id: usize,
+    selection: FileSelection,
+) {
+    match torrent_engine::update_torrent_files(state, app_handle, torrent_id, selection.only_files).await {
+        Ok(_) => report_skipped_files(app_handle, match_id, torrent_id, &selection.skipped),
+        Err(e) => warn!(torrent_id, error = %e, "Failed to apply interest file filters"),
+    }
+}
+
+/// Emit rss:files-skipped with the paths an interest's file filters left out.
+fn report_skipped_files(app_handle: &AppHandle, match_id: &str, torrent_id: usize, skipped: &[String]) {
+    info!(match_id, torrent_id, skipped = skipped.len(), "File filters left out files");
+    let _ = app_handle.emit(
+        "rss:files-skipped",
+        serde_json::json!({
+            "match_id": match_id,
+            "torrent_id": torrent_id,
+            "skipped": skipped,
+        }),
+    );
+}
+
+/// Drop pending matches for the same torrent as `approved`, by info hash or,
+/// for items without a magnet, by normalized title. Emits
+/// rss:duplicates-cleared with the removed match ids.
+async fn clear_duplicate_matches(app_handle: &AppHandle, state: &AppState, approved: &PendingMatch, info_hash: &str) {
+    let title = normalize_title(&approved.title);
+    let removed: Vec<String> = {
+        let mut matches = state.rss_state.pending_matches.write().await;
+        let mut removed = Vec::new();
+        matches.retain(|m| {
+            let duplicate = match m.magnet_uri.as_deref().and_then(extract_info_hash) {
+                Some(hash) => hash.eq_ignore_ascii_case(info_hash),
+                None => normalize_title(&m.title) == title,
+            };
+            if duplicate {
+                removed.push(m.id.clone());
+            }
+            !duplicate
+        });
+        removed
+    };
+    if removed.is_empty() {
+        return;
+    }
+
+    info!("Cleared {} duplicate matches of {}", removed.len(), approved.title);
+    crate::commands::rss::persist_pending_matches(state);
+    let _ = app_handle.emit("rss:duplicates-cleared", &removed);
+}
+
+/// Count an approved match toward its interest's cap, disabling the interest
+/// once the cap is reached or, if asked, once a full season has been grabbed.
+async fn record_approval(app_handle: &AppHandle, state: &AppState, pending: &PendingMatch) {
+    let rss_state = &state.rss_state;
+    let count = {
+        let mut counts = rss_state.match_counts.write().await;
+        let count = counts.entry(pending.interest_id.clone()).or_default();
+        *count += 1;
+        *count
+    };
+    crate::commands::rss::persist_match_counts(state);
+
+    let completed = {
+        let mut interests = rss_state.interests.write().await;
+        let Some(interest) = interests.iter_mut().find(|i| i.id == pending.interest_id) else {
+            return;
+        };
+        let cap_reached = interest.max_matches.is_some_and(|max| count >= max);
+        let season_done = interest.auto_disable_when_complete && is_season_pack(&pending.title);
+        if !interest.enabled || !(cap_reached || season_done) {
+            return;
+        }
+        interest.enabled = false;
+        interest.name.clone()
+    };
+    crate::commands::rss::persist_interests(state);
+
+    info!("Interest {} completed after {} approved matches", completed, count);
+    let _ = app_handle.emit(
+        "rss:interest-completed",
+        serde_json::json!({
+            "interest_id": pending.interest_id,
+            "interest_name": completed,
+            "match_count": count,
+        }),
+    );
+}
+
+/// A release covering a whole season rather than a single episode.
+fn is_season_pack(title: &str) -> bool {
+    matches!(episode::parse(title), Some(Episode::SeasonPack(_)))
+}
+
+/// Fill an interest's organize template from the parsed torrent name.
+/// Fails on unknown placeholders or ones the name has no value for.
+pub fn render_organize_template(
+    template: &str,
+    interest_name: &str,
+    torrent_name: &str,
+) -> std::result::Result<String, String> {
+    let info = crate::services::media_info::parse(torrent_name);
+    let mut out = String::new();
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let end = rest[start..]
+            .find('}')
+            .ok_or_else(|| format!("Unclosed placeholder in \"{template}\""))?;
+        let key = &rest[start + 1..start + end];
+        let value = match key {
+            "interest" => Some(interest_name.to_string()),
+            "name" => Some(torrent_name.to_string()),
+            "title" => Some(info.title.clone()).filter(|t| !t.is_empty()),
+            "season" => info.season.map(|n| format!("{n:02}")),
+            "episode" => info.episode.map(|n| format!("{n:02}")),
+            "year" => info.year.map(|y| y.to_string()),
+            "quality" => info.quality.map(|q| q.as_str().to_string()),
+            _ => return Err(format!("Unknown placeholder {{{key}}}")),
+        }
+        .ok_or_else(|| format!("No {{{key}}} found in \"{torrent_name}\""))?;
+        // Values must not introduce extra path levels
+        out.push_str(value.replace(['/', '\\', ':'], "-").trim());
+        rest = &rest[start + end + 1..];
+    }
+    out.push_str(rest);
+
+    if out.trim().is_empty() {
+        return Err("Template renders to an empty path".into());
+    }
+    if std::path::Path::new(&out)
+        .components()
+        .any(|c| matches!(c, std::path::Component::ParentDir))
+    {
+        return Err(format!("Path \"{out}\" must not contain \"..\""));
+    }
+    Ok(out)
+}
+
+/// Move a finished torrent that came from an approved match into its interest's
+/// organize folder. Returns the new folder; failures are reported to the frontend.
+pub async fn organize_completed(
+    app_handle: &AppHandle,
+    torrent_id: usize,
+    torrent_name: &str,
+) -> Option<std::path::PathBuf> {
+    let state = app_handle.state::<AppState>();
+    let origin = state.rss_state.approved_torrents.write().await.remove(&torrent_id)?;
+    let template = {
+        let interests = state.rss_state.interests.read().await;
+        interests
+            .iter()
+            .find(|i| i.id == origin.interest_id)
+            .and_then(|i| i.organize_template.clone())
+            .filter(|t| !t.trim().is_empty())?
+    };
+
+    let result = match render_organize_template(&template, &origin.interest_name, torrent_name) {
+        Ok(destination) => torrent_engine::move_torrent_files(&state, app_handle, torrent_id, destination.clone())
+            .await
+            .map(|_| torrent_engine::expand_path(&destination))
+            .map_err(|e| e.to_string()),
+        Err(e) => Err(e),
+    };
+
+    match result {
+        Ok(dest) => {
+            info!("Organized '{}' into {}", torrent_name, dest.display());
+            Some(dest)
+        }
+        Err(message) => {
+            warn!("Failed to organize '{}': {}", torrent_name, message);
+            let _ = app_handle.emit(
+                "rss:organize-failed",
+                serde_json::json!({
+                    "torrent_id": torrent_id,
+                    "interest": origin.interest_name,
+                    "template": template,
+                    "message": message,
+                }),
+            );
+            None
+        }
+    }
+}
+
 /// Reject a pending match (discard it).
 pub async fn reject_match(app_handle: &AppHandle, match_id: &str) -> Result<()> {
     let state = app_handle.state::<AppState>();
     let rss_state = &state.rss_state;
+    cancel_metadata_fetch(rss_state, match_id).await;
 
-    let mut matches = rss_state.pending_matches.write().await;
-    matches.retain(|m| m.id != match_id);
+    let count = {
+        let mut matches = rss_state.pending_matches.write().await;
+        matches.retain(|m| m.id != match_id);
+        matches.len()
+    };
+    crate::commands::rss::persist_pending_matches(&state);
 
     // Emit pending count update
-    let count = matches.len();
-    let _ = app_handle.emit("rss:pending-count", count);
+    let _ = event_bus::emit(app_handle, "rss:pending-count", count);
 
     Ok(())
 }
@@ -1252,25 +3320,65 @@ pub async fn check_feeds_now(app_handle: &AppHandle) -> Result<usize> {
     }
 
     let mut total_matched = 0;
+    let mut sources_to_update: Vec<Source> = Vec::new();
 
-    for source in sources {
+    for mut source in sources {
         if !source.enabled {
             continue;
         }
 
         match check_source_for_matches(app_handle, rss_state, &source, &enabled_interests).await {
-            Ok(count) => {
+            Ok((count, new_items)) => {
                 total_matched += count;
                 if count > 0 {
                     info!("Source {} matched {} new items", source.name, count);
                 }
+                record_check_success(&mut source, new_items);
             }
             Err(e) => {
                 warn!("Failed to check source {}: {}", source.name, e);
+                record_check_failure(app_handle, &mut source, &e);
             }
         }
+        source.last_checked = Some(Utc::now().to_rfc3339());
+        sources_to_update.push(source);
     }
 
+    {
+        let mut sources_lock = rss_state.sources.write().await;
+        for updated in sources_to_update {
+            if let Some(src) = sources_lock.iter_mut().find(|s| s.id == updated.id) {
+                src.failure_count = updated.failure_count;
+                src.retry_after = updated.retry_after;
+                src.last_error = updated.last_error;
+                src.last_success_at = updated.last_success_at;
+                src.items_seen_total = updated.items_seen_total;
+                src.last_checked = updated.last_checked;
+            }
+        }
+    }
+    crate::commands::rss::persist_sources_internal(&state);
+
+    let global_interval_mins = state.config.read().await.rss_check_interval_minutes;
+    total_matched += crate::services::scraper::check_scrapers(
+        app_handle,
+        &state,
+        &enabled_interests,
+        global_interval_mins,
+        true,
+        true,
+    )
+    .await;
+    total_matched += crate::services::torznab::check_indexers(
+        app_handle,
+        &state,
+        &enabled_interests,
+        global_interval_mins,
+        true,
+        true,
+    )
+    .await;
+
     Ok(total_matched)
 }
 
@@ -1300,7 +3408,7 @@ pub async fn recheck_interest(app_handle: &AppHandle, interest_id: &str) -> Resu
         }
 
         match check_source_for_matches(app_handle, rss_state, &source, &interest_vec).await {
-            Ok(count) => {
+            Ok((count, _)) => {
                 total_matched += count;
                 if count > 0 {
                     info!("Found {} alternatives for interest '{}' from source '{}'", count, interest.name, source.name);
@@ -1314,3 +3422,401 @@ pub async fn recheck_interest(app_handle: &AppHandle, interest_id: &str) -> Resu
 
     Ok(total_matched)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_preset_filter_is_valid() {
+        for preset in interest_presets() {
+            assert!(!preset.filters.is_empty(), "{} has no filters", preset.id);
+            for filter in &preset.filters {
+                if let Err(e) = validate_filter(filter) {
+                    panic!("{}: {:?} filter {:?} is invalid: {}", preset.id, filter.filter_type, filter.value, e.message);
+                }
+            }
+        }
+    }
+
+    fn pending(source: &str, title: &str, magnet: Option<&str>, size: Option<u64>) -> PendingMatch {
+        PendingMatch {
+            id: uuid::Uuid::new_v4().to_string(),
+            source_id: source.to_lowercase(),
+            source_name: source.into(),
+            sources: vec![source.into()],
+            interest_id: "i1".into(),
+            interest_name: "Show".into(),
+            matched_interests: vec![MatchedInterest { id: "i1".into(), name: "Show".into() }],
+            title: title.into(),
+            magnet_uri: magnet.map(Into::into),
+            torrent_url: magnet.is_none().then(|| format!("https://{}/file.torrent", source.to_lowercase())),
+            size,
+            web_seeds: Vec::new(),
+            last_error: None,
+            failure_count: 0,
+            rejected_reason: None,
+            seeders_rejected_reason: None,
+            health: None,
+            created_at: Utc::now().to_rfc3339(),
+            metadata: None,
+            is_upgrade: false,
+            replaces_torrent_id: None,
+            replaces_title: None,
+        }
+    }
+
+    #[test]
+    fn release_titles_normalize_across_sources() {
+        let expected = "show name s01e02 1080p web dl";
+        assert_eq!(normalize_release_title("Show.Name.S01E02.1080p.WEB-DL"), expected);
+        assert_eq!(normalize_release_title("show name s01e02 1080p web-dl [rarbg]"), expected);
+        assert_eq!(normalize_release_title("[EZTV] Show_Name  S01E02 1080p WEB DL"), expected);
+        assert_ne!(normalize_release_title("Show.Name.S01E03.1080p.WEB-DL"), expected);
+    }
+
+    #[test]
+    fn duplicate_matches_merge_sources_and_prefer_magnets() {
+        let mut matches = vec![pending("TorrentSite", "Show.Name.S01E02.1080p", None, Some(1_500_000_000))];
+
+        let same = pending("Indexer", "[grp] show name s01e02 1080p", Some("magnet:?xt=urn:btih:c12fe1c06bba254a9dc9f519b335aa7c1367a88a"), Some(1_500_000_000));
+        assert!(merge_duplicate(&mut matches, &same));
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].sources, ["TorrentSite", "Indexer"]);
+        assert_eq!(matches[0].source_name, "Indexer");
+        assert!(matches[0].magnet_uri.is_some());
+
+        let other_size = pending("Tracker", "Show Name S01E02 1080p", None, Some(900_000_000));
+        assert!(!merge_duplicate(&mut matches, &other_size));
+
+        let same_hash = pending("Mirror", "Renamed", Some("magnet:?xt=urn:btih:YEX6DQDLXISUVHOJ6UM3GNNKPQJWPKEK"), None);
+        assert!(merge_duplicate(&mut matches, &same_hash));
+        assert_eq!(matches[0].sources, ["TorrentSite", "Indexer", "Mirror"]);
+        assert_eq!(matches[0].source_name, "Indexer");
+    }
+
+    #[test]
+    fn test_extract_info_hash_hex() {
+        let magnet = "magnet:?xt=urn:btih:C12FE1C06BBA254A9DC9F519B335AA7C1367A88A&dn=Test";
+        assert_eq!(
+            extract_info_hash(magnet),
+            Some("c12fe1c06bba254a9dc9f519b335aa7c1367a88a".to_string())
+        );
+    }
+
+    #[test]
+    fn test_extract_info_hash_base32() {
+        let magnet = "magnet:?dn=Test&xt=urn:btih:YEX6DQDLXISUVHOJ6UM3GNNKPQJWPKEK";
+        assert_eq!(
+            extract_info_hash(magnet),
+            Some("c12fe1c06bba254a9dc9f519b335aa7c1367a88a".to_string())
+        );
+    }
+
+    #[test]
+    fn test_extract_info_hash_invalid() {
+        assert_eq!(extract_info_hash("magnet:?dn=NoHash"), None);
+        assert_eq!(extract_info_hash("magnet:?xt=urn:btih:tooshort"), None);
+        assert_eq!(extract_info_hash("https://example.com/file.torrent"), None);
+    }
+
+    #[test]
+    fn test_is_torrent_file() {
+        assert!(is_torrent_file(b"d8:announce3:url4:infod4:name1:a6:lengthi5eee"));
+        assert!(!is_torrent_file(b"d8:announce3:urle"));
+        assert!(!is_torrent_file(b"d4:info3:abce"));
+        assert!(!is_torrent_file(b"<html>not a torrent</html>"));
+        // Truncated download
+        assert!(!is_torrent_file(b"d4:infod4:name1:a"));
+        assert!(!is_torrent_file(b"d4:infod4:name99:ae"));
+    }
+
+    fn filter(filter_type: FilterType, value: &str) -> FeedFilter {
+        FeedFilter {
+            filter_type,
+            value: value.to_string(),
+            enabled: true,
+        }
+    }
+
+    #[test]
+    fn test_validate_filter() {
+        let kind = |f: FeedFilter| validate_filter(&f).err().map(|e| e.kind);
+
+        // Every type rejects an empty value
+        for filter_type in [
+            FilterType::MustContain,
+            FilterType::MustNotContain,
+            FilterType::Regex,
+            FilterType::Wildcard,
+            FilterType::SizeRange,
+            FilterType::MinSeeders,
+        ] {
+            assert_eq!(kind(filter(filter_type, "  ")), Some(FilterErrorKind::EmptyValue));
+        }
+
+        assert!(validate_filter(&filter(FilterType::MustContain, "1080p")).is_ok());
+        assert!(validate_filter(&filter(FilterType::MustNotContain, "(CAM")).is_ok());
+        assert!(validate_filter(&filter(FilterType::Wildcard, "Show*(2024)?")).is_ok());
+
+        assert!(validate_filter(&filter(FilterType::Regex, r"Show\.S\d+E\d+")).is_ok());
+        let err = validate_filter(&filter(FilterType::Regex, "Show (S01")).unwrap_err();
+        assert_eq!(err.kind, FilterErrorKind::InvalidRegex);
+        assert_eq!(err.position, Some(5));
+        assert!(err.message.contains("unclosed group"));
+        assert_eq!(kind(filter(FilterType::Regex, "a{2,1}")), Some(FilterErrorKind::InvalidRegex));
+
+        assert!(validate_filter(&filter(FilterType::SizeRange, "500-4000")).is_ok());
+        assert!(validate_filter(&filter(FilterType::SizeRange, "-4000")).is_ok());
+        assert!(validate_filter(&filter(FilterType::SizeRange, "500-")).is_ok());
+        assert_eq!(kind(filter(FilterType::SizeRange, "4000")), Some(FilterErrorKind::InvalidSizeRange));
+        assert_eq!(kind(filter(FilterType::SizeRange, "-")), Some(FilterErrorKind::InvalidSizeRange));
+        assert_eq!(kind(filter(FilterType::SizeRange, "4000-500")), Some(FilterErrorKind::InvalidSizeRange));
+        let err = validate_filter(&filter(FilterType::SizeRange, "500-4GB")).unwrap_err();
+        assert_eq!(err.kind, FilterErrorKind::InvalidSizeRange);
+        assert_eq!(err.position, Some(4));
+
+        assert!(validate_filter(&filter(FilterType::MinSeeders, "5")).is_ok());
+        assert_eq!(kind(filter(FilterType::MinSeeders, "-1")), Some(FilterErrorKind::InvalidNumber));
+        assert_eq!(kind(filter(FilterType::MinSeeders, "five")), Some(FilterErrorKind::InvalidNumber));
+    }
+
+    #[test]
+    fn test_parse_size_range() {
+        assert_eq!(parse_size_range("500-4000"), Some((Some(500), Some(4000))));
+        assert_eq!(parse_size_range("-2000"), Some((None, Some(2000))));
+        assert_eq!(parse_size_range("4000-"), Some((Some(4000), None)));
+        assert_eq!(parse_size_range(" 100 - 200 "), Some((Some(100), Some(200))));
+        assert_eq!(parse_size_range("-"), None);
+        assert_eq!(parse_size_range("4000"), None);
+        assert_eq!(parse_size_range("1GB-2GB"), None);
+
+        let item = |mb: u64| ParsedFeedItem {
+            id: "x".into(),
+            guid: "x".into(),
+            title: "x".into(),
+            magnet_uri: None,
+            torrent_url: None,
+            size: Some(mb * 1024 * 1024),
+            seeders: None,
+            published_date: None,
+            web_seeds: Vec::new(),
+        };
+        let max = filter(FilterType::SizeRange, "-2000");
+        assert!(evaluate_single_filter(&item(1500), &max));
+        assert!(!evaluate_single_filter(&item(2500), &max));
+        let min = filter(FilterType::SizeRange, "4000-");
+        assert!(evaluate_single_filter(&item(8000), &min));
+        assert!(!evaluate_single_filter(&item(3000), &min));
+    }
+
+    #[test]
+    fn test_extract_seeders() {
+        let xml = r#"<rss xmlns:nyaa="https://nyaa.si/xmlns/nyaa"><channel>
+            <item><title>A</title><nyaa:seeders>42</nyaa:seeders></item>
+            <item><title>B</title><torrent:seeds> 7 </torrent:seeds></item>
+            <item><title>C</title><torznab:attr name="seeders" value="13"/></item>
+            <item><title>D</title></item>
+        </channel></rss>"#;
+        assert_eq!(seeders_per_entry(xml), vec![Some(42), Some(7), Some(13), None]);
+
+        assert_eq!(extract_seeders_from_text("Size: 1.2 GB | Seeders: 31 | Leechers: 4"), Some(31));
+        assert_eq!(extract_seeders_from_text("seeds=5"), Some(5));
+        assert_eq!(extract_seeders_from_text("Leechers: 4"), None);
+    }
+
+    #[test]
+    fn test_validate_interest_filters() {
+        // Blank filters are drafts, disabled ones are never evaluated
+        let mut disabled = filter(FilterType::Regex, "(");
+        disabled.enabled = false;
+        assert!(validate_interest_filters(&[filter(FilterType::MustContain, ""), disabled]).is_ok());
+
+        let err = validate_interest_filters(&[
+            filter(FilterType::MustContain, "Show"),
+            filter(FilterType::Regex, "Show (S01"),
+        ])
+        .unwrap_err();
+        assert!(err.to_string().contains("Filter 2"));
+    }
+
+    #[test]
+    fn test_render_organize_template() {
+        assert_eq!(
+            render_organize_template("~/TV/{interest}/Season {season}", "Show", "Show.S02E05.720p.WEB-DL").unwrap(),
+            "~/TV/Show/Season 02"
+        );
+        assert_eq!(
+            render_organize_template("/Media/{title} - {quality}", "Any", "Show.S02E05.720p.WEB-DL").unwrap(),
+            "/Media/Show - 720p"
+        );
+        // Values can't add path levels
+        assert_eq!(
+            render_organize_template("/Media/{interest}", "AC/DC: Live", "x").unwrap(),
+            "/Media/AC-DC- Live"
+        );
+        assert!(render_organize_template("/Media/{season}", "Movie", "Movie.2019.1080p.BluRay").is_err());
+        assert!(render_organize_template("/Media/{resolution}", "Show", "Show.S02E05").is_err());
+        assert!(render_organize_template("/Media/{interest", "Show", "Show.S02E05").is_err());
+        assert!(render_organize_template("/Media/../{interest}", "Show", "Show.S02E05").is_err());
+    }
+
+    #[test]
+    fn stale_alerts_count_from_the_last_match_or_reset() {
+        let now = chrono::DateTime::parse_from_rfc3339("2026-03-10T12:00:00+00:00").unwrap().with_timezone(&Utc);
+        let mut interest = interest_from_preset("webDl1080p", "Show", None).unwrap();
+        interest.last_match_at = Some("2026-03-01T12:00:00+00:00".into());
+        assert!(!is_stale(&interest, now), "no alert configured");
+
+        interest.stale_alert_days = Some(7);
+        assert!(is_stale(&interest, now));
+        interest.stale_alert_reset_at = Some("2026-03-05T12:00:00+00:00".into());
+        assert!(!is_stale(&interest, now), "alert already sent");
+        interest.stale_alert_days = Some(5);
+        assert!(is_stale(&interest, now));
+        interest.enabled = false;
+        assert!(!is_stale(&interest, now));
+    }
+
+    #[test]
+    fn file_filters_leave_out_samples_but_never_everything() {
+        let files = vec![
+            ("Show.S01/Show.S01E01.mkv".to_string(), 900 * 1024 * 1024),
+            ("Show.S01/Sample/show.s01e01.sample.mkv".to_string(), 20 * 1024 * 1024),
+            ("Show.S01/Show.S01E02.mkv".to_string(), 900 * 1024 * 1024),
+            ("Show.S01/extras.rar".to_string(), 50 * 1024 * 1024),
+        ];
+        let filters = vec![
+            filter(FilterType::MustNotContain, "sample"),
+            filter(FilterType::Wildcard, "*.mkv"),
+        ];
+        assert_eq!(
+            select_files(&files, &filters),
+            Some(FileSelection {
+                only_files: vec![0, 2],
+                skipped: vec!["Show.S01/Sample/show.s01e01.sample.mkv".into(), "Show.S01/extras.rar".into()],
+            })
+        );
+
+        assert_eq!(select_files(&files, &[]), None);
+        assert_eq!(select_files(&files, &[filter(FilterType::SizeRange, "10-")]), None, "nothing to leave out");
+        assert_eq!(select_files(&files, &[filter(FilterType::MustContain, "2160p")]), None, "everything left out");
+    }
+
+    #[test]
+    fn metadata_filters_look_inside_the_torrent() {
+        let file = |name: &str| TorrentFilePreview { name: name.into(), size: 0, is_video: false, is_suspicious: false };
+        let metadata = TorrentMetadata {
+            name: "Show.S01.1080p".into(),
+            total_size: 2 * 1024 * 1024 * 1024,
+            file_count: 2,
+            files: vec![file("Show.S01/Show.S01E01.mkv"), file("Show.S01/Show.S01E02.mkv")],
+        };
+        assert_eq!(metadata_rejection(&metadata, &[filter(FilterType::Wildcard, "*.mkv")]), None);
+        assert_eq!(
+            metadata_rejection(&metadata, &[filter(FilterType::Regex, r"\.(srt|ass)$")]),
+            Some(r"No file matches /\.(srt|ass)$/".into())
+        );
+
+        let mut with_exe = metadata.clone();
+        with_exe.files.push(file("Show.S01/setup.exe"));
+        assert_eq!(
+            metadata_rejection(&with_exe, &[filter(FilterType::MustNotContain, ".exe")]),
+            Some("A file contains \".exe\"".into())
+        );
+        assert_eq!(metadata_rejection(&with_exe, &[]), None);
+    }
+
+    #[tokio::test]
+    async fn only_the_queued_interest_marks_episodes_seen() {
+        let rss_state = RssState::new();
+        let interest = |id: &str, priority: i32| -> Interest {
+            serde_json::from_value(serde_json::json!({
+                "id": id,
+                "name": id,
+                "enabled": true,
+                "filters": [filter(FilterType::MustContain, "Show")],
+                "smart_episode_filter": true,
+                "priority": priority,
+            }))
+            .unwrap()
+        };
+        let (primary, secondary) = (interest("primary", 1), interest("secondary", 0));
+        let item = ParsedFeedItem {
+            id: "x".into(),
+            guid: "x".into(),
+            title: "Show S01E02 1080p".into(),
+            magnet_uri: None,
+            torrent_url: None,
+            size: None,
+            seeders: None,
+            published_date: None,
+            web_seeds: Vec::new(),
+        };
+
+        let (matched, _) = find_matching_interests(&rss_state, &item, &[&secondary, &primary]).await;
+        assert_eq!(matched.iter().map(|i| i.id.as_str()).collect::<Vec<_>>(), ["primary", "secondary"]);
+        // Matching alone records nothing
+        assert!(rss_state.seen_episodes.lock().await.is_empty());
+
+        mark_episode_seen(&rss_state, &primary, &item.title).await;
+        assert!(matches!(check_episode(&rss_state, &primary, &item.title).await, EpisodeCheck::Duplicate));
+        assert!(matches!(check_episode(&rss_state, &secondary, &item.title).await, EpisodeCheck::New));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_feed_times_out() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let app = axum::Router::new().route(
+            "/feed",
+            axum::routing::get(|| async {
+                tokio::time::sleep(Duration::from_secs(10)).await;
+                "<rss version=\"2.0\"><channel></channel></rss>"
+            }),
+        );
+        tokio::spawn(async move {
+            let _ = axum::serve(listener, app).await;
+        });
+
+        let opts = FeedFetchOptions {
+            timeout_secs: Some(1),
+            ..Default::default()
+        };
+        let started = std::time::Instant::now();
+        let result = fetch_feed(&format!("http://{}/feed", addr), &opts).await;
+        assert!(result.is_err());
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_feed_sends_user_agent() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let app = axum::Router::new().route(
+            "/feed",
+            axum::routing::get(|headers: axum::http::HeaderMap| async move {
+                let ua = headers
+                    .get(axum::http::header::USER_AGENT)
+                    .and_then(|v| v.to_str().ok())
+                    .unwrap_or_default()
+                    .to_string();
+                format!(
+                    "<rss version=\"2.0\"><channel><title>t</title><item><title>{}</title><guid>1</guid></item></channel></rss>",
+                    ua
+                )
+            }),
+        );
+        tokio::spawn(async move {
+            let _ = axum::serve(listener, app).await;
+        });
+
+        let opts = FeedFetchOptions {
+            user_agent: Some("CustomAgent/1.0"),
+            ..Default::default()
+        };
+        let items = fetch_feed(&format!("http://{}/feed", addr), &opts).await.unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].title, "CustomAgent/1.0");
+    }
+}
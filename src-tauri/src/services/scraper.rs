@@ -6,19 +6,28 @@ use std::sync::Arc;
 use chrono::Utc;
 use regex::Regex;
 use scraper::{Html, Selector};
-use tauri::{AppHandle, Emitter};
+use tauri::{AppHandle, Emitter, Manager};
 use tokio::sync::{Mutex, RwLock};
 use tracing::{info, warn};
 
 use crate::errors::{Result, WhenThenError};
-use crate::models::{Interest, PendingMatch, ScrapedItem, ScraperConfig, ScraperTestResult};
-use crate::services::rss::{evaluate_filters_with_logic, ParsedFeedItem, RssState};
+use crate::models::{FeedTestItem, Interest, MatchedInterest, PendingMatch, ScrapedItem, ScraperConfig, ScraperTestResult};
+use crate::services::event_bus;
+use crate::services::rss::{
+    check_on_schedule, evaluate_filters_with_logic, is_bad_item, queue_pending, search_term, search_url_for_term,
+    CheckSchedule, ParsedFeedItem, RssState, Scheduled,
+};
+use crate::state::AppState;
+
+/// Most detail pages fetched per scraper run.
+const MAX_DETAIL_FETCHES: usize = 20;
 
-#[allow(dead_code)]
 pub struct ScraperState {
     pub configs: Arc<RwLock<Vec<ScraperConfig>>>,
     /// Seen items: key -> ISO timestamp
     pub seen_items: Arc<Mutex<HashMap<String, String>>>,
+    /// Links found on detail pages, by detail page URL
+    pub detail_cache: Arc<Mutex<HashMap<String, DetailLinks>>>,
 }
 
 impl ScraperState {
@@ -26,42 +35,97 @@ impl ScraperState {
         Self {
             configs: Arc::new(RwLock::new(Vec::new())),
             seen_items: Arc::new(Mutex::new(HashMap::new())),
+            detail_cache: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 }
 
+/// Download links found on an item's detail page.
+#[derive(Debug, Clone, Default)]
+pub struct DetailLinks {
+    pub magnet_uri: Option<String>,
+    pub torrent_url: Option<String>,
+}
+
+/// Items on one results page, plus the next page's URL when the page links to it.
+pub struct ScrapedPage {
+    pub items: Vec<ScrapedItem>,
+    pub next_url: Option<String>,
+}
+
 /// Scrape a page using the given config.
-pub async fn scrape_page(config: &ScraperConfig, url: &str) -> Result<Vec<ScrapedItem>> {
+pub async fn scrape_page(config: &ScraperConfig, url: &str) -> Result<ScrapedPage> {
+    let html = fetch_html(config, url).await?;
+    parse_page(&html, config, url)
+}
+
+/// Fetch a page, honouring the config's request delay and custom headers.
+async fn fetch_html(config: &ScraperConfig, url: &str) -> Result<String> {
     // Rate limit
     tokio::time::sleep(std::time::Duration::from_millis(config.request_delay_ms)).await;
 
     let client = reqwest::Client::new();
-    let response = client
-        .get(url)
-        .header("User-Agent", "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7)")
+    let mut request = client.get(url);
+    if !config.extra_headers.keys().any(|k| k.eq_ignore_ascii_case("user-agent")) {
+        request = request.header("User-Agent", "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7)");
+    }
+    if let Some(cookie) = config.cookie_string.as_deref().filter(|c| !c.is_empty()) {
+        request = request.header(reqwest::header::COOKIE, cookie);
+    }
+    for (name, value) in &config.extra_headers {
+        request = request.header(name.as_str(), value.as_str());
+    }
+
+    let response = request
         .send()
         .await
         .map_err(|e| WhenThenError::Scraper(format!("Request failed: {}", e)))?;
 
-    if !response.status().is_success() {
+    let status = response.status();
+    let cf_ray = response.headers().contains_key("cf-ray");
+    let html = response
+        .text()
+        .await
+        .map_err(|e| WhenThenError::Scraper(format!("Failed to read response: {}", e)))?;
+
+    if is_challenge_page(status.as_u16(), cf_ray, &html) {
+        return Err(WhenThenError::ScraperBlocked(format!(
+            "{} answered with a bot challenge (HTTP {}). Open it in a browser, then paste the cf_clearance cookie and that browser's User-Agent into the scraper's settings.",
+            config.name,
+            status.as_u16()
+        )));
+    }
+
+    if !status.is_success() {
         return Err(WhenThenError::Scraper(format!(
             "Request returned status {}",
-            response.status()
+            status
         )));
     }
 
-    let html = response
-        .text()
-        .await
-        .map_err(|e| WhenThenError::Scraper(format!("Failed to read response: {}", e)))?;
+    Ok(html)
+}
 
-    parse_page(&html, config)
+/// Whether a response is an anti-bot interstitial (Cloudflare and lookalikes)
+/// rather than the page asked for.
+fn is_challenge_page(status: u16, cf_ray: bool, html: &str) -> bool {
+    let challenge_title = html.contains("<title>Just a moment") || html.contains("<title>Attention Required");
+    let challenge_status = matches!(status, 403 | 503);
+    challenge_title || (challenge_status && (cf_ray || html.contains("Just a moment") || html.contains("cf-chl")))
 }
 
 /// Parse HTML page using scraper config selectors.
-fn parse_page(html: &str, config: &ScraperConfig) -> Result<Vec<ScrapedItem>> {
+fn parse_page(html: &str, config: &ScraperConfig, url: &str) -> Result<ScrapedPage> {
     let document = Html::parse_document(html);
 
+    let next_sel = config
+        .next_page_selector
+        .as_ref()
+        .filter(|s| !s.is_empty())
+        .map(|s| Selector::parse(s))
+        .transpose()
+        .map_err(|_| WhenThenError::Scraper("Invalid next page selector".into()))?;
+
     let item_sel = Selector::parse(&config.item_selector)
         .map_err(|_| WhenThenError::Scraper(format!("Invalid item selector: {}", config.item_selector)))?;
 
@@ -71,6 +135,14 @@ fn parse_page(html: &str, config: &ScraperConfig) -> Result<Vec<ScrapedItem>> {
     let link_sel = Selector::parse(&config.link_selector)
         .map_err(|_| WhenThenError::Scraper(format!("Invalid link selector: {}", config.link_selector)))?;
 
+    let detail_sel = config
+        .detail_link_selector
+        .as_ref()
+        .filter(|s| !s.is_empty())
+        .map(|s| Selector::parse(s))
+        .transpose()
+        .map_err(|_| WhenThenError::Scraper("Invalid detail link selector".into()))?;
+
     let size_sel = config
         .size_selector
         .as_ref()
@@ -93,32 +165,25 @@ fn parse_page(html: &str, config: &ScraperConfig) -> Result<Vec<ScrapedItem>> {
         }
 
         // Get link (magnet or torrent URL)
-        let mut magnet_uri = None;
-        let mut torrent_url = None;
-
-        if let Some(link_elem) = item.select(&link_sel).next() {
-            if let Some(href) = link_elem.value().attr("href") {
-                if href.starts_with("magnet:") {
-                    magnet_uri = Some(href.to_string());
-                } else if href.ends_with(".torrent") || href.contains("/download") {
-                    let url = if href.starts_with("http") {
-                        href.to_string()
-                    } else {
-                        format!("{}{}", config.base_url, href)
-                    };
-                    torrent_url = Some(url);
-                } else {
-                    // Try to find magnet in the element text or data attributes
-                    let text = link_elem.text().collect::<String>();
-                    if let Some(mag) = extract_magnet(&text) {
-                        magnet_uri = Some(mag);
-                    }
-                }
-            }
-        }
+        let DetailLinks { magnet_uri, torrent_url } = item
+            .select(&link_sel)
+            .next()
+            .map(|link_elem| links_from_element(link_elem, &config.base_url))
+            .unwrap_or_default();
+
+        // No link in the list; remember the detail page to look there instead
+        let detail_url = if magnet_uri.is_none() && torrent_url.is_none() {
+            detail_sel.as_ref().and_then(|sel| {
+                let href = item.select(sel).next()?.value().attr("href")?;
+                let base = reqwest::Url::parse(&config.base_url).ok()?;
+                Some(base.join(href).ok()?.to_string())
+            })
+        } else {
+            None
+        };
 
         // Skip items without any download link
-        if magnet_uri.is_none() && torrent_url.is_none() {
+        if magnet_uri.is_none() && torrent_url.is_none() && detail_url.is_none() {
             continue;
         }
 
@@ -134,10 +199,127 @@ fn parse_page(html: &str, config: &ScraperConfig) -> Result<Vec<ScrapedItem>> {
             magnet_uri,
             torrent_url,
             size,
+            detail_url,
+            from_detail_page: false,
         });
     }
 
-    Ok(items)
+    // Resolve the next-page link against the page it was found on
+    let next_url = next_sel.and_then(|sel| {
+        let href = document.select(&sel).next()?.value().attr("href")?;
+        let next = reqwest::Url::parse(url).ok()?.join(href).ok()?;
+        Some(next.to_string()).filter(|next| next != url)
+    });
+
+    Ok(ScrapedPage { items, next_url })
+}
+
+/// Magnet or torrent link from an anchor's href, or a magnet in its text.
+fn links_from_element(link_elem: scraper::ElementRef, base_url: &str) -> DetailLinks {
+    let mut links = DetailLinks::default();
+    if let Some(href) = link_elem.value().attr("href") {
+        if href.starts_with("magnet:") {
+            links.magnet_uri = Some(href.to_string());
+        } else if href.ends_with(".torrent") || href.contains("/download") {
+            let url = if href.starts_with("http") {
+                href.to_string()
+            } else {
+                format!("{}{}", base_url, href)
+            };
+            links.torrent_url = Some(url);
+        } else {
+            // Try to find magnet in the element text or data attributes
+            let text = link_elem.text().collect::<String>();
+            if let Some(mag) = extract_magnet(&text) {
+                links.magnet_uri = Some(mag);
+            }
+        }
+    }
+    links
+}
+
+/// Pull the download link out of a detail page. Without a detail selector, the
+/// first magnet link on the page is used.
+fn parse_detail_page(html: &str, config: &ScraperConfig) -> Result<DetailLinks> {
+    let document = Html::parse_document(html);
+    let selector = config
+        .detail_magnet_selector
+        .as_deref()
+        .filter(|s| !s.is_empty())
+        .unwrap_or("a[href^='magnet:']");
+    let sel = Selector::parse(selector)
+        .map_err(|_| WhenThenError::Scraper(format!("Invalid detail magnet selector: {}", selector)))?;
+
+    Ok(document
+        .select(&sel)
+        .map(|elem| links_from_element(elem, &config.base_url))
+        .find(|links| links.magnet_uri.is_some() || links.torrent_url.is_some())
+        .unwrap_or_default())
+}
+
+/// Fill in links for items that only have a detail page. Cached pages are free;
+/// otherwise each fetch uses up one of `budget`. Items left without a link stay
+/// in the list so callers can decide what to do with them.
+async fn resolve_details(
+    config: &ScraperConfig,
+    cache: &Mutex<HashMap<String, DetailLinks>>,
+    items: &mut [ScrapedItem],
+    budget: &mut usize,
+) {
+    for item in items.iter_mut() {
+        let Some(detail_url) = item.detail_url.clone() else {
+            continue;
+        };
+        if has_link(item) {
+            continue;
+        }
+
+        let cached = cache.lock().await.get(&detail_url).cloned();
+        let links = match cached {
+            Some(links) => links,
+            None if *budget == 0 => continue,
+            None => {
+                *budget -= 1;
+                let fetched = match fetch_html(config, &detail_url).await {
+                    Ok(html) => parse_detail_page(&html, config),
+                    Err(e) => Err(e),
+                };
+                match fetched {
+                    Ok(links) => {
+                        cache.lock().await.insert(detail_url.clone(), links.clone());
+                        links
+                    }
+                    Err(e) => {
+                        warn!("Failed to fetch detail page {}: {}", detail_url, e);
+                        continue;
+                    }
+                }
+            }
+        };
+
+        item.from_detail_page = links.magnet_uri.is_some() || links.torrent_url.is_some();
+        item.magnet_uri = links.magnet_uri;
+        item.torrent_url = links.torrent_url;
+    }
+}
+
+fn has_link(item: &ScrapedItem) -> bool {
+    item.magnet_uri.is_some() || item.torrent_url.is_some()
+}
+
+/// URL of the page after `page`, from the page's next link or the page_param template.
+fn next_page_url(config: &ScraperConfig, first_url: &str, page: u32, scraped: &ScrapedPage) -> Option<String> {
+    if page >= config.max_pages {
+        return None;
+    }
+    if config.next_page_selector.as_deref().is_some_and(|s| !s.is_empty()) {
+        return scraped.next_url.clone();
+    }
+    config
+        .page_param
+        .as_ref()
+        .filter(|p| !p.is_empty())
+        .map(|param| format!("{}{}", first_url, param.replace("{page}", &(page + 1).to_string())))
 }
 
 /// Extract magnet link from text.
@@ -172,21 +354,76 @@ fn parse_size(text: &str) -> Option<u64> {
 }
 
 /// Build search URL from template.
-#[allow(dead_code)]
 fn build_search_url(config: &ScraperConfig, interest: &Interest) -> Option<String> {
-    config.search_url_template.as_ref().map(|template| {
-        let term = interest
-            .search_term
-            .as_deref()
-            .filter(|s| !s.is_empty())
-            .unwrap_or(&interest.name);
-        let encoded = urlencoding::encode(term);
-        template.replace("{search}", &encoded)
-    })
+    search_url(config, search_term(interest))
+}
+
+/// The config's search URL for a term, when it has a search template.
+pub(crate) fn search_url(config: &ScraperConfig, term: &str) -> Option<String> {
+    config
+        .search_url_template
+        .as_ref()
+        .map(|template| search_url_for_term(template, term))
+}
+
+impl Scheduled for ScraperConfig {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn schedule(&mut self) -> CheckSchedule<'_> {
+        CheckSchedule {
+            check_interval: self.check_interval,
+            next_check_at: &mut self.next_check_at,
+            failure_count: &mut self.failure_count,
+            retry_after: &mut self.retry_after,
+            last_checked: &mut self.last_checked,
+            last_error: &mut self.last_error,
+        }
+    }
 }
 
-/// Check a scraper config against all interests and queue matches.
-#[allow(dead_code)]
+/// Check enabled scrapers against interests on their schedules (see
+/// check_on_schedule). Returns the number of new matches.
+pub async fn check_scrapers(
+    app_handle: &AppHandle,
+    state: &AppState,
+    interests: &[&Interest],
+    global_interval_mins: u32,
+    global_check_due: bool,
+    force: bool,
+) -> usize {
+    let checked = check_on_schedule(
+        app_handle,
+        &state.scraper_state.configs,
+        "Scraper",
+        global_interval_mins,
+        global_check_due,
+        force,
+        |config| async move {
+            check_scraper_for_matches(app_handle, &state.scraper_state, &state.rss_state, &config, interests).await
+        },
+    )
+    .await;
+    let Some(total_matched) = checked else {
+        return 0;
+    };
+    crate::commands::scraper::persist_configs(state);
+    crate::commands::scraper::persist_seen_items(state);
+
+    total_matched
+}
+
+/// Check a scraper config against all interests and queue matches. Fails only
+/// when no interest's search could be fetched at all.
 pub async fn check_scraper_for_matches(
     app_handle: &AppHandle,
     scraper_state: &ScraperState,
@@ -195,6 +432,9 @@ pub async fn check_scraper_for_matches(
     interests: &[&Interest],
 ) -> Result<usize> {
     let mut matched_count = 0;
+    let mut detail_budget = MAX_DETAIL_FETCHES;
+    let mut first_error = None;
+    let mut any_fetched = false;
 
     for interest in interests {
         let url = match build_search_url(config, interest) {
@@ -202,32 +442,99 @@ pub async fn check_scraper_for_matches(
             None => config.base_url.clone(),
         };
 
-        info!("Scraping {} for interest '{}'", url, interest.name);
-
-        match scrape_page(config, &url).await {
-            Ok(items) => {
-                let count = process_scraped_items(
-                    app_handle,
-                    scraper_state,
-                    rss_state,
-                    config,
-                    interest,
-                    &items,
-                )
-                .await;
-                matched_count += count;
+        let first_url = url.clone();
+        let mut url = url;
+        let mut page = 1;
+
+        loop {
+            info!("Scraping {} for interest '{}' (page {})", url, interest.name, page);
+
+            let scraped = match scrape_page(config, &url).await {
+                Ok(scraped) => scraped,
+                Err(e) => {
+                    warn!("Failed to scrape {} for '{}': {}", url, interest.name, e);
+                    if first_error.is_none() {
+                        first_error = Some(e);
+                    }
+                    break;
+                }
+            };
+            any_fetched = true;
+            if scraped.items.is_empty() {
+                break;
+            }
+
+            let next = next_page_url(config, &first_url, page, &scraped);
+
+            // Only unseen items are worth a detail page fetch
+            let mut unseen: Vec<ScrapedItem> = {
+                let seen = scraper_state.seen_items.lock().await;
+                scraped
+                    .items
+                    .into_iter()
+                    .filter(|item| !seen.contains_key(&seen_key(config, interest, item)))
+                    .collect()
+            };
+            // Newest items come first, so a page of known items means we've caught up
+            let all_seen = unseen.is_empty();
+
+            resolve_details(config, &scraper_state.detail_cache, &mut unseen, &mut detail_budget).await;
+            // Unresolved items get another chance next run
+            unseen.retain(has_link);
+
+            matched_count += process_scraped_items(
+                app_handle,
+                scraper_state,
+                rss_state,
+                config,
+                interest,
+                &unseen,
+            )
+            .await;
+
+            if all_seen {
+                break;
             }
-            Err(e) => {
-                warn!("Failed to scrape {} for '{}': {}", url, interest.name, e);
+            match next {
+                Some(next) => url = next,
+                None => break,
             }
+            page += 1;
         }
     }
 
-    Ok(matched_count)
+    match first_error {
+        Some(e) if !any_fetched => Err(e),
+        _ => {
+            if matched_count > 0 {
+                let count = rss_state.pending_matches.read().await.len();
+                let _ = event_bus::emit(app_handle, "rss:pending-count", count);
+            }
+            Ok(matched_count)
+        }
+    }
+}
+
+fn seen_key(config: &ScraperConfig, interest: &Interest, item: &ScrapedItem) -> String {
+    format!("{}:{}:{}", config.id, interest.id, item.title)
+}
+
+/// A scraped item in the shape the feed filters evaluate.
+fn feed_item(item: &ScrapedItem, published_date: Option<String>) -> ParsedFeedItem {
+    ParsedFeedItem {
+        id: item.title.clone(),
+        guid: item.title.clone(),
+        title: item.title.clone(),
+        magnet_uri: item.magnet_uri.clone(),
+        torrent_url: item.torrent_url.clone(),
+        size: item.size,
+        seeders: None,
+        published_date,
+        web_seeds: Vec::new(),
+    }
 }
 
 /// Process scraped items and create pending matches.
-#[allow(dead_code)]
 async fn process_scraped_items(
     app_handle: &AppHandle,
     scraper_state: &ScraperState,
@@ -240,7 +547,7 @@ async fn process_scraped_items(
 
     for item in items {
         let mut seen = scraper_state.seen_items.lock().await;
-        let item_key = format!("{}:{}:{}", config.id, interest.id, item.title);
+        let item_key = seen_key(config, interest, item);
 
         if seen.contains_key(&item_key) {
             continue;
@@ -249,15 +556,13 @@ async fn process_scraped_items(
         let now = Utc::now().to_rfc3339();
 
         // Convert to ParsedFeedItem for filter evaluation
-        let feed_item = ParsedFeedItem {
-            id: item.title.clone(),
-            guid: item.title.clone(),
-            title: item.title.clone(),
-            magnet_uri: item.magnet_uri.clone(),
-            torrent_url: item.torrent_url.clone(),
-            size: item.size,
-            published_date: Some(now.clone()),
-        };
+        let feed_item = feed_item(item, Some(now.clone()));
+
+        if is_bad_item(rss_state, &item.title, item.magnet_uri.as_deref()).await {
+            info!("Skipping bad item: {}", item.title);
+            seen.insert(item_key, now);
+            continue;
+        }
 
         let matched = evaluate_filters_with_logic(&feed_item, &interest.filters, &interest.filter_logic);
         if matched.is_none() {
@@ -268,20 +573,38 @@ async fn process_scraped_items(
         seen.insert(item_key, now.clone());
         drop(seen);
 
+        let source_name = format!("{} (scraper)", config.name);
         let pending = PendingMatch {
             id: uuid::Uuid::new_v4().to_string(),
             source_id: config.id.clone(),
-            source_name: format!("{} (scraper)", config.name),
+            sources: vec![source_name.clone()],
+            source_name,
             interest_id: interest.id.clone(),
             interest_name: interest.name.clone(),
+            matched_interests: vec![MatchedInterest {
+                id: interest.id.clone(),
+                name: interest.name.clone(),
+            }],
             title: item.title.clone(),
             magnet_uri: item.magnet_uri.clone(),
             torrent_url: item.torrent_url.clone(),
+            size: item.size,
+            web_seeds: Vec::new(),
+            last_error: None,
+            failure_count: 0,
+            rejected_reason: None,
+            seeders_rejected_reason: None,
+            health: None,
             created_at: now,
             metadata: None,
+            is_upgrade: false,
+            replaces_torrent_id: None,
+            replaces_title: None,
         };
 
-        rss_state.pending_matches.write().await.push(pending.clone());
+        if !queue_pending(app_handle, rss_state, &pending).await {
+            continue;
+        }
         matched_count += 1;
 
         let _ = app_handle.emit(
@@ -293,18 +616,99 @@ async fn process_scraped_items(
                 "title": item.title,
             }),
         );
+        crate::services::notifications::notify_rss_match(app_handle, &item.title, &interest.name).await;
     }
 
     matched_count
 }
 
-/// Test a scraper config.
+/// Test a scraper config, following pages up to max_pages.
 pub async fn test_scraper(config: &ScraperConfig) -> Result<ScraperTestResult> {
-    let url = config.search_url_template.as_ref().unwrap_or(&config.base_url);
-    let items = scrape_page(config, url).await?;
+    let first_url = config.search_url_template.as_ref().unwrap_or(&config.base_url);
+    let mut url = first_url.clone();
+    let mut items = Vec::new();
+    let mut page_counts = Vec::new();
+    let mut page = 1;
+    // A private cache so the test really fetches detail pages
+    let detail_cache = Mutex::new(HashMap::new());
+    let mut detail_budget = MAX_DETAIL_FETCHES;
+
+    loop {
+        let scraped = match scrape_page(config, &url).await {
+            Ok(scraped) => scraped,
+            // Later pages failing shouldn't hide what page 1 found
+            Err(e) if page > 1 => {
+                warn!("Scraper test stopped at page {}: {}", page, e);
+                break;
+            }
+            Err(e) => return Err(e),
+        };
+        page_counts.push(scraped.items.len());
+        if scraped.items.is_empty() {
+            break;
+        }
+        let next = next_page_url(config, first_url, page, &scraped);
+        let mut page_items = scraped.items;
+        resolve_details(config, &detail_cache, &mut page_items, &mut detail_budget).await;
+        items.extend(page_items);
+        match next {
+            Some(next) => url = next,
+            None => break,
+        }
+        page += 1;
+    }
 
     Ok(ScraperTestResult {
         total_count: items.len(),
         items,
+        page_counts,
     })
 }
+
+/// Run an interest's filters over the first page of a scraper, flagging
+/// matches that were already seen for that interest.
+pub async fn test_interest(
+    scraper_state: &ScraperState,
+    interest: &Interest,
+    config: &ScraperConfig,
+) -> Result<Vec<FeedTestItem>> {
+    let url = build_search_url(config, interest).unwrap_or_else(|| config.base_url.clone());
+    let scraped = scrape_page(config, &url).await?;
+
+    let seen = scraper_state.seen_items.lock().await;
+    Ok(scraped
+        .items
+        .iter()
+        .map(|item| {
+            let matched_filter =
+                evaluate_filters_with_logic(&feed_item(item, None), &interest.filters, &interest.filter_logic);
+            FeedTestItem {
+                title: item.title.clone(),
+                matches: matched_filter.is_some(),
+                would_have_matched: matched_filter.is_some() && seen.contains_key(&seen_key(config, interest, item)),
+                matched_filter,
+                size: item.size,
+                seeders: None,
+                source_id: Some(config.id.clone()),
+                source_name: Some(config.name.clone()),
+            }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_challenge_page() {
+        let cloudflare = "<!DOCTYPE html><html><head><title>Just a moment...</title></head><body>cf-chl</body></html>";
+        assert!(is_challenge_page(503, true, cloudflare));
+        assert!(is_challenge_page(403, false, "<html><head><title>Attention Required! | Cloudflare</title>"));
+        assert!(is_challenge_page(403, true, "<html>denied</html>"));
+
+        // A plain 403 without Cloudflare markers is an ordinary error
+        assert!(!is_challenge_page(403, false, "<html><title>Forbidden</title></html>"));
+        assert!(!is_challenge_page(200, true, "<html><title>Search results</title></html>"));
+    }
+}
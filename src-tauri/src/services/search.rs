@@ -0,0 +1,235 @@
+// One-off searches across feed sources, scrapers and Torznab indexers, without
+// creating an interest. Nothing here touches seen items or the screener inbox.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+
+use tauri::AppHandle;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+use tracing::{info, warn};
+
+use crate::errors::{Result, WhenThenError};
+use crate::models::{
+    ScraperConfig, SearchFailure, SearchResponse, SearchResult, Source, TorrentAddedResponse, TorznabIndexer,
+};
+use crate::services::rss::{
+    fetch_feed, fetch_torrent_url, has_search_placeholder, search_url_for_term, FeedFetchOptions,
+};
+use crate::services::{scraper, torrent_engine, torznab};
+use crate::state::AppState;
+
+/// Searches running at once.
+const MAX_PARALLEL_SEARCHES: usize = 4;
+
+/// Longest a single source may take before it's reported as failed.
+const SEARCH_TIMEOUT: Duration = Duration::from_secs(20);
+
+enum SearchTarget {
+    Source(Source),
+    Scraper(ScraperConfig),
+    Indexer(TorznabIndexer),
+}
+
+impl SearchTarget {
+    fn name(&self) -> String {
+        match self {
+            Self::Source(source) => source.name.clone(),
+            Self::Scraper(config) => format!("{} (scraper)", config.name),
+            Self::Indexer(indexer) => format!("{} (Torznab)", indexer.name),
+        }
+    }
+
+    async fn search(&self, query: &str) -> Result<Vec<SearchResult>> {
+        let source_name = self.name();
+        match self {
+            Self::Source(source) => {
+                let url = search_url_for_term(&source.url, query);
+                let items = fetch_feed(&url, &FeedFetchOptions::from_source(source)).await?;
+                Ok(items
+                    .into_iter()
+                    .map(|item| SearchResult {
+                        title: item.title,
+                        source_name: source_name.clone(),
+                        size: item.size,
+                        seeders: item.seeders,
+                        magnet_uri: item.magnet_uri,
+                        torrent_url: item.torrent_url,
+                    })
+                    .collect())
+            }
+            Self::Scraper(config) => {
+                let Some(url) = scraper::search_url(config, query) else {
+                    return Ok(Vec::new());
+                };
+                let page = scraper::scrape_page(config, &url).await?;
+                Ok(page
+                    .items
+                    .into_iter()
+                    .map(|item| SearchResult {
+                        title: item.title,
+                        source_name: source_name.clone(),
+                        size: item.size,
+                        seeders: None,
+                        magnet_uri: item.magnet_uri,
+                        torrent_url: item.torrent_url,
+                    })
+                    .collect())
+            }
+            Self::Indexer(indexer) => {
+                let items = torznab::search(indexer, query).await?;
+                Ok(items
+                    .into_iter()
+                    .map(|item| SearchResult {
+                        title: item.title,
+                        source_name: source_name.clone(),
+                        size: item.size,
+                        seeders: item.seeders,
+                        magnet_uri: item.magnet_uri,
+                        torrent_url: item.torrent_url,
+                    })
+                    .collect())
+            }
+        }
+    }
+}
+
+/// Everything that can be searched: enabled sources with a {search}
+/// placeholder, scrapers with a search template, and enabled indexers.
+async fn search_targets(state: &AppState) -> Vec<SearchTarget> {
+    let sources = state.rss_state.sources.read().await;
+    let scrapers = state.scraper_state.configs.read().await;
+    let indexers = state.torznab_state.indexers.read().await;
+
+    let sources = sources
+        .iter()
+        .filter(|s| s.enabled && has_search_placeholder(&s.url))
+        .cloned()
+        .map(SearchTarget::Source);
+    let scrapers = scrapers
+        .iter()
+        .filter(|c| c.enabled && c.search_url_template.is_some())
+        .cloned()
+        .map(SearchTarget::Scraper);
+    let indexers = indexers
+        .iter()
+        .filter(|i| i.enabled)
+        .cloned()
+        .map(SearchTarget::Indexer);
+
+    sources.chain(scrapers).chain(indexers).collect()
+}
+
+/// Search every searchable source for `query`. A source that fails or times
+/// out is listed in `failures` and the rest still return results.
+pub async fn search_all(state: &AppState, query: &str) -> Result<SearchResponse> {
+    let query = query.trim();
+    if query.is_empty() {
+        return Err(WhenThenError::InvalidInput("Search query is empty".into()));
+    }
+
+    let targets = search_targets(state).await;
+    if targets.is_empty() {
+        return Err(WhenThenError::NotFound(
+            "No sources support searching. Add a feed with a {search} placeholder, a scraper with a search URL, or a Torznab indexer".into(),
+        ));
+    }
+    info!("Searching {} sources for '{}'", targets.len(), query);
+
+    let permits = Arc::new(Semaphore::new(MAX_PARALLEL_SEARCHES));
+    let mut tasks = JoinSet::new();
+    for target in targets {
+        let permits = permits.clone();
+        let query = query.to_string();
+        tasks.spawn(async move {
+            let _permit = permits.acquire_owned().await;
+            let result = match tokio::time::timeout(SEARCH_TIMEOUT, target.search(&query)).await {
+                Ok(result) => result,
+                Err(_) => Err(WhenThenError::Rss(format!("Timed out after {}s", SEARCH_TIMEOUT.as_secs()))),
+            };
+            (target.name(), result)
+        });
+    }
+
+    let mut results = Vec::new();
+    let mut failures = Vec::new();
+    while let Some(joined) = tasks.join_next().await {
+        match joined {
+            Ok((_, Ok(found))) => results.extend(found),
+            Ok((source_name, Err(e))) => {
+                warn!("Search failed for {}: {}", source_name, e);
+                failures.push(SearchFailure {
+                    source_name,
+                    message: e.to_string(),
+                });
+            }
+            Err(e) => warn!("Search task failed: {}", e),
+        }
+    }
+
+    Ok(SearchResponse {
+        results: merge_results(results),
+        failures,
+    })
+}
+
+/// Drop results without a link and duplicates by title and size, best seeded first.
+fn merge_results(results: Vec<SearchResult>) -> Vec<SearchResult> {
+    let mut seen = HashSet::new();
+    let mut merged: Vec<SearchResult> = results
+        .into_iter()
+        .filter(|r| r.magnet_uri.is_some() || r.torrent_url.is_some())
+        .filter(|r| seen.insert((r.title.trim().to_lowercase(), r.size)))
+        .collect();
+    merged.sort_by(|a, b| b.seeders.cmp(&a.seeders));
+    merged
+}
+
+/// Add a search result as a torrent.
+pub async fn add_result(state: &AppState, app_handle: &AppHandle, result: SearchResult) -> Result<TorrentAddedResponse> {
+    info!("Adding search result '{}' from {}", result.title, result.source_name);
+    match (result.magnet_uri, result.torrent_url) {
+        (Some(magnet), _) => torrent_engine::add_magnet(state, app_handle, magnet, None).await,
+        (None, Some(url)) => {
+            let bytes = fetch_torrent_url(&state.rss_state, &url).await?;
+            torrent_engine::add_torrent_bytes(state, app_handle, bytes, None).await
+        }
+        (None, None) => Err(WhenThenError::InvalidInput("No torrent URI".into())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(title: &str, size: Option<u64>, seeders: Option<u32>) -> SearchResult {
+        SearchResult {
+            title: title.into(),
+            source_name: "Feed".into(),
+            size,
+            seeders,
+            magnet_uri: Some(format!("magnet:?dn={title}")),
+            torrent_url: None,
+        }
+    }
+
+    #[test]
+    fn merges_duplicates_by_title_and_size() {
+        let mut no_link = result("Show S01E03", None, Some(99));
+        no_link.magnet_uri = None;
+        let merged = merge_results(vec![
+            result("Show S01E01", Some(100), Some(5)),
+            result("show s01e01 ", Some(100), Some(7)),
+            result("Show S01E01", Some(200), None),
+            result("Show S01E02", None, Some(50)),
+            no_link,
+        ]);
+
+        let titles: Vec<_> = merged.iter().map(|r| (r.title.as_str(), r.size)).collect();
+        assert_eq!(
+            titles,
+            vec![("Show S01E02", None), ("Show S01E01", Some(100)), ("Show S01E01", Some(200))]
+        );
+    }
+}
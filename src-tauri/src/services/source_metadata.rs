@@ -0,0 +1,189 @@
+// Name, description and icon for a feed source, so a pasted URL shows up in
+// the sources list as something recognizable. Every step is best effort: a
+// feed or site that can't be reached leaves the name as the URL's host.
+
+use std::path::PathBuf;
+
+use reqwest::Url;
+use scraper::{Html, Selector};
+use tauri::{AppHandle, Manager};
+use tracing::{debug, warn};
+
+use crate::models::SourceMetadata;
+use crate::services::rss::{build_feed_client, FeedFetchOptions};
+
+/// Metadata lookups are a nicety; don't keep the user waiting on a slow site.
+const METADATA_TIMEOUT_SECS: u64 = 10;
+
+/// Larger responses aren't icons.
+const MAX_ICON_BYTES: usize = 512 * 1024;
+
+/// Host without a leading "www.", for naming a source after its URL.
+pub fn name_from_url(url: &str) -> String {
+    Url::parse(url)
+        .ok()
+        .and_then(|u| u.host_str().map(|h| h.trim_start_matches("www.").to_string()))
+        .unwrap_or_else(|| url.to_string())
+}
+
+/// Icon URLs a page declares with `<link rel="icon">` and friends, in page order.
+fn icon_links(html: &str, base: &Url) -> Vec<Url> {
+    let document = Html::parse_document(html);
+    let selector = Selector::parse("link[rel][href]").unwrap();
+    document
+        .select(&selector)
+        .filter(|link| {
+            link.value()
+                .attr("rel")
+                .is_some_and(|rel| rel.split_whitespace().any(|r| r.eq_ignore_ascii_case("icon")))
+        })
+        .filter_map(|link| base.join(link.value().attr("href")?).ok())
+        .collect()
+}
+
+/// File extension for a downloaded icon.
+fn icon_extension(content_type: Option<&str>, url: &Url) -> &'static str {
+    match content_type.unwrap_or_default().split(';').next().unwrap_or_default().trim() {
+        "image/png" => "png",
+        "image/svg+xml" => "svg",
+        "image/gif" => "gif",
+        "image/jpeg" => "jpg",
+        "image/webp" => "webp",
+        _ if url.path().ends_with(".png") => "png",
+        _ if url.path().ends_with(".svg") => "svg",
+        _ => "ico",
+    }
+}
+
+/// Cached icon for `host`, whatever format it was saved in.
+fn cached_icon(dir: &std::path::Path, host: &str) -> Option<PathBuf> {
+    std::fs::read_dir(dir).ok()?.flatten().map(|e| e.path()).find(|p| {
+        p.file_stem().and_then(|s| s.to_str()) == Some(host)
+    })
+}
+
+/// Fetch the feed's title, description and site link, then the site's icon.
+pub async fn fetch(app: &AppHandle, url: &str) -> SourceMetadata {
+    let mut metadata = SourceMetadata {
+        suggested_name: name_from_url(url),
+        description: None,
+        icon_path: None,
+    };
+    let Ok(feed_url) = Url::parse(url) else {
+        return metadata;
+    };
+    let client = match build_feed_client(&FeedFetchOptions {
+        timeout_secs: Some(METADATA_TIMEOUT_SECS),
+        ..Default::default()
+    }) {
+        Ok(client) => client,
+        Err(e) => {
+            warn!(error = %e, "Can't build a client for source metadata");
+            return metadata;
+        }
+    };
+
+    let mut site = None;
+    match fetch_feed_info(&client, url).await {
+        Ok((title, description, link)) => {
+            if let Some(title) = title.filter(|t| !t.trim().is_empty()) {
+                metadata.suggested_name = title.trim().to_string();
+            }
+            metadata.description = description.filter(|d| !d.trim().is_empty());
+            site = link.and_then(|l| Url::parse(&l).ok());
+        }
+        Err(e) => debug!(url, error = %e, "Couldn't read feed for metadata"),
+    }
+
+    let site = site
+        .filter(|s| s.host_str().is_some())
+        .unwrap_or_else(|| feed_url.join("/").unwrap_or(feed_url));
+    metadata.icon_path = fetch_icon(app, &client, &site)
+        .await
+        .map(|p| p.to_string_lossy().to_string());
+    metadata
+}
+
+async fn fetch_feed_info(
+    client: &reqwest::Client,
+    url: &str,
+) -> crate::errors::Result<(Option<String>, Option<String>, Option<String>)> {
+    let bytes = client.get(url).send().await?.error_for_status()?.bytes().await?;
+    let feed = feed_rs::parser::parse(&bytes[..])?;
+    Ok((
+        feed.title.map(|t| t.content),
+        feed.description.map(|d| d.content),
+        feed.links.first().map(|l| l.href.clone()),
+    ))
+}
+
+/// The site's icon, downloaded into app_data_dir/icons once per host.
+async fn fetch_icon(app: &AppHandle, client: &reqwest::Client, site: &Url) -> Option<PathBuf> {
+    let host = site.host_str()?.to_string();
+    let dir = app.path().app_data_dir().ok()?.join("icons");
+    if let Some(path) = cached_icon(&dir, &host) {
+        return Some(path);
+    }
+
+    let homepage = site.join("/").ok()?;
+    let mut candidates = match client.get(homepage.clone()).send().await {
+        Ok(response) if response.status().is_success() => match response.text().await {
+            Ok(html) => icon_links(&html, &homepage),
+            Err(_) => Vec::new(),
+        },
+        _ => Vec::new(),
+    };
+    candidates.extend(homepage.join("/favicon.ico").ok());
+
+    for candidate in candidates {
+        let Ok(response) = client.get(candidate.clone()).send().await else {
+            continue;
+        };
+        if !response.status().is_success() {
+            continue;
+        }
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(String::from);
+        if content_type.as_deref().is_some_and(|t| t.starts_with("text/")) {
+            continue;
+        }
+        let Ok(bytes) = response.bytes().await else {
+            continue;
+        };
+        if bytes.is_empty() || bytes.len() > MAX_ICON_BYTES {
+            continue;
+        }
+
+        let path = dir.join(format!("{host}.{}", icon_extension(content_type.as_deref(), &candidate)));
+        if let Err(e) = std::fs::create_dir_all(&dir).and_then(|_| std::fs::write(&path, &bytes)) {
+            warn!(error = %e, "Failed to cache source icon");
+            return None;
+        }
+        return Some(path);
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn icons_come_from_link_tags_and_names_from_the_host() {
+        let base = Url::parse("https://www.example.org/").unwrap();
+        let html = r#"<html><head>
+            <link rel="stylesheet" href="/style.css">
+            <link rel="shortcut icon" href="/static/fav.png">
+            <link rel="apple-touch-icon" href="/touch.png">
+            <link rel="icon" href="https://cdn.example.org/icon.svg">
+        </head></html>"#;
+        let icons: Vec<String> = icon_links(html, &base).into_iter().map(String::from).collect();
+        assert_eq!(icons, ["https://www.example.org/static/fav.png", "https://cdn.example.org/icon.svg"]);
+
+        assert_eq!(name_from_url("https://www.example.org/rss?cat=1"), "example.org");
+        assert_eq!(name_from_url("not a url"), "not a url");
+    }
+}
@@ -0,0 +1,219 @@
+// Bandwidth usage, sampled from per-torrent counters and kept in daily buckets
+// across sessions.
+
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
+
+use chrono::Local;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_store::StoreExt;
+use tokio::sync::{oneshot, Mutex};
+use tracing::{info, warn};
+
+use crate::models::{DailyUsage, StatsRange, UsageStats};
+use crate::services::torrent_engine;
+use crate::state::AppState;
+
+const STATS_STORE: &str = "stats.json";
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Default)]
+pub struct BandwidthStats {
+    /// Daily totals by local date ("YYYY-MM-DD")
+    days: BTreeMap<String, DailyUsage>,
+    /// Counters from the previous sample, by info hash
+    last: HashMap<String, (u64, u64)>,
+    /// Month ("YYYY-MM") the data cap last paused torrents in, so it fires once a month
+    cap_reached_month: Option<String>,
+}
+
+impl BandwidthStats {
+    fn month_totals(&self, month: &str) -> (u64, u64) {
+        self.days
+            .range(month.to_string()..)
+            .take_while(|(date, _)| date.starts_with(month))
+            .fold((0, 0), |(down, up), (_, day)| (down + day.downloaded, up + day.uploaded))
+    }
+}
+
+pub struct StatsSamplerHandle {
+    shutdown_tx: oneshot::Sender<()>,
+    task: tauri::async_runtime::JoinHandle<()>,
+}
+
+/// Start sampling every 30 seconds.
+pub fn start(app: AppHandle) -> StatsSamplerHandle {
+    let (shutdown_tx, mut shutdown_rx) = oneshot::channel();
+    let task = tauri::async_runtime::spawn(async move {
+        let mut ticker = tokio::time::interval(SAMPLE_INTERVAL);
+        ticker.tick().await;
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => sample(&app).await,
+                _ = &mut shutdown_rx => break,
+            }
+        }
+        // Count what moved since the last tick before the session goes away
+        sample(&app).await;
+    });
+    StatsSamplerHandle { shutdown_tx, task }
+}
+
+/// Stop the sampler after a final sample.
+pub async fn stop(handle: &Mutex<Option<StatsSamplerHandle>>) {
+    if let Some(h) = handle.lock().await.take() {
+        let _ = h.shutdown_tx.send(());
+        let _ = h.task.await;
+        info!("Stats sampler stopped");
+    }
+}
+
+/// Bytes moved since the previous sample. Counters only cover the current run
+/// of a torrent, so one that appeared since then, or whose counters went
+/// backwards because it was paused and resumed, counts from zero. Torrents
+/// removed between samples lose at most one interval of traffic.
+fn deltas(last: &HashMap<String, (u64, u64)>, current: &HashMap<String, (u64, u64)>) -> (u64, u64) {
+    let step = |now: u64, prev: u64| if now >= prev { now - prev } else { now };
+    current.iter().fold((0, 0), |(down, up), (hash, &(now_down, now_up))| {
+        let (prev_down, prev_up) = last.get(hash).copied().unwrap_or((0, 0));
+        (down + step(now_down, prev_down), up + step(now_up, prev_up))
+    })
+}
+
+async fn sample(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    let counters = torrent_engine::transfer_counters(&state).await;
+    let today = Local::now().format("%Y-%m-%d").to_string();
+    let month = today[..7].to_string();
+
+    let (changed, (month_down, month_up)) = {
+        let mut stats = state.bandwidth_stats.lock().await;
+        let (down, up) = deltas(&stats.last, &counters);
+        stats.last = counters;
+        if down > 0 || up > 0 {
+            let day = stats.days.entry(today.clone()).or_insert_with(|| DailyUsage {
+                date: today.clone(),
+                ..Default::default()
+            });
+            day.downloaded += down;
+            day.uploaded += up;
+        }
+        (down > 0 || up > 0, stats.month_totals(&month))
+    };
+    if changed {
+        persist(app, &state).await;
+    }
+
+    let Some(cap) = state.config.read().await.monthly_data_cap.filter(|cap| *cap > 0) else {
+        return;
+    };
+    let used = month_down + month_up;
+    if used < cap {
+        return;
+    }
+    let first_time = {
+        let mut stats = state.bandwidth_stats.lock().await;
+        let first_time = stats.cap_reached_month.as_deref() != Some(month.as_str());
+        stats.cap_reached_month = Some(month);
+        first_time
+    };
+    // Torrents the user resumes after this stay running until next month
+    if first_time {
+        warn!("Monthly data cap reached ({} of {} bytes), pausing all torrents", used, cap);
+        torrent_engine::pause_all(&state, app).await;
+        persist(app, &state).await;
+        let _ = app.emit("stats:cap-reached", serde_json::json!({ "used": used, "cap": cap }));
+    }
+}
+
+async fn persist(app: &AppHandle, state: &AppState) {
+    if let Ok(store) = app.store(STATS_STORE) {
+        let stats = state.bandwidth_stats.lock().await;
+        let days: Vec<&DailyUsage> = stats.days.values().collect();
+        if let Ok(value) = serde_json::to_value(days) {
+            store.set("days", value);
+        }
+        store.set("cap_reached_month", serde_json::json!(stats.cap_reached_month));
+        if let Err(e) = store.save() {
+            tracing::error!("Failed to save stats: {}", e);
+        }
+    }
+}
+
+pub async fn load(app: &AppHandle, state: &AppState) {
+    if let Ok(store) = app.store(STATS_STORE) {
+        if let Err(e) = store.reload() {
+            tracing::warn!("Could not load stats store: {}", e);
+        }
+        let mut stats = state.bandwidth_stats.lock().await;
+        if let Some(days) = store
+            .get("days")
+            .and_then(|value| serde_json::from_value::<Vec<DailyUsage>>(value).ok())
+        {
+            info!("Loaded {} days of bandwidth stats", days.len());
+            stats.days = days.into_iter().map(|day| (day.date.clone(), day)).collect();
+        }
+        stats.cap_reached_month = store
+            .get("cap_reached_month")
+            .and_then(|value| serde_json::from_value(value).ok());
+    }
+}
+
+/// Daily totals for the range plus this month's sum.
+pub async fn usage(state: &AppState, range: StatsRange) -> UsageStats {
+    let today = Local::now().date_naive();
+    let month = today.format("%Y-%m").to_string();
+    let cutoff = range
+        .days()
+        .map(|days| (today - chrono::Duration::days(days - 1)).format("%Y-%m-%d").to_string());
+
+    let monthly_data_cap = state.config.read().await.monthly_data_cap;
+
+    let stats = state.bandwidth_stats.lock().await;
+    let days = stats
+        .days
+        .values()
+        .filter(|day| cutoff.as_ref().is_none_or(|cutoff| day.date >= *cutoff))
+        .cloned()
+        .collect();
+    let (month_downloaded, month_uploaded) = stats.month_totals(&month);
+
+    UsageStats {
+        days,
+        month_downloaded,
+        month_uploaded,
+        monthly_data_cap,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deltas_handle_new_restarted_and_removed_torrents() {
+        let last = HashMap::from([
+            ("steady".to_string(), (100, 10)),
+            ("restarted".to_string(), (500, 50)),
+            ("removed".to_string(), (900, 90)),
+        ]);
+        let current = HashMap::from([
+            ("steady".to_string(), (150, 15)),
+            ("restarted".to_string(), (20, 2)),
+            ("new".to_string(), (30, 3)),
+        ]);
+        assert_eq!(deltas(&last, &current), (50 + 20 + 30, 5 + 2 + 3));
+    }
+
+    #[test]
+    fn month_totals_only_count_that_month() {
+        let mut stats = BandwidthStats::default();
+        for (date, down) in [("2024-01-31", 1), ("2024-02-01", 2), ("2024-02-29", 3), ("2024-03-01", 4)] {
+            stats.days.insert(
+                date.to_string(),
+                DailyUsage { date: date.to_string(), downloaded: down, uploaded: 0 },
+            );
+        }
+        assert_eq!(stats.month_totals("2024-02"), (5, 0));
+    }
+}
@@ -1,9 +1,15 @@
 use std::path::Path;
-use tracing::info;
+use tracing::{info, warn};
 
 use crate::errors::{WhenThenError, Result};
 use crate::models::SubtitleData;
 
+/// Cues converted to WebVTT, plus how many were skipped for bad timestamps.
+struct Converted {
+    vtt: String,
+    dropped_cues: usize,
+}
+
 pub fn load_subtitle_file(path: &str) -> Result<SubtitleData> {
     let path = Path::new(path);
     if !path.exists() {
@@ -22,18 +28,23 @@ pub fn load_subtitle_file(path: &str) -> Result<SubtitleData> {
         .unwrap_or("subtitles")
         .to_string();
 
-    let content = std::fs::read_to_string(path)
+    let bytes = std::fs::read(path)
         .map_err(|e| WhenThenError::SubtitleParse(format!("Failed to read file: {e}")))?;
+    let (content, encoding) = decode(&bytes);
 
-    let vtt_content = match extension.as_str() {
+    let converted = match extension.as_str() {
         "vtt" => {
-            info!("Loaded VTT subtitle: {}", original_name);
-            content
+            info!("Loaded VTT subtitle: {} ({})", original_name, encoding);
+            Converted { vtt: content, dropped_cues: 0 }
         }
         "srt" => {
-            info!("Converting SRT to VTT: {}", original_name);
+            info!("Converting SRT to VTT: {} ({})", original_name, encoding);
             srt_to_vtt(&content)?
         }
+        "ass" | "ssa" => {
+            info!("Converting {} to VTT: {} ({})", extension.to_uppercase(), original_name, encoding);
+            ass_to_vtt(&content)?
+        }
         _ => {
             return Err(WhenThenError::UnsupportedFormat(format!(
                 "Unsupported subtitle format: .{}",
@@ -42,31 +53,143 @@ pub fn load_subtitle_file(path: &str) -> Result<SubtitleData> {
         }
     };
 
+    if converted.dropped_cues > 0 {
+        warn!("Dropped {} cues with malformed timestamps from {}", converted.dropped_cues, original_name);
+    }
+
     Ok(SubtitleData {
-        vtt_content,
+        vtt_content: converted.vtt.clone(),
+        source_vtt: converted.vtt,
+        offset_ms: 0,
         original_name,
+        format: extension,
+        encoding: encoding.to_string(),
+        dropped_cues: converted.dropped_cues,
     })
 }
 
-fn srt_to_vtt(srt_content: &str) -> Result<String> {
+/// Decode subtitle bytes to text, guessing the encoding when there's no BOM
+/// and the file isn't valid UTF-8 (common for older Windows-made subs).
+fn decode(bytes: &[u8]) -> (String, &'static str) {
+    if let Some((encoding, bom_len)) = encoding_rs::Encoding::for_bom(bytes) {
+        let (text, _) = encoding.decode_without_bom_handling(&bytes[bom_len..]);
+        return (text.into_owned(), encoding.name());
+    }
+    if let Ok(text) = std::str::from_utf8(bytes) {
+        return (text.to_string(), encoding_rs::UTF_8.name());
+    }
+
+    let mut detector = chardetng::EncodingDetector::new();
+    detector.feed(bytes, true);
+    let encoding = detector.guess(None, true);
+    let (text, _, _) = encoding.decode(bytes);
+    (text.into_owned(), encoding.name())
+}
+
+/// Parse `H:MM:SS.cc`, `HH:MM:SS,mmm` and similar into milliseconds.
+fn parse_timestamp(ts: &str) -> Option<u64> {
+    let ts = ts.trim();
+    let (clock, fraction) = ts.split_once(['.', ',']).unwrap_or((ts, "0"));
+    let parts: Vec<&str> = clock.split(':').collect();
+    let [h, m, s] = parts.as_slice() else { return None };
+    let (h, m, s): (u64, u64, u64) = (h.parse().ok()?, m.parse().ok()?, s.parse().ok()?);
+    if m >= 60 || s >= 60 || fraction.is_empty() || fraction.len() > 3 {
+        return None;
+    }
+    // Scale ".5" / ".50" / ".500" to milliseconds
+    let millis = fraction.parse::<u64>().ok()? * 10u64.pow(3 - fraction.len() as u32);
+    Some(((h * 60 + m) * 60 + s) * 1000 + millis)
+}
+
+fn format_timestamp(ms: u64) -> String {
+    format!(
+        "{:02}:{:02}:{:02}.{:03}",
+        ms / 3_600_000,
+        ms / 60_000 % 60,
+        ms / 1000 % 60,
+        ms % 1000
+    )
+}
+
+/// Parse a `start --> end` timing line; cue settings after the end time are ignored.
+fn parse_timing(line: &str) -> Option<(u64, u64)> {
+    let (start, rest) = line.split_once("-->")?;
+    let end = rest.split_whitespace().next()?;
+    Some((parse_timestamp(start)?, parse_timestamp(end)?))
+}
+
+/// Shift every cue in a VTT document by `offset_ms`, clamping at zero. Cues
+/// pushed entirely before the start are dropped.
+pub fn shift_vtt(vtt: &str, offset_ms: i64) -> String {
+    let shift = |ms: u64| (ms as i64).saturating_add(offset_ms).max(0) as u64;
+    let mut out = String::new();
+    let mut skipping = false;
+
+    for line in vtt.lines() {
+        if line.trim().is_empty() {
+            // A blank line ends the current cue
+            if !skipping {
+                out.push('\n');
+            }
+            skipping = false;
+            continue;
+        }
+        if skipping {
+            continue;
+        }
+        match parse_timing(line) {
+            Some((start, end)) => {
+                let (start, end) = (shift(start), shift(end));
+                if end <= start {
+                    // Collapsed to nothing; drop the cue and its text
+                    skipping = true;
+                    continue;
+                }
+                // Keep cue settings (position, alignment) after the end time
+                let settings = line
+                    .split_once("-->")
+                    .and_then(|(_, rest)| rest.trim().split_once(char::is_whitespace))
+                    .map(|(_, s)| format!(" {}", s.trim()))
+                    .unwrap_or_default();
+                out.push_str(&format!("{} --> {}{}\n", format_timestamp(start), format_timestamp(end), settings));
+            }
+            None => {
+                out.push_str(line);
+                out.push('\n');
+            }
+        }
+    }
+    out
+}
+
+fn srt_to_vtt(srt_content: &str) -> Result<Converted> {
     let mut vtt = String::from("WEBVTT\n\n");
+    let mut dropped_cues = 0;
     let content = srt_content.replace('\r', "");
     let blocks: Vec<&str> = content.split("\n\n").collect();
 
     for block in blocks {
         let lines: Vec<&str> = block.trim().lines().collect();
-        if lines.len() < 3 {
+        if lines.is_empty() {
             continue;
         }
 
-        // Skip the sequence number (first line)
-        // Convert timestamp format: 00:00:00,000 -> 00:00:00.000
-        let timestamp = lines[1].replace(',', ".");
+        // The sequence number is optional in the wild; find the timing line
+        let Some(timing_idx) = lines.iter().take(2).position(|l| l.contains("-->")) else {
+            dropped_cues += 1;
+            continue;
+        };
+        let Some((start, end)) = parse_timing(lines[timing_idx]) else {
+            dropped_cues += 1;
+            continue;
+        };
 
-        let text: Vec<&str> = lines[2..].to_vec();
+        let text = &lines[timing_idx + 1..];
+        if text.is_empty() {
+            continue;
+        }
 
-        vtt.push_str(&timestamp);
-        vtt.push('\n');
+        vtt.push_str(&format!("{} --> {}\n", format_timestamp(start), format_timestamp(end)));
         for line in text {
             vtt.push_str(line);
             vtt.push('\n');
@@ -74,19 +197,190 @@ fn srt_to_vtt(srt_content: &str) -> Result<String> {
         vtt.push('\n');
     }
 
-    Ok(vtt)
+    Ok(Converted { vtt, dropped_cues })
+}
+
+fn ass_to_vtt(ass_content: &str) -> Result<Converted> {
+    let mut in_events = false;
+    let mut format: Vec<String> = Vec::new();
+    let mut cues: Vec<(u64, u64, String)> = Vec::new();
+    let mut dropped_cues = 0;
+
+    for line in ass_content.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            in_events = line.eq_ignore_ascii_case("[events]");
+            continue;
+        }
+        if !in_events {
+            continue;
+        }
+        if let Some(fields) = line.strip_prefix("Format:") {
+            format = fields.split(',').map(|f| f.trim().to_lowercase()).collect();
+            continue;
+        }
+        let Some(dialogue) = line.strip_prefix("Dialogue:") else {
+            continue;
+        };
+        if format.is_empty() {
+            return Err(WhenThenError::SubtitleParse("Dialogue before [Events] Format line".into()));
+        }
+
+        // Text is the last field and may itself contain commas
+        let values: Vec<&str> = dialogue.splitn(format.len(), ',').collect();
+        let field = |name: &str| format.iter().position(|f| f == name).and_then(|i| values.get(i).copied());
+        let times = field("start")
+            .and_then(parse_timestamp)
+            .zip(field("end").and_then(parse_timestamp));
+        let (Some((start, end)), Some(text)) = (times, field("text")) else {
+            dropped_cues += 1;
+            continue;
+        };
+
+        let text = ass_text_to_vtt(text);
+        if !text.trim().is_empty() {
+            cues.push((start, end, text));
+        }
+    }
+
+    if format.is_empty() {
+        return Err(WhenThenError::SubtitleParse("No [Events] section found".into()));
+    }
+
+    // ASS files don't have to list events in time order; players expect it
+    cues.sort_by_key(|(start, _, _)| *start);
+
+    let mut vtt = String::from("WEBVTT\n\n");
+    for (start, end, text) in cues {
+        vtt.push_str(&format!("{} --> {}\n{}\n\n", format_timestamp(start), format_timestamp(end), text));
+    }
+    Ok(Converted { vtt, dropped_cues })
+}
+
+/// Convert ASS dialogue text: keep italics/bold as VTT tags, drop every other override.
+fn ass_text_to_vtt(text: &str) -> String {
+    let mut out = String::new();
+    let mut italic = false;
+    let mut bold = false;
+    let mut rest = text;
+
+    while !rest.is_empty() {
+        if let Some(block) = rest.strip_prefix('{') {
+            let Some(end) = block.find('}') else {
+                // Unterminated override block: treat as literal text
+                out.push_str(&escape_vtt(rest));
+                break;
+            };
+            for tag in block[..end].split('\\').skip(1) {
+                let (on, is_italic) = match tag {
+                    "i1" => (true, true),
+                    "i0" | "i" => (false, true),
+                    "b0" | "b" => (false, false),
+                    // \b1 or a font weight like \b700
+                    t if t.starts_with('b') && t[1..].parse::<u32>().is_ok() => (true, false),
+                    _ => continue,
+                };
+                let (flag, name) = if is_italic { (&mut italic, "i") } else { (&mut bold, "b") };
+                if *flag != on {
+                    *flag = on;
+                    out.push_str(&if on { format!("<{name}>") } else { format!("</{name}>") });
+                }
+            }
+            rest = &block[end + 1..];
+            continue;
+        }
+
+        let next = rest.find('{').unwrap_or(rest.len());
+        let chunk = rest[..next]
+            .replace("\\N", "\n")
+            .replace("\\n", "\n")
+            .replace("\\h", "\u{a0}");
+        out.push_str(&escape_vtt(&chunk));
+        rest = &rest[next..];
+    }
+
+    if bold {
+        out.push_str("</b>");
+    }
+    if italic {
+        out.push_str("</i>");
+    }
+    out
+}
+
+fn escape_vtt(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const SRT_FIXTURE: &[u8] = include_bytes!("../../tests/fixtures/subtitles/sample.srt");
+    const SRT_CP1252_FIXTURE: &[u8] = include_bytes!("../../tests/fixtures/subtitles/sample_cp1252.srt");
+    const ASS_FIXTURE: &[u8] = include_bytes!("../../tests/fixtures/subtitles/sample.ass");
+
     #[test]
     fn test_srt_to_vtt_conversion() {
         let srt = "1\n00:00:01,000 --> 00:00:04,000\nHello World\n\n2\n00:00:05,000 --> 00:00:08,000\nSecond line";
-        let result = srt_to_vtt(srt).unwrap();
+        let result = srt_to_vtt(srt).unwrap().vtt;
         assert!(result.starts_with("WEBVTT"));
         assert!(result.contains("00:00:01.000 --> 00:00:04.000"));
         assert!(result.contains("Hello World"));
     }
+
+    #[test]
+    fn test_srt_fixture_drops_malformed_cues() {
+        let (content, encoding) = decode(SRT_FIXTURE);
+        assert_eq!(encoding, "UTF-8");
+        let converted = srt_to_vtt(&content).unwrap();
+        assert_eq!(converted.dropped_cues, 1);
+        assert!(converted.vtt.contains("00:00:01.000 --> 00:00:03.500\n<i>Where are we?</i>"));
+        assert!(converted.vtt.contains("01:02:03.040 --> 01:02:05.000"));
+        assert!(!converted.vtt.contains("This cue has a broken timestamp"));
+    }
+
+    #[test]
+    fn test_cp1252_srt_is_transcoded() {
+        let (content, encoding) = decode(SRT_CP1252_FIXTURE);
+        assert_eq!(encoding, "windows-1252");
+        let converted = srt_to_vtt(&content).unwrap();
+        assert!(converted.vtt.contains("¿Qué pasó aquí, señor?"));
+        assert_eq!(converted.dropped_cues, 0);
+    }
+
+    #[test]
+    fn test_ass_fixture_to_vtt() {
+        let (content, _) = decode(ASS_FIXTURE);
+        let converted = ass_to_vtt(&content).unwrap();
+        assert_eq!(converted.dropped_cues, 1);
+        let expected = "WEBVTT\n\n\
+            00:00:01.500 --> 00:00:04.000\nHello, <i>world</i>\n\n\
+            00:00:05.000 --> 00:00:07.250\n<b>Top</b> line\nsecond line\n\n\
+            00:00:08.000 --> 00:00:10.000\nPositioned &lt;text&gt;\n\n";
+        assert_eq!(converted.vtt, expected);
+    }
+
+    #[test]
+    fn test_shift_vtt() {
+        let vtt = "WEBVTT\n\n00:00:01.000 --> 00:00:02.500 align:start\nFirst\n\n00:00:05.000 --> 00:00:07.000\nSecond\nrow\n\n";
+        assert_eq!(
+            shift_vtt(vtt, 1500),
+            "WEBVTT\n\n00:00:02.500 --> 00:00:04.000 align:start\nFirst\n\n00:00:06.500 --> 00:00:08.500\nSecond\nrow\n\n"
+        );
+        // First cue ends before zero and disappears; second is clamped at the start
+        assert_eq!(
+            shift_vtt(vtt, -6000),
+            "WEBVTT\n\n00:00:00.000 --> 00:00:01.000\nSecond\nrow\n\n"
+        );
+        assert_eq!(shift_vtt(vtt, 0), vtt);
+    }
+
+    #[test]
+    fn test_vtt_fixture_passes_through() {
+        let vtt = include_str!("../../tests/fixtures/subtitles/sample.vtt");
+        let (content, encoding) = decode(vtt.as_bytes());
+        assert_eq!(encoding, "UTF-8");
+        assert_eq!(content, vtt);
+    }
 }
@@ -46,6 +46,22 @@ pub fn score_infos(video: &MediaInfo, subtitle: &MediaInfo) -> f64 {
     score
 }
 
+/// Highest score any subtitle can reach against this video. Only fields the
+/// video name actually carries can match, so a bare title tops out low.
+pub fn max_score(video: &MediaInfo) -> f64 {
+    let mut max = TITLE_WEIGHT;
+    if video.quality.is_some() {
+        max += QUALITY_WEIGHT;
+    }
+    if video.source.is_some() {
+        max += SOURCE_WEIGHT;
+    }
+    if video.release_group.is_some() {
+        max += GROUP_WEIGHT;
+    }
+    max
+}
+
 /// Jaro-Winkler string similarity (0.0 to 1.0).
 fn jaro_winkler(s1: &str, s2: &str) -> f64 {
     if s1.is_empty() && s2.is_empty() {
@@ -1,31 +1,232 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
 
-use tracing::info;
+use regex::Regex;
+use tracing::{info, warn};
 
 use crate::errors::{WhenThenError, Result};
-use crate::models::SubtitleDownloadResult;
-use crate::services::{media_info, opensub_client, subtitle_scorer, torrent_engine::expand_path};
+use crate::models::{SubtitleCandidate, SubtitleDownloadResult, SubtitleQuota, SubtitleSource};
+use crate::services::{media_info, opensub_client, subtitle_handler, subtitle_scorer, torrent_engine, torrent_outputs};
 use crate::state::AppState;
 
+/// Most candidates offered to the user for one search.
+const MAX_CANDIDATES: usize = 20;
+
+/// Share of the best possible score a subtitle already on disk needs to be
+/// used instead of searching OpenSubtitles.
+const LOCAL_MATCH_RATIO: f64 = 0.8;
+
+const SUBTITLE_EXTENSIONS: &[&str] = &["srt", "vtt", "ass", "ssa"];
+
+/// Release-name tokens shaped like language codes.
+const NOT_LANGUAGES: &[&str] = &["web", "web-dl", "dts", "aac", "ac3", "hdr", "dvd", "avc", "rip", "dl"];
+
+/// Hearing-impaired markers that can follow the language: "Movie.en.sdh.srt".
+const HI_MARKERS: &[&str] = &["sdh", "hi", "cc"];
+
+/// Language suffix of a subtitle file stem: "Movie.en", "Movie.pt-BR", "Movie.eng".
+static LANGUAGE_SUFFIX_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)^(.+)\.([a-z]{2,3}(?:-[a-z]{2})?)$").unwrap());
+
+/// A torrent file resolved to where it currently lives on disk.
+pub(crate) struct VideoFile {
+    pub path: PathBuf,
+    pub name: String,
+    pub download_dir: String,
+}
+
+/// Search and save the best-scored subtitle in one go, for automations.
+/// A matching subtitle already next to the video is used without searching.
 pub async fn search_and_download(
     state: &AppState,
     torrent_id: usize,
     file_index: usize,
     languages: Vec<String>,
 ) -> Result<SubtitleDownloadResult> {
-    // Get API key and base directory from config
-    let (api_key, download_dir) = {
-        let cfg = state.config.read().await;
-        (cfg.opensubtitles_api_key.clone(), cfg.download_directory.clone())
+    let video = locate_video(state, torrent_id, file_index).await?;
+    if let Some(local) = find_local_subtitle(&video, &languages) {
+        return Ok(local);
+    }
+
+    let auth = auth(state).await?;
+    let candidates = scored_candidates(&auth, &video, &languages).await?;
+
+    let best = candidates.first().ok_or_else(|| {
+        WhenThenError::OpenSubtitles(format!("No subtitles found for '{}'", video.name))
+    })?;
+    info!(
+        "Selected subtitle: {} (language: {}, score: {:.2}, downloads: {})",
+        best.file_name, best.language, best.score, best.download_count
+    );
+
+    let downloaded = opensub_client::download(&auth, best.file_id).await?;
+    save_subtitle(&video, &best.language, downloaded)
+}
+
+/// Look for a subtitle shipped with the video: beside it, in a Subs/ folder,
+/// or in Subs/<video name>/. Returns the best one that scores well enough and loads.
+fn find_local_subtitle(video: &VideoFile, languages: &[String]) -> Option<SubtitleDownloadResult> {
+    let dir = video.path.parent()?;
+    let mut dirs = vec![dir.to_path_buf()];
+    if let Some(subs) = find_subdir(dir, "subs") {
+        if let Some(per_video) = find_subdir(&subs, &video.name) {
+            dirs.push(per_video);
+        }
+        dirs.push(subs);
+    }
+
+    let video_info = media_info::parse(&video.name);
+    let max_score = subtitle_scorer::max_score(&video_info);
+
+    let mut matches: Vec<(PathBuf, String, f64)> = dirs
+        .iter()
+        .filter_map(|d| std::fs::read_dir(d).ok())
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|e| SUBTITLE_EXTENSIONS.contains(&e.to_lowercase().as_str()))
+        })
+        .filter_map(|path| {
+            let stem = path.file_stem()?.to_str()?;
+            // Forced subtitles only cover foreign-language lines
+            if stem.to_lowercase().ends_with(".forced") {
+                return None;
+            }
+            let (base, language) = subtitle_language(stem);
+            if language != "unknown" && !language_requested(&language, languages) {
+                return None;
+            }
+
+            let sub_info = media_info::parse(base);
+            let other_episode = sub_info.season.is_some_and(|s| video_info.season.is_some_and(|v| v != s))
+                || sub_info.episode.is_some_and(|e| video_info.episode.is_some_and(|v| v != e));
+            if other_episode {
+                return None;
+            }
+
+            let score = subtitle_scorer::score_infos(&video_info, &sub_info) / max_score;
+            (score >= LOCAL_MATCH_RATIO).then_some((path.clone(), language, score))
+        })
+        .collect();
+
+    matches.sort_by(|a, b| b.2.partial_cmp(&a.2).unwrap_or(std::cmp::Ordering::Equal));
+
+    matches.into_iter().find_map(|(path, language, score)| {
+        let path_str = path.to_string_lossy().to_string();
+        if let Err(e) = subtitle_handler::load_subtitle_file(&path_str) {
+            warn!("Skipping local subtitle {}: {}", path.display(), e);
+            return None;
+        }
+        info!("Using local subtitle: {} (language: {}, score: {:.2})", path.display(), language, score);
+        Some(SubtitleDownloadResult {
+            file_name: path.file_name()?.to_string_lossy().to_string(),
+            file_path: path_str,
+            quota: None,
+            source: SubtitleSource::Local,
+            language,
+        })
+    })
+}
+
+/// Case-insensitive lookup of a direct subfolder.
+fn find_subdir(dir: &Path, name: &str) -> Option<PathBuf> {
+    std::fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .find(|entry| {
+            entry.file_name().to_string_lossy().eq_ignore_ascii_case(name)
+                && entry.path().is_dir()
+        })
+        .map(|entry| entry.path())
+}
+
+/// Split a language suffix off a subtitle file stem, falling back to "unknown".
+fn subtitle_language(stem: &str) -> (&str, String) {
+    let stem = match stem.rsplit_once('.') {
+        Some((rest, marker)) if HI_MARKERS.contains(&marker.to_lowercase().as_str()) => rest,
+        _ => stem,
     };
+    match LANGUAGE_SUFFIX_RE.captures(stem) {
+        Some(caps) if !NOT_LANGUAGES.contains(&caps[2].to_lowercase().as_str()) => {
+            (caps.get(1).map_or(stem, |m| m.as_str()), caps[2].to_lowercase())
+        }
+        _ => (stem, "unknown".to_string()),
+    }
+}
 
-    let moved_location = state.torrent_locations.read().await.get(&torrent_id).cloned();
+/// Whether a filename language matches one of the requested codes; "eng" counts as "en".
+fn language_requested(language: &str, languages: &[String]) -> bool {
+    languages.iter().any(|l| {
+        let l = l.to_lowercase();
+        language == l || (language.len() == 3 && language.starts_with(&l))
+    })
+}
 
+/// Scored search results for the user to pick from. Nothing is downloaded.
+pub async fn search_candidates(
+    state: &AppState,
+    torrent_id: usize,
+    file_index: usize,
+    languages: Vec<String>,
+) -> Result<Vec<SubtitleCandidate>> {
+    let auth = auth(state).await?;
+    let video = locate_video(state, torrent_id, file_index).await?;
+    let mut candidates = scored_candidates(&auth, &video, &languages).await?;
+    candidates.truncate(MAX_CANDIDATES);
+    Ok(candidates)
+}
+
+/// Download a candidate picked from `search_candidates` and save it next to the video.
+pub async fn download_candidate(
+    state: &AppState,
+    torrent_id: usize,
+    file_index: usize,
+    file_id: i64,
+    language: String,
+) -> Result<SubtitleDownloadResult> {
+    // The language ends up in the saved file name
+    if language.is_empty() || !language.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+        return Err(WhenThenError::InvalidInput(format!("Invalid subtitle language: {language}")));
+    }
+    let auth = auth(state).await?;
+    let video = locate_video(state, torrent_id, file_index).await?;
+    let downloaded = opensub_client::download(&auth, file_id).await?;
+    save_subtitle(&video, &language, downloaded)
+}
+
+/// Remaining OpenSubtitles downloads, if known.
+pub async fn quota(state: &AppState) -> Result<Option<SubtitleQuota>> {
+    opensub_client::quota(&auth(state).await?).await
+}
+
+async fn auth(state: &AppState) -> Result<opensub_client::Auth> {
+    let cfg = state.config.read().await;
+    // OpenSubtitles requires an API key
+    if cfg.opensubtitles_api_key.is_empty() {
+        return Err(WhenThenError::OpenSubtitles(
+            "OpenSubtitles API key not configured. Add your API key in Settings to enable subtitle search.".into()
+        ));
+    }
+    Ok(opensub_client::Auth {
+        api_key: cfg.opensubtitles_api_key.clone(),
+        username: cfg.opensubtitles_username.trim().to_string(),
+        password: cfg.opensubtitles_password.clone(),
+        session: state.opensub_session.clone(),
+    })
+}
+
+pub(crate) async fn locate_video(state: &AppState, torrent_id: usize, file_index: usize) -> Result<VideoFile> {
+    let download_dir = state.config.read().await.download_directory.clone();
+
+    let torrent_id = torrent_engine::resolve_id(state, torrent_id).await;
 
     let session = {
         let guard = state.torrent_session.read().await;
         guard.as_ref().ok_or_else(|| {
-            WhenThenError::Torrent("Torrent session not initialized".into())
+            WhenThenError::SessionNotReady
         })?.clone()
     };
 
@@ -51,70 +252,85 @@ pub async fn search_and_download(
 
     let torrent_name = handle.name().unwrap_or_else(|| "Unknown".to_string());
 
-    let video_file_path = if let Some(ref loc) = moved_location {
-        // Files were moved - check the new location
-        let moved_path = PathBuf::from(loc);
-        // Try with torrent folder name (multi-file torrents)
-        let with_name = moved_path.join(&torrent_name).join(file_path_str);
-        if with_name.exists() {
-            with_name
-        } else {
-            // Try direct path (single-file or flat structure)
-            let file_name = PathBuf::from(file_path_str).file_name()
-                .map(|n| n.to_string_lossy().to_string())
-                .unwrap_or_else(|| file_path_str.clone());
-            moved_path.join(&torrent_name).join(&file_name)
-        }
-    } else {
-        // Use default download directory
-        expand_path(&download_dir).join(file_path_str)
-    };
+    let output_folder = torrent_outputs::folder(state, &handle.info_hash().as_string()).await;
+    let video_file_path = torrent_outputs::file_path(&output_folder, &torrent_name, file_path_str);
     let video_file_name = video_file_path
         .file_stem()
         .and_then(|s| s.to_str())
         .unwrap_or(&torrent_name)
         .to_string();
 
-    let movie_hash = if video_file_path.exists() {
-        opensub_client::compute_hash(&video_file_path)
+    Ok(VideoFile {
+        path: video_file_path,
+        name: video_file_name,
+        download_dir,
+    })
+}
+
+/// Search OpenSubtitles and score every result, best first.
+async fn scored_candidates(
+    auth: &opensub_client::Auth,
+    video: &VideoFile,
+    languages: &[String],
+) -> Result<Vec<SubtitleCandidate>> {
+    let movie_hash = if video.path.exists() {
+        opensub_client::compute_hash(&video.path)
     } else {
         None
     };
 
     info!(
         "Searching subtitles for '{}' (languages: {:?}, hash: {:?})",
-        video_file_name, languages, movie_hash
+        video.name, languages, movie_hash
     );
 
     // Parse video file metadata for scoring
-    let video_info = media_info::parse(&video_file_name);
+    let video_info = media_info::parse(&video.name);
 
-    // OpenSubtitles requires an API key
-    if api_key.is_empty() {
-        return Err(WhenThenError::OpenSubtitles(
-            "OpenSubtitles API key not configured. Add your API key in Settings to enable subtitle search.".into()
-        ));
-    }
+    let results = opensub_client::search(auth, languages, &video.name, movie_hash.as_deref()).await?;
+
+    let mut candidates: Vec<_> = results
+        .into_iter()
+        .map(|r| {
+            let sub_info = media_info::parse(&r.file_name);
+            SubtitleCandidate {
+                score: subtitle_scorer::score_infos(&video_info, &sub_info),
+                file_id: r.file_id,
+                file_name: r.file_name,
+                language: r.language,
+                download_count: r.download_count,
+                ratings: r.ratings,
+                moviehash_match: r.moviehash_match,
+            }
+        })
+        .collect();
 
-    let (original_name, content, selected_lang) = search_opensubtitles(
-        &api_key,
-        &languages,
-        &video_file_name,
-        movie_hash.as_deref(),
-        &video_info,
-    ).await?;
+    // Sort by score descending, then by download count as tiebreaker
+    candidates.sort_by(|a, b| {
+        b.score.partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then(b.download_count.cmp(&a.download_count))
+    });
 
-    // Determine output path alongside the video file
-    let extension = original_name
+    Ok(candidates)
+}
+
+/// Write the subtitle alongside the video file.
+fn save_subtitle(
+    video: &VideoFile,
+    language: &str,
+    downloaded: opensub_client::DownloadedSubtitle,
+) -> Result<SubtitleDownloadResult> {
+    let extension = downloaded.file_name
         .rsplit('.')
         .next()
         .unwrap_or("srt");
 
-    let subtitle_filename = format!("{}.{}.{}", video_file_name, selected_lang, extension);
-    let output_dir = video_file_path
+    let subtitle_filename = format!("{}.{}.{}", video.name, language, extension);
+    let output_dir = video.path
         .parent()
         .map(|p| p.to_path_buf())
-        .unwrap_or_else(|| PathBuf::from(&download_dir));
+        .unwrap_or_else(|| PathBuf::from(&video.download_dir));
 
     if !output_dir.exists() {
         std::fs::create_dir_all(&output_dir)
@@ -122,7 +338,7 @@ pub async fn search_and_download(
     }
 
     let output_path = output_dir.join(&subtitle_filename);
-    std::fs::write(&output_path, &content)
+    std::fs::write(&output_path, &downloaded.content)
         .map_err(|e| WhenThenError::Internal(format!("Failed to write subtitle file: {e}")))?;
 
     info!("Subtitle saved to: {}", output_path.display());
@@ -130,49 +346,28 @@ pub async fn search_and_download(
     Ok(SubtitleDownloadResult {
         file_name: subtitle_filename,
         file_path: output_path.to_string_lossy().to_string(),
+        quota: Some(downloaded.quota),
+        source: SubtitleSource::OpenSubtitles,
+        language: language.to_string(),
     })
 }
 
-/// Search OpenSubtitles and return best match using scoring.
-async fn search_opensubtitles(
-    api_key: &str,
-    languages: &[String],
-    video_file_name: &str,
-    movie_hash: Option<&str>,
-    video_info: &crate::models::MediaInfo,
-) -> Result<(String, Vec<u8>, String)> {
-    let results = opensub_client::search(api_key, languages, video_file_name, movie_hash).await?;
-
-    if results.is_empty() {
-        return Err(WhenThenError::OpenSubtitles(format!(
-            "No subtitles found for '{}'",
-            video_file_name
-        )));
-    }
-
-    // Score each result and pick the best
-    let mut scored: Vec<_> = results
-        .iter()
-        .map(|r| {
-            let sub_info = media_info::parse(&r.file_name);
-            let score = subtitle_scorer::score_infos(video_info, &sub_info);
-            (r, score)
-        })
-        .collect();
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    // Sort by score descending, then by download count as tiebreaker
-    scored.sort_by(|a, b| {
-        b.1.partial_cmp(&a.1)
-            .unwrap_or(std::cmp::Ordering::Equal)
-            .then(b.0.download_count.cmp(&a.0.download_count))
-    });
-
-    let best = scored[0].0;
-    info!(
-        "Selected subtitle: {} (language: {}, score: {:.2}, downloads: {})",
-        best.file_name, best.language, scored[0].1, best.download_count
-    );
+    #[test]
+    fn test_subtitle_language() {
+        assert_eq!(subtitle_language("Movie.2024.1080p.en"), ("Movie.2024.1080p", "en".to_string()));
+        assert_eq!(subtitle_language("Movie.pt-BR"), ("Movie", "pt-br".to_string()));
+        assert_eq!(subtitle_language("Movie.2024.1080p.BluRay"), ("Movie.2024.1080p.BluRay", "unknown".to_string()));
+        assert_eq!(subtitle_language("2_English"), ("2_English", "unknown".to_string()));
+        assert_eq!(subtitle_language("Movie.2024.WEB-DL"), ("Movie.2024.WEB-DL", "unknown".to_string()));
+        assert_eq!(subtitle_language("Movie.es.sdh"), ("Movie", "es".to_string()));
 
-    let (original_name, content) = opensub_client::download(api_key, best.file_id).await?;
-    Ok((original_name, content, best.language.clone()))
+        let requested = vec!["en".to_string(), "es".to_string()];
+        assert!(language_requested("eng", &requested));
+        assert!(language_requested("es", &requested));
+        assert!(!language_requested("fr", &requested));
+    }
 }
@@ -0,0 +1,213 @@
+// Preview frames for video files, grabbed with ffmpeg when it's installed.
+//
+// Thumbnails are made on request, at most two at a time, and kept under
+// app_data_dir/thumbs as `<info_hash>_<file_index>.jpg` so they survive id
+// changes and restarts. Without ffmpeg every request reports unavailable.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use tokio::sync::Semaphore;
+use tracing::{info, warn};
+
+use crate::errors::{Result, WhenThenError};
+use crate::models::{ThumbnailInfo, ThumbnailStatus};
+use crate::services::{extractor, subtitle_search, torrent_engine};
+use crate::state::AppState;
+
+/// ffmpeg processes allowed at once.
+const MAX_CONCURRENT: usize = 2;
+
+/// Seek positions tried in order; the first skips black intro frames, the
+/// second catches clips shorter than that.
+const SEEK_POSITIONS: [&str; 2] = ["10", "0"];
+
+/// Thumbnail width in pixels; height follows the aspect ratio.
+const WIDTH: u32 = 320;
+
+static FFMPEG: OnceLock<Option<PathBuf>> = OnceLock::new();
+
+/// Installed ffmpeg, looked up once per run.
+fn ffmpeg() -> Option<&'static Path> {
+    FFMPEG
+        .get_or_init(|| {
+            let found = extractor::find_tool(&["ffmpeg"]);
+            match &found {
+                Some(path) => info!("Thumbnails will use {}", path.display()),
+                None => info!("ffmpeg not found, thumbnails disabled"),
+            }
+            found
+        })
+        .as_deref()
+}
+
+pub struct Thumbnails {
+    dir: OnceLock<PathBuf>,
+    permits: Arc<Semaphore>,
+    /// Keys queued or being generated
+    in_flight: Mutex<HashSet<String>>,
+    /// Keys ffmpeg couldn't get a frame from, so they aren't retried each request
+    failed: Mutex<HashSet<String>>,
+}
+
+impl Default for Thumbnails {
+    fn default() -> Self {
+        Self {
+            dir: OnceLock::new(),
+            permits: Arc::new(Semaphore::new(MAX_CONCURRENT)),
+            in_flight: Mutex::new(HashSet::new()),
+            failed: Mutex::new(HashSet::new()),
+        }
+    }
+}
+
+fn key(info_hash: &str, file_index: usize) -> String {
+    format!("{info_hash}_{file_index}")
+}
+
+impl Thumbnails {
+    /// Where thumbnails are written. Called once during setup.
+    pub fn set_dir(&self, dir: PathBuf) {
+        let _ = self.dir.set(dir);
+    }
+
+    /// Path of a file's thumbnail, whether or not it exists yet.
+    pub fn path_for(&self, info_hash: &str, file_index: usize) -> Option<PathBuf> {
+        self.dir.get().map(|dir| dir.join(format!("{}.jpg", key(info_hash, file_index))))
+    }
+}
+
+fn ready(url: String) -> ThumbnailInfo {
+    ThumbnailInfo {
+        status: ThumbnailStatus::Ready,
+        url: Some(url),
+        reason: None,
+    }
+}
+
+fn generating() -> ThumbnailInfo {
+    ThumbnailInfo {
+        status: ThumbnailStatus::Generating,
+        url: None,
+        reason: None,
+    }
+}
+
+fn unavailable(reason: &str) -> ThumbnailInfo {
+    ThumbnailInfo {
+        status: ThumbnailStatus::Unavailable,
+        url: None,
+        reason: Some(reason.to_string()),
+    }
+}
+
+/// The thumbnail's URL if it exists, otherwise start making it in the
+/// background and report where things stand.
+pub async fn request(state: &AppState, torrent_id: usize, file_index: usize) -> Result<ThumbnailInfo> {
+    let Some(ffmpeg) = ffmpeg() else {
+        return Ok(unavailable("ffmpeg is not installed"));
+    };
+
+    let session = state
+        .torrent_session
+        .read()
+        .await
+        .clone()
+        .ok_or(WhenThenError::SessionNotReady)?;
+    let internal_id = torrent_engine::resolve_id(state, torrent_id).await;
+    let handle = session
+        .get(librqbit::api::TorrentIdOrHash::Id(internal_id))
+        .ok_or(WhenThenError::TorrentNotFound(torrent_id))?;
+    let info_hash = handle.info_hash().as_string();
+
+    let thumbnails = state.thumbnails.clone();
+    let Some(output) = thumbnails.path_for(&info_hash, file_index) else {
+        return Ok(unavailable("No thumbnail directory"));
+    };
+    if output.is_file() {
+        let url = format!(
+            "{}/thumb/{}/{}?token={}",
+            state.media_server.base_url(),
+            torrent_id,
+            file_index,
+            state.media_server.stream_token
+        );
+        return Ok(ready(url));
+    }
+
+    let key = key(&info_hash, file_index);
+    if thumbnails.failed.lock().unwrap_or_else(|e| e.into_inner()).contains(&key) {
+        return Ok(unavailable("No frame could be read from this file"));
+    }
+    if thumbnails.in_flight.lock().unwrap_or_else(|e| e.into_inner()).contains(&key) {
+        return Ok(generating());
+    }
+
+    let video = subtitle_search::locate_video(state, torrent_id, file_index).await?;
+    let is_video = mime_guess::from_path(&video.path)
+        .first_raw()
+        .is_some_and(|mime| mime.starts_with("video/"));
+    if !is_video {
+        return Ok(unavailable("Not a video file"));
+    }
+    let readiness = torrent_engine::stream_readiness(&handle, file_index)
+        .ok_or_else(|| WhenThenError::FileNotFound(format!("File index {} not found", file_index)))?;
+    if !readiness.ready || !video.path.is_file() {
+        return Ok(unavailable("Not enough of the file is downloaded yet"));
+    }
+
+    // Checked again under the lock in case another request got here first
+    if !thumbnails.in_flight.lock().unwrap_or_else(|e| e.into_inner()).insert(key.clone()) {
+        return Ok(generating());
+    }
+
+    tauri::async_runtime::spawn(async move {
+        let Ok(_permit) = thumbnails.permits.clone().acquire_owned().await else {
+            return;
+        };
+        let source = video.path;
+        let target = output.clone();
+        let result = tokio::task::spawn_blocking(move || generate(ffmpeg, &source, &target)).await;
+        match result {
+            Ok(Ok(())) => info!("Generated thumbnail {}", output.display()),
+            Ok(Err(e)) => {
+                warn!("Thumbnail for {} failed: {}", key, e);
+                thumbnails.failed.lock().unwrap_or_else(|e| e.into_inner()).insert(key.clone());
+            }
+            Err(e) => warn!("Thumbnail task for {} failed: {}", key, e),
+        }
+        thumbnails.in_flight.lock().unwrap_or_else(|e| e.into_inner()).remove(&key);
+    });
+
+    Ok(generating())
+}
+
+/// Grab one scaled frame into `output`. Written under a temp name and
+/// renamed, so the media server never serves half a JPEG.
+fn generate(ffmpeg: &Path, video: &Path, output: &Path) -> std::result::Result<(), String> {
+    if let Some(dir) = output.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let temp = output.with_extension("tmp.jpg");
+    let scale = format!("scale={WIDTH}:-2");
+
+    let mut last_error = String::new();
+    for seek in SEEK_POSITIONS {
+        let result = Command::new(ffmpeg)
+            .args(["-nostdin", "-v", "error", "-ss", seek, "-i"])
+            .arg(video)
+            .args(["-frames:v", "1", "-vf", &scale, "-y"])
+            .arg(&temp)
+            .output()
+            .map_err(|e| e.to_string())?;
+        // Seeking past the end exits cleanly without writing anything
+        if result.status.success() && temp.is_file() {
+            return std::fs::rename(&temp, output).map_err(|e| e.to_string());
+        }
+        last_error = String::from_utf8_lossy(&result.stderr).trim().to_string();
+    }
+    let _ = std::fs::remove_file(&temp);
+    Err(if last_error.is_empty() { "ffmpeg produced no frame".into() } else { last_error })
+}
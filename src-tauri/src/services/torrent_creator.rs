@@ -0,0 +1,432 @@
+// Make .torrent files from local files and folders. Pieces are hashed on a
+// blocking thread; the result is written where the user asked and added to
+// the session pointing at the source, so it seeds in place without copying.
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use sha1::{Digest, Sha1};
+use tauri::{AppHandle, Emitter};
+use tracing::info;
+
+use crate::errors::{Result, WhenThenError};
+use crate::models::{TorrentAddOptions, TorrentCreated};
+use crate::services::torrent_engine::{self, expand_path};
+use crate::services::torrent_outputs;
+use crate::state::AppState;
+
+/// Minimum time between progress events.
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(500);
+
+const MIN_PIECE_SIZE: u64 = 16 * 1024;
+const MAX_PIECE_SIZE: u64 = 16 * 1024 * 1024;
+
+/// Automatic piece sizes grow until the torrent has about this many pieces.
+const TARGET_PIECES: u64 = 1500;
+
+#[derive(Debug, Clone, Serialize)]
+struct CreateProgress {
+    path: String,
+    hashed_bytes: u64,
+    total_bytes: u64,
+}
+
+/// What goes into the torrent besides the files.
+#[derive(Debug, Clone, Default)]
+pub struct CreateOptions {
+    /// The first is the main announce URL
+    pub trackers: Vec<String>,
+    /// Picked from the total size when None
+    pub piece_size: Option<u64>,
+    pub private: bool,
+    pub comment: Option<String>,
+    pub created_by: Option<String>,
+    /// Unix seconds
+    pub creation_date: Option<i64>,
+}
+
+/// A file to include, with its path inside the torrent.
+struct SourceFile {
+    path: PathBuf,
+    /// Empty when the source is a single file
+    components: Vec<String>,
+    len: u64,
+}
+
+/// Smallest power of two from 16 KiB to 16 MiB that keeps the piece count
+/// near `TARGET_PIECES`.
+pub fn auto_piece_size(total_len: u64) -> u64 {
+    let mut size = MIN_PIECE_SIZE;
+    while size < MAX_PIECE_SIZE && total_len / size > TARGET_PIECES {
+        size *= 2;
+    }
+    size
+}
+
+/// Files under `root` in a fixed order, so the same folder always makes the
+/// same torrent. Hidden files and symlinks are left out.
+fn collect_files(root: &Path) -> io::Result<Vec<SourceFile>> {
+    let meta = std::fs::metadata(root)?;
+    if meta.is_file() {
+        return Ok(vec![SourceFile {
+            path: root.to_path_buf(),
+            components: Vec::new(),
+            len: meta.len(),
+        }]);
+    }
+    let mut files = Vec::new();
+    walk(root, &mut Vec::new(), &mut files)?;
+    files.sort_by(|a, b| a.components.cmp(&b.components));
+    Ok(files)
+}
+
+fn walk(dir: &Path, prefix: &mut Vec<String>, files: &mut Vec<SourceFile>) -> io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name().into_string().map_err(|name| {
+            io::Error::new(io::ErrorKind::InvalidData, format!("{name:?} isn't a UTF-8 file name"))
+        })?;
+        if name.starts_with('.') {
+            continue;
+        }
+        let kind = entry.file_type()?;
+        prefix.push(name);
+        if kind.is_dir() {
+            walk(&entry.path(), prefix, files)?;
+        } else if kind.is_file() {
+            files.push(SourceFile {
+                path: entry.path(),
+                components: prefix.clone(),
+                len: entry.metadata()?.len(),
+            });
+        }
+        prefix.pop();
+    }
+    Ok(())
+}
+
+/// SHA-1 of each piece over the files laid end to end, calling `progress`
+/// with the bytes hashed so far. None when `cancel` was set.
+fn hash_pieces(
+    files: &[SourceFile],
+    piece_size: u64,
+    cancel: &AtomicBool,
+    mut progress: impl FnMut(u64),
+) -> io::Result<Option<Vec<u8>>> {
+    let mut pieces = Vec::new();
+    let mut buf = vec![0u8; piece_size as usize];
+    let mut filled = 0;
+    let mut hashed = 0u64;
+
+    for file in files {
+        let mut reader = File::open(&file.path)?;
+        let mut remaining = file.len;
+        while remaining > 0 {
+            if cancel.load(Ordering::Relaxed) {
+                return Ok(None);
+            }
+            let want = (buf.len() - filled).min(remaining as usize);
+            reader.read_exact(&mut buf[filled..filled + want])?;
+            filled += want;
+            remaining -= want as u64;
+            hashed += want as u64;
+            if filled == buf.len() {
+                pieces.extend_from_slice(&Sha1::digest(&buf));
+                filled = 0;
+                progress(hashed);
+            }
+        }
+    }
+    if filled > 0 {
+        pieces.extend_from_slice(&Sha1::digest(&buf[..filled]));
+        progress(hashed);
+    }
+    Ok(Some(pieces))
+}
+
+/// Just enough bencode to write a metainfo file. Dictionary keys are sorted
+/// by the map, as the format requires.
+enum Bencode {
+    Int(i64),
+    Bytes(Vec<u8>),
+    List(Vec<Bencode>),
+    Dict(BTreeMap<&'static str, Bencode>),
+}
+
+impl Bencode {
+    fn str(value: &str) -> Self {
+        Bencode::Bytes(value.as_bytes().to_vec())
+    }
+
+    fn encode(&self, out: &mut Vec<u8>) {
+        match self {
+            Bencode::Int(n) => out.extend_from_slice(format!("i{n}e").as_bytes()),
+            Bencode::Bytes(bytes) => {
+                out.extend_from_slice(format!("{}:", bytes.len()).as_bytes());
+                out.extend_from_slice(bytes);
+            }
+            Bencode::List(items) => {
+                out.push(b'l');
+                items.iter().for_each(|item| item.encode(out));
+                out.push(b'e');
+            }
+            Bencode::Dict(entries) => {
+                out.push(b'd');
+                for (key, value) in entries {
+                    Bencode::str(key).encode(out);
+                    value.encode(out);
+                }
+                out.push(b'e');
+            }
+        }
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        self.encode(&mut out);
+        out
+    }
+}
+
+/// The .torrent file contents and its info hash in lowercase hex.
+fn metainfo(
+    name: &str,
+    files: &[SourceFile],
+    piece_size: u64,
+    pieces: Vec<u8>,
+    options: &CreateOptions,
+) -> (Vec<u8>, String) {
+    let mut info = BTreeMap::new();
+    info.insert("name", Bencode::str(name));
+    info.insert("piece length", Bencode::Int(piece_size as i64));
+    info.insert("pieces", Bencode::Bytes(pieces));
+    if options.private {
+        info.insert("private", Bencode::Int(1));
+    }
+    match files {
+        [single] if single.components.is_empty() => {
+            info.insert("length", Bencode::Int(single.len as i64));
+        }
+        _ => {
+            let list = files
+                .iter()
+                .map(|file| {
+                    let mut entry = BTreeMap::new();
+                    entry.insert("length", Bencode::Int(file.len as i64));
+                    entry.insert("path", Bencode::List(file.components.iter().map(|c| Bencode::str(c)).collect()));
+                    Bencode::Dict(entry)
+                })
+                .collect();
+            info.insert("files", Bencode::List(list));
+        }
+    }
+    let info = Bencode::Dict(info);
+    let info_hash = Sha1::digest(info.to_bytes()).iter().map(|b| format!("{b:02x}")).collect();
+
+    let trackers: Vec<&str> = options.trackers.iter().map(|t| t.trim()).filter(|t| !t.is_empty()).collect();
+    let mut root = BTreeMap::new();
+    if let Some(first) = trackers.first() {
+        root.insert("announce", Bencode::str(first));
+    }
+    if trackers.len() > 1 {
+        let tiers = trackers.iter().map(|t| Bencode::List(vec![Bencode::str(t)])).collect();
+        root.insert("announce-list", Bencode::List(tiers));
+    }
+    if let Some(comment) = options.comment.as_deref().filter(|c| !c.trim().is_empty()) {
+        root.insert("comment", Bencode::str(comment));
+    }
+    if let Some(created_by) = &options.created_by {
+        root.insert("created by", Bencode::str(created_by));
+    }
+    if let Some(date) = options.creation_date {
+        root.insert("creation date", Bencode::Int(date));
+    }
+    root.insert("info", info);
+
+    (Bencode::Dict(root).to_bytes(), info_hash)
+}
+
+/// Hash `source` into a .torrent at `destination` (or inside it, when it's a
+/// folder) and start seeding it. Emits torrent:create-progress while hashing.
+pub async fn create(
+    state: &AppState,
+    app_handle: &AppHandle,
+    source: String,
+    destination: String,
+    options: CreateOptions,
+) -> Result<TorrentCreated> {
+    let source_path = expand_path(&source);
+    let name = source_path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .ok_or_else(|| WhenThenError::InvalidInput(format!("Cannot share {source}")))?;
+    if !source_path.exists() {
+        return Err(WhenThenError::FileNotFound(source));
+    }
+    if let Some(size) = options.piece_size {
+        if !size.is_power_of_two() || !(MIN_PIECE_SIZE..=MAX_PIECE_SIZE).contains(&size) {
+            return Err(WhenThenError::InvalidInput(
+                "Piece size must be a power of two from 16 KiB to 16 MiB".into(),
+            ));
+        }
+    }
+    let mut torrent_path = expand_path(&destination);
+    if torrent_path.is_dir() {
+        torrent_path = torrent_path.join(format!("{name}.torrent"));
+    }
+
+    let cancel = Arc::new(AtomicBool::new(false));
+    {
+        let mut jobs = state.create_jobs.lock().await;
+        if jobs.contains_key(&source) {
+            return Err(WhenThenError::InvalidInput("A torrent is already being made from this path".into()));
+        }
+        jobs.insert(source.clone(), cancel.clone());
+    }
+
+    info!(source = %source_path.display(), "Creating torrent");
+    let app = app_handle.clone();
+    let progress_path = source.clone();
+    let root = source_path.clone();
+    let piece_size = options.piece_size;
+    let task = tokio::task::spawn_blocking(move || {
+        let read_error = |e: io::Error| WhenThenError::Internal(format!("Cannot read {}: {e}", root.display()));
+        let files = collect_files(&root).map_err(read_error)?;
+        let total_bytes: u64 = files.iter().map(|f| f.len).sum();
+        if total_bytes == 0 {
+            return Err(WhenThenError::InvalidInput(format!("{} has nothing to share", root.display())));
+        }
+        let piece_size = piece_size.unwrap_or_else(|| auto_piece_size(total_bytes));
+        let mut last_emit = Instant::now();
+        let pieces = hash_pieces(&files, piece_size, &cancel, |hashed_bytes| {
+            if hashed_bytes == total_bytes || last_emit.elapsed() >= PROGRESS_INTERVAL {
+                last_emit = Instant::now();
+                let _ = app.emit(
+                    "torrent:create-progress",
+                    CreateProgress { path: progress_path.clone(), hashed_bytes, total_bytes },
+                );
+            }
+        })
+        .map_err(read_error)?;
+        Ok(pieces.map(|pieces| (files, total_bytes, piece_size, pieces)))
+    })
+    .await;
+
+    state.create_jobs.lock().await.remove(&source);
+    let hashed = task.map_err(|e| WhenThenError::Internal(format!("Torrent creation task failed: {e}")))??;
+    let Some((files, total_bytes, piece_size, pieces)) = hashed else {
+        info!(source = %source_path.display(), "Torrent creation cancelled");
+        return Err(WhenThenError::InvalidInput("Torrent creation was cancelled".into()));
+    };
+
+    let piece_count = pieces.len() / 20;
+    let (bytes, info_hash) = metainfo(&name, &files, piece_size, pieces, &options);
+    std::fs::write(&torrent_path, bytes)
+        .map_err(|e| WhenThenError::Internal(format!("Cannot write {}: {e}", torrent_path.display())))?;
+    info!(path = %torrent_path.display(), %info_hash, piece_size, piece_count, "Torrent file written");
+
+    // librqbit puts files straight into an explicit output folder, so a
+    // folder seeds from itself and a single file from its parent
+    let seed_folder = if source_path.is_dir() {
+        source_path.clone()
+    } else {
+        source_path.parent().map(Path::to_path_buf).unwrap_or_default()
+    };
+    let torrent = torrent_engine::add_torrent_file(
+        state,
+        app_handle,
+        torrent_path.to_string_lossy().to_string(),
+        Some(TorrentAddOptions {
+            output_folder: Some(seed_folder.to_string_lossy().to_string()),
+            only_files: None,
+            add_paused: Some(false),
+            label: None,
+            allow_bad: false,
+        }),
+    )
+    .await?;
+    // The files are the user's own; cleanup must never delete them
+    torrent_outputs::record_external(app_handle, state, &torrent.info_hash).await;
+
+    Ok(TorrentCreated {
+        torrent_path: torrent_path.to_string_lossy().to_string(),
+        info_hash,
+        piece_size,
+        piece_count,
+        total_bytes,
+        torrent,
+    })
+}
+
+/// Stop hashing for `source`. Returns false when nothing is being made from it.
+pub async fn cancel(state: &AppState, source: &str) -> bool {
+    match state.create_jobs.lock().await.get(source) {
+        Some(flag) => {
+            flag.store(true, Ordering::Relaxed);
+            true
+        }
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn folders_hash_to_the_golden_torrent() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().to_path_buf();
+        let root = dir.join("share");
+        std::fs::create_dir_all(root.join("dir")).unwrap();
+        std::fs::write(root.join("dir").join("b.txt"), b"world!").unwrap();
+        std::fs::write(root.join("a.txt"), b"hello").unwrap();
+        std::fs::write(root.join(".DS_Store"), b"junk").unwrap();
+
+        let files = collect_files(&root).unwrap();
+        let order: Vec<_> = files.iter().map(|f| f.components.join("/")).collect();
+        assert_eq!(order, vec!["a.txt", "dir/b.txt"]);
+
+        let pieces = hash_pieces(&files, MIN_PIECE_SIZE, &AtomicBool::new(false), |_| {}).unwrap().unwrap();
+        let options = CreateOptions {
+            trackers: vec!["http://tracker.example/announce".into(), " udp://backup.example:6969 ".into(), "".into()],
+            private: true,
+            comment: Some("golden".into()),
+            ..Default::default()
+        };
+        let (bytes, info_hash) = metainfo("share", &files, MIN_PIECE_SIZE, pieces, &options);
+        assert_eq!(bytes, include_bytes!("../../tests/fixtures/torrents/share.torrent"));
+        assert_eq!(info_hash, "5fdf659336849238fa0fc28ab66c45427a016490");
+    }
+
+    #[test]
+    fn pieces_span_file_boundaries_and_the_last_one_is_short() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().to_path_buf();
+        std::fs::write(dir.join("a"), b"abcde").unwrap();
+        std::fs::write(dir.join("b"), b"fghij").unwrap();
+
+        let files = collect_files(&dir).unwrap();
+        let mut seen = Vec::new();
+        let pieces = hash_pieces(&files, 4, &AtomicBool::new(false), |n| seen.push(n)).unwrap().unwrap();
+        let chunks: [&[u8]; 3] = [b"abcd", b"efgh", b"ij"];
+        let expected: Vec<u8> = chunks.iter().flat_map(|c| Sha1::digest(c).to_vec()).collect();
+        assert_eq!(pieces, expected);
+        assert_eq!(seen, vec![4, 8, 10]);
+        assert!(hash_pieces(&files, 4, &AtomicBool::new(true), |_| {}).unwrap().is_none());
+    }
+
+    #[test]
+    fn piece_size_grows_with_the_total() {
+        assert_eq!(auto_piece_size(0), MIN_PIECE_SIZE);
+        assert_eq!(auto_piece_size(700 * 1024 * 1024), 512 * 1024);
+        assert_eq!(auto_piece_size(4 * 1024 * 1024 * 1024), 4 * 1024 * 1024);
+        assert_eq!(auto_piece_size(u64::MAX), MAX_PIECE_SIZE);
+    }
+}
@@ -1,3 +1,4 @@
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::sync::Arc;
 use std::num::NonZeroU32;
 use std::path::PathBuf;
@@ -7,14 +8,20 @@ use librqbit::{
     dht::PersistentDhtConfig,
     limits::LimitsConfig,
 };
-use tauri::{AppHandle, Emitter};
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_store::StoreExt;
 use tracing::{info, debug, warn};
 
+use crate::commands::automation::{run_completion_action, CompletionPayload};
 use crate::errors::{WhenThenError, Result};
 use crate::models::{
-    AppConfig, TorrentAddedResponse, TorrentFileInfo, TorrentSummary, TorrentDetails,
-    TorrentState, TorrentAddOptions,
+    AppConfig, CompletionAction, FileOrderStatus, StreamReadiness, TorrentAddedResponse, TorrentFileInfo, TorrentSummary,
+    TorrentDetails, TorrentState, TorrentAddOptions, TorrentPeer, TorrentPeers, TorrentTracker, TorrentsDelta,
 };
+use crate::services::media_server::MediaServerHandle;
+use crate::services::file_order::{self, FileOrder};
+use crate::services::torrent_ids::TorrentIdMap;
+use crate::services::{display_names, event_bus, magnet, rss, torrent_labels, torrent_outputs, web_seeds};
 use crate::state::AppState;
 
 fn speed_limit(bps: u64) -> Option<NonZeroU32> {
@@ -34,6 +41,19 @@ pub fn expand_path(path: &str) -> PathBuf {
     PathBuf::from(path)
 }
 
+/// Ports librqbit tries after the configured listen port.
+const LISTEN_PORT_SPAN: u16 = 20;
+
+/// The ports librqbit may listen on, as the half-open range it's given.
+pub fn listen_ports(first: u16) -> std::ops::Range<u16> {
+    first..first.saturating_add(LISTEN_PORT_SPAN)
+}
+
+/// The last port librqbit may listen on, for showing the range.
+pub fn last_listen_port(first: u16) -> u16 {
+    listen_ports(first).end.saturating_sub(1).max(first)
+}
+
 pub async fn init_session(config: &AppConfig, persistence_dir: PathBuf) -> Result<Arc<Session>> {
     let output_dir = if config.download_directory.is_empty() {
         dirs::download_dir().unwrap_or_else(|| dirs::home_dir().unwrap_or_default().join("Downloads"))
@@ -64,8 +84,9 @@ pub async fn init_session(config: &AppConfig, persistence_dir: PathBuf) -> Resul
                 folder: Some(persistence_dir.clone()),
             }),
             fastresume: true,
-            listen_port_range: Some(port..port + 20),
-            enable_upnp_port_forwarding: config.enable_upnp,
+            listen_port_range: Some(listen_ports(port)),
+            // Done by services::port_mapping, which reports the outcome and can be toggled live
+            enable_upnp_port_forwarding: false,
             ratelimits: LimitsConfig {
                 download_bps: speed_limit(config.max_download_speed),
                 upload_bps: speed_limit(config.max_upload_speed),
@@ -74,15 +95,34 @@ pub async fn init_session(config: &AppConfig, persistence_dir: PathBuf) -> Resul
         },
     )
     .await
-    .map_err(|e| WhenThenError::Torrent(format!("Failed to init torrent session: {e}")))?;
+    .map_err(|e| WhenThenError::torrent("Failed to init torrent session", e))?;
 
     info!(
         "Torrent session initialized — download dir: {}, persistence: {}, listen port: {}..{}, UPnP: {}",
-        output_dir_display, persistence_dir.display(), port, port + 20, config.enable_upnp
+        output_dir_display, persistence_dir.display(), port, last_listen_port(port), config.enable_upnp
     );
     Ok(session)
 }
 
+/// How long quitting waits for librqbit to flush fastresume state.
+const SESSION_SHUTDOWN_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Stop the torrent session so piece progress is persisted before exit.
+/// The session is taken out of AppState first, so the session monitor stops on its next tick.
+pub async fn shutdown_session(state: &AppState) {
+    let Some(session) = state.torrent_session.write().await.take() else {
+        return;
+    };
+
+    match tokio::time::timeout(SESSION_SHUTDOWN_TIMEOUT, session.stop()).await {
+        Ok(()) => info!("Torrent session stopped"),
+        Err(_) => warn!(
+            "Torrent session did not stop within {}s, exiting anyway",
+            SESSION_SHUTDOWN_TIMEOUT.as_secs()
+        ),
+    }
+}
+
 /// Safe to call on a running session.
 pub fn apply_speed_limits(session: &Session, download_bps: u64, upload_bps: u64) {
     session.ratelimits.set_download_bps(speed_limit(download_bps));
@@ -106,148 +146,219 @@ pub async fn sync_restored_torrents(
         torrents.map(|(id, h)| (id, h.clone())).collect::<Vec<_>>()
     });
 
-    let mut summaries = Vec::new();
+    let mut restored = Vec::new();
 
     for (id, handle) in torrent_list {
         let name = handle.name().unwrap_or_else(|| "Unknown".to_string());
         let stats = handle.stats();
 
-        // Don't restore completed torrents
-        if stats.finished {
+        // Don't restore completed downloads, unless only part of a file order is done.
+        // Created and imported torrents are there to seed, so they always stay.
+        let info_hash = handle.info_hash().as_string();
+        let ordered = state.file_orders.read().await.get(&info_hash).is_some();
+        let external = state.torrent_outputs.read().await.is_external(&info_hash);
+        if stats.finished && ordered {
+            tokio::spawn(advance_file_order(app_handle.clone(), id, id));
+        } else if stats.finished && !external {
             info!(torrent_id = id, name = %name, "Removing completed torrent from session");
             let _ = session
                 .delete(librqbit::api::TorrentIdOrHash::Id(id), false)
                 .await;
+            torrent_labels::set_label(app_handle, state, &info_hash, None).await;
+            display_names::set_name(app_handle, state, &info_hash, None).await;
+            torrent_outputs::forget(app_handle, state, &info_hash).await;
             continue;
         }
 
+        // Added before output folders were recorded, so it went to the default
+        if !state.torrent_outputs.read().await.contains(&info_hash) {
+            let folder = torrent_outputs::default_folder(state).await;
+            torrent_outputs::record(app_handle, state, &info_hash, &folder).await;
+        }
+
         {
+            let shown = display_name(state, &handle).await;
             let mut names = state.torrent_names.write().await;
-            names.entry(id).or_insert_with(|| name.clone());
+            names.entry(id).or_insert(shown);
         }
 
-        let state_val = match stats.state {
-            librqbit::TorrentStatsState::Paused => TorrentState::Paused,
-            librqbit::TorrentStatsState::Error => TorrentState::Error,
-            librqbit::TorrentStatsState::Initializing => TorrentState::Initializing,
-            _ => TorrentState::Downloading,
-        };
+        watch_progress(state, id).await;
+        restored.push((id, handle));
+    }
 
-        spawn_progress_emitter(state, app_handle.clone(), id);
+    restore_queue(state, app_handle, &session).await;
 
-        let total_bytes = stats.total_bytes;
-        let downloaded = stats.progress_bytes;
-        let progress = if total_bytes > 0 {
-            downloaded as f64 / total_bytes as f64
-        } else {
-            0.0
-        };
+    // Same summaries as the progress loop sends, so nothing jumps on its first tick
+    let names = state.torrent_names.read().await;
+    let queue = state.download_queue.lock().await;
+    let ids = state.torrent_ids.read().await;
+    let labels = state.torrent_labels.read().await;
+    let mut speeds = state.download_speeds.lock().await;
+    let summaries = restored
+        .iter()
+        .map(|(id, handle)| summarize(*id, handle, &names, &queue, &ids, &labels, &mut speeds))
+        .collect();
 
-        // librqbit reports speeds in Mbps; convert to bytes/sec
-        let (dl_speed, ul_speed, peers) = if let Some(ref live) = stats.live {
-            (
-                (live.download_speed.mbps * 1024.0 * 1024.0) as u64,
-                (live.upload_speed.mbps * 1024.0 * 1024.0) as u64,
-                live.snapshot.peer_stats.live,
-            )
-        } else {
-            (0, 0, 0)
-        };
+    Ok(summaries)
+}
 
-        let file_count = stats.file_progress.len();
+/// Free bytes on the volume holding `dir`. The folder may not exist yet,
+/// so the nearest existing ancestor is measured instead.
+pub(crate) fn available_space(dir: &std::path::Path) -> Option<u64> {
+    let existing = dir.ancestors().find(|p| p.exists())?;
+    fs4::available_space(existing).ok()
+}
 
-        summaries.push(TorrentSummary {
-            id,
-            name,
-            info_hash: handle.info_hash().as_string(),
-            state: state_val,
-            progress,
-            download_speed: dl_speed,
-            upload_speed: ul_speed,
-            peers_connected: peers,
-            total_bytes,
-            downloaded_bytes: downloaded,
-            file_count,
-        });
+/// Whether `available` covers `required` while leaving `reserve` bytes free.
+fn has_room(available: u64, required: u64, reserve: u64) -> bool {
+    available.saturating_sub(reserve) >= required
+}
+
+fn format_gb(bytes: u64) -> String {
+    format!("{:.1} GB", bytes as f64 / 1_000_000_000.0)
+}
+
+fn check_disk_space(dir: &std::path::Path, required: u64, reserve: u64) -> Result<()> {
+    let Some(available) = available_space(dir) else {
+        return Ok(()); // Can't measure; let the download fail on its own if it must
+    };
+    if has_room(available, required, reserve) {
+        return Ok(());
     }
+    Err(WhenThenError::Config(format!(
+        "Not enough disk space in {}: {} needed, {} available ({} kept free)",
+        dir.display(),
+        format_gb(required),
+        format_gb(available),
+        format_gb(reserve),
+    )))
+}
 
-    Ok(summaries)
+/// Refuse a .torrent add up front when its selected files won't fit.
+async fn preflight_disk_space(
+    state: &AppState,
+    session: &Session,
+    torrent: Vec<u8>,
+    output_folder: Option<String>,
+    only_files: Option<Vec<usize>>,
+) -> Result<()> {
+    let reserve = state.config.read().await.disk_space_reserve_bytes;
+
+    let list_opts = AddTorrentOptions {
+        list_only: true,
+        output_folder,
+        only_files: only_files.clone(),
+        ..Default::default()
+    };
+    // Invalid torrents are reported by the real add
+    let Ok(AddTorrentResponse::ListOnly(listed)) = session
+        .add_torrent(AddTorrent::TorrentFileBytes(torrent.into()), Some(list_opts))
+        .await
+    else {
+        return Ok(());
+    };
+
+    let required: u64 = listed
+        .info
+        .iter_file_details()
+        .map(|files| {
+            files
+                .enumerate()
+                .filter(|(idx, _)| only_files.as_ref().is_none_or(|only| only.contains(idx)))
+                .map(|(_, fi)| {
+                    let relative = fi.filename.to_string().unwrap_or_default();
+                    fi.len.saturating_sub(on_disk_len(&listed.output_folder, &relative))
+                })
+                .sum()
+        })
+        .unwrap_or(0);
+
+    check_disk_space(&listed.output_folder, required, reserve)
 }
 
-fn check_disk_space(download_dir: &str) -> Result<()> {
-    let path = std::path::Path::new(download_dir);
-    if !path.exists() {
-        return Ok(()); // Will be created later; skip check
+/// Bytes of a torrent file already in `folder`, e.g. when seeding data
+/// that's already there.
+fn on_disk_len(folder: &std::path::Path, relative: &str) -> u64 {
+    if relative.is_empty() {
+        return 0;
     }
-    // TODO: check available space when std::fs::available_space stabilizes
-    let _ = path;
-    Ok(())
+    std::fs::metadata(folder.join(relative)).map_or(0, |meta| meta.len())
 }
 
 use crate::models::PendingMagnet;
 
+/// Map librqbit's state to ours; queued torrents are paused in librqbit.
+fn torrent_state(finished: bool, state: &librqbit::TorrentStatsState, queued: bool) -> TorrentState {
+    if finished {
+        return TorrentState::Completed;
+    }
+    match state {
+        librqbit::TorrentStatsState::Paused if queued => TorrentState::Queued,
+        librqbit::TorrentStatsState::Paused => TorrentState::Paused,
+        librqbit::TorrentStatsState::Error => TorrentState::Error,
+        librqbit::TorrentStatsState::Initializing => TorrentState::Initializing,
+        _ => TorrentState::Downloading,
+    }
+}
+
 /// Parse a magnet URL to extract info hash and display name without blocking.
 pub fn parse_magnet_info(magnet_url: &str) -> PendingMagnet {
-    let mut info_hash = String::new();
-    let mut name = String::new();
-
-    // Parse query string from magnet URL
-    if let Some(query_start) = magnet_url.find('?') {
-        let query = &magnet_url[query_start + 1..];
-        for part in query.split('&') {
-            if let Some((key, value)) = part.split_once('=') {
-                match key {
-                    "xt" => {
-                        // Extract info hash from urn:btih:HASH
-                        if let Some(hash) = value.strip_prefix("urn:btih:") {
-                            info_hash = hash.to_string();
-                        }
-                    }
-                    "dn" => {
-                        // URL decode the display name
-                        name = urlencoding::decode(value)
-                            .map(|s| s.to_string())
-                            .unwrap_or_else(|_| value.to_string());
-                    }
-                    _ => {}
-                }
-            }
-        }
-    }
+    let (info_hash, name) = match magnet::parse(magnet_url) {
+        Ok(parsed) => (parsed.info_hash, parsed.name),
+        Err(_) => (String::new(), None),
+    };
 
     // Fallback if no name found
-    if name.is_empty() {
-        name = if info_hash.is_empty() {
+    let name = name.unwrap_or_else(|| {
+        if info_hash.is_empty() {
             "Unknown".to_string()
         } else {
-            format!("Magnet {}", &info_hash[..8.min(info_hash.len())])
-        };
-    }
+            format!("Magnet {}", &info_hash[..8])
+        }
+    });
 
     PendingMagnet { info_hash, name }
 }
 
-/// Reliable public trackers to inject into magnets for better peer discovery.
-const FALLBACK_TRACKERS: &[&str] = &[
-    "udp://tracker.opentrackr.org:1337/announce",
-    "udp://open.stealth.si:80/announce",
-    "udp://tracker.torrent.eu.org:451/announce",
-    "udp://tracker.bittor.pw:1337/announce",
-    "udp://public.popcorn-tracker.org:6969/announce",
-    "udp://tracker.dler.org:6969/announce",
-    "udp://exodus.desync.com:6969/announce",
-    "udp://open.demonii.com:1337/announce",
-];
-
-/// Inject fallback trackers into a magnet URL for better peer discovery.
-fn inject_fallback_trackers(magnet_url: &str) -> String {
+/// External id of the torrent with this info hash, if the session has it.
+pub async fn managed_torrent_id(state: &AppState, session: &Session, info_hash: &str) -> Option<usize> {
+    let id = session.with_torrents(|torrents| {
+        torrents
+            .filter(|(_, handle)| handle.info_hash().as_string().eq_ignore_ascii_case(info_hash))
+            .map(|(id, _)| id)
+            .next()
+    })?;
+    Some(state.torrent_ids.read().await.external(id))
+}
+
+/// Append `&tr=` parameters for trackers the magnet doesn't already list.
+fn append_trackers(magnet_url: &str, trackers: &[String]) -> String {
+    let existing: Vec<String> = magnet_url
+        .split_once('?')
+        .map(|(_, query)| {
+            query
+                .split('&')
+                .filter_map(|part| part.strip_prefix("tr="))
+                .map(|value| {
+                    urlencoding::decode(value)
+                        .map(|s| s.into_owned())
+                        .unwrap_or_else(|_| value.to_string())
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
     let mut result = magnet_url.to_string();
-    for tracker in FALLBACK_TRACKERS {
-        let encoded = urlencoding::encode(tracker);
-        let param = format!("&tr={}", encoded);
-        if !result.contains(&encoded.to_string()) {
-            result.push_str(&param);
+    let mut added: Vec<&str> = Vec::new();
+    for tracker in trackers {
+        let tracker = tracker.trim();
+        if tracker.is_empty() || existing.iter().any(|t| t == tracker) || added.contains(&tracker) {
+            continue;
         }
+        result.push(if result.contains('?') { '&' } else { '?' });
+        result.push_str("tr=");
+        result.push_str(&urlencoding::encode(tracker));
+        added.push(tracker);
     }
     result
 }
@@ -261,18 +372,26 @@ pub async fn add_magnet(
     let session = {
         let guard = state.torrent_session.read().await;
         guard.as_ref().ok_or_else(|| {
-            WhenThenError::Torrent("Torrent session not initialized".into())
+            WhenThenError::SessionNotReady
         })?.clone()
     };
 
-    let incomplete_dir = {
+    // Malformed links are left for librqbit to judge; this only catches ones marked bad
+    let allow_bad = options.as_ref().is_some_and(|opts| opts.allow_bad);
+    if let Some(info_hash) = magnet::info_hash(&magnet_url).filter(|_| !allow_bad) {
+        if let Some(bad) = rss::bad_item_for_hash(&state.rss_state, &info_hash).await {
+            return Err(WhenThenError::MarkedBad(bad.title));
+        }
+    }
+
+    let (incomplete_dir, default_trackers) = {
         let cfg = state.config.read().await;
-        let _ = check_disk_space(&cfg.download_directory);
-        if cfg.incomplete_directory.is_empty() {
+        let incomplete_dir = if cfg.incomplete_directory.is_empty() {
             None
         } else {
             Some(expand_path(&cfg.incomplete_directory).to_string_lossy().to_string())
-        }
+        };
+        (incomplete_dir, cfg.default_trackers.clone())
     };
 
     let (output_folder, only_files) = if let Some(ref opts) = options {
@@ -281,18 +400,21 @@ pub async fn add_magnet(
     } else {
         (None, None)
     };
+    let paused = starts_paused(state, options.as_ref()).await;
 
     let effective_output = output_folder.or(incomplete_dir);
+    let queue_it = !paused && at_capacity(state, &session).await;
 
     let add_opts = AddTorrentOptions {
-        output_folder: effective_output,
+        output_folder: effective_output.clone(),
         only_files,
+        paused: paused || queue_it,
         overwrite: true,
         ..Default::default()
     };
 
-    // Inject fallback trackers for better peer discovery
-    let magnet_url = inject_fallback_trackers(&magnet_url);
+    // Append default trackers for better peer discovery
+    let magnet_url = append_trackers(&magnet_url, &default_trackers);
     debug!("Adding magnet: {}", &magnet_url);
 
     let response = session
@@ -303,7 +425,7 @@ pub async fn add_magnet(
         .await
         .map_err(|e| {
             warn!("Failed to add magnet: {}", e);
-            WhenThenError::Torrent(format!("Failed to add magnet: {e}"))
+            WhenThenError::torrent("Failed to add magnet", e)
         })?;
 
     let (handle, is_new) = match response {
@@ -315,24 +437,31 @@ pub async fn add_magnet(
     };
 
     let id = handle.id();
-    let name = handle.name().unwrap_or_else(|| "Unknown".to_string());
+    let name = display_name(state, &handle).await;
     let info_hash = handle.info_hash().as_string();
 
     state.torrent_names.write().await.insert(id, name.clone());
 
-    let media_server_port = state.media_server.port;
-    let local_ip = get_local_ip();
-    let files = build_file_list(&handle, &local_ip, media_server_port);
+    let files = build_file_list(&handle, &state.media_server);
 
     let result = TorrentAddedResponse {
-        id,
+        id: state.torrent_ids.read().await.external(id),
         name,
         info_hash,
         files,
+        already_existed: !is_new,
     };
 
     if is_new {
-        spawn_progress_emitter(state, app_handle.clone(), id);
+        if queue_it {
+            enqueue(state, app_handle, id, false).await;
+        }
+        apply_label(state, app_handle, &result.info_hash, options.as_ref()).await;
+        record_output(state, app_handle, &result.info_hash, effective_output.as_deref()).await;
+        // A torrent added paused is watched from its first resume
+        if !paused {
+            watch_progress(state, id).await;
+        }
         app_handle
             .emit("torrent:added", &result)
             .unwrap_or_default();
@@ -352,7 +481,7 @@ pub async fn add_torrent_file(
     let session = {
         let guard = state.torrent_session.read().await;
         guard.as_ref().ok_or_else(|| {
-            WhenThenError::Torrent("Torrent session not initialized".into())
+            WhenThenError::SessionNotReady
         })?.clone()
     };
 
@@ -374,12 +503,17 @@ pub async fn add_torrent_file(
     } else {
         (None, None)
     };
+    let paused = starts_paused(state, options.as_ref()).await;
 
     let effective_output = output_folder.or(incomplete_dir);
+    preflight_disk_space(state, &session, file_content.clone(), effective_output.clone(), only_files.clone())
+        .await?;
+    let queue_it = !paused && at_capacity(state, &session).await;
 
     let add_opts = AddTorrentOptions {
-        output_folder: effective_output,
+        output_folder: effective_output.clone(),
         only_files,
+        paused: paused || queue_it,
         overwrite: true,
         ..Default::default()
     };
@@ -390,7 +524,7 @@ pub async fn add_torrent_file(
             Some(add_opts),
         )
         .await
-        .map_err(|e| WhenThenError::Torrent(format!("Failed to add torrent: {e}")))?;
+        .map_err(|e| WhenThenError::torrent("Failed to add torrent", e))?;
 
     let (handle, is_new) = match response {
         AddTorrentResponse::Added(_, handle) => (handle, true),
@@ -401,24 +535,31 @@ pub async fn add_torrent_file(
     };
 
     let id = handle.id();
-    let name = handle.name().unwrap_or_else(|| "Unknown".to_string());
+    let name = display_name(state, &handle).await;
     let info_hash = handle.info_hash().as_string();
 
     state.torrent_names.write().await.insert(id, name.clone());
 
-    let media_server_port = state.media_server.port;
-    let local_ip = get_local_ip();
-    let files = build_file_list(&handle, &local_ip, media_server_port);
+    let files = build_file_list(&handle, &state.media_server);
 
     let result = TorrentAddedResponse {
-        id,
+        id: state.torrent_ids.read().await.external(id),
         name,
         info_hash,
         files,
+        already_existed: !is_new,
     };
 
     if is_new {
-        spawn_progress_emitter(state, app_handle.clone(), id);
+        if queue_it {
+            enqueue(state, app_handle, id, false).await;
+        }
+        apply_label(state, app_handle, &result.info_hash, options.as_ref()).await;
+        record_output(state, app_handle, &result.info_hash, effective_output.as_deref()).await;
+        // A torrent added paused is watched from its first resume
+        if !paused {
+            watch_progress(state, id).await;
+        }
         app_handle
             .emit("torrent:added", &result)
             .unwrap_or_default();
@@ -440,16 +581,101 @@ pub async fn add_torrent_bytes(
     file_bytes: Vec<u8>,
     options: Option<TorrentAddOptions>,
 ) -> Result<TorrentAddedResponse> {
+    add_bytes(state, app_handle, file_bytes, options, true)
+        .await
+        .map(|(result, _, _)| result)
+}
+
+/// Longest an import waits for librqbit to hash-check the existing data.
+const IMPORT_CHECK_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30 * 60);
+
+/// Add a torrent whose data already sits in `save_path`, e.g. one seeded by
+/// another client. librqbit hash-checks the existing files instead of
+/// downloading them; this waits for that check so imports don't pile up on the
+/// disk. The bool is false when the torrent was already managed.
+pub async fn import_torrent_bytes(
+    state: &AppState,
+    app_handle: &AppHandle,
+    file_bytes: Vec<u8>,
+    save_path: String,
+) -> Result<(TorrentAddedResponse, bool)> {
+    let output_folder = import_folder(state, file_bytes.clone(), &save_path).await?;
+    let options = TorrentAddOptions {
+        output_folder: Some(output_folder),
+        only_files: None,
+        add_paused: Some(false),
+        label: None,
+        allow_bad: false,
+    };
+    // The data is already on disk, so the free space check would be wrong
+    let (result, handle, is_new) = add_bytes(state, app_handle, file_bytes, Some(options), false).await?;
+
+    if is_new {
+        torrent_outputs::record_external(app_handle, state, &result.info_hash).await;
+        let checked = tokio::time::timeout(IMPORT_CHECK_TIMEOUT, async {
+            while matches!(handle.stats().state, librqbit::TorrentStatsState::Initializing) {
+                tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+            }
+        })
+        .await;
+        if checked.is_err() {
+            return Err(WhenThenError::Torrent("Timed out waiting for the hash check".into()));
+        }
+        if let Some(error) = handle.stats().error {
+            return Err(WhenThenError::Torrent(error));
+        }
+    }
+
+    Ok((result, is_new))
+}
+
+/// Other clients save multi-file torrents in a folder named after the torrent
+/// inside the save path; librqbit takes an explicit output folder as is, so
+/// the name has to be joined here.
+async fn import_folder(state: &AppState, file_bytes: Vec<u8>, save_path: &str) -> Result<String> {
+    let session = {
+        let guard = state.torrent_session.read().await;
+        guard.as_ref().ok_or(WhenThenError::SessionNotReady)?.clone()
+    };
+    let list_opts = AddTorrentOptions {
+        list_only: true,
+        ..Default::default()
+    };
+    let listed = match session
+        .add_torrent(AddTorrent::TorrentFileBytes(file_bytes.into()), Some(list_opts))
+        .await
+        .map_err(|e| WhenThenError::torrent("Failed to read torrent", e))?
+    {
+        AddTorrentResponse::ListOnly(listed) => listed,
+        // Already managed: the real add reports it
+        _ => return Ok(save_path.to_string()),
+    };
+
+    let save_path = expand_path(save_path);
+    let name = listed.info.name.as_ref().map(|name| String::from_utf8_lossy(name.as_ref()).to_string());
+    let folder = match name {
+        Some(name) if listed.info.files.is_some() => save_path.join(name),
+        _ => save_path,
+    };
+    Ok(folder.to_string_lossy().to_string())
+}
+
+async fn add_bytes(
+    state: &AppState,
+    app_handle: &AppHandle,
+    file_bytes: Vec<u8>,
+    options: Option<TorrentAddOptions>,
+    check_space: bool,
+) -> Result<(TorrentAddedResponse, Arc<librqbit::ManagedTorrent>, bool)> {
     let session = {
         let guard = state.torrent_session.read().await;
         guard.as_ref().ok_or_else(|| {
-            WhenThenError::Torrent("Torrent session not initialized".into())
+            WhenThenError::SessionNotReady
         })?.clone()
     };
 
     let incomplete_dir = {
         let cfg = state.config.read().await;
-        let _ = check_disk_space(&cfg.download_directory);
         if cfg.incomplete_directory.is_empty() {
             None
         } else {
@@ -463,12 +689,19 @@ pub async fn add_torrent_bytes(
     } else {
         (None, None)
     };
+    let paused = starts_paused(state, options.as_ref()).await;
 
     let effective_output = output_folder.or(incomplete_dir);
+    if check_space {
+        preflight_disk_space(state, &session, file_bytes.clone(), effective_output.clone(), only_files.clone())
+            .await?;
+    }
+    let queue_it = !paused && at_capacity(state, &session).await;
 
     let add_opts = AddTorrentOptions {
-        output_folder: effective_output,
+        output_folder: effective_output.clone(),
         only_files,
+        paused: paused || queue_it,
         overwrite: true,
         ..Default::default()
     };
@@ -479,7 +712,7 @@ pub async fn add_torrent_bytes(
             Some(add_opts),
         )
         .await
-        .map_err(|e| WhenThenError::Torrent(format!("Failed to add torrent: {e}")))?;
+        .map_err(|e| WhenThenError::torrent("Failed to add torrent", e))?;
 
     let (handle, is_new) = match response {
         AddTorrentResponse::Added(_, handle) => (handle, true),
@@ -490,24 +723,31 @@ pub async fn add_torrent_bytes(
     };
 
     let id = handle.id();
-    let name = handle.name().unwrap_or_else(|| "Unknown".to_string());
+    let name = display_name(state, &handle).await;
     let info_hash = handle.info_hash().as_string();
 
     state.torrent_names.write().await.insert(id, name.clone());
 
-    let media_server_port = state.media_server.port;
-    let local_ip = get_local_ip();
-    let files = build_file_list(&handle, &local_ip, media_server_port);
+    let files = build_file_list(&handle, &state.media_server);
 
     let result = TorrentAddedResponse {
-        id,
+        id: state.torrent_ids.read().await.external(id),
         name,
         info_hash,
         files,
+        already_existed: !is_new,
     };
 
     if is_new {
-        spawn_progress_emitter(state, app_handle.clone(), id);
+        if queue_it {
+            enqueue(state, app_handle, id, false).await;
+        }
+        apply_label(state, app_handle, &result.info_hash, options.as_ref()).await;
+        record_output(state, app_handle, &result.info_hash, effective_output.as_deref()).await;
+        // A torrent added paused is watched from its first resume
+        if !paused {
+            watch_progress(state, id).await;
+        }
         app_handle
             .emit("torrent:added", &result)
             .unwrap_or_default();
@@ -515,10 +755,43 @@ pub async fn add_torrent_bytes(
         info!(id, "Torrent already managed, skipping torrent:added event");
     }
 
-    Ok(result)
+    Ok((result, handle, is_new))
+}
+
+/// Record where a newly added torrent downloads to. Without an explicit
+/// folder librqbit uses the session's, which is the download directory.
+async fn record_output(state: &AppState, app_handle: &AppHandle, info_hash: &str, output_folder: Option<&str>) {
+    let folder = match output_folder {
+        Some(folder) => PathBuf::from(folder),
+        None => expand_path(&state.config.read().await.download_directory),
+    };
+    torrent_outputs::record_added(app_handle, state, info_hash, &folder, output_folder.is_some()).await;
+}
+
+/// Output folder for re-adding a torrent, as librqbit resolved it when the
+/// torrent was first added.
+async fn readd_folder(state: &AppState, handle: &librqbit::ManagedTorrent) -> String {
+    let info_hash = handle.info_hash().as_string();
+    let folder = torrent_outputs::folder(state, &info_hash).await;
+    let flat = state.torrent_outputs.read().await.is_flat(&info_hash);
+    let multi_file = handle.with_metadata(|meta| meta.info.files.is_some()).unwrap_or(false);
+    let name = handle.name().unwrap_or_default();
+    torrent_outputs::add_folder(&folder, &name, multi_file, flat).to_string_lossy().to_string()
 }
 
+/// Label a newly added torrent per its add options.
+async fn apply_label(state: &AppState, app_handle: &AppHandle, info_hash: &str, options: Option<&TorrentAddOptions>) {
+    if let Some(label) = options.and_then(|opts| torrent_labels::normalize(opts.label.as_deref())) {
+        torrent_labels::set_label(app_handle, state, info_hash, Some(label)).await;
+    }
+}
+
+/// The session monitor's latest list; sampled here only before its first tick.
 pub async fn list_torrents(state: &AppState) -> Result<Vec<TorrentSummary>> {
+    if let Some(list) = state.torrent_snapshot.read().await.as_ref() {
+        return Ok(list.clone());
+    }
+
     let session = {
         let guard = state.torrent_session.read().await;
         match guard.as_ref() {
@@ -527,71 +800,40 @@ pub async fn list_torrents(state: &AppState) -> Result<Vec<TorrentSummary>> {
         }
     };
 
-    let mut summaries = Vec::new();
-    let names = state.torrent_names.read().await;
-
-    let torrent_list: Vec<_> = session.with_torrents(|torrents| {
-        torrents.map(|(id, h)| (id, h.clone())).collect::<Vec<_>>()
-    });
-
-    for (id, handle) in torrent_list {
-        let stats = handle.stats();
-        let name = names.get(&id).cloned()
-            .unwrap_or_else(|| handle.name().unwrap_or_else(|| "Unknown".to_string()));
-        let total_bytes = stats.total_bytes;
-        let downloaded = stats.progress_bytes;
-        let progress = if total_bytes > 0 {
-            downloaded as f64 / total_bytes as f64
-        } else {
-            0.0
-        };
+    Ok(sample_torrents(state, &session).await.into_iter().map(|(_, summary)| summary).collect())
+}
 
-        let (dl_speed, ul_speed, peers) = if let Some(ref live) = stats.live {
-            (
-                (live.download_speed.mbps * 1024.0 * 1024.0) as u64,
-                (live.upload_speed.mbps * 1024.0 * 1024.0) as u64,
-                live.snapshot.peer_stats.live,
-            )
-        } else {
-            (0, 0, 0)
-        };
+/// Combined download speed (bytes/s) and number of torrents still downloading.
+/// Only reads the session, so it's cheap enough to poll.
+pub async fn download_activity(state: &AppState) -> (u64, usize) {
+    let session = {
+        let guard = state.torrent_session.read().await;
+        match guard.as_ref() {
+            Some(s) => s.clone(),
+            None => return (0, 0),
+        }
+    };
 
-        let state_val = if stats.finished {
-            TorrentState::Completed
-        } else {
-            match stats.state {
-                librqbit::TorrentStatsState::Paused => TorrentState::Paused,
-                librqbit::TorrentStatsState::Error => TorrentState::Error,
-                librqbit::TorrentStatsState::Initializing => TorrentState::Initializing,
-                _ => TorrentState::Downloading,
+    session.with_torrents(|torrents| {
+        torrents.fold((0, 0), |(speed, count), (_, handle)| {
+            let stats = handle.stats();
+            match stats.live {
+                Some(ref live) if !stats.finished => (
+                    speed + (live.download_speed.mbps * 1024.0 * 1024.0) as u64,
+                    count + 1,
+                ),
+                _ => (speed, count),
             }
-        };
-
-        let file_count = stats.file_progress.len();
-
-        summaries.push(TorrentSummary {
-            id,
-            name,
-            info_hash: handle.info_hash().as_string(),
-            state: state_val,
-            progress,
-            download_speed: dl_speed,
-            upload_speed: ul_speed,
-            peers_connected: peers,
-            total_bytes,
-            downloaded_bytes: downloaded,
-            file_count,
-        });
-    }
-
-    Ok(summaries)
+        })
+    })
 }
 
 pub async fn get_torrent_details(state: &AppState, id: usize) -> Result<TorrentDetails> {
+    let id = resolve_id(state, id).await;
     let session = {
         let guard = state.torrent_session.read().await;
         guard.as_ref().ok_or_else(|| {
-            WhenThenError::Torrent("Torrent session not initialized".into())
+            WhenThenError::SessionNotReady
         })?.clone()
     };
 
@@ -621,27 +863,20 @@ pub async fn get_torrent_details(state: &AppState, id: usize) -> Result<TorrentD
         (0, 0, 0)
     };
 
-    let state_val = if stats.finished {
-        TorrentState::Completed
-    } else {
-        match stats.state {
-            librqbit::TorrentStatsState::Paused => TorrentState::Paused,
-            librqbit::TorrentStatsState::Error => TorrentState::Error,
-            librqbit::TorrentStatsState::Initializing => TorrentState::Initializing,
-            _ => TorrentState::Downloading,
-        }
-    };
+    let queued = state.download_queue.lock().await.contains(&id);
+    let state_val = torrent_state(stats.finished, &stats.state, queued);
 
-    let local_ip = get_local_ip();
-    let media_server_port = state.media_server.port;
-    let files = build_file_list(&handle, &local_ip, media_server_port);
+    let files = build_file_list(&handle, &state.media_server);
 
-    let output_folder = String::new(); // Session doesn't directly expose this
+    let info_hash = handle.info_hash().as_string();
+    let output_folder = torrent_outputs::folder(state, &info_hash).await.to_string_lossy().to_string();
+    let file_order = state.file_orders.read().await.get(&info_hash).map(FileOrder::status);
 
     Ok(TorrentDetails {
-        id,
+        id: state.torrent_ids.read().await.external(id),
         name,
-        info_hash: handle.info_hash().as_string(),
+        label: state.torrent_labels.read().await.get(&info_hash),
+        info_hash,
         state: state_val,
         progress,
         download_speed: dl_speed,
@@ -652,14 +887,17 @@ pub async fn get_torrent_details(state: &AppState, id: usize) -> Result<TorrentD
         file_count: files.len(),
         files,
         output_folder,
+        trackers: tracker_list(&handle),
+        file_order,
     })
 }
 
 pub async fn get_torrent_files(state: &AppState, id: usize) -> Result<Vec<TorrentFileInfo>> {
+    let id = resolve_id(state, id).await;
     let session = {
         let guard = state.torrent_session.read().await;
         guard.as_ref().ok_or_else(|| {
-            WhenThenError::Torrent("Torrent session not initialized".into())
+            WhenThenError::SessionNotReady
         })?.clone()
     };
 
@@ -667,16 +905,15 @@ pub async fn get_torrent_files(state: &AppState, id: usize) -> Result<Vec<Torren
         .get(librqbit::api::TorrentIdOrHash::Id(id))
         .ok_or(WhenThenError::TorrentNotFound(id))?;
 
-    let local_ip = get_local_ip();
-    let media_server_port = state.media_server.port;
-    Ok(build_file_list(&handle, &local_ip, media_server_port))
+    Ok(build_file_list(&handle, &state.media_server))
 }
 
-pub async fn pause_torrent(state: &AppState, id: usize) -> Result<()> {
+pub async fn pause_torrent(state: &AppState, app_handle: &AppHandle, id: usize) -> Result<()> {
+    let id = resolve_id(state, id).await;
     let session = {
         let guard = state.torrent_session.read().await;
         guard.as_ref().ok_or_else(|| {
-            WhenThenError::Torrent("Torrent session not initialized".into())
+            WhenThenError::SessionNotReady
         })?.clone()
     };
 
@@ -684,16 +921,24 @@ pub async fn pause_torrent(state: &AppState, id: usize) -> Result<()> {
         .get(librqbit::api::TorrentIdOrHash::Id(id))
         .ok_or(WhenThenError::TorrentNotFound(id))?;
 
-    session.pause(&handle).await
-        .map_err(|e| WhenThenError::Torrent(format!("Failed to pause: {e}")))?;
+    // A queued torrent is already paused; pausing just takes it out of the queue
+    if !dequeue(state, app_handle, id).await {
+        session.pause(&handle).await
+            .map_err(|e| WhenThenError::torrent("Failed to pause", e))?;
+    }
+    promote_queued(state, app_handle).await;
     Ok(())
 }
 
-pub async fn resume_torrent(state: &AppState, id: usize) -> Result<()> {
+/// Starts the torrent, or moves it to the front of the queue when all download slots are taken.
+pub async fn resume_torrent(state: &AppState, app_handle: &AppHandle, id: usize) -> Result<()> {
+    let id = resolve_id(state, id).await;
+    // Resuming by hand on battery overrides pause_on_battery for this torrent
+    state.power.user_resumed(id).await;
     let session = {
         let guard = state.torrent_session.read().await;
         guard.as_ref().ok_or_else(|| {
-            WhenThenError::Torrent("Torrent session not initialized".into())
+            WhenThenError::SessionNotReady
         })?.clone()
     };
 
@@ -701,21 +946,81 @@ pub async fn resume_torrent(state: &AppState, id: usize) -> Result<()> {
         .get(librqbit::api::TorrentIdOrHash::Id(id))
         .ok_or(WhenThenError::TorrentNotFound(id))?;
 
+    if at_capacity(state, &session).await {
+        enqueue(state, app_handle, id, true).await;
+        return Ok(());
+    }
+
+    dequeue(state, app_handle, id).await;
     session.unpause(&handle).await
-        .map_err(|e| WhenThenError::Torrent(format!("Failed to resume: {e}")))?;
+        .map_err(|e| WhenThenError::torrent("Failed to resume", e))?;
+    watch_progress(state, id).await;
     Ok(())
 }
 
+/// Tear down a live torrent's peer connections and tracker/DHT announcers and
+/// start them again. librqbit has no call to announce or drop peers on their
+/// own, so this pauses and unpauses the torrent, which also briefly stops
+/// transfers. Returns the handle and the number of live peers beforehand, or
+/// None when the torrent isn't actively running (paused, queued, finished).
+async fn restart_live(state: &AppState, id: usize) -> Result<Option<(Arc<librqbit::ManagedTorrent>, usize)>> {
+    let id = resolve_id(state, id).await;
+    let session = {
+        let guard = state.torrent_session.read().await;
+        guard.as_ref().ok_or_else(|| {
+            WhenThenError::SessionNotReady
+        })?.clone()
+    };
+
+    let handle = session
+        .get(librqbit::api::TorrentIdOrHash::Id(id))
+        .ok_or(WhenThenError::TorrentNotFound(id))?;
+
+    let stats = handle.stats();
+    let Some(live) = stats.live.as_ref().filter(|_| !stats.finished) else {
+        return Ok(None);
+    };
+    let peers = live.snapshot.peer_stats.live;
+
+    session.pause(&handle).await
+        .map_err(|e| WhenThenError::torrent("Failed to pause", e))?;
+    session.unpause(&handle).await
+        .map_err(|e| WhenThenError::torrent("Failed to resume", e))?;
+    Ok(Some((handle, peers)))
+}
+
+/// Announce to every tracker and query the DHT again right away. Returns the
+/// number of trackers announced to; 0 when the torrent isn't running.
+pub async fn reannounce_torrent(state: &AppState, id: usize) -> Result<usize> {
+    let Some((handle, _)) = restart_live(state, id).await? else {
+        return Ok(0);
+    };
+    let trackers = tracker_list(&handle).len();
+    info!(id, trackers, "Re-announced torrent");
+    Ok(trackers)
+}
+
+/// Drop all peer connections so fresh ones are made. Returns how many peers
+/// were connected; 0 when the torrent isn't running.
+pub async fn force_reconnect_torrent(state: &AppState, id: usize) -> Result<usize> {
+    let Some((_, peers)) = restart_live(state, id).await? else {
+        return Ok(0);
+    };
+    info!(id, peers, "Reconnected torrent peers");
+    Ok(peers)
+}
+
 /// Forces piece re-verification via delete + re-add.
 pub async fn recheck_torrent(
     state: &AppState,
     app_handle: &AppHandle,
     id: usize,
 ) -> Result<TorrentAddedResponse> {
+    let id = resolve_id(state, id).await;
     let session = {
         let guard = state.torrent_session.read().await;
         guard.as_ref().ok_or_else(|| {
-            WhenThenError::Torrent("Torrent session not initialized".into())
+            WhenThenError::SessionNotReady
         })?.clone()
     };
 
@@ -725,20 +1030,26 @@ pub async fn recheck_torrent(
 
     let torrent_bytes = handle
         .with_metadata(|m| m.torrent_bytes.clone())
-        .map_err(|e| WhenThenError::Torrent(format!("Cannot read torrent metadata: {e}")))?;
+        .map_err(|e| WhenThenError::torrent("Cannot read torrent metadata", e))?;
 
-    let name = handle.name().unwrap_or_else(|| "Unknown".to_string());
+    let name = display_name(state, &handle).await;
+    let trackers = readd_trackers(state, &handle).await;
+    let queued = state.download_queue.lock().await.contains(&id);
+    let output_folder = readd_folder(state, &handle).await;
 
     // Delete from session, keep files on disk
     session
         .delete(librqbit::api::TorrentIdOrHash::Id(id), false)
         .await
-        .map_err(|e| WhenThenError::Torrent(format!("Failed to delete torrent for recheck: {e}")))?;
+        .map_err(|e| WhenThenError::torrent("Failed to delete torrent for recheck", e))?;
 
     state.torrent_names.write().await.remove(&id);
 
     // Re-add with same bytes — librqbit will hash-check all pieces on init
     let add_opts = AddTorrentOptions {
+        output_folder: Some(output_folder),
+        trackers,
+        paused: queued,
         overwrite: true,
         ..Default::default()
     };
@@ -749,7 +1060,7 @@ pub async fn recheck_torrent(
             Some(add_opts),
         )
         .await
-        .map_err(|e| WhenThenError::Torrent(format!("Failed to re-add torrent for recheck: {e}")))?;
+        .map_err(|e| WhenThenError::torrent("Failed to re-add torrent for recheck", e))?;
 
     let new_handle = match response {
         AddTorrentResponse::Added(_, h) => h,
@@ -762,20 +1073,19 @@ pub async fn recheck_torrent(
     let new_id = new_handle.id();
     let info_hash = new_handle.info_hash().as_string();
 
-    state.torrent_names.write().await.insert(new_id, name.clone());
+    let external_id = track_readd(state, app_handle, id, new_id, &name).await;
 
-    let media_server_port = state.media_server.port;
-    let local_ip = get_local_ip();
-    let files = build_file_list(&new_handle, &local_ip, media_server_port);
+    let files = build_file_list(&new_handle, &state.media_server);
 
     let result = TorrentAddedResponse {
-        id: new_id,
+        id: external_id,
         name: name.clone(),
         info_hash,
         files,
+        already_existed: false,
     };
 
-    spawn_progress_emitter(state, app_handle.clone(), new_id);
+    watch_progress(state, new_id).await;
 
     #[derive(serde::Serialize, Clone)]
     struct TorrentRechecked {
@@ -785,239 +1095,1078 @@ pub async fn recheck_torrent(
     }
 
     app_handle
-        .emit("torrent:rechecked", &TorrentRechecked { old_id: id, new_id, name })
+        .emit("torrent:rechecked", &TorrentRechecked { old_id: external_id, new_id: external_id, name })
         .unwrap_or_default();
 
-    info!(old_id = id, new_id, "Torrent rechecked");
+    info!(external_id, old_id = id, new_id, "Torrent rechecked");
 
     Ok(result)
 }
 
-pub async fn delete_torrent(state: &AppState, id: usize, delete_files: bool) -> Result<()> {
+pub async fn delete_torrent(state: &AppState, app_handle: &AppHandle, id: usize, delete_files: bool) -> Result<()> {
+    let id = resolve_id(state, id).await;
     let session = {
         let guard = state.torrent_session.read().await;
         guard.as_ref().ok_or_else(|| {
-            WhenThenError::Torrent("Torrent session not initialized".into())
+            WhenThenError::SessionNotReady
         })?.clone()
     };
 
+    let info_hash = session
+        .get(librqbit::api::TorrentIdOrHash::Id(id))
+        .map(|handle| handle.info_hash().as_string());
+
     session
         .delete(librqbit::api::TorrentIdOrHash::Id(id), delete_files)
         .await
-        .map_err(|e| WhenThenError::Torrent(format!("Failed to delete torrent: {e}")))?;
+        .map_err(|e| WhenThenError::torrent("Failed to delete torrent", e))?;
 
     state.torrent_names.write().await.remove(&id);
+    state.torrent_ids.write().await.remove(id);
+    if let Some(info_hash) = info_hash {
+        torrent_labels::set_label(app_handle, state, &info_hash, None).await;
+        display_names::set_name(app_handle, state, &info_hash, None).await;
+        torrent_outputs::forget(app_handle, state, &info_hash).await;
+        if state.file_orders.write().await.remove(&info_hash).is_some() {
+            file_order::persist(app_handle, state).await;
+        }
+    }
+    dequeue(state, app_handle, id).await;
+    promote_queued(state, app_handle).await;
+    crate::tray::refresh_menu();
     Ok(())
 }
 
-fn build_file_list(
-    handle: &Arc<librqbit::ManagedTorrent>,
-    local_ip: &str,
-    port: u16,
-) -> Vec<TorrentFileInfo> {
-    let id = handle.id();
-    let mut files = Vec::new();
-
-    let file_infos: Vec<(String, u64)> = match handle.with_metadata(|meta| {
-        meta.info.iter_file_details()
-            .map(|iter| {
-                iter.map(|fi| {
-                    let path_str = fi.filename.to_string()
-                        .unwrap_or_else(|_| "<INVALID NAME>".to_string());
-                    (path_str, fi.len)
-                }).collect::<Vec<_>>()
-            })
-            .unwrap_or_default()
-    }) {
-        Ok(infos) => infos,
-        Err(_) => return files,
+/// Folder holding a torrent's data: its own folder for multi-file torrents,
+/// otherwise the directory its file is in.
+pub async fn torrent_folder(state: &AppState, id: usize) -> Result<PathBuf> {
+    let id = resolve_id(state, id).await;
+    let session = {
+        let guard = state.torrent_session.read().await;
+        guard.as_ref().ok_or_else(|| {
+            WhenThenError::SessionNotReady
+        })?.clone()
     };
 
-    for (idx, (path_str, length)) in file_infos.into_iter().enumerate() {
-        let name = path_str.rsplit('/').next().unwrap_or(&path_str).to_string();
-        let mime = mime_guess::from_path(&name).first_raw().map(String::from);
-        let is_playable = mime.as_ref().is_some_and(|m| {
-            m.starts_with("video/") || m.starts_with("audio/")
-        });
-        let stream_url = if is_playable {
-            Some(format!("http://{}:{}/torrent/{}/stream/{}", local_ip, port, id, idx))
-        } else {
-            None
-        };
+    let handle = session
+        .get(librqbit::api::TorrentIdOrHash::Id(id))
+        .ok_or(WhenThenError::TorrentNotFound(id))?;
+    let name = handle.name().unwrap_or_default();
 
-        files.push(TorrentFileInfo {
-            index: idx,
-            name,
-            path: path_str,
-            length,
-            is_playable,
-            mime_type: mime,
-            stream_url,
-        });
-    }
+    let base = torrent_outputs::folder(state, &handle.info_hash().as_string()).await;
+    let own = base.join(&name);
+    Ok(if !name.is_empty() && own.is_dir() { own } else { base })
+}
 
-    files
+/// The name a torrent is shown under: the user's, or the one in its metadata.
+pub async fn display_name(state: &AppState, handle: &librqbit::ManagedTorrent) -> String {
+    let custom = state.display_names.read().await.get(&handle.info_hash().as_string());
+    custom.unwrap_or_else(|| handle.name().unwrap_or_else(|| "Unknown".to_string()))
 }
 
-fn spawn_progress_emitter(state: &AppState, app_handle: AppHandle, torrent_id: usize) {
-    let session = state.torrent_session.clone();
-    let config = state.config.clone();
+/// Show a torrent under `name`; a blank name goes back to the metadata name.
+/// Files on disk keep their names. Returns the name now shown.
+pub async fn set_display_name(state: &AppState, app_handle: &AppHandle, id: usize, name: &str) -> Result<String> {
+    let id = resolve_id(state, id).await;
+    let session = state
+        .torrent_session
+        .read()
+        .await
+        .clone()
+        .ok_or(WhenThenError::SessionNotReady)?;
+    let handle = session
+        .get(librqbit::api::TorrentIdOrHash::Id(id))
+        .ok_or(WhenThenError::TorrentNotFound(id))?;
 
-    debug!(torrent_id, "Progress emitter started");
+    let name = Some(name.trim()).filter(|n| !n.is_empty()).map(str::to_string);
+    display_names::set_name(app_handle, state, &handle.info_hash().as_string(), name).await;
+    let shown = display_name(state, &handle).await;
+    state.torrent_names.write().await.insert(id, shown.clone());
 
-    tokio::spawn(async move {
-        let mut prev_state: Option<String> = None;
+    let _ = app_handle.emit("torrents:changed", ());
+    crate::tray::refresh_menu();
+    Ok(shown)
+}
 
-        loop {
-            tokio::time::sleep(std::time::Duration::from_millis(500)).await;
-
-            let s = {
-                let guard = session.read().await;
-                match guard.as_ref() {
-                    Some(s) => s.clone(),
-                    None => {
-                        warn!(torrent_id, "Progress emitter exiting: session gone");
-                        break;
-                    }
-                }
-            };
+/// librqbit's current id for an id the frontend knows; see TorrentIdMap.
+pub async fn resolve_id(state: &AppState, id: usize) -> usize {
+    state.torrent_ids.read().await.resolve(id)
+}
 
-            let handle = match s.get(librqbit::api::TorrentIdOrHash::Id(torrent_id)) {
-                Some(h) => h,
-                None => {
-                    warn!(torrent_id, "Progress emitter exiting: torrent not in session");
-                    break;
-                }
-            };
+/// Carry per-torrent state over to the id librqbit assigned on re-add.
+/// Returns the id the frontend keeps using.
+async fn track_readd(state: &AppState, app_handle: &AppHandle, old_id: usize, new_id: usize, name: &str) -> usize {
+    state.torrent_names.write().await.insert(new_id, name.to_string());
+    replace_queued(state, app_handle, old_id, new_id).await;
+    state.torrent_ids.write().await.readded(old_id, new_id)
+}
 
-            let stats = handle.stats();
-            let total_bytes = stats.total_bytes;
-            let downloaded = stats.progress_bytes;
-            let progress = if total_bytes > 0 {
-                downloaded as f64 / total_bytes as f64
+const QUEUE_STORE: &str = "queue.json";
+
+/// Whether max_active_downloads torrents are already downloading.
+async fn at_capacity(state: &AppState, session: &Session) -> bool {
+    let max = state.config.read().await.max_active_downloads as usize;
+    if max == 0 {
+        return false;
+    }
+    let active = session.with_torrents(|torrents| {
+        torrents
+            .filter(|(_, h)| {
+                let stats = h.stats();
+                !stats.finished
+                    && !matches!(
+                        stats.state,
+                        librqbit::TorrentStatsState::Paused | librqbit::TorrentStatsState::Error
+                    )
+            })
+            .count()
+    });
+    active >= max
+}
+
+/// Save the queue by info hash, since torrent ids change across launches.
+async fn persist_queue(state: &AppState, app_handle: &AppHandle) {
+    let Some(session) = state.torrent_session.read().await.clone() else {
+        return;
+    };
+    let hashes: Vec<String> = state
+        .download_queue
+        .lock()
+        .await
+        .iter()
+        .filter_map(|id| session.get(librqbit::api::TorrentIdOrHash::Id(*id)))
+        .map(|h| h.info_hash().as_string())
+        .collect();
+
+    if let Ok(store) = app_handle.store(QUEUE_STORE) {
+        store.set("queue", serde_json::json!(hashes));
+        if let Err(e) = store.save() {
+            warn!("Failed to save download queue: {e}");
+        }
+    }
+}
+
+async fn enqueue(state: &AppState, app_handle: &AppHandle, id: usize, front: bool) {
+    {
+        let mut queue = state.download_queue.lock().await;
+        queue.retain(|q| *q != id);
+        if front {
+            queue.push_front(id);
+        } else {
+            queue.push_back(id);
+        }
+    }
+    info!(id, front, "Torrent queued");
+    persist_queue(state, app_handle).await;
+}
+
+/// Remove a torrent from the queue; returns whether it was queued.
+async fn dequeue(state: &AppState, app_handle: &AppHandle, id: usize) -> bool {
+    let removed = {
+        let mut queue = state.download_queue.lock().await;
+        let before = queue.len();
+        queue.retain(|q| *q != id);
+        queue.len() != before
+    };
+    if removed {
+        persist_queue(state, app_handle).await;
+    }
+    removed
+}
+
+/// Keep a queued torrent's place when a delete + re-add gives it a new id.
+async fn replace_queued(state: &AppState, app_handle: &AppHandle, old_id: usize, new_id: usize) {
+    let replaced = {
+        let mut queue = state.download_queue.lock().await;
+        match queue.iter_mut().find(|q| **q == old_id) {
+            Some(slot) => {
+                *slot = new_id;
+                true
+            }
+            None => false,
+        }
+    };
+    if replaced {
+        persist_queue(state, app_handle).await;
+    }
+}
+
+/// Start queued torrents in FIFO order while download slots are free.
+pub async fn promote_queued(state: &AppState, app_handle: &AppHandle) {
+    // Nothing new starts while torrents are held for battery
+    if state.power.holding() {
+        return;
+    }
+    let Some(session) = state.torrent_session.read().await.clone() else {
+        return;
+    };
+
+    let mut changed = false;
+    while !at_capacity(state, &session).await {
+        let Some(id) = state.download_queue.lock().await.pop_front() else {
+            break;
+        };
+        changed = true;
+        let Some(handle) = session.get(librqbit::api::TorrentIdOrHash::Id(id)) else {
+            continue;
+        };
+        match session.unpause(&handle).await {
+            Ok(_) => {
+                info!(id, "Started queued torrent");
+                watch_progress(state, id).await;
+            }
+            Err(e) => warn!(id, error = %e, "Failed to start queued torrent"),
+        }
+    }
+
+    if changed {
+        persist_queue(state, app_handle).await;
+    }
+}
+
+/// Resume every paused torrent, oldest first; those over the limit join the back of the queue.
+/// Called for the user, so on battery it overrides pause_on_battery.
+pub async fn resume_all(state: &AppState, app_handle: &AppHandle, session: &Session) {
+    let ids: Vec<usize> = session.with_torrents(|torrents| torrents.map(|(id, _)| id).collect());
+    for &id in &ids {
+        state.power.user_resumed(id).await;
+    }
+    resume_ids(state, app_handle, session, ids).await;
+}
+
+/// Resume the given torrents that are paused and not queued, oldest first;
+/// those over the limit join the back of the queue.
+pub async fn resume_ids(state: &AppState, app_handle: &AppHandle, session: &Session, ids: Vec<usize>) {
+    let queue = state.download_queue.lock().await.clone();
+    let mut paused: Vec<(usize, Arc<librqbit::ManagedTorrent>)> = ids
+        .into_iter()
+        .filter(|id| !queue.contains(id))
+        .filter_map(|id| session.get(librqbit::api::TorrentIdOrHash::Id(id)).map(|h| (id, h)))
+        .filter(|(_, h)| matches!(h.stats().state, librqbit::TorrentStatsState::Paused))
+        .collect();
+    paused.sort_by_key(|(id, _)| *id);
+
+    for (id, handle) in paused {
+        if at_capacity(state, session).await {
+            enqueue(state, app_handle, id, false).await;
+        } else if let Err(e) = session.unpause(&handle).await {
+            warn!(id, error = %e, "Failed to resume torrent");
+        } else {
+            watch_progress(state, id).await;
+        }
+    }
+}
+
+/// Pause every torrent and empty the queue, so nothing starts on its own.
+pub async fn pause_all(state: &AppState, app_handle: &AppHandle) {
+    let Some(session) = state.torrent_session.read().await.clone() else {
+        return;
+    };
+
+    let had_queue = {
+        let mut queue = state.download_queue.lock().await;
+        let had_queue = !queue.is_empty();
+        queue.clear();
+        had_queue
+    };
+    if had_queue {
+        persist_queue(state, app_handle).await;
+    }
+
+    let running: Vec<(usize, Arc<librqbit::ManagedTorrent>)> = session.with_torrents(|torrents| {
+        torrents
+            .filter(|(_, h)| !matches!(h.stats().state, librqbit::TorrentStatsState::Paused))
+            .map(|(id, h)| (id, h.clone()))
+            .collect()
+    });
+    for (id, handle) in running {
+        if let Err(e) = session.pause(&handle).await {
+            warn!(id, error = %e, "Failed to pause torrent");
+        }
+    }
+}
+
+/// Bytes fetched and uploaded this run by each live torrent, by info hash.
+pub async fn transfer_counters(state: &AppState) -> HashMap<String, (u64, u64)> {
+    let Some(session) = state.torrent_session.read().await.clone() else {
+        return HashMap::new();
+    };
+
+    session.with_torrents(|torrents| {
+        torrents
+            .filter_map(|(_, handle)| {
+                let stats = handle.stats();
+                let live = stats.live.as_ref()?;
+                Some((
+                    handle.info_hash().as_string(),
+                    (live.snapshot.fetched_bytes, live.snapshot.uploaded_bytes),
+                ))
+            })
+            .collect()
+    })
+}
+
+/// Rebuild the queue after launch: the saved order first, then any unpaused
+/// torrents over the limit in id order, so the result is the same every run.
+async fn restore_queue(state: &AppState, app_handle: &AppHandle, session: &Session) {
+    let saved: Vec<String> = app_handle
+        .store(QUEUE_STORE)
+        .ok()
+        .and_then(|store| store.get("queue"))
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default();
+
+    let mut torrents: Vec<(usize, Arc<librqbit::ManagedTorrent>)> = session.with_torrents(|torrents| {
+        torrents
+            .filter(|(_, h)| !h.stats().finished)
+            .map(|(id, h)| (id, h.clone()))
+            .collect()
+    });
+    torrents.sort_by_key(|(id, _)| *id);
+
+    let mut queue = VecDeque::new();
+    for hash in &saved {
+        if let Some((id, _)) = torrents.iter().find(|(_, h)| h.info_hash().as_string() == *hash) {
+            if !queue.contains(id) {
+                queue.push_back(*id);
+            }
+        }
+    }
+
+    let max = state.config.read().await.max_active_downloads as usize;
+    let mut active = 0;
+    for (id, handle) in &torrents {
+        let running = !matches!(
+            handle.stats().state,
+            librqbit::TorrentStatsState::Paused | librqbit::TorrentStatsState::Error
+        );
+        if queue.contains(id) {
+            // Queued torrents must stay paused until promoted
+            if running {
+                let _ = session.pause(handle).await;
+            }
+        } else if running {
+            if max > 0 && active >= max {
+                let _ = session.pause(handle).await;
+                queue.push_back(*id);
             } else {
-                0.0
-            };
+                active += 1;
+            }
+        }
+    }
+
+    if !queue.is_empty() {
+        info!(queued = queue.len(), "Restored download queue");
+    }
+    *state.download_queue.lock().await = queue;
+    persist_queue(state, app_handle).await;
+    promote_queued(state, app_handle).await;
+}
+
+/// (path, length) for every file in the torrent, or empty while metadata is unavailable.
+fn file_details(handle: &Arc<librqbit::ManagedTorrent>) -> Vec<(String, u64)> {
+    handle.with_metadata(|meta| {
+        meta.info.iter_file_details()
+            .map(|iter| {
+                iter.map(|fi| {
+                    let path_str = fi.filename.to_string()
+                        .unwrap_or_else(|_| "<INVALID NAME>".to_string());
+                    (path_str, fi.len)
+                }).collect::<Vec<_>>()
+            })
+            .unwrap_or_default()
+    }).unwrap_or_default()
+}
+
+/// Bytes from the start of a file that must be downloaded before casting it.
+const READY_HEAD_BYTES: u64 = 20 * 1024 * 1024;
+
+/// Length of the downloaded run at the start of a file spanning
+/// `offset..offset + len` in the torrent, counting at most `limit` bytes.
+fn contiguous_head(have: impl Fn(usize) -> bool, piece_length: u64, offset: u64, len: u64, limit: u64) -> u64 {
+    let wanted = len.min(limit);
+    if wanted == 0 || piece_length == 0 {
+        return wanted;
+    }
+    let end = offset + wanted;
+    let mut piece = offset / piece_length;
+    while piece * piece_length < end {
+        if !have(piece as usize) {
+            return (piece * piece_length).saturating_sub(offset);
+        }
+        piece += 1;
+    }
+    wanted
+}
+
+/// Whether enough of the start of `file_idx` is downloaded to stream it,
+/// from the torrent's piece bitfield. None if there's no such file.
+pub fn stream_readiness(handle: &librqbit::ManagedTorrent, file_idx: usize) -> Option<StreamReadiness> {
+    // (offset in torrent, length) of each file, plus the piece length
+    let (piece_length, file) = handle
+        .with_metadata(|meta| {
+            let mut offset = 0;
+            let files: Vec<(u64, u64)> = meta
+                .info
+                .iter_file_details()
+                .map(|iter| {
+                    iter.map(|fi| {
+                        let file = (offset, fi.len);
+                        offset += fi.len;
+                        file
+                    })
+                    .collect()
+                })
+                .unwrap_or_default();
+            (meta.info.piece_length as u64, files.get(file_idx).copied())
+        })
+        .ok()?;
+    let (offset, total_bytes) = file?;
 
-            let (dl_speed, ul_speed, peers) = if let Some(ref live) = stats.live {
-                (
-                    (live.download_speed.mbps * 1024.0 * 1024.0) as u64,
-                    (live.upload_speed.mbps * 1024.0 * 1024.0) as u64,
-                    live.snapshot.peer_stats.live,
+    let stats = handle.stats();
+    let required = total_bytes.min(READY_HEAD_BYTES);
+    let buffered_head_bytes = if stats.finished {
+        required
+    } else {
+        // Without a live chunk tracker (paused, initializing) nothing counts
+        handle
+            .with_chunk_tracker(|chunks| {
+                let have = chunks.get_have_pieces();
+                contiguous_head(
+                    |piece| have.get(piece).is_some_and(|bit| *bit),
+                    piece_length,
+                    offset,
+                    total_bytes,
+                    READY_HEAD_BYTES,
                 )
-            } else {
-                (0, 0, 0)
+            })
+            .unwrap_or(0)
+    };
+
+    let ready = buffered_head_bytes >= required;
+    let speed = stats
+        .live
+        .as_ref()
+        .map(|live| (live.download_speed.mbps * 1024.0 * 1024.0) as u64)
+        .unwrap_or(0);
+    let est_seconds_until_ready = if ready {
+        Some(0)
+    } else if speed > 0 {
+        Some((required - buffered_head_bytes).div_ceil(speed))
+    } else {
+        None
+    };
+
+    Some(StreamReadiness {
+        ready,
+        buffered_head_bytes,
+        total_bytes,
+        est_seconds_until_ready,
+    })
+}
+
+fn build_file_list(
+    handle: &Arc<librqbit::ManagedTorrent>,
+    media_server: &MediaServerHandle,
+) -> Vec<TorrentFileInfo> {
+    let id = handle.id();
+    let base_url = media_server.base_url();
+    let mut files = Vec::new();
+    let file_infos = file_details(handle);
+    let file_progress = handle.stats().file_progress;
+    let only_files = handle.only_files();
+
+    for (idx, (path_str, length)) in file_infos.into_iter().enumerate() {
+        let name = path_str.rsplit('/').next().unwrap_or(&path_str).to_string();
+        let mime = mime_guess::from_path(&name).first_raw().map(String::from);
+        let is_playable = mime.as_ref().is_some_and(|m| {
+            m.starts_with("video/") || m.starts_with("audio/")
+        });
+        let downloaded_bytes = file_progress.get(idx).copied().unwrap_or(0);
+        let progress = if length > 0 {
+            (downloaded_bytes as f64 / length as f64).min(1.0)
+        } else {
+            1.0
+        };
+        let stream_url = if is_playable {
+            Some(format!(
+                "{}/torrent/{}/stream/{}?token={}",
+                base_url, id, idx, media_server.stream_token
+            ))
+        } else {
+            None
+        };
+
+        files.push(TorrentFileInfo {
+            index: idx,
+            name,
+            path: path_str,
+            length,
+            is_playable,
+            mime_type: mime,
+            stream_url,
+            downloaded_bytes,
+            progress,
+            selected: only_files.as_ref().is_none_or(|selected| selected.contains(&idx)),
+        });
+    }
+
+    files
+}
+
+/// How often the session monitor samples every torrent.
+const MONITOR_INTERVAL: std::time::Duration = std::time::Duration::from_millis(500);
+
+/// How often the whole list goes out as torrents:snapshot, so a frontend that
+/// missed a delta catches up.
+const SNAPSHOT_INTERVAL: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Whether a new torrent is added paused: its own add_paused, or else the
+/// add_torrents_paused setting.
+async fn starts_paused(state: &AppState, options: Option<&TorrentAddOptions>) -> bool {
+    match options.and_then(|opts| opts.add_paused) {
+        Some(paused) => paused,
+        None => state.config.read().await.add_torrents_paused,
+    }
+}
+
+/// Start reporting progress, low disk space and completion for a torrent.
+/// The session monitor picks it up on its next tick.
+async fn watch_progress(state: &AppState, torrent_id: usize) {
+    state.progress_watch.lock().await.insert(torrent_id);
+    debug!(torrent_id, "Watching torrent progress");
+}
+
+/// What the monitor remembers about a watched torrent between ticks.
+#[derive(Default)]
+struct Watched {
+    state: Option<TorrentState>,
+    // Magnet sizes are only known once metadata arrives, so check space then
+    space_checked: bool,
+    // Same for an approved magnet's file list, which interest file filters wait on
+    files_filtered: bool,
+}
+
+/// Weight of the newest sample in the smoothed download speed. At one sample
+/// per MONITOR_INTERVAL, a change in speed settles over a few seconds.
+const SPEED_SMOOTHING: f64 = 0.2;
+
+/// Exponential moving average of the download speed, seeded by the first sample.
+fn smooth_speed(average: Option<f64>, sample: u64) -> f64 {
+    match average {
+        Some(average) => average + SPEED_SMOOTHING * (sample as f64 - average),
+        None => sample as f64,
+    }
+}
+
+/// Speeds are reported in whole KiB/s, so the trickle librqbit's estimator
+/// leaves behind on an idle torrent reads as 0.
+const SPEED_STEP: f64 = 1024.0;
+
+/// A speed still counts as unchanged within 1/SPEED_TOLERANCE (5%) of the
+/// last one sent, so jitter on a busy torrent doesn't resend it every tick.
+const SPEED_TOLERANCE: u64 = 20;
+
+/// librqbit's speed in MiB/s as bytes/s, rounded to SPEED_STEP.
+fn round_speed(mbps: f64) -> u64 {
+    ((mbps * 1024.0 * 1024.0 / SPEED_STEP).round() * SPEED_STEP) as u64
+}
+
+/// Whether `current` is close enough to the `sent` speed to skip an update.
+/// Stopping or starting always counts as a change.
+fn near_speed(sent: u64, current: u64) -> bool {
+    if sent == 0 || current == 0 {
+        return sent == current;
+    }
+    sent.abs_diff(current) <= sent.max(current) / SPEED_TOLERANCE
+}
+
+/// Whether an ETA moved little enough to skip an update, with the same 5%
+/// leeway as speeds. Becoming known, unknown or zero always counts as a change.
+fn near_eta(sent: Option<u64>, current: Option<u64>) -> bool {
+    match (sent, current) {
+        (Some(sent), Some(current)) => near_speed(sent, current),
+        _ => sent == current,
+    }
+}
+
+/// Seconds to download `remaining` bytes at `speed` bytes/s. None when
+/// there's no progress to extrapolate from.
+fn eta_secs(finished: bool, remaining: u64, speed: f64) -> Option<u64> {
+    if finished || remaining == 0 {
+        return Some(0);
+    }
+    (speed >= 1.0).then(|| (remaining as f64 / speed).ceil() as u64)
+}
+
+fn summarize(
+    id: usize,
+    handle: &librqbit::ManagedTorrent,
+    names: &HashMap<usize, String>,
+    queue: &VecDeque<usize>,
+    ids: &TorrentIdMap,
+    labels: &torrent_labels::TorrentLabels,
+    speeds: &mut HashMap<usize, f64>,
+) -> TorrentSummary {
+    let stats = handle.stats();
+    let name = names.get(&id).cloned()
+        .unwrap_or_else(|| handle.name().unwrap_or_else(|| "Unknown".to_string()));
+    let total_bytes = stats.total_bytes;
+    let downloaded = stats.progress_bytes;
+    let progress = if total_bytes > 0 {
+        downloaded as f64 / total_bytes as f64
+    } else {
+        0.0
+    };
+
+    let (dl_speed, ul_speed, peers, uploaded_bytes, queued_peers, connecting_peers) =
+        if let Some(ref live) = stats.live {
+            (
+                round_speed(live.download_speed.mbps),
+                round_speed(live.upload_speed.mbps),
+                live.snapshot.peer_stats.live,
+                live.snapshot.uploaded_bytes,
+                live.snapshot.peer_stats.queued,
+                live.snapshot.peer_stats.connecting,
+            )
+        } else {
+            (0, 0, 0, 0, 0, 0)
+        };
+
+    // Paused or not yet started: no speed to carry over
+    let eta = if stats.live.is_some() && !stats.finished {
+        let average = smooth_speed(speeds.get(&id).copied(), dl_speed);
+        speeds.insert(id, average);
+        eta_secs(false, total_bytes.saturating_sub(downloaded), average)
+    } else {
+        speeds.remove(&id);
+        eta_secs(stats.finished, total_bytes.saturating_sub(downloaded), 0.0)
+    };
+
+    let info_hash = handle.info_hash().as_string();
+
+    TorrentSummary {
+        id: ids.external(id),
+        name,
+        label: labels.get(&info_hash),
+        info_hash,
+        state: torrent_state(stats.finished, &stats.state, queue.contains(&id)),
+        progress,
+        download_speed: dl_speed,
+        upload_speed: ul_speed,
+        peers_connected: peers,
+        queued_peers,
+        connecting_peers,
+        total_bytes,
+        downloaded_bytes: downloaded,
+        uploaded_bytes,
+        file_count: stats.file_progress.len(),
+        eta_secs: eta,
+        availability: None,
+    }
+}
+
+/// A summary of every torrent in the session, with librqbit's id.
+async fn sample_torrents(state: &AppState, session: &Session) -> Vec<(usize, TorrentSummary)> {
+    let names = state.torrent_names.read().await;
+    let queue = state.download_queue.lock().await;
+    let ids = state.torrent_ids.read().await;
+    let labels = state.torrent_labels.read().await;
+    let mut speeds = state.download_speeds.lock().await;
+
+    let samples: Vec<(usize, TorrentSummary)> = session.with_torrents(|torrents| {
+        torrents
+            .map(|(id, handle)| (id, summarize(id, handle, &names, &queue, &ids, &labels, &mut speeds)))
+            .collect()
+    });
+    if speeds.len() > samples.len() {
+        let sampled: HashSet<usize> = samples.iter().map(|(id, _)| *id).collect();
+        speeds.retain(|id, _| sampled.contains(id));
+    }
+    samples
+}
+
+/// One loop for the whole session: samples every torrent each tick, keeps
+/// the latest list in AppState for torrent_list, and emits torrents:delta with
+/// only the entries that changed. Watched torrents also get torrent:progress,
+/// low disk space warnings and completion handling.
+pub fn spawn_session_monitor(app_handle: AppHandle) {
+    tokio::spawn(async move {
+        let mut previous: HashMap<usize, TorrentSummary> = HashMap::new();
+        let mut watched: HashMap<usize, Watched> = HashMap::new();
+        let mut last_snapshot = std::time::Instant::now();
+        let mut interval = tokio::time::interval(MONITOR_INTERVAL);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+        debug!("Session monitor started");
+        loop {
+            interval.tick().await;
+            let state = app_handle.state::<AppState>();
+            let Some(session) = state.torrent_session.read().await.clone() else {
+                // Expected on quit, when shutdown_session takes the session
+                *state.torrent_snapshot.write().await = None;
+                debug!("Session monitor exiting: session gone");
+                break;
             };
 
-            let state_val = if stats.finished {
-                TorrentState::Completed
-            } else {
-                match stats.state {
-                    librqbit::TorrentStatsState::Paused => TorrentState::Paused,
-                    librqbit::TorrentStatsState::Error => TorrentState::Error,
-                    librqbit::TorrentStatsState::Initializing => TorrentState::Initializing,
-                    _ => TorrentState::Downloading,
+            let added = std::mem::take(&mut *state.progress_watch.lock().await);
+            for torrent_id in &added {
+                watched.entry(*torrent_id).or_default();
+            }
+
+            let samples = sample_torrents(&state, &session).await;
+            let delta = diff_summaries(&mut previous, samples.iter().map(|(_, summary)| summary));
+
+            // Watched torrents report when they change, and once when first watched
+            let changed: HashSet<usize> = delta.changed.iter().map(|summary| summary.id).collect();
+            for (torrent_id, summary) in &samples {
+                if changed.contains(&summary.id) || added.contains(torrent_id) {
+                    report_progress(&app_handle, &state, &mut watched, *torrent_id, summary).await;
+                }
+            }
+            if !delta.removed.is_empty() || watched.len() > samples.len() {
+                let sampled: HashSet<usize> = samples.iter().map(|(torrent_id, _)| *torrent_id).collect();
+                watched.retain(|torrent_id, _| sampled.contains(torrent_id));
+            }
+
+            if !delta.changed.is_empty() || !delta.removed.is_empty() {
+                if let Err(e) = event_bus::emit(&app_handle, "torrents:delta", &delta) {
+                    warn!(error = %e, "Failed to emit torrent list delta");
                 }
+            }
+
+            let list: Vec<TorrentSummary> = samples.into_iter().map(|(_, summary)| summary).collect();
+            if last_snapshot.elapsed() >= SNAPSHOT_INTERVAL {
+                last_snapshot = std::time::Instant::now();
+                if let Err(e) = event_bus::emit(&app_handle, "torrents:snapshot", &list) {
+                    warn!(error = %e, "Failed to emit torrent list snapshot");
+                }
+            }
+            *state.torrent_snapshot.write().await = Some(list);
+        }
+    });
+}
+
+/// Whether `current` can be skipped against the `sent` summary: speeds and
+/// the ETA may drift within SPEED_TOLERANCE, everything else must match. The counters
+/// that move on a busy torrent are compared first, so an idle one costs a
+/// few integer compares before the strings.
+fn is_steady(sent: &TorrentSummary, current: &TorrentSummary) -> bool {
+    let TorrentSummary {
+        id,
+        name,
+        info_hash,
+        state,
+        progress,
+        download_speed,
+        upload_speed,
+        peers_connected,
+        queued_peers,
+        connecting_peers,
+        total_bytes,
+        downloaded_bytes,
+        uploaded_bytes,
+        file_count,
+        label,
+        eta_secs,
+        availability,
+    } = current;
+    sent.downloaded_bytes == *downloaded_bytes
+        && sent.uploaded_bytes == *uploaded_bytes
+        && near_speed(sent.download_speed, *download_speed)
+        && near_speed(sent.upload_speed, *upload_speed)
+        && sent.peers_connected == *peers_connected
+        && sent.queued_peers == *queued_peers
+        && sent.connecting_peers == *connecting_peers
+        && near_eta(sent.eta_secs, *eta_secs)
+        && sent.state == *state
+        && sent.progress == *progress
+        && sent.total_bytes == *total_bytes
+        && sent.file_count == *file_count
+        && sent.availability == *availability
+        && sent.id == *id
+        && sent.name == *name
+        && sent.label == *label
+        && sent.info_hash == *info_hash
+}
+
+/// Entries of `current` that differ from `previous`, and ids that are gone.
+/// Updates `previous` to match; only changed entries are cloned.
+fn diff_summaries<'a>(
+    previous: &mut HashMap<usize, TorrentSummary>,
+    current: impl ExactSizeIterator<Item = &'a TorrentSummary> + Clone,
+) -> TorrentsDelta {
+    let count = current.len();
+    let mut changed = Vec::new();
+    for summary in current.clone() {
+        if !previous.get(&summary.id).is_some_and(|sent| is_steady(sent, summary)) {
+            previous.insert(summary.id, summary.clone());
+            changed.push(summary.clone());
+        }
+    }
+
+    // Something left the session only if more are remembered than were listed
+    let mut removed = Vec::new();
+    if previous.len() > count {
+        let listed: HashSet<usize> = current.map(|summary| summary.id).collect();
+        previous.retain(|id, _| {
+            let keep = listed.contains(id);
+            if !keep {
+                removed.push(*id);
+            }
+            keep
+        });
+    }
+    TorrentsDelta { changed, removed }
+}
+
+/// torrent:progress, state change logging and the disk space check for a
+/// watched torrent that changed; hands off to finish_download once complete.
+async fn report_progress(
+    app_handle: &AppHandle,
+    state: &AppState,
+    watched: &mut HashMap<usize, Watched>,
+    torrent_id: usize,
+    summary: &TorrentSummary,
+) {
+    let Some(watch) = watched.get_mut(&torrent_id) else {
+        return;
+    };
+    // Events carry the id the frontend knows, which survives re-adds
+    let external_id = summary.id;
+
+    if !watch.files_filtered && summary.total_bytes > 0 {
+        watch.files_filtered = true;
+        if state.rss_state.pending_file_filters.read().await.contains_key(&summary.info_hash) {
+            // Narrowing the selection re-adds the torrent, which starts a fresh watch
+            tokio::spawn(rss::apply_pending_file_filters(app_handle.clone(), external_id, summary.info_hash.clone()));
+        }
+    }
+
+    if !watch.space_checked && summary.total_bytes > 0 && summary.state != TorrentState::Completed {
+        watch.space_checked = true;
+        let (dir, reserve) = {
+            let cfg = state.config.read().await;
+            let dir = if cfg.incomplete_directory.is_empty() {
+                cfg.download_directory.clone()
+            } else {
+                cfg.incomplete_directory.clone()
             };
+            (expand_path(&dir), cfg.disk_space_reserve_bytes)
+        };
+        let required = summary.total_bytes.saturating_sub(summary.downloaded_bytes);
+        if let Some(available) = available_space(&dir) {
+            if !has_room(available, required, reserve) {
+                warn!(torrent_id, required, available, "Low disk space for torrent");
+                app_handle
+                    .emit(
+                        "torrent:low-disk-space",
+                        serde_json::json!({
+                            "torrent_id": external_id,
+                            "required": required,
+                            "available": available,
+                        }),
+                    )
+                    .unwrap_or_default();
+            }
+        }
+    }
+
+    if watch.state.as_ref() != Some(&summary.state) {
+        info!(
+            torrent_id,
+            state = ?summary.state,
+            total_bytes = summary.total_bytes,
+            peers = summary.peers_connected,
+            "Torrent state changed"
+        );
+        watch.state = Some(summary.state.clone());
+        crate::tray::refresh_menu();
+    }
+
+    #[derive(serde::Serialize, Clone)]
+    struct TorrentProgress {
+        id: usize,
+        progress: f64,
+        download_speed: u64,
+        upload_speed: u64,
+        peers_connected: usize,
+        queued_peers: usize,
+        connecting_peers: usize,
+        downloaded_bytes: u64,
+        uploaded_bytes: u64,
+        total_bytes: u64,
+        state: TorrentState,
+        eta_secs: Option<u64>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        availability: Option<f64>,
+    }
+
+    let progress_event = TorrentProgress {
+        id: external_id,
+        progress: summary.progress,
+        download_speed: summary.download_speed,
+        upload_speed: summary.upload_speed,
+        peers_connected: summary.peers_connected,
+        queued_peers: summary.queued_peers,
+        connecting_peers: summary.connecting_peers,
+        downloaded_bytes: summary.downloaded_bytes,
+        uploaded_bytes: summary.uploaded_bytes,
+        total_bytes: summary.total_bytes,
+        state: summary.state.clone(),
+        eta_secs: summary.eta_secs,
+        availability: summary.availability,
+    };
+
+    if let Err(e) = event_bus::emit(app_handle, "torrent:progress", &progress_event) {
+        warn!(torrent_id, error = %e, "Failed to emit progress event");
+    }
+
+    if summary.state == TorrentState::Completed {
+        watched.remove(&torrent_id);
+        // Only the files up to the current position were selected; the re-add
+        // that widens the selection starts a fresh watch
+        if state.file_orders.read().await.get(&summary.info_hash).is_some() {
+            tokio::spawn(advance_file_order(app_handle.clone(), torrent_id, external_id));
+            return;
+        }
+        // Moving files and running hooks can take a while; the monitor keeps ticking
+        tokio::spawn(finish_download(app_handle.clone(), torrent_id, external_id));
+    }
+}
+
+/// Everything that happens once a watched torrent completes.
+async fn finish_download(app_handle: AppHandle, torrent_id: usize, external_id: usize) {
+    info!(torrent_id, "Download complete");
+
+    let state = app_handle.state::<AppState>();
+    let Some(session) = state.torrent_session.read().await.clone() else {
+        return;
+    };
+    let Some(handle) = session.get(librqbit::api::TorrentIdOrHash::Id(torrent_id)) else {
+        warn!(torrent_id, "Completed torrent left the session before it was handled");
+        return;
+    };
+    let config = state.config.clone();
+    let name = handle.name().unwrap_or_else(|| "Unknown".to_string());
+    let info_hash = handle.info_hash().as_string();
+    let output_folder = torrent_outputs::folder(&state, &info_hash).await;
+    let mut final_path = output_folder.join(&name);
+
+    let cfg = config.read().await;
+    let on_complete_action = cfg.on_complete_action.clone();
+    let download_dir = expand_path(&cfg.download_directory);
+    // Only downloads that went to the incomplete directory move on completion
+    let from_incomplete = !cfg.incomplete_directory.is_empty()
+        && output_folder == expand_path(&cfg.incomplete_directory)
+        && output_folder != download_dir;
+    drop(cfg);
+
+    state.completed_history.write().await.record(crate::models::CompletedEntry {
+        info_hash: info_hash.clone(),
+        name: name.clone(),
+        total_bytes: handle.stats().total_bytes,
+        completed_at: chrono::Utc::now().to_rfc3339(),
+    });
+    crate::services::completed_history::persist(&app_handle, &state).await;
+
+    if from_incomplete && final_path.exists() {
+        let dst = download_dir.join(&name);
+        if let Err(e) = std::fs::rename(&final_path, &dst) {
+            warn!(
+                torrent_id,
+                src = %final_path.display(),
+                dst = %dst.display(),
+                error = %e,
+                "Failed to move completed torrent from incomplete dir"
+            );
+        } else {
+            info!(torrent_id, dst = %dst.display(), "Moved completed download");
+            torrent_outputs::record(&app_handle, &state, &info_hash, &download_dir).await;
+            final_path = dst;
+        }
+    }
 
-            let state_str = format!("{:?}", state_val);
-            if prev_state.as_ref() != Some(&state_str) {
-                info!(
-                    torrent_id,
-                    state = %state_str,
-                    total_bytes,
-                    peers,
-                    "Torrent state changed"
-                );
-                prev_state = Some(state_str);
-            }
+    if let Some(dest) =
+        crate::services::rss::organize_completed(&app_handle, external_id, &name).await
+    {
+        final_path = dest.join(&name);
+    }
 
-            #[derive(serde::Serialize, Clone)]
-            struct TorrentProgress {
-                id: usize,
-                progress: f64,
-                download_speed: u64,
-                upload_speed: u64,
-                peers_connected: usize,
-                queued_peers: usize,
-                connecting_peers: usize,
-                downloaded_bytes: u64,
-                uploaded_bytes: u64,
-                total_bytes: u64,
-                state: TorrentState,
-            }
+    let (auto_extract, extract_subfolder) = {
+        let cfg = config.read().await;
+        (cfg.auto_extract_archives, cfg.extract_subfolder.clone())
+    };
+    if auto_extract {
+        // Multi-file torrents finish as a folder, single-file ones as the file itself
+        let (files, torrent_dir) = if final_path.is_dir() {
+            let files = file_details(&handle)
+                .into_iter()
+                .map(|(path, _)| final_path.join(path))
+                .collect();
+            (files, final_path.clone())
+        } else {
+            let dir = final_path.parent().map(PathBuf::from).unwrap_or_default();
+            (vec![final_path.clone()], dir)
+        };
+        let dest = if extract_subfolder.trim().is_empty() {
+            torrent_dir
+        } else {
+            torrent_dir.join(extract_subfolder.trim())
+        };
+        crate::services::extractor::spawn_extraction(app_handle.clone(), external_id, files, dest);
+    }
 
-            let (uploaded_bytes, queued_peers, connecting_peers) = if let Some(ref live) = stats.live {
-                (
-                    live.snapshot.uploaded_bytes,
-                    live.snapshot.peer_stats.queued,
-                    live.snapshot.peer_stats.connecting,
-                )
-            } else {
-                (0, 0, 0)
-            };
+    crate::services::notifications::notify_torrent_complete(&app_handle, &name).await;
 
-            let progress_event = TorrentProgress {
-                id: torrent_id,
-                progress,
-                download_speed: dl_speed,
-                upload_speed: ul_speed,
-                peers_connected: peers,
-                queued_peers,
-                connecting_peers,
-                downloaded_bytes: downloaded,
-                uploaded_bytes,
-                total_bytes,
-                state: state_val.clone(),
-            };
+    if let Some(action) = on_complete_action {
+        let payload = CompletionPayload {
+            name,
+            info_hash,
+            files: file_details(&handle).into_iter().map(|(path, _)| path).collect(),
+            path: final_path.to_string_lossy().to_string(),
+        };
+        spawn_completion_action(app_handle.clone(), external_id, action, payload);
+    }
 
-            if let Err(e) = app_handle.emit("torrent:progress", &progress_event) {
-                warn!(torrent_id, error = %e, "Failed to emit progress event");
-            }
+    app_handle
+        .emit("torrent:completed", external_id)
+        .unwrap_or_default();
 
-            if state_val == TorrentState::Completed {
-                info!(torrent_id, "Download complete");
-
-                let cfg = config.read().await;
-                if !cfg.incomplete_directory.is_empty()
-                    && cfg.incomplete_directory != cfg.download_directory
-                {
-                    let name = handle.name().unwrap_or_else(|| "Unknown".to_string());
-                    let src = expand_path(&cfg.incomplete_directory).join(&name);
-                    let dst = expand_path(&cfg.download_directory).join(&name);
-                    drop(cfg);
-
-                    if src.exists() {
-                        if let Err(e) = std::fs::rename(&src, &dst) {
-                            warn!(
-                                torrent_id,
-                                src = %src.display(),
-                                dst = %dst.display(),
-                                error = %e,
-                                "Failed to move completed torrent from incomplete dir"
-                            );
-                        } else {
-                            info!(torrent_id, dst = %dst.display(), "Moved completed download");
-                        }
-                    }
-                }
+    promote_queued(&state, &app_handle).await;
+}
 
-                app_handle
-                    .emit("torrent:completed", torrent_id)
-                    .unwrap_or_default();
-                break;
+/// Run the completion hook in its own task; failures are reported, never propagated.
+fn spawn_completion_action(app_handle: AppHandle, torrent_id: usize, action: CompletionAction, payload: CompletionPayload) {
+    tokio::spawn(async move {
+        match run_completion_action(&action, &payload).await {
+            Ok(_) => info!(torrent_id, "Completion hook finished"),
+            Err(e) => {
+                warn!(torrent_id, error = %e, "Completion hook failed");
+                let _ = app_handle.emit(
+                    "automation:error",
+                    serde_json::json!({
+                        "torrent_id": torrent_id,
+                        "name": payload.name,
+                        "message": e.to_string(),
+                    }),
+                );
             }
         }
-
-        debug!(torrent_id, "Progress emitter stopped");
     });
 }
 
-pub async fn move_torrent_files(state: &AppState, torrent_id: usize, destination: String) -> Result<()> {
+pub async fn move_torrent_files(
+    state: &AppState,
+    app_handle: &AppHandle,
+    torrent_id: usize,
+    destination: String,
+) -> Result<()> {
+    let torrent_id = resolve_id(state, torrent_id).await;
     let session = {
         let guard = state.torrent_session.read().await;
         guard.as_ref().ok_or_else(|| {
-            WhenThenError::Torrent("Torrent session not initialized".into())
+            WhenThenError::SessionNotReady
         })?.clone()
     };
 
@@ -1031,10 +2180,8 @@ pub async fn move_torrent_files(state: &AppState, torrent_id: usize, destination
             .map_err(|e| WhenThenError::Internal(format!("Cannot create destination: {e}")))?;
     }
 
-    let output_folder = {
-        let cfg = state.config.read().await;
-        expand_path(&cfg.download_directory)
-    };
+    let info_hash = handle.info_hash().as_string();
+    let output_folder = torrent_outputs::folder(state, &info_hash).await;
     let torrent_name = handle.name().unwrap_or_else(|| "Unknown".to_string());
     let source_path = output_folder.join(&torrent_name);
 
@@ -1118,17 +2265,17 @@ pub async fn move_torrent_files(state: &AppState, torrent_id: usize, destination
         }
     }
 
-    // Record the new location for subtitle searches and other operations
-    state.torrent_locations.write().await.insert(torrent_id, dest_path.to_string_lossy().to_string());
+    torrent_outputs::record(app_handle, state, &info_hash, &dest_path).await;
 
     Ok(())
 }
 
 pub async fn rename_torrent_files(state: &AppState, torrent_id: usize, renames: Vec<(usize, String)>) -> Result<()> {
+    let torrent_id = resolve_id(state, torrent_id).await;
     let session = {
         let guard = state.torrent_session.read().await;
         guard.as_ref().ok_or_else(|| {
-            WhenThenError::Torrent("Torrent session not initialized".into())
+            WhenThenError::SessionNotReady
         })?.clone()
     };
 
@@ -1136,10 +2283,7 @@ pub async fn rename_torrent_files(state: &AppState, torrent_id: usize, renames:
         .get(librqbit::api::TorrentIdOrHash::Id(torrent_id))
         .ok_or(WhenThenError::TorrentNotFound(torrent_id))?;
 
-    let output_folder = {
-        let cfg = state.config.read().await;
-        expand_path(&cfg.download_directory)
-    };
+    let output_folder = torrent_outputs::folder(state, &handle.info_hash().as_string()).await;
     let torrent_name = handle.name().unwrap_or_else(|| "Unknown".to_string());
 
     for (file_idx, new_name) in &renames {
@@ -1154,17 +2298,8 @@ pub async fn rename_torrent_files(state: &AppState, torrent_id: usize, renames:
         }).ok().flatten();
 
         if let Some(orig_rel) = original_path_str {
-            let source = output_folder.join(&torrent_name).join(&orig_rel);
+            let source = torrent_outputs::file_path(&output_folder, &torrent_name, &orig_rel);
             if !source.exists() {
-                // Try without torrent name prefix (single-file torrents)
-                let source_alt = output_folder.join(&orig_rel);
-                if source_alt.exists() {
-                    let parent = source_alt.parent().unwrap_or(&output_folder);
-                    let target = parent.join(new_name);
-                    std::fs::rename(&source_alt, &target).map_err(|e| {
-                        WhenThenError::Internal(format!("Failed to rename file: {e}"))
-                    })?;
-                }
                 continue;
             }
             let parent = source.parent().unwrap_or(&output_folder);
@@ -1188,11 +2323,47 @@ pub async fn update_torrent_files(
     if only_files.is_empty() {
         return Err(WhenThenError::Torrent("Cannot deselect all files".into()));
     }
+    let result = readd_torrent(state, app_handle, id, Some(only_files)).await?;
+
+    #[derive(serde::Serialize, Clone)]
+    struct TorrentFilesUpdated {
+        old_id: usize,
+        new_id: usize,
+        name: String,
+    }
+
+    app_handle
+        .emit("torrent:files-updated", &TorrentFilesUpdated { old_id: result.id, new_id: result.id, name: result.name.clone() })
+        .unwrap_or_default();
+
+    info!(external_id = result.id, "Torrent file selection updated");
+
+    Ok(result)
+}
+
+/// Attach an HTTP web seed. librqbit reads neither a metainfo's url-list nor
+/// a magnet's ws= parameters, so there's no way to use one yet; the URL is
+/// still checked so the error says what's actually wrong.
+pub fn add_web_seed(id: usize, url: &str) -> Result<TorrentAddedResponse> {
+    web_seeds::validate(url.trim())?;
+    debug!(external_id = id, "Web seed refused: not supported by librqbit");
+    Err(WhenThenError::Torrent("Web seeds aren't supported by the torrent engine".into()))
+}
+
+/// Delete and re-add a torrent from its metainfo, with a new file selection
+/// (None keeps the current one).
+async fn readd_torrent(
+    state: &AppState,
+    app_handle: &AppHandle,
+    id: usize,
+    only_files: Option<Vec<usize>>,
+) -> Result<TorrentAddedResponse> {
+    let id = resolve_id(state, id).await;
 
     let session = {
         let guard = state.torrent_session.read().await;
         guard.as_ref().ok_or_else(|| {
-            WhenThenError::Torrent("Torrent session not initialized".into())
+            WhenThenError::SessionNotReady
         })?.clone()
     };
 
@@ -1202,19 +2373,407 @@ pub async fn update_torrent_files(
 
     let torrent_bytes = handle
         .with_metadata(|m| m.torrent_bytes.clone())
-        .map_err(|e| WhenThenError::Torrent(format!("Cannot read torrent metadata: {e}")))?;
+        .map_err(|e| WhenThenError::torrent("Cannot read torrent metadata", e))?;
+    let only_files = only_files.or_else(|| handle.only_files());
 
-    let name = handle.name().unwrap_or_else(|| "Unknown".to_string());
+    let name = display_name(state, &handle).await;
+    let trackers = readd_trackers(state, &handle).await;
+    let queued = state.download_queue.lock().await.contains(&id);
+    let output_folder = readd_folder(state, &handle).await;
+
+    session
+        .delete(librqbit::api::TorrentIdOrHash::Id(id), false)
+        .await
+        .map_err(|e| WhenThenError::torrent("Failed to delete torrent for re-add", e))?;
+
+    state.torrent_names.write().await.remove(&id);
+
+    let add_opts = AddTorrentOptions {
+        output_folder: Some(output_folder),
+        only_files,
+        trackers,
+        paused: queued,
+        overwrite: true,
+        ..Default::default()
+    };
+
+    let response = session
+        .add_torrent(
+            AddTorrent::TorrentFileBytes(torrent_bytes),
+            Some(add_opts),
+        )
+        .await
+        .map_err(|e| WhenThenError::torrent("Failed to re-add torrent", e))?;
+
+    let new_handle = match response {
+        AddTorrentResponse::Added(_, h) => h,
+        AddTorrentResponse::AlreadyManaged(_, h) => h,
+        AddTorrentResponse::ListOnly(_) => {
+            return Err(WhenThenError::Torrent("Torrent re-added in list-only mode".into()));
+        }
+    };
+
+    let new_id = new_handle.id();
+    let info_hash = new_handle.info_hash().as_string();
+
+    let external_id = track_readd(state, app_handle, id, new_id, &name).await;
+
+    let files = build_file_list(&new_handle, &state.media_server);
+
+    let result = TorrentAddedResponse {
+        id: external_id,
+        name,
+        info_hash,
+        files,
+        already_existed: false,
+    };
+
+    watch_progress(state, new_id).await;
+    debug!(external_id, old_id = id, new_id, "Torrent re-added");
+
+    Ok(result)
+}
+
+/// Which files of a torrent are fully downloaded, by index.
+fn completed_files(handle: &Arc<librqbit::ManagedTorrent>) -> Vec<bool> {
+    let progress = handle.stats().file_progress;
+    file_details(handle)
+        .iter()
+        .enumerate()
+        .map(|(idx, (_, len))| progress.get(idx).copied().unwrap_or(0) >= *len)
+        .collect()
+}
+
+#[derive(serde::Serialize, Clone)]
+struct FileOrderAdvanced {
+    id: usize,
+    position: usize,
+    current_file: Option<usize>,
+    done: bool,
+}
+
+/// Download a torrent's files in `order`, one after another. Files already
+/// downloaded are skipped; the rest of the selection follows the last one.
+pub async fn set_file_order(
+    state: &AppState,
+    app_handle: &AppHandle,
+    id: usize,
+    order: Vec<usize>,
+) -> Result<FileOrderStatus> {
+    let internal = resolve_id(state, id).await;
+    let session = state.torrent_session.read().await.clone().ok_or(WhenThenError::SessionNotReady)?;
+    let handle = session
+        .get(librqbit::api::TorrentIdOrHash::Id(internal))
+        .ok_or(WhenThenError::TorrentNotFound(internal))?;
+
+    let complete = completed_files(&handle);
+    file_order::validate(&order, complete.len())?;
+    let info_hash = handle.info_hash().as_string();
+
+    // An order replacing another keeps the selection from before the first
+    let restore = match state.file_orders.read().await.get(&info_hash) {
+        Some(existing) => existing.restore.clone(),
+        None => handle.only_files().unwrap_or_else(|| (0..complete.len()).collect()),
+    };
+    let mut entry = FileOrder { order, position: 0, restore };
+    entry.position = file_order::next_position(&entry.order, 0, |i| complete[i]);
+    let status = entry.status();
+
+    if entry.position == entry.order.len() {
+        state.file_orders.write().await.remove(&info_hash);
+        file_order::persist(app_handle, state).await;
+        update_torrent_files(state, app_handle, id, entry.full_selection()).await?;
+    } else {
+        let selection = entry.selection();
+        state.file_orders.write().await.insert(&info_hash, entry);
+        file_order::persist(app_handle, state).await;
+        update_torrent_files(state, app_handle, id, selection).await?;
+    }
+    info!(id, position = status.position, "Torrent file order set");
+    Ok(status)
+}
+
+/// Stop downloading in order and put the full selection back.
+pub async fn cancel_file_order(state: &AppState, app_handle: &AppHandle, id: usize) -> Result<()> {
+    let internal = resolve_id(state, id).await;
+    let session = state.torrent_session.read().await.clone().ok_or(WhenThenError::SessionNotReady)?;
+    let handle = session
+        .get(librqbit::api::TorrentIdOrHash::Id(internal))
+        .ok_or(WhenThenError::TorrentNotFound(internal))?;
+
+    let Some(entry) = state.file_orders.write().await.remove(&handle.info_hash().as_string()) else {
+        return Ok(());
+    };
+    file_order::persist(app_handle, state).await;
+    update_torrent_files(state, app_handle, id, entry.full_selection()).await?;
+    info!(id, "Torrent file order canceled");
+    Ok(())
+}
+
+/// The files selected so far are done: move on to the next unfinished one,
+/// or restore the full selection after the last.
+async fn advance_file_order(app_handle: AppHandle, torrent_id: usize, external_id: usize) {
+    let state = app_handle.state::<AppState>();
+    let Some(session) = state.torrent_session.read().await.clone() else {
+        return;
+    };
+    let Some(handle) = session.get(librqbit::api::TorrentIdOrHash::Id(torrent_id)) else {
+        return;
+    };
+    let info_hash = handle.info_hash().as_string();
+    let complete = completed_files(&handle);
+
+    let (selection, event) = {
+        let mut orders = state.file_orders.write().await;
+        let Some(mut entry) = orders.get(&info_hash).cloned() else {
+            return;
+        };
+        entry.position =
+            file_order::next_position(&entry.order, entry.position, |i| complete.get(i).copied().unwrap_or(false));
+        let done = entry.position == entry.order.len();
+        let event = FileOrderAdvanced {
+            id: external_id,
+            position: entry.position,
+            current_file: entry.order.get(entry.position).copied(),
+            done,
+        };
+        if done {
+            orders.remove(&info_hash);
+            (entry.full_selection(), event)
+        } else {
+            let selection = entry.selection();
+            orders.insert(&info_hash, entry);
+            (selection, event)
+        }
+    };
+    file_order::persist(&app_handle, &state).await;
+
+    info!(external_id, position = event.position, done = event.done, "Advancing torrent file order");
+    if let Err(e) = update_torrent_files(&state, &app_handle, external_id, selection).await {
+        warn!(external_id, error = %e, "Failed to widen file selection for file order");
+        return;
+    }
+    app_handle.emit("torrent:file-order-advanced", &event).unwrap_or_default();
+}
+
+/// Trackers for a delete + re-add. The re-added bytes only carry the
+/// .torrent's own announce list, so the torrent's current trackers are passed
+/// along; the configured defaults stand in when it has none.
+async fn readd_trackers(state: &AppState, handle: &Arc<librqbit::ManagedTorrent>) -> Option<Vec<String>> {
+    let current: Vec<String> = handle.shared().trackers.iter().map(|url| url.to_string()).collect();
+    let defaults = state.config.read().await.default_trackers.clone();
+    trackers_to_keep(current, defaults)
+}
+
+fn trackers_to_keep(current: Vec<String>, defaults: Vec<String>) -> Option<Vec<String>> {
+    let trackers = if current.is_empty() { defaults } else { current };
+    (!trackers.is_empty()).then_some(trackers)
+}
+
+/// Trackers the torrent announces to, from the magnet or .torrent plus any added later.
+fn tracker_list(handle: &Arc<librqbit::ManagedTorrent>) -> Vec<TorrentTracker> {
+    let mut trackers: Vec<TorrentTracker> = handle
+        .shared()
+        .trackers
+        .iter()
+        .map(|url| TorrentTracker { url: url.to_string() })
+        .collect();
+    trackers.sort_by(|a, b| a.url.cmp(&b.url));
+    trackers
+}
+
+pub async fn get_torrent_trackers(state: &AppState, id: usize) -> Result<Vec<TorrentTracker>> {
+    let id = resolve_id(state, id).await;
+    let session = {
+        let guard = state.torrent_session.read().await;
+        guard.as_ref().ok_or_else(|| {
+            WhenThenError::SessionNotReady
+        })?.clone()
+    };
+
+    let handle = session
+        .get(librqbit::api::TorrentIdOrHash::Id(id))
+        .ok_or(WhenThenError::TorrentNotFound(id))?;
+
+    Ok(tracker_list(&handle))
+}
+
+/// Per-peer byte counters from the previous peer list request, to turn into rates.
+#[derive(Default)]
+pub struct PeerSample {
+    at: Option<std::time::Instant>,
+    bytes: HashMap<String, (u64, u64)>,
+}
+
+/// One peer as read from librqbit's per-peer stats snapshot.
+#[derive(Debug, PartialEq)]
+struct RawPeer {
+    address: String,
+    state: String,
+    downloaded: u64,
+    uploaded: u64,
+}
+
+/// Read peers out of a serialized per-peer snapshot. The snapshot type isn't
+/// stable across librqbit releases, so it's read loosely; None means the
+/// shape wasn't recognised.
+fn parse_peer_snapshot(snapshot: &serde_json::Value) -> Option<Vec<RawPeer>> {
+    let peers = snapshot.get("peers")?.as_object()?;
+    Some(
+        peers
+            .iter()
+            .map(|(address, peer)| {
+                let counter = |name: &str| {
+                    peer.get("counters")
+                        .and_then(|c| c.get(name))
+                        .and_then(|v| v.as_u64())
+                        .unwrap_or(0)
+                };
+                RawPeer {
+                    address: address.clone(),
+                    state: peer.get("state").and_then(|s| s.as_str()).unwrap_or("unknown").to_string(),
+                    downloaded: counter("fetched_bytes"),
+                    uploaded: counter("uploaded_bytes"),
+                }
+            })
+            .collect(),
+    )
+}
+
+/// Peers of a live torrent with rates since the previous call. Paused torrents
+/// have no peers; if librqbit's per-peer snapshot can't be read, only the
+/// connected count is returned.
+pub async fn get_torrent_peers(state: &AppState, id: usize) -> Result<TorrentPeers> {
+    let id = resolve_id(state, id).await;
+    let session = {
+        let guard = state.torrent_session.read().await;
+        guard.as_ref().ok_or_else(|| {
+            WhenThenError::SessionNotReady
+        })?.clone()
+    };
+
+    let handle = session
+        .get(librqbit::api::TorrentIdOrHash::Id(id))
+        .ok_or(WhenThenError::TorrentNotFound(id))?;
+
+    let Some(live) = handle.live() else {
+        state.peer_samples.lock().await.remove(&id);
+        return Ok(TorrentPeers::default());
+    };
+    let connected = handle
+        .stats()
+        .live
+        .map(|live| live.snapshot.peer_stats.live)
+        .unwrap_or(0);
+
+    let snapshot = live.per_peer_stats_snapshot(librqbit::api::PeerStatsFilter::default());
+    let Some(raw) = serde_json::to_value(&snapshot).ok().as_ref().and_then(parse_peer_snapshot) else {
+        return Ok(TorrentPeers {
+            peers: Vec::new(),
+            connected,
+            details_available: false,
+        });
+    };
+
+    let now = std::time::Instant::now();
+    let mut samples = state.peer_samples.lock().await;
+    let sample = samples.entry(id).or_default();
+    let elapsed = sample
+        .at
+        .map(|at| now.duration_since(at).as_secs_f64())
+        .filter(|secs| *secs > 0.0);
+    let rate = |bytes: u64, prev: Option<u64>| match (elapsed, prev) {
+        (Some(secs), Some(prev)) => (bytes.saturating_sub(prev) as f64 / secs) as u64,
+        _ => 0,
+    };
+
+    let mut peers: Vec<TorrentPeer> = raw
+        .iter()
+        .map(|peer| {
+            let prev = sample.bytes.get(&peer.address);
+            TorrentPeer {
+                address: peer.address.clone(),
+                state: peer.state.clone(),
+                client: None,
+                download_speed: rate(peer.downloaded, prev.map(|p| p.0)),
+                upload_speed: rate(peer.uploaded, prev.map(|p| p.1)),
+                downloaded_bytes: peer.downloaded,
+                progress: None,
+            }
+        })
+        .collect();
+    peers.sort_by(|a, b| b.download_speed.cmp(&a.download_speed).then(b.downloaded_bytes.cmp(&a.downloaded_bytes)));
+
+    sample.at = Some(now);
+    sample.bytes = raw
+        .into_iter()
+        .map(|peer| (peer.address, (peer.downloaded, peer.uploaded)))
+        .collect();
+
+    Ok(TorrentPeers {
+        peers,
+        connected,
+        details_available: true,
+    })
+}
+
+/// librqbit can't add trackers to a running torrent, so this uses delete + re-add
+/// like update_torrent_files, keeping the file selection and moved location.
+pub async fn add_torrent_tracker(
+    state: &AppState,
+    app_handle: &AppHandle,
+    id: usize,
+    tracker_url: String,
+) -> Result<TorrentAddedResponse> {
+    let id = resolve_id(state, id).await;
+    let tracker_url = tracker_url.trim().to_string();
+    let parsed = reqwest::Url::parse(&tracker_url)
+        .map_err(|e| WhenThenError::InvalidInput(format!("Invalid tracker URL: {e}")))?;
+    if !matches!(parsed.scheme(), "http" | "https" | "udp" | "ws" | "wss") {
+        return Err(WhenThenError::InvalidInput(format!(
+            "Unsupported tracker scheme: {}",
+            parsed.scheme()
+        )));
+    }
+
+    let session = {
+        let guard = state.torrent_session.read().await;
+        guard.as_ref().ok_or_else(|| {
+            WhenThenError::SessionNotReady
+        })?.clone()
+    };
+
+    let handle = session
+        .get(librqbit::api::TorrentIdOrHash::Id(id))
+        .ok_or(WhenThenError::TorrentNotFound(id))?;
+
+    let mut trackers: Vec<String> = tracker_list(&handle).into_iter().map(|t| t.url).collect();
+    if trackers.iter().any(|t| *t == parsed.as_str() || *t == tracker_url) {
+        return Err(WhenThenError::InvalidInput("Torrent already uses this tracker".into()));
+    }
+    trackers.push(tracker_url);
+
+    let torrent_bytes = handle
+        .with_metadata(|m| m.torrent_bytes.clone())
+        .map_err(|e| WhenThenError::torrent("Cannot read torrent metadata", e))?;
+
+    let name = display_name(state, &handle).await;
+    let only_files = handle.only_files();
+    let paused = matches!(handle.stats().state, librqbit::TorrentStatsState::Paused);
+    let location = readd_folder(state, &handle).await;
 
     session
         .delete(librqbit::api::TorrentIdOrHash::Id(id), false)
         .await
-        .map_err(|e| WhenThenError::Torrent(format!("Failed to delete torrent for file update: {e}")))?;
+        .map_err(|e| WhenThenError::torrent("Failed to delete torrent for tracker update", e))?;
 
     state.torrent_names.write().await.remove(&id);
 
     let add_opts = AddTorrentOptions {
-        only_files: Some(only_files.into_iter().collect()),
+        only_files,
+        output_folder: Some(location),
+        trackers: Some(trackers),
+        paused,
         overwrite: true,
         ..Default::default()
     };
@@ -1225,7 +2784,7 @@ pub async fn update_torrent_files(
             Some(add_opts),
         )
         .await
-        .map_err(|e| WhenThenError::Torrent(format!("Failed to re-add torrent with new file selection: {e}")))?;
+        .map_err(|e| WhenThenError::torrent("Failed to re-add torrent with new tracker", e))?;
 
     let new_handle = match response {
         AddTorrentResponse::Added(_, h) => h,
@@ -1238,39 +2797,250 @@ pub async fn update_torrent_files(
     let new_id = new_handle.id();
     let info_hash = new_handle.info_hash().as_string();
 
-    state.torrent_names.write().await.insert(new_id, name.clone());
+    let external_id = track_readd(state, app_handle, id, new_id, &name).await;
 
-    let media_server_port = state.media_server.port;
-    let local_ip = get_local_ip();
-    let files = build_file_list(&new_handle, &local_ip, media_server_port);
+    let files = build_file_list(&new_handle, &state.media_server);
 
     let result = TorrentAddedResponse {
-        id: new_id,
+        id: external_id,
         name: name.clone(),
         info_hash,
         files,
+        already_existed: false,
     };
 
-    spawn_progress_emitter(state, app_handle.clone(), new_id);
+    watch_progress(state, new_id).await;
 
     #[derive(serde::Serialize, Clone)]
-    struct TorrentFilesUpdated {
+    struct TorrentTrackersUpdated {
         old_id: usize,
         new_id: usize,
         name: String,
     }
 
     app_handle
-        .emit("torrent:files-updated", &TorrentFilesUpdated { old_id: id, new_id, name })
+        .emit("torrent:trackers-updated", &TorrentTrackersUpdated { old_id: external_id, new_id: external_id, name })
         .unwrap_or_default();
 
-    info!(old_id = id, new_id, "Torrent file selection updated");
+    info!(external_id, old_id = id, new_id, "Torrent tracker added");
 
     Ok(result)
 }
 
+/// librqbit doesn't report the port it bound, so look for a listener in the
+/// range it tries.
+pub async fn bound_listen_port(first: u16) -> Option<u16> {
+    for port in listen_ports(first) {
+        let addr = std::net::SocketAddr::from(([127, 0, 0, 1], port));
+        if let Ok(Ok(_)) = tokio::time::timeout(std::time::Duration::from_millis(300), tokio::net::TcpStream::connect(addr)).await {
+            return Some(port);
+        }
+    }
+    None
+}
+
+/// Nodes in the DHT routing table, when DHT is running and reports it.
+pub fn dht_node_count(session: &Session) -> Option<u64> {
+    // Read loosely; the stats shape isn't part of librqbit's stable API
+    let dht = session.get_dht()?;
+    serde_json::to_value(dht.stats())
+        .ok()
+        .and_then(|stats| stats.get("routing_table_size").and_then(|n| n.as_u64()))
+}
+
 pub fn get_local_ip() -> String {
     local_ip_address::local_ip()
         .map(|ip| ip.to_string())
         .unwrap_or_else(|_| "127.0.0.1".to_string())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_peer_snapshot_loosely() {
+        let snapshot = serde_json::json!({
+            "peers": {
+                "10.0.0.2:51413": {
+                    "counters": { "fetched_bytes": 2048, "errors": 0 },
+                    "state": "live"
+                },
+                "10.0.0.3:6881": { "state": "connecting" }
+            }
+        });
+        let mut peers = parse_peer_snapshot(&snapshot).unwrap();
+        peers.sort_by(|a, b| a.address.cmp(&b.address));
+        assert_eq!(
+            peers[0],
+            RawPeer { address: "10.0.0.2:51413".into(), state: "live".into(), downloaded: 2048, uploaded: 0 }
+        );
+        assert_eq!(peers[1].state, "connecting");
+        assert!(parse_peer_snapshot(&serde_json::json!({ "other": 1 })).is_none());
+    }
+
+    fn summary(id: usize, progress: f64) -> TorrentSummary {
+        TorrentSummary {
+            id,
+            name: format!("Torrent {id}"),
+            info_hash: format!("{id:040x}"),
+            state: TorrentState::Downloading,
+            progress,
+            download_speed: 0,
+            upload_speed: 0,
+            peers_connected: 0,
+            queued_peers: 0,
+            connecting_peers: 0,
+            total_bytes: 100,
+            downloaded_bytes: (progress * 100.0) as u64,
+            uploaded_bytes: 0,
+            file_count: 1,
+            label: None,
+            eta_secs: None,
+            availability: None,
+        }
+    }
+
+    #[test]
+    fn speed_average_damps_spikes() {
+        let mut average = smooth_speed(None, 1000);
+        assert_eq!(average, 1000.0);
+        average = smooth_speed(Some(average), 6000);
+        assert_eq!(average, 2000.0);
+        for _ in 0..50 {
+            average = smooth_speed(Some(average), 0);
+        }
+        assert!(average < 1.0);
+    }
+
+    #[test]
+    fn eta_needs_speed_and_is_zero_when_done() {
+        assert_eq!(eta_secs(false, 1000, 0.0), None);
+        assert_eq!(eta_secs(false, 1000, 0.5), None);
+        assert_eq!(eta_secs(false, 1000, 300.0), Some(4));
+        assert_eq!(eta_secs(true, 1000, 0.0), Some(0));
+        assert_eq!(eta_secs(false, 0, 0.0), Some(0));
+    }
+
+    #[test]
+    fn deltas_carry_only_changed_and_removed_torrents() {
+        let mut previous = HashMap::new();
+        let first = vec![summary(1, 0.1), summary(2, 1.0), summary(3, 1.0)];
+        let delta = diff_summaries(&mut previous, first.iter());
+        assert_eq!(delta.changed.len(), 3);
+        assert!(delta.removed.is_empty());
+
+        // Steady state: nothing to send
+        let delta = diff_summaries(&mut previous, first.iter());
+        assert!(delta.changed.is_empty() && delta.removed.is_empty());
+
+        let second = vec![summary(1, 0.2), summary(2, 1.0)];
+        let delta = diff_summaries(&mut previous, second.iter());
+        assert_eq!(delta.changed, vec![summary(1, 0.2)]);
+        assert_eq!(delta.removed, vec![3]);
+        assert_eq!(previous.len(), 2);
+    }
+
+    #[test]
+    fn speed_jitter_is_not_a_change() {
+        assert_eq!(round_speed(0.0001), 0);
+        assert_eq!(round_speed(1.0), 1024 * 1024);
+        assert!(near_speed(1_000_000, 1_040_000));
+        assert!(!near_speed(1_000_000, 1_100_000));
+        assert!(!near_speed(0, 1024) && !near_speed(1024, 0));
+        assert!(near_eta(Some(600), Some(590)));
+        assert!(!near_eta(Some(600), Some(500)));
+        assert!(!near_eta(Some(5), Some(0)) && !near_eta(None, Some(600)));
+
+        // 200 idle seeds with a few hundred bytes/s of estimator trickle: with
+        // raw speeds compared exactly, every one was resent on every tick
+        let seeding = |tick: usize| -> Vec<TorrentSummary> {
+            (0..200)
+                .map(|id| TorrentSummary {
+                    state: TorrentState::Completed,
+                    upload_speed: round_speed((tick + id) as f64 % 3.0 * 0.0002),
+                    ..summary(id, 1.0)
+                })
+                .collect()
+        };
+        let mut previous = HashMap::new();
+        assert_eq!(diff_summaries(&mut previous, seeding(0).iter()).changed.len(), 200);
+        let resent: usize = (1..=20).map(|tick| diff_summaries(&mut previous, seeding(tick).iter()).changed.len()).sum();
+        assert_eq!(resent, 0);
+
+        // A real change still goes out
+        let mut faster = seeding(21);
+        faster[7].upload_speed = round_speed(0.5);
+        let delta = diff_summaries(&mut previous, faster.iter());
+        assert_eq!(delta.changed.len(), 1);
+        assert_eq!(delta.changed[0].id, 7);
+    }
+
+    fn trackers(urls: &[&str]) -> Vec<String> {
+        urls.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_has_room_keeps_reserve() {
+        assert!(has_room(10_000, 5_000, 2_000));
+        assert!(has_room(10_000, 8_000, 2_000));
+        assert!(!has_room(10_000, 8_001, 2_000));
+        // Less free space than the reserve itself
+        assert!(!has_room(1_000, 1, 2_000));
+        assert!(has_room(1_000, 0, 2_000));
+    }
+
+    #[test]
+    fn test_append_trackers_adds_missing() {
+        let magnet = "magnet:?xt=urn:btih:abc&dn=Test";
+        let result = append_trackers(magnet, &trackers(&["udp://a.example:80/announce"]));
+        assert_eq!(result, "magnet:?xt=urn:btih:abc&dn=Test&tr=udp%3A%2F%2Fa.example%3A80%2Fannounce");
+    }
+
+    #[test]
+    fn test_append_trackers_skips_encoded_existing() {
+        let magnet = "magnet:?xt=urn:btih:abc&tr=udp%3A%2F%2Fa.example%3A80%2Fannounce";
+        let result = append_trackers(
+            magnet,
+            &trackers(&["udp://a.example:80/announce", "udp://b.example:80/announce"]),
+        );
+        assert_eq!(result.matches("a.example").count(), 1);
+        assert!(result.ends_with("&tr=udp%3A%2F%2Fb.example%3A80%2Fannounce"));
+    }
+
+    #[test]
+    fn test_append_trackers_skips_unencoded_existing() {
+        let magnet = "magnet:?xt=urn:btih:abc&tr=udp://a.example:80/announce";
+        let result = append_trackers(magnet, &trackers(&["udp://a.example:80/announce"]));
+        assert_eq!(result, magnet);
+    }
+
+    #[test]
+    fn test_append_trackers_ignores_blank_and_duplicate_defaults() {
+        let magnet = "magnet:?xt=urn:btih:abc";
+        let result = append_trackers(
+            magnet,
+            &trackers(&["", "  ", "udp://a.example:80", "udp://a.example:80"]),
+        );
+        assert_eq!(result, "magnet:?xt=urn:btih:abc&tr=udp%3A%2F%2Fa.example%3A80");
+    }
+
+    #[test]
+    fn test_readds_keep_the_torrents_trackers() {
+        let current = trackers(&["udp://a.example:80"]);
+        let defaults = trackers(&["udp://d.example:80"]);
+        assert_eq!(trackers_to_keep(current.clone(), defaults.clone()), Some(current));
+        assert_eq!(trackers_to_keep(Vec::new(), defaults.clone()), Some(defaults));
+        assert_eq!(trackers_to_keep(Vec::new(), Vec::new()), None);
+    }
+
+    #[test]
+    fn test_contiguous_head_stops_at_first_missing_piece() {
+        // 10-byte pieces, file starting mid-piece at offset 15
+        let have = |piece: usize| piece != 3;
+        assert_eq!(contiguous_head(have, 10, 15, 100, 1000), 15);
+        assert_eq!(contiguous_head(have, 10, 15, 100, 10), 10);
+        assert_eq!(contiguous_head(|_| true, 10, 15, 100, 1000), 100);
+        assert_eq!(contiguous_head(|_| false, 10, 15, 100, 1000), 0);
+    }
+}
@@ -0,0 +1,88 @@
+// Stable torrent ids across delete + re-add.
+//
+// librqbit hands out a new id whenever a torrent is re-added (recheck, file
+// selection, tracker changes). The frontend keeps using the id the torrent was
+// first added under; this map translates between the two.
+
+use std::collections::HashMap;
+
+#[derive(Debug, Default)]
+pub struct TorrentIdMap {
+    /// Retired librqbit id -> current librqbit id
+    aliases: HashMap<usize, usize>,
+}
+
+impl TorrentIdMap {
+    /// Current librqbit id for any id the torrent has had.
+    pub fn resolve(&self, id: usize) -> usize {
+        self.aliases.get(&id).copied().unwrap_or(id)
+    }
+
+    /// The id the torrent was first added under. librqbit ids only grow,
+    /// so the original is the smallest alias.
+    pub fn external(&self, internal: usize) -> usize {
+        self.aliases
+            .iter()
+            .filter(|(_, current)| **current == internal)
+            .map(|(retired, _)| *retired)
+            .min()
+            .unwrap_or(internal)
+    }
+
+    /// Record a delete + re-add; returns the torrent's external id.
+    pub fn readded(&mut self, old_internal: usize, new_internal: usize) -> usize {
+        for current in self.aliases.values_mut() {
+            if *current == old_internal {
+                *current = new_internal;
+            }
+        }
+        self.aliases.insert(old_internal, new_internal);
+        self.external(new_internal)
+    }
+
+    /// Forget a deleted torrent.
+    pub fn remove(&mut self, internal: usize) {
+        self.aliases.retain(|_, current| *current != internal);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_untouched_ids_pass_through() {
+        let ids = TorrentIdMap::default();
+        assert_eq!(ids.resolve(4), 4);
+        assert_eq!(ids.external(4), 4);
+    }
+
+    #[test]
+    fn test_recheck_keeps_external_id() {
+        let mut ids = TorrentIdMap::default();
+        // Torrent 2 rechecked (re-added as 5), then its files changed (re-added as 9)
+        assert_eq!(ids.readded(2, 5), 2);
+        assert_eq!(ids.readded(5, 9), 2);
+
+        // Listing the session (internal ids) reports the original id
+        let listed: Vec<usize> = [1, 9].iter().map(|id| ids.external(*id)).collect();
+        assert_eq!(listed, vec![1, 2]);
+
+        // Old and new ids from the frontend reach the live torrent
+        assert_eq!(ids.resolve(2), 9);
+        assert_eq!(ids.resolve(5), 9);
+        assert_eq!(ids.resolve(9), 9);
+        assert_eq!(ids.resolve(1), 1);
+    }
+
+    #[test]
+    fn test_remove_drops_aliases() {
+        let mut ids = TorrentIdMap::default();
+        ids.readded(2, 5);
+        ids.readded(3, 6);
+        ids.remove(5);
+        assert_eq!(ids.resolve(2), 2);
+        assert_eq!(ids.resolve(3), 6);
+        assert_eq!(ids.external(6), 3);
+    }
+}
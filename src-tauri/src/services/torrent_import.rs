@@ -0,0 +1,166 @@
+// Import torrents seeded by another client (Transmission, qBittorrent) without
+// downloading them again.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use tauri::{AppHandle, Emitter};
+use tracing::{info, warn};
+
+use crate::errors::{Result, WhenThenError};
+use crate::models::{ImportReport, ImportStatus};
+use crate::services::torrent_engine;
+use crate::state::AppState;
+
+/// Import every .torrent file in `dir`, one at a time. Each torrent's data
+/// folder comes from `destination_map` (keyed by file name, with or without
+/// ".torrent"), falling back to the save path in the other client's resume data.
+pub async fn import_directory(
+    state: &AppState,
+    app_handle: &AppHandle,
+    dir: &Path,
+    destination_map: &HashMap<String, String>,
+) -> Result<Vec<ImportReport>> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map_err(|e| WhenThenError::FileNotFound(format!("{}: {e}", dir.display())))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("torrent"))
+        })
+        .collect();
+    files.sort();
+
+    let total = files.len();
+    info!("Importing {} torrents from {}", total, dir.display());
+    let mut reports = Vec::with_capacity(total);
+
+    for (index, path) in files.iter().enumerate() {
+        let file = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let _ = app_handle.emit(
+            "torrent:import-progress",
+            serde_json::json!({
+                "current": index + 1,
+                "total": total,
+                "file": file,
+            }),
+        );
+
+        let report = import_file(state, app_handle, path, &file, destination_map).await;
+        if let Some(error) = &report.error {
+            warn!("Failed to import {}: {}", file, error);
+        }
+        reports.push(report);
+    }
+
+    Ok(reports)
+}
+
+async fn import_file(
+    state: &AppState,
+    app_handle: &AppHandle,
+    path: &Path,
+    file: &str,
+    destination_map: &HashMap<String, String>,
+) -> ImportReport {
+    let mut report = ImportReport {
+        file: file.to_string(),
+        status: ImportStatus::Failed,
+        torrent_id: None,
+        name: None,
+        destination: None,
+        error: None,
+    };
+
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let Some(destination) = destination_map
+        .get(file)
+        .or_else(|| destination_map.get(&stem))
+        .cloned()
+        .or_else(|| resume_save_path(path))
+    else {
+        report.error = Some("No data folder in the destination map or the client's resume data".into());
+        return report;
+    };
+    report.destination = Some(destination.clone());
+
+    let bytes = match tokio::fs::read(path).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            report.error = Some(e.to_string());
+            return report;
+        }
+    };
+
+    match torrent_engine::import_torrent_bytes(state, app_handle, bytes, destination).await {
+        Ok((added, is_new)) => {
+            report.status = if is_new {
+                ImportStatus::Imported
+            } else {
+                ImportStatus::AlreadyManaged
+            };
+            report.torrent_id = Some(added.id);
+            report.name = Some(added.name);
+        }
+        Err(e) => report.error = Some(e.to_string()),
+    }
+    report
+}
+
+/// Save path from the resume data next to a client's .torrent file:
+/// qBittorrent keeps `<hash>.fastresume` beside it in BT_backup, Transmission
+/// keeps `<name>.resume` in a Resume folder beside its Torrents folder.
+fn resume_save_path(torrent: &Path) -> Option<String> {
+    let stem = torrent.file_stem()?.to_string_lossy().to_string();
+    let dir = torrent.parent()?;
+
+    if let Ok(data) = std::fs::read(dir.join(format!("{stem}.fastresume"))) {
+        if let Some(path) = bencode_str(&data, "qBt-savePath").or_else(|| bencode_str(&data, "save_path")) {
+            return Some(path);
+        }
+    }
+
+    let parent = dir.parent()?;
+    ["resume", "Resume"].iter().find_map(|name| {
+        let data = std::fs::read(parent.join(name).join(format!("{stem}.resume"))).ok()?;
+        bencode_str(&data, "destination")
+    })
+}
+
+/// Value of a top-level string key in a bencoded dictionary. Good enough for
+/// resume files, where the keys we want are unique.
+fn bencode_str(data: &[u8], key: &str) -> Option<String> {
+    let needle = format!("{}:{}", key.len(), key);
+    let start = data
+        .windows(needle.len())
+        .position(|w| w == needle.as_bytes())?
+        + needle.len();
+
+    let rest = &data[start..];
+    let colon = rest.iter().position(|&b| b == b':')?;
+    let len: usize = std::str::from_utf8(&rest[..colon]).ok()?.parse().ok()?;
+    let value = rest.get(colon + 1..colon + 1 + len)?;
+    let value = String::from_utf8_lossy(value).to_string();
+    (!value.is_empty()).then_some(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_bencoded_save_paths() {
+        let fastresume = b"d12:qBt-category0:12:qBt-savePath18:/Volumes/Media/TV/9:save_path18:/Volumes/Media/TV/e";
+        assert_eq!(bencode_str(fastresume, "qBt-savePath").as_deref(), Some("/Volumes/Media/TV/"));
+
+        let resume = b"d11:destination19:/Users/me/Downloads5:addedi1700000000ee";
+        assert_eq!(bencode_str(resume, "destination").as_deref(), Some("/Users/me/Downloads"));
+        assert_eq!(bencode_str(resume, "missing"), None);
+    }
+}
@@ -0,0 +1,102 @@
+// User-assigned torrent labels ("shows", "movies", ...).
+//
+// Keyed by info hash rather than torrent id, since ids change when a torrent
+// is rechecked or re-added.
+
+use std::collections::{BTreeSet, HashMap};
+
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
+use tracing::{info, warn};
+
+use crate::state::AppState;
+
+const LABELS_STORE: &str = "torrent_labels.json";
+
+#[derive(Debug, Default)]
+pub struct TorrentLabels {
+    by_hash: HashMap<String, String>,
+}
+
+/// Trimmed label, or None when it's blank.
+pub fn normalize(label: Option<&str>) -> Option<String> {
+    label.map(str::trim).filter(|l| !l.is_empty()).map(str::to_string)
+}
+
+impl TorrentLabels {
+    pub fn get(&self, info_hash: &str) -> Option<String> {
+        self.by_hash.get(info_hash).cloned()
+    }
+
+    /// Set or, with None, clear a torrent's label. Returns true if it changed.
+    pub fn set(&mut self, info_hash: &str, label: Option<String>) -> bool {
+        match label {
+            Some(label) => self.by_hash.insert(info_hash.to_string(), label.clone()) != Some(label),
+            None => self.by_hash.remove(info_hash).is_some(),
+        }
+    }
+
+    /// Every label in use, sorted and without duplicates.
+    pub fn labels(&self) -> Vec<String> {
+        self.by_hash.values().cloned().collect::<BTreeSet<_>>().into_iter().collect()
+    }
+}
+
+pub async fn persist(app: &AppHandle, state: &AppState) {
+    if let Ok(store) = app.store(LABELS_STORE) {
+        let labels = state.torrent_labels.read().await;
+        if let Ok(value) = serde_json::to_value(&labels.by_hash) {
+            store.set("labels", value);
+            if let Err(e) = store.save() {
+                warn!("Failed to save torrent labels: {}", e);
+            }
+        }
+    }
+}
+
+pub async fn load(app: &AppHandle, state: &AppState) {
+    if let Ok(store) = app.store(LABELS_STORE) {
+        if let Err(e) = store.reload() {
+            warn!("Could not load torrent labels store: {}", e);
+        }
+        if let Some(value) = store.get("labels") {
+            if let Ok(by_hash) = serde_json::from_value::<HashMap<String, String>>(value) {
+                info!("Loaded {} torrent labels from disk", by_hash.len());
+                // Labels set before the store was read are newer, so they win
+                let mut labels = state.torrent_labels.write().await;
+                for (info_hash, label) in by_hash {
+                    labels.by_hash.entry(info_hash).or_insert(label);
+                }
+            }
+        }
+    }
+}
+
+/// Label a torrent and save the change.
+pub async fn set_label(app: &AppHandle, state: &AppState, info_hash: &str, label: Option<String>) {
+    let changed = state.torrent_labels.write().await.set(info_hash, label);
+    if changed {
+        persist(app, state).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn labels_are_listed_once_and_cleared_by_none() {
+        let mut labels = TorrentLabels::default();
+        assert!(labels.set("a", Some("shows".into())));
+        assert!(!labels.set("a", Some("shows".into())));
+        labels.set("b", Some("movies".into()));
+        labels.set("c", Some("shows".into()));
+        assert_eq!(labels.labels(), vec!["movies", "shows"]);
+
+        assert!(labels.set("b", None));
+        assert_eq!(labels.get("b"), None);
+        assert_eq!(labels.labels(), vec!["shows"]);
+        assert_eq!(normalize(Some("  ")), None);
+        assert_eq!(normalize(Some(" linux ")), Some("linux".into()));
+    }
+}
@@ -0,0 +1,248 @@
+// Where each torrent's data lives on disk.
+//
+// librqbit doesn't report a torrent's output folder, so it's recorded here
+// when the torrent is added and updated whenever its files are moved. Keyed by
+// info hash so it survives re-adds and restarts. Torrents seeding data the
+// app didn't download, created from the user's own files or imported from
+// another client, are remembered too so that data is never deleted.
+//
+// librqbit puts a multi-file torrent under a folder named after it only when
+// no output folder is given; an explicit one is used as is. The recorded
+// folder is the one above the torrent's own, and torrents added into an
+// explicit folder are marked flat, so a re-add can hand librqbit the folder
+// it resolved the first time.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
+use tracing::{info, warn};
+
+use crate::services::torrent_engine::expand_path;
+use crate::state::AppState;
+
+const OUTPUTS_STORE: &str = "torrent_outputs.json";
+
+#[derive(Debug, Default)]
+pub struct TorrentOutputs {
+    by_hash: HashMap<String, String>,
+    /// Info hashes of torrents created from the user's files or imported
+    external: HashSet<String>,
+    /// Info hashes of torrents whose files sit straight in their folder
+    flat: HashSet<String>,
+}
+
+impl TorrentOutputs {
+    pub fn get(&self, info_hash: &str) -> Option<PathBuf> {
+        self.by_hash.get(info_hash).map(PathBuf::from)
+    }
+
+    pub fn contains(&self, info_hash: &str) -> bool {
+        self.by_hash.contains_key(info_hash)
+    }
+
+    pub fn is_external(&self, info_hash: &str) -> bool {
+        self.external.contains(info_hash)
+    }
+
+    /// Returns true if the torrent wasn't already marked.
+    pub(crate) fn mark_external(&mut self, info_hash: &str) -> bool {
+        self.external.insert(info_hash.to_string())
+    }
+
+    pub fn is_flat(&self, info_hash: &str) -> bool {
+        self.flat.contains(info_hash)
+    }
+
+    /// Returns true if the folder changed.
+    fn set(&mut self, info_hash: &str, folder: &Path) -> bool {
+        let folder = folder.to_string_lossy().to_string();
+        self.by_hash.insert(info_hash.to_string(), folder.clone()) != Some(folder)
+    }
+
+    /// Returns true if the layout changed.
+    fn set_flat(&mut self, info_hash: &str, flat: bool) -> bool {
+        if flat {
+            self.flat.insert(info_hash.to_string())
+        } else {
+            self.flat.remove(info_hash)
+        }
+    }
+}
+
+pub async fn persist(app: &AppHandle, state: &AppState) {
+    if let Ok(store) = app.store(OUTPUTS_STORE) {
+        let outputs = state.torrent_outputs.read().await;
+        if let (Ok(value), Ok(external), Ok(flat)) = (
+            serde_json::to_value(&outputs.by_hash),
+            serde_json::to_value(&outputs.external),
+            serde_json::to_value(&outputs.flat),
+        ) {
+            store.set("outputs", value);
+            store.set("external", external);
+            store.set("flat", flat);
+            if let Err(e) = store.save() {
+                warn!("Failed to save torrent output folders: {}", e);
+            }
+        }
+    }
+}
+
+/// Saved folders win over any defaults filled in before the store loaded.
+pub async fn load(app: &AppHandle, state: &AppState) {
+    if let Ok(store) = app.store(OUTPUTS_STORE) {
+        if let Err(e) = store.reload() {
+            warn!("Could not load torrent output folders store: {}", e);
+        }
+        if let Some(value) = store.get("outputs") {
+            if let Ok(by_hash) = serde_json::from_value::<HashMap<String, String>>(value) {
+                info!("Loaded {} torrent output folders from disk", by_hash.len());
+                state.torrent_outputs.write().await.by_hash.extend(by_hash);
+            }
+        }
+        if let Some(value) = store.get("external") {
+            if let Ok(external) = serde_json::from_value::<HashSet<String>>(value) {
+                state.torrent_outputs.write().await.external.extend(external);
+            }
+        }
+        if let Some(value) = store.get("flat") {
+            if let Ok(flat) = serde_json::from_value::<HashSet<String>>(value) {
+                state.torrent_outputs.write().await.flat.extend(flat);
+            }
+        }
+    }
+}
+
+/// Record where a torrent's data is and save the change.
+pub async fn record(app: &AppHandle, state: &AppState, info_hash: &str, folder: &Path) {
+    let changed = state.torrent_outputs.write().await.set(info_hash, folder);
+    if changed {
+        persist(app, state).await;
+    }
+}
+
+/// Record where a newly added torrent's data goes. `explicit` is whether
+/// librqbit was given the folder, and so uses it as is.
+pub async fn record_added(app: &AppHandle, state: &AppState, info_hash: &str, folder: &Path, explicit: bool) {
+    let changed = {
+        let mut outputs = state.torrent_outputs.write().await;
+        let moved = outputs.set(info_hash, folder);
+        outputs.set_flat(info_hash, explicit) || moved
+    };
+    if changed {
+        persist(app, state).await;
+    }
+}
+
+/// Record that a torrent seeds data the app didn't download and save it.
+pub async fn record_external(app: &AppHandle, state: &AppState, info_hash: &str) {
+    let changed = state.torrent_outputs.write().await.mark_external(info_hash);
+    if changed {
+        persist(app, state).await;
+    }
+}
+
+pub async fn forget(app: &AppHandle, state: &AppState, info_hash: &str) {
+    let removed = {
+        let mut outputs = state.torrent_outputs.write().await;
+        let external = outputs.external.remove(info_hash);
+        let flat = outputs.flat.remove(info_hash);
+        outputs.by_hash.remove(info_hash).is_some() || external || flat
+    };
+    if removed {
+        persist(app, state).await;
+    }
+}
+
+/// Folder new torrents download into when no output folder is given: the
+/// incomplete directory if one is set, otherwise the download directory.
+pub async fn default_folder(state: &AppState) -> PathBuf {
+    let cfg = state.config.read().await;
+    if cfg.incomplete_directory.is_empty() {
+        expand_path(&cfg.download_directory)
+    } else {
+        expand_path(&cfg.incomplete_directory)
+    }
+}
+
+/// A torrent's output folder. Torrents with nothing recorded are assumed to
+/// be in the download directory.
+pub async fn folder(state: &AppState, info_hash: &str) -> PathBuf {
+    if let Some(folder) = state.torrent_outputs.read().await.get(info_hash) {
+        return folder;
+    }
+    expand_path(&state.config.read().await.download_directory)
+}
+
+/// The output folder to give librqbit when re-adding a torrent: the one it
+/// resolved when the torrent was first added, since an explicit folder is
+/// used as is.
+pub fn add_folder(folder: &Path, torrent_name: &str, multi_file: bool, flat: bool) -> PathBuf {
+    if multi_file && !flat {
+        folder.join(torrent_name)
+    } else {
+        folder.to_path_buf()
+    }
+}
+
+/// On-disk path of a file inside a torrent's output folder. Multi-file
+/// torrents keep their files under a folder named after the torrent; single
+/// files sit in the output folder itself.
+pub fn file_path(folder: &Path, torrent_name: &str, relative: &str) -> PathBuf {
+    let nested = folder.join(torrent_name).join(relative);
+    if nested.exists() {
+        return nested;
+    }
+    folder.join(relative)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn files_are_found_with_or_without_the_torrent_folder() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().to_path_buf();
+        std::fs::create_dir_all(dir.join("Show S01").join("extras")).unwrap();
+        std::fs::write(dir.join("Show S01").join("extras").join("a.mkv"), b"").unwrap();
+
+        assert_eq!(file_path(&dir, "Show S01", "extras/a.mkv"), dir.join("Show S01").join("extras/a.mkv"));
+        assert_eq!(file_path(&dir, "movie.mkv", "movie.mkv"), dir.join("movie.mkv"));
+
+        let mut outputs = TorrentOutputs::default();
+        assert!(outputs.set("abc", &dir));
+        assert!(!outputs.set("abc", &dir));
+        assert_eq!(outputs.get("abc"), Some(dir.clone()));
+    }
+
+    #[test]
+    fn multi_file_readds_keep_the_torrent_folder() {
+        let downloads = Path::new("/downloads");
+        let mut outputs = TorrentOutputs::default();
+
+        // Added to the default folder: librqbit made downloads/Show S01
+        outputs.set("multi", downloads);
+        assert_eq!(
+            add_folder(downloads, "Show S01", true, outputs.is_flat("multi")),
+            downloads.join("Show S01")
+        );
+        // Single files sit in the folder either way
+        assert_eq!(add_folder(downloads, "movie.mkv", false, false), downloads);
+
+        // Added into an interest's folder, which librqbit used as is
+        let shows = Path::new("/media/shows");
+        outputs.set("explicit", shows);
+        assert!(outputs.set_flat("explicit", true));
+        assert_eq!(add_folder(shows, "Show S02", true, outputs.is_flat("explicit")), shows);
+
+        // Moved: the torrent folder comes along into the destination
+        let archive = Path::new("/archive");
+        outputs.set("multi", archive);
+        assert_eq!(
+            add_folder(&outputs.get("multi").unwrap(), "Show S01", true, outputs.is_flat("multi")),
+            archive.join("Show S01")
+        );
+    }
+}
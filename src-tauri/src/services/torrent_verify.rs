@@ -0,0 +1,316 @@
+// Hash-check a torrent's files on disk against its piece hashes, without
+// touching the session. Unlike a recheck the torrent keeps its id, trackers
+// and peers; this only reports what's good, corrupt or missing.
+
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use sha1::{Digest, Sha1};
+use tauri::{AppHandle, Emitter};
+use tracing::info;
+
+use crate::errors::{Result, WhenThenError};
+use crate::models::{FileVerifyResult, TorrentVerifyReport};
+use crate::services::{torrent_engine, torrent_outputs};
+use crate::state::AppState;
+
+/// Minimum time between progress events.
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Clone, Serialize)]
+struct VerifyProgress {
+    id: usize,
+    checked_pieces: usize,
+    total_pieces: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum PieceResult {
+    Good,
+    Bad,
+    Missing,
+}
+
+/// A torrent file placed in the torrent's byte stream.
+#[derive(Clone)]
+struct Layout {
+    path: PathBuf,
+    /// Offset of the file's first byte in the torrent
+    offset: u64,
+    len: u64,
+}
+
+/// Reads piece ranges out of a torrent's files, keeping each file open
+/// between pieces.
+struct PieceReader {
+    files: Vec<Layout>,
+    /// None until first opened; Some(None) when missing or too short
+    open: Vec<Option<Option<File>>>,
+}
+
+impl PieceReader {
+    fn new(files: Vec<Layout>) -> Self {
+        let open = files.iter().map(|_| None).collect();
+        Self { files, open }
+    }
+
+    /// Indices of the files overlapping `start..end`.
+    fn overlapping(&self, start: u64, end: u64) -> impl Iterator<Item = usize> + '_ {
+        self.files
+            .iter()
+            .enumerate()
+            .filter(move |(_, f)| f.len > 0 && f.offset < end && f.offset + f.len > start)
+            .map(|(i, _)| i)
+    }
+
+    /// Fill `buf` with the torrent bytes starting at `start`. False if any
+    /// file the range covers is missing or shorter than it should be.
+    fn read(&mut self, start: u64, buf: &mut [u8]) -> bool {
+        let end = start + buf.len() as u64;
+        let indices: Vec<usize> = self.overlapping(start, end).collect();
+        for i in indices {
+            let (offset, len) = (self.files[i].offset, self.files[i].len);
+            let from = start.max(offset);
+            let to = end.min(offset + len);
+            let slot = &mut self.open[i];
+            if slot.is_none() {
+                let path = &self.files[i].path;
+                let file = File::open(path)
+                    .ok()
+                    .filter(|f| f.metadata().is_ok_and(|m| m.len() >= len));
+                *slot = Some(file);
+            }
+            let Some(Some(file)) = slot.as_mut() else {
+                return false;
+            };
+            let target = &mut buf[(from - start) as usize..(to - start) as usize];
+            if file.seek(SeekFrom::Start(from - offset)).is_err() || file.read_exact(target).is_err() {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// Check every piece in order, calling `progress` with the number checked so
+/// far. Stops early when `cancel` is set.
+fn check_pieces(
+    reader: &mut PieceReader,
+    hashes: &[u8],
+    piece_length: u64,
+    total_len: u64,
+    cancel: &AtomicBool,
+    mut progress: impl FnMut(usize),
+) -> Vec<PieceResult> {
+    let total_pieces = hashes.len() / 20;
+    let mut results = Vec::with_capacity(total_pieces);
+    let mut buf = vec![0u8; piece_length as usize];
+
+    for piece in 0..total_pieces {
+        if cancel.load(Ordering::Relaxed) {
+            break;
+        }
+        let start = piece as u64 * piece_length;
+        let len = piece_length.min(total_len.saturating_sub(start)) as usize;
+        let data = &mut buf[..len];
+        let result = if !reader.read(start, data) {
+            PieceResult::Missing
+        } else if Sha1::digest(&*data).as_slice() == &hashes[piece * 20..piece * 20 + 20] {
+            PieceResult::Good
+        } else {
+            PieceResult::Bad
+        };
+        results.push(result);
+        progress(piece + 1);
+    }
+    results
+}
+
+/// Tally piece results overall and per file. Pieces not reached before a
+/// cancel count toward neither.
+fn report(
+    id: usize,
+    files: &[(String, Layout)],
+    results: &[PieceResult],
+    piece_length: u64,
+    total_pieces: usize,
+    cancelled: bool,
+) -> TorrentVerifyReport {
+    let count = |wanted: PieceResult| results.iter().filter(|r| **r == wanted).count();
+    let good_pieces = count(PieceResult::Good);
+
+    let files = files
+        .iter()
+        .enumerate()
+        .map(|(index, (path, layout))| {
+            let mut file = FileVerifyResult {
+                index,
+                path: path.clone(),
+                ..Default::default()
+            };
+            if layout.len == 0 {
+                return file;
+            }
+            let first = (layout.offset / piece_length) as usize;
+            let last = ((layout.offset + layout.len - 1) / piece_length) as usize;
+            for result in results.iter().take(last + 1).skip(first) {
+                match result {
+                    PieceResult::Good => file.good_pieces += 1,
+                    PieceResult::Bad => file.bad_pieces += 1,
+                    PieceResult::Missing => file.missing_pieces += 1,
+                }
+            }
+            file
+        })
+        .collect();
+
+    TorrentVerifyReport {
+        id,
+        total_pieces,
+        good_pieces,
+        bad_pieces: count(PieceResult::Bad),
+        missing_pieces: count(PieceResult::Missing),
+        percent_good: if total_pieces == 0 { 0.0 } else { good_pieces as f64 * 100.0 / total_pieces as f64 },
+        files,
+        cancelled,
+    }
+}
+
+/// Hash-check torrent `id`'s data, emitting torrent:verify-progress as it goes.
+pub async fn verify(state: &AppState, app_handle: &AppHandle, id: usize) -> Result<TorrentVerifyReport> {
+    let internal_id = torrent_engine::resolve_id(state, id).await;
+    let session = state
+        .torrent_session
+        .read()
+        .await
+        .clone()
+        .ok_or(WhenThenError::SessionNotReady)?;
+    let handle = session
+        .get(librqbit::api::TorrentIdOrHash::Id(internal_id))
+        .ok_or(WhenThenError::TorrentNotFound(id))?;
+
+    let (piece_length, hashes, relative) = handle
+        .with_metadata(|meta| {
+            let files: Vec<(String, u64)> = meta
+                .info
+                .iter_file_details()
+                .map(|iter| {
+                    iter.map(|fi| (fi.filename.to_string().unwrap_or_default(), fi.len))
+                        .collect()
+                })
+                .unwrap_or_default();
+            (meta.info.piece_length as u64, meta.info.pieces.as_ref().to_vec(), files)
+        })
+        .map_err(|e| WhenThenError::torrent("Cannot read torrent metadata", e))?;
+    if piece_length == 0 || hashes.len() % 20 != 0 {
+        return Err(WhenThenError::Torrent("Torrent metadata has no usable piece hashes".into()));
+    }
+
+    let name = handle.name().unwrap_or_else(|| "Unknown".to_string());
+    let folder = torrent_outputs::folder(state, &handle.info_hash().as_string()).await;
+    let mut offset = 0;
+    let files: Vec<(String, Layout)> = relative
+        .into_iter()
+        .map(|(path, len)| {
+            let layout = Layout {
+                path: torrent_outputs::file_path(&folder, &name, &path),
+                offset,
+                len,
+            };
+            offset += len;
+            (path, layout)
+        })
+        .collect();
+    let total_len = offset;
+
+    let cancel = Arc::new(AtomicBool::new(false));
+    {
+        let mut jobs = state.verify_jobs.lock().await;
+        if jobs.contains_key(&id) {
+            return Err(WhenThenError::InvalidInput("This torrent is already being verified".into()));
+        }
+        jobs.insert(id, cancel.clone());
+    }
+
+    info!(id, pieces = hashes.len() / 20, folder = %folder.display(), "Verifying torrent data");
+    let app = app_handle.clone();
+    let total_pieces = hashes.len() / 20;
+    let task = tokio::task::spawn_blocking(move || {
+        let mut reader = PieceReader::new(files.iter().map(|(_, l)| l.clone()).collect());
+        let mut last_emit = Instant::now();
+        let results = check_pieces(&mut reader, &hashes, piece_length, total_len, &cancel, |checked| {
+            if checked == total_pieces || last_emit.elapsed() >= PROGRESS_INTERVAL {
+                last_emit = Instant::now();
+                let _ = app.emit("torrent:verify-progress", VerifyProgress { id, checked_pieces: checked, total_pieces });
+            }
+        });
+        let cancelled = cancel.load(Ordering::Relaxed);
+        report(id, &files, &results, piece_length, total_pieces, cancelled)
+    })
+    .await;
+
+    state.verify_jobs.lock().await.remove(&id);
+    let report = task.map_err(|e| WhenThenError::Internal(format!("Verify task failed: {e}")))?;
+    info!(
+        id,
+        good = report.good_pieces,
+        bad = report.bad_pieces,
+        missing = report.missing_pieces,
+        cancelled = report.cancelled,
+        "Torrent verify finished"
+    );
+    Ok(report)
+}
+
+/// Stop a running verify. Returns false when none is running for `id`.
+pub async fn cancel(state: &AppState, id: usize) -> bool {
+    match state.verify_jobs.lock().await.get(&id) {
+        Some(flag) => {
+            flag.store(true, Ordering::Relaxed);
+            true
+        }
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pieces_across_file_boundaries_are_checked_and_attributed_to_both_files() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path().to_path_buf();
+        std::fs::write(dir.join("a"), b"abcdef").unwrap();
+        // The middle piece is corrupt on disk
+        std::fs::write(dir.join("b"), b"gXij").unwrap();
+
+        // Pieces of 4 bytes over "abcdefghij" plus a missing third file
+        let data = b"abcdefghij";
+        let mut hashes = Vec::new();
+        for chunk in data.chunks(4) {
+            hashes.extend_from_slice(&Sha1::digest(chunk));
+        }
+        hashes.extend_from_slice(&[0u8; 20]);
+        let layout = |name: &str, offset, len| Layout { path: dir.join(name), offset, len };
+        let files = vec![
+            ("a".to_string(), layout("a", 0, 6)),
+            ("b".to_string(), layout("b", 6, 4)),
+            ("c".to_string(), layout("c", 10, 2)),
+        ];
+        let mut reader = PieceReader::new(files.iter().map(|(_, l)| l.clone()).collect());
+        let results = check_pieces(&mut reader, &hashes, 4, 12, &AtomicBool::new(false), |_| {});
+        assert_eq!(results, vec![PieceResult::Good, PieceResult::Bad, PieceResult::Missing]);
+
+        let tally = report(1, &files, &results, 4, 3, false);
+        assert_eq!((tally.files[0].good_pieces, tally.files[0].bad_pieces), (1, 1));
+        assert_eq!((tally.files[1].bad_pieces, tally.files[1].missing_pieces), (1, 1));
+        assert_eq!(tally.files[2].missing_pieces, 1);
+        assert_eq!(tally.good_pieces, 1);
+    }
+}
@@ -0,0 +1,363 @@
+// Torznab client for Jackett/Prowlarr indexers. Results are mapped into feed
+// items so interest filters and the screener inbox treat them like RSS.
+
+use std::sync::{Arc, LazyLock};
+
+use regex::Regex;
+use tauri::AppHandle;
+use tokio::sync::RwLock;
+use tracing::{info, warn};
+
+use crate::errors::{Result, WhenThenError};
+use crate::models::{FeedAuth, Interest, Source, TorznabIndexer, TorznabSearchResult};
+use crate::services::rss::{
+    build_feed_client, check_on_schedule, parse_feed_entries, process_items_for_interest, search_term, CheckSchedule,
+    FeedFetchOptions, ParsedFeedItem, Scheduled,
+};
+use crate::state::AppState;
+
+pub struct TorznabState {
+    pub indexers: Arc<RwLock<Vec<TorznabIndexer>>>,
+}
+
+impl TorznabState {
+    pub fn new() -> Self {
+        Self {
+            indexers: Arc::new(RwLock::new(Vec::new())),
+        }
+    }
+}
+
+static ITEM_START_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"<item[\s>]").unwrap());
+
+static ATTR_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"<(?:torznab|newznab):attr\s+name="([^"]*)"\s+value="([^"]*)""#).unwrap()
+});
+
+static SIZE_TAG_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"<size>\s*(\d+)\s*</size>").unwrap());
+
+/// `<error code="100" description="Incorrect user credentials"/>`, sent instead of a feed.
+static ERROR_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"<error\s+code="(\d+)"\s+description="([^"]*)""#).unwrap());
+
+/// Values from an item's `torznab:attr` elements that feed-rs drops.
+#[derive(Debug, Default, PartialEq)]
+struct ItemAttrs {
+    magnet_uri: Option<String>,
+    size: Option<u64>,
+    seeders: Option<u32>,
+}
+
+/// The indexer's API endpoint. "/api" is appended unless the URL already ends with it.
+fn api_url(indexer: &TorznabIndexer) -> String {
+    let base = indexer.base_url.trim().trim_end_matches('/');
+    if base.ends_with("/api") {
+        base.to_string()
+    } else {
+        format!("{base}/api")
+    }
+}
+
+fn search_url(indexer: &TorznabIndexer, query: &str) -> String {
+    let mut url = format!(
+        "{}?t=search&apikey={}&q={}",
+        api_url(indexer),
+        urlencoding::encode(&indexer.api_key),
+        urlencoding::encode(query)
+    );
+    if !indexer.categories.is_empty() {
+        let cats: Vec<String> = indexer.categories.iter().map(|c| c.to_string()).collect();
+        url.push_str("&cat=");
+        url.push_str(&cats.join(","));
+    }
+    url
+}
+
+/// Run a search against an indexer.
+pub async fn search(indexer: &TorznabIndexer, query: &str) -> Result<Vec<ParsedFeedItem>> {
+    let client = build_feed_client(&FeedFetchOptions::default())?;
+    // reqwest errors quote the URL, which carries the API key
+    let response = client
+        .get(search_url(indexer, query))
+        .send()
+        .await
+        .map_err(|e| WhenThenError::Rss(e.without_url().to_string()))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        return Err(WhenThenError::Rss(format!("{} returned HTTP {}", indexer.name, status)));
+    }
+
+    let bytes = response
+        .bytes()
+        .await
+        .map_err(|e| WhenThenError::Rss(e.without_url().to_string()))?;
+    parse_results(&bytes)
+}
+
+/// Parse a Torznab response into feed items.
+fn parse_results(raw: &[u8]) -> Result<Vec<ParsedFeedItem>> {
+    let text = String::from_utf8_lossy(raw);
+    if let Some(caps) = ERROR_RE.captures(&text) {
+        return Err(WhenThenError::Rss(format!(
+            "Indexer error {}: {}",
+            &caps[1],
+            unescape_xml(&caps[2])
+        )));
+    }
+
+    let feed = feed_rs::parser::parse(raw)?;
+    let mut items = parse_feed_entries(feed, raw);
+
+    let attrs = attrs_per_item(&text);
+    if attrs.len() == items.len() {
+        for (item, attrs) in items.iter_mut().zip(attrs) {
+            if attrs.magnet_uri.is_some() {
+                item.magnet_uri = attrs.magnet_uri;
+            }
+            item.size = attrs.size.or(item.size);
+            item.seeders = attrs.seeders.or(item.seeders);
+        }
+    }
+
+    Ok(items)
+}
+
+/// Attributes of each `<item>` in a raw Torznab document, in order.
+fn attrs_per_item(xml: &str) -> Vec<ItemAttrs> {
+    let starts: Vec<usize> = ITEM_START_RE.find_iter(xml).map(|m| m.start()).collect();
+    starts
+        .iter()
+        .enumerate()
+        .map(|(i, &start)| {
+            let end = starts.get(i + 1).copied().unwrap_or(xml.len());
+            let block = &xml[start..end];
+            let mut attrs = ItemAttrs::default();
+            for caps in ATTR_RE.captures_iter(block) {
+                let value = unescape_xml(&caps[2]);
+                match &caps[1] {
+                    "magneturl" if value.starts_with("magnet:") => attrs.magnet_uri = Some(value),
+                    "size" => attrs.size = value.parse().ok(),
+                    "seeders" => attrs.seeders = value.parse().ok(),
+                    _ => {}
+                }
+            }
+            if attrs.size.is_none() {
+                attrs.size = SIZE_TAG_RE.captures(block).and_then(|c| c[1].parse().ok());
+            }
+            attrs
+        })
+        .collect()
+}
+
+fn unescape_xml(value: &str) -> String {
+    value
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Search an indexer and return the results for display.
+pub async fn search_results(indexer: &TorznabIndexer, query: &str) -> Result<Vec<TorznabSearchResult>> {
+    let items = search(indexer, query).await?;
+    Ok(items
+        .into_iter()
+        .map(|item| TorznabSearchResult {
+            title: item.title,
+            magnet_uri: item.magnet_uri,
+            torrent_url: item.torrent_url,
+            size: item.size,
+            seeders: item.seeders,
+            published_date: item.published_date,
+        })
+        .collect())
+}
+
+/// The indexer as a feed source, so matches go through the feed pipeline.
+fn as_source(indexer: &TorznabIndexer) -> Source {
+    Source {
+        id: indexer.id.clone(),
+        name: format!("{} (Torznab)", indexer.name),
+        url: api_url(indexer),
+        enabled: indexer.enabled,
+        check_interval: indexer.check_interval,
+        next_check_at: None,
+        use_guid_dedup: true,
+        etag: None,
+        last_modified: None,
+        failure_count: indexer.failure_count,
+        retry_after: None,
+        check_interval_minutes: 0,
+        last_checked: None,
+        auth: FeedAuth::default(),
+        user_agent: None,
+        request_timeout_secs: None,
+        last_error: None,
+        last_success_at: None,
+        items_seen_total: 0,
+        icon_path: None,
+    }
+}
+
+/// Search an indexer for each interest and queue matches. Fails only when
+/// no search succeeded at all.
+async fn check_indexer_for_matches(
+    app_handle: &AppHandle,
+    state: &AppState,
+    indexer: &TorznabIndexer,
+    interests: &[&Interest],
+) -> Result<usize> {
+    let source = as_source(indexer);
+    let mut matched_count = 0;
+    let mut first_error = None;
+    let mut any_fetched = false;
+
+    for interest in interests {
+        let query = search_term(interest);
+        info!("Searching {} for interest '{}'", indexer.name, interest.name);
+
+        match search(indexer, query).await {
+            Ok(items) => {
+                any_fetched = true;
+                let (count, _) =
+                    process_items_for_interest(app_handle, &state.rss_state, &source, interest, &items, true).await;
+                matched_count += count;
+            }
+            Err(e) => {
+                warn!("Failed to search {} for '{}': {}", indexer.name, interest.name, e);
+                if first_error.is_none() {
+                    first_error = Some(e);
+                }
+            }
+        }
+    }
+
+    match first_error {
+        Some(e) if !any_fetched => Err(e),
+        _ => Ok(matched_count),
+    }
+}
+
+impl Scheduled for TorznabIndexer {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    fn schedule(&mut self) -> CheckSchedule<'_> {
+        CheckSchedule {
+            check_interval: self.check_interval,
+            next_check_at: &mut self.next_check_at,
+            failure_count: &mut self.failure_count,
+            retry_after: &mut self.retry_after,
+            last_checked: &mut self.last_checked,
+            last_error: &mut self.last_error,
+        }
+    }
+}
+
+/// Search enabled indexers for interests on their schedules (see
+/// check_on_schedule). Returns the number of new matches.
+pub async fn check_indexers(
+    app_handle: &AppHandle,
+    state: &AppState,
+    interests: &[&Interest],
+    global_interval_mins: u32,
+    global_check_due: bool,
+    force: bool,
+) -> usize {
+    let checked = check_on_schedule(
+        app_handle,
+        &state.torznab_state.indexers,
+        "Indexer",
+        global_interval_mins,
+        global_check_due,
+        force,
+        |indexer| async move { check_indexer_for_matches(app_handle, state, &indexer, interests).await },
+    )
+    .await;
+    let Some(total_matched) = checked else {
+        return 0;
+    };
+    crate::commands::torznab::persist_indexers(app_handle, state).await;
+    crate::commands::rss::persist_seen_items(state);
+
+    total_matched
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn indexer(base_url: &str) -> TorznabIndexer {
+        TorznabIndexer {
+            id: "1".into(),
+            name: "Prowlarr".into(),
+            base_url: base_url.into(),
+            api_key: "k&y".into(),
+            enabled: true,
+            categories: vec![5000, 5040],
+            check_interval: None,
+            next_check_at: None,
+            failure_count: 0,
+            retry_after: None,
+            last_checked: None,
+            last_error: None,
+        }
+    }
+
+    #[test]
+    fn search_url_appends_api_once() {
+        assert_eq!(
+            search_url(&indexer("http://localhost:9696/1/"), "The Show"),
+            "http://localhost:9696/1/api?t=search&apikey=k%26y&q=The%20Show&cat=5000,5040"
+        );
+        assert!(search_url(&indexer("http://jackett/torznab/api"), "x").starts_with("http://jackett/torznab/api?"));
+    }
+
+    #[test]
+    fn parses_torznab_attrs() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0" xmlns:torznab="http://torznab.com/schemas/2015/feed">
+<channel><title>Prowlarr</title>
+<item>
+  <title>The.Show.S01E01.1080p</title>
+  <guid>abc</guid>
+  <link>http://localhost:9696/1/download?id=1</link>
+  <enclosure url="http://localhost:9696/1/download?id=1" length="1500000000" type="application/x-bittorrent"/>
+  <torznab:attr name="seeders" value="42"/>
+  <torznab:attr name="size" value="1500000000"/>
+  <torznab:attr name="magneturl" value="magnet:?xt=urn:btih:abc&amp;dn=The.Show"/>
+</item>
+<item>
+  <title>The.Show.S01E02.720p</title>
+  <guid>def</guid>
+  <size>700000000</size>
+</item>
+</channel></rss>"#;
+
+        let items = parse_results(xml.as_bytes()).unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].magnet_uri.as_deref(), Some("magnet:?xt=urn:btih:abc&dn=The.Show"));
+        assert_eq!(items[0].seeders, Some(42));
+        assert_eq!(items[0].size, Some(1_500_000_000));
+        assert_eq!(items[1].magnet_uri, None);
+        assert_eq!(items[1].size, Some(700_000_000));
+    }
+
+    #[test]
+    fn reports_indexer_errors() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?><error code="100" description="Invalid API Key"/>"#;
+        let err = parse_results(xml.as_bytes()).unwrap_err();
+        assert!(err.to_string().contains("Invalid API Key"));
+    }
+}
@@ -0,0 +1,331 @@
+// Tracker scrapes: how many seeders and leechers a tracker knows of for a
+// torrent, without joining the swarm. UDP trackers speak BEP 15 (connect,
+// then scrape with the connection id); HTTP trackers answer on the scrape URL
+// next to their announce URL (BEP 48).
+//
+// Only the first few trackers are asked, all at once and with a short
+// timeout, so a dead tracker list costs seconds rather than minutes.
+
+use std::time::Duration;
+
+use sha1::{Digest, Sha1};
+use tokio::net::UdpSocket;
+
+use crate::models::{TorrentHealth, TrackerScrape};
+use crate::services::rss::bencode_value_end;
+use crate::services::web_seeds::{byte_string, dict_entries};
+
+/// Magic constant every BEP 15 connect request starts with.
+const PROTOCOL_ID: u64 = 0x0417_2710_1980;
+const ACTION_CONNECT: u32 = 0;
+const ACTION_SCRAPE: u32 = 2;
+const ACTION_ERROR: u32 = 3;
+
+/// Trackers asked per check, in the order the torrent lists them.
+pub const MAX_TRACKERS: usize = 5;
+const SCRAPE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Counts a tracker reported for one torrent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScrapeCounts {
+    pub seeders: u32,
+    pub completed: u32,
+    pub leechers: u32,
+}
+
+pub fn connect_request(transaction_id: u32) -> [u8; 16] {
+    let mut packet = [0; 16];
+    packet[..8].copy_from_slice(&PROTOCOL_ID.to_be_bytes());
+    packet[8..12].copy_from_slice(&ACTION_CONNECT.to_be_bytes());
+    packet[12..].copy_from_slice(&transaction_id.to_be_bytes());
+    packet
+}
+
+/// The connection id from a connect response.
+pub fn parse_connect_response(packet: &[u8], transaction_id: u32) -> Result<u64, String> {
+    check_header(packet, ACTION_CONNECT, transaction_id)?;
+    let id = packet.get(8..16).ok_or("Connect response too short")?;
+    Ok(u64::from_be_bytes(id.try_into().unwrap()))
+}
+
+pub fn scrape_request(connection_id: u64, transaction_id: u32, info_hash: &[u8; 20]) -> [u8; 36] {
+    let mut packet = [0; 36];
+    packet[..8].copy_from_slice(&connection_id.to_be_bytes());
+    packet[8..12].copy_from_slice(&ACTION_SCRAPE.to_be_bytes());
+    packet[12..16].copy_from_slice(&transaction_id.to_be_bytes());
+    packet[16..].copy_from_slice(info_hash);
+    packet
+}
+
+/// Counts for the single info hash a scrape request asked about.
+pub fn parse_scrape_response(packet: &[u8], transaction_id: u32) -> Result<ScrapeCounts, String> {
+    check_header(packet, ACTION_SCRAPE, transaction_id)?;
+    let field = |offset: usize| {
+        packet
+            .get(offset..offset + 4)
+            .map(|b| u32::from_be_bytes(b.try_into().unwrap()))
+            .ok_or("Scrape response too short")
+    };
+    Ok(ScrapeCounts { seeders: field(8)?, completed: field(12)?, leechers: field(16)? })
+}
+
+/// Check a response's action and transaction id, turning the tracker's own
+/// error responses into their message.
+fn check_header(packet: &[u8], action: u32, transaction_id: u32) -> Result<(), String> {
+    if packet.len() < 8 {
+        return Err("Response too short".into());
+    }
+    let got_action = u32::from_be_bytes(packet[..4].try_into().unwrap());
+    let got_transaction = u32::from_be_bytes(packet[4..8].try_into().unwrap());
+    if got_transaction != transaction_id {
+        return Err("Response is for another request".into());
+    }
+    match got_action {
+        a if a == action => Ok(()),
+        ACTION_ERROR => Err(String::from_utf8_lossy(&packet[8..]).trim().to_string()),
+        other => Err(format!("Unexpected action {other}")),
+    }
+}
+
+/// The scrape URL for an HTTP announce URL, which by convention swaps
+/// "announce" at the start of the last path segment for "scrape". None for
+/// trackers that don't follow it and so can't be scraped.
+pub fn http_scrape_url(announce: &str) -> Option<String> {
+    let (path, query) = announce.split_once('?').map_or((announce, None), |(p, q)| (p, Some(q)));
+    let slash = path.rfind('/')?;
+    let rest = path[slash + 1..].strip_prefix("announce")?;
+    let mut url = format!("{}scrape{}", &path[..=slash], rest);
+    if let Some(query) = query {
+        url.push('?');
+        url.push_str(query);
+    }
+    Some(url)
+}
+
+/// Counts for `info_hash` in a bencoded HTTP scrape response.
+pub fn parse_http_scrape(body: &[u8], info_hash: &[u8; 20]) -> Result<ScrapeCounts, String> {
+    let entries = dict_entries(body).ok_or("Scrape response isn't bencoded")?;
+    if let Some(reason) = value(&entries, b"failure reason").and_then(byte_string) {
+        return Err(String::from_utf8_lossy(reason).into_owned());
+    }
+    let files = value(&entries, b"files").and_then(dict_entries).ok_or("Scrape response has no files")?;
+    let stats = value(&files, info_hash)
+        .and_then(dict_entries)
+        .ok_or("Tracker doesn't know this torrent")?;
+    let count = |key: &[u8]| value(&stats, key).and_then(bencode_int).unwrap_or(0);
+    Ok(ScrapeCounts { seeders: count(b"complete"), completed: count(b"downloaded"), leechers: count(b"incomplete") })
+}
+
+fn value<'a>(entries: &[(&[u8], &'a [u8])], key: &[u8]) -> Option<&'a [u8]> {
+    entries.iter().find(|(k, _)| *k == key).map(|(_, v)| *v)
+}
+
+fn bencode_int(raw: &[u8]) -> Option<u32> {
+    std::str::from_utf8(raw.strip_prefix(b"i")?.strip_suffix(b"e")?).ok()?.parse().ok()
+}
+
+/// The info hash, as lowercase hex, and trackers of a .torrent: `announce`
+/// followed by every tier of `announce-list`, without duplicates.
+pub fn torrent_trackers(metainfo: &[u8]) -> Option<(String, Vec<String>)> {
+    let entries = dict_entries(metainfo)?;
+    let info = entries.iter().find(|(key, _)| *key == b"info").map(|(_, v)| *v)?;
+    let info_hash = Sha1::digest(info).iter().map(|b| format!("{b:02x}")).collect();
+
+    let mut trackers: Vec<String> = Vec::new();
+    let mut push = |raw: &[u8]| {
+        if let Some(url) = byte_string(raw).and_then(|s| std::str::from_utf8(s).ok()) {
+            if !url.is_empty() && !trackers.iter().any(|t| t == url) {
+                trackers.push(url.to_string());
+            }
+        }
+    };
+    for (key, value) in &entries {
+        match *key {
+            b"announce" => push(*value),
+            b"announce-list" => {
+                for tier in list_items(value) {
+                    list_items(tier).into_iter().for_each(&mut push);
+                }
+            }
+            _ => {}
+        }
+    }
+    Some((info_hash, trackers))
+}
+
+/// Raw items of a bencoded list.
+fn list_items(list: &[u8]) -> Vec<&[u8]> {
+    if list.first() != Some(&b'l') {
+        return Vec::new();
+    }
+    let mut items = Vec::new();
+    let mut cur = 1;
+    while let Some(end) = list.get(cur).filter(|&&b| b != b'e').and_then(|_| bencode_value_end(list, cur, 1)) {
+        items.push(&list[cur..end]);
+        cur = end;
+    }
+    items
+}
+
+fn hash_bytes(hex: &str) -> Option<[u8; 20]> {
+    let mut bytes = [0; 20];
+    for (i, byte) in bytes.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(hex.get(i * 2..i * 2 + 2)?, 16).ok()?;
+    }
+    Some(bytes)
+}
+
+fn transaction_id() -> u32 {
+    uuid::Uuid::new_v4().as_u128() as u32
+}
+
+async fn scrape_udp(tracker: &str, info_hash: &[u8; 20]) -> Result<ScrapeCounts, String> {
+    let url = reqwest::Url::parse(tracker).map_err(|e| e.to_string())?;
+    let host = url.host_str().ok_or("Tracker URL has no host")?;
+    let port = url.port().ok_or("Tracker URL has no port")?;
+    let addr = tokio::net::lookup_host((host, port))
+        .await
+        .map_err(|e| e.to_string())?
+        .next()
+        .ok_or("Tracker host didn't resolve")?;
+    let socket = UdpSocket::bind(if addr.is_ipv6() { "[::]:0" } else { "0.0.0.0:0" })
+        .await
+        .map_err(|e| e.to_string())?;
+    socket.connect(addr).await.map_err(|e| e.to_string())?;
+
+    let mut buf = [0u8; 1024];
+    let id = transaction_id();
+    socket.send(&connect_request(id)).await.map_err(|e| e.to_string())?;
+    let len = socket.recv(&mut buf).await.map_err(|e| e.to_string())?;
+    let connection_id = parse_connect_response(&buf[..len], id)?;
+
+    let id = transaction_id();
+    socket.send(&scrape_request(connection_id, id, info_hash)).await.map_err(|e| e.to_string())?;
+    let len = socket.recv(&mut buf).await.map_err(|e| e.to_string())?;
+    parse_scrape_response(&buf[..len], id)
+}
+
+async fn scrape_http(tracker: &str, info_hash: &[u8; 20]) -> Result<ScrapeCounts, String> {
+    let url = http_scrape_url(tracker).ok_or("Tracker doesn't support scrapes")?;
+    let separator = if url.contains('?') { '&' } else { '?' };
+    let url = format!("{url}{separator}info_hash={}", urlencoding::encode_binary(info_hash));
+    let client = reqwest::Client::builder().timeout(SCRAPE_TIMEOUT).build().map_err(|e| e.to_string())?;
+    let response = client.get(url).send().await.and_then(|r| r.error_for_status()).map_err(|e| e.to_string())?;
+    let body = response.bytes().await.map_err(|e| e.to_string())?;
+    parse_http_scrape(&body, info_hash)
+}
+
+async fn scrape_one(tracker: String, info_hash: [u8; 20]) -> TrackerScrape {
+    let scrape = async {
+        match tracker.split_once("://").map(|(scheme, _)| scheme.to_ascii_lowercase()).as_deref() {
+            Some("udp") => scrape_udp(&tracker, &info_hash).await,
+            Some("http" | "https") => scrape_http(&tracker, &info_hash).await,
+            _ => Err("Unsupported tracker".to_string()),
+        }
+    };
+    let result = tokio::time::timeout(SCRAPE_TIMEOUT, scrape)
+        .await
+        .unwrap_or_else(|_| Err("Timed out".into()));
+    match result {
+        Ok(counts) => TrackerScrape {
+            tracker,
+            seeders: Some(counts.seeders),
+            leechers: Some(counts.leechers),
+            completed: Some(counts.completed),
+            error: None,
+        },
+        Err(error) => TrackerScrape { tracker, seeders: None, leechers: None, completed: None, error: Some(error) },
+    }
+}
+
+/// Scrape the first MAX_TRACKERS trackers at once. Results come back in
+/// tracker order, each with its counts or error.
+pub async fn scrape(trackers: &[String], info_hash: &str) -> Result<Vec<TrackerScrape>, String> {
+    let hash = hash_bytes(info_hash).ok_or_else(|| format!("Invalid info hash \"{info_hash}\""))?;
+    let handles: Vec<_> = trackers
+        .iter()
+        .take(MAX_TRACKERS)
+        .map(|tracker| tokio::spawn(scrape_one(tracker.clone(), hash)))
+        .collect();
+    let mut results = Vec::with_capacity(handles.len());
+    for handle in handles {
+        results.push(handle.await.map_err(|e| e.to_string())?);
+    }
+    Ok(results)
+}
+
+/// The highest counts among trackers that answered, or None if none did.
+pub fn aggregate(results: &[TrackerScrape], checked_at: String) -> Option<TorrentHealth> {
+    let answered = results.iter().filter(|r| r.error.is_none());
+    answered.fold(None, |best: Option<TorrentHealth>, r| {
+        let seeders = r.seeders.unwrap_or(0);
+        let leechers = r.leechers.unwrap_or(0);
+        Some(match best {
+            Some(b) => TorrentHealth { seeders: b.seeders.max(seeders), leechers: b.leechers.max(leechers), ..b },
+            None => TorrentHealth { seeders, leechers, checked_at: checked_at.clone() },
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HASH: &str = "c12fe1c06bba254a9dc9f519b335aa7c1367a88a";
+
+    #[test]
+    fn udp_exchange_matches_recorded_packets() {
+        assert_eq!(
+            connect_request(0x1234_5678),
+            [0x00, 0x00, 0x04, 0x17, 0x27, 0x10, 0x19, 0x80, 0, 0, 0, 0, 0x12, 0x34, 0x56, 0x78]
+        );
+        let connected = [0, 0, 0, 0, 0x12, 0x34, 0x56, 0x78, 0xa1, 0xb2, 0xc3, 0xd4, 0xe5, 0xf6, 0x07, 0x18];
+        assert_eq!(parse_connect_response(&connected, 0x1234_5678), Ok(0xa1b2_c3d4_e5f6_0718));
+        assert!(parse_connect_response(&connected, 0x1111_1111).is_err(), "another request's response");
+
+        let hash = hash_bytes(HASH).unwrap();
+        let request = scrape_request(0xa1b2_c3d4_e5f6_0718, 0x9abc_def0, &hash);
+        assert_eq!(request[..16], [0xa1, 0xb2, 0xc3, 0xd4, 0xe5, 0xf6, 0x07, 0x18, 0, 0, 0, 2, 0x9a, 0xbc, 0xde, 0xf0]);
+        assert_eq!(request[16..], hash);
+
+        let scraped = [0, 0, 0, 2, 0x9a, 0xbc, 0xde, 0xf0, 0, 0, 0, 0x7b, 0, 0, 0x04, 0xd2, 0, 0, 0, 0x2d];
+        assert_eq!(
+            parse_scrape_response(&scraped, 0x9abc_def0),
+            Ok(ScrapeCounts { seeders: 123, completed: 1234, leechers: 45 })
+        );
+        let mut error = vec![0, 0, 0, 3, 0x9a, 0xbc, 0xde, 0xf0];
+        error.extend_from_slice(b"unregistered torrent");
+        assert_eq!(parse_scrape_response(&error, 0x9abc_def0), Err("unregistered torrent".into()));
+        assert!(parse_scrape_response(&scraped[..12], 0x9abc_def0).is_err());
+    }
+
+    #[test]
+    fn http_scrapes_and_torrent_trackers() {
+        assert_eq!(http_scrape_url("http://t.example/announce").as_deref(), Some("http://t.example/scrape"));
+        assert_eq!(
+            http_scrape_url("https://t.example/x/announce.php?passkey=ab").as_deref(),
+            Some("https://t.example/x/scrape.php?passkey=ab")
+        );
+        assert_eq!(http_scrape_url("http://t.example/a"), None);
+
+        let hash = hash_bytes(HASH).unwrap();
+        let mut body = b"d5:filesd20:".to_vec();
+        body.extend_from_slice(&hash);
+        body.extend_from_slice(b"d8:completei123e10:downloadedi1234e10:incompletei45eeee");
+        assert_eq!(parse_http_scrape(&body, &hash), Ok(ScrapeCounts { seeders: 123, completed: 1234, leechers: 45 }));
+        assert_eq!(
+            parse_http_scrape(b"d14:failure reason20:unregistered torrente", &hash),
+            Err("unregistered torrent".into())
+        );
+        assert!(parse_http_scrape(b"d5:filesdee", &hash).is_err());
+
+        let torrent = b"d8:announce18:udp://t.example:8013:announce-listll18:udp://t.example:80el25:http://b.example/announceee4:infod4:name3:isoee";
+        assert_eq!(
+            torrent_trackers(torrent),
+            Some((
+                "6a404f55bf72785847ec939ecb922bfab5eea25a".into(),
+                vec!["udp://t.example:80".into(), "http://b.example/announce".into()]
+            ))
+        );
+    }
+}
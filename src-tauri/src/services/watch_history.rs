@@ -0,0 +1,149 @@
+// Playback history and resume positions.
+//
+// Entries are keyed by "<info_hash>:<file_index>" for torrent files and by the
+// absolute path for local files, so they survive torrent ids changing between
+// launches.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
+use tracing::{info, warn};
+
+use crate::models::WatchEntry;
+use crate::state::AppState;
+
+const HISTORY_STORE: &str = "watch_history.json";
+
+/// How often positions reported during playback are written to disk.
+const SAVE_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Watched this far through, the file counts as finished.
+const COMPLETED_FRACTION: f64 = 0.95;
+
+/// Positions this close to the start aren't worth resuming from.
+const MIN_RESUME_SECS: f64 = 10.0;
+
+#[derive(Debug, Default)]
+pub struct WatchHistory {
+    entries: HashMap<String, WatchEntry>,
+    /// Device id -> history key of what it's playing
+    playing: HashMap<String, String>,
+    last_saved: Option<Instant>,
+}
+
+pub fn torrent_key(info_hash: &str, file_index: usize) -> String {
+    format!("{info_hash}:{file_index}")
+}
+
+pub fn local_key(path: &str) -> String {
+    std::fs::canonicalize(path)
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_else(|_| path.to_string())
+}
+
+impl WatchHistory {
+    /// Remember what a device was just told to play.
+    pub fn start(&mut self, device_id: &str, key: String) {
+        self.playing.insert(device_id.to_string(), key);
+    }
+
+    /// Record a device's current position. Returns true when it's time to save.
+    pub fn record(&mut self, device_id: &str, position: f64, duration: f64) -> bool {
+        let Some(key) = self.playing.get(device_id) else {
+            return false;
+        };
+        // The receiver reports 0/0 while loading; don't overwrite a saved position with it
+        if duration <= 0.0 {
+            return false;
+        }
+        self.entries.insert(
+            key.clone(),
+            WatchEntry {
+                position_secs: position,
+                duration_secs: duration,
+                completed: position >= duration * COMPLETED_FRACTION,
+                updated_at: chrono::Utc::now().to_rfc3339(),
+            },
+        );
+        match self.last_saved {
+            Some(at) if at.elapsed() < SAVE_INTERVAL => false,
+            _ => {
+                self.last_saved = Some(Instant::now());
+                true
+            }
+        }
+    }
+
+    /// Record the final position and forget the device's current item.
+    pub fn stop(&mut self, device_id: &str, position: f64, duration: f64) {
+        self.record(device_id, position, duration);
+        self.playing.remove(device_id);
+    }
+
+    /// Where to pick up again, if the file was started but not finished.
+    pub fn resume_position(&self, key: &str) -> Option<f64> {
+        self.entries
+            .get(key)
+            .filter(|e| !e.completed && e.position_secs >= MIN_RESUME_SECS)
+            .map(|e| e.position_secs)
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+pub async fn persist(app: &AppHandle, state: &AppState) {
+    if let Ok(store) = app.store(HISTORY_STORE) {
+        let history = state.watch_history.read().await;
+        if let Ok(value) = serde_json::to_value(&history.entries) {
+            store.set("entries", value);
+            if let Err(e) = store.save() {
+                warn!("Failed to save watch history: {}", e);
+            }
+        }
+    }
+}
+
+pub async fn load(app: &AppHandle, state: &AppState) {
+    if let Ok(store) = app.store(HISTORY_STORE) {
+        if let Err(e) = store.reload() {
+            warn!("Could not load watch history store: {}", e);
+        }
+        if let Some(value) = store.get("entries") {
+            if let Ok(entries) = serde_json::from_value::<HashMap<String, WatchEntry>>(value) {
+                info!("Loaded {} watch history entries from disk", entries.len());
+                state.watch_history.write().await.entries = entries;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resume_position() {
+        let mut history = WatchHistory::default();
+        history.start("tv", torrent_key("abc", 0));
+        assert!(history.record("tv", 1200.0, 6000.0));
+        // Saves are throttled while playback continues
+        assert!(!history.record("tv", 1210.0, 6000.0));
+        assert_eq!(history.resume_position("abc:0"), Some(1210.0));
+
+        // Loading reports no duration yet and must not clobber the position
+        history.record("tv", 0.0, 0.0);
+        assert_eq!(history.resume_position("abc:0"), Some(1210.0));
+
+        // Finished files start from the beginning next time
+        history.stop("tv", 5900.0, 6000.0);
+        assert!(history.entries["abc:0"].completed);
+        assert_eq!(history.resume_position("abc:0"), None);
+
+        // Nothing is recorded for a device after it stopped
+        assert!(!history.record("tv", 100.0, 6000.0));
+    }
+}
@@ -0,0 +1,68 @@
+// HTTP web seeds (BEP 19): plain URLs serving the torrent's data, which keep
+// a torrent with one slow seeder moving.
+//
+// librqbit reads neither a metainfo's `url-list` nor a magnet's `ws=`
+// parameters, and has no call for adding one to a running torrent. Seed
+// hints are still picked up from feeds and kept on their matches, but
+// nothing can use them until the engine does.
+
+use crate::errors::{Result, WhenThenError};
+use crate::services::rss::bencode_value_end;
+
+/// Whether `url` can serve as a web seed.
+pub fn validate(url: &str) -> Result<()> {
+    match reqwest::Url::parse(url) {
+        Ok(parsed) if matches!(parsed.scheme(), "http" | "https") => Ok(()),
+        Ok(_) => Err(WhenThenError::InvalidInput("Web seeds must be http or https URLs".into())),
+        Err(e) => Err(WhenThenError::InvalidInput(format!("Invalid web seed URL: {e}"))),
+    }
+}
+
+/// Whether a feed enclosure looks like the content itself rather than a
+/// .torrent, making it usable as a web seed.
+pub fn is_seed_hint(url: &str, media_type: Option<&str>) -> bool {
+    if !(url.starts_with("http://") || url.starts_with("https://")) || url.ends_with(".torrent") {
+        return false;
+    }
+    let path = url.split(['?', '#']).next().unwrap_or(url);
+    let guessed = mime_guess::from_path(path).first_raw();
+    media_type.or(guessed).is_some_and(|t| {
+        t.starts_with("video/") || t.starts_with("audio/") || t == "application/x-iso9660-image"
+    })
+}
+
+/// Top-level (key, raw value) pairs of a bencoded dictionary.
+pub(crate) fn dict_entries(data: &[u8]) -> Option<Vec<(&[u8], &[u8])>> {
+    if data.first() != Some(&b'd') {
+        return None;
+    }
+    let mut entries = Vec::new();
+    let mut cur = 1;
+    while *data.get(cur)? != b'e' {
+        let key_end = bencode_value_end(data, cur, 1)?;
+        let value_end = bencode_value_end(data, key_end, 1)?;
+        entries.push((byte_string(&data[cur..key_end])?, &data[key_end..value_end]));
+        cur = value_end;
+    }
+    (cur + 1 == data.len()).then_some(entries)
+}
+
+/// Contents of an encoded byte string, e.g. `b"4:spam"` -> `b"spam"`.
+pub(crate) fn byte_string(encoded: &[u8]) -> Option<&[u8]> {
+    let colon = encoded.iter().position(|&b| b == b':')?;
+    encoded.get(colon + 1..)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn enclosures_of_content_are_seed_hints() {
+        assert!(is_seed_hint("https://cdn.example.org/show.s01e01.mkv", None));
+        assert!(is_seed_hint("https://cdn.example.org/get?id=4", Some("video/mp4")));
+        assert!(!is_seed_hint("https://example.org/show.torrent", None));
+        assert!(!is_seed_hint("https://example.org/download/4", Some("application/x-bittorrent")));
+        assert!(!is_seed_hint("magnet:?xt=urn:btih:abc", None));
+    }
+}
@@ -0,0 +1,89 @@
+// System-wide shortcut that toggles the inbox panel. The accelerator comes
+// from config.global_shortcut (e.g. "Alt+Super+T"); at most one is registered.
+
+use std::sync::Mutex;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+use tracing::{info, warn};
+
+use crate::errors::{Result, WhenThenError};
+
+/// The registered accelerator, as the user typed it.
+static CURRENT: Mutex<Option<(String, Shortcut)>> = Mutex::new(None);
+
+#[derive(Serialize, Clone)]
+struct RegistrationFailed {
+    shortcut: String,
+    error: String,
+}
+
+/// The plugin, with every press toggling the panel.
+pub fn plugin() -> tauri::plugin::TauriPlugin<tauri::Wry> {
+    tauri_plugin_global_shortcut::Builder::new()
+        .with_handler(|app, _shortcut, event| {
+            if event.state() == ShortcutState::Pressed {
+                crate::tray::toggle_panel(app);
+            }
+        })
+        .build()
+}
+
+/// Parse an accelerator, with an error the settings form can show as is.
+pub fn parse(accelerator: &str) -> Result<Shortcut> {
+    accelerator
+        .trim()
+        .parse::<Shortcut>()
+        .map_err(|e| WhenThenError::InvalidInput(format!("\"{}\" isn't a valid shortcut: {e}", accelerator.trim())))
+}
+
+/// Register `accelerator` in place of the current shortcut; None or blank
+/// just unregisters. If it's taken, the previous one is put back and
+/// shortcut:registration-failed goes out.
+pub fn apply(app: &AppHandle, accelerator: Option<&str>) -> Result<()> {
+    let accelerator = accelerator.map(str::trim).filter(|a| !a.is_empty());
+    let next = accelerator.map(parse).transpose()?;
+
+    let mut current = CURRENT.lock().unwrap_or_else(|e| e.into_inner());
+    let previous = current.take();
+    if let Some((_, shortcut)) = &previous {
+        if let Err(e) = app.global_shortcut().unregister(*shortcut) {
+            warn!(error = %e, "Failed to unregister the global shortcut");
+        }
+    }
+    let (Some(accelerator), Some(shortcut)) = (accelerator, next) else {
+        return Ok(());
+    };
+
+    match app.global_shortcut().register(shortcut) {
+        Ok(()) => {
+            info!(accelerator, "Registered global shortcut");
+            *current = Some((accelerator.to_string(), shortcut));
+            Ok(())
+        }
+        Err(e) => {
+            warn!(accelerator, error = %e, "Global shortcut unavailable");
+            let _ = app.emit(
+                "shortcut:registration-failed",
+                RegistrationFailed {
+                    shortcut: accelerator.to_string(),
+                    error: e.to_string(),
+                },
+            );
+            if let Some((name, shortcut)) = previous {
+                if app.global_shortcut().register(shortcut).is_ok() {
+                    *current = Some((name, shortcut));
+                }
+            }
+            Err(WhenThenError::ShortcutTaken(accelerator.to_string()).with_details(e.to_string()))
+        }
+    }
+}
+
+/// Release the shortcut, for shutdown.
+pub fn unregister(app: &AppHandle) {
+    if CURRENT.lock().unwrap_or_else(|e| e.into_inner()).take().is_some() {
+        let _ = app.global_shortcut().unregister_all();
+    }
+}
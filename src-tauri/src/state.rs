@@ -1,31 +1,96 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
+use tauri::AppHandle;
 use tokio::sync::{Mutex, RwLock};
 
-use crate::models::{AppConfig, DiscoveredDevice, SubtitleData};
-use crate::services::chromecast_device::ChromecastConnection;
+use crate::models::{AppConfig, CastGroup, DiscoveredDevice, SubtitleData, TorrentSummary};
+use crate::services::device_connection::DeviceConnection;
+use crate::services::event_bus::EventBus;
 use crate::services::folder_watcher::FolderWatcherHandle;
-use crate::services::media_server::{MediaServerHandle, TokenEntry};
+use crate::services::logs::LogStore;
+use crate::services::media_server::{parse_bind_address, MediaServerHandle, MediaServerState, TokenEntry};
+use crate::services::opensub_client::OpenSubSession;
+use crate::services::persistence::RssWriter;
+use crate::services::port_mapping::PortMapping;
+use crate::services::power_monitor::PowerMonitor;
 use crate::services::rss::RssState;
 use crate::services::scraper::ScraperState;
+use crate::services::stats::{BandwidthStats, StatsSamplerHandle};
+use crate::services::thumbnails::Thumbnails;
+use crate::services::torznab::TorznabState;
+use crate::services::torrent_engine::PeerSample;
+use crate::services::torrent_ids::TorrentIdMap;
+use crate::services::display_names::DisplayNames;
+use crate::services::file_order::FileOrders;
+use crate::services::torrent_labels::TorrentLabels;
+use crate::services::torrent_outputs::TorrentOutputs;
+use crate::services::watch_history::WatchHistory;
+use crate::services::completed_history::CompletedHistory;
 
 #[derive(Clone)]
 pub struct AppState {
     pub torrent_session: Arc<RwLock<Option<Arc<librqbit::Session>>>>,
     pub discovered_devices: Arc<RwLock<HashMap<String, DiscoveredDevice>>>,
-    pub active_connections: Arc<Mutex<HashMap<String, ChromecastConnection>>>,
+    pub active_connections: Arc<Mutex<HashMap<String, DeviceConnection>>>,
+    /// Cast groups by group id
+    pub cast_groups: Arc<RwLock<HashMap<String, CastGroup>>>,
     pub media_server: Arc<MediaServerHandle>,
     pub current_subtitles: Arc<RwLock<Option<SubtitleData>>>,
+    /// OpenSubtitles login token and last known quota
+    pub opensub_session: Arc<Mutex<OpenSubSession>>,
     pub config: Arc<RwLock<AppConfig>>,
     pub discovery_shutdown: Arc<Mutex<Option<tokio::sync::oneshot::Sender<()>>>>,
     pub local_file_tokens: Arc<RwLock<HashMap<String, TokenEntry>>>,
+    /// Name shown for each torrent, by librqbit id
     pub torrent_names: Arc<RwLock<HashMap<usize, String>>>,
-    /// Tracks where torrent files have been moved to (torrent_id -> folder path)
-    pub torrent_locations: Arc<RwLock<HashMap<usize, String>>>,
+    /// Names the user gave torrents, by info hash
+    pub display_names: Arc<RwLock<DisplayNames>>,
+    /// Keeps the frontend's torrent ids stable across delete + re-add
+    pub torrent_ids: Arc<RwLock<TorrentIdMap>>,
+    /// User labels by info hash
+    pub torrent_labels: Arc<RwLock<TorrentLabels>>,
+    /// Where each torrent's data is on disk, by info hash
+    pub torrent_outputs: Arc<RwLock<TorrentOutputs>>,
+    /// File download orders in progress, by info hash
+    pub file_orders: Arc<RwLock<FileOrders>>,
+    /// The session monitor's latest torrent list; None until its first tick
+    pub torrent_snapshot: Arc<RwLock<Option<Vec<TorrentSummary>>>>,
+    /// Torrents for the session monitor to start reporting on, by librqbit id
+    pub progress_watch: Arc<Mutex<HashSet<usize>>>,
+    /// Smoothed download speed in bytes/s, by librqbit id, for ETAs
+    pub download_speeds: Arc<Mutex<HashMap<usize, f64>>>,
+    /// Torrents waiting for a download slot, in start order
+    pub download_queue: Arc<Mutex<VecDeque<usize>>>,
+    /// Per-peer counters from the last peer list request, by torrent id
+    pub peer_samples: Arc<Mutex<HashMap<usize, PeerSample>>>,
+    /// Cancel flags for running data verifications, by torrent id
+    pub verify_jobs: Arc<Mutex<HashMap<usize, Arc<AtomicBool>>>>,
+    /// Cancel flags for torrents being made, by source path
+    pub create_jobs: Arc<Mutex<HashMap<String, Arc<AtomicBool>>>>,
+    /// Resume positions and what each cast device is playing
+    pub watch_history: Arc<RwLock<WatchHistory>>,
+    /// When each finished download completed, by info hash
+    pub completed_history: Arc<RwLock<CompletedHistory>>,
+    /// Video preview frames made with ffmpeg
+    pub thumbnails: Arc<Thumbnails>,
     pub folder_watcher: Arc<Mutex<Option<FolderWatcherHandle>>>,
+    /// UPnP forwarding of the listen port
+    pub port_mapping: Arc<PortMapping>,
+    /// Battery state and the torrents paused for it
+    pub power: Arc<PowerMonitor>,
+    /// Daily download/upload totals
+    pub bandwidth_stats: Arc<Mutex<BandwidthStats>>,
+    pub stats_sampler: Arc<Mutex<Option<StatsSamplerHandle>>>,
     pub rss_state: Arc<RssState>,
+    /// Debounced writer for the RSS stores
+    pub rss_writer: Arc<RssWriter>,
     pub scraper_state: Arc<ScraperState>,
+    pub torznab_state: Arc<TorznabState>,
+    /// Recent log output, mirrored from tracing
+    pub logs: Arc<LogStore>,
+    /// Events mirrored to remote control clients
+    pub event_bus: Arc<EventBus>,
     /// Set when the app is launched via file association or deep link.
     pub opened_via_url: Arc<AtomicBool>,
     /// Set when user explicitly requests quit (menu, Cmd+Q).
@@ -35,22 +100,67 @@ pub struct AppState {
 impl AppState {
     pub fn new(config: AppConfig) -> Self {
         let media_server_port = config.media_server_port;
+        let media_server_bind = parse_bind_address(&config.media_server_bind_address);
         Self {
             torrent_session: Arc::new(RwLock::new(None)),
             discovered_devices: Arc::new(RwLock::new(HashMap::new())),
             active_connections: Arc::new(Mutex::new(HashMap::new())),
-            media_server: Arc::new(MediaServerHandle::new(media_server_port)),
+            cast_groups: Arc::new(RwLock::new(HashMap::new())),
+            media_server: Arc::new(MediaServerHandle::new(media_server_port, media_server_bind)),
             current_subtitles: Arc::new(RwLock::new(None)),
+            opensub_session: Arc::new(Mutex::new(OpenSubSession::default())),
             config: Arc::new(RwLock::new(config)),
             discovery_shutdown: Arc::new(Mutex::new(None)),
             local_file_tokens: Arc::new(RwLock::new(HashMap::new())),
             torrent_names: Arc::new(RwLock::new(HashMap::new())),
-            torrent_locations: Arc::new(RwLock::new(HashMap::new())),
+            display_names: Arc::new(RwLock::new(DisplayNames::default())),
+            torrent_ids: Arc::new(RwLock::new(TorrentIdMap::default())),
+            torrent_labels: Arc::new(RwLock::new(TorrentLabels::default())),
+            torrent_outputs: Arc::new(RwLock::new(TorrentOutputs::default())),
+            file_orders: Arc::new(RwLock::new(FileOrders::default())),
+            torrent_snapshot: Arc::new(RwLock::new(None)),
+            progress_watch: Arc::new(Mutex::new(HashSet::new())),
+            download_speeds: Arc::new(Mutex::new(HashMap::new())),
+            download_queue: Arc::new(Mutex::new(VecDeque::new())),
+            peer_samples: Arc::new(Mutex::new(HashMap::new())),
+            verify_jobs: Arc::new(Mutex::new(HashMap::new())),
+            create_jobs: Arc::new(Mutex::new(HashMap::new())),
+            watch_history: Arc::new(RwLock::new(WatchHistory::default())),
+            completed_history: Arc::new(RwLock::new(CompletedHistory::default())),
+            thumbnails: Arc::new(Thumbnails::default()),
             folder_watcher: Arc::new(Mutex::new(None)),
+            port_mapping: Arc::new(PortMapping::default()),
+            power: Arc::new(PowerMonitor::default()),
+            bandwidth_stats: Arc::new(Mutex::new(BandwidthStats::default())),
+            stats_sampler: Arc::new(Mutex::new(None)),
             rss_state: Arc::new(RssState::new()),
+            rss_writer: Arc::new(RssWriter::new()),
             scraper_state: Arc::new(ScraperState::new()),
+            torznab_state: Arc::new(TorznabState::new()),
+            logs: Arc::new(LogStore::new()),
+            event_bus: Arc::new(EventBus::default()),
             opened_via_url: Arc::new(AtomicBool::new(false)),
             quit_requested: Arc::new(AtomicBool::new(false)),
         }
     }
 }
+
+impl AppState {
+    /// Shared state handed to the media server's handlers.
+    pub async fn media_server_state(&self, app_handle: &AppHandle) -> MediaServerState {
+        let config = self.config.read().await;
+        MediaServerState {
+            torrent_session: self.torrent_session.clone(),
+            current_subtitles: self.current_subtitles.clone(),
+            local_file_tokens: self.local_file_tokens.clone(),
+            torrent_ids: self.torrent_ids.clone(),
+            thumbnails: self.thumbnails.clone(),
+            completed_history: self.completed_history.clone(),
+            app_handle: Some(app_handle.clone()),
+            event_bus: self.event_bus.clone(),
+            stream_token: self.media_server.stream_token.clone(),
+            require_stream_auth: config.require_stream_auth,
+            tls_enabled: config.media_server_tls_enabled,
+        }
+    }
+}
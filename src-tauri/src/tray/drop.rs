@@ -0,0 +1,124 @@
+// Torrents dropped on the app: .torrent files and text containing magnet links.
+//
+// Tauri's tray icon can't receive drops, so drops reach this through the main
+// window's native drop events or from the frontend, and results are reported
+// with tray:drop-result so the UI can toast them.
+
+use std::path::PathBuf;
+use std::sync::LazyLock;
+
+use regex::Regex;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+use tracing::{info, warn};
+
+use crate::services::torrent_engine;
+use crate::state::AppState;
+
+static MAGNET_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"(?i)magnet:\?[^\s"'<>]+"#).unwrap());
+
+#[derive(Debug, Clone, Serialize)]
+struct DropFailure {
+    item: String,
+    error: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+struct DropResult {
+    added: Vec<String>,
+    failed: Vec<DropFailure>,
+}
+
+/// Every magnet URI in a text snippet, in order and without repeats.
+pub fn extract_magnets(text: &str) -> Vec<String> {
+    let mut magnets: Vec<String> = Vec::new();
+    for m in MAGNET_RE.find_iter(text) {
+        let magnet = m.as_str().to_string();
+        if !magnets.contains(&magnet) {
+            magnets.push(magnet);
+        }
+    }
+    magnets
+}
+
+/// Add dropped .torrent files and any magnet links in dropped text, then show
+/// the main window and report what happened.
+pub fn handle_drop(app: &AppHandle, paths: Vec<PathBuf>, text: Option<String>) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<AppState>();
+        let mut result = DropResult::default();
+
+        for path in paths {
+            let display = path.to_string_lossy().to_string();
+            let is_torrent = path
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("torrent"));
+            if !is_torrent {
+                result.failed.push(DropFailure {
+                    item: display,
+                    error: "Not a .torrent file".into(),
+                });
+                continue;
+            }
+            match torrent_engine::add_torrent_file(&state, &app, display.clone(), None).await {
+                Ok(added) => result.added.push(added.name),
+                Err(e) => result.failed.push(DropFailure {
+                    item: display,
+                    error: e.to_string(),
+                }),
+            }
+        }
+
+        let magnets = text.as_deref().map(extract_magnets).unwrap_or_default();
+        if text.is_some() && magnets.is_empty() {
+            result.failed.push(DropFailure {
+                item: text.unwrap_or_default(),
+                error: "No magnet link found".into(),
+            });
+        }
+        for magnet in magnets {
+            // Show it right away, metadata can take a while
+            let pending = torrent_engine::parse_magnet_info(&magnet);
+            let _ = app.emit("torrent:pending", &pending);
+            match torrent_engine::add_magnet(&state, &app, magnet.clone(), None).await {
+                Ok(added) => result.added.push(added.name),
+                Err(e) => {
+                    let _ = app.emit(
+                        "torrent:pending-failed",
+                        &serde_json::json!({ "info_hash": pending.info_hash, "error": e.to_string() }),
+                    );
+                    result.failed.push(DropFailure {
+                        item: magnet,
+                        error: e.to_string(),
+                    });
+                }
+            }
+        }
+
+        info!("Drop added {} torrents, {} failed", result.added.len(), result.failed.len());
+        for failure in &result.failed {
+            warn!("Dropped item {} not added: {}", failure.item, failure.error);
+        }
+
+        super::show_main_window(&app);
+        let _ = app.emit("tray:drop-result", &result);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_magnets() {
+        let text = "Grab this: magnet:?xt=urn:btih:abc&dn=One\n\
+                    <a href=\"magnet:?xt=urn:btih:def&dn=Two\">two</a> and again magnet:?xt=urn:btih:abc&dn=One";
+        assert_eq!(
+            extract_magnets(text),
+            vec!["magnet:?xt=urn:btih:abc&dn=One", "magnet:?xt=urn:btih:def&dn=Two"]
+        );
+        assert!(extract_magnets("no links here").is_empty());
+    }
+}
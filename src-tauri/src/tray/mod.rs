@@ -1,35 +1,239 @@
 // Tray icon setup, right-click menu, left-click shows main window.
+//
+// The menu lists the torrents that are still going, so it's rebuilt whenever
+// they change. Rebuilds are debounced; torrent item ids look like
+// `torrent:<id>:<action>` with the id the frontend knows.
 
-use std::sync::atomic::Ordering;
+pub mod drop;
+
+use std::cmp::Reverse;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::LazyLock;
+use std::time::Duration;
 
 use tauri::{
     image::Image,
-    menu::{Menu, MenuItem},
+    menu::{Menu, MenuItem, PredefinedMenuItem, Submenu},
     tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
-    AppHandle, Listener, Manager,
+    AppHandle, Emitter, Listener, Manager, Wry,
 };
+use tokio::sync::Notify;
 
-use crate::i18n::t;
+use crate::i18n::{t, t_with};
+use crate::models::{TorrentState, TorrentSummary};
+use crate::services::torrent_engine;
 use crate::state::AppState;
-use tracing::info;
+use tracing::{info, warn};
+
+const IDLE_TOOLTIP: &str = "When";
+
+/// How often download activity is reflected in the tray.
+const ACTIVITY_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Changes within this window are folded into one menu rebuild.
+const MENU_DEBOUNCE: Duration = Duration::from_secs(1);
+
+/// Torrents listed in the menu.
+const MENU_TORRENTS: usize = 5;
 
-const MAIN_LABEL: &str = "main";
+/// Longest torrent name shown before it's cut short.
+const MENU_NAME_CHARS: usize = 40;
+
+// Either one lights the icon up
+static PENDING_MATCHES: AtomicUsize = AtomicUsize::new(0);
+static DOWNLOADING: AtomicBool = AtomicBool::new(false);
+
+static MENU_DIRTY: LazyLock<Notify> = LazyLock::new(Notify::new);
 
 // Embed both icon variants
 const ICON_NORMAL: &[u8] = include_bytes!("../../icons/tray.png");
 const ICON_ACTIVE: &[u8] = include_bytes!("../../icons/tray-active.png");
 
+/// What the menu shows, read from state before it's built.
+#[derive(Default)]
+struct MenuSnapshot {
+    torrents: Vec<TorrentSummary>,
+    pending: usize,
+}
+
+async fn snapshot(app: &AppHandle) -> MenuSnapshot {
+    let state = app.state::<AppState>();
+    let mut torrents: Vec<TorrentSummary> = torrent_engine::list_torrents(&state)
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|t| t.state != TorrentState::Completed)
+        .collect();
+    // Transferring first, then waiting, then stopped; newest first within each
+    torrents.sort_by_key(|t| {
+        let rank = match t.state {
+            TorrentState::Downloading | TorrentState::Initializing => 0,
+            TorrentState::Queued => 1,
+            _ => 2,
+        };
+        (rank, Reverse(t.id))
+    });
+    torrents.truncate(MENU_TORRENTS);
+
+    MenuSnapshot {
+        torrents,
+        pending: state.rss_state.pending_matches.read().await.len(),
+    }
+}
+
+fn short_name(name: &str) -> String {
+    if name.chars().count() <= MENU_NAME_CHARS {
+        return name.to_string();
+    }
+    let cut: String = name.chars().take(MENU_NAME_CHARS - 1).collect();
+    format!("{}…", cut.trim_end())
+}
+
+fn torrent_submenu(app: &AppHandle, torrent: &TorrentSummary) -> tauri::Result<Submenu<Wry>> {
+    let percent = ((torrent.progress * 100.0).floor() as u64).min(100).to_string();
+    let title = t_with("tray.torrent", &[("name", &short_name(&torrent.name)), ("percent", &percent)]);
+    let id = torrent.id;
+
+    let progress = MenuItem::with_id(
+        app,
+        format!("torrent:{id}:progress"),
+        t_with("tray.progress", &[("percent", &percent)]),
+        false,
+        None::<&str>,
+    )?;
+    let toggle = if matches!(torrent.state, TorrentState::Paused | TorrentState::Error) {
+        MenuItem::with_id(app, format!("torrent:{id}:resume"), t("common.resume"), true, None::<&str>)?
+    } else {
+        MenuItem::with_id(app, format!("torrent:{id}:pause"), t("common.pause"), true, None::<&str>)?
+    };
+    let open_folder = MenuItem::with_id(app, format!("torrent:{id}:open-folder"), t("tray.openFolder"), true, None::<&str>)?;
+
+    Submenu::with_items(
+        app,
+        title,
+        true,
+        &[&progress, &PredefinedMenuItem::separator(app)?, &toggle, &open_folder],
+    )
+}
+
+fn build_menu(app: &AppHandle, snapshot: &MenuSnapshot) -> tauri::Result<Menu<Wry>> {
+    let menu = Menu::new(app)?;
+
+    for torrent in &snapshot.torrents {
+        menu.append(&torrent_submenu(app, torrent)?)?;
+    }
+    if !snapshot.torrents.is_empty() {
+        menu.append(&PredefinedMenuItem::separator(app)?)?;
+    }
+
+    menu.append(&MenuItem::with_id(app, "pause-all", t("tray.pauseAll"), true, None::<&str>)?)?;
+    menu.append(&MenuItem::with_id(app, "resume-all", t("tray.resumeAll"), true, None::<&str>)?)?;
+
+    if snapshot.pending > 0 {
+        let count = snapshot.pending.to_string();
+        menu.append(&PredefinedMenuItem::separator(app)?)?;
+        menu.append(&MenuItem::with_id(
+            app,
+            "pending",
+            t_with("tray.pendingMatches", &[("count", &count)]),
+            true,
+            None::<&str>,
+        )?)?;
+    }
+
+    menu.append(&PredefinedMenuItem::separator(app)?)?;
+    menu.append(&MenuItem::with_id(app, "show", t("tray.showWindow"), true, None::<&str>)?)?;
+    menu.append(&MenuItem::with_id(app, "quit", t("tray.quit"), true, None::<&str>)?)?;
+    Ok(menu)
+}
+
+/// Schedule a rebuild of the right-click menu, e.g. after torrents change or
+/// the language does.
+pub fn refresh_menu() {
+    MENU_DIRTY.notify_one();
+}
+
+/// Rebuild the menu once things have been quiet for MENU_DEBOUNCE.
+async fn rebuild_menu_on_change(app: AppHandle) {
+    loop {
+        MENU_DIRTY.notified().await;
+        tokio::time::sleep(MENU_DEBOUNCE).await;
+
+        let snapshot = snapshot(&app).await;
+        let Some(tray) = app.tray_by_id("main") else {
+            continue;
+        };
+        match build_menu(&app, &snapshot) {
+            Ok(menu) => {
+                let _ = tray.set_menu(Some(menu));
+            }
+            Err(e) => tracing::error!("Failed to rebuild tray menu: {}", e),
+        }
+    }
+}
+
+/// Split a `torrent:<id>:<action>` menu id.
+fn parse_torrent_item(id: &str) -> Option<(usize, &str)> {
+    let (torrent_id, action) = id.strip_prefix("torrent:")?.split_once(':')?;
+    Some((torrent_id.parse().ok()?, action))
+}
+
+fn handle_torrent_item(app: &AppHandle, torrent_id: usize, action: &str) {
+    let app = app.clone();
+    let action = action.to_string();
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<AppState>();
+        let result = match action.as_str() {
+            "pause" => torrent_engine::pause_torrent(&state, &app, torrent_id).await,
+            "resume" => torrent_engine::resume_torrent(&state, &app, torrent_id).await,
+            "open-folder" => match torrent_engine::torrent_folder(&state, torrent_id).await {
+                Ok(folder) => {
+                    use tauri_plugin_shell::ShellExt;
+                    #[allow(deprecated)]
+                    app.shell()
+                        .open(folder.to_string_lossy(), None)
+                        .map_err(|e| crate::errors::WhenThenError::Internal(format!("Failed to open folder: {e}")))
+                }
+                Err(e) => Err(e),
+            },
+            _ => return,
+        };
+        match result {
+            Ok(()) if action != "open-folder" => {
+                let _ = app.emit("torrents:changed", ());
+            }
+            Ok(()) => {}
+            Err(e) => warn!(torrent_id, error = %e, "Tray action {} failed", action),
+        }
+    });
+}
+
+fn handle_bulk_item(app: &AppHandle, resume: bool) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<AppState>();
+        if resume {
+            let Some(session) = state.torrent_session.read().await.clone() else {
+                return;
+            };
+            torrent_engine::resume_all(&state, &app, &session).await;
+        } else {
+            torrent_engine::pause_all(&state, &app).await;
+        }
+        let _ = app.emit("torrents:changed", ());
+    });
+}
+
 pub fn setup(app: &AppHandle) -> tauri::Result<()> {
-    let show_item = MenuItem::with_id(app, "show", t("tray.showWindow"), true, None::<&str>)?;
-    let quit_item = MenuItem::with_id(app, "quit", t("tray.quit"), true, None::<&str>)?;
-    let menu = Menu::with_items(app, &[&show_item, &quit_item])?;
+    // Filled in by the first rebuild, once the session is up
+    let menu = build_menu(app, &MenuSnapshot::default())?;
 
     let icon = Image::from_bytes(ICON_NORMAL).expect("bundled tray icon");
 
     let _tray = TrayIconBuilder::with_id("main")
         .icon(icon)
         .icon_as_template(true)
-        .tooltip("When")
+        .tooltip(IDLE_TOOLTIP)
         .menu(&menu)
         .show_menu_on_left_click(false)
         .on_menu_event(|app, event| match event.id.as_ref() {
@@ -41,7 +245,16 @@ pub fn setup(app: &AppHandle) -> tauri::Result<()> {
                 state.quit_requested.store(true, Ordering::SeqCst);
                 app.exit(0);
             }
-            _ => {}
+            "pause-all" => handle_bulk_item(app, false),
+            "resume-all" => handle_bulk_item(app, true),
+            "pending" => {
+                let _ = crate::windows::show(app, crate::windows::PICKER);
+            }
+            id => {
+                if let Some((torrent_id, action)) = parse_torrent_item(id) {
+                    handle_torrent_item(app, torrent_id, action);
+                }
+            }
         })
         .on_tray_icon_event(|tray, event| {
             let app = tray.app_handle();
@@ -60,30 +273,97 @@ pub fn setup(app: &AppHandle) -> tauri::Result<()> {
     let app_handle = app.clone();
     app.listen("rss:pending-count", move |event| {
         if let Ok(count) = event.payload().parse::<usize>() {
-            set_icon_active(&app_handle, count > 0);
+            if PENDING_MATCHES.swap(count, Ordering::Relaxed) != count {
+                refresh_icon(&app_handle);
+                refresh_menu();
+            }
         }
     });
+    for event in ["torrents:changed", "torrent:added", "torrent:completed"] {
+        app.listen(event, |_| refresh_menu());
+    }
+
+    tauri::async_runtime::spawn(track_activity(app.clone()));
+    tauri::async_runtime::spawn(rebuild_menu_on_change(app.clone()));
+    refresh_menu();
 
     info!("Tray icon ready");
     Ok(())
 }
 
-fn show_main_window(app: &AppHandle) {
-    if let Some(win) = app.get_webview_window(MAIN_LABEL) {
-        let _ = win.show();
-        let _ = win.unminimize();
-        let _ = win.set_focus();
+/// Show download speed and the number of active torrents in the tooltip while
+/// anything is downloading, and go back to the idle tooltip afterwards.
+async fn track_activity(app: AppHandle) {
+    let mut showing = false;
+    loop {
+        tokio::time::sleep(ACTIVITY_INTERVAL).await;
+
+        let state = app.state::<AppState>();
+        let (speed, active) = if state.config.read().await.show_tray_activity {
+            torrent_engine::download_activity(&state).await
+        } else {
+            (0, 0)
+        };
+
+        let Some(tray) = app.tray_by_id("main") else {
+            continue;
+        };
+        if active > 0 {
+            let tooltip = t_with(
+                "tray.activity",
+                &[("speed", &format_speed(speed)), ("count", &active.to_string())],
+            );
+            let _ = tray.set_tooltip(Some(tooltip));
+        } else if showing {
+            let _ = tray.set_tooltip(Some(IDLE_TOOLTIP));
+        }
+
+        if showing != (active > 0) {
+            showing = active > 0;
+            DOWNLOADING.store(showing, Ordering::Relaxed);
+            refresh_icon(&app);
+        }
+    }
+}
+
+fn format_speed(bytes_per_sec: u64) -> String {
+    const MB: f64 = 1024.0 * 1024.0;
+    let bytes = bytes_per_sec as f64;
+    if bytes >= MB {
+        format!("{:.1} MB/s", bytes / MB)
+    } else {
+        format!("{:.0} KB/s", bytes / 1024.0)
     }
 }
 
+/// Show the inbox panel on the screen under the mouse, or hide it if it's up.
+pub(crate) fn toggle_panel(app: &AppHandle) {
+    use crate::windows;
+
+    let visible = app
+        .webview_windows()
+        .iter()
+        .any(|(label, window)| windows::is_picker(label) && window.is_visible().unwrap_or(false));
+    let _ = if visible {
+        windows::hide(app, windows::PICKER)
+    } else {
+        windows::show(app, windows::PICKER)
+    };
+}
+
+pub(crate) fn show_main_window(app: &AppHandle) {
+    let _ = crate::windows::show(app, crate::windows::MAIN);
+}
+
 pub fn set_visible(app: &AppHandle, visible: bool) {
     if let Some(tray) = app.tray_by_id("main") {
         let _ = tray.set_visible(visible);
     }
 }
 
-/// Update tray icon to show active state (colored) when there are pending matches.
-pub fn set_icon_active(app: &AppHandle, active: bool) {
+/// Show the active (colored) icon while there are pending matches or downloads.
+fn refresh_icon(app: &AppHandle) {
+    let active = PENDING_MATCHES.load(Ordering::Relaxed) > 0 || DOWNLOADING.load(Ordering::Relaxed);
     if let Some(tray) = app.tray_by_id("main") {
         let (icon_bytes, as_template) = if active {
             (ICON_ACTIVE, false) // Colored icon, not a template
@@ -97,3 +377,16 @@ pub fn set_icon_active(app: &AppHandle, active: bool) {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn torrent_item_ids_carry_the_torrent_id() {
+        assert_eq!(parse_torrent_item("torrent:12:pause"), Some((12, "pause")));
+        assert_eq!(parse_torrent_item("torrent:3:open-folder"), Some((3, "open-folder")));
+        assert_eq!(parse_torrent_item("torrent:x:pause"), None);
+        assert_eq!(parse_torrent_item("pause-all"), None);
+    }
+}
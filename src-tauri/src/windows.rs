@@ -0,0 +1,168 @@
+// Webview windows. Main comes from tauri.conf.json; the others are made on
+// demand. Every window hides on close instead of being destroyed, so it
+// reopens instantly with its state intact.
+
+use std::collections::HashSet;
+use std::sync::{Mutex, OnceLock};
+
+use tauri::{AppHandle, LogicalSize, Manager, Monitor, WebviewUrl, WebviewWindow, WebviewWindowBuilder, WindowEvent};
+use tracing::{debug, info};
+
+use crate::errors::{Result, WhenThenError};
+
+pub const MAIN: &str = "main";
+
+/// The screener inbox in a window of its own. There's one per screen,
+/// labelled picker-<n>, so opening it shows it where the user is looking.
+pub const PICKER: &str = "picker";
+
+/// How a window made on demand looks.
+pub struct WindowOptions {
+    pub title: &'static str,
+    pub width: f64,
+    pub height: f64,
+    pub always_on_top: bool,
+}
+
+const PICKER_URL: &str = "index.html?view=inbox";
+
+const PICKER_OPTIONS: WindowOptions = WindowOptions {
+    title: "When",
+    width: 420.0,
+    height: 560.0,
+    always_on_top: true,
+};
+
+/// Labels with the close handler attached; a label leaves when its window is
+/// destroyed, so a recreated window gets a fresh handler.
+fn handled() -> &'static Mutex<HashSet<String>> {
+    static HANDLED: OnceLock<Mutex<HashSet<String>>> = OnceLock::new();
+    HANDLED.get_or_init(Default::default)
+}
+
+/// Hide the window when it's closed. Safe to call more than once per window.
+pub fn hide_on_close(window: &WebviewWindow) {
+    let label = window.label().to_string();
+    if !handled().lock().unwrap_or_else(|e| e.into_inner()).insert(label.clone()) {
+        return;
+    }
+    // Look the window up by label rather than keeping a handle in its own listener
+    let app = window.app_handle().clone();
+    window.on_window_event(move |event| match event {
+        WindowEvent::CloseRequested { api, .. } => {
+            api.prevent_close();
+            if let Some(win) = app.get_webview_window(&label) {
+                let _ = win.hide();
+            }
+        }
+        WindowEvent::Destroyed => {
+            handled().lock().unwrap_or_else(|e| e.into_inner()).remove(&label);
+            debug!(label, "Window destroyed");
+        }
+        _ => {}
+    });
+}
+
+/// The window with `label`, created from `url` if it doesn't exist yet.
+/// New windows start hidden.
+pub fn ensure_window(app: &AppHandle, label: &str, url: &str, options: &WindowOptions) -> tauri::Result<WebviewWindow> {
+    if let Some(window) = app.get_webview_window(label) {
+        hide_on_close(&window);
+        return Ok(window);
+    }
+    let window = WebviewWindowBuilder::new(app, label, WebviewUrl::App(url.into()))
+        .title(options.title)
+        .inner_size(options.width, options.height)
+        .always_on_top(options.always_on_top)
+        .visible(false)
+        .build()?;
+    hide_on_close(&window);
+    info!(label, "Window created");
+    Ok(window)
+}
+
+fn window_error(e: tauri::Error) -> WhenThenError {
+    WhenThenError::Internal(format!("Window: {e}"))
+}
+
+/// Bring a window to the front, creating it first if needed. `picker` opens
+/// the picker on the screen under the mouse.
+pub fn show(app: &AppHandle, label: &str) -> Result<()> {
+    let window = if label == PICKER {
+        let (index, monitor) = cursor_monitor(app).map_err(window_error)?;
+        let window = ensure_window(app, &format!("{PICKER}-{index}"), PICKER_URL, &PICKER_OPTIONS)
+            .map_err(window_error)?;
+        if !window.is_visible().unwrap_or(false) {
+            if let Some(monitor) = monitor {
+                center_on(&window, &monitor).map_err(window_error)?;
+            }
+        }
+        window
+    } else {
+        app.get_webview_window(label)
+            .ok_or_else(|| WhenThenError::NotFound(format!("Window {label}")))?
+    };
+    hide_on_close(&window);
+    window.show().map_err(window_error)?;
+    let _ = window.unminimize();
+    window.set_focus().map_err(window_error)
+}
+
+/// Hide a window; `picker` hides the pickers on every screen.
+pub fn hide(app: &AppHandle, label: &str) -> Result<()> {
+    for (name, window) in app.webview_windows() {
+        if name == label || (label == PICKER && is_picker(&name)) {
+            window.hide().map_err(window_error)?;
+        }
+    }
+    Ok(())
+}
+
+pub fn is_picker(label: &str) -> bool {
+    label
+        .strip_prefix(PICKER)
+        .and_then(|rest| rest.strip_prefix('-'))
+        .is_some_and(|index| index.parse::<usize>().is_ok())
+}
+
+/// Index and monitor of the screen under the mouse, or the first screen.
+fn cursor_monitor(app: &AppHandle) -> tauri::Result<(usize, Option<Monitor>)> {
+    let monitors = app.available_monitors()?;
+    let cursor = app.cursor_position().ok();
+    let found = cursor.and_then(|point| {
+        monitors.iter().position(|m| {
+            let (pos, size) = (m.position(), m.size());
+            point.x >= pos.x as f64
+                && point.y >= pos.y as f64
+                && point.x < pos.x as f64 + size.width as f64
+                && point.y < pos.y as f64 + size.height as f64
+        })
+    });
+    let index = found.unwrap_or(0);
+    Ok((index, monitors.into_iter().nth(index)))
+}
+
+fn center_on(window: &WebviewWindow, monitor: &Monitor) -> tauri::Result<()> {
+    let scale = monitor.scale_factor();
+    let size: LogicalSize<f64> = window.outer_size()?.to_logical(scale);
+    let area: LogicalSize<f64> = monitor.size().to_logical(scale);
+    let origin = monitor.position().to_logical::<f64>(scale);
+    window.set_position(tauri::LogicalPosition::new(
+        origin.x + (area.width - size.width) / 2.0,
+        origin.y + (area.height - size.height) / 2.0,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_numbered_pickers_count_as_pickers() {
+        assert!(is_picker("picker-0"));
+        assert!(is_picker("picker-12"));
+        assert!(!is_picker("picker"));
+        assert!(!is_picker("picker-main"));
+        assert!(!is_picker("main"));
+    }
+}
@@ -1,4 +1,7 @@
+import { getCurrentWebview } from "@tauri-apps/api/webview";
+import type { UnlistenFn } from "@tauri-apps/api/event";
 import {
+  torrentAddDropped,
   torrentAddMagnet,
   torrentAddFile,
   torrentAddBytes,
@@ -9,6 +12,7 @@ import { devicesState } from "$lib/state/devices.svelte";
 import { subtitlesState } from "$lib/state/subtitles.svelte";
 import { uiState } from "$lib/state/ui.svelte";
 import { t } from "$lib/i18n";
+import { isCommandError } from "$lib/types/errors";
 import type { TorrentAddedResponse } from "$lib/types/torrent";
 
 export type DropContentType =
@@ -30,7 +34,7 @@ const MEDIA_EXTENSIONS = [
   "webm",
   "ogg",
 ];
-const SUBTITLE_EXTENSIONS = ["srt", "vtt"];
+const SUBTITLE_EXTENSIONS = ["srt", "vtt", "ass", "ssa"];
 const TORRENT_EXTENSION = "torrent";
 
 export function classifyContent(input: string): DropContentType {
@@ -65,14 +69,24 @@ export function classifyContent(input: string): DropContentType {
   return "unknown";
 }
 
+/** Add a magnet, asking before adding one that was marked bad from the inbox. */
+export async function addMagnet(magnet: string): Promise<TorrentAddedResponse | null> {
+  try {
+    return await torrentAddMagnet(magnet);
+  } catch (err) {
+    if (!isCommandError(err) || err.code !== "MARKED_BAD") throw err;
+    if (!window.confirm(t("toast.confirmMarkedBad"))) return null;
+    return torrentAddMagnet(magnet, { allow_bad: true });
+  }
+}
+
 export async function handleDroppedContent(input: string): Promise<TorrentAddedResponse | null> {
   const type = classifyContent(input);
 
   try {
     switch (type) {
       case "magnet": {
-        const response = await torrentAddMagnet(input);
-        return response;
+        return await addMagnet(input);
       }
 
       case "torrent": {
@@ -115,6 +129,32 @@ export async function handleDroppedContent(input: string): Promise<TorrentAddedR
   }
 }
 
+/** Event a drop target gets when native files land on it; `detail` holds the paths. */
+export const NATIVE_DROP_EVENT = "nativedrop";
+
+/**
+ * Native file drops, which the webview hands to Tauri. Elements marked
+ * `data-drop-target` take drops that land on them; anywhere else the files
+ * are added like a tray drop, with results arriving as tray:drop-result.
+ */
+export async function setupNativeDrop(): Promise<UnlistenFn> {
+  return getCurrentWebview().onDragDropEvent((event) => {
+    if (event.payload.type !== "drop") return;
+    const { paths, position } = event.payload;
+    const scale = window.devicePixelRatio || 1;
+    const target = document
+      .elementFromPoint(position.x / scale, position.y / scale)
+      ?.closest("[data-drop-target]");
+    if (target) {
+      target.dispatchEvent(new CustomEvent<string[]>(NATIVE_DROP_EVENT, { detail: paths, bubbles: true }));
+      return;
+    }
+    torrentAddDropped(paths).catch((err: any) => {
+      uiState.addToast(t("toast.somethingWentWrong", { error: err?.message || String(err) }), "error");
+    });
+  });
+}
+
 /** Read a File object's bytes and add it as a torrent. Only handles .torrent files. */
 export async function handleDroppedFile(file: File): Promise<TorrentAddedResponse | null> {
   if (!file.name.toLowerCase().endsWith(".torrent")) {
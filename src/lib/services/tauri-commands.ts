@@ -4,9 +4,17 @@ import type {
   TorrentSummary,
   TorrentFileInfo,
   TorrentAddOptions,
+  TorrentVerifyReport,
+  TorrentCreateOptions,
+  TorrentCreated,
+  CompletedPage,
+  FileOrderStatus,
+  MagnetPreview,
+  ImportReport,
+  UsageStats,
 } from "$lib/types/torrent";
-import type { SubtitleInfo, MediaPlayer, PlaybackStatusResponse } from "$lib/types/playback";
-import type { AppSettings } from "$lib/types/settings";
+import type { SubtitleInfo, MediaPlayer, PlaybackStatusResponse, CastGroup, StreamReadiness, ThumbnailInfo, MediaServerCertInfo } from "$lib/types/playback";
+import type { AppSettings, DiagnosticsReport, LogEntry, NetworkStatus, PowerCapabilities, SettingsImportSummary } from "$lib/types/settings";
 
 const DEFAULT_TIMEOUT_MS = 30_000;
 
@@ -41,6 +49,10 @@ export async function torrentAddMagnet(
   return invokeWithTimeout("torrent_add_magnet", { magnet_url: magnetUrl, options }, 60_000);
 }
 
+export async function magnetParse(uri: string): Promise<MagnetPreview> {
+  return invoke("magnet_parse", { uri });
+}
+
 export async function torrentAddFile(
   path: string,
   options?: TorrentAddOptions,
@@ -48,6 +60,19 @@ export async function torrentAddFile(
   return invokeWithTimeout("torrent_add_file", { path, options }, 60_000);
 }
 
+// Results arrive as a tray:drop-result event
+export async function torrentAddDropped(paths: string[], text?: string): Promise<void> {
+  return invoke("torrent_add_dropped", { paths, text });
+}
+
+// Hash-checks every torrent, so no timeout; progress arrives as torrent:import-progress
+export async function torrentImportDirectory(
+  path: string,
+  destinationMap?: Record<string, string>,
+): Promise<ImportReport[]> {
+  return invoke("torrent_import_directory", { path, destinationMap });
+}
+
 export async function torrentAddBytes(
   fileBytes: number[],
   options?: TorrentAddOptions,
@@ -75,6 +100,62 @@ export async function torrentRecheck(id: number): Promise<TorrentAddedResponse>
   return invokeWithTimeout("torrent_recheck", { id }, 60_000);
 }
 
+export async function torrentAddWebseed(id: number, url: string): Promise<TorrentAddedResponse> {
+  return invokeWithTimeout("torrent_add_webseed", { id, url }, 60_000);
+}
+
+export async function torrentSetFileOrder(id: number, orderedIndices: number[]): Promise<FileOrderStatus> {
+  return invokeWithTimeout("torrent_set_file_order", { id, orderedIndices }, 60_000);
+}
+
+export async function torrentCancelFileOrder(id: number): Promise<void> {
+  return invokeWithTimeout("torrent_cancel_file_order", { id }, 60_000);
+}
+
+export async function torrentReannounce(id: number): Promise<number> {
+  return dedup(`torrent_reannounce:${id}`, () => invoke("torrent_reannounce", { id }));
+}
+
+export async function torrentForceReconnect(id: number): Promise<number> {
+  return dedup(`torrent_force_reconnect:${id}`, () => invoke("torrent_force_reconnect", { id }));
+}
+
+export async function torrentRevealFile(id: number, fileIndex: number): Promise<void> {
+  return invoke("torrent_reveal_file", { id, fileIndex });
+}
+
+export async function torrentOpenFolder(id: number): Promise<void> {
+  return invoke("torrent_open_folder", { id });
+}
+
+export async function torrentVerify(id: number): Promise<TorrentVerifyReport> {
+  return invoke("torrent_verify", { id });
+}
+
+export async function torrentVerifyCancel(id: number): Promise<boolean> {
+  return invoke("torrent_verify_cancel", { id });
+}
+
+export async function torrentCreate(
+  path: string,
+  destination: string,
+  options: TorrentCreateOptions = {},
+): Promise<TorrentCreated> {
+  return invoke("torrent_create", { path, destination, ...options });
+}
+
+export async function torrentCreateCancel(path: string): Promise<boolean> {
+  return invoke("torrent_create_cancel", { path });
+}
+
+export async function completedHistory(offset = 0, limit = 50): Promise<CompletedPage> {
+  return invoke("completed_history", { offset, limit });
+}
+
+export async function completedFeedUrl(): Promise<string> {
+  return invoke("completed_feed_url");
+}
+
 export async function torrentDelete(
   id: number,
   deleteFiles: boolean,
@@ -82,6 +163,22 @@ export async function torrentDelete(
   return dedup(`torrent_delete:${id}`, () => invoke("torrent_delete", { id, deleteFiles }));
 }
 
+export async function torrentList(label?: string): Promise<TorrentSummary[]> {
+  return invoke("torrent_list", { label: label ?? null });
+}
+
+export async function torrentSetLabel(id: number, label: string | null): Promise<void> {
+  return invoke("torrent_set_label", { id, label });
+}
+
+export async function torrentSetDisplayName(id: number, name: string): Promise<string> {
+  return invoke("torrent_set_display_name", { id, name });
+}
+
+export async function torrentListLabels(): Promise<string[]> {
+  return invoke("torrent_list_labels");
+}
+
 // Chromecast commands
 export async function chromecastStartDiscovery(): Promise<void> {
   return invoke("chromecast_start_discovery");
@@ -96,8 +193,30 @@ export async function playbackCastTorrent(
   deviceId: string,
   torrentId: number,
   fileIndex: number,
+  startPosition?: number,
+  waitReadySecs?: number,
+): Promise<void> {
+  return invoke("playback_cast_torrent", { deviceId, torrentId, fileIndex, startPosition, waitReadySecs });
+}
+
+export async function playbackCastTorrentQueue(
+  deviceId: string,
+  torrentId: number,
+  fileIndices: number[],
 ): Promise<void> {
-  return invoke("playback_cast_torrent", { deviceId, torrentId, fileIndex });
+  return invokeWithTimeout("playback_cast_torrent_queue", { deviceId, torrentId, fileIndices }, 60_000);
+}
+
+export async function playbackQueueNext(deviceId: string): Promise<void> {
+  return invoke("playback_queue_next", { deviceId });
+}
+
+export async function playbackQueuePrev(deviceId: string): Promise<void> {
+  return invoke("playback_queue_prev", { deviceId });
+}
+
+export async function playbackCheckReady(torrentId: number, fileIndex: number): Promise<StreamReadiness> {
+  return invoke("playback_check_ready", { torrentId, fileIndex });
 }
 
 export async function playbackCastLocalFile(
@@ -107,6 +226,13 @@ export async function playbackCastLocalFile(
   return invoke("playback_cast_local_file", { deviceId, filePath });
 }
 
+export async function playbackCastGroup(
+  deviceIds: string[],
+  media: { torrentId: number; fileIndex: number } | { filePath: string },
+): Promise<CastGroup> {
+  return invoke("playback_cast_group", { deviceIds, ...media });
+}
+
 // Playback control commands
 export async function playbackPlay(deviceId: string): Promise<void> {
   return invoke("playback_play", { deviceId });
@@ -128,10 +254,22 @@ export async function playbackSetVolume(deviceId: string, volume: number): Promi
   return invoke("playback_set_volume", { deviceId, volume });
 }
 
+export async function playbackSetMuted(deviceId: string, muted: boolean): Promise<void> {
+  return invoke("playback_set_muted", { deviceId, muted });
+}
+
 export async function playbackGetStatus(deviceId: string): Promise<PlaybackStatusResponse> {
   return invoke("playback_get_status", { deviceId });
 }
 
+export async function playbackGetResume(torrentId: number, fileIndex: number): Promise<number | null> {
+  return invoke("playback_get_resume", { torrentId, fileIndex });
+}
+
+export async function playbackClearHistory(): Promise<void> {
+  return invoke("playback_clear_history");
+}
+
 // Local playback commands
 export async function playbackOpenInApp(
   torrentId: number,
@@ -160,14 +298,37 @@ export async function subtitleLoadFile(
   return invoke("subtitle_load_file", { path });
 }
 
+export async function subtitleSetOffset(offsetMs: number): Promise<SubtitleInfo> {
+  return invoke("subtitle_set_offset", { offsetMs });
+}
+
 export async function getPlaylistUrl(torrentId: number): Promise<string> {
   return invoke("get_playlist_url", { torrentId });
 }
 
+export async function mediaServerCertInfo(): Promise<MediaServerCertInfo | null> {
+  return invoke("media_server_cert_info");
+}
+
+export async function mediaGetThumbnail(torrentId: number, fileIndex: number): Promise<ThumbnailInfo> {
+  return invoke("media_get_thumbnail", { torrentId, fileIndex });
+}
+
 // Subtitle search commands
+export interface SubtitleQuota {
+  remaining: number;
+  requests: number;
+  allowed: number | null;
+  reset_time: string | null;
+  logged_in: boolean;
+}
+
 export interface SubtitleDownloadResult {
   file_name: string;
   file_path: string;
+  quota: SubtitleQuota | null;
+  source: "local" | "opensubtitles";
+  language: string;
 }
 
 export async function subtitleSearchOpensubtitles(
@@ -178,6 +339,37 @@ export async function subtitleSearchOpensubtitles(
   return invokeWithTimeout("subtitle_search_opensubtitles", { torrentId, fileIndex, languages }, 30_000);
 }
 
+export interface SubtitleCandidate {
+  file_id: number;
+  file_name: string;
+  language: string;
+  score: number;
+  download_count: number;
+  ratings: number;
+  moviehash_match: boolean;
+}
+
+export async function subtitleSearchCandidates(
+  torrentId: number,
+  fileIndex: number,
+  languages: string[],
+): Promise<SubtitleCandidate[]> {
+  return invokeWithTimeout("subtitle_search_candidates", { torrentId, fileIndex, languages }, 30_000);
+}
+
+export async function subtitleDownloadCandidate(
+  torrentId: number,
+  fileIndex: number,
+  fileId: number,
+  language: string,
+): Promise<SubtitleDownloadResult> {
+  return invokeWithTimeout("subtitle_download_candidate", { torrentId, fileIndex, fileId, language }, 30_000);
+}
+
+export async function subtitleQuota(): Promise<SubtitleQuota | null> {
+  return invokeWithTimeout("subtitle_quota", {}, 15_000);
+}
+
 // Automation commands
 export async function checkAutomationPermission(): Promise<string> {
   return invoke("check_automation_permission");
@@ -233,6 +425,20 @@ export async function settingsUpdate(
   return invoke("settings_update", { config });
 }
 
+export async function settingsExport(
+  path: string,
+  includeSecrets = false,
+): Promise<void> {
+  return invoke("settings_export", { path, includeSecrets });
+}
+
+export async function settingsImport(
+  path: string,
+  merge: boolean,
+): Promise<SettingsImportSummary> {
+  return invoke("settings_import", { path, merge });
+}
+
 // i18n commands
 export async function getTranslations(
   locale?: string,
@@ -240,7 +446,72 @@ export async function getTranslations(
   return invoke("get_translations", { locale: locale ?? null });
 }
 
+export async function getAvailableLocales(): Promise<string[]> {
+  return invoke("get_available_locales");
+}
+
+export async function i18nSetLocale(
+  locale: string,
+): Promise<Record<string, Record<string, string>>> {
+  return invoke("i18n_set_locale", { locale });
+}
+
 // Demo/screenshot commands
 export async function rssSeedDemo(): Promise<void> {
   return invoke("rss_seed_demo");
 }
+
+export async function statsGet(range: "week" | "month" | "year" | "all"): Promise<UsageStats> {
+  return invoke("stats_get", { range });
+}
+
+// Log commands
+export async function logsGetRecent(
+  limit?: number,
+  levelFilter?: "error" | "warn" | "info" | "debug" | "trace",
+): Promise<LogEntry[]> {
+  return invoke("logs_get_recent", { limit, levelFilter });
+}
+
+export async function logsExport(
+  path: string,
+  keepLogFiles?: boolean,
+): Promise<number> {
+  return invoke("logs_export", { path, keepLogFiles });
+}
+
+export async function logsSetLevel(level: string): Promise<void> {
+  return invoke("logs_set_level", { level });
+}
+
+// Diagnostics
+export async function diagnosticsRun(): Promise<DiagnosticsReport> {
+  return invoke("diagnostics_run");
+}
+
+export async function networkStatus(checkReachability = false): Promise<NetworkStatus> {
+  return invoke("network_status", { checkReachability });
+}
+
+// Windows
+export async function windowShow(label: string): Promise<void> {
+  return invoke("window_show", { label });
+}
+
+export async function windowHide(label: string): Promise<void> {
+  return invoke("window_hide", { label });
+}
+
+// Startup
+export async function getStartAtLogin(): Promise<boolean> {
+  return invoke("get_start_at_login");
+}
+
+export async function setStartAtLogin(enabled: boolean): Promise<void> {
+  return invoke("set_start_at_login", { enabled });
+}
+
+// Power
+export async function powerCapabilities(): Promise<PowerCapabilities> {
+  return invoke("power_capabilities");
+}
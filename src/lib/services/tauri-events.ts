@@ -5,20 +5,23 @@ import { playbackState } from "$lib/state/playback.svelte";
 import { queueState } from "$lib/state/queue.svelte";
 import { uiState } from "$lib/state/ui.svelte";
 import { tasksState } from "$lib/state/tasks.svelte";
+import { feedsState } from "$lib/state/feeds.svelte";
 import { tryExecuteNext } from "./execution-pipeline";
 import { assignTorrentToPlaylet, findBestMatch, shouldSkipAutoAssign } from "./playlet-assignment";
 import { initNotifications, notifyDownloadComplete, notifyRssMatch } from "./notifications";
 import { playbackCastTorrent } from "./tauri-commands";
 import { t } from "$lib/i18n";
+import { formatBytes } from "$lib/utils";
 import type {
   DeviceFoundEvent,
   DeviceLostEvent,
   DeviceConnectedEvent,
   DeviceDisconnectedEvent,
-  TorrentProgress,
+  DeviceReconnectEvent,
 } from "$lib/types";
-import type { PlaybackStatusResponse } from "$lib/types/playback";
-import type { TorrentAddedResponse } from "$lib/types/torrent";
+import type { PlaybackStatusResponse, StreamReadiness } from "$lib/types/playback";
+import type { PowerStatus } from "$lib/types/settings";
+import type { FileOrderAdvancedEvent, TorrentAddedResponse, TorrentSummary, TorrentsDelta } from "$lib/types/torrent";
 
 let unlisteners: (() => void)[] = [];
 
@@ -58,6 +61,27 @@ export async function setupEventListeners() {
     ),
   );
 
+  unlisteners.push(
+    await listen<DeviceReconnectEvent>("chromecast:reconnecting", (event) => {
+      devicesState.updateDeviceStatus(event.payload.id, "connecting");
+      uiState.addToast(t("toast.deviceReconnecting", { name: event.payload.name }), "info");
+    }),
+  );
+
+  unlisteners.push(
+    await listen<DeviceReconnectEvent>("chromecast:reconnected", (event) => {
+      devicesState.updateDeviceStatus(event.payload.id, "connected");
+      uiState.addToast(t("toast.deviceReconnected", { name: event.payload.name }), "success");
+    }),
+  );
+
+  unlisteners.push(
+    await listen<DeviceReconnectEvent>("chromecast:gone", (event) => {
+      devicesState.updateDeviceStatus(event.payload.id, "discovered");
+      uiState.addToast(t("toast.deviceGone", { name: event.payload.name }), "error");
+    }),
+  );
+
   unlisteners.push(
     await listen<{ id: string; error: string }>(
       "chromecast:connection-error",
@@ -208,11 +232,37 @@ export async function setupEventListeners() {
   );
 
   unlisteners.push(
-    await listen<TorrentProgress>("torrent:progress", (event) => {
-      torrentsState.updateProgress(event.payload);
+    await listen<FileOrderAdvancedEvent>("torrent:file-order-advanced", (event) => {
+      if (event.payload.done) {
+        uiState.addToast(t("toast.fileOrderDone"), "info");
+      }
     }),
   );
 
+  unlisteners.push(
+    await listen<TorrentsDelta>("torrents:delta", (event) => {
+      torrentsState.applyDelta(event.payload);
+    }),
+  );
+
+  unlisteners.push(
+    await listen<TorrentSummary[]>("torrents:snapshot", (event) => {
+      torrentsState.applySnapshot(event.payload);
+    }),
+  );
+
+  unlisteners.push(
+    await listen<{ id: number; name: string; info_hash: string; data_deleted: boolean }[]>(
+      "torrents:auto-removed",
+      (event) => {
+        for (const removed of event.payload) {
+          torrentsState.removeTorrent(removed.id);
+        }
+        uiState.addToast(t("toast.autoRemoved", { count: event.payload.length }), "info");
+      },
+    ),
+  );
+
   unlisteners.push(
     await listen<number>("torrent:completed", async (event) => {
       const torrentId = event.payload;
@@ -252,6 +302,58 @@ export async function setupEventListeners() {
     ),
   );
 
+  unlisteners.push(
+    await listen<{ added: string[]; failed: { item: string; error: string }[] }>(
+      "tray:drop-result",
+      (event) => {
+        const { added, failed } = event.payload;
+        if (added.length === 1) {
+          uiState.addToast(t("toast.torrentAdded", { name: added[0] }), "success");
+        } else if (added.length > 1) {
+          uiState.addToast(t("toast.dropAdded", { count: added.length }), "success");
+        }
+        for (const { error } of failed) {
+          uiState.addToast(t("toast.somethingWentWrong", { error }), "error");
+        }
+      },
+    ),
+  );
+
+  unlisteners.push(
+    await listen<{ interest_id: string; interest_name: string; match_count: number }>(
+      "rss:interest-completed",
+      (event) => {
+        const { interest_id, interest_name, match_count } = event.payload;
+        feedsState.markInterestCompleted(interest_id, match_count);
+        uiState.addToast(t("toast.interestCompleted", { name: interest_name }), "info");
+      },
+    ),
+  );
+
+  unlisteners.push(
+    await listen<{ interest_id: string; interest_name: string; quiet_days: number }>(
+      "rss:interest-stale",
+      (event) => {
+        const { interest_name, quiet_days } = event.payload;
+        uiState.addToast(t("toast.interestStale", { name: interest_name, days: quiet_days }), "info");
+      },
+    ),
+  );
+
+  await setupInboxListeners();
+
+  unlisteners.push(
+    await listen<{ match_id: string; torrent_id: number; skipped: string[] }>("rss:files-skipped", (event) => {
+      uiState.addToast(t("toast.filesSkipped", { count: event.payload.skipped.length }), "info");
+    }),
+  );
+
+  unlisteners.push(
+    await listen<{ used: number; cap: number }>("stats:cap-reached", (event) => {
+      uiState.addToast(t("toast.dataCapReached", { cap: formatBytes(event.payload.cap) }), "info");
+    }),
+  );
+
   // Playback events
   unlisteners.push(
     await listen<PlaybackStatusResponse>(
@@ -262,6 +364,15 @@ export async function setupEventListeners() {
     ),
   );
 
+  unlisteners.push(
+    await listen<{ device_id: string; torrent_id: number; file_index: number; readiness: StreamReadiness }>(
+      "playback:buffering",
+      (event) => {
+        playbackState.setBuffering(event.payload.readiness);
+      },
+    ),
+  );
+
   unlisteners.push(
     await listen<{ device_id: string }>("playback:finished", async (event) => {
       const deviceId = event.payload.device_id;
@@ -293,6 +404,91 @@ export async function setupEventListeners() {
       },
     ),
   );
+
+  unlisteners.push(
+    await listen<{ device_id: string; index: number; title: string }>(
+      "playback:queue-item-changed",
+      (event) => {
+        const device = devicesState.devices.find((d) => d.id === event.payload.device_id);
+        playbackState.setContext(event.payload.title, device?.name ?? null);
+      },
+    ),
+  );
+
+  unlisteners.push(
+    await listen<{ device_id: string; error: string }>(
+      "playback:queue-unsupported",
+      (event) => {
+        uiState.addToast(t("toast.castQueueUnsupported", { error: event.payload.error }), "error");
+      },
+    ),
+  );
+
+  unlisteners.push(
+    await listen<PowerStatus>("power:state-changed", (event) => {
+      const { holding, paused } = event.payload;
+      if (holding && paused > 0) {
+        uiState.addToast(t("toast.pausedOnBattery", { count: paused }), "info");
+      }
+    }),
+  );
+
+  unlisteners.push(
+    await listen<{ shortcut: string; error: string }>("shortcut:registration-failed", (event) => {
+      uiState.addToast(t("toast.shortcutTaken", { shortcut: event.payload.shortcut }), "error");
+    }),
+  );
+}
+
+// Inbox state: the only listeners picker windows set up, since everything
+// else would run the app's side effects once per window
+export async function setupInboxListeners() {
+  unlisteners.push(
+    await listen<{ match_id: string; error: string; failure_count: number; retrying: boolean }>(
+      "rss:approve-failed",
+      (event) => {
+        const { match_id, error, failure_count, retrying } = event.payload;
+        feedsState.markApproveFailed(match_id, error, failure_count);
+        if (retrying) {
+          uiState.addToast(t("toast.approveRetrying", { error }), "info");
+        }
+      },
+    ),
+  );
+
+  unlisteners.push(
+    await listen<{ match_id: string; torrent_id: number }>("rss:approved", (event) => {
+      feedsState.matchApproved(event.payload.match_id, event.payload.torrent_id);
+      uiState.addToast(t("toast.approveRetrySucceeded"), "success");
+    }),
+  );
+
+  unlisteners.push(
+    await listen<{ match_id: string; health: unknown }>("rss:health-checked", (event) => {
+      feedsState.setMatchHealth(event.payload.match_id, event.payload.health);
+    }),
+  );
+
+  unlisteners.push(
+    await listen<{ match_id: string; reason: string; stage: "metadata" | "seeders" }>("rss:auto-rejected", (event) => {
+      const { match_id, reason, stage } = event.payload;
+      const title = feedsState.pendingMatches.find((m) => m.id === match_id)?.title ?? "";
+      feedsState.markAutoRejected(match_id, reason, stage);
+      uiState.addToast(t("toast.autoRejected", { title, reason }), "info");
+    }),
+  );
+
+  unlisteners.push(
+    await listen<{ match_id: string; metadata: unknown }>("rss:metadata-fetched", (event) => {
+      feedsState.setMatchMetadata(event.payload.match_id, event.payload.metadata);
+    }),
+  );
+
+  unlisteners.push(
+    await listen<string[]>("rss:duplicates-cleared", (event) => {
+      feedsState.removeMatches(event.payload);
+    }),
+  );
 }
 
 export function cleanupEventListeners() {
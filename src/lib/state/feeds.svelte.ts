@@ -20,6 +20,14 @@ export interface Source {
   // Backoff state
   failureCount?: number;
   retryAfter?: string;
+  // Cached site icon on disk
+  iconPath?: string;
+}
+
+export interface SourceMetadata {
+  suggestedName: string;
+  description?: string;
+  iconPath?: string;
 }
 
 export interface Interest {
@@ -32,14 +40,58 @@ export interface Interest {
   downloadPath?: string;
   // Enable smart episode detection to prevent duplicate episodes
   smartEpisodeFilter?: boolean;
+  // Don't match releases of a whole season
+  skipSeasonPacks?: boolean;
+  // Queue better releases of episodes that were already matched
+  upgradePolicy?: UpgradePolicy;
+  // Disable after this many approved matches
+  maxMatches?: number;
+  // Disable once a full season pack is approved
+  autoDisableWhenComplete?: boolean;
+  // Label for torrents added from approved matches
+  defaultLabel?: string;
+  staleAlertDays?: number;
+  lastMatchAt?: string;
+  // Files of a matched torrent to download; the rest are skipped
+  fileFilters?: FeedFilter[];
+  // Run on a match's file list once its metadata is in; failing ones are marked rejected
+  metadataFilters?: FeedFilter[];
+  // Fewest seeders a match needs; fewer marks it rejected
+  minSeeders?: number;
+  // Add approved torrents paused or started, whatever the global setting
+  addPaused?: boolean;
+}
+
+export interface UpgradePolicy {
+  enabled: boolean;
+  // Rust quality id, e.g. "q1080p"
+  targetQuality?: string;
+  autoReplace: boolean;
 }
 
 export interface FeedFilter {
-  type: "must_contain" | "must_not_contain" | "regex" | "size_range" | "wildcard";
+  type: "must_contain" | "must_not_contain" | "regex" | "size_range" | "wildcard" | "min_seeders";
   value: string;
   enabled: boolean;
 }
 
+// Ready-made interest; nameKey and descriptionKey are i18n keys
+export interface InterestPreset {
+  id: string;
+  nameKey: string;
+  descriptionKey: string;
+  filters: FeedFilter[];
+  filterLogic: "and" | "or";
+  smartEpisodeFilter: boolean;
+}
+
+export interface FilterValidationError {
+  kind: "empty_value" | "invalid_regex" | "invalid_size_range" | "invalid_number";
+  message: string;
+  // Character offset of the problem in the value
+  position?: number;
+}
+
 interface FeedTestResult {
   items: FeedTestItem[];
   totalCount: number;
@@ -51,12 +103,18 @@ interface FeedTestItem {
   matches: boolean;
   matchedFilter?: string;
   size?: number;
+  seeders?: number;
+  sourceId?: string;
+  sourceName?: string;
+  // Matches but was already seen before the filter existed
+  wouldHaveMatched?: boolean;
 }
 
 export interface PendingMatch {
   id: string;
   sourceId: string;
   sourceName: string;
+  sources: string[];
   interestId: string;
   interestName: string;
   title: string;
@@ -64,6 +122,32 @@ export interface PendingMatch {
   torrentUrl?: string;
   createdAt: string;
   metadata?: TorrentMetadata;
+  isUpgrade?: boolean;
+  replacesTorrentId?: number;
+  replacesTitle?: string;
+  lastError?: string;
+  failureCount: number;
+  // Turned down by the interest's metadata filters
+  rejectedReason?: string;
+  // Turned down by the interest's min seeders
+  seedersRejectedReason?: string;
+  health?: TorrentHealth;
+  // Each tracker's answer to the last scrape
+  trackers?: TrackerScrape[];
+}
+
+export interface TorrentHealth {
+  seeders: number;
+  leechers: number;
+  checkedAt: string;
+}
+
+export interface TrackerScrape {
+  tracker: string;
+  seeders?: number;
+  leechers?: number;
+  completed?: number;
+  error?: string;
 }
 
 interface TorrentMetadata {
@@ -105,12 +189,57 @@ export interface Scraper {
   sizeSelector?: string;
   enabled: boolean;
   requestDelayMs?: number;
+  maxPages?: number;
+  nextPageSelector?: string;
+  pageParam?: string;
+  detailLinkSelector?: string;
+  detailMagnetSelector?: string;
+  cookieString?: string;
+  extraHeaders?: Record<string, string>;
   checkInterval?: number;
+  lastChecked?: string;
+  lastError?: string;
+}
+
+export interface TorznabIndexer {
+  id: string;
+  name: string;
+  baseUrl: string;
+  apiKey: string;
+  enabled: boolean;
+  categories?: number[];
+  checkInterval?: number;
+  lastChecked?: string;
+  lastError?: string;
+}
+
+export interface TorznabSearchResult {
+  title: string;
+  magnetUri?: string;
+  torrentUrl?: string;
+  size?: number;
+  seeders?: number;
+  publishedDate?: string;
+}
+
+export interface SearchResult {
+  title: string;
+  sourceName: string;
+  size?: number;
+  seeders?: number;
+  magnetUri?: string;
+  torrentUrl?: string;
+}
+
+export interface SearchResponse {
+  results: SearchResult[];
+  failures: { sourceName: string; message: string }[];
 }
 
 interface ScraperTestResult {
   items: ScrapedItem[];
   totalCount: number;
+  pageCounts: number[];
 }
 
 interface ScrapedItem {
@@ -118,6 +247,8 @@ interface ScrapedItem {
   magnetUri?: string;
   torrentUrl?: string;
   size?: number;
+  detailUrl?: string;
+  fromDetailPage: boolean;
 }
 
 function sourceFromRust(s: any): Source {
@@ -134,6 +265,7 @@ function sourceFromRust(s: any): Source {
     lastModified: s.last_modified,
     failureCount: s.failure_count,
     retryAfter: s.retry_after,
+    iconPath: s.icon_path,
   };
 }
 
@@ -151,6 +283,7 @@ function sourceToRust(s: Source): any {
     last_modified: s.lastModified,
     failure_count: s.failureCount ?? 0,
     retry_after: s.retryAfter,
+    icon_path: s.iconPath,
   };
 }
 
@@ -171,6 +304,31 @@ function interestFromRust(i: any): Interest {
     filterLogic: i.filter_logic || "and",
     downloadPath: i.download_path,
     smartEpisodeFilter: i.smart_episode_filter ?? false,
+    skipSeasonPacks: i.skip_season_packs ?? false,
+    upgradePolicy: i.upgrade_policy
+      ? {
+          enabled: i.upgrade_policy.enabled,
+          targetQuality: i.upgrade_policy.target_quality,
+          autoReplace: i.upgrade_policy.auto_replace ?? false,
+        }
+      : undefined,
+    maxMatches: i.max_matches ?? undefined,
+    autoDisableWhenComplete: i.auto_disable_when_complete ?? false,
+    defaultLabel: i.default_label ?? undefined,
+    staleAlertDays: i.stale_alert_days ?? undefined,
+    lastMatchAt: i.last_match_at ?? undefined,
+    fileFilters: (i.file_filters ?? []).map((f: any) => ({
+      type: f.type,
+      value: f.value,
+      enabled: f.enabled,
+    })),
+    addPaused: i.add_paused ?? undefined,
+    metadataFilters: (i.metadata_filters ?? []).map((f: any) => ({
+      type: f.type,
+      value: f.value,
+      enabled: f.enabled,
+    })),
+    minSeeders: i.min_seeders ?? undefined,
   };
 }
 
@@ -187,6 +345,47 @@ function interestToRust(i: Interest): any {
     filter_logic: i.filterLogic,
     download_path: i.downloadPath,
     smart_episode_filter: i.smartEpisodeFilter ?? false,
+    skip_season_packs: i.skipSeasonPacks ?? false,
+    upgrade_policy: i.upgradePolicy
+      ? {
+          enabled: i.upgradePolicy.enabled,
+          target_quality: i.upgradePolicy.targetQuality,
+          auto_replace: i.upgradePolicy.autoReplace,
+        }
+      : undefined,
+    max_matches: i.maxMatches,
+    auto_disable_when_complete: i.autoDisableWhenComplete ?? false,
+    default_label: i.defaultLabel || undefined,
+    stale_alert_days: i.staleAlertDays || undefined,
+    file_filters: (i.fileFilters ?? []).map((f) => ({
+      type: f.type,
+      value: f.value,
+      enabled: f.enabled,
+    })),
+    add_paused: i.addPaused,
+    metadata_filters: (i.metadataFilters ?? []).map((f) => ({
+      type: f.type,
+      value: f.value,
+      enabled: f.enabled,
+    })),
+    min_seeders: i.minSeeders ?? undefined,
+  };
+}
+
+function testResultFromRust(result: any): FeedTestResult {
+  return {
+    items: result.items.map((item: any) => ({
+      title: item.title,
+      matches: item.matches,
+      matchedFilter: item.matched_filter,
+      size: item.size,
+      seeders: item.seeders,
+      sourceId: item.source_id,
+      sourceName: item.source_name,
+      wouldHaveMatched: item.would_have_matched ?? false,
+    })),
+    totalCount: result.total_count,
+    matchedCount: result.matched_count,
   };
 }
 
@@ -195,6 +394,7 @@ function pendingFromRust(p: any): PendingMatch {
     id: p.id,
     sourceId: p.source_id,
     sourceName: p.source_name,
+    sources: p.sources?.length ? p.sources : [p.source_name],
     interestId: p.interest_id,
     interestName: p.interest_name,
     title: p.title,
@@ -202,6 +402,22 @@ function pendingFromRust(p: any): PendingMatch {
     torrentUrl: p.torrent_url,
     createdAt: p.created_at,
     metadata: p.metadata ? metadataFromRust(p.metadata) : undefined,
+    isUpgrade: p.is_upgrade ?? false,
+    replacesTorrentId: p.replaces_torrent_id ?? undefined,
+    replacesTitle: p.replaces_title ?? undefined,
+    lastError: p.last_error ?? undefined,
+    failureCount: p.failure_count ?? 0,
+    rejectedReason: p.rejected_reason ?? undefined,
+    seedersRejectedReason: p.seeders_rejected_reason ?? undefined,
+    health: p.health ? healthFromRust(p.health) : undefined,
+  };
+}
+
+function healthFromRust(h: any): TorrentHealth {
+  return {
+    seeders: h.seeders,
+    leechers: h.leechers,
+    checkedAt: h.checked_at,
   };
 }
 
@@ -242,6 +458,16 @@ function scraperFromRust(s: any): Scraper {
     sizeSelector: s.size_selector,
     enabled: s.enabled,
     requestDelayMs: s.request_delay_ms,
+    maxPages: s.max_pages,
+    nextPageSelector: s.next_page_selector,
+    pageParam: s.page_param,
+    detailLinkSelector: s.detail_link_selector,
+    detailMagnetSelector: s.detail_magnet_selector,
+    cookieString: s.cookie_string,
+    extraHeaders: s.extra_headers,
+    checkInterval: s.check_interval,
+    lastChecked: s.last_checked,
+    lastError: s.last_error,
   };
 }
 
@@ -257,6 +483,40 @@ function scraperToRust(s: Scraper): any {
     size_selector: s.sizeSelector,
     enabled: s.enabled,
     request_delay_ms: s.requestDelayMs ?? 500,
+    max_pages: s.maxPages ?? 1,
+    next_page_selector: s.nextPageSelector,
+    page_param: s.pageParam,
+    detail_link_selector: s.detailLinkSelector,
+    detail_magnet_selector: s.detailMagnetSelector,
+    cookie_string: s.cookieString,
+    extra_headers: s.extraHeaders ?? {},
+    check_interval: s.checkInterval,
+  };
+}
+
+function indexerFromRust(i: any): TorznabIndexer {
+  return {
+    id: i.id,
+    name: i.name,
+    baseUrl: i.base_url,
+    apiKey: i.api_key,
+    enabled: i.enabled,
+    categories: i.categories,
+    checkInterval: i.check_interval,
+    lastChecked: i.last_checked,
+    lastError: i.last_error,
+  };
+}
+
+function indexerToRust(i: TorznabIndexer): any {
+  return {
+    id: i.id,
+    name: i.name,
+    base_url: i.baseUrl,
+    api_key: i.apiKey,
+    enabled: i.enabled,
+    categories: i.categories ?? [],
+    check_interval: i.checkInterval,
   };
 }
 
@@ -264,7 +524,10 @@ class FeedsState {
   sources = $state<Source[]>([]);
   interests = $state<Interest[]>([]);
   scrapers = $state<Scraper[]>([]);
+  indexers = $state<TorznabIndexer[]>([]);
   pendingMatches = $state<PendingMatch[]>([]);
+  // Approved matches per interest id
+  matchCounts = $state<Record<string, number>>({});
   torrentInterests = $state<Map<number, TorrentInterestLink>>(new Map());
 
   get enabledSources() {
@@ -275,6 +538,13 @@ class FeedsState {
     return this.interests.filter((i) => i.enabled);
   }
 
+  // Interests that disabled themselves after reaching their cap
+  get finishedInterests() {
+    return this.interests.filter(
+      (i) => !i.enabled && i.maxMatches !== undefined && (this.matchCounts[i.id] ?? 0) >= i.maxMatches,
+    );
+  }
+
   get pendingCount() {
     return this.pendingMatches.length;
   }
@@ -296,15 +566,40 @@ class FeedsState {
     };
 
     try {
-      await invoke("rss_add_source", { source: sourceToRust(newSource) });
-      this.sources = [...this.sources, newSource];
-      return newSource;
+      const added = sourceFromRust(await invoke("rss_add_source", { source: sourceToRust(newSource) }));
+      this.sources = [...this.sources, added];
+      return added;
     } catch (e) {
       console.error("Failed to add source:", e);
       throw e;
     }
   }
 
+  async fetchSourceMetadata(url: string): Promise<SourceMetadata> {
+    const m: any = await invoke("rss_fetch_source_metadata", { url });
+    return {
+      suggestedName: m.suggested_name,
+      description: m.description,
+      iconPath: m.icon_path,
+    };
+  }
+
+  // Name an unnamed source after its feed and pick up the site icon.
+  // Failures leave the source as it is.
+  async fillSourceMetadata(id: string) {
+    const source = this.sources.find((s) => s.id === id);
+    if (!source?.url || (source.name && source.iconPath)) return;
+    try {
+      const metadata = await this.fetchSourceMetadata(source.url);
+      await this.updateSource(id, {
+        name: source.name || metadata.suggestedName,
+        iconPath: source.iconPath ?? metadata.iconPath,
+      });
+    } catch (e) {
+      console.error("Failed to fetch source metadata:", e);
+    }
+  }
+
   async updateSource(id: string, updates: Partial<Source>) {
     const index = this.sources.findIndex((s) => s.id === id);
     if (index < 0) return;
@@ -395,19 +690,40 @@ class FeedsState {
     }
   }
 
-  async toggleInterest(id: string, enabled: boolean) {
+  async toggleInterest(id: string, enabled: boolean, resetCount = false) {
     const index = this.interests.findIndex((i) => i.id === id);
     if (index < 0) return;
 
     try {
-      await invoke("rss_toggle_interest", { interestId: id, enabled });
+      await invoke("rss_toggle_interest", { interestId: id, enabled, resetCount });
       this.interests[index] = { ...this.interests[index], enabled };
+      if (resetCount) {
+        const { [id]: _, ...rest } = this.matchCounts;
+        this.matchCounts = rest;
+      }
     } catch (e) {
       console.error("Failed to toggle interest:", e);
       throw e;
     }
   }
 
+  async loadMatchCounts() {
+    try {
+      this.matchCounts = await invoke("rss_interest_match_counts");
+    } catch (e) {
+      console.error("Failed to load interest match counts:", e);
+    }
+  }
+
+  // Called when the backend disabled an interest that reached its cap
+  markInterestCompleted(id: string, matchCount: number) {
+    this.matchCounts = { ...this.matchCounts, [id]: matchCount };
+    const index = this.interests.findIndex((i) => i.id === id);
+    if (index >= 0) {
+      this.interests[index] = { ...this.interests[index], enabled: false };
+    }
+  }
+
   async testInterest(url: string, filters: FeedFilter[]): Promise<FeedTestResult> {
     const result: any = await invoke("rss_test_interest", {
       url,
@@ -418,16 +734,46 @@ class FeedsState {
       })),
     });
 
-    return {
-      items: result.items.map((item: any) => ({
-        title: item.title,
-        matches: item.matches,
-        matchedFilter: item.matched_filter,
-        size: item.size,
-      })),
-      totalCount: result.total_count,
-      matchedCount: result.matched_count,
-    };
+    return testResultFromRust(result);
+  }
+
+  async loadPresets(): Promise<InterestPreset[]> {
+    const result: any[] = await invoke("rss_interest_presets");
+    return result.map((p) => ({
+      id: p.id,
+      nameKey: p.name_key,
+      descriptionKey: p.description_key,
+      filters: p.filters,
+      filterLogic: p.filter_logic,
+      smartEpisodeFilter: p.smart_episode_filter,
+    }));
+  }
+
+  async addInterestFromPreset(presetId: string, name: string, searchTerm?: string) {
+    try {
+      const result = await invoke("rss_apply_preset", { presetId, name, searchTerm });
+      const interest = interestFromRust(result);
+      this.interests = [...this.interests, interest];
+      return interest;
+    } catch (e) {
+      console.error("Failed to add interest from preset:", e);
+      throw e;
+    }
+  }
+
+  async validateFilter(filter: FeedFilter): Promise<FilterValidationError | null> {
+    return await invoke("rss_validate_filter", {
+      filter: { type: filter.type, value: filter.value, enabled: filter.enabled },
+    });
+  }
+
+  // Test against saved sources and scrapers; reuses cached feed items where possible
+  async testInterestAgainstSources(interest: Interest, sourceIds: string[]): Promise<FeedTestResult> {
+    const result: any = await invoke("rss_test_interest_against_sources", {
+      interest: interestToRust(interest),
+      sourceIds,
+    });
+    return testResultFromRust(result);
   }
 
   // Pending matches operations
@@ -458,10 +804,62 @@ class FeedsState {
     return metadata;
   }
 
-  async approveMatch(matchId: string): Promise<number> {
-    const match = this.pendingMatches.find((m) => m.id === matchId);
-    const torrentId: number = await invoke("rss_approve_match", { matchId });
+  /** Scrape the match's trackers; the best counts also land on the match. */
+  async scrapeHealth(matchId: string): Promise<TrackerScrape[]> {
+    const result: any = await invoke("rss_scrape_health", { matchId });
+    if (result.health) this.setMatchHealth(matchId, result.health);
+    const index = this.pendingMatches.findIndex((m) => m.id === matchId);
+    if (index >= 0) {
+      this.pendingMatches[index] = { ...this.pendingMatches[index], trackers: result.trackers };
+    }
+    return result.trackers;
+  }
 
+  setMatchHealth(matchId: string, raw: any) {
+    const index = this.pendingMatches.findIndex((m) => m.id === matchId);
+    if (index >= 0) {
+      this.pendingMatches[index] = { ...this.pendingMatches[index], health: healthFromRust(raw) };
+    }
+  }
+
+  /** Fetch metadata for every match without it, in the background. */
+  async prefetchMetadata(): Promise<number> {
+    return invoke("rss_prefetch_metadata");
+  }
+
+  markApproveFailed(matchId: string, error: string, failureCount: number) {
+    const index = this.pendingMatches.findIndex((m) => m.id === matchId);
+    if (index >= 0) {
+      this.pendingMatches[index] = { ...this.pendingMatches[index], lastError: error, failureCount };
+    }
+  }
+
+  markAutoRejected(matchId: string, reason: string, stage: "metadata" | "seeders") {
+    const index = this.pendingMatches.findIndex((m) => m.id === matchId);
+    if (index >= 0) {
+      const field = stage === "seeders" ? "seedersRejectedReason" : "rejectedReason";
+      this.pendingMatches[index] = { ...this.pendingMatches[index], [field]: reason };
+    }
+  }
+
+  setMatchMetadata(matchId: string, raw: any) {
+    const index = this.pendingMatches.findIndex((m) => m.id === matchId);
+    if (index >= 0) {
+      this.pendingMatches[index] = { ...this.pendingMatches[index], metadata: metadataFromRust(raw) };
+    }
+  }
+
+  async approveMatch(matchId: string): Promise<number | null> {
+    const torrentId: number | null = await invoke("rss_approve_match", { matchId });
+    // Still being retried after an earlier failure; it stays in the inbox
+    if (torrentId === null) return null;
+    this.matchApproved(matchId, torrentId);
+    return torrentId;
+  }
+
+  // A match's torrent was added, by hand or by a background retry
+  matchApproved(matchId: string, torrentId: number) {
+    const match = this.pendingMatches.find((m) => m.id === matchId);
     // Track which interest this torrent came from
     if (match) {
       this.torrentInterests.set(torrentId, {
@@ -469,9 +867,7 @@ class FeedsState {
         interestName: match.interestName,
       });
     }
-
     this.pendingMatches = this.pendingMatches.filter((m) => m.id !== matchId);
-    return torrentId;
   }
 
   getTorrentInterest(torrentId: number): TorrentInterestLink | undefined {
@@ -495,6 +891,12 @@ class FeedsState {
     this.pendingMatches = [];
   }
 
+  // Matches the backend dropped because their torrent was already added
+  removeMatches(matchIds: string[]) {
+    const removed = new Set(matchIds);
+    this.pendingMatches = this.pendingMatches.filter((m) => !removed.has(m.id));
+  }
+
   updatePendingCount(count: number) {
     if (count > this.pendingMatches.length) {
       this.loadPending();
@@ -579,11 +981,122 @@ class FeedsState {
         magnetUri: item.magnet_uri,
         torrentUrl: item.torrent_url,
         size: item.size,
+        detailUrl: item.detail_url,
+        fromDetailPage: item.from_detail_page,
       })),
       totalCount: result.total_count,
+      pageCounts: result.page_counts,
+    };
+  }
+
+  // Torznab indexer operations
+  async loadIndexers() {
+    try {
+      const result: any[] = await invoke("torznab_list_indexers");
+      this.indexers = result.map(indexerFromRust);
+    } catch (e) {
+      console.error("Failed to load indexers:", e);
+    }
+  }
+
+  async addIndexer(indexer: Omit<TorznabIndexer, "id">): Promise<TorznabIndexer> {
+    const newIndexer: TorznabIndexer = {
+      ...indexer,
+      id: crypto.randomUUID(),
+    };
+
+    try {
+      await invoke("torznab_add_indexer", { indexer: indexerToRust(newIndexer) });
+      this.indexers = [...this.indexers, newIndexer];
+      return newIndexer;
+    } catch (e) {
+      console.error("Failed to add indexer:", e);
+      throw e;
+    }
+  }
+
+  async updateIndexer(id: string, updates: Partial<TorznabIndexer>) {
+    const index = this.indexers.findIndex((i) => i.id === id);
+    if (index < 0) return;
+
+    const updated = { ...this.indexers[index], ...updates };
+
+    try {
+      await invoke("torznab_update_indexer", { indexer: indexerToRust(updated) });
+      this.indexers[index] = updated;
+    } catch (e) {
+      console.error("Failed to update indexer:", e);
+      throw e;
+    }
+  }
+
+  async removeIndexer(id: string) {
+    try {
+      await invoke("torznab_remove_indexer", { id });
+      this.indexers = this.indexers.filter((i) => i.id !== id);
+    } catch (e) {
+      console.error("Failed to remove indexer:", e);
+      throw e;
+    }
+  }
+
+  async toggleIndexer(id: string, enabled: boolean) {
+    const index = this.indexers.findIndex((i) => i.id === id);
+    if (index < 0) return;
+
+    try {
+      await invoke("torznab_toggle_indexer", { id, enabled });
+      this.indexers[index] = { ...this.indexers[index], enabled };
+    } catch (e) {
+      console.error("Failed to toggle indexer:", e);
+      throw e;
+    }
+  }
+
+  async searchIndexer(indexerId: string, query: string): Promise<TorznabSearchResult[]> {
+    const result: any[] = await invoke("torznab_search", { indexerId, query });
+    return result.map((item) => ({
+      title: item.title,
+      magnetUri: item.magnet_uri,
+      torrentUrl: item.torrent_url,
+      size: item.size,
+      seeders: item.seeders,
+      publishedDate: item.published_date,
+    }));
+  }
+
+  // Manual search across every searchable source
+  async search(query: string): Promise<SearchResponse> {
+    const result: any = await invoke("rss_search", { query });
+    return {
+      results: result.results.map((r: any) => ({
+        title: r.title,
+        sourceName: r.source_name,
+        size: r.size,
+        seeders: r.seeders,
+        magnetUri: r.magnet_uri,
+        torrentUrl: r.torrent_url,
+      })),
+      failures: result.failures.map((f: any) => ({
+        sourceName: f.source_name,
+        message: f.message,
+      })),
     };
   }
 
+  async addSearchResult(result: SearchResult): Promise<void> {
+    await invoke("rss_add_search_result", {
+      result: {
+        title: result.title,
+        source_name: result.sourceName,
+        size: result.size,
+        seeders: result.seeders,
+        magnet_uri: result.magnetUri,
+        torrent_url: result.torrentUrl,
+      },
+    });
+  }
+
   // Legacy compatibility
   get feeds() {
     return this.sources;
@@ -592,7 +1105,9 @@ class FeedsState {
   async loadFeeds() {
     await this.loadSources();
     await this.loadInterests();
+    await this.loadMatchCounts();
     await this.loadScrapers();
+    await this.loadIndexers();
   }
 
   // Bad items operations
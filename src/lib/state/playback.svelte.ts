@@ -1,6 +1,7 @@
 import type {
   PlaybackState,
   PlaybackStatusResponse,
+  StreamReadiness,
 } from "$lib/types/playback";
 
 let status = $state<PlaybackStatusResponse | null>(null);
@@ -9,6 +10,8 @@ let activeTorrentName = $state<string | null>(null);
 let activeDeviceName = $state<string | null>(null);
 let activeTorrentId = $state<number | null>(null);
 let activeFileIndex = $state<number | null>(null);
+// How much of the file is in while a cast waits for it to buffer
+let buffering = $state<StreamReadiness | null>(null);
 
 export const playbackState = {
   get status() {
@@ -50,6 +53,9 @@ export const playbackState = {
   get activeFileIndex() {
     return activeFileIndex;
   },
+  get buffering() {
+    return buffering;
+  },
 
   setStatus(newStatus: PlaybackStatusResponse) {
     status = newStatus;
@@ -59,6 +65,10 @@ export const playbackState = {
     isLoading = value;
   },
 
+  setBuffering(readiness: StreamReadiness | null) {
+    buffering = readiness?.ready ? null : readiness;
+  },
+
   setContext(torrentName: string | null, deviceName: string | null, torrentId?: number | null, fileIndex?: number | null) {
     activeTorrentName = torrentName;
     activeDeviceName = deviceName;
@@ -73,5 +83,6 @@ export const playbackState = {
     activeDeviceName = null;
     activeTorrentId = null;
     activeFileIndex = null;
+    buffering = null;
   },
 };
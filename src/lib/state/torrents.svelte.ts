@@ -1,6 +1,6 @@
 import type {
   TorrentSummary,
-  TorrentProgress,
+  TorrentsDelta,
 } from "$lib/types/torrent";
 
 let torrents = $state<TorrentSummary[]>([]);
@@ -48,27 +48,37 @@ export const torrentsState = {
     torrents = torrents.filter((t) => t.id !== id);
   },
 
-  updateProgress(progress: TorrentProgress) {
-    const idx = torrents.findIndex((t) => t.id === progress.id);
-    if (idx >= 0) {
-      torrents[idx] = {
-        ...torrents[idx],
-        progress: progress.progress,
-        download_speed: progress.download_speed,
-        upload_speed: progress.upload_speed,
-        peers_connected: progress.peers_connected,
-        queued_peers: progress.queued_peers ?? 0,
-        connecting_peers: progress.connecting_peers ?? 0,
-        downloaded_bytes: progress.downloaded_bytes,
-        uploaded_bytes: progress.uploaded_bytes ?? 0,
-        total_bytes: progress.total_bytes,
-        state: progress.state,
-      };
+  setTorrents(newTorrents: TorrentSummary[]) {
+    torrents = newTorrents;
+  },
+
+  // Only entries already in the list are updated; additions come from torrent:added
+  applyDelta(delta: TorrentsDelta) {
+    for (const summary of delta.changed) {
+      const idx = torrents.findIndex((t) => t.id === summary.id);
+      if (idx >= 0) {
+        torrents[idx] = { ...torrents[idx], ...summary };
+      }
+    }
+    if (delta.removed.length > 0) {
+      const removed = new Set(delta.removed);
+      torrents = torrents.filter((t) => !removed.has(t.id));
     }
   },
 
-  setTorrents(newTorrents: TorrentSummary[]) {
-    torrents = newTorrents;
+  // Resync with the backend's full list, keeping the user's order and pending magnets
+  applySnapshot(snapshot: TorrentSummary[]) {
+    const byId = new Map(snapshot.map((s) => [s.id, s]));
+    const known = new Set(torrents.map((t) => t.id));
+    torrents = torrents
+      .filter((t) => t.id < 0 || byId.has(t.id))
+      .map((t) => (byId.has(t.id) ? { ...t, ...byId.get(t.id) } : t));
+    for (const summary of snapshot) {
+      if (known.has(summary.id)) continue;
+      if (!this.promotePending(summary.info_hash, summary)) {
+        torrents = [...torrents, summary];
+      }
+    }
   },
 
   reorder(fromIndex: number, toIndex: number) {
@@ -4,9 +4,12 @@ export interface ChromecastDeviceInfo {
   model: string;
   address: string;
   port: number;
+  protocol: DeviceProtocol;
   status: DeviceStatus;
 }
 
+export type DeviceProtocol = "chromecast" | "airplay";
+
 export type DeviceStatus = "discovered" | "connecting" | "connected" | "error";
 
 export interface DeviceFoundEvent {
@@ -31,3 +34,10 @@ export interface DeviceDisconnectedEvent {
   reason: string;
 }
 
+/** chromecast:reconnecting, chromecast:reconnected and chromecast:gone */
+export interface DeviceReconnectEvent {
+  id: string;
+  name: string;
+  reason: string | null;
+}
+
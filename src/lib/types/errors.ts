@@ -0,0 +1,15 @@
+/** Error returned by a Tauri command. `code` is stable; `message` is for display. */
+export interface CommandError {
+  code: string;
+  message: string;
+  details?: string;
+}
+
+export function isCommandError(value: unknown): value is CommandError {
+  return (
+    typeof value === "object" &&
+    value !== null &&
+    typeof (value as CommandError).code === "string" &&
+    typeof (value as CommandError).message === "string"
+  );
+}
@@ -3,6 +3,7 @@ export type {
   DeviceLostEvent,
   DeviceConnectedEvent,
   DeviceDisconnectedEvent,
+  DeviceReconnectEvent,
 } from "./device";
 
 export type { TorrentProgress } from "./torrent";
@@ -6,3 +6,4 @@ export * from "./events";
 export * from "./ui";
 export * from "./playlet";
 export * from "./task";
+export * from "./errors";
@@ -7,14 +7,55 @@ export interface PlaybackStatusResponse {
   is_muted: boolean;
   media_title: string | null;
   content_type: string | null;
+  /** Cast groups only: largest position gap between the leader and another member */
+  max_drift_secs?: number;
+}
+
+export interface CastGroup {
+  id: string;
+  device_ids: string[];
+  leader: string;
 }
 
 export type PlaybackState = "idle" | "buffering" | "playing" | "paused";
 
+/** How much of the start of a torrent file is downloaded, before casting it */
+export interface StreamReadiness {
+  ready: boolean;
+  buffered_head_bytes: number;
+  total_bytes: number;
+  est_seconds_until_ready: number | null;
+}
+
+/** Sent when a torrent stream starts or stops waiting on undownloaded pieces */
+export interface StreamWaitingEvent {
+  torrent_id: number;
+  file_index: number;
+  waiting: boolean;
+}
+
+export type ThumbnailStatus = "ready" | "generating" | "unavailable";
+
+export interface ThumbnailInfo {
+  status: ThumbnailStatus;
+  url: string | null;
+  reason: string | null;
+}
+
+export interface MediaServerCertInfo {
+  /** Colon-separated SHA-256 of the certificate */
+  fingerprint_sha256: string;
+  not_after: string;
+  hosts: string[];
+}
+
 export interface SubtitleInfo {
   url: string;
   name: string;
   format: string;
+  encoding: string;
+  dropped_cues: number;
+  offset_ms: number;
 }
 
 export interface MediaPlayer {
@@ -1,4 +1,5 @@
 export interface AppSettings {
+  config_version?: number;
   download_directory: string;
   theme: ThemeMode;
   color_scheme: string;
@@ -6,25 +7,57 @@ export interface AppSettings {
   max_download_speed: number;
   max_upload_speed: number;
   media_server_port: number;
+  /** Serve media over HTTPS with a self-signed certificate */
+  media_server_tls_enabled?: boolean;
   auto_play_next: boolean;
   subtitle_languages: string[];
   opensubtitles_api_key: string;
+  opensubtitles_username: string;
+  opensubtitles_password: string;
   enable_upnp: boolean;
   listen_port: number;
-  watch_folders: string[];
+  /** Port check service; `{port}` is replaced with the listen port */
+  port_check_url?: string;
+  watch_folders: WatchFolderRule[];
   watch_folders_enabled: boolean;
   incomplete_directory: string;
   max_concurrent_tasks: number;
+  /** Add new torrents paused */
+  add_torrents_paused: boolean;
+  /** Remove finished torrents after seeding this many days (0 = never) */
+  remove_completed_after_days: number;
+  /** Also delete the data of torrents removed that way */
+  remove_completed_delete_data: boolean;
   picker_countdown_seconds: number;
   skip_template_picker: boolean;
   delete_torrent_file_on_add: boolean;
   show_tray_icon: boolean;
+  start_at_login: boolean;
+  pause_on_battery: boolean;
+  /** Toggles the inbox panel from anywhere, e.g. "Alt+Super+T" */
+  global_shortcut: string | null;
+  show_tray_activity: boolean;
   default_cast_device: string;
   default_media_player: string;
   default_move_destination: string;
   rss_check_interval_minutes: number;
   locale: string;
   metadata_timeout_secs: number;
+  /** Bytes per calendar month (down + up); torrents pause once it's reached */
+  monthly_data_cap?: number;
+  log_to_file?: boolean;
+}
+
+/** A watched folder and how torrents dropped into it are added */
+export interface WatchFolderRule {
+  path: string;
+  /** Download directory for torrents from this folder (null = default) */
+  output_folder: string | null;
+  /** Off adds torrents paused */
+  auto_start: boolean;
+  label: string | null;
+  /** Interest whose folder, label and organize template apply */
+  interest_id: string | null;
 }
 
 export type ThemeMode = "light" | "dark" | "system";
@@ -40,16 +73,25 @@ export const DEFAULT_SETTINGS: AppSettings = {
   auto_play_next: true,
   subtitle_languages: ["en"],
   opensubtitles_api_key: "",
+  opensubtitles_username: "",
+  opensubtitles_password: "",
   enable_upnp: true,
   listen_port: 4240,
   watch_folders: [],
   watch_folders_enabled: false,
   incomplete_directory: "",
   max_concurrent_tasks: 0,
+  add_torrents_paused: false,
+  remove_completed_after_days: 0,
+  remove_completed_delete_data: false,
   picker_countdown_seconds: 5,
   skip_template_picker: false,
   delete_torrent_file_on_add: false,
   show_tray_icon: true,
+  start_at_login: false,
+  pause_on_battery: false,
+  global_shortcut: null,
+  show_tray_activity: true,
   default_cast_device: "",
   default_media_player: "",
   default_move_destination: "",
@@ -57,3 +99,66 @@ export const DEFAULT_SETTINGS: AppSettings = {
   locale: "system",
   metadata_timeout_secs: 30,
 };
+
+export interface SettingsImportSummary {
+  sources: number;
+  interests: number;
+  scrapers: number;
+  indexers: number;
+  bad_items: number;
+}
+
+export interface LogEntry {
+  timestamp: string;
+  level: "ERROR" | "WARN" | "INFO" | "DEBUG" | "TRACE";
+  target: string;
+  message: string;
+}
+
+export type CheckStatus = "pass" | "warn" | "fail" | "skipped";
+
+export interface DiagnosticCheck {
+  id: string;
+  label: string;
+  status: CheckStatus;
+  detail: string;
+  remediation?: string;
+}
+
+export interface DiagnosticsReport {
+  ran_at: string;
+  checks: DiagnosticCheck[];
+}
+
+export type UpnpStatus = "disabled" | "pending" | "mapped" | "failed";
+
+export interface UpnpResult {
+  status: UpnpStatus;
+  port: number | null;
+  external_ip: string | null;
+  error: string | null;
+}
+
+export type PortReachability = "open" | "closed" | "unknown";
+
+export interface NetworkStatus {
+  listen_port_start: number;
+  listen_port_end: number;
+  bound_port: number | null;
+  upnp: UpnpResult;
+  dht_nodes: number | null;
+  reachability: PortReachability;
+}
+
+export interface PowerCapabilities {
+  battery_detection: boolean;
+}
+
+/** Sent as power:state-changed */
+export interface PowerStatus {
+  on_battery: boolean;
+  /** Torrents are being paused because of the battery */
+  holding: boolean;
+  /** How many torrents are paused for battery */
+  paused: number;
+}
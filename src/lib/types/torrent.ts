@@ -3,6 +3,29 @@ export interface TorrentAddedResponse {
   name: string;
   info_hash: string;
   files: TorrentFileInfo[];
+  already_existed?: boolean;
+}
+
+export interface ImportReport {
+  file: string;
+  status: "imported" | "already_managed" | "failed";
+  torrent_id?: number;
+  name?: string;
+  destination?: string;
+  error?: string;
+}
+
+export interface DailyUsage {
+  date: string;
+  downloaded: number;
+  uploaded: number;
+}
+
+export interface UsageStats {
+  days: DailyUsage[];
+  month_downloaded: number;
+  month_uploaded: number;
+  monthly_data_cap?: number;
 }
 
 export interface TorrentSummary {
@@ -20,12 +43,38 @@ export interface TorrentSummary {
   downloaded_bytes: number;
   uploaded_bytes?: number;
   file_count: number;
+  label?: string | null;
+  /** Seconds left at the smoothed speed; null while stalled */
+  eta_secs?: number | null;
+  /** Distributed copies, when the engine reports it */
+  availability?: number | null;
+}
+
+export interface TorrentsDelta {
+  changed: TorrentSummary[];
+  /** Ids that left the session */
+  removed: number[];
 }
 
 export interface TorrentDetails extends TorrentSummary {
   files: TorrentFileInfo[];
   added_at: string;
   output_folder: string;
+  file_order: FileOrderStatus | null;
+}
+
+export interface FileOrderStatus {
+  strategy: "selection";
+  order: number[];
+  position: number;
+  current_file: number | null;
+}
+
+export interface FileOrderAdvancedEvent {
+  id: number;
+  position: number;
+  current_file: number | null;
+  done: boolean;
 }
 
 export interface TorrentFileInfo {
@@ -38,9 +87,87 @@ export interface TorrentFileInfo {
   stream_url: string | null;
 }
 
+export interface FileVerifyResult {
+  index: number;
+  path: string;
+  good_pieces: number;
+  bad_pieces: number;
+  missing_pieces: number;
+}
+
+export interface TorrentVerifyReport {
+  id: number;
+  total_pieces: number;
+  good_pieces: number;
+  bad_pieces: number;
+  missing_pieces: number;
+  percent_good: number;
+  files: FileVerifyResult[];
+  cancelled: boolean;
+}
+
+export interface TorrentVerifyProgress {
+  id: number;
+  checked_pieces: number;
+  total_pieces: number;
+}
+
+export interface TorrentCreateOptions {
+  trackers?: string[];
+  /** Bytes, a power of two; picked from the total size when omitted */
+  pieceSize?: number;
+  private?: boolean;
+  comment?: string;
+}
+
+export interface TorrentCreated {
+  torrent_path: string;
+  info_hash: string;
+  piece_size: number;
+  piece_count: number;
+  total_bytes: number;
+  torrent: TorrentAddedResponse;
+}
+
+/** A finished download, for the history view */
+export interface CompletedEntry {
+  info_hash: string;
+  name: string;
+  total_bytes: number;
+  /** RFC 3339, UTC */
+  completed_at: string;
+}
+
+export interface CompletedPage {
+  items: CompletedEntry[];
+  /** Entries across all pages */
+  total: number;
+}
+
+export interface TorrentCreateProgress {
+  path: string;
+  hashed_bytes: number;
+  total_bytes: number;
+}
+
+export interface MagnetPreview {
+  info_hash: string;
+  name: string | null;
+  trackers: string[];
+  web_seeds: string[];
+  /** Id of the torrent already in the session with this hash */
+  existing_id: number | null;
+  marked_bad: boolean;
+}
+
 export interface TorrentAddOptions {
   output_folder?: string;
   only_files?: number[];
+  /** Overrides the add_torrents_paused setting */
+  add_paused?: boolean;
+  label?: string;
+  /** Add a magnet even though it was marked bad */
+  allow_bad?: boolean;
 }
 
 export interface TorrentProgress {
@@ -55,6 +182,8 @@ export interface TorrentProgress {
   uploaded_bytes: number;
   total_bytes: number;
   state: TorrentState;
+  eta_secs: number | null;
+  availability?: number | null;
 }
 
 export type TorrentState =
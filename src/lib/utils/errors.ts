@@ -0,0 +1,8 @@
+import { isCommandError } from "$lib/types/errors";
+
+/** Display text for anything a command or promise rejected with. */
+export function errorMessage(error: unknown): string {
+  if (isCommandError(error)) return error.message;
+  if (error instanceof Error) return error.message;
+  return String(error);
+}
@@ -1,3 +1,4 @@
-export { formatSpeed } from "./format";
+export { formatBytes, formatSpeed } from "./format";
+export { errorMessage } from "./errors";
 export { useContextMenu } from "./context-menu.svelte";
 export type { ContextMenuState } from "./context-menu.svelte";